@@ -45,6 +45,17 @@ pub enum SensitiveAction {
         args: Vec<String>,
     },
 
+    /// Run a shell command that names paths outside the workspace.
+    ///
+    /// Raised once per command by the spawn pre-flight, listing every
+    /// flagged path, so the user answers one prompt per command.
+    ShellOutsideWorkspace {
+        /// The command line.
+        command: String,
+        /// The paths outside the workspace it names.
+        paths: Vec<String>,
+    },
+
     /// Make a network request.
     NetworkRequest {
         /// Target host.
@@ -137,6 +148,7 @@ impl SensitiveAction {
             Self::FileDelete { .. } => "file_delete",
             Self::FileWriteOutsideSandbox { .. } => "file_write_outside_sandbox",
             Self::ExecuteCommand { .. } => "execute_command",
+            Self::ShellOutsideWorkspace { .. } => "shell_outside_workspace",
             Self::NetworkRequest { .. } => "network_request",
             Self::TransmitData { .. } => "transmit_data",
             Self::FinancialTransaction { .. } => "financial_transaction",
@@ -166,6 +178,12 @@ impl SensitiveAction {
                     format!("Execute: {command} {}", args.join(" "))
                 }
             },
+            Self::ShellOutsideWorkspace { command, paths } => {
+                format!(
+                    "Execute outside workspace: {command} (touches {})",
+                    paths.join(", ")
+                )
+            },
             Self::NetworkRequest { host, port } => format!("Network request to {host}:{port}"),
             Self::TransmitData {
                 destination,
@@ -0,0 +1,197 @@
+//! Canonical identity of a single action, for "allow this exact action" grants.
+//!
+//! An [`AllowancePattern::ExactAction`](super::AllowancePattern::ExactAction)
+//! remembers one action rather than a family of them: `cargo test` in
+//! `/work/app`, but not `cargo test --release` and not `cargo test` in another
+//! workspace. The action is reduced to a canonical form and hashed together
+//! with the workspace root, so trivially different spellings of the same
+//! action share a digest while anything that changes its meaning does not.
+//!
+//! Normalization rules:
+//!
+//! - **Paths** are joined onto the workspace root when relative, and `.`,
+//!   `..`, repeated and trailing separators are resolved lexically. Symlinks
+//!   are not followed: their target can change after the grant is made.
+//! - **Commands** are split into words on unquoted, unescaped whitespace, so
+//!   `cargo  test`, `cargo test ` and `cargo` with args `["test"]` are the
+//!   same action. Word order is kept (`cp a b` is not `cp b a`), and quoted
+//!   or escaped text is kept verbatim, quotes included, so `echo "a  b"` is
+//!   not `echo "a b"`. Entries of `args` are already single words and are
+//!   never split.
+//! - **Hosts** are lowercased and lose a trailing dot; **HTTP methods** are
+//!   uppercased.
+//! - **Permission lists** are sorted and deduplicated, since their order
+//!   carries no meaning.
+//! - **Workspace root** is normalized like a path. A grant made outside any
+//!   workspace never matches inside one, and vice versa.
+//!
+//! The normalized fields are hashed as RFC 8785 canonical JSON
+//! ([`astrid_crypto::canonical_json`]), so field order never matters.
+
+use astrid_core::types::Permission;
+use astrid_crypto::ContentHash;
+use serde_json::{Value, json};
+use std::path::{Component, Path, PathBuf};
+
+use crate::action::SensitiveAction;
+
+/// Hex digest identifying `action` within `workspace_root`.
+///
+/// Two actions share a digest exactly when they have the same canonical
+/// form (see the module docs).
+#[must_use]
+pub fn exact_action_digest(action: &SensitiveAction, workspace_root: Option<&Path>) -> String {
+    let workspace = workspace_root.map(|root| normalize_path(&root.to_string_lossy(), None));
+    let canonical = json!({
+        "action": canonical_action(action, workspace_root),
+        "type": action.action_type(),
+        "workspace": workspace,
+    });
+    ContentHash::hash_canonical_json(&canonical).to_hex()
+}
+
+/// Normalized fields of `action`, before hashing.
+#[must_use]
+pub fn canonical_action(action: &SensitiveAction, workspace_root: Option<&Path>) -> Value {
+    let path = |p: &str| normalize_path(p, workspace_root);
+    match action {
+        SensitiveAction::FileRead { path: p }
+        | SensitiveAction::FileDelete { path: p }
+        | SensitiveAction::FileWriteOutsideSandbox { path: p } => json!({ "path": path(p) }),
+        SensitiveAction::ExecuteCommand { command, args } => {
+            let mut words = command_words(command);
+            words.extend(args.iter().cloned());
+            json!({ "argv": words })
+        },
+        SensitiveAction::ShellOutsideWorkspace { command, paths } => {
+            let mut paths: Vec<String> = paths.iter().map(|p| path(p)).collect();
+            paths.sort();
+            paths.dedup();
+            json!({ "argv": command_words(command), "paths": paths })
+        },
+        SensitiveAction::NetworkRequest { host, port } => {
+            json!({ "host": normalize_host(host), "port": port })
+        },
+        SensitiveAction::TransmitData {
+            destination,
+            data_type,
+        } => json!({ "destination": destination.trim(), "data_type": data_type.trim() }),
+        SensitiveAction::FinancialTransaction { amount, recipient } => {
+            json!({ "amount": amount.trim(), "recipient": recipient.trim() })
+        },
+        SensitiveAction::AccessControlChange { resource, change } => {
+            json!({ "resource": resource.trim(), "change": change.trim() })
+        },
+        SensitiveAction::CapabilityGrant {
+            resource_pattern,
+            permissions,
+        } => json!({
+            "resource_pattern": resource_pattern.trim(),
+            "permissions": sorted_permissions(permissions),
+        }),
+        SensitiveAction::McpToolCall { server, tool } => {
+            json!({ "server": server, "tool": tool })
+        },
+        SensitiveAction::CapsuleExecution {
+            capsule_id,
+            capability,
+        } => json!({ "capsule_id": capsule_id, "capability": capability }),
+        SensitiveAction::CapsuleHttpRequest {
+            capsule_id,
+            url,
+            method,
+        } => json!({
+            "capsule_id": capsule_id,
+            "url": url.trim(),
+            "method": method.trim().to_ascii_uppercase(),
+        }),
+        SensitiveAction::CapsuleFileAccess {
+            capsule_id,
+            path: p,
+            mode,
+        } => json!({ "capsule_id": capsule_id, "path": path(p), "mode": mode.to_string() }),
+        SensitiveAction::CapsuleNetBind { capsule_id } => json!({ "capsule_id": capsule_id }),
+    }
+}
+
+/// Split a command on unquoted, unescaped whitespace, keeping each word's
+/// raw text (quotes and backslashes included).
+fn command_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, c) if c.is_whitespace() => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                continue;
+            },
+            (None | Some('"'), '\\') => {
+                word.push(c);
+                if let Some(escaped) = chars.next() {
+                    word.push(escaped);
+                }
+                continue;
+            },
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            _ => {},
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Make `path` absolute against `root` (when relative) and resolve `.` and
+/// `..` lexically.
+fn normalize_path(path: &str, root: Option<&Path>) -> String {
+    let path = Path::new(path.trim());
+    let joined = match root {
+        Some(root) if path.is_relative() => root.join(path),
+        _ => path.to_path_buf(),
+    };
+    let mut normalized = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                // `..` at the root stays at the root; leading `..` of a
+                // relative path with no workspace to join onto is kept.
+                if normalized.as_os_str().is_empty() || normalized.ends_with("..") {
+                    if !joined.has_root() {
+                        normalized.push("..");
+                    }
+                } else {
+                    normalized.pop();
+                }
+            },
+            other => normalized.push(other),
+        }
+    }
+    normalized.to_string_lossy().into_owned()
+}
+
+/// Lowercase a host name and drop a trailing dot.
+fn normalize_host(host: &str) -> String {
+    let host = host.trim();
+    host.strip_suffix('.').unwrap_or(host).to_ascii_lowercase()
+}
+
+/// Permission names, sorted and without duplicates.
+fn sorted_permissions(permissions: &[Permission]) -> Vec<String> {
+    let mut names: Vec<String> = permissions.iter().map(ToString::to_string).collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+#[path = "exact_tests.rs"]
+mod tests;
@@ -0,0 +1,142 @@
+use super::*;
+
+fn cmd(command: &str, args: &[&str]) -> SensitiveAction {
+    SensitiveAction::ExecuteCommand {
+        command: command.to_string(),
+        args: args.iter().map(ToString::to_string).collect(),
+    }
+}
+
+fn digest(action: &SensitiveAction) -> String {
+    exact_action_digest(action, Some(Path::new("/work/app")))
+}
+
+#[test]
+fn command_spellings_share_a_digest() {
+    let base = digest(&cmd("cargo test", &[]));
+    assert_eq!(digest(&cmd("cargo  test", &[])), base);
+    assert_eq!(digest(&cmd(" cargo test\t", &[])), base);
+    assert_eq!(digest(&cmd("cargo", &["test"])), base);
+}
+
+#[test]
+fn command_meaning_changes_the_digest() {
+    let base = digest(&cmd("cargo test", &[]));
+    assert_ne!(digest(&cmd("cargo test --release", &[])), base);
+    assert_ne!(digest(&cmd("test cargo", &[])), base);
+    assert_ne!(digest(&cmd("cp a b", &[])), digest(&cmd("cp b a", &[])));
+    // Quoted and escaped whitespace is part of the word.
+    assert_ne!(
+        digest(&cmd("echo \"a  b\"", &[])),
+        digest(&cmd("echo \"a b\"", &[]))
+    );
+    assert_ne!(
+        digest(&cmd("echo a\\ b", &[])),
+        digest(&cmd("echo a b", &[]))
+    );
+    // An argument containing a space is one word, not two.
+    assert_ne!(
+        digest(&cmd("echo", &["a b"])),
+        digest(&cmd("echo a b", &[]))
+    );
+}
+
+#[test]
+fn command_words_keep_quotes_and_escapes() {
+    assert_eq!(
+        command_words(r#"git commit -m "fix:  a" 'x y' a\ b"#),
+        vec!["git", "commit", "-m", "\"fix:  a\"", "'x y'", "a\\ b"]
+    );
+    assert!(command_words("  ").is_empty());
+}
+
+#[test]
+fn workspace_is_part_of_the_identity() {
+    let action = cmd("cargo test", &[]);
+    let here = exact_action_digest(&action, Some(Path::new("/work/app")));
+    assert_eq!(
+        exact_action_digest(&action, Some(Path::new("/work/./app/"))),
+        here
+    );
+    assert_ne!(
+        exact_action_digest(&action, Some(Path::new("/work/other"))),
+        here
+    );
+    assert_ne!(exact_action_digest(&action, None), here);
+}
+
+#[test]
+fn paths_are_resolved_against_the_workspace() {
+    let read = |path: &str| {
+        digest(&SensitiveAction::FileRead {
+            path: path.to_string(),
+        })
+    };
+    let base = read("/work/app/src/main.rs");
+    assert_eq!(read("src/main.rs"), base);
+    assert_eq!(read("./src//main.rs"), base);
+    assert_eq!(read("src/../src/main.rs"), base);
+    assert_eq!(read("/work/app/src/main.rs/"), base);
+    assert_ne!(read("src/lib.rs"), base);
+    assert_ne!(read("../app2/src/main.rs"), base);
+}
+
+#[test]
+fn normalize_path_rules() {
+    assert_eq!(normalize_path("/a/b/../../..", None), "/");
+    assert_eq!(normalize_path("../../x", None), "../../x");
+    assert_eq!(normalize_path("a/./b/..", None), "a");
+    assert_eq!(normalize_path("../x", Some(Path::new("/w/p"))), "/w/x");
+}
+
+#[test]
+fn hosts_methods_and_permissions_are_normalized() {
+    let net = |host: &str| {
+        digest(&SensitiveAction::NetworkRequest {
+            host: host.to_string(),
+            port: 443,
+        })
+    };
+    assert_eq!(net("API.Example.com."), net("api.example.com"));
+    assert_ne!(net("api.example.org"), net("api.example.com"));
+
+    let http = |method: &str| {
+        digest(&SensitiveAction::CapsuleHttpRequest {
+            capsule_id: "c".to_string(),
+            url: "https://example.com/x".to_string(),
+            method: method.to_string(),
+        })
+    };
+    assert_eq!(http("get"), http("GET"));
+    assert_ne!(http("POST"), http("GET"));
+
+    let grant = |permissions: Vec<Permission>| {
+        digest(&SensitiveAction::CapabilityGrant {
+            resource_pattern: "mcp://fs:*".to_string(),
+            permissions,
+        })
+    };
+    assert_eq!(
+        grant(vec![Permission::Write, Permission::Read, Permission::Read]),
+        grant(vec![Permission::Read, Permission::Write])
+    );
+    assert_ne!(
+        grant(vec![Permission::Read]),
+        grant(vec![Permission::Write])
+    );
+}
+
+#[test]
+fn action_types_never_collide() {
+    let read = SensitiveAction::FileRead {
+        path: "/tmp/x".to_string(),
+    };
+    let delete = SensitiveAction::FileDelete {
+        path: "/tmp/x".to_string(),
+    };
+    assert_eq!(
+        canonical_action(&read, None),
+        canonical_action(&delete, None)
+    );
+    assert_ne!(digest(&read), digest(&delete));
+}
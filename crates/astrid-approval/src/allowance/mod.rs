@@ -1,15 +1,17 @@
 //! Allowance types and store for pre-approved action patterns.
 //!
 //! An [`Allowance`] grants pre-approved access for actions matching a specific
-//! pattern. Created when users select "Allow Session" or "Create Allowance"
-//! during approval flows.
+//! pattern. Created when users select "Allow Session", "Allow This Exact
+//! Action" or "Create Allowance" during approval flows.
 //!
 //! The [`AllowanceStore`] holds active allowances in memory, supporting
 //! pattern-based matching, use tracking, expiration cleanup, and session clearing.
 
+mod exact;
 mod pattern;
 mod store;
 
+pub use exact::{canonical_action, exact_action_digest};
 pub use pattern::AllowancePattern;
 pub use store::AllowanceStore;
 
@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::path::Path;
 
+use super::exact::exact_action_digest;
 use crate::action::SensitiveAction;
 
 /// Pattern describing what actions an allowance covers.
@@ -88,9 +89,40 @@ pub enum AllowancePattern {
         /// Plugin identifier.
         capsule_id: String,
     },
+
+    /// Match one exact action in one workspace ("Allow This Exact Action").
+    ///
+    /// The action is identified by [`exact_action_digest`], which hashes its
+    /// normalized arguments together with the workspace root. Build with
+    /// [`AllowancePattern::exact_action`].
+    ExactAction {
+        /// Action type label ([`SensitiveAction::action_type`]).
+        action_type: String,
+        /// Human-readable summary of the remembered action, for listings.
+        summary: String,
+        /// Hex digest of the canonical action and workspace.
+        digest: String,
+    },
 }
 
 impl AllowancePattern {
+    /// Pattern matching exactly `action` within `workspace_root`.
+    #[must_use]
+    pub fn exact_action(action: &SensitiveAction, workspace_root: Option<&Path>) -> Self {
+        Self::ExactAction {
+            action_type: action.action_type().to_string(),
+            summary: action.summary(),
+            digest: exact_action_digest(action, workspace_root),
+        }
+    }
+
+    /// Whether this pattern remembers a single exact action rather than a
+    /// family of actions.
+    #[must_use]
+    pub fn is_exact_action(&self) -> bool {
+        matches!(self, Self::ExactAction { .. })
+    }
+
     /// Check if this pattern matches a sensitive action.
     ///
     /// Matching rules:
@@ -101,6 +133,8 @@ impl AllowancePattern {
     /// - `NetworkHost` matches `NetworkRequest` when host matches and port is allowed.
     /// - `WorkspaceRelative` variants additionally validate that the action's path
     ///   starts with `workspace_root` (if provided) before matching the pattern.
+    /// - `ExactAction` matches an action of the same type whose digest, taken
+    ///   against `workspace_root`, is the remembered one.
     /// - `Custom` never matches (extensibility point for future use).
     ///
     /// # Arguments
@@ -309,6 +343,18 @@ impl AllowancePattern {
                 },
             ) => capsule_id == action_pid,
 
+            (
+                Self::ExactAction {
+                    action_type,
+                    digest,
+                    ..
+                },
+                action,
+            ) => {
+                action.action_type() == action_type
+                    && exact_action_digest(action, workspace_root) == *digest
+            },
+
             // Custom never matches (future extensibility), and all other combinations don't match
             _ => false,
         }
@@ -391,6 +437,12 @@ impl fmt::Display for AllowancePattern {
                 capability,
             } => write!(f, "capsule://{capsule_id}:{capability}"),
             Self::CapsuleWildcard { capsule_id } => write!(f, "capsule://{capsule_id}:*"),
+            Self::ExactAction {
+                summary, digest, ..
+            } => {
+                let short = digest.get(..12).unwrap_or(digest);
+                write!(f, "exact:{summary} [{short}]")
+            },
         }
     }
 }
@@ -10,7 +10,10 @@
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
+use std::time::Instant;
+
+use crate::budget_alerts::{BudgetAlert, BudgetAlerts, BudgetScope};
 
 /// Configuration for budget limits.
 ///
@@ -32,6 +35,10 @@ pub struct BudgetConfig {
     pub per_action_max_usd: f64,
     /// Warning threshold as a percentage of session budget (0-100).
     pub warn_at_percent: u8,
+    /// Percentages at which to raise a one-time [`BudgetAlert`]. Empty
+    /// means just `warn_at_percent`.
+    #[serde(default)]
+    pub alert_percents: Vec<u8>,
 }
 
 impl BudgetConfig {
@@ -44,6 +51,7 @@ impl BudgetConfig {
             session_max_usd,
             per_action_max_usd,
             warn_at_percent: 80,
+            alert_percents: Vec::new(),
         }
     }
 
@@ -54,11 +62,29 @@ impl BudgetConfig {
         self
     }
 
+    /// Set the percentages at which one-time alerts are raised.
+    #[must_use]
+    pub fn with_alert_percents(mut self, percents: impl IntoIterator<Item = u8>) -> Self {
+        self.alert_percents = percents.into_iter().map(|p| p.min(100)).collect();
+        self
+    }
+
     /// Get the warning threshold as a dollar amount.
     #[must_use]
     pub fn warn_threshold_usd(&self) -> f64 {
         self.session_max_usd * f64::from(self.warn_at_percent) / 100.0
     }
+
+    /// The alert thresholds in effect: `alert_percents`, or
+    /// `warn_at_percent` when that is empty.
+    #[must_use]
+    pub fn alert_thresholds(&self) -> Vec<u8> {
+        if self.alert_percents.is_empty() {
+            vec![self.warn_at_percent]
+        } else {
+            self.alert_percents.clone()
+        }
+    }
 }
 
 impl Default for BudgetConfig {
@@ -168,6 +194,7 @@ impl fmt::Display for ExceededReason {
 pub struct BudgetTracker {
     config: BudgetConfig,
     session_spent: RwLock<f64>,
+    alerts: Mutex<BudgetAlerts>,
 }
 
 impl BudgetTracker {
@@ -175,6 +202,7 @@ impl BudgetTracker {
     #[must_use]
     pub fn new(config: BudgetConfig) -> Self {
         Self {
+            alerts: Mutex::new(BudgetAlerts::new(config.alert_thresholds())),
             config,
             session_spent: RwLock::new(0.0),
         }
@@ -317,11 +345,31 @@ impl BudgetTracker {
         &self.config
     }
 
-    /// Reset the session spend to zero.
+    /// Report the alert thresholds the current spend crosses for the
+    /// first time. Call after each committed cost.
+    pub fn take_alerts(&self) -> Vec<BudgetAlert> {
+        let spent = self.spent();
+        self.alerts.lock().map_or_else(
+            |_| Vec::new(),
+            |mut alerts| {
+                alerts.observe(
+                    BudgetScope::Session,
+                    spent,
+                    self.config.session_max_usd,
+                    Instant::now(),
+                )
+            },
+        )
+    }
+
+    /// Reset the session spend to zero and re-arm its alerts.
     pub fn reset(&self) {
         if let Ok(mut spent) = self.session_spent.write() {
             *spent = 0.0;
         }
+        if let Ok(mut alerts) = self.alerts.lock() {
+            alerts.reset();
+        }
     }
 }
 
@@ -414,6 +462,7 @@ pub struct WorkspaceBudgetTracker {
     max_usd: Option<f64>,
     total_spent: RwLock<f64>,
     warn_at_percent: u8,
+    alerts: Mutex<BudgetAlerts>,
 }
 
 impl WorkspaceBudgetTracker {
@@ -423,13 +472,38 @@ impl WorkspaceBudgetTracker {
     /// 100 are clamped.
     #[must_use]
     pub fn new(max_usd: Option<f64>, warn_at_percent: u8) -> Self {
+        let warn_at_percent = warn_at_percent.min(100);
         Self {
             max_usd,
             total_spent: RwLock::new(0.0),
-            warn_at_percent: warn_at_percent.min(100),
+            warn_at_percent,
+            alerts: Mutex::new(BudgetAlerts::new([warn_at_percent])),
+        }
+    }
+
+    /// Raise one-time alerts at these percentages instead of just
+    /// `warn_at_percent`.
+    #[must_use]
+    pub fn with_alert_percents(self, percents: impl IntoIterator<Item = u8>) -> Self {
+        Self {
+            alerts: Mutex::new(BudgetAlerts::new(percents)),
+            ..self
         }
     }
 
+    /// Report the alert thresholds the current spend crosses for the
+    /// first time. Always empty without a workspace limit.
+    pub fn take_alerts(&self) -> Vec<BudgetAlert> {
+        let Some(max) = self.max_usd else {
+            return Vec::new();
+        };
+        let spent = self.spent();
+        self.alerts.lock().map_or_else(
+            |_| Vec::new(),
+            |mut alerts| alerts.observe(BudgetScope::Workspace, spent, max, Instant::now()),
+        )
+    }
+
     /// Record an actual cost against the workspace budget.
     ///
     /// Only positive, finite values are accepted.
@@ -1047,4 +1121,49 @@ mod tests {
         let deserialized: WorkspaceBudgetSnapshot = serde_json::from_str(&json).unwrap();
         assert!((deserialized.total_spent_usd - 25.0).abs() < f64::EPSILON);
     }
+
+    #[test]
+    fn test_alert_thresholds_default_to_warn_percent() {
+        let config = BudgetConfig::new(100.0, 10.0).with_warn_at_percent(70);
+        assert_eq!(config.alert_thresholds(), vec![70]);
+        let config = config.with_alert_percents([50, 90, 120]);
+        assert_eq!(config.alert_thresholds(), vec![50, 90, 100]);
+
+        // Configs saved before `alert_percents` existed still load.
+        let json = r#"{"session_max_usd":1.0,"per_action_max_usd":1.0,"warn_at_percent":80}"#;
+        let loaded: BudgetConfig = serde_json::from_str(json).unwrap();
+        assert!(loaded.alert_percents.is_empty());
+    }
+
+    #[test]
+    fn test_tracker_alerts_once_until_reset() {
+        let tracker = make_tracker(10.0, 10.0);
+        tracker.record_cost(5.0);
+        assert!(tracker.take_alerts().is_empty());
+        tracker.record_cost(3.5);
+        assert_eq!(tracker.take_alerts().len(), 1);
+        tracker.record_cost(1.0);
+        assert!(tracker.take_alerts().is_empty());
+
+        tracker.reset();
+        tracker.record_cost(9.0);
+        assert_eq!(tracker.take_alerts().len(), 1);
+    }
+
+    #[test]
+    fn test_workspace_tracker_alerts() {
+        let unlimited = WorkspaceBudgetTracker::new(None, 80);
+        unlimited.record_cost(1_000.0);
+        assert!(unlimited.take_alerts().is_empty());
+
+        let tracker = WorkspaceBudgetTracker::new(Some(100.0), 80).with_alert_percents([25, 50]);
+        tracker.record_cost(60.0);
+        let alerts: Vec<u8> = tracker
+            .take_alerts()
+            .iter()
+            .map(|a| a.threshold_percent)
+            .collect();
+        assert_eq!(alerts, vec![25, 50]);
+        assert!(tracker.take_alerts().is_empty());
+    }
 }
@@ -0,0 +1,325 @@
+//! One-shot budget threshold alerts.
+//!
+//! [`BudgetTracker`](crate::budget::BudgetTracker) and
+//! [`WorkspaceBudgetTracker`](crate::budget::WorkspaceBudgetTracker) each own
+//! a [`BudgetAlerts`] that reports every configured threshold the first time
+//! spending crosses it, together with a projection of when the limit will be
+//! reached at the recent burn rate. A threshold never fires twice for the
+//! same tracker, even if a refund drops spending back below it.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Spend samples kept for the burn rate.
+const BURN_RATE_SAMPLES: usize = 20;
+
+/// Which budget an alert is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    /// The per-session budget.
+    Session,
+    /// The budget shared by all sessions of a workspace.
+    Workspace,
+}
+
+impl fmt::Display for BudgetScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Session => f.write_str("session"),
+            Self::Workspace => f.write_str("workspace"),
+        }
+    }
+}
+
+/// Spending crossed a warning threshold for the first time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetAlert {
+    /// The budget that crossed the threshold.
+    pub scope: BudgetScope,
+    /// The threshold that was crossed, as a percentage of `limit`.
+    pub threshold_percent: u8,
+    /// Spend when the threshold was crossed (USD).
+    pub current_spend: f64,
+    /// The budget limit (USD).
+    pub limit: f64,
+    /// Percentage of the budget used (0-100).
+    pub percent_used: f64,
+    /// Recent spend per hour (USD), if there are enough samples.
+    pub burn_rate_per_hour: Option<f64>,
+    /// Time until the limit is reached at `burn_rate_per_hour`.
+    pub time_to_limit: Option<Duration>,
+}
+
+impl fmt::Display for BudgetAlert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} budget at {:.0}%: ${:.2} of ${:.2}",
+            self.scope, self.percent_used, self.current_spend, self.limit
+        )?;
+        if let Some(remaining) = self.time_to_limit {
+            write!(
+                f,
+                ", limit reached in ~{}m",
+                remaining.as_secs().div_ceil(60)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Tracks which thresholds have fired and recent spend for one budget.
+#[derive(Debug, Clone)]
+pub struct BudgetAlerts {
+    /// Thresholds in ascending order, without duplicates.
+    thresholds: Vec<u8>,
+    /// Number of leading `thresholds` that have already fired.
+    fired: usize,
+    /// Recent `(time, total spend)` samples, oldest first.
+    samples: VecDeque<(Instant, f64)>,
+}
+
+impl BudgetAlerts {
+    /// Create alerts for the given thresholds (percentages, 0-100).
+    ///
+    /// Values above 100 are clamped to 100.
+    #[must_use]
+    pub fn new(thresholds: impl IntoIterator<Item = u8>) -> Self {
+        let mut thresholds: Vec<u8> = thresholds.into_iter().map(|t| t.min(100)).collect();
+        thresholds.sort_unstable();
+        thresholds.dedup();
+        Self {
+            thresholds,
+            fired: 0,
+            samples: VecDeque::with_capacity(BURN_RATE_SAMPLES),
+        }
+    }
+
+    /// Record the current spend and return an alert for every threshold it
+    /// crosses for the first time, lowest first.
+    pub fn observe(
+        &mut self,
+        scope: BudgetScope,
+        spent: f64,
+        limit: f64,
+        now: Instant,
+    ) -> Vec<BudgetAlert> {
+        if !spent.is_finite() || !limit.is_finite() || limit <= 0.0 {
+            return Vec::new();
+        }
+        if self.samples.len() == BURN_RATE_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((now, spent));
+
+        let percent_used = spent / limit * 100.0;
+        let crossed = self
+            .thresholds
+            .iter()
+            .take_while(|&&t| percent_used >= f64::from(t))
+            .count();
+        if crossed <= self.fired {
+            return Vec::new();
+        }
+
+        let burn_rate_per_hour = burn_rate_per_hour(self.samples.make_contiguous());
+        let time_to_limit = burn_rate_per_hour.and_then(|rate| time_to_limit(spent, limit, rate));
+        let alerts = self
+            .thresholds
+            .get(self.fired..crossed)
+            .unwrap_or_default()
+            .iter()
+            .map(|&threshold_percent| BudgetAlert {
+                scope,
+                threshold_percent,
+                current_spend: spent,
+                limit,
+                percent_used,
+                burn_rate_per_hour,
+                time_to_limit,
+            })
+            .collect();
+        self.fired = crossed;
+        alerts
+    }
+
+    /// Forget fired thresholds and spend samples.
+    pub fn reset(&mut self) {
+        self.fired = 0;
+        self.samples.clear();
+    }
+}
+
+/// Spend per hour between the oldest and newest sample.
+///
+/// `None` with fewer than two samples, no elapsed time, or no spend.
+#[must_use]
+pub fn burn_rate_per_hour(samples: &[(Instant, f64)]) -> Option<f64> {
+    let (first, last) = (samples.first()?, samples.last()?);
+    let elapsed = last.0.saturating_duration_since(first.0).as_secs_f64();
+    let spent = last.1 - first.1;
+    if elapsed <= 0.0 || spent <= 0.0 {
+        return None;
+    }
+    Some(spent / elapsed * 3600.0)
+}
+
+/// Time until `spent` reaches `limit` at `rate_per_hour`.
+#[must_use]
+pub fn time_to_limit(spent: f64, limit: f64, rate_per_hour: f64) -> Option<Duration> {
+    if rate_per_hour <= 0.0 || !rate_per_hour.is_finite() {
+        return None;
+    }
+    let hours = (limit - spent).max(0.0) / rate_per_hour;
+    Duration::try_from_secs_f64(hours * 3600.0).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(start: Instant, mins: u64) -> Instant {
+        start.checked_add(Duration::from_mins(mins)).unwrap()
+    }
+
+    #[test]
+    fn each_threshold_fires_once() {
+        let start = Instant::now();
+        let mut alerts = BudgetAlerts::new([50, 80, 90]);
+        let mut fired = Vec::new();
+        for (i, spent) in [10.0, 40.0, 55.0, 60.0, 81.0, 85.0, 92.0, 95.0]
+            .into_iter()
+            .enumerate()
+        {
+            let now = at(start, u64::try_from(i).unwrap());
+            for alert in alerts.observe(BudgetScope::Session, spent, 100.0, now) {
+                fired.push((alert.threshold_percent, spent));
+            }
+        }
+        assert_eq!(fired, vec![(50, 55.0), (80, 81.0), (90, 92.0)]);
+    }
+
+    #[test]
+    fn jump_past_several_thresholds_fires_each() {
+        let mut alerts = BudgetAlerts::new([90, 50, 80, 80]);
+        let fired: Vec<u8> = alerts
+            .observe(BudgetScope::Workspace, 95.0, 100.0, Instant::now())
+            .iter()
+            .map(|a| a.threshold_percent)
+            .collect();
+        assert_eq!(fired, vec![50, 80, 90]);
+    }
+
+    #[test]
+    fn refund_below_threshold_does_not_refire() {
+        let start = Instant::now();
+        let mut alerts = BudgetAlerts::new([80]);
+        assert_eq!(
+            alerts
+                .observe(BudgetScope::Session, 85.0, 100.0, start)
+                .len(),
+            1
+        );
+        assert!(
+            alerts
+                .observe(BudgetScope::Session, 70.0, 100.0, at(start, 1))
+                .is_empty()
+        );
+        assert!(
+            alerts
+                .observe(BudgetScope::Session, 86.0, 100.0, at(start, 2))
+                .is_empty()
+        );
+
+        alerts.reset();
+        assert_eq!(
+            alerts
+                .observe(BudgetScope::Session, 86.0, 100.0, at(start, 3))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn projection_uses_recent_burn_rate() {
+        let start = Instant::now();
+        let mut alerts = BudgetAlerts::new([80]);
+        // $1 per minute: 60/h, so the remaining $20 lasts 20 minutes.
+        for (i, spent) in [70.0, 71.0, 72.0, 73.0, 74.0, 75.0, 76.0, 77.0, 78.0, 79.0]
+            .into_iter()
+            .enumerate()
+        {
+            let now = at(start, u64::try_from(i).unwrap());
+            assert!(
+                alerts
+                    .observe(BudgetScope::Session, spent, 100.0, now)
+                    .is_empty()
+            );
+        }
+        let alert = alerts
+            .observe(BudgetScope::Session, 80.0, 100.0, at(start, 10))
+            .pop()
+            .unwrap();
+        let rate = alert.burn_rate_per_hour.unwrap();
+        assert!((rate - 60.0).abs() < 1e-9);
+        assert_eq!(alert.time_to_limit, Some(Duration::from_mins(20)));
+        assert!(alert.to_string().contains("limit reached in ~20m"));
+    }
+
+    #[test]
+    fn projection_needs_elapsed_time_and_spend() {
+        let now = Instant::now();
+        assert_eq!(burn_rate_per_hour(&[]), None);
+        assert_eq!(burn_rate_per_hour(&[(now, 5.0)]), None);
+        assert_eq!(burn_rate_per_hour(&[(now, 5.0), (now, 9.0)]), None);
+        assert_eq!(burn_rate_per_hour(&[(now, 5.0), (at(now, 1), 5.0)]), None);
+        assert_eq!(time_to_limit(50.0, 100.0, 0.0), None);
+        assert_eq!(time_to_limit(50.0, 100.0, f64::NAN), None);
+        assert_eq!(
+            time_to_limit(120.0, 100.0, 10.0),
+            Some(Duration::ZERO),
+            "spend past the limit projects zero time left"
+        );
+    }
+
+    #[test]
+    fn only_recent_samples_count() {
+        let start = Instant::now();
+        let mut alerts = BudgetAlerts::new([99]);
+        // Slow early spending, then $2/minute for the last 20 samples.
+        alerts.observe(BudgetScope::Session, 0.0, 1000.0, start);
+        alerts.observe(BudgetScope::Session, 1.0, 1000.0, at(start, 600));
+        let mut spent = 1.0;
+        for i in 1..=BURN_RATE_SAMPLES {
+            spent += 2.0;
+            alerts.observe(
+                BudgetScope::Session,
+                spent,
+                1000.0,
+                at(start, 600 + u64::try_from(i).unwrap()),
+            );
+        }
+        let rate = burn_rate_per_hour(alerts.samples.make_contiguous()).unwrap();
+        assert!((rate - 120.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn invalid_limits_never_alert() {
+        let mut alerts = BudgetAlerts::new([0]);
+        let now = Instant::now();
+        assert!(
+            alerts
+                .observe(BudgetScope::Session, 1.0, 0.0, now)
+                .is_empty()
+        );
+        assert!(
+            alerts
+                .observe(BudgetScope::Session, f64::NAN, 10.0, now)
+                .is_empty()
+        );
+    }
+}
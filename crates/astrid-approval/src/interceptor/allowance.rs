@@ -40,6 +40,7 @@ impl AllowanceValidator {
     /// links this allowance back to the audit entry that recorded the
     /// user's approval decision, maintaining the chain-link proof in the
     /// audit trail.
+    #[must_use]
     pub fn create_allowance_for_action(
         &self,
         principal: &PrincipalId,
@@ -50,7 +51,35 @@ impl AllowanceValidator {
         let Some(pattern) = action_to_allowance_pattern(action) else {
             return InterceptProof::UserApproval { approval_audit_id };
         };
+        self.store_allowance(principal, pattern, session_only, approval_audit_id)
+    }
+
+    /// Remembers exactly this action in the current workspace ("Allow This
+    /// Exact Action").
+    ///
+    /// The allowance outlives the session like a workspace allowance, but its
+    /// [`AllowancePattern::ExactAction`] pattern only matches actions with the
+    /// same canonical digest, taken against the same workspace root.
+    #[must_use]
+    pub fn create_exact_action_allowance(
+        &self,
+        principal: &PrincipalId,
+        action: &SensitiveAction,
+        approval_audit_id: AuditEntryId,
+    ) -> InterceptProof {
+        let pattern = AllowancePattern::exact_action(action, self.workspace_root.as_deref());
+        self.store_allowance(principal, pattern, false, approval_audit_id)
+    }
 
+    /// Signs and stores an allowance for `pattern`, falling back to a
+    /// one-time approval proof if the store rejects it.
+    fn store_allowance(
+        &self,
+        principal: &PrincipalId,
+        pattern: AllowancePattern,
+        session_only: bool,
+        approval_audit_id: AuditEntryId,
+    ) -> InterceptProof {
         let allowance_id = AllowanceId::new();
         let signature = self.runtime_key.sign(allowance_id.0.as_bytes());
         let ws_root = self.workspace_root.clone();
@@ -146,7 +175,8 @@ pub fn action_to_allowance_pattern(action: &SensitiveAction) -> Option<Allowance
                 capability: "net_bind".to_string(),
             })
         },
-        SensitiveAction::TransmitData { .. }
+        SensitiveAction::ShellOutsideWorkspace { .. }
+        | SensitiveAction::TransmitData { .. }
         | SensitiveAction::FinancialTransaction { .. }
         | SensitiveAction::AccessControlChange { .. }
         | SensitiveAction::CapabilityGrant { .. } => None,
@@ -16,11 +16,19 @@ pub fn sensitive_action_to_audit(action: &SensitiveAction) -> AuditAction {
         SensitiveAction::FileWriteOutsideSandbox { path } => AuditAction::FileWrite {
             path: path.clone(),
             content_hash: astrid_crypto::ContentHash::zero(),
+            size: None,
+            sealed_content: None,
         },
         SensitiveAction::ExecuteCommand { command, args } => AuditAction::ApprovalRequested {
             action_type: "execute_command".to_string(),
             resource: format!("{command} {}", args.join(" ")),
         },
+        SensitiveAction::ShellOutsideWorkspace { command, paths } => {
+            AuditAction::ApprovalRequested {
+                action_type: "shell_outside_workspace".to_string(),
+                resource: format!("{command} ({})", paths.join(", ")),
+            }
+        },
         SensitiveAction::NetworkRequest { host, port } => AuditAction::ApprovalRequested {
             action_type: "network_request".to_string(),
             resource: format!("{host}:{port}"),
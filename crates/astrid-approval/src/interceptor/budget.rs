@@ -1,5 +1,6 @@
 use super::types::BudgetWarning;
 use crate::budget::{BudgetResult, BudgetTracker, WorkspaceBudgetTracker};
+use crate::budget_alerts::BudgetAlert;
 use crate::error::ApprovalError;
 use std::sync::Arc;
 
@@ -172,4 +173,16 @@ impl BudgetValidator {
             }),
         }
     }
+
+    /// Collect first-time threshold alerts from both budgets, workspace first.
+    #[must_use]
+    pub fn take_alerts(&self) -> Vec<BudgetAlert> {
+        let mut alerts = self
+            .workspace_tracker
+            .as_ref()
+            .map(|ws| ws.take_alerts())
+            .unwrap_or_default();
+        alerts.extend(self.tracker.take_alerts());
+        alerts
+    }
 }
@@ -17,12 +17,26 @@ pub struct CapabilityValidator {
     pub(crate) store: Arc<CapabilityStore>,
     /// Global keypair validating token authenticity.
     pub(crate) runtime_key: Arc<KeyPair>,
+    /// Workspace tokens are checked against and "Allow Always" grants are
+    /// bound to. `None` means only global tokens apply.
+    pub(crate) workspace: Option<String>,
 }
 
 impl CapabilityValidator {
     /// Creates a new `CapabilityValidator`.
     pub fn new(store: Arc<CapabilityStore>, runtime_key: Arc<KeyPair>) -> Self {
-        Self { store, runtime_key }
+        Self {
+            store,
+            runtime_key,
+            workspace: None,
+        }
+    }
+
+    /// Scopes capability checks and new grants to `workspace`.
+    #[must_use]
+    pub fn in_workspace(mut self, workspace: Option<String>) -> Self {
+        self.workspace = workspace;
+        self
     }
 
     /// Cross-references a requested sensitive action against actively issued capability tokens.
@@ -37,6 +51,9 @@ impl CapabilityValidator {
     /// pattern and permission match — this is the fail-closed cross-principal
     /// check required by issue #668.
     ///
+    /// **Workspace scope:** Tokens bound to another workspace are ignored;
+    /// unbound tokens apply in every workspace.
+    ///
     /// **Design trade-off:** Single-use tokens are consumed *before* the audit
     /// write in `intercept()`. If audit subsequently fails (fail-closed), the
     /// token is gone but the action is denied. This is the correct security
@@ -53,8 +70,11 @@ impl CapabilityValidator {
 
         // Build a proper validator with issuer trust, matching secure.rs
         let trusted_key = self.runtime_key.export_public_key();
-        let validator =
+        let mut validator =
             astrid_capabilities::CapabilityValidator::new(&self.store).trust_issuer(trusted_key);
+        if let Some(workspace) = &self.workspace {
+            validator = validator.in_workspace(workspace.clone());
+        }
 
         let result = validator.check(principal, &resource, permission);
         let found_token = result.token()?;
@@ -108,6 +128,10 @@ impl CapabilityValidator {
     /// the signing payload (`SIGNING_DATA_VERSION` v2, issue #668), so cross-
     /// principal copy-forge attempts fail signature verification.
     ///
+    /// Unless `all_workspaces` is set, the token is also bound to the current
+    /// workspace, so the grant does not follow the user into other projects.
+    /// Without a known workspace the token is global either way.
+    ///
     /// # Errors
     ///
     /// Returns an error if the action cannot be mapped to a resource, or if the resource pattern is invalid.
@@ -115,6 +139,7 @@ impl CapabilityValidator {
         &self,
         principal: &PrincipalId,
         action: &SensitiveAction,
+        all_workspaces: bool,
         approval_audit_id: astrid_capabilities::AuditEntryId,
     ) -> ApprovalResult<InterceptProof> {
         let (resource_str, permission) =
@@ -129,7 +154,7 @@ impl CapabilityValidator {
             reason: format!("invalid resource pattern for capability: {e}"),
         })?;
 
-        let token = CapabilityToken::create(
+        let mut token = CapabilityToken::create(
             resource,
             vec![permission],
             TokenScope::Persistent,
@@ -139,14 +164,23 @@ impl CapabilityValidator {
             Some(ALLOW_ALWAYS_DEFAULT_TTL),
             principal.clone(),
         );
+        if !all_workspaces && let Some(workspace) = &self.workspace {
+            token = token.bound_to_workspace(workspace.clone(), &self.runtime_key);
+        }
         let token_id = token.id.clone();
+        let workspace = token.workspace.as_deref().unwrap_or("*").to_string();
 
         if let Err(e) = self.store.add(token) {
             tracing::error!("failed to store 'Allow Always' capability token: {e}");
             return Ok(InterceptProof::UserApproval { approval_audit_id });
         }
 
-        tracing::info!(%token_id, %resource_str, "created 'Allow Always' capability token (TTL: 1h)");
+        tracing::info!(
+            %token_id,
+            %resource_str,
+            %workspace,
+            "created 'Allow Always' capability token (TTL: 1h)"
+        );
         Ok(InterceptProof::CapabilityCreated {
             token_id,
             approval_audit_id,
@@ -33,8 +33,10 @@ pub(crate) use capability::CapabilityValidator;
 pub use types::*;
 
 use crate::error::{ApprovalError, ApprovalResult};
-use astrid_audit::{AuditEntryId, AuditLog, AuditOutcome, AuthorizationProof as AuditAuthProof};
-use astrid_capabilities::CapabilityStore;
+use astrid_audit::{
+    AuditEntryId, AuditLog, AuditOutcome, AuthorizationProof as AuditAuthProof, SessionReport,
+};
+use astrid_capabilities::{CapabilityStore, workspace_key};
 use astrid_core::principal::PrincipalId;
 use astrid_core::types::SessionId;
 use astrid_crypto::KeyPair;
@@ -82,7 +84,8 @@ impl SecurityInterceptor {
     ) -> Self {
         Self {
             user_id: runtime_key.key_id(),
-            capability_validator: CapabilityValidator::new(capability_store, runtime_key.clone()),
+            capability_validator: CapabilityValidator::new(capability_store, runtime_key.clone())
+                .in_workspace(workspace_root.as_deref().map(workspace_key)),
             budget_validator: BudgetValidator::new(budget_tracker, workspace_budget_tracker),
             allowance_validator: AllowanceValidator::new(
                 allowance_store,
@@ -109,13 +112,32 @@ impl SecurityInterceptor {
     ///
     /// Returns `ApprovalError` if the action is denied by policy, budget,
     /// or user decision.
-    #[expect(clippy::too_many_lines)]
     pub async fn intercept(
         &self,
         principal: &PrincipalId,
         action: &SensitiveAction,
         context: &str,
         estimated_cost: Option<f64>,
+    ) -> ApprovalResult<InterceptResult> {
+        let mut result = self
+            .check_action(principal, action, context, estimated_cost)
+            .await?;
+        // The cost is committed by now, so thresholds it crosses are final.
+        if estimated_cost.is_some() {
+            result.budget_alerts = self.budget_validator.take_alerts();
+        }
+        Ok(result)
+    }
+
+    /// Run the policy, capability, budget and approval checks of
+    /// [`intercept`](Self::intercept).
+    #[expect(clippy::too_many_lines)]
+    async fn check_action(
+        &self,
+        principal: &PrincipalId,
+        action: &SensitiveAction,
+        context: &str,
+        estimated_cost: Option<f64>,
     ) -> ApprovalResult<InterceptResult> {
         // Step 1: Policy check (hard boundaries)
         let policy_result = self.policy.check(action);
@@ -154,6 +176,7 @@ impl SecurityInterceptor {
                 proof,
                 audit_id,
                 budget_warning: cap_budget_warning,
+                budget_alerts: Vec::new(),
             });
         }
 
@@ -184,6 +207,7 @@ impl SecurityInterceptor {
                 proof,
                 audit_id,
                 budget_warning,
+                budget_alerts: Vec::new(),
             });
         }
 
@@ -228,6 +252,7 @@ impl SecurityInterceptor {
                             },
                             audit_id: approval_audit_id,
                             budget_warning,
+                            budget_alerts: Vec::new(),
                         });
                     },
                     ApprovalProof::SessionApproval { .. } => {
@@ -254,6 +279,33 @@ impl SecurityInterceptor {
                             proof,
                             audit_id: approval_audit_id,
                             budget_warning,
+                            budget_alerts: Vec::new(),
+                        });
+                    },
+                    ApprovalProof::ExactActionApproval { .. } => {
+                        let audit_action = sensitive_action_to_audit(action);
+                        let approval_audit_id = self
+                            .audit_log
+                            .append(
+                                self.session_id.clone(),
+                                audit_action,
+                                AuditAuthProof::UserApproval {
+                                    user_id: self.user_id,
+                                    approval_entry_id: None,
+                                },
+                                AuditOutcome::success(),
+                            )
+                            .map_err(|e| ApprovalError::AuditFailed(e.to_string()))?;
+                        let proof = self.allowance_validator.create_exact_action_allowance(
+                            principal,
+                            action,
+                            approval_audit_id.clone(),
+                        );
+                        return Ok(InterceptResult {
+                            proof,
+                            audit_id: approval_audit_id,
+                            budget_warning,
+                            budget_alerts: Vec::new(),
                         });
                     },
                     ApprovalProof::WorkspaceApproval { .. } => {
@@ -280,9 +332,11 @@ impl SecurityInterceptor {
                             proof,
                             audit_id: approval_audit_id,
                             budget_warning,
+                            budget_alerts: Vec::new(),
                         });
                     },
-                    ApprovalProof::AlwaysAllow => {
+                    proof @ (ApprovalProof::AlwaysAllow
+                    | ApprovalProof::AlwaysAllowAllWorkspaces) => {
                         let audit_action = sensitive_action_to_audit(action);
                         let approval_audit_id = self
                             .audit_log
@@ -300,6 +354,7 @@ impl SecurityInterceptor {
                         let result = self.capability_validator.handle_allow_always(
                             principal,
                             action,
+                            matches!(proof, ApprovalProof::AlwaysAllowAllWorkspaces),
                             approval_audit_id.clone(),
                         );
                         if let Ok(r) = result {
@@ -307,6 +362,7 @@ impl SecurityInterceptor {
                                 proof: r,
                                 audit_id: approval_audit_id,
                                 budget_warning,
+                                budget_alerts: Vec::new(),
                             });
                         }
                         // Fall back to one-time approval if creation fails
@@ -317,6 +373,7 @@ impl SecurityInterceptor {
                             proof,
                             audit_id: approval_audit_id,
                             budget_warning,
+                            budget_alerts: Vec::new(),
                         });
                     },
                 };
@@ -325,6 +382,7 @@ impl SecurityInterceptor {
                     proof: intercept_proof,
                     audit_id,
                     budget_warning,
+                    budget_alerts: Vec::new(),
                 })
             },
             ApprovalOutcome::Denied { reason } => {
@@ -426,6 +484,30 @@ impl SecurityInterceptor {
     pub fn budget_tracker(&self) -> &BudgetTracker {
         &self.budget_validator.tracker
     }
+
+    /// Build the end-of-session report for this interceptor's session.
+    ///
+    /// The audit log supplies the activity; the session budget and the
+    /// approval manager's deferred queue supply spend and unresolved
+    /// actions.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ApprovalError::AuditFailed` if the audit log cannot be read.
+    pub fn session_report(&self) -> ApprovalResult<SessionReport> {
+        let mut report = self
+            .audit_log
+            .session_report(&self.session_id)
+            .map_err(|e| ApprovalError::AuditFailed(e.to_string()))?;
+        report.cost_usd = Some(self.budget_tracker().spent());
+        report.unresolved_deferred = self
+            .approval_manager
+            .get_pending_resolutions()
+            .iter()
+            .map(|r| format!("{} ({})", r.action.summary(), r.reason))
+            .collect();
+        Ok(report)
+    }
 }
 
 impl std::fmt::Debug for SecurityInterceptor {
@@ -69,6 +69,23 @@ impl ApprovalHandler for WorkspaceApproveHandler {
     }
 }
 
+/// Handler that approves the exact action and counts prompts.
+struct ExactApproveHandler(std::sync::atomic::AtomicUsize);
+
+#[async_trait::async_trait]
+impl ApprovalHandler for ExactApproveHandler {
+    async fn request_approval(&self, request: ApprovalRequest) -> Option<ApprovalResponse> {
+        self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Some(ApprovalResponse::new(
+            request.id,
+            ApprovalDecision::ApproveExactAction,
+        ))
+    }
+    fn is_available(&self) -> bool {
+        true
+    }
+}
+
 /// Build result holding the interceptor plus shared handles for test assertions.
 struct TestInterceptor {
     interceptor: SecurityInterceptor,
@@ -436,6 +453,50 @@ async fn test_capability_budget_exceeded_creates_audit_entry() {
     }
 }
 
+#[tokio::test]
+async fn test_budget_alert_fires_once_when_threshold_crossed() {
+    let t = make_interceptor_with_audit(
+        SecurityPolicy::default(),
+        Some(Arc::new(SessionApproveHandler)),
+    )
+    .await;
+    let action = SensitiveAction::McpToolCall {
+        server: "test".to_string(),
+        tool: "read".to_string(),
+    };
+
+    // Session budget is $100 with an alert at 80%: the ninth $9 call
+    // crosses it, later calls stay quiet.
+    let mut alerts = Vec::new();
+    for _ in 0..10 {
+        let result = t
+            .interceptor
+            .intercept(&PrincipalId::default(), &action, "test", Some(9.0))
+            .await
+            .unwrap();
+        alerts.push(result.budget_alerts);
+    }
+    let fired: Vec<usize> = alerts
+        .iter()
+        .enumerate()
+        .filter(|(_, a)| !a.is_empty())
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(fired, vec![8]);
+    let alert = &alerts[8][0];
+    assert_eq!(alert.scope, crate::budget_alerts::BudgetScope::Session);
+    assert_eq!(alert.threshold_percent, 80);
+    assert!((alert.current_spend - 81.0).abs() < 1e-9);
+
+    // Calls without a cost never report alerts.
+    let result = t
+        .interceptor
+        .intercept(&PrincipalId::default(), &action, "test", None)
+        .await
+        .unwrap();
+    assert!(result.budget_alerts.is_empty());
+}
+
 #[tokio::test]
 async fn test_budget_rollback_on_dual_budget_denial() {
     // Workspace budget is large, session budget is small.
@@ -580,3 +641,268 @@ async fn test_session_approval_no_duplicate_audit_entry() {
         "second call should add one more audit entry (allowance-based)"
     );
 }
+
+// -----------------------------------------------------------------------
+// Exact action approval — remembered without re-prompting
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_exact_action_approval_is_remembered() {
+    let handler = Arc::new(ExactApproveHandler(std::sync::atomic::AtomicUsize::new(0)));
+    let interceptor = make_interceptor(
+        SecurityPolicy::default(),
+        Some(Arc::clone(&handler) as Arc<dyn ApprovalHandler>),
+    )
+    .await;
+    let prompts = || handler.0.load(std::sync::atomic::Ordering::SeqCst);
+    let run = |command: &str| SensitiveAction::ExecuteCommand {
+        command: command.to_string(),
+        args: vec![],
+    };
+    let principal = PrincipalId::default();
+
+    let first = interceptor
+        .intercept(&principal, &run("cargo test"), "test", None)
+        .await
+        .unwrap();
+    assert!(
+        matches!(first.proof, InterceptProof::WorkspaceApproval { .. }),
+        "expected WorkspaceApproval proof, got {:?}",
+        first.proof
+    );
+    assert_eq!(prompts(), 1);
+
+    // A different spelling of the same action is served from memory.
+    let second = interceptor
+        .intercept(&principal, &run("cargo  test "), "test", None)
+        .await
+        .unwrap();
+    assert!(
+        matches!(second.proof, InterceptProof::Allowance { .. }),
+        "expected Allowance proof, got {:?}",
+        second.proof
+    );
+    assert_eq!(prompts(), 1);
+
+    // A different action prompts again.
+    interceptor
+        .intercept(&principal, &run("cargo test --release"), "test", None)
+        .await
+        .unwrap();
+    assert_eq!(prompts(), 2);
+}
+
+// -----------------------------------------------------------------------
+// Allow Always — capability tokens are scoped to the workspace
+// -----------------------------------------------------------------------
+
+/// Handler that answers every request with a fixed decision.
+struct FixedDecisionHandler(ApprovalDecision);
+
+#[async_trait::async_trait]
+impl ApprovalHandler for FixedDecisionHandler {
+    async fn request_approval(&self, request: ApprovalRequest) -> Option<ApprovalResponse> {
+        Some(ApprovalResponse::new(request.id, self.0.clone()))
+    }
+    fn is_available(&self) -> bool {
+        true
+    }
+}
+
+async fn make_workspace_interceptor(
+    capability_store: &Arc<CapabilityStore>,
+    runtime_key: &Arc<KeyPair>,
+    workspace_root: &str,
+    decision: ApprovalDecision,
+) -> SecurityInterceptor {
+    let allowance_store = Arc::new(AllowanceStore::new());
+    let approval_manager = Arc::new(ApprovalManager::new(
+        Arc::clone(&allowance_store),
+        Arc::new(DeferredResolutionStore::new()),
+    ));
+    let interceptor = SecurityInterceptor::new(
+        Arc::clone(capability_store),
+        approval_manager,
+        SecurityPolicy::default(),
+        Arc::new(BudgetTracker::new(BudgetConfig::new(100.0, 10.0))),
+        Arc::new(AuditLog::in_memory(KeyPair::generate())),
+        Arc::clone(runtime_key),
+        SessionId::new(),
+        allowance_store,
+        Some(PathBuf::from(workspace_root)),
+        None,
+    );
+    interceptor
+        .approval_manager
+        .register_handler(Arc::new(FixedDecisionHandler(decision)))
+        .await;
+    interceptor
+}
+
+#[tokio::test]
+async fn test_allow_always_token_is_bound_to_workspace() {
+    let store = Arc::new(CapabilityStore::in_memory());
+    let key = Arc::new(KeyPair::generate());
+    let deny = || ApprovalDecision::Deny {
+        reason: "test deny".to_string(),
+    };
+    let action = SensitiveAction::FileDelete {
+        path: "/home/user/file.txt".to_string(),
+    };
+    let principal = PrincipalId::default();
+
+    let grant = make_workspace_interceptor(
+        &store,
+        &key,
+        "/work/project-a",
+        ApprovalDecision::ApproveAlways,
+    )
+    .await;
+    let result = grant
+        .intercept(&principal, &action, "test", None)
+        .await
+        .unwrap();
+    let InterceptProof::CapabilityCreated { token_id, .. } = result.proof else {
+        panic!("expected CapabilityCreated proof, got {:?}", result.proof);
+    };
+    let token = store.get(&token_id).unwrap().unwrap();
+    assert_eq!(token.workspace.as_deref(), Some("/work/project-a"));
+
+    // Same workspace: the token authorizes without prompting.
+    let same = make_workspace_interceptor(&store, &key, "/work/project-a", deny()).await;
+    let result = same
+        .intercept(&principal, &action, "test", None)
+        .await
+        .unwrap();
+    assert!(
+        matches!(result.proof, InterceptProof::Capability { .. }),
+        "{:?}",
+        result.proof
+    );
+
+    // Another workspace: the token does not apply, so the user is asked.
+    let other = make_workspace_interceptor(&store, &key, "/work/project-b", deny()).await;
+    assert!(
+        other
+            .intercept(&principal, &action, "test", None)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_allow_always_all_workspaces_token_is_global() {
+    let store = Arc::new(CapabilityStore::in_memory());
+    let key = Arc::new(KeyPair::generate());
+    let action = SensitiveAction::FileDelete {
+        path: "/home/user/file.txt".to_string(),
+    };
+    let principal = PrincipalId::default();
+
+    let grant = make_workspace_interceptor(
+        &store,
+        &key,
+        "/work/project-a",
+        ApprovalDecision::ApproveAlwaysAllWorkspaces,
+    )
+    .await;
+    let result = grant
+        .intercept(&principal, &action, "test", None)
+        .await
+        .unwrap();
+    let InterceptProof::CapabilityCreated { token_id, .. } = result.proof else {
+        panic!("expected CapabilityCreated proof, got {:?}", result.proof);
+    };
+    assert_eq!(store.get(&token_id).unwrap().unwrap().workspace, None);
+
+    let other = make_workspace_interceptor(
+        &store,
+        &key,
+        "/work/project-b",
+        ApprovalDecision::Deny {
+            reason: "test deny".to_string(),
+        },
+    )
+    .await;
+    let result = other
+        .intercept(&principal, &action, "test", None)
+        .await
+        .unwrap();
+    assert!(
+        matches!(result.proof, InterceptProof::Capability { .. }),
+        "{:?}",
+        result.proof
+    );
+}
+
+#[tokio::test]
+async fn test_session_approval_is_bound_to_workspace() {
+    let store = Arc::new(CapabilityStore::in_memory());
+    let key = Arc::new(KeyPair::generate());
+    let action = SensitiveAction::FileDelete {
+        path: "/home/user/file.txt".to_string(),
+    };
+    let principal = PrincipalId::default();
+
+    let grant = make_workspace_interceptor(
+        &store,
+        &key,
+        "/work/project-a",
+        ApprovalDecision::ApproveSession,
+    )
+    .await;
+    let result = grant
+        .intercept(&principal, &action, "test", None)
+        .await
+        .unwrap();
+    assert!(
+        matches!(result.proof, InterceptProof::SessionApproval { .. }),
+        "{:?}",
+        result.proof
+    );
+
+    let allowances = &grant.allowance_validator.store;
+    let project_a = PathBuf::from("/work/project-a");
+    let project_b = PathBuf::from("/work/project-b");
+    let allowance = allowances
+        .find_matching(&principal, &action, Some(&project_a))
+        .unwrap();
+    assert!(allowance.session_only);
+    assert_eq!(
+        allowance.workspace_root.as_deref(),
+        Some(project_a.as_path())
+    );
+    assert!(
+        allowances
+            .find_matching(&principal, &action, Some(&project_b))
+            .is_none()
+    );
+}
+
+// -----------------------------------------------------------------------
+// Session report — audit activity plus spend and deferred actions
+// -----------------------------------------------------------------------
+
+#[tokio::test]
+async fn test_session_report_includes_deferred_and_spend() {
+    // No handler registered: the approval is deferred.
+    let t = make_interceptor_with_audit(SecurityPolicy::default(), None).await;
+    let action = SensitiveAction::FileDelete {
+        path: "/home/user/file.txt".to_string(),
+    };
+    let result = t
+        .interceptor
+        .intercept(&PrincipalId::default(), &action, "cleanup", None)
+        .await;
+    assert!(matches!(result, Err(ApprovalError::Deferred)));
+    t.budget_tracker.record_cost(1.25);
+
+    let report = t.interceptor.session_report().unwrap();
+    assert_eq!(report.session_id, t.session_id);
+    assert_eq!(report.entries, 1);
+    assert!(report.files.is_empty(), "deferred delete did not happen");
+    assert_eq!(report.approvals_denied.len(), 1);
+    assert_eq!(report.unresolved_deferred.len(), 1);
+    assert!(report.unresolved_deferred[0].contains("/home/user/file.txt"));
+    assert_eq!(report.cost_usd, Some(1.25));
+}
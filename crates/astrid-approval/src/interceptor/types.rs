@@ -25,6 +25,9 @@ pub struct InterceptResult {
     pub audit_id: AuditEntryId,
     /// Optional budget warning (e.g. nearing limit).
     pub budget_warning: Option<BudgetWarning>,
+    /// Thresholds this action's cost crossed for the first time, workspace
+    /// first. Callers forward these to the user.
+    pub budget_alerts: Vec<crate::budget_alerts::BudgetAlert>,
 }
 
 /// How an action was authorized through the interceptor.
@@ -9,7 +9,8 @@
 //!   [`ApprovalRequest`], [`ApprovalDecision`], [`ApprovalResponse`]
 //! - **Allowance System**: [`Allowance`], [`AllowancePattern`], `AllowanceStore`
 //! - **Approval Manager**: Orchestrates the full approval flow
-//! - **Budget Tracking**: Session and per-action spending limits
+//! - **Budget Tracking**: Session and per-action spending limits, with
+//!   one-time alerts as spending crosses thresholds
 //! - **Security Policy**: Hard boundaries (blocked/approval-required tools)
 //! - **Security Interceptor**: Combines all layers (intersection semantics)
 //!
@@ -53,6 +54,7 @@ pub mod prelude;
 pub mod action;
 pub mod allowance;
 pub mod budget;
+pub mod budget_alerts;
 pub mod deferred;
 /// Error types and results for the approval module.
 pub mod error;
@@ -66,6 +68,7 @@ pub use allowance::{Allowance, AllowanceId, AllowancePattern, AllowanceStore};
 pub use budget::{
     BudgetConfig, BudgetResult, BudgetTracker, WorkspaceBudgetSnapshot, WorkspaceBudgetTracker,
 };
+pub use budget_alerts::{BudgetAlert, BudgetScope};
 pub use deferred::{
     ActionContext, DeferredResolution, DeferredResolutionStore, FallbackBehavior, PendingAction,
     Priority, ResolutionId,
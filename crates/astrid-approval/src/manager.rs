@@ -128,14 +128,23 @@ pub enum ApprovalProof {
         /// ID of the newly created allowance.
         allowance_id: crate::allowance::AllowanceId,
     },
+    /// Approved for this exact action in the workspace (survives session end).
+    ExactActionApproval {
+        /// ID of the newly created allowance.
+        allowance_id: crate::allowance::AllowanceId,
+    },
     /// Approved for the workspace scope (survives session end).
     WorkspaceApproval {
         /// ID of the newly created allowance.
         allowance_id: crate::allowance::AllowanceId,
     },
     /// Authorized by "Allow Always" — the interceptor should create a
-    /// persistent `CapabilityToken` with an `approval_audit_id` chain-link.
+    /// persistent `CapabilityToken` with an `approval_audit_id` chain-link,
+    /// bound to the current workspace.
     AlwaysAllow,
+    /// Like [`AlwaysAllow`](Self::AlwaysAllow), but the token applies in
+    /// every workspace.
+    AlwaysAllowAllWorkspaces,
     /// Authorized with a custom allowance created by the user.
     CustomAllowance {
         /// ID of the newly created allowance.
@@ -286,6 +295,15 @@ impl ApprovalManager {
                     },
                 }
             },
+            ApprovalDecision::ApproveExactAction => {
+                // The interceptor remembers the exact action, since it knows
+                // the workspace root the digest is taken against.
+                ApprovalOutcome::Allowed {
+                    proof: ApprovalProof::ExactActionApproval {
+                        allowance_id: crate::allowance::AllowanceId::new(),
+                    },
+                }
+            },
             ApprovalDecision::ApproveWorkspace => {
                 // Workspace-scoped allowance — the interceptor creates a non-session
                 // allowance (workspace-scoped, survives session end).
@@ -302,6 +320,9 @@ impl ApprovalManager {
                     proof: ApprovalProof::AlwaysAllow,
                 }
             },
+            ApprovalDecision::ApproveAlwaysAllWorkspaces => ApprovalOutcome::Allowed {
+                proof: ApprovalProof::AlwaysAllowAllWorkspaces,
+            },
             ApprovalDecision::ApproveWithAllowance(allowance) => {
                 let allowance_id = allowance.id.clone();
                 // Store the allowance
@@ -111,6 +111,9 @@ impl SecurityPolicy {
             SensitiveAction::ExecuteCommand { command, args } => {
                 self.check_execute_command(command, args)
             },
+            SensitiveAction::ShellOutsideWorkspace { command, paths } => {
+                self.check_shell_outside_workspace(command, paths)
+            },
             SensitiveAction::McpToolCall { server, tool } => self.check_mcp_tool(server, tool),
             SensitiveAction::FileRead { path } => self.check_file_path(path, "file read"),
             SensitiveAction::FileWriteOutsideSandbox { path } => {
@@ -178,6 +181,26 @@ impl SecurityPolicy {
         ))
     }
 
+    /// Check a shell command naming paths outside the workspace: the command
+    /// against the blocked tools, then each path against the path rules.
+    fn check_shell_outside_workspace(&self, command: &str, paths: &[String]) -> PolicyResult {
+        let program = command.split_whitespace().next().unwrap_or_default();
+        if let blocked @ PolicyResult::Blocked { .. } = self.check_execute_command(program, &[]) {
+            return blocked;
+        }
+        for path in paths {
+            if let blocked @ PolicyResult::Blocked { .. } =
+                self.check_file_path(path, "shell command path")
+            {
+                return blocked;
+            }
+        }
+        PolicyResult::RequiresApproval(RiskAssessment::new(format!(
+            "command names paths outside the workspace: {}",
+            paths.join(", ")
+        )))
+    }
+
     /// Check an MCP tool call.
     fn check_mcp_tool(&self, server: &str, tool: &str) -> PolicyResult {
         let qualified = format!("{server}:{tool}");
@@ -499,6 +522,23 @@ mod tests {
         assert!(policy.check(&action).is_blocked());
     }
 
+    #[test]
+    fn test_shell_outside_workspace_checks_each_path() {
+        let policy = SecurityPolicy::default();
+
+        let action = SensitiveAction::ShellOutsideWorkspace {
+            command: "cp notes.txt /home/user/backup/".to_string(),
+            paths: vec!["/home/user/backup".to_string()],
+        };
+        assert!(policy.check(&action).requires_approval());
+
+        let action = SensitiveAction::ShellOutsideWorkspace {
+            command: "cat /home/user/a /etc/shadow".to_string(),
+            paths: vec!["/home/user/a".to_string(), "/etc/shadow".to_string()],
+        };
+        assert!(policy.check(&action).is_blocked());
+    }
+
     #[test]
     fn test_default_requires_approval_for_delete() {
         let policy = SecurityPolicy::default();
@@ -22,7 +22,8 @@ pub use crate::{
 
 // Budget types
 pub use crate::{
-    BudgetConfig, BudgetResult, BudgetTracker, WorkspaceBudgetSnapshot, WorkspaceBudgetTracker,
+    BudgetAlert, BudgetConfig, BudgetResult, BudgetScope, BudgetTracker, WorkspaceBudgetSnapshot,
+    WorkspaceBudgetTracker,
 };
 
 // Policy types
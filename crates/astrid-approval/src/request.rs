@@ -137,6 +137,10 @@ pub enum ApprovalDecision {
     Approve,
     /// Approved for the rest of the session — creates a session-scoped allowance.
     ApproveSession,
+    /// Approved for this exact action in the current workspace — creates an
+    /// [`ExactAction`](crate::allowance::AllowancePattern::ExactAction)
+    /// allowance that survives session end but matches nothing else.
+    ApproveExactAction,
     /// Approved for the current workspace — creates a workspace-scoped allowance.
     ///
     /// Workspace allowances persist beyond session end but are scoped to the
@@ -147,7 +151,11 @@ pub enum ApprovalDecision {
     ///
     /// Unlike session allowances (in-memory), this creates a cryptographically
     /// signed capability token with an `approval_audit_id` chain-link.
+    /// The token is bound to the current workspace.
     ApproveAlways,
+    /// Like [`ApproveAlways`](Self::ApproveAlways), but the capability token
+    /// is not bound to a workspace and applies in every project.
+    ApproveAlwaysAllWorkspaces,
     /// Create a reusable allowance (session or persistent).
     ///
     /// Boxed to keep [`ApprovalDecision`] compact — [`Allowance`] gained a
@@ -175,8 +183,10 @@ impl ApprovalDecision {
         matches!(
             self,
             Self::ApproveSession
+                | Self::ApproveExactAction
                 | Self::ApproveWorkspace
                 | Self::ApproveAlways
+                | Self::ApproveAlwaysAllWorkspaces
                 | Self::ApproveWithAllowance(_)
         )
     }
@@ -184,7 +194,7 @@ impl ApprovalDecision {
     /// Check if this decision creates a persistent capability token.
     #[must_use]
     pub fn creates_capability(&self) -> bool {
-        matches!(self, Self::ApproveAlways)
+        matches!(self, Self::ApproveAlways | Self::ApproveAlwaysAllWorkspaces)
     }
 
     /// Get the denial reason, if this is a denial.
@@ -202,8 +212,10 @@ impl fmt::Display for ApprovalDecision {
         match self {
             Self::Approve => write!(f, "Approve (once)"),
             Self::ApproveSession => write!(f, "Approve (session)"),
+            Self::ApproveExactAction => write!(f, "Approve (this exact action)"),
             Self::ApproveWorkspace => write!(f, "Approve (workspace)"),
             Self::ApproveAlways => write!(f, "Approve (always)"),
+            Self::ApproveAlwaysAllWorkspaces => write!(f, "Approve (always, all workspaces)"),
             Self::ApproveWithAllowance(a) => write!(f, "Approve (allowance: {})", a.id),
             Self::Deny { reason } => write!(f, "Deny: {reason}"),
         }
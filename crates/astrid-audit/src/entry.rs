@@ -7,6 +7,8 @@
 use astrid_capabilities::AuditEntryId;
 use astrid_core::{Permission, SessionId, Timestamp, TokenId};
 use astrid_crypto::{ContentHash, KeyPair, PublicKey, Signature};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
 use serde::{Deserialize, Serialize};
 
 use crate::error::{AuditError, AuditResult};
@@ -255,11 +257,25 @@ pub enum AuditAction {
     },
 
     /// File was written.
+    ///
+    /// The content itself is never stored in plaintext. Build this with
+    /// [`AuditAction::file_write`] to also record its size and, optionally,
+    /// a copy sealed to the audit key for [`AuditLog::reveal`].
+    ///
+    /// [`AuditLog::reveal`]: crate::AuditLog::reveal
     FileWrite {
         /// File path.
         path: String,
         /// Hash of the written content.
         content_hash: ContentHash,
+        /// Size of the written content in bytes. Absent on entries recorded
+        /// before sizes were kept.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        size: Option<u64>,
+        /// The content sealed to the audit key
+        /// ([`astrid_crypto::sealed`]), base64-encoded.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sealed_content: Option<String>,
     },
 
     /// File was deleted.
@@ -268,6 +284,39 @@ pub enum AuditAction {
         path: String,
     },
 
+    /// Sealed content of another entry was decrypted for investigation.
+    ContentRevealed {
+        /// Entry whose content was revealed.
+        entry_id: AuditEntryId,
+        /// Recorded hash of the revealed content.
+        content_hash: ContentHash,
+        /// Key ID (hex) of the key pair used to decrypt it.
+        revealed_by: String,
+    },
+
+    /// A capsule read (or was refused) a secret value.
+    SecretRead {
+        /// Capsule that asked for the secret.
+        capsule_id: String,
+        /// Secret name. The value is never recorded.
+        name: String,
+    },
+
+    /// An LLM judge hook decided whether an operation may proceed.
+    JudgeDecision {
+        /// Hook event the judge was asked about (e.g. `pre_tool_call`).
+        event: String,
+        /// `allow`, `deny` or `modify`.
+        decision: String,
+        /// The judge's stated reason, or why the fallback applied.
+        reason: String,
+        /// Whether the hook's fallback decision applied instead of a verdict.
+        fallback: bool,
+        /// Model that answered, if the judge replied at all.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+    },
+
     /// Capability token was created.
     CapabilityCreated {
         /// Token ID.
@@ -433,8 +482,46 @@ pub enum AuditAction {
 }
 
 impl AuditAction {
+    /// A [`FileWrite`](Self::FileWrite) recording the hash and size of
+    /// `content`, and the content sealed to `seal_to` when given.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AuditError::CryptoError`] if `seal_to` is not a usable
+    /// public key.
+    pub fn file_write(
+        path: impl Into<String>,
+        content: &[u8],
+        seal_to: Option<&PublicKey>,
+    ) -> AuditResult<Self> {
+        let sealed_content = seal_to
+            .map(|key| astrid_crypto::sealed::encrypt_for(key, content))
+            .transpose()?
+            .map(|sealed| BASE64.encode(sealed));
+        Ok(Self::FileWrite {
+            path: path.into(),
+            content_hash: ContentHash::hash(content),
+            size: Some(u64::try_from(content.len()).unwrap_or(u64::MAX)),
+            sealed_content,
+        })
+    }
+
+    /// The recorded hash and base64 sealed copy of this action's content,
+    /// if it has one.
+    pub(crate) fn sealed_content(&self) -> Option<(ContentHash, &str)> {
+        match self {
+            Self::FileWrite {
+                content_hash,
+                sealed_content: Some(sealed),
+                ..
+            } => Some((*content_hash, sealed)),
+            _ => None,
+        }
+    }
+
     /// Get a human-readable description of the action.
     #[must_use]
+    #[expect(clippy::too_many_lines)]
     pub fn description(&self) -> String {
         match self {
             Self::McpToolCall { server, tool, .. } => {
@@ -471,6 +558,21 @@ impl AuditAction {
             Self::FileDelete { path } => {
                 format!("Deleted file {path}")
             },
+            Self::ContentRevealed { entry_id, .. } => {
+                format!("Revealed content of entry {entry_id}")
+            },
+            Self::SecretRead { capsule_id, name } => {
+                format!("Capsule {capsule_id} read secret {name}")
+            },
+            Self::JudgeDecision {
+                event,
+                decision,
+                fallback,
+                ..
+            } => {
+                let how = if *fallback { " (fallback)" } else { "" };
+                format!("Judge decided {decision} on {event}{how}")
+            },
             Self::CapabilityCreated { resource, .. } => {
                 format!("Created capability for {resource}")
             },
@@ -43,6 +43,17 @@ pub enum AuditError {
         session_id: String,
     },
 
+    /// The entry has no sealed content to reveal.
+    #[error("audit entry {entry_id} has no sealed content")]
+    NotSealed {
+        /// The entry that was asked for.
+        entry_id: String,
+    },
+
+    /// The caller lacks the capability the operation requires.
+    #[error("permission denied: {0}")]
+    PermissionDenied(String),
+
     /// Crypto error.
     #[error("crypto error: {0}")]
     CryptoError(#[from] astrid_crypto::CryptoError),
@@ -62,11 +62,13 @@ pub mod prelude;
 mod entry;
 mod error;
 mod log;
+mod report;
 mod storage;
 
 pub use entry::{ApprovalScope, AuditAction, AuditEntry, AuditOutcome, AuthorizationProof};
 pub use error::{AuditError, AuditResult};
-pub use log::{AuditLog, ChainIssue, ChainVerificationResult};
+pub use log::{AuditLog, ChainIssue, ChainVerificationResult, REVEAL_CAPABILITY};
+pub use report::{ApprovalRecord, FileActivity, SessionReport};
 
 // Re-export AuditEntryId from capabilities for convenience
 pub use astrid_capabilities::AuditEntryId;
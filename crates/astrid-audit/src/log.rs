@@ -2,15 +2,21 @@
 //!
 //! Provides a high-level API for recording and verifying audit entries.
 
-use astrid_capabilities::AuditEntryId;
+use astrid_capabilities::{AuditEntryId, CapabilityCheck};
 use astrid_core::SessionId;
-use astrid_crypto::{ContentHash, KeyPair};
+use astrid_crypto::{
+    BatchItem, ContentHash, CryptoError, CryptoResult, KeyPair, SignatureVerifier,
+};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::collections::HashSet;
 use std::path::Path;
 use std::sync::RwLock;
 use tracing::{debug, error, warn};
 
 use crate::entry::{AuditAction, AuditEntry, AuditOutcome, AuthorizationProof};
 use crate::error::{AuditError, AuditResult};
+use crate::report::SessionReport;
 use crate::storage::{AuditStorage, SurrealKvAuditStorage};
 
 /// Key for the per-chain head cache: (session, optional principal).
@@ -19,6 +25,9 @@ use crate::storage::{AuditStorage, SurrealKvAuditStorage};
 /// Principal entries use `(session_id, Some(principal))`.
 type ChainKey = (SessionId, Option<astrid_core::PrincipalId>);
 
+/// Capability a principal must hold to [`AuditLog::reveal`] sealed content.
+pub const REVEAL_CAPABILITY: &str = "audit:reveal";
+
 /// Audit log for recording and verifying security events.
 pub struct AuditLog {
     /// Storage backend.
@@ -188,6 +197,91 @@ impl AuditLog {
         self.storage.get(id)
     }
 
+    /// Decrypt the sealed content of an entry for investigation.
+    ///
+    /// Only entries recorded with sealed content (see
+    /// [`AuditAction::file_write`]) can be revealed, only by a principal
+    /// holding [`REVEAL_CAPABILITY`], and only with the key pair the content
+    /// was sealed to. Every attempt on such an entry is itself audited in
+    /// the entry's session as [`AuditAction::ContentRevealed`] under the
+    /// requesting principal, with a failure outcome when it is denied or
+    /// the content cannot be opened.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AuditError::EntryNotFound`] if the entry does not exist,
+    /// [`AuditError::NotSealed`] if it carries no sealed content,
+    /// [`AuditError::PermissionDenied`] if `check` does not grant
+    /// [`REVEAL_CAPABILITY`], [`AuditError::CryptoError`] if `keypair`
+    /// cannot open it, and [`AuditError::IntegrityViolation`] if the
+    /// opened content does not match the recorded hash.
+    pub fn reveal(
+        &self,
+        entry_id: &AuditEntryId,
+        keypair: &KeyPair,
+        check: &CapabilityCheck<'_>,
+    ) -> AuditResult<Vec<u8>> {
+        let entry = self
+            .get(entry_id)?
+            .ok_or_else(|| AuditError::EntryNotFound {
+                entry_id: entry_id.to_string(),
+            })?;
+        let Some((content_hash, sealed)) = entry.action.sealed_content() else {
+            return Err(AuditError::NotSealed {
+                entry_id: entry_id.to_string(),
+            });
+        };
+
+        let principal = check.principal().clone();
+        let revealed = AuditAction::ContentRevealed {
+            entry_id: entry_id.clone(),
+            content_hash,
+            revealed_by: keypair.key_id_hex(),
+        };
+        if let Err(e) = check.require(REVEAL_CAPABILITY) {
+            self.append_with_principal(
+                entry.session_id,
+                principal,
+                revealed,
+                AuthorizationProof::Denied {
+                    reason: e.to_string(),
+                },
+                AuditOutcome::failure(e.to_string()),
+            )?;
+            return Err(AuditError::PermissionDenied(e.to_string()));
+        }
+
+        let opened = BASE64
+            .decode(sealed)
+            .map_err(|e| AuditError::SerializationError(format!("invalid sealed content: {e}")))
+            .and_then(|bytes| Ok(astrid_crypto::sealed::decrypt(keypair, &bytes)?))
+            .and_then(|plaintext| {
+                if ContentHash::hash(&plaintext) == content_hash {
+                    Ok(plaintext)
+                } else {
+                    Err(AuditError::IntegrityViolation {
+                        entry_id: entry_id.to_string(),
+                        reason: "revealed content does not match the recorded hash".to_string(),
+                    })
+                }
+            });
+
+        let outcome = match &opened {
+            Ok(_) => AuditOutcome::success(),
+            Err(e) => AuditOutcome::failure(e.to_string()),
+        };
+        self.append_with_principal(
+            entry.session_id,
+            principal.clone(),
+            revealed,
+            AuthorizationProof::System {
+                reason: format!("policy allow: {principal} holds {REVEAL_CAPABILITY}"),
+            },
+            outcome,
+        )?;
+        opened
+    }
+
     /// Get all entries for a session.
     ///
     /// # Errors
@@ -197,6 +291,22 @@ impl AuditLog {
         self.storage.get_session_entries(session_id)
     }
 
+    /// Build the end-of-session report for a session.
+    ///
+    /// Entries are streamed from storage into the report one at a time.
+    /// The report's cost and deferred-action fields are left empty; see
+    /// [`SessionReport`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails to retrieve entries.
+    pub fn session_report(&self, session_id: &SessionId) -> AuditResult<SessionReport> {
+        let mut report = SessionReport::new(session_id.clone());
+        self.storage
+            .for_each_session_entry(session_id, &mut |entry| report.record(&entry))?;
+        Ok(report)
+    }
+
     /// Verify the integrity of all audit chains in a session.
     ///
     /// Each principal (and the system chain) is verified independently.
@@ -231,6 +341,7 @@ impl AuditLog {
 
         let mut issues = Vec::new();
         let mut entries_verified: usize = 0;
+        let invalid = invalid_signatures(&entries);
 
         // Verify each chain independently.
         for chain_entries in chains.values_mut() {
@@ -246,8 +357,8 @@ impl AuditLog {
 
             // Verify signatures.
             for entry in chain_entries.iter() {
-                if let Err(e) = entry.verify_signature() {
-                    error!(entry_id = %entry.id, error = %e, "Invalid signature");
+                if invalid.contains(&entry.id) {
+                    error!(entry_id = %entry.id, "Invalid signature");
                     issues.push(ChainIssue::InvalidSignature {
                         entry_id: entry.id.clone(),
                     });
@@ -317,9 +428,10 @@ impl AuditLog {
             });
         }
 
+        let invalid = invalid_signatures(&sorted);
         for entry in &sorted {
-            if let Err(e) = entry.verify_signature() {
-                error!(entry_id = %entry.id, error = %e, "Invalid signature");
+            if invalid.contains(&entry.id) {
+                error!(entry_id = %entry.id, "Invalid signature");
                 issues.push(ChainIssue::InvalidSignature {
                     entry_id: entry.id.clone(),
                 });
@@ -420,6 +532,16 @@ impl AuditLog {
         self.storage.flush()
     }
 
+    /// Close the underlying storage, releasing its on-disk lock so the log
+    /// can be reopened. Appends after this fail.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails to close.
+    pub fn close(&self) -> AuditResult<()> {
+        self.storage.close()
+    }
+
     /// Get the runtime public key.
     #[must_use]
     pub fn runtime_public_key(&self) -> astrid_crypto::PublicKey {
@@ -435,6 +557,44 @@ impl std::fmt::Debug for AuditLog {
     }
 }
 
+/// IDs of the entries whose signatures do not verify.
+///
+/// Signatures are checked in batches, which is much faster than
+/// [`AuditEntry::verify_signature`] one entry at a time on long chains.
+/// Any batch error other than a list of failing entries falls back to
+/// checking each entry on its own, so an unexpected error never passes
+/// the chain.
+fn invalid_signatures(entries: &[AuditEntry]) -> HashSet<AuditEntryId> {
+    let signing_data: Vec<Vec<u8>> = entries.iter().map(AuditEntry::signing_data).collect();
+    let items: Vec<BatchItem<'_>> = entries
+        .iter()
+        .zip(&signing_data)
+        .map(|(entry, data)| (data.as_slice(), &entry.signature, &entry.runtime_key))
+        .collect();
+    invalid_from_batch(entries, SignatureVerifier::new().verify_batch(&items))
+}
+
+/// IDs of the failing entries, given the batch verification `result` for
+/// `entries`.
+fn invalid_from_batch(entries: &[AuditEntry], result: CryptoResult<()>) -> HashSet<AuditEntryId> {
+    match result {
+        Ok(()) => HashSet::new(),
+        Err(CryptoError::BatchVerificationFailed { invalid }) => invalid
+            .into_iter()
+            .filter_map(|index| entries.get(index))
+            .map(|entry| entry.id.clone())
+            .collect(),
+        Err(e) => {
+            warn!(error = %e, "Batch signature verification failed, checking entries one by one");
+            entries
+                .iter()
+                .filter(|entry| entry.verify_signature().is_err())
+                .map(|entry| entry.id.clone())
+                .collect()
+        },
+    }
+}
+
 /// Result of chain verification.
 #[derive(Debug, Clone)]
 pub struct ChainVerificationResult {
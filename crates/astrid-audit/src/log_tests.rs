@@ -1,4 +1,8 @@
 use super::*;
+use crate::storage::SurrealKvAuditStorage;
+use astrid_storage::testing::FaultInjectingKvStore;
+use astrid_storage::{KvStore, MemoryKvStore};
+use std::sync::Arc;
 
 /// Append `count` test entries to the log, returning their IDs.
 fn append_test_entries(log: &AuditLog, session_id: &SessionId, count: u32) -> Vec<AuditEntryId> {
@@ -102,6 +106,22 @@ fn test_verify_detects_tampered_signature() {
     )));
 }
 
+#[test]
+fn test_unexpected_batch_error_checks_each_signature() {
+    let log = AuditLog::in_memory(KeyPair::generate());
+    let session_id = SessionId::new();
+    let ids = append_test_entries(&log, &session_id, 3);
+
+    let mut entries: Vec<AuditEntry> = ids.iter().map(|id| log.get(id).unwrap().unwrap()).collect();
+    let mut bad_sig = *entries[1].signature.as_bytes();
+    bad_sig[0] ^= 0xFF;
+    entries[1].signature = astrid_crypto::Signature::from_bytes(bad_sig);
+
+    // An error that names no entries must not pass the chain.
+    let invalid = invalid_from_batch(&entries, Err(CryptoError::SignatureVerificationFailed));
+    assert_eq!(invalid, HashSet::from([ids[1].clone()]));
+}
+
 #[test]
 fn test_verify_detects_broken_link() {
     let keypair = KeyPair::generate();
@@ -214,6 +234,35 @@ fn test_verify_all_detects_tampered_session() {
     assert!(!b_result.1.valid);
 }
 
+#[test]
+fn test_batch_verification_pinpoints_tampered_entries() {
+    let keypair = KeyPair::generate();
+    let log = AuditLog::in_memory(keypair);
+    let session_id = SessionId::new();
+    // Enough entries to span several verification batches.
+    let ids = append_test_entries(&log, &session_id, 600);
+
+    for id in [&ids[10], &ids[517]] {
+        let mut entry = log.get(id).unwrap().unwrap();
+        let mut bad_sig = *entry.signature.as_bytes();
+        bad_sig[0] ^= 0xFF;
+        entry.signature = astrid_crypto::Signature::from_bytes(bad_sig);
+        log.storage.store(&entry).unwrap();
+    }
+
+    let result = log.verify_chain(&session_id).unwrap();
+    assert!(!result.valid);
+    let flagged: HashSet<_> = result
+        .issues
+        .iter()
+        .filter_map(|issue| match issue {
+            ChainIssue::InvalidSignature { entry_id } => Some(entry_id.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(flagged, HashSet::from([ids[10].clone(), ids[517].clone()]));
+}
+
 #[test]
 fn test_verify_empty_log_is_valid() {
     let keypair = KeyPair::generate();
@@ -453,3 +502,316 @@ fn test_mixed_session_verify_chain_passes() {
     assert!(result.valid, "mixed chain: {:?}", result.issues);
     assert_eq!(result.entries_verified, 4);
 }
+
+// -- Storage failure paths --
+
+/// An audit log over a fault-injecting in-memory store.
+fn faulty_log() -> (AuditLog, Arc<FaultInjectingKvStore>) {
+    let store = Arc::new(FaultInjectingKvStore::new(MemoryKvStore::new()));
+    let storage = SurrealKvAuditStorage::with_store(Arc::clone(&store) as Arc<dyn KvStore>);
+    let log = AuditLog {
+        storage: Box::new(storage),
+        runtime_key: KeyPair::generate(),
+        chain_heads: RwLock::new(std::collections::HashMap::new()),
+    };
+    (log, store)
+}
+
+#[test]
+fn test_append_surfaces_each_storage_write_failure() {
+    // `store` issues four KV operations per entry: write the entry and the
+    // chain head, then read and rewrite the session index.
+    for failing_op in 1..=4 {
+        let (log, store) = faulty_log();
+        let session_id = SessionId::new();
+        append_test_entries(&log, &session_id, 1);
+
+        store.fail_nth(failing_op);
+        let result = log.append(
+            session_id.clone(),
+            AuditAction::SessionEnded {
+                reason: "test".to_string(),
+                duration_secs: 0,
+            },
+            AuthorizationProof::System {
+                reason: "test".to_string(),
+            },
+            AuditOutcome::success(),
+        );
+        assert!(
+            matches!(result, Err(AuditError::StorageError(_))),
+            "op {failing_op}: {result:?}"
+        );
+
+        // Later appends still land and the session chain still verifies.
+        append_test_entries(&log, &session_id, 1);
+        let verification = log.verify_chain(&session_id).unwrap();
+        assert!(
+            verification.valid,
+            "op {failing_op}: {:?}",
+            verification.issues
+        );
+    }
+}
+
+#[test]
+fn test_storage_outage_fails_reads_and_recovers() {
+    let (log, store) = faulty_log();
+    let session_id = SessionId::new();
+    let ids = append_test_entries(&log, &session_id, 2);
+
+    store.fail_all(true);
+    assert!(matches!(log.get(&ids[0]), Err(AuditError::StorageError(_))));
+    assert!(log.verify_chain(&session_id).is_err());
+    assert!(log.count().is_err());
+
+    store.fail_all(false);
+    assert_eq!(log.count().unwrap(), 2);
+    assert!(log.verify_chain(&session_id).unwrap().valid);
+}
+
+#[test]
+fn test_corrupted_entry_bytes_are_rejected() {
+    let (log, store) = faulty_log();
+    let session_id = SessionId::new();
+    let ids = append_test_entries(&log, &session_id, 2);
+
+    store.corrupt_key("audit:entries", &ids[1].0.to_string());
+    assert!(matches!(
+        log.get(&ids[1]),
+        Err(AuditError::SerializationError(_))
+    ));
+    assert!(log.get(&ids[0]).unwrap().is_some());
+    assert!(matches!(
+        log.verify_chain(&session_id),
+        Err(AuditError::SerializationError(_))
+    ));
+}
+
+// -- Sealed content and reveal --
+
+/// An audit log over a plain in-memory store the test can inspect.
+fn inspectable_log() -> (AuditLog, Arc<MemoryKvStore>) {
+    let store = Arc::new(MemoryKvStore::new());
+    let storage = SurrealKvAuditStorage::with_store(Arc::clone(&store) as Arc<dyn KvStore>);
+    let log = AuditLog {
+        storage: Box::new(storage),
+        runtime_key: KeyPair::generate(),
+        chain_heads: RwLock::new(std::collections::HashMap::new()),
+    };
+    (log, store)
+}
+
+fn append_file_write(log: &AuditLog, session_id: &SessionId, action: AuditAction) -> AuditEntryId {
+    log.append(
+        session_id.clone(),
+        action,
+        AuthorizationProof::NotRequired {
+            reason: "test".to_string(),
+        },
+        AuditOutcome::success(),
+    )
+    .unwrap()
+}
+
+/// Reveal `id` as the `investigator` principal, granted
+/// [`REVEAL_CAPABILITY`] only when `granted` is set.
+fn reveal_as(
+    log: &AuditLog,
+    id: &AuditEntryId,
+    keypair: &KeyPair,
+    granted: bool,
+) -> AuditResult<Vec<u8>> {
+    let profile = astrid_core::profile::PrincipalProfile {
+        grants: if granted {
+            vec![REVEAL_CAPABILITY.to_string()]
+        } else {
+            Vec::new()
+        },
+        ..Default::default()
+    };
+    let groups = astrid_core::groups::GroupConfig::builtin_only();
+    let investigator = astrid_core::PrincipalId::new("investigator").unwrap();
+    log.reveal(
+        id,
+        keypair,
+        &CapabilityCheck::new(&profile, &groups, investigator),
+    )
+}
+
+/// The `ContentRevealed` entries of a session, with their outcomes.
+fn reveal_entries(log: &AuditLog, session_id: &SessionId) -> Vec<AuditOutcome> {
+    log.get_session_entries(session_id)
+        .unwrap()
+        .into_iter()
+        .filter(|e| matches!(e.action, AuditAction::ContentRevealed { .. }))
+        .map(|e| e.outcome)
+        .collect()
+}
+
+#[test]
+fn test_reveal_round_trip_is_audited() {
+    let (log, _) = inspectable_log();
+    let audit_key = KeyPair::generate();
+    let session_id = SessionId::new();
+    let content = b"API_KEY=sk-live-1234";
+
+    let action =
+        AuditAction::file_write("/work/.env", content, Some(&audit_key.export_public_key()))
+            .unwrap();
+    let AuditAction::FileWrite {
+        content_hash, size, ..
+    } = &action
+    else {
+        unreachable!()
+    };
+    assert_eq!(*content_hash, ContentHash::hash(content));
+    assert_eq!(*size, Some(20));
+    let id = append_file_write(&log, &session_id, action);
+
+    assert_eq!(reveal_as(&log, &id, &audit_key, true).unwrap(), content);
+    let reveals = reveal_entries(&log, &session_id);
+    assert!(matches!(reveals.as_slice(), [AuditOutcome::Success { .. }]));
+    assert!(log.verify_chain(&session_id).unwrap().valid);
+}
+
+#[test]
+fn test_reveal_with_wrong_key_fails_and_is_audited() {
+    let (log, _) = inspectable_log();
+    let audit_key = KeyPair::generate();
+    let session_id = SessionId::new();
+    let action =
+        AuditAction::file_write("/tmp/x", b"secret", Some(&audit_key.export_public_key())).unwrap();
+    let id = append_file_write(&log, &session_id, action);
+
+    let err = reveal_as(&log, &id, &KeyPair::generate(), true).unwrap_err();
+    assert!(matches!(err, AuditError::CryptoError(_)), "{err:?}");
+    let reveals = reveal_entries(&log, &session_id);
+    assert!(matches!(reveals.as_slice(), [AuditOutcome::Failure { .. }]));
+}
+
+#[test]
+fn test_reveal_requires_capability_and_audits_the_denial() {
+    let (log, _) = inspectable_log();
+    let audit_key = KeyPair::generate();
+    let session_id = SessionId::new();
+    let action =
+        AuditAction::file_write("/tmp/x", b"secret", Some(&audit_key.export_public_key())).unwrap();
+    let id = append_file_write(&log, &session_id, action);
+
+    // Holding the key pair is not enough without the capability.
+    let err = reveal_as(&log, &id, &audit_key, false).unwrap_err();
+    assert!(matches!(err, AuditError::PermissionDenied(_)), "{err:?}");
+
+    let denied: Vec<_> = log
+        .get_session_entries(&session_id)
+        .unwrap()
+        .into_iter()
+        .filter(|e| matches!(e.action, AuditAction::ContentRevealed { .. }))
+        .collect();
+    assert_eq!(denied.len(), 1);
+    assert_eq!(
+        denied[0]
+            .principal
+            .as_ref()
+            .map(astrid_core::PrincipalId::as_str),
+        Some("investigator")
+    );
+    assert!(matches!(
+        denied[0].authorization,
+        AuthorizationProof::Denied { .. }
+    ));
+    assert!(matches!(denied[0].outcome, AuditOutcome::Failure { .. }));
+}
+
+#[test]
+fn test_reveal_requires_sealed_content() {
+    let (log, _) = inspectable_log();
+    let session_id = SessionId::new();
+    let id = append_file_write(
+        &log,
+        &session_id,
+        AuditAction::file_write("/tmp/x", b"data", None).unwrap(),
+    );
+
+    let key = KeyPair::generate();
+    assert!(matches!(
+        reveal_as(&log, &id, &key, true),
+        Err(AuditError::NotSealed { .. })
+    ));
+    assert!(matches!(
+        reveal_as(&log, &AuditEntryId::new(), &key, true),
+        Err(AuditError::EntryNotFound { .. })
+    ));
+    assert!(reveal_entries(&log, &session_id).is_empty());
+}
+
+#[test]
+fn test_plaintext_never_reaches_storage() {
+    let (log, store) = inspectable_log();
+    let audit_key = KeyPair::generate();
+    let session_id = SessionId::new();
+    let content = b"correct horse battery staple";
+
+    let sealed = AuditAction::file_write("/a", content, Some(&audit_key.export_public_key()));
+    let unsealed = AuditAction::file_write("/b", content, None);
+    let id = append_file_write(&log, &session_id, sealed.unwrap());
+    append_file_write(&log, &session_id, unsealed.unwrap());
+    reveal_as(&log, &id, &audit_key, true).unwrap();
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let mut scanned = 0;
+    for namespace in ["audit:entries", "audit:session_index", "audit:chain_heads"] {
+        for key in rt.block_on(store.list_keys(namespace)).unwrap() {
+            let value = rt.block_on(store.get(namespace, &key)).unwrap().unwrap();
+            assert!(
+                !value.windows(content.len()).any(|w| w == content),
+                "plaintext stored under {namespace}/{key}"
+            );
+            scanned += 1;
+        }
+    }
+    assert!(scanned >= 3, "scanned only {scanned} values");
+}
+
+#[test]
+fn test_file_write_without_content_fields_round_trips() {
+    // Entries written before sizes and sealed content existed must still
+    // deserialize, and must serialize to the same bytes so their
+    // signatures keep verifying.
+    let json = serde_json::json!({
+        "type": "file_write",
+        "path": "/tmp/x",
+        "content_hash": ContentHash::hash(b"x"),
+    });
+    let action: AuditAction = serde_json::from_value(json.clone()).unwrap();
+    assert!(matches!(
+        &action,
+        AuditAction::FileWrite {
+            size: None,
+            sealed_content: None,
+            ..
+        }
+    ));
+    assert_eq!(serde_json::to_value(&action).unwrap(), json);
+}
+
+#[test]
+fn test_session_report_covers_only_its_session() {
+    let log = AuditLog::in_memory(KeyPair::generate());
+    let session_id = SessionId::new();
+    let other = SessionId::new();
+    append_test_entries(&log, &session_id, 3);
+    append_test_entries(&log, &other, 2);
+
+    let report = log.session_report(&session_id).unwrap();
+    assert_eq!(report.session_id, session_id);
+    assert_eq!(report.entries, 3);
+    assert_eq!(report.tool_calls.len(), 3);
+    assert!(report.tool_calls.contains_key("test:tool_0"));
+    assert!(report.started_at.is_some());
+
+    let empty = log.session_report(&SessionId::new()).unwrap();
+    assert_eq!(empty.entries, 0);
+    assert_eq!(empty.started_at, None);
+}
@@ -42,5 +42,8 @@ pub use crate::{ApprovalScope, AuditAction, AuditEntry, AuditOutcome, Authorizat
 // Log and verification
 pub use crate::{AuditLog, ChainIssue, ChainVerificationResult};
 
+// Reports
+pub use crate::SessionReport;
+
 // Re-export from capabilities
 pub use crate::AuditEntryId;
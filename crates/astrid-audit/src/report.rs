@@ -0,0 +1,457 @@
+//! Session-end reports.
+//!
+//! A [`SessionReport`] summarizes what happened in one session: files
+//! touched, commands run, tool calls, approval decisions and LLM usage. It
+//! is assembled by folding the session's audit entries through
+//! [`SessionReport::record`], one at a time, so a long session never has
+//! to be held in memory ([`AuditLog::session_report`] streams from
+//! storage).
+//!
+//! Spend and deferred actions are not in the audit log. Whoever owns the
+//! budget tracker and deferred queue fills in [`SessionReport::cost_usd`]
+//! and [`SessionReport::unresolved_deferred`].
+//!
+//! [`AuditLog::session_report`]: crate::AuditLog::session_report
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use astrid_core::{SessionId, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::entry::{AuditAction, AuditEntry, AuditOutcome, AuthorizationProof};
+use crate::error::{AuditError, AuditResult};
+
+/// `action_type` the approval interceptor records for shell commands.
+const EXECUTE_COMMAND: &str = "execute_command";
+
+/// What happened to one file during a session.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileActivity {
+    /// Successful reads.
+    pub reads: usize,
+    /// Successful writes.
+    pub writes: usize,
+    /// Whether the file was deleted.
+    pub deleted: bool,
+}
+
+/// A single approval decision.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalRecord {
+    /// When the decision was recorded.
+    pub at: Timestamp,
+    /// What was approved or denied.
+    pub action: String,
+    /// Approval scope, or the reason for a denial.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Summary of a session, built from its audit trail.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionReport {
+    /// Session the report covers.
+    pub session_id: SessionId,
+    /// Time of the first audit entry.
+    pub started_at: Option<Timestamp>,
+    /// Time of the last audit entry.
+    pub ended_at: Option<Timestamp>,
+    /// Reason recorded when the session ended, if it has.
+    pub end_reason: Option<String>,
+    /// Number of audit entries folded into the report.
+    pub entries: usize,
+    /// Files touched, keyed by path.
+    pub files: BTreeMap<String, FileActivity>,
+    /// Commands run, in order.
+    pub commands: Vec<String>,
+    /// Tool calls per `server:tool` (or `capsule:tool`).
+    pub tool_calls: BTreeMap<String, usize>,
+    /// Approvals the user granted.
+    pub approvals_granted: Vec<ApprovalRecord>,
+    /// Actions that were denied.
+    pub approvals_denied: Vec<ApprovalRecord>,
+    /// Number of LLM requests.
+    pub llm_requests: usize,
+    /// LLM input tokens.
+    pub input_tokens: usize,
+    /// LLM output tokens.
+    pub output_tokens: usize,
+    /// Actions that failed or were refused.
+    pub failures: usize,
+    /// Security violations, in order.
+    pub security_violations: Vec<String>,
+    /// Session spend in USD. Not recorded in the audit log; set by the
+    /// owner of the budget tracker.
+    pub cost_usd: Option<f64>,
+    /// Actions still waiting for a decision. Not recorded in the audit log;
+    /// set by the owner of the deferred queue.
+    pub unresolved_deferred: Vec<String>,
+}
+
+impl SessionReport {
+    /// An empty report for `session_id`.
+    #[must_use]
+    pub fn new(session_id: SessionId) -> Self {
+        Self {
+            session_id,
+            started_at: None,
+            ended_at: None,
+            end_reason: None,
+            entries: 0,
+            files: BTreeMap::new(),
+            commands: Vec::new(),
+            tool_calls: BTreeMap::new(),
+            approvals_granted: Vec::new(),
+            approvals_denied: Vec::new(),
+            llm_requests: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+            failures: 0,
+            security_violations: Vec::new(),
+            cost_usd: None,
+            unresolved_deferred: Vec::new(),
+        }
+    }
+
+    /// Fold one audit entry into the report.
+    ///
+    /// Entries must be recorded in the order they were appended.
+    pub fn record(&mut self, entry: &AuditEntry) {
+        self.entries = self.entries.saturating_add(1);
+        self.started_at.get_or_insert(entry.timestamp);
+        self.ended_at = Some(entry.timestamp);
+
+        self.record_authorization(entry);
+        if let AuditOutcome::Failure { .. } = entry.outcome {
+            self.failures = self.failures.saturating_add(1);
+        }
+        let denied = matches!(entry.authorization, AuthorizationProof::Denied { .. });
+        let succeeded = !denied && matches!(entry.outcome, AuditOutcome::Success { .. });
+
+        match &entry.action {
+            AuditAction::FileRead { path } if succeeded => {
+                let file = self.files.entry(path.clone()).or_default();
+                file.reads = file.reads.saturating_add(1);
+            },
+            AuditAction::FileWrite { path, .. } if succeeded => {
+                let file = self.files.entry(path.clone()).or_default();
+                file.writes = file.writes.saturating_add(1);
+            },
+            AuditAction::FileDelete { path } if succeeded => {
+                self.files.entry(path.clone()).or_default().deleted = true;
+            },
+            AuditAction::ApprovalRequested {
+                action_type,
+                resource,
+            } if succeeded && action_type == EXECUTE_COMMAND => {
+                self.commands.push(resource.trim().to_string());
+            },
+            AuditAction::McpToolCall { server, tool, .. } if succeeded => {
+                self.count_tool_call(format!("{server}:{tool}"));
+            },
+            AuditAction::CapsuleToolCall {
+                capsule_id, tool, ..
+            } if succeeded => {
+                self.count_tool_call(format!("{capsule_id}:{tool}"));
+            },
+            AuditAction::ApprovalGranted {
+                action,
+                resource,
+                scope,
+            } => {
+                let action = match resource {
+                    Some(resource) => format!("{action} on {resource}"),
+                    None => action.clone(),
+                };
+                self.approvals_granted.push(ApprovalRecord {
+                    at: entry.timestamp,
+                    action,
+                    detail: Some(scope.to_string()),
+                });
+            },
+            AuditAction::ApprovalDenied { action, reason } => {
+                self.approvals_denied.push(ApprovalRecord {
+                    at: entry.timestamp,
+                    action: action.clone(),
+                    detail: reason.clone(),
+                });
+            },
+            AuditAction::LlmRequest {
+                input_tokens,
+                output_tokens,
+                ..
+            } => {
+                self.llm_requests = self.llm_requests.saturating_add(1);
+                self.input_tokens = self.input_tokens.saturating_add(*input_tokens);
+                self.output_tokens = self.output_tokens.saturating_add(*output_tokens);
+            },
+            AuditAction::SessionEnded { reason, .. } => {
+                self.end_reason = Some(reason.clone());
+            },
+            AuditAction::SecurityViolation {
+                violation_type,
+                details,
+            } => {
+                self.security_violations
+                    .push(format!("{violation_type}: {details}"));
+            },
+            _ => {},
+        }
+    }
+
+    /// Record the approval decision carried by an entry's authorization.
+    fn record_authorization(&mut self, entry: &AuditEntry) {
+        match (&entry.authorization, &entry.action) {
+            (_, AuditAction::ApprovalGranted { .. } | AuditAction::ApprovalDenied { .. }) => {},
+            (AuthorizationProof::Denied { reason }, action) => {
+                self.approvals_denied.push(ApprovalRecord {
+                    at: entry.timestamp,
+                    action: describe(action),
+                    detail: Some(reason.clone()),
+                });
+            },
+            // A root approval: the user just said yes. Later entries
+            // authorized by it point back at it and are not counted again.
+            (
+                AuthorizationProof::UserApproval {
+                    approval_entry_id: None,
+                    ..
+                },
+                action,
+            ) => {
+                self.approvals_granted.push(ApprovalRecord {
+                    at: entry.timestamp,
+                    action: describe(action),
+                    detail: None,
+                });
+            },
+            _ => {},
+        }
+    }
+
+    fn count_tool_call(&mut self, name: String) {
+        let count = self.tool_calls.entry(name).or_default();
+        *count = count.saturating_add(1);
+    }
+
+    /// Render the report as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AuditError::SerializationError`] if serialization fails.
+    pub fn to_json(&self) -> AuditResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| AuditError::SerializationError(e.to_string()))
+    }
+
+    /// Render the report as Markdown for humans.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        // Writing to a String cannot fail.
+        let _ = self.write_markdown(&mut out);
+        out
+    }
+
+    fn write_markdown(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "# Session report\n")?;
+        writeln!(out, "- Session: `{}`", self.session_id)?;
+        if let Some(at) = self.started_at {
+            writeln!(out, "- Started: {at}")?;
+        }
+        match (self.ended_at, &self.end_reason) {
+            (Some(at), Some(reason)) => writeln!(out, "- Ended: {at} ({reason})")?,
+            (Some(at), None) => writeln!(out, "- Last activity: {at}")?,
+            _ => {},
+        }
+        writeln!(out, "- Audit entries: {}", self.entries)?;
+
+        writeln!(out, "\n## Files\n")?;
+        if self.files.is_empty() {
+            writeln!(out, "_None._")?;
+        } else {
+            writeln!(out, "| Path | Reads | Writes | Deleted |")?;
+            writeln!(out, "| --- | --- | --- | --- |")?;
+            for (path, file) in &self.files {
+                let deleted = if file.deleted { "yes" } else { "no" };
+                writeln!(
+                    out,
+                    "| `{path}` | {} | {} | {deleted} |",
+                    file.reads, file.writes
+                )?;
+            }
+        }
+
+        writeln!(out, "\n## Commands\n")?;
+        if self.commands.is_empty() {
+            writeln!(out, "_None._")?;
+        }
+        for command in &self.commands {
+            writeln!(out, "- `{command}`")?;
+        }
+
+        writeln!(out, "\n## Tool calls\n")?;
+        if self.tool_calls.is_empty() {
+            writeln!(out, "_None._")?;
+        } else {
+            writeln!(out, "| Tool | Calls |")?;
+            writeln!(out, "| --- | --- |")?;
+            for (tool, calls) in &self.tool_calls {
+                writeln!(out, "| `{tool}` | {calls} |")?;
+            }
+        }
+
+        writeln!(out, "\n## Approvals\n")?;
+        if self.approvals_granted.is_empty() && self.approvals_denied.is_empty() {
+            writeln!(out, "_None._")?;
+        }
+        for (verdict, records) in [
+            ("Granted", &self.approvals_granted),
+            ("Denied", &self.approvals_denied),
+        ] {
+            for record in records {
+                match &record.detail {
+                    Some(detail) => writeln!(out, "- {verdict}: {} ({detail})", record.action)?,
+                    None => writeln!(out, "- {verdict}: {}", record.action)?,
+                }
+            }
+        }
+
+        writeln!(out, "\n## Usage\n")?;
+        writeln!(out, "- LLM requests: {}", self.llm_requests)?;
+        writeln!(out, "- Input tokens: {}", self.input_tokens)?;
+        writeln!(out, "- Output tokens: {}", self.output_tokens)?;
+        if let Some(cost) = self.cost_usd {
+            writeln!(out, "- Cost: ${cost:.2}")?;
+        }
+        writeln!(out, "- Failed or refused actions: {}", self.failures)?;
+
+        if !self.security_violations.is_empty() {
+            writeln!(out, "\n## Security violations\n")?;
+            for violation in &self.security_violations {
+                writeln!(out, "- {violation}")?;
+            }
+        }
+
+        if !self.unresolved_deferred.is_empty() {
+            writeln!(out, "\n## Unresolved deferred actions\n")?;
+            for action in &self.unresolved_deferred {
+                writeln!(out, "- {action}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Short description of an action for the approval lists.
+///
+/// The interceptor records gated actions without a dedicated variant
+/// (commands, network requests) as `ApprovalRequested`; show those as
+/// `kind: target` rather than as a request.
+fn describe(action: &AuditAction) -> String {
+    match action {
+        AuditAction::ApprovalRequested {
+            action_type,
+            resource,
+        } => format!("{action_type}: {}", resource.trim()),
+        other => other.description(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astrid_crypto::{ContentHash, KeyPair};
+
+    fn entry(
+        keypair: &KeyPair,
+        session_id: &SessionId,
+        action: AuditAction,
+        authorization: AuthorizationProof,
+        outcome: AuditOutcome,
+    ) -> AuditEntry {
+        AuditEntry::create(
+            session_id.clone(),
+            action,
+            authorization,
+            outcome,
+            ContentHash::zero(),
+            keypair,
+        )
+    }
+
+    #[test]
+    fn denied_actions_are_not_counted_as_done() {
+        let keypair = KeyPair::generate();
+        let session_id = SessionId::new();
+        let mut report = SessionReport::new(session_id.clone());
+
+        report.record(&entry(
+            &keypair,
+            &session_id,
+            AuditAction::FileDelete {
+                path: "/etc/hosts".to_string(),
+            },
+            AuthorizationProof::Denied {
+                reason: "outside workspace".to_string(),
+            },
+            AuditOutcome::failure("outside workspace"),
+        ));
+
+        assert!(report.files.is_empty());
+        assert_eq!(report.failures, 1);
+        assert_eq!(report.approvals_denied.len(), 1);
+        assert_eq!(
+            report.approvals_denied[0].detail.as_deref(),
+            Some("outside workspace")
+        );
+    }
+
+    #[test]
+    fn only_root_user_approvals_are_counted() {
+        let keypair = KeyPair::generate();
+        let session_id = SessionId::new();
+        let mut report = SessionReport::new(session_id.clone());
+        let action = || AuditAction::ApprovalRequested {
+            action_type: EXECUTE_COMMAND.to_string(),
+            resource: "cargo test ".to_string(),
+        };
+
+        let root = entry(
+            &keypair,
+            &session_id,
+            action(),
+            AuthorizationProof::UserApproval {
+                user_id: keypair.key_id(),
+                approval_entry_id: None,
+            },
+            AuditOutcome::success(),
+        );
+        report.record(&root);
+        report.record(&entry(
+            &keypair,
+            &session_id,
+            action(),
+            AuthorizationProof::UserApproval {
+                user_id: keypair.key_id(),
+                approval_entry_id: Some(root.id.clone()),
+            },
+            AuditOutcome::success(),
+        ));
+
+        assert_eq!(report.approvals_granted.len(), 1);
+        assert_eq!(report.commands, ["cargo test", "cargo test"]);
+    }
+
+    #[test]
+    fn empty_report_renders() {
+        let report = SessionReport::new(SessionId::new());
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("## Files\n\n_None._"));
+        assert!(!markdown.contains("Cost"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["entries"], 0);
+    }
+}
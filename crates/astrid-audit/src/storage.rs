@@ -51,6 +51,18 @@ pub(crate) trait AuditStorage: Send + Sync {
     /// Returns an error if retrieval or deserialization fails.
     fn get_session_entries(&self, session_id: &SessionId) -> AuditResult<Vec<AuditEntry>>;
 
+    /// Visit the entries of a session in insertion order, loading one at a
+    /// time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if retrieval or deserialization fails.
+    fn for_each_session_entry(
+        &self,
+        session_id: &SessionId,
+        visit: &mut dyn FnMut(AuditEntry),
+    ) -> AuditResult<()>;
+
     /// Count total entries.
     ///
     /// # Errors
@@ -78,6 +90,13 @@ pub(crate) trait AuditStorage: Send + Sync {
     ///
     /// Returns an error if the storage backend fails to flush.
     fn flush(&self) -> AuditResult<()>;
+
+    /// Close the backend, releasing its on-disk lock. Later writes fail.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the storage backend fails to close.
+    fn close(&self) -> AuditResult<()>;
 }
 
 // -- Namespace constants (crate-internal) --
@@ -157,6 +176,8 @@ fn chain_head_key(session_id: &SessionId, principal: Option<&astrid_core::Princi
 /// SurrealKV-based storage backend for audit logs.
 pub(crate) struct SurrealKvAuditStorage {
     store: Arc<dyn KvStore>,
+    /// The same store when it is on disk, kept concrete so it can be closed.
+    persistent: Option<Arc<SurrealKvStore>>,
 }
 
 impl SurrealKvAuditStorage {
@@ -166,10 +187,12 @@ impl SurrealKvAuditStorage {
     ///
     /// Returns an error if the `SurrealKV` store fails to open.
     pub(crate) fn open(path: impl AsRef<Path>) -> AuditResult<Self> {
-        let store =
-            SurrealKvStore::open(path).map_err(|e| AuditError::StorageError(e.to_string()))?;
+        let store = Arc::new(
+            SurrealKvStore::open(path).map_err(|e| AuditError::StorageError(e.to_string()))?,
+        );
         Ok(Self {
-            store: Arc::new(store),
+            store: Arc::clone(&store) as Arc<dyn KvStore>,
+            persistent: Some(store),
         })
     }
 
@@ -178,6 +201,16 @@ impl SurrealKvAuditStorage {
     pub(crate) fn in_memory() -> Self {
         Self {
             store: Arc::new(MemoryKvStore::new()),
+            persistent: None,
+        }
+    }
+
+    /// Wrap an existing KV store (for fault-injection tests).
+    #[cfg(test)]
+    pub(crate) fn with_store(store: Arc<dyn KvStore>) -> Self {
+        Self {
+            store,
+            persistent: None,
         }
     }
 
@@ -212,15 +245,12 @@ impl AuditStorage for SurrealKvAuditStorage {
         block_on(self.store.set(NS_ENTRIES, &entry_key, entry_data))
             .map_err(|e| AuditError::StorageError(e.to_string()))?;
 
-        // Update session index (append entry ID to the list).
-        let mut entry_ids = self.get_session_entry_ids(&entry.session_id)?;
-        entry_ids.push(entry.id.clone());
-        let index_data = serde_json::to_vec(&entry_ids)
-            .map_err(|e| AuditError::SerializationError(e.to_string()))?;
-        block_on(self.store.set(NS_SESSION_INDEX, &session_key, index_data))
-            .map_err(|e| AuditError::StorageError(e.to_string()))?;
-
         // Update chain head for the entry's chain (system or principal).
+        //
+        // Written before the session index: if the index update then fails,
+        // the entry is not part of the verifiable chain, and the log's cached
+        // head (not yet advanced) links the next entry past it. Indexing
+        // first would leave an indexed entry that nothing links to.
         let chain_key = chain_head_key(&entry.session_id, entry.principal.as_ref());
         block_on(
             self.store
@@ -228,6 +258,14 @@ impl AuditStorage for SurrealKvAuditStorage {
         )
         .map_err(|e| AuditError::StorageError(e.to_string()))?;
 
+        // Update session index (append entry ID to the list).
+        let mut entry_ids = self.get_session_entry_ids(&entry.session_id)?;
+        entry_ids.push(entry.id.clone());
+        let index_data = serde_json::to_vec(&entry_ids)
+            .map_err(|e| AuditError::SerializationError(e.to_string()))?;
+        block_on(self.store.set(NS_SESSION_INDEX, &session_key, index_data))
+            .map_err(|e| AuditError::StorageError(e.to_string()))?;
+
         Ok(())
     }
 
@@ -270,16 +308,22 @@ impl AuditStorage for SurrealKvAuditStorage {
     }
 
     fn get_session_entries(&self, session_id: &SessionId) -> AuditResult<Vec<AuditEntry>> {
-        let ids = self.get_session_entry_ids(session_id)?;
-        let mut entries = Vec::with_capacity(ids.len());
+        let mut entries = Vec::new();
+        self.for_each_session_entry(session_id, &mut |entry| entries.push(entry))?;
+        Ok(entries)
+    }
 
-        for id in ids {
+    fn for_each_session_entry(
+        &self,
+        session_id: &SessionId,
+        visit: &mut dyn FnMut(AuditEntry),
+    ) -> AuditResult<()> {
+        for id in self.get_session_entry_ids(session_id)? {
             if let Some(entry) = self.get(&id)? {
-                entries.push(entry);
+                visit(entry);
             }
         }
-
-        Ok(entries)
+        Ok(())
     }
 
     fn count(&self) -> AuditResult<usize> {
@@ -310,6 +354,15 @@ impl AuditStorage for SurrealKvAuditStorage {
         // KvStore commits on every set(), no explicit flush needed.
         Ok(())
     }
+
+    fn close(&self) -> AuditResult<()> {
+        match &self.persistent {
+            Some(store) => {
+                block_on(store.close()).map_err(|e| AuditError::StorageError(e.to_string()))
+            },
+            None => Ok(()),
+        }
+    }
 }
 
 impl std::fmt::Debug for SurrealKvAuditStorage {
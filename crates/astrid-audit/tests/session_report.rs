@@ -0,0 +1,211 @@
+//! Golden files for session reports.
+//!
+//! A scripted session is folded into a [`SessionReport`] and rendered. The
+//! outputs must match `tests/fixtures/session_report.{md,json}` exactly;
+//! when the format changes on purpose, update the fixtures in the same
+//! commit.
+
+use std::path::Path;
+
+use astrid_audit::{
+    ApprovalScope, AuditAction, AuditEntry, AuditOutcome, AuthorizationProof, SessionReport,
+};
+use astrid_core::{SessionId, Timestamp};
+use astrid_crypto::{ContentHash, KeyPair};
+use chrono::{TimeZone, Utc};
+
+/// One step of the scripted session: seconds after the start, the action,
+/// how it was authorized and how it ended.
+type Step = (i64, AuditAction, AuthorizationProof, AuditOutcome);
+
+const USER_ID: [u8; 8] = [7; 8];
+
+fn not_required() -> AuthorizationProof {
+    AuthorizationProof::NotRequired {
+        reason: "policy allowed".to_string(),
+    }
+}
+
+fn approved() -> AuthorizationProof {
+    AuthorizationProof::UserApproval {
+        user_id: USER_ID,
+        approval_entry_id: None,
+    }
+}
+
+fn scripted_session() -> Vec<Step> {
+    let mut steps = opening_steps();
+    steps.extend(tool_steps());
+    steps.extend(closing_steps());
+    steps
+}
+
+/// Session start, a first model turn, and a read-modify-write of a file.
+fn opening_steps() -> Vec<Step> {
+    vec![
+        (
+            0,
+            AuditAction::SessionStarted {
+                user_id: USER_ID,
+                platform: "cli".to_string(),
+            },
+            AuthorizationProof::System {
+                reason: "session start".to_string(),
+            },
+            AuditOutcome::success(),
+        ),
+        (
+            5,
+            AuditAction::LlmRequest {
+                model: "test-model".to_string(),
+                input_tokens: 1200,
+                output_tokens: 300,
+            },
+            not_required(),
+            AuditOutcome::success(),
+        ),
+        (
+            10,
+            AuditAction::FileRead {
+                path: "src/lib.rs".to_string(),
+            },
+            not_required(),
+            AuditOutcome::success(),
+        ),
+        (
+            20,
+            AuditAction::file_write("src/lib.rs", b"pub fn answer() -> u32 { 42 }", None).unwrap(),
+            not_required(),
+            AuditOutcome::success(),
+        ),
+    ]
+}
+
+/// Tool calls (one failing), approvals, and a denied delete.
+fn tool_steps() -> Vec<Step> {
+    vec![
+        (
+            30,
+            AuditAction::McpToolCall {
+                server: "search".to_string(),
+                tool: "query".to_string(),
+                args_hash: ContentHash::zero(),
+            },
+            not_required(),
+            AuditOutcome::success(),
+        ),
+        (
+            31,
+            AuditAction::McpToolCall {
+                server: "search".to_string(),
+                tool: "query".to_string(),
+                args_hash: ContentHash::zero(),
+            },
+            not_required(),
+            AuditOutcome::failure("timeout"),
+        ),
+        (
+            40,
+            AuditAction::ApprovalRequested {
+                action_type: "execute_command".to_string(),
+                resource: "cargo test ".to_string(),
+            },
+            approved(),
+            AuditOutcome::success(),
+        ),
+        (
+            50,
+            AuditAction::FileDelete {
+                path: "/etc/hosts".to_string(),
+            },
+            AuthorizationProof::Denied {
+                reason: "outside workspace".to_string(),
+            },
+            AuditOutcome::failure("outside workspace"),
+        ),
+        (
+            55,
+            AuditAction::ApprovalGranted {
+                action: "network_request".to_string(),
+                resource: Some("crates.io:443".to_string()),
+                scope: ApprovalScope::Session,
+            },
+            approved(),
+            AuditOutcome::success(),
+        ),
+    ]
+}
+
+/// A second model turn and the session end.
+fn closing_steps() -> Vec<Step> {
+    vec![
+        (
+            60,
+            AuditAction::LlmRequest {
+                model: "test-model".to_string(),
+                input_tokens: 1500,
+                output_tokens: 200,
+            },
+            not_required(),
+            AuditOutcome::success(),
+        ),
+        (
+            90,
+            AuditAction::SessionEnded {
+                reason: "user exit".to_string(),
+                duration_secs: 90,
+            },
+            AuthorizationProof::System {
+                reason: "session end".to_string(),
+            },
+            AuditOutcome::success(),
+        ),
+    ]
+}
+
+fn scripted_report() -> SessionReport {
+    let keypair = KeyPair::generate();
+    let session_id = SessionId(uuid::Uuid::from_u128(0x1519));
+    let start = Utc.with_ymd_and_hms(2026, 10, 17, 9, 0, 0).unwrap();
+
+    let mut report = SessionReport::new(session_id.clone());
+    for (offset, action, authorization, outcome) in scripted_session() {
+        let mut entry = AuditEntry::create(
+            session_id.clone(),
+            action,
+            authorization,
+            outcome,
+            ContentHash::zero(),
+            &keypair,
+        );
+        let at = start
+            .checked_add_signed(chrono::Duration::seconds(offset))
+            .unwrap();
+        entry.timestamp = Timestamp::from_datetime(at);
+        report.record(&entry);
+    }
+    report.cost_usd = Some(0.42);
+    report.unresolved_deferred = vec!["Execute: rm -rf build (queued: no approver)".to_string()];
+    report
+}
+
+fn fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn session_report_markdown_golden() {
+    assert_eq!(
+        scripted_report().to_markdown(),
+        fixture("session_report.md")
+    );
+}
+
+#[test]
+fn session_report_json_golden() {
+    let json = scripted_report().to_json().unwrap();
+    assert_eq!(format!("{json}\n"), fixture("session_report.json"));
+}
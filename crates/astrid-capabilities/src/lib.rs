@@ -72,5 +72,5 @@ pub use handle::{DirHandle, FileHandle};
 pub use pattern::ResourcePattern;
 pub use policy::{CapabilityCheck, PermissionError};
 pub use store::CapabilityStore;
-pub use token::{AuditEntryId, CapabilityToken, TokenScope};
+pub use token::{AuditEntryId, CapabilityToken, TokenScope, workspace_key};
 pub use validator::{AuthorizationResult, CapabilityValidator};
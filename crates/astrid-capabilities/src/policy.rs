@@ -111,6 +111,12 @@ impl<'a> CapabilityCheck<'a> {
         }
     }
 
+    /// The principal this check evaluates.
+    #[must_use]
+    pub fn principal(&self) -> &PrincipalId {
+        &self.principal
+    }
+
     /// Return `true` if the principal holds capability `cap`.
     ///
     /// Precedence: revokes > grants > group-inherited. Missing group
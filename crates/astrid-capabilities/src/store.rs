@@ -393,15 +393,48 @@ impl CapabilityStore {
             .is_some()
     }
 
+    /// Workspace-aware variant of [`has_capability`](Self::has_capability).
+    ///
+    /// Tokens bound to a workspace only match when `workspace` names the
+    /// same one; unbound tokens match everywhere.
+    pub fn has_capability_in_workspace(
+        &self,
+        principal: &PrincipalId,
+        workspace: Option<&str>,
+        resource: &str,
+        permission: Permission,
+    ) -> bool {
+        self.find_capability_in_workspace(principal, workspace, resource, permission)
+            .is_some()
+    }
+
     /// Find a token owned by `principal` that grants the given capability.
     ///
+    /// Only unbound (global) tokens are considered; use
+    /// [`find_capability_in_workspace`](Self::find_capability_in_workspace)
+    /// when the caller knows its workspace.
+    pub fn find_capability(
+        &self,
+        principal: &PrincipalId,
+        resource: &str,
+        permission: Permission,
+    ) -> Option<CapabilityToken> {
+        self.find_capability_in_workspace(principal, None, resource, permission)
+    }
+
+    /// Find a token owned by `principal` that grants the given capability
+    /// in `workspace`.
+    ///
     /// Scans session tokens under `principal` first, then the persistent
     /// store's `caps:tokens:{principal}` prefix. Tokens whose `principal`
-    /// field does not match the caller are skipped — revocation stays
+    /// field does not match the caller are skipped, as are tokens bound to
+    /// a different workspace (see
+    /// [`CapabilityToken::applies_to_workspace`]) — revocation stays
     /// global but grants are always principal-filtered.
-    pub fn find_capability(
+    pub fn find_capability_in_workspace(
         &self,
         principal: &PrincipalId,
+        workspace: Option<&str>,
         resource: &str,
         permission: Permission,
     ) -> Option<CapabilityToken> {
@@ -415,7 +448,10 @@ impl CapabilityStore {
                     // slipped into the wrong principal's inner map.
                     continue;
                 }
-                if !token.is_expired() && token.grants(resource, permission) {
+                if !token.is_expired()
+                    && token.applies_to_workspace(workspace)
+                    && token.grants(resource, permission)
+                {
                     match self.is_consumed_single_use(token) {
                         Ok(true) => {},
                         Ok(false) => return Some(token.clone()),
@@ -457,6 +493,9 @@ impl CapabilityStore {
                     if token.principal != *principal {
                         continue;
                     }
+                    if !token.applies_to_workspace(workspace) {
+                        continue;
+                    }
                     // Revocation is global.
                     if let Ok(revoked) = self.revoked.read()
                         && revoked.contains(&token.id)
@@ -502,3 +502,71 @@ async fn test_persistent_v1_token_rejected_after_upgrade() {
         "v1 tokens must be rejected with InvalidSignature; got {result:?}"
     );
 }
+
+#[tokio::test]
+async fn test_workspace_bound_token_only_matches_its_workspace() {
+    let kv: Arc<dyn KvStore> = Arc::new(MemoryKvStore::new());
+    let store = CapabilityStore::with_kv_store(kv).unwrap();
+    let keypair = test_keypair();
+
+    for (resource, scope) in [
+        ("mcp://session:tool", TokenScope::Session),
+        ("mcp://persistent:tool", TokenScope::Persistent),
+    ] {
+        let token = CapabilityToken::create(
+            ResourcePattern::exact(resource).unwrap(),
+            vec![Permission::Invoke],
+            scope,
+            keypair.key_id(),
+            AuditEntryId::new(),
+            &keypair,
+            None,
+            default_principal(),
+        )
+        .bound_to_workspace("project-a", &keypair);
+        store.add(token).unwrap();
+
+        let p = default_principal();
+        assert!(store.has_capability_in_workspace(
+            &p,
+            Some("project-a"),
+            resource,
+            Permission::Invoke
+        ));
+        assert!(!store.has_capability_in_workspace(
+            &p,
+            Some("project-b"),
+            resource,
+            Permission::Invoke
+        ));
+        // Workspace-unaware lookups only see global tokens.
+        assert!(!store.has_capability(&p, resource, Permission::Invoke));
+    }
+}
+
+#[tokio::test]
+async fn test_unbound_token_matches_every_workspace() {
+    let store = CapabilityStore::in_memory();
+    let keypair = test_keypair();
+    let token = CapabilityToken::create(
+        ResourcePattern::exact("mcp://test:tool").unwrap(),
+        vec![Permission::Invoke],
+        TokenScope::Session,
+        keypair.key_id(),
+        AuditEntryId::new(),
+        &keypair,
+        None,
+        default_principal(),
+    );
+    store.add(token).unwrap();
+
+    let p = default_principal();
+    for workspace in [None, Some("project-a"), Some("project-b")] {
+        assert!(store.has_capability_in_workspace(
+            &p,
+            workspace,
+            "mcp://test:tool",
+            Permission::Invoke
+        ));
+    }
+}
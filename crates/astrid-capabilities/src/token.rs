@@ -7,11 +7,13 @@
 //! - Scoped (session or persistent)
 //! - Time-bounded (optional expiration)
 
+use astrid_core::dirs::WorkspaceDir;
 use astrid_core::principal::PrincipalId;
 use astrid_core::{Permission, Timestamp, TokenId};
 use astrid_crypto::{ContentHash, KeyPair, PublicKey, Signature};
 use chrono::{Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use uuid::Uuid;
 
 use crate::error::{CapabilityError, CapabilityResult};
@@ -26,11 +28,39 @@ use crate::pattern::ResourcePattern;
 ///      [`CapabilityError::InvalidSignature`]; operators must re-mint them
 ///      (see [`CapabilityStore::find_capability`](crate::CapabilityStore::find_capability)
 ///      for the runtime log).
+/// v3 — v2 plus a length-prefixed workspace binding. Only used for tokens
+///      bound to a workspace; unbound tokens keep the v2 layout so tokens
+///      minted before workspace scoping still verify.
 const SIGNING_DATA_VERSION: u8 = 0x02;
 
+/// Signing data version for workspace-bound tokens. See
+/// [`SIGNING_DATA_VERSION`].
+const SIGNING_DATA_VERSION_WORKSPACE: u8 = 0x03;
+
 /// Default clock skew tolerance in seconds.
 const DEFAULT_CLOCK_SKEW_SECS: i64 = 30;
 
+/// Stable key capability tokens use to name the workspace rooted at `root`.
+///
+/// The UUID in `.astrid/workspace-id` (see [`WorkspaceDir::workspace_id`]),
+/// generated on first use, so grants follow the project when it is moved or
+/// reached through a symlink. Falls back to the canonical root path if the
+/// ID cannot be read or written (e.g. a read-only checkout), and to the path
+/// as given if the root does not exist.
+#[must_use]
+pub fn workspace_key(root: &Path) -> String {
+    let Ok(root) = std::fs::canonicalize(root) else {
+        return root.display().to_string();
+    };
+    match WorkspaceDir::from_path(&root).workspace_id() {
+        Ok(id) => id.to_string(),
+        Err(e) => {
+            tracing::debug!(root = %root.display(), error = %e, "No workspace ID, keying by path");
+            root.display().to_string()
+        },
+    }
+}
+
 /// Write a length-prefixed byte slice to the output buffer.
 ///
 /// Format: 4-byte little-endian length followed by the data.
@@ -117,6 +147,16 @@ pub struct CapabilityToken {
     /// key. The field has no serde default — old v1 tokens without it
     /// deserialize as `MissingField` and get rejected at load time.
     pub principal: PrincipalId,
+    /// Workspace this token is confined to (`None` = all workspaces).
+    ///
+    /// Holds the [`workspace_key`] of the workspace: the UUID in
+    /// `.astrid/workspace-id`, or the canonical root path where no ID can
+    /// be stored. Signed into the payload (v3), so a token cannot be moved
+    /// to another workspace
+    /// or unbound without the runtime private key. Tokens from before
+    /// workspace scoping deserialize as unbound and stay global.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
     /// Cryptographic signature of the token.
     pub signature: Signature,
 }
@@ -192,6 +232,7 @@ impl CapabilityToken {
             approval_audit_id,
             single_use,
             principal,
+            workspace: None,
             signature: Signature::from_bytes([0u8; 64]), // Placeholder
         };
 
@@ -202,10 +243,37 @@ impl CapabilityToken {
         token
     }
 
+    /// Confine the token to `workspace` and re-sign it.
+    ///
+    /// `runtime_key` must be the key the token was created with.
+    #[must_use]
+    pub fn bound_to_workspace(
+        mut self,
+        workspace: impl Into<String>,
+        runtime_key: &KeyPair,
+    ) -> Self {
+        self.workspace = Some(workspace.into());
+        self.signature = runtime_key.sign(&self.signing_data());
+        self
+    }
+
+    /// Whether this token may be used in `workspace`.
+    ///
+    /// Unbound tokens apply everywhere. A bound token applies only when
+    /// the caller names the same workspace; a caller that does not know
+    /// its workspace (`None`) only sees unbound tokens.
+    #[must_use]
+    pub fn applies_to_workspace(&self, workspace: Option<&str>) -> bool {
+        match &self.workspace {
+            None => true,
+            Some(bound) => workspace == Some(bound.as_str()),
+        }
+    }
+
     /// Get the data used for signing (excludes the signature itself).
     ///
     /// Format (v2, issue #668):
-    /// - 1 byte: version (0x02)
+    /// - 1 byte: version (0x02, or 0x03 if bound to a workspace)
     /// - Length-prefixed token ID (UUID bytes)
     /// - Length-prefixed resource pattern string
     /// - 4 bytes: number of permissions
@@ -219,6 +287,7 @@ impl CapabilityToken {
     /// - Length-prefixed audit entry ID (UUID bytes)
     /// - 1 byte: `single_use` flag
     /// - Length-prefixed principal string (v2 addition)
+    /// - Length-prefixed workspace string (v3 only)
     ///
     /// v1 tokens (without the principal suffix) fail signature verification
     /// against v2 verifiers and must be re-minted. There is no silent upgrade
@@ -229,7 +298,11 @@ impl CapabilityToken {
         let mut data = Vec::with_capacity(512);
 
         // Version prefix
-        data.push(SIGNING_DATA_VERSION);
+        data.push(if self.workspace.is_some() {
+            SIGNING_DATA_VERSION_WORKSPACE
+        } else {
+            SIGNING_DATA_VERSION
+        });
 
         // Token ID
         write_length_prefixed(&mut data, self.id.0.as_bytes());
@@ -274,6 +347,11 @@ impl CapabilityToken {
         // enforces ASCII alphanumeric + `-_` at construction).
         write_length_prefixed(&mut data, self.principal.as_str().as_bytes());
 
+        // Workspace (v3).
+        if let Some(workspace) = &self.workspace {
+            write_length_prefixed(&mut data, workspace.as_bytes());
+        }
+
         data
     }
 
@@ -729,4 +807,85 @@ mod tests {
             Err(CapabilityError::InvalidSignature)
         ));
     }
+
+    #[test]
+    fn test_workspace_binding_is_signed_and_scoped() {
+        let keypair = test_keypair();
+        let token = CapabilityToken::create(
+            ResourcePattern::exact("mcp://test:tool").unwrap(),
+            vec![Permission::Invoke],
+            TokenScope::Session,
+            keypair.key_id(),
+            AuditEntryId::new(),
+            &keypair,
+            None,
+            PrincipalId::default(),
+        );
+        let unbound_payload = token.signing_data();
+        assert_eq!(unbound_payload[0], SIGNING_DATA_VERSION);
+        assert!(token.applies_to_workspace(None));
+        assert!(token.applies_to_workspace(Some("project-a")));
+
+        let bound = token.bound_to_workspace("project-a", &keypair);
+        assert_eq!(bound.signing_data()[0], SIGNING_DATA_VERSION_WORKSPACE);
+        assert!(bound.verify_signature().is_ok());
+        assert!(bound.applies_to_workspace(Some("project-a")));
+        assert!(!bound.applies_to_workspace(Some("project-b")));
+        assert!(!bound.applies_to_workspace(None));
+
+        // Moving the token to another workspace breaks the signature.
+        let mut moved = bound.clone();
+        moved.workspace = Some("project-b".to_string());
+        assert!(matches!(
+            moved.verify_signature(),
+            Err(CapabilityError::InvalidSignature)
+        ));
+        // So does stripping the binding.
+        let mut stripped = bound;
+        stripped.workspace = None;
+        assert!(stripped.verify_signature().is_err());
+    }
+
+    #[test]
+    fn test_token_without_workspace_field_deserializes_unbound() {
+        let keypair = test_keypair();
+        let token = CapabilityToken::create(
+            ResourcePattern::exact("mcp://test:tool").unwrap(),
+            vec![Permission::Invoke],
+            TokenScope::Persistent,
+            keypair.key_id(),
+            AuditEntryId::new(),
+            &keypair,
+            None,
+            PrincipalId::default(),
+        );
+        let json = serde_json::to_value(&token).unwrap();
+        assert!(json.get("workspace").is_none());
+
+        let restored: CapabilityToken = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.workspace, None);
+        assert!(restored.verify_signature().is_ok());
+    }
+
+    #[test]
+    fn test_workspace_key_follows_the_workspace_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        std::fs::create_dir(&project).unwrap();
+
+        let key = workspace_key(&project);
+        let id = WorkspaceDir::from_path(&project).workspace_id().unwrap();
+        assert_eq!(key, id.to_string());
+        assert_eq!(workspace_key(&project), key);
+
+        // Moving the project keeps its grants.
+        let moved = dir.path().join("moved");
+        std::fs::rename(&project, &moved).unwrap();
+        assert_eq!(workspace_key(&moved), key);
+
+        // Another project gets its own key.
+        let other = dir.path().join("other");
+        std::fs::create_dir(&other).unwrap();
+        assert_ne!(workspace_key(&other), key);
+    }
 }
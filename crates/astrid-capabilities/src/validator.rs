@@ -48,6 +48,7 @@ impl AuthorizationResult {
 pub struct CapabilityValidator<'a> {
     store: &'a CapabilityStore,
     trusted_issuers: Vec<PublicKey>,
+    workspace: Option<String>,
 }
 
 impl<'a> CapabilityValidator<'a> {
@@ -57,9 +58,19 @@ impl<'a> CapabilityValidator<'a> {
         Self {
             store,
             trusted_issuers: Vec::new(),
+            workspace: None,
         }
     }
 
+    /// Check against tokens bound to `workspace` as well as global ones.
+    ///
+    /// Without this, only unbound tokens authorize.
+    #[must_use]
+    pub fn in_workspace(mut self, workspace: impl Into<String>) -> Self {
+        self.workspace = Some(workspace.into());
+        self
+    }
+
     /// Add a trusted issuer (runtime public key).
     #[must_use]
     pub fn trust_issuer(mut self, issuer: PublicKey) -> Self {
@@ -72,7 +83,8 @@ impl<'a> CapabilityValidator<'a> {
     /// Tokens are filtered by their `CapabilityToken::principal` before
     /// expiry/signature checks — a token minted for another principal will
     /// never be considered, even if the resource pattern matches. See
-    /// [`CapabilityStore::find_capability`] for the fail-closed semantics.
+    /// [`CapabilityStore::find_capability_in_workspace`] for the fail-closed
+    /// semantics.
     #[must_use]
     pub fn check(
         &self,
@@ -80,7 +92,12 @@ impl<'a> CapabilityValidator<'a> {
         resource: &str,
         permission: Permission,
     ) -> AuthorizationResult {
-        if let Some(token) = self.store.find_capability(principal, resource, permission) {
+        if let Some(token) = self.store.find_capability_in_workspace(
+            principal,
+            self.workspace.as_deref(),
+            resource,
+            permission,
+        ) {
             // Validate the token
             if self.validate_token(&token).is_ok() {
                 return AuthorizationResult::Authorized {
@@ -170,6 +170,18 @@ pub enum CapsuleState {
     Unloading,
 }
 
+impl fmt::Display for CapsuleState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unloaded => f.write_str("unloaded"),
+            Self::Loading => f.write_str("loading"),
+            Self::Ready => f.write_str("ready"),
+            Self::Failed(reason) => write!(f, "failed: {reason}"),
+            Self::Unloading => f.write_str("unloading"),
+        }
+    }
+}
+
 /// A loaded capsule that can provide tools and integrations to the runtime.
 #[async_trait]
 pub trait Capsule: Send + Sync {
@@ -229,6 +241,21 @@ pub trait Capsule: Send + Sync {
         ))
     }
 
+    /// Like [`invoke_interceptor`](Self::invoke_interceptor), but the guest
+    /// call is interrupted once `cancel` fires, releasing the capsule for
+    /// other callers.
+    ///
+    /// The default ignores `cancel`.
+    fn invoke_interceptor_cancellable(
+        &self,
+        action: &str,
+        payload: &[u8],
+        caller: Option<&astrid_events::ipc::IpcMessage>,
+        _cancel: &tokio_util::sync::CancellationToken,
+    ) -> CapsuleResult<InterceptResult> {
+        self.invoke_interceptor(action, payload, caller)
+    }
+
     /// Probe liveness beyond what `state()` reports.
     ///
     /// Returns the current state by default. Composite capsules delegate
@@ -388,6 +415,25 @@ impl Capsule for CompositeCapsule {
         ))
     }
 
+    fn invoke_interceptor_cancellable(
+        &self,
+        action: &str,
+        payload: &[u8],
+        caller: Option<&astrid_events::ipc::IpcMessage>,
+        cancel: &tokio_util::sync::CancellationToken,
+    ) -> CapsuleResult<InterceptResult> {
+        for engine in &self.engines {
+            match engine.invoke_interceptor_cancellable(action, payload, caller, cancel) {
+                Ok(result) => return Ok(result),
+                Err(CapsuleError::NotSupported(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(CapsuleError::NotSupported(
+            "no engine supports interceptors".into(),
+        ))
+    }
+
     fn check_health(&self) -> CapsuleState {
         for engine in &self.engines {
             let health = engine.check_health();
@@ -476,6 +522,8 @@ mod tests {
             uplinks: Vec::new(),
             interceptors: Vec::new(),
             topics: Vec::new(),
+            cron_jobs: Vec::new(),
+            health: Default::default(),
         }
     }
 
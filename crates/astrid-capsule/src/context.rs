@@ -5,9 +5,12 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use astrid_audit::AuditLog;
+use astrid_core::SessionId;
 use astrid_core::principal::PrincipalId;
 use astrid_events::EventBus;
 use astrid_storage::ScopedKvStore;
+use astrid_workspace::WorkspaceMode;
 
 use astrid_core::session_token::SessionToken;
 
@@ -15,6 +18,23 @@ use crate::profile_cache::PrincipalProfileCache;
 use crate::registry::CapsuleRegistry;
 use crate::schema_catalog::SchemaCatalog;
 
+/// The kernel audit log, and the session host-side entries are filed under.
+///
+/// Host functions that must leave a durable record (file writes, secret
+/// reads) append here directly rather than publishing an event, so the
+/// entry exists before the host call returns.
+#[derive(Clone)]
+pub struct CapsuleAudit {
+    /// The kernel's audit log.
+    pub log: Arc<AuditLog>,
+    /// The kernel session the entries belong to.
+    pub session_id: SessionId,
+    /// File writes up to this many bytes have their content sealed into
+    /// the entry; larger writes, and all writes when `0`, record only the
+    /// hash and size.
+    pub seal_writes_up_to: u64,
+}
+
 /// Context provided to a capsule during lifecycle operations (load/unload).
 ///
 /// Not `Clone` by design - `session_token` holds secret bytes that should
@@ -64,6 +84,13 @@ pub struct CapsuleContext {
     /// never reach Agent B's view of the same tree. Tests and single-tenant
     /// deployments may leave this `None`.
     pub overlay_registry: Option<Arc<astrid_vfs::OverlayVfsRegistry>>,
+    /// Kernel audit log for host-side audit entries. Tests may leave this
+    /// `None`, in which case nothing is audited.
+    pub audit: Option<CapsuleAudit>,
+    /// Workspace mode (`workspace.mode` in config). Decides whether a
+    /// spawned command naming paths outside the workspace asks for approval
+    /// or is refused outright. Defaults to [`WorkspaceMode::Safe`].
+    pub workspace_mode: WorkspaceMode,
 }
 
 impl CapsuleContext {
@@ -90,6 +117,8 @@ impl CapsuleContext {
             schema_catalog: Arc::new(SchemaCatalog::new()),
             profile_cache: None,
             overlay_registry: None,
+            audit: None,
+            workspace_mode: WorkspaceMode::Safe,
         }
     }
 
@@ -134,4 +163,28 @@ impl CapsuleContext {
         self.overlay_registry = Some(registry);
         self
     }
+
+    /// Set the kernel audit log, the session its entries belong to, and
+    /// the largest file write whose content is sealed into it.
+    #[must_use]
+    pub fn with_audit_log(
+        mut self,
+        log: Arc<AuditLog>,
+        session_id: SessionId,
+        seal_writes_up_to: u64,
+    ) -> Self {
+        self.audit = Some(CapsuleAudit {
+            log,
+            session_id,
+            seal_writes_up_to,
+        });
+        self
+    }
+
+    /// Set the workspace mode host functions enforce.
+    #[must_use]
+    pub fn with_workspace_mode(mut self, mode: WorkspaceMode) -> Self {
+        self.workspace_mode = mode;
+        self
+    }
 }
@@ -0,0 +1,858 @@
+//! Static cron jobs declared in `Capsule.toml`.
+//!
+//! A capsule declares jobs as `[[cron]]` tables with a `name` and a
+//! `schedule`. Schedules have six fields, seconds first:
+//! `sec min hour day-of-month month day-of-week`. The classic five-field
+//! form is also accepted and fires at second 0. Each field takes `*`,
+//! numbers, ranges (`1-5`), steps (`*/15`, `10-40/10`, `5/20`) and comma
+//! lists. Months and weekdays also take three-letter names (`JAN`, `MON`),
+//! weekday `7` is Sunday, and `?` means `*` in the two day fields. When
+//! both day fields are restricted, a day matching either one fires.
+//!
+//! Schedules match local wall-clock time, and each matching wall-clock
+//! time fires once. Times skipped when daylight saving time starts do not
+//! fire. Times repeated when it ends fire on their first occurrence only.
+//!
+//! [`CronScheduler`] tracks the next fire time of every job and hands out
+//! [`CronTick`]s as they come due. It never reads the clock itself:
+//! callers pass `now`, so the kernel drives it from a timer task and tests
+//! drive it with a fake clock.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{
+    DateTime, Datelike, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta,
+    TimeZone, Timelike, Utc,
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::capsule::CapsuleId;
+use crate::manifest::CronDef;
+
+/// Action passed to `astrid_hook_trigger` when a cron job fires.
+pub const CRON_TRIGGER_ACTION: &str = "astrid_cron_trigger";
+
+/// How many years [`CronSchedule::next_after`] searches before giving up.
+///
+/// Long enough to reach the next February 29th across a skipped leap year.
+const SEARCH_YEARS: i32 = 10;
+
+/// A cron expression that failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid cron schedule '{expression}': {reason}")]
+pub struct CronParseError {
+    /// The expression as written.
+    pub expression: String,
+    /// What is wrong with it.
+    pub reason: String,
+}
+
+/// Bounds and names of one schedule field.
+struct FieldSpec {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+    /// Value of the first entry in `names`.
+    names_start: u32,
+}
+
+const SECOND: FieldSpec = FieldSpec {
+    name: "second",
+    min: 0,
+    max: 59,
+    names: &[],
+    names_start: 0,
+};
+const MINUTE: FieldSpec = FieldSpec {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+    names_start: 0,
+};
+const HOUR: FieldSpec = FieldSpec {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+    names_start: 0,
+};
+const DAY: FieldSpec = FieldSpec {
+    name: "day-of-month",
+    min: 1,
+    max: 31,
+    names: &[],
+    names_start: 0,
+};
+const MONTH: FieldSpec = FieldSpec {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &[
+        "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+    ],
+    names_start: 1,
+};
+const WEEKDAY: FieldSpec = FieldSpec {
+    name: "day-of-week",
+    min: 0,
+    max: 7,
+    names: &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"],
+    names_start: 0,
+};
+
+/// Set bit for `value`, or an empty set if it does not fit.
+fn bit(value: u32) -> u64 {
+    1u64.checked_shl(value).unwrap_or(0)
+}
+
+fn contains(set: u64, value: u32) -> bool {
+    set & bit(value) != 0
+}
+
+/// A parsed cron schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    seconds: u64,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Whether both day fields must match, rather than either.
+    days_and_weekdays: bool,
+}
+
+impl CronSchedule {
+    /// Parse a five- or six-field cron expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CronParseError`] naming the offending field if the
+    /// expression is malformed or can never fire.
+    pub fn parse(expression: &str) -> Result<Self, CronParseError> {
+        let error = |reason: String| CronParseError {
+            expression: expression.to_string(),
+            reason,
+        };
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let (second, rest) = match fields.as_slice() {
+            [second, rest @ ..] if rest.len() == 5 => (*second, rest),
+            rest if rest.len() == 5 => ("0", rest),
+            _ => {
+                return Err(error(format!(
+                    "expected 6 fields (sec min hour day month weekday) or 5, found {}",
+                    fields.len()
+                )));
+            },
+        };
+
+        let (seconds, _) = parse_field(second, &SECOND).map_err(error)?;
+        let (minutes, _) = parse_field(rest[0], &MINUTE).map_err(error)?;
+        let (hours, _) = parse_field(rest[1], &HOUR).map_err(error)?;
+        let (days, any_day) = parse_field(rest[2], &DAY).map_err(error)?;
+        let (months, _) = parse_field(rest[3], &MONTH).map_err(error)?;
+        let (mut weekdays, any_weekday) = parse_field(rest[4], &WEEKDAY).map_err(error)?;
+        // Weekday 7 is another name for Sunday.
+        if contains(weekdays, 7) {
+            weekdays = (weekdays & !bit(7)) | bit(0);
+        }
+
+        let schedule = Self {
+            expression: expression.to_string(),
+            seconds,
+            minutes,
+            hours,
+            days,
+            months,
+            weekdays,
+            days_and_weekdays: any_day || any_weekday,
+        };
+
+        // A day-of-month that no selected month has (`31 2`) never fires.
+        if any_weekday
+            && !(1..=12u32).any(|month| {
+                contains(months, month)
+                    && (1..=days_in_month_max(month)).any(|day| contains(days, day))
+            })
+        {
+            return Err(error(
+                "the day-of-month never occurs in the selected months".into(),
+            ));
+        }
+
+        Ok(schedule)
+    }
+
+    /// The expression as written in the manifest.
+    #[must_use]
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// The first fire time strictly after `after`, in `after`'s time zone.
+    ///
+    /// Returns `None` if nothing matches within the next ten years.
+    #[must_use]
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let mut local = after.naive_local();
+        loop {
+            local = self.next_local(local)?;
+            let candidate = match tz.from_local_datetime(&local) {
+                LocalResult::Single(t) => Some(t),
+                // Repeated by a jump back: the first occurrence, unless
+                // `after` is already past it.
+                LocalResult::Ambiguous(earliest, latest) => {
+                    [earliest, latest].into_iter().find(|t| t > after)
+                },
+                // Skipped by a jump forward.
+                LocalResult::None => None,
+            };
+            if let Some(t) = candidate.filter(|t| t > after) {
+                return Some(t);
+            }
+        }
+    }
+
+    /// The first matching wall-clock time strictly after `after`.
+    fn next_local(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let limit = after.year().checked_add(SEARCH_YEARS)?;
+        let mut t = after
+            .with_nanosecond(0)?
+            .checked_add_signed(TimeDelta::seconds(1))?;
+        loop {
+            if t.year() > limit {
+                return None;
+            }
+            if !contains(self.months, t.month()) {
+                t = t
+                    .date()
+                    .with_day(1)?
+                    .checked_add_months(Months::new(1))?
+                    .and_time(NaiveTime::MIN);
+            } else if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_time(NaiveTime::MIN);
+            } else if !contains(self.hours, t.hour()) {
+                t = t
+                    .with_minute(0)?
+                    .with_second(0)?
+                    .checked_add_signed(TimeDelta::hours(1))?;
+            } else if !contains(self.minutes, t.minute()) {
+                t = t
+                    .with_second(0)?
+                    .checked_add_signed(TimeDelta::minutes(1))?;
+            } else if !contains(self.seconds, t.second()) {
+                t = t.checked_add_signed(TimeDelta::seconds(1))?;
+            } else {
+                return Some(t);
+            }
+        }
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = contains(self.days, date.day());
+        let weekday = contains(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.days_and_weekdays {
+            day && weekday
+        } else {
+            day || weekday
+        }
+    }
+}
+
+/// Longest a month can be in any year.
+fn days_in_month_max(month: u32) -> u32 {
+    match month {
+        2 => 29,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parse one field into a bit set, and whether it was a bare wildcard.
+fn parse_field(text: &str, spec: &FieldSpec) -> Result<(u64, bool), String> {
+    let is_day_field = spec.name == DAY.name || spec.name == WEEKDAY.name;
+    let is_wildcard = |s: &str| s == "*" || (is_day_field && s == "?");
+
+    let mut set = 0u64;
+    for part in text.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<usize>() {
+                Ok(step) if step > 0 => (range, Some(step)),
+                _ => {
+                    return Err(format!(
+                        "{} step '{step}' must be a positive number",
+                        spec.name
+                    ));
+                },
+            },
+            None => (part, None),
+        };
+        let (low, high) = if is_wildcard(range) {
+            (spec.min, spec.max)
+        } else if let Some((low, high)) = range.split_once('-') {
+            (parse_value(low, spec)?, parse_value(high, spec)?)
+        } else {
+            let value = parse_value(range, spec)?;
+            // `5/20` runs from 5 to the end of the field.
+            (value, if step.is_some() { spec.max } else { value })
+        };
+        if low > high {
+            return Err(format!("{} range '{range}' is backwards", spec.name));
+        }
+        for value in (low..=high).step_by(step.unwrap_or(1)) {
+            set |= bit(value);
+        }
+    }
+    Ok((set, is_wildcard(text)))
+}
+
+fn parse_value(text: &str, spec: &FieldSpec) -> Result<u32, String> {
+    let named = spec
+        .names
+        .iter()
+        .zip(spec.names_start..)
+        .find(|(name, _)| name.eq_ignore_ascii_case(text))
+        .map(|(_, value)| value);
+    let value = match named {
+        Some(value) => value,
+        None => text
+            .parse::<u32>()
+            .map_err(|_| format!("{} value '{text}' is not a number", spec.name))?,
+    };
+    if value < spec.min || value > spec.max {
+        return Err(format!(
+            "{} value {value} is out of range {}-{}",
+            spec.name, spec.min, spec.max
+        ));
+    }
+    Ok(value)
+}
+
+/// A cron job that came due.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronTick {
+    /// The capsule that declared the job.
+    pub capsule_id: CapsuleId,
+    /// The job name from `[[cron]]`.
+    pub name: String,
+    /// When the job was scheduled to fire.
+    pub fire_time: DateTime<Utc>,
+}
+
+impl CronTick {
+    /// The payload passed to the capsule alongside [`CRON_TRIGGER_ACTION`].
+    #[must_use]
+    pub fn payload(&self) -> Vec<u8> {
+        serde_json::json!({
+            "name": self.name,
+            "fire_time": self.fire_time.to_rfc3339(),
+        })
+        .to_string()
+        .into_bytes()
+    }
+}
+
+/// A job as reported by [`CronScheduler::jobs`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CronJobStatus {
+    /// The capsule that declared the job.
+    pub capsule_id: CapsuleId,
+    /// The job name from `[[cron]]`.
+    pub name: String,
+    /// The cron expression.
+    pub schedule: String,
+    /// Whether the job currently fires.
+    pub enabled: bool,
+    /// When the job fires next. `None` while disabled.
+    pub next_fire: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+struct ScheduledJob {
+    schedule: CronSchedule,
+    enabled: bool,
+    next_fire: Option<DateTime<Utc>>,
+}
+
+/// Tracks the static cron jobs of every registered capsule.
+///
+/// Schedules are evaluated in `Tz`. Missed fire times are not replayed:
+/// after a job fires, its next fire time is computed from the current
+/// time, so a late or suspended kernel fires each job at most once when
+/// it catches up.
+#[derive(Debug)]
+pub struct CronScheduler<Tz: TimeZone> {
+    tz: Tz,
+    jobs: HashMap<CapsuleId, BTreeMap<String, ScheduledJob>>,
+}
+
+impl<Tz: TimeZone> CronScheduler<Tz> {
+    /// Create an empty scheduler evaluating schedules in `tz`.
+    #[must_use]
+    pub fn new(tz: Tz) -> Self {
+        Self {
+            tz,
+            jobs: HashMap::new(),
+        }
+    }
+
+    /// Schedule a capsule's jobs, replacing any it already had.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CronParseError`] if any schedule is invalid, in which case
+    /// none of the capsule's jobs are scheduled.
+    pub fn add_capsule(
+        &mut self,
+        capsule_id: &CapsuleId,
+        defs: &[CronDef],
+        now: DateTime<Utc>,
+    ) -> Result<(), CronParseError> {
+        let mut jobs = BTreeMap::new();
+        for def in defs {
+            let schedule = CronSchedule::parse(&def.schedule)?;
+            let next_fire = if def.enabled {
+                self.next_fire_after(&schedule, now)
+            } else {
+                None
+            };
+            jobs.insert(
+                def.name.clone(),
+                ScheduledJob {
+                    schedule,
+                    enabled: def.enabled,
+                    next_fire,
+                },
+            );
+        }
+        if jobs.is_empty() {
+            self.jobs.remove(capsule_id);
+        } else {
+            self.jobs.insert(capsule_id.clone(), jobs);
+        }
+        Ok(())
+    }
+
+    /// Drop all of a capsule's jobs.
+    pub fn remove_capsule(&mut self, capsule_id: &CapsuleId) {
+        self.jobs.remove(capsule_id);
+    }
+
+    /// Enable or disable one job. Returns `false` if there is no such job.
+    ///
+    /// A re-enabled job next fires at its first match after `now`.
+    pub fn set_enabled(
+        &mut self,
+        capsule_id: &CapsuleId,
+        name: &str,
+        enabled: bool,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let tz = self.tz.clone();
+        let Some(job) = self
+            .jobs
+            .get_mut(capsule_id)
+            .and_then(|jobs| jobs.get_mut(name))
+        else {
+            return false;
+        };
+        if job.enabled != enabled {
+            job.enabled = enabled;
+            job.next_fire = if enabled {
+                next_fire_in(&tz, &job.schedule, now)
+            } else {
+                None
+            };
+        }
+        true
+    }
+
+    /// The earliest upcoming fire time across all enabled jobs.
+    #[must_use]
+    pub fn next_fire(&self) -> Option<DateTime<Utc>> {
+        self.jobs
+            .values()
+            .flat_map(BTreeMap::values)
+            .filter_map(|job| job.next_fire)
+            .min()
+    }
+
+    /// Take every job due at `now` and schedule its next fire.
+    ///
+    /// Ticks are ordered by fire time.
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<CronTick> {
+        let tz = self.tz.clone();
+        let mut ticks = Vec::new();
+        for (capsule_id, jobs) in &mut self.jobs {
+            for (name, job) in jobs.iter_mut() {
+                let Some(fire_time) = job.next_fire.filter(|t| *t <= now) else {
+                    continue;
+                };
+                job.next_fire = next_fire_in(&tz, &job.schedule, now);
+                ticks.push(CronTick {
+                    capsule_id: capsule_id.clone(),
+                    name: name.clone(),
+                    fire_time,
+                });
+            }
+        }
+        ticks.sort_by(|a, b| {
+            (a.fire_time, a.capsule_id.as_str(), &a.name).cmp(&(
+                b.fire_time,
+                b.capsule_id.as_str(),
+                &b.name,
+            ))
+        });
+        ticks
+    }
+
+    /// Every scheduled job, ordered by capsule and name.
+    #[must_use]
+    pub fn jobs(&self) -> Vec<CronJobStatus> {
+        let mut status: Vec<CronJobStatus> = self
+            .jobs
+            .iter()
+            .flat_map(|(capsule_id, jobs)| {
+                jobs.iter().map(|(name, job)| CronJobStatus {
+                    capsule_id: capsule_id.clone(),
+                    name: name.clone(),
+                    schedule: job.schedule.expression().to_string(),
+                    enabled: job.enabled,
+                    next_fire: job.next_fire,
+                })
+            })
+            .collect();
+        status.sort_by(|a, b| {
+            (a.capsule_id.as_str(), &a.name).cmp(&(b.capsule_id.as_str(), &b.name))
+        });
+        status
+    }
+
+    fn next_fire_after(
+        &self,
+        schedule: &CronSchedule,
+        now: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        next_fire_in(&self.tz, schedule, now)
+    }
+}
+
+/// The next fire time of `schedule` after `now`, evaluated in `tz`.
+fn next_fire_in<Tz: TimeZone>(
+    tz: &Tz,
+    schedule: &CronSchedule,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    schedule
+        .next_after(&now.with_timezone(tz))
+        .map(|t| t.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, MappedLocalTime};
+
+    use super::*;
+
+    /// US Eastern time with the 2026 transitions: clocks jump from 02:00 to
+    /// 03:00 on March 8th and fall back from 02:00 to 01:00 on November 1st.
+    #[derive(Debug, Clone, Copy)]
+    struct Eastern2026;
+
+    impl Eastern2026 {
+        fn est() -> FixedOffset {
+            FixedOffset::west_opt(5 * 3600).unwrap()
+        }
+
+        fn edt() -> FixedOffset {
+            FixedOffset::west_opt(4 * 3600).unwrap()
+        }
+    }
+
+    impl TimeZone for Eastern2026 {
+        type Offset = FixedOffset;
+
+        fn from_offset(_offset: &FixedOffset) -> Self {
+            Self
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(
+            &self,
+            local: &NaiveDateTime,
+        ) -> MappedLocalTime<FixedOffset> {
+            let valid: Vec<FixedOffset> = [Self::edt(), Self::est()]
+                .into_iter()
+                .filter(|offset| {
+                    let utc = local
+                        .checked_sub_signed(TimeDelta::seconds(i64::from(offset.local_minus_utc())))
+                        .unwrap();
+                    self.offset_from_utc_datetime(&utc) == *offset
+                })
+                .collect();
+            match valid.as_slice() {
+                [offset] => MappedLocalTime::Single(*offset),
+                [earliest, latest] => MappedLocalTime::Ambiguous(*earliest, *latest),
+                _ => MappedLocalTime::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            let start = utc_time("2026-03-08T07:00:00Z").naive_utc();
+            let end = utc_time("2026-11-01T06:00:00Z").naive_utc();
+            if (start..end).contains(utc) {
+                Self::edt()
+            } else {
+                Self::est()
+            }
+        }
+    }
+
+    fn utc_time(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn local(s: &str) -> DateTime<Eastern2026> {
+        let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        Eastern2026.from_local_datetime(&naive).earliest().unwrap()
+    }
+
+    /// The first `n` fire times after `start`, as UTC RFC 3339 strings.
+    fn fires(expression: &str, start: &str, n: usize) -> Vec<String> {
+        let schedule = CronSchedule::parse(expression).unwrap();
+        let mut t = local(start);
+        (0..n)
+            .map(|_| {
+                t = schedule.next_after(&t).unwrap();
+                t.with_timezone(&Utc)
+                    .format("%Y-%m-%dT%H:%M:%SZ")
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn job(name: &str, schedule: &str) -> CronDef {
+        CronDef {
+            name: name.into(),
+            schedule: schedule.into(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn parses_six_and_five_field_forms() {
+        assert_eq!(
+            fires("*/20 * * * * *", "2026-01-01 00:00:00", 3),
+            [
+                "2026-01-01T05:00:20Z",
+                "2026-01-01T05:00:40Z",
+                "2026-01-01T05:01:00Z"
+            ]
+        );
+        assert_eq!(
+            fires("30 9 * * MON-FRI", "2026-01-02 10:00:00", 2),
+            ["2026-01-05T14:30:00Z", "2026-01-06T14:30:00Z"]
+        );
+        assert_eq!(
+            fires("0 0 12 ? JAN,jul 7", "2026-01-01 00:00:00", 2),
+            ["2026-01-04T17:00:00Z", "2026-01-11T17:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either() {
+        // The 13th (a Monday), or any Friday.
+        assert_eq!(
+            fires("0 0 0 13 * FRI", "2026-04-09 00:00:00", 3),
+            [
+                "2026-04-10T04:00:00Z",
+                "2026-04-13T04:00:00Z",
+                "2026-04-17T04:00:00Z"
+            ]
+        );
+    }
+
+    #[test]
+    fn leap_day_schedule_waits_for_leap_year() {
+        assert_eq!(
+            fires("0 0 0 29 2 *", "2026-01-01 00:00:00", 1),
+            ["2028-02-29T05:00:00Z"]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for (expression, reason) in [
+            ("* * * *", "expected 6 fields"),
+            ("60 * * * * *", "second value 60 is out of range 0-59"),
+            ("* * 24 * * *", "hour value 24"),
+            ("* * * 0 * *", "day-of-month value 0"),
+            ("* * * * 13 *", "month value 13"),
+            ("* * * * * 8", "day-of-week value 8"),
+            (
+                "* * * * * FUNDAY",
+                "day-of-week value 'FUNDAY' is not a number",
+            ),
+            ("*/0 * * * * *", "second step '0'"),
+            ("* 30-10 * * * *", "minute range '30-10' is backwards"),
+            ("? * * * * *", "second value '?'"),
+            ("0 0 0 31 FEB *", "never occurs"),
+            ("poll-gateway", "expected 6 fields"),
+        ] {
+            let err = CronSchedule::parse(expression).unwrap_err();
+            assert_eq!(err.expression, expression);
+            assert!(
+                err.reason.contains(reason),
+                "{expression}: expected '{reason}' in '{}'",
+                err.reason
+            );
+        }
+    }
+
+    #[test]
+    fn spring_forward_skips_missing_times() {
+        // 02:30 does not exist on March 8th.
+        assert_eq!(
+            fires("0 30 2 * * *", "2026-03-07 12:00:00", 2),
+            ["2026-03-09T06:30:00Z", "2026-03-10T06:30:00Z"]
+        );
+        // Every 30 minutes: 01:30 EST is followed directly by 03:00 EDT.
+        assert_eq!(
+            fires("0 0,30 * * * *", "2026-03-08 01:00:00", 3),
+            [
+                "2026-03-08T06:30:00Z",
+                "2026-03-08T07:00:00Z",
+                "2026-03-08T07:30:00Z"
+            ]
+        );
+    }
+
+    #[test]
+    fn fall_back_fires_repeated_times_once() {
+        // 01:30 happens twice on November 1st; only the first fires.
+        assert_eq!(
+            fires("0 30 1 * * *", "2026-10-31 12:00:00", 2),
+            ["2026-11-01T05:30:00Z", "2026-11-02T06:30:00Z"]
+        );
+        // Hourly: 01:00 EDT, then 02:00 EST, with no second 01:00.
+        assert_eq!(
+            fires("0 0 * * * *", "2026-11-01 00:30:00", 3),
+            [
+                "2026-11-01T05:00:00Z",
+                "2026-11-01T07:00:00Z",
+                "2026-11-01T08:00:00Z"
+            ]
+        );
+    }
+
+    #[test]
+    fn scheduler_fires_due_jobs_against_a_fake_clock() {
+        let capsule = CapsuleId::from_static("discord");
+        let mut scheduler = CronScheduler::new(Eastern2026);
+        scheduler
+            .add_capsule(
+                &capsule,
+                &[
+                    job("poll-gateway", "*/30 * * * * *"),
+                    job("nightly", "0 0 1 * * *"),
+                ],
+                utc_time("2026-11-01T04:59:50Z"),
+            )
+            .unwrap();
+        assert_eq!(
+            scheduler.next_fire(),
+            Some(utc_time("2026-11-01T05:00:00Z"))
+        );
+        assert!(
+            scheduler
+                .take_due(utc_time("2026-11-01T04:59:59Z"))
+                .is_empty()
+        );
+
+        // 01:00 EDT: both jobs are due.
+        let ticks = scheduler.take_due(utc_time("2026-11-01T05:00:00Z"));
+        let names: Vec<&str> = ticks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["nightly", "poll-gateway"]);
+        assert!(
+            ticks
+                .iter()
+                .all(|t| t.fire_time == utc_time("2026-11-01T05:00:00Z"))
+        );
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&ticks[0].payload()).unwrap(),
+            serde_json::json!({"name": "nightly", "fire_time": "2026-11-01T05:00:00+00:00"})
+        );
+
+        // A late check fires a missed job once rather than replaying it.
+        let ticks = scheduler.take_due(utc_time("2026-11-01T05:02:10Z"));
+        assert_eq!(ticks.len(), 1);
+        assert_eq!(ticks[0].fire_time, utc_time("2026-11-01T05:00:30Z"));
+        assert_eq!(
+            scheduler.next_fire(),
+            Some(utc_time("2026-11-01T05:02:30Z"))
+        );
+
+        // The repeated 01:00 EST does not fire `nightly` again.
+        let status = scheduler.jobs();
+        assert_eq!(status[0].name, "nightly");
+        assert_eq!(status[0].next_fire, Some(utc_time("2026-11-02T06:00:00Z")));
+    }
+
+    #[test]
+    fn scheduler_enable_disable_and_remove() {
+        let capsule = CapsuleId::from_static("anthropic");
+        let now = utc_time("2026-03-08T06:59:00Z");
+        let mut scheduler = CronScheduler::new(Eastern2026);
+        let mut paused = job("refresh", "0 0 * * * *");
+        paused.enabled = false;
+        scheduler.add_capsule(&capsule, &[paused], now).unwrap();
+        assert_eq!(scheduler.next_fire(), None);
+
+        assert!(scheduler.set_enabled(&capsule, "refresh", true, now));
+        // 02:00 EST does not exist; the next hour is 03:00 EDT.
+        assert_eq!(
+            scheduler.next_fire(),
+            Some(utc_time("2026-03-08T07:00:00Z"))
+        );
+        assert!(!scheduler.set_enabled(&capsule, "missing", false, now));
+
+        assert!(scheduler.set_enabled(&capsule, "refresh", false, now));
+        assert!(
+            scheduler
+                .take_due(utc_time("2026-03-08T09:00:00Z"))
+                .is_empty()
+        );
+        assert!(!scheduler.jobs()[0].enabled);
+
+        scheduler.remove_capsule(&capsule);
+        assert!(scheduler.jobs().is_empty());
+    }
+
+    #[test]
+    fn scheduler_rejects_capsule_with_any_bad_schedule() {
+        let capsule = CapsuleId::from_static("bad");
+        let mut scheduler = CronScheduler::new(Utc);
+        let err = scheduler
+            .add_capsule(
+                &capsule,
+                &[job("ok", "* * * * * *"), job("broken", "61 * * * * *")],
+                Utc::now(),
+            )
+            .unwrap_err();
+        assert_eq!(err.expression, "61 * * * * *");
+        assert!(scheduler.jobs().is_empty());
+    }
+}
@@ -392,6 +392,36 @@ pub fn load_manifest(path: &Path) -> CapsuleResult<CapsuleManifest> {
         }
     }
 
+    // Validate [[cron]] jobs: unique, non-empty names and parseable schedules.
+    let mut seen_jobs: HashSet<&str> = HashSet::new();
+    for job in &manifest.cron_jobs {
+        if job.name.trim().is_empty() {
+            return Err(CapsuleError::ManifestParseError {
+                path: path.to_path_buf(),
+                message: "[[cron]] name must not be empty".into(),
+            });
+        }
+        if !seen_jobs.insert(&job.name) {
+            return Err(CapsuleError::ManifestParseError {
+                path: path.to_path_buf(),
+                message: format!("[[cron]] duplicate job name: '{}'", job.name),
+            });
+        }
+        if let Err(e) = crate::cron::CronSchedule::parse(&job.schedule) {
+            return Err(CapsuleError::ManifestParseError {
+                path: path.to_path_buf(),
+                message: format!("[[cron]] '{}': {e}", job.name),
+            });
+        }
+    }
+
+    if manifest.health.failure_threshold == 0 {
+        return Err(CapsuleError::ManifestParseError {
+            path: path.to_path_buf(),
+            message: "[health] failure_threshold must be at least 1".into(),
+        });
+    }
+
     Ok(manifest)
 }
 
@@ -458,6 +488,47 @@ version = "0.1.0"
         assert!(load_from_toml(&toml).is_ok());
     }
 
+    #[test]
+    fn load_manifest_parses_cron_jobs() {
+        let toml = format!(
+            "{VALID_HEADER}\n[[cron]]\nname = \"poll-gateway\"\nschedule = \"*/30 * * * * *\"\n\n\
+             [[cron]]\nname = \"nightly\"\nschedule = \"0 3 * * *\"\nenabled = false"
+        );
+        let manifest = load_from_toml(&toml).unwrap();
+        assert_eq!(manifest.cron_jobs.len(), 2);
+        assert_eq!(manifest.cron_jobs[0].name, "poll-gateway");
+        assert!(manifest.cron_jobs[0].enabled);
+        assert!(!manifest.cron_jobs[1].enabled);
+    }
+
+    #[test]
+    fn load_manifest_rejects_bad_cron_jobs() {
+        for (jobs, expected) in [
+            (
+                "[[cron]]\nname = \"tick\"\nschedule = \"poll-gateway\"",
+                "[[cron]] 'tick': invalid cron schedule 'poll-gateway': expected 6 fields",
+            ),
+            (
+                "[[cron]]\nname = \"tick\"\nschedule = \"* 99 * * * *\"",
+                "minute value 99 is out of range 0-59",
+            ),
+            (
+                "[[cron]]\nname = \"tick\"\nschedule = \"* * * * * *\"\n\n\
+                 [[cron]]\nname = \"tick\"\nschedule = \"0 * * * * *\"",
+                "duplicate job name: 'tick'",
+            ),
+            (
+                "[[cron]]\nname = \"\"\nschedule = \"* * * * * *\"",
+                "name must not be empty",
+            ),
+        ] {
+            let msg = load_from_toml(&format!("{VALID_HEADER}\n{jobs}"))
+                .unwrap_err()
+                .to_string();
+            assert!(msg.contains(expected), "expected '{expected}', got: {msg}");
+        }
+    }
+
     #[test]
     fn load_manifest_accepts_valid_semver() {
         let toml = "[package]\nname = \"test\"\nversion = \"1.2.3\"\n";
@@ -880,4 +951,22 @@ version = "0.1.0"
             Some(std::path::Path::new("schemas/chunk.json"))
         );
     }
+
+    #[test]
+    fn health_section_defaults_and_overrides() {
+        use crate::manifest::RestartPolicy;
+
+        let manifest = load_from_toml(VALID_HEADER).unwrap();
+        assert_eq!(manifest.health.restart, RestartPolicy::OnFailure);
+        assert_eq!(manifest.health.failure_threshold, 3);
+
+        let toml =
+            format!("{VALID_HEADER}\n[health]\nrestart = \"always\"\nfailure_threshold = 5\n");
+        let manifest = load_from_toml(&toml).unwrap();
+        assert_eq!(manifest.health.restart, RestartPolicy::Always);
+        assert_eq!(manifest.health.failure_threshold, 5);
+
+        let toml = format!("{VALID_HEADER}\n[health]\nfailure_threshold = 0\n");
+        assert!(load_from_toml(&toml).is_err());
+    }
 }
@@ -26,6 +26,14 @@
 //! - Exact match: `user.prompt` matches only `user.prompt`
 //! - Single-segment wildcard: `tool.execute.*.result` matches
 //!   `tool.execute.search.result` but not `tool.execute.result`
+//!
+//! # Capsule Swaps
+//!
+//! While a capsule is being reloaded the kernel holds the write side of the
+//! dispatcher's swap gate (see [`EventDispatcher::with_swap_gate`]). The
+//! dispatcher takes the read side for every event, so events published
+//! during the swap wait in the bus receiver and are routed to the new
+//! instance once the gate opens, instead of finding no interceptor.
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -46,6 +54,10 @@ const CAPSULE_EVENT_QUEUE_CAPACITY: usize = 256;
 
 /// Work item sent to a per-capsule ordered queue.
 struct InterceptorWork {
+    /// The capsule instance the event was matched against. Carried per item
+    /// rather than held by the queue task, so a reloaded capsule's queue
+    /// delivers to the new instance and never pins the old one.
+    capsule: Arc<dyn Capsule>,
     action: String,
     payload: Arc<Vec<u8>>,
     topic: Arc<String>,
@@ -71,6 +83,9 @@ pub struct EventDispatcher {
     /// home directories created. When `None`, provisioning is ungated
     /// (pre-production behavior).
     identity_store: Option<Arc<dyn astrid_storage::IdentityStore>>,
+    /// Held for reading while an event is matched and queued; the kernel
+    /// holds it for writing while it swaps a capsule.
+    swap_gate: Arc<RwLock<()>>,
 }
 
 impl EventDispatcher {
@@ -86,9 +101,20 @@ impl EventDispatcher {
             event_bus,
             receiver,
             identity_store: None,
+            swap_gate: Arc::new(RwLock::new(())),
         }
     }
 
+    /// Share a swap gate with the kernel.
+    ///
+    /// Events are not dispatched while another task holds the gate for
+    /// writing; they queue in the bus receiver until it is released.
+    #[must_use]
+    pub fn with_swap_gate(mut self, gate: Arc<RwLock<()>>) -> Self {
+        self.swap_gate = gate;
+        self
+    }
+
     /// Set the identity store for principal validation during auto-provisioning.
     #[must_use]
     pub fn with_identity_store(mut self, store: Arc<dyn astrid_storage::IdentityStore>) -> Self {
@@ -220,9 +246,11 @@ impl EventDispatcher {
                 }
             }
 
+            let _swap = self.swap_gate.read().await;
             let matches = find_matching_interceptors(&self.registry, &topic).await;
             dispatch_to_capsule_queues(
                 &mut capsule_queues,
+                &self.registry,
                 matches,
                 topic,
                 payload_bytes,
@@ -247,6 +275,7 @@ impl EventDispatcher {
 /// apply across capsules for the same event.
 fn dispatch_to_capsule_queues(
     queues: &mut HashMap<CapsuleId, mpsc::Sender<InterceptorWork>>,
+    registry: &Arc<RwLock<CapsuleRegistry>>,
     matches: Vec<(Arc<dyn Capsule>, String)>,
     topic: Arc<String>,
     payload_bytes: Arc<Vec<u8>>,
@@ -265,7 +294,15 @@ fn dispatch_to_capsule_queues(
     // For single-interceptor events (common case), skip chain overhead.
     if matches_owned.len() == 1 {
         let (capsule, action) = matches_owned.into_iter().next().unwrap();
-        dispatch_single(queues, capsule, action, topic, payload_bytes, ipc_message);
+        dispatch_single(
+            queues,
+            registry,
+            capsule,
+            action,
+            topic,
+            payload_bytes,
+            ipc_message,
+        );
         return;
     }
 
@@ -273,6 +310,7 @@ fn dispatch_to_capsule_queues(
     // Spawned as a task so the dispatcher loop doesn't block.
     let topic_clone = Arc::clone(&topic);
     let ipc_clone = ipc_message.clone();
+    let registry = Arc::clone(registry);
     tokio::task::spawn(async move {
         let mut current_payload = (*payload_bytes).clone();
 
@@ -284,7 +322,10 @@ fn dispatch_to_capsule_queues(
                 "Dispatching interceptor (chain)"
             );
             let caller = ipc_clone.as_deref();
-            match capsule.invoke_interceptor(action, &current_payload, caller) {
+            let result = session_span(caller)
+                .in_scope(|| capsule.invoke_interceptor(action, &current_payload, caller));
+            record_outcome(&registry, capsule.id(), &result).await;
+            match result {
                 Ok(crate::capsule::InterceptResult::Continue(modified_payload)) => {
                     debug!(
                         capsule_id = %capsule.id(),
@@ -346,6 +387,7 @@ fn dispatch_to_capsule_queues(
 /// for ordered delivery without chain overhead.
 fn dispatch_single(
     queues: &mut HashMap<CapsuleId, mpsc::Sender<InterceptorWork>>,
+    registry: &Arc<RwLock<CapsuleRegistry>>,
     capsule: Arc<dyn Capsule>,
     action: String,
     topic: Arc<String>,
@@ -354,9 +396,10 @@ fn dispatch_single(
 ) {
     let sender = queues.entry(capsule.id().clone()).or_insert_with(|| {
         let (tx, mut rx) = mpsc::channel::<InterceptorWork>(CAPSULE_EVENT_QUEUE_CAPACITY);
-        let capsule = Arc::clone(&capsule);
+        let registry = Arc::clone(registry);
         tokio::task::spawn(async move {
             while let Some(work) = rx.recv().await {
+                let capsule = work.capsule;
                 debug!(
                     capsule_id = %capsule.id(),
                     action = %work.action,
@@ -364,7 +407,10 @@ fn dispatch_single(
                     "Dispatching interceptor (ordered)"
                 );
                 let caller = work.ipc_message.as_deref();
-                match capsule.invoke_interceptor(&work.action, &work.payload, caller) {
+                let result = session_span(caller)
+                    .in_scope(|| capsule.invoke_interceptor(&work.action, &work.payload, caller));
+                record_outcome(&registry, capsule.id(), &result).await;
+                match result {
                     Ok(crate::capsule::InterceptResult::Continue(_)) => {
                         debug!(
                             capsule_id = %capsule.id(),
@@ -412,6 +458,7 @@ fn dispatch_single(
     });
 
     let work = InterceptorWork {
+        capsule: Arc::clone(&capsule),
         action,
         payload: Arc::clone(&payload_bytes),
         topic: Arc::clone(&topic),
@@ -426,6 +473,41 @@ fn dispatch_single(
     }
 }
 
+/// Count an interceptor outcome towards the capsule's run of consecutive
+/// traps. Only a guest trap ([`crate::error::CapsuleError::Trap`]) counts:
+/// host-side denials such as a disabled principal or an invalid profile
+/// surface as `WasmError` and must not let one principal fail a capsule for
+/// everyone. `NotSupported` means the guest never ran, so it neither counts
+/// nor ends the run.
+async fn record_outcome(
+    registry: &RwLock<CapsuleRegistry>,
+    capsule_id: &CapsuleId,
+    result: &crate::error::CapsuleResult<crate::capsule::InterceptResult>,
+) {
+    let trapped = match result {
+        Err(crate::error::CapsuleError::NotSupported(_)) => return,
+        Err(crate::error::CapsuleError::Trap(_)) => true,
+        Err(crate::error::CapsuleError::WasmError(_)) => return,
+        _ => false,
+    };
+    let traps = registry
+        .read()
+        .await
+        .record_interceptor_result(capsule_id, trapped);
+    if trapped {
+        warn!(capsule_id = %capsule_id, traps, "Capsule interceptor trapped");
+    }
+}
+
+/// A span tagging the interceptor's logs with the message's session, so
+/// they are captured by `astrid_telemetry::session_logs`.
+fn session_span(message: Option<&astrid_events::ipc::IpcMessage>) -> tracing::Span {
+    match message.and_then(|m| m.payload.session_id()) {
+        Some(session_id) => tracing::info_span!("session", session_id),
+        None => tracing::Span::none(),
+    }
+}
+
 /// Find all capsules with interceptors matching the given topic.
 ///
 /// Takes a brief read lock on the registry. Only `Ready` capsules are
@@ -487,6 +569,9 @@ mod tests {
         invocation_log: Option<Arc<Mutex<Vec<String>>>>,
         /// Override the default `Continue` result for testing chain semantics.
         result_override: Option<InterceptResult>,
+        /// Fail every invocation with this error (a guest trap or a
+        /// host-side denial).
+        failure: Option<fn(String) -> crate::error::CapsuleError>,
     }
 
     impl MockCapsule {
@@ -537,6 +622,8 @@ mod tests {
                     priority,
                 }],
                 topics: Vec::new(),
+                cron_jobs: Vec::new(),
+                health: Default::default(),
             };
             let capsule = Self {
                 id: CapsuleId::from_static(name),
@@ -544,6 +631,7 @@ mod tests {
                 invoked: Arc::clone(&invoked),
                 invocation_log,
                 result_override: None,
+                failure: None,
             };
             (capsule, invoked)
         }
@@ -576,6 +664,9 @@ mod tests {
             if let Some(ref log) = self.invocation_log {
                 log.lock().unwrap().push(self.id.to_string());
             }
+            if let Some(failure) = self.failure {
+                return Err(failure("unreachable".into()));
+            }
             if let Some(ref result) = self.result_override {
                 return Ok(result.clone());
             }
@@ -910,6 +1001,115 @@ mod tests {
         handle.abort();
     }
 
+    #[tokio::test]
+    async fn dispatch_holds_events_during_capsule_swap() {
+        let (old, old_invoked) = MockCapsule::new("tools", "tool.v1.execute.search");
+
+        let mut registry = CapsuleRegistry::new();
+        registry.register(Box::new(old)).unwrap();
+        let registry = Arc::new(RwLock::new(registry));
+
+        let bus = Arc::new(EventBus::with_capacity(64));
+        let gate = Arc::new(RwLock::new(()));
+        let dispatcher = EventDispatcher::new(Arc::clone(&registry), Arc::clone(&bus))
+            .with_swap_gate(Arc::clone(&gate));
+        let handle = tokio::spawn(dispatcher.run());
+        tokio::task::yield_now().await;
+
+        // Opens the per-capsule queue for "tools" with the old instance.
+        publish_ipc(&bus, "tool.v1.execute.search");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(old_invoked.load(Ordering::SeqCst));
+
+        // Swap in a copy whose tool list changed. The event published
+        // mid-swap matches only the new copy.
+        let swap = gate.write().await;
+        registry
+            .write()
+            .await
+            .unregister(&CapsuleId::from_static("tools"))
+            .unwrap();
+        publish_ipc(&bus, "tool.v1.execute.fetch");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let (new, new_invoked) = MockCapsule::new("tools", "tool.v1.execute.fetch");
+        registry.write().await.register(Box::new(new)).unwrap();
+        assert!(!new_invoked.load(Ordering::SeqCst));
+        drop(swap);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(
+            new_invoked.load(Ordering::SeqCst),
+            "event published during the swap should reach the new copy"
+        );
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn dispatch_counts_consecutive_traps() {
+        let (mut capsule, invoked) = MockCapsule::new("trapper", "test.topic");
+        capsule.failure = Some(crate::error::CapsuleError::Trap);
+        let id = capsule.id.clone();
+
+        let mut registry = CapsuleRegistry::new();
+        registry.register(Box::new(capsule)).unwrap();
+        let registry = Arc::new(RwLock::new(registry));
+
+        let bus = Arc::new(EventBus::with_capacity(64));
+        let dispatcher = EventDispatcher::new(Arc::clone(&registry), Arc::clone(&bus));
+        let handle = tokio::spawn(dispatcher.run());
+        tokio::task::yield_now().await;
+
+        for _ in 0..3 {
+            publish_ipc(&bus, "test.topic");
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert!(invoked.load(Ordering::SeqCst));
+        let registry = registry.read().await;
+        assert_eq!(registry.consecutive_traps(&id), 3);
+        assert!(matches!(
+            registry.health(&id),
+            Some(CapsuleState::Failed(_))
+        ));
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn dispatch_ignores_host_denials_when_counting_traps() {
+        // A principal that is disabled or has an invalid profile is denied
+        // before the guest runs. Those denials must not fail the capsule
+        // for every other principal.
+        let (mut capsule, invoked) = MockCapsule::new("denied", "test.topic");
+        capsule.failure = Some(crate::error::CapsuleError::WasmError);
+        let id = capsule.id.clone();
+
+        let mut registry = CapsuleRegistry::new();
+        registry.register(Box::new(capsule)).unwrap();
+        let registry = Arc::new(RwLock::new(registry));
+
+        let bus = Arc::new(EventBus::with_capacity(64));
+        let dispatcher = EventDispatcher::new(Arc::clone(&registry), Arc::clone(&bus));
+        let handle = tokio::spawn(dispatcher.run());
+        tokio::task::yield_now().await;
+
+        for _ in 0..5 {
+            publish_ipc(&bus, "test.topic");
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert!(invoked.load(Ordering::SeqCst));
+        let registry = registry.read().await;
+        assert_eq!(registry.consecutive_traps(&id), 0);
+        assert!(!matches!(
+            registry.health(&id),
+            Some(CapsuleState::Failed(_))
+        ));
+
+        handle.abort();
+    }
+
     #[test]
     fn intercept_result_from_guest_bytes() {
         // Empty = Continue
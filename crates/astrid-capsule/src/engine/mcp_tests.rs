@@ -45,6 +45,8 @@ mod tests {
                 ipc_publish: vec![],
                 ipc_subscribe: vec![],
                 identity: vec![],
+                call: vec![],
+                secrets: vec![],
                 allow_prompt_injection: false,
             },
             env: HashMap::new(),
@@ -61,6 +63,8 @@ mod tests {
             uplinks: vec![],
             interceptors: vec![],
             topics: vec![],
+            cron_jobs: vec![],
+            health: Default::default(),
         }
     }
 
@@ -107,6 +111,8 @@ mod tests {
             schema_catalog: std::sync::Arc::new(crate::schema_catalog::SchemaCatalog::new()),
             profile_cache: None,
             overlay_registry: None,
+            audit: None,
+            workspace_mode: astrid_workspace::WorkspaceMode::Safe,
         };
 
         let result = engine.load(&ctx).await;
@@ -171,6 +177,8 @@ mod tests {
             schema_catalog: std::sync::Arc::new(crate::schema_catalog::SchemaCatalog::new()),
             profile_cache: None,
             overlay_registry: None,
+            audit: None,
+            workspace_mode: astrid_workspace::WorkspaceMode::Safe,
         };
 
         let result = engine.load(&ctx).await;
@@ -232,6 +240,8 @@ mod tests {
             schema_catalog: std::sync::Arc::new(crate::schema_catalog::SchemaCatalog::new()),
             profile_cache: None,
             overlay_registry: None,
+            audit: None,
+            workspace_mode: astrid_workspace::WorkspaceMode::Safe,
         };
 
         let result = engine.load(&ctx).await;
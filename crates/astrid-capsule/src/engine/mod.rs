@@ -70,6 +70,21 @@ pub(crate) trait ExecutionEngine: Send + Sync {
         ))
     }
 
+    /// Like [`invoke_interceptor`](Self::invoke_interceptor), but abandons
+    /// the guest call once `cancel` fires.
+    ///
+    /// The default ignores `cancel`. `WasmEngine` interrupts the guest at
+    /// its next epoch tick.
+    fn invoke_interceptor_cancellable(
+        &self,
+        action: &str,
+        payload: &[u8],
+        caller: Option<&astrid_events::ipc::IpcMessage>,
+        _cancel: &tokio_util::sync::CancellationToken,
+    ) -> CapsuleResult<crate::capsule::InterceptResult> {
+        self.invoke_interceptor(action, payload, caller)
+    }
+
     /// Probe engine liveness beyond what `state()` reports.
     ///
     /// The default implementation returns the capsule's current state.
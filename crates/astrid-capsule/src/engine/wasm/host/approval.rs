@@ -4,6 +4,8 @@
 //! needs human consent for a sensitive action. Checks the shared
 //! [`AllowanceStore`] first (instant path), then publishes an
 //! [`ApprovalRequired`] IPC event and blocks until the frontend responds.
+//! Host functions route their own approvals (e.g. a spawned command naming
+//! paths outside the workspace) through the same event.
 
 use crate::engine::wasm::bindings::astrid::capsule::approval;
 use crate::engine::wasm::bindings::astrid::capsule::types::{ApprovalRequest, ApprovalResponse};
@@ -15,7 +17,7 @@ use astrid_core::principal::PrincipalId;
 use astrid_core::types::Timestamp;
 use astrid_crypto::KeyPair;
 use astrid_events::AstridEvent;
-use astrid_events::ipc::{IpcMessage, IpcPayload};
+use astrid_events::ipc::{ApprovalRisk, IpcMessage, IpcPayload};
 use uuid::Uuid;
 
 /// Maximum timeout for approval requests (60 seconds).
@@ -175,6 +177,44 @@ fn create_allowance_from_decision(
     }
 }
 
+/// Remember exactly this command for the workspace (`"approve_exact"`).
+///
+/// Unlike [`create_allowance_from_decision`], the grant is not a glob over
+/// the action prefix: it is an [`AllowancePattern::ExactAction`] over the
+/// full resource string, so only the same command (modulo whitespace) in
+/// the same workspace is auto-approved by [`check_allowance`].
+fn create_exact_allowance(
+    store: &AllowanceStore,
+    principal: &PrincipalId,
+    resource: &str,
+    workspace_root: Option<std::path::PathBuf>,
+) {
+    if resource.trim().is_empty() {
+        return;
+    }
+    let action = SensitiveAction::ExecuteCommand {
+        command: resource.to_owned(),
+        args: vec![],
+    };
+    let keypair = KeyPair::generate();
+    let allowance = Allowance {
+        id: AllowanceId::new(),
+        principal: principal.clone(),
+        action_pattern: AllowancePattern::exact_action(&action, workspace_root.as_deref()),
+        created_at: Timestamp::now(),
+        expires_at: None,
+        max_uses: None,
+        uses_remaining: None,
+        session_only: false,
+        workspace_root,
+        signature: keypair.sign(b"plugin-approval"),
+    };
+
+    if let Err(e) = store.add_allowance(allowance) {
+        tracing::warn!("Failed to add exact approval allowance: {e}");
+    }
+}
+
 impl approval::Host for HostState {
     /// Host function: `request_approval(request) -> ApprovalResponse`
     ///
@@ -185,11 +225,7 @@ impl approval::Host for HostState {
         mut request: ApprovalRequest,
     ) -> Result<ApprovalResponse, String> {
         let allowance_store = self.allowance_store.clone();
-        let event_bus = self.event_bus.clone();
-        let runtime_handle = self.runtime_handle.clone();
         let capsule_id = self.capsule_id.to_string();
-        let cancel_token = self.cancel_token.clone();
-        let host_semaphore = self.host_semaphore.clone();
         let workspace_root = self.workspace_root.clone();
         // Layer 4 (#668): the invoking principal scopes allowance lookups.
         // Falls back to the capsule owner for load-time / tests / daemons.
@@ -226,7 +262,130 @@ impl approval::Host for HostState {
             return Ok(ApprovalResponse { approved: true });
         }
 
-        // Slow path: publish ApprovalRequired and wait for response.
+        // Slow path: ask the frontend.
+        let reason = format!("Capsule '{capsule_id}' requests approval");
+        let risk = guest_request_risk(&request.action, &request.target_resource, &workspace_root);
+        let Some(decision) = self.await_approval_decision(
+            &request.action,
+            &request.target_resource,
+            reason,
+            Some(risk),
+        )?
+        else {
+            tracing::warn!(
+                plugin = %capsule_id,
+                action = %request.action,
+                "Approval request timed out or was cancelled"
+            );
+            // Timeout/cancellation = deny
+            return Ok(ApprovalResponse { approved: false });
+        };
+
+        let approved = is_approval(&decision);
+
+        // Create allowance for exact/session/always decisions.
+        if approved
+            && decision == "approve_exact"
+            && let Some(ref store) = allowance_store
+        {
+            create_exact_allowance(
+                store,
+                &principal,
+                &request.target_resource,
+                Some(workspace_root.clone()),
+            );
+        } else if approved && let Some(ref store) = allowance_store {
+            create_allowance_from_decision(
+                store,
+                &principal,
+                &request.action,
+                &decision,
+                Some(workspace_root.clone()),
+                &capsule_id,
+            );
+        }
+
+        Ok(ApprovalResponse { approved })
+    }
+}
+
+/// Risk of an action the host built: reads of paths inside the workspace
+/// are low, everything else is high.
+fn host_action_risk(action: &SensitiveAction, workspace_root: &std::path::Path) -> ApprovalRisk {
+    let read = match action {
+        SensitiveAction::FileRead { path }
+        | SensitiveAction::CapsuleFileAccess {
+            path,
+            mode: astrid_core::types::Permission::Read,
+            ..
+        } => std::path::Path::new(path),
+        _ => return ApprovalRisk::High,
+    };
+    let inside = read.is_absolute()
+        && read.starts_with(workspace_root)
+        && !read
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir));
+    if inside {
+        ApprovalRisk::Low
+    } else {
+        ApprovalRisk::High
+    }
+}
+
+/// Risk of an approval request a capsule described itself.
+///
+/// The host cannot see what the capsule will do, only check what it names:
+/// a `file_read` is low when its resource is an absolute path that resolves,
+/// symlinks included, to an existing path inside the workspace. Anything
+/// else is high.
+fn guest_request_risk(
+    action: &str,
+    resource: &str,
+    workspace_root: &std::path::Path,
+) -> ApprovalRisk {
+    let path = std::path::Path::new(resource);
+    if action != "file_read" || !path.is_absolute() {
+        return ApprovalRisk::High;
+    }
+    let (Ok(path), Ok(root)) = (
+        std::fs::canonicalize(path),
+        std::fs::canonicalize(workspace_root),
+    ) else {
+        return ApprovalRisk::High;
+    };
+    host_action_risk(
+        &SensitiveAction::FileRead {
+            path: path.display().to_string(),
+        },
+        &root,
+    )
+}
+
+/// Whether a frontend decision approves the request.
+fn is_approval(decision: &str) -> bool {
+    matches!(
+        decision,
+        "approve" | "approve_exact" | "approve_session" | "approve_always"
+    )
+}
+
+impl HostState {
+    /// Publish an `ApprovalRequired` event and block until the frontend
+    /// answers, returning its decision. `None` means the request timed out
+    /// or the capsule was cancelled, which callers treat as a denial.
+    ///
+    /// `risk` is set only for actions the host built itself.
+    fn await_approval_decision(
+        &self,
+        action: &str,
+        resource: &str,
+        reason: String,
+        risk: Option<ApprovalRisk>,
+    ) -> Result<Option<String>, String> {
+        let event_bus = self.event_bus.clone();
+        let capsule_id = self.capsule_id.to_string();
+
         let request_id = Uuid::new_v4().to_string();
         let response_topic = format!("astrid.v1.approval.response.{request_id}");
 
@@ -235,9 +394,10 @@ impl approval::Host for HostState {
 
         let request_payload = IpcPayload::ApprovalRequired {
             request_id: request_id.clone(),
-            action: request.action.clone(),
-            resource: request.target_resource.clone(),
-            reason: format!("Capsule '{capsule_id}' requests approval"),
+            action: action.to_owned(),
+            resource: resource.to_owned(),
+            reason,
+            risk,
         };
         let message = IpcMessage::new(
             "astrid.v1.approval",
@@ -251,17 +411,17 @@ impl approval::Host for HostState {
 
         tracing::debug!(
             plugin = %capsule_id,
-            action = %request.action,
-            resource = %request.target_resource,
+            %action,
+            %resource,
             %request_id,
             "Published approval request, waiting for response"
         );
 
         // Block until response, timeout, or cancellation.
         let event = util::bounded_block_on_cancellable(
-            &runtime_handle,
-            &host_semaphore,
-            &cancel_token,
+            &self.runtime_handle,
+            &self.host_semaphore,
+            &self.cancel_token,
             async {
                 tokio::time::timeout(
                     std::time::Duration::from_millis(MAX_APPROVAL_TIMEOUT_MS),
@@ -274,56 +434,84 @@ impl approval::Host for HostState {
         )
         .flatten();
 
-        match event {
-            Some(event) => {
-                if let AstridEvent::Ipc { message, .. } = &*event {
-                    match &message.payload {
-                        IpcPayload::ApprovalResponse {
-                            decision, reason, ..
-                        } => {
-                            let approved = matches!(
-                                decision.as_str(),
-                                "approve" | "approve_session" | "approve_always"
-                            );
-
-                            // Create allowance for session/always decisions.
-                            if approved && let Some(ref store) = allowance_store {
-                                create_allowance_from_decision(
-                                    store,
-                                    &principal,
-                                    &request.action,
-                                    decision,
-                                    Some(workspace_root.clone()),
-                                    &capsule_id,
-                                );
-                            }
-
-                            tracing::info!(
-                                plugin = %capsule_id,
-                                action = %request.action,
-                                %decision,
-                                reason = reason.as_deref().unwrap_or("none"),
-                                "Approval response received"
-                            );
-
-                            Ok(ApprovalResponse { approved })
-                        },
-                        _ => Err("unexpected IPC payload type in approval response".to_string()),
-                    }
-                } else {
-                    Err("unexpected event type in approval response".to_string())
-                }
-            },
-            None => {
-                tracing::warn!(
-                    plugin = %capsule_id,
-                    action = %request.action,
-                    "Approval request timed out or was cancelled"
-                );
-                // Timeout/cancellation = deny
-                Ok(ApprovalResponse { approved: false })
-            },
+        let Some(event) = event else {
+            return Ok(None);
+        };
+        let AstridEvent::Ipc { message, .. } = &*event else {
+            return Err("unexpected event type in approval response".to_string());
+        };
+        let IpcPayload::ApprovalResponse {
+            decision, reason, ..
+        } = &message.payload
+        else {
+            return Err("unexpected IPC payload type in approval response".to_string());
+        };
+
+        tracing::info!(
+            plugin = %capsule_id,
+            %action,
+            %decision,
+            reason = reason.as_deref().unwrap_or("none"),
+            "Approval response received"
+        );
+        Ok(Some(decision.clone()))
+    }
+
+    /// Ask the user to approve a sensitive action the host itself detected,
+    /// such as a spawned command naming paths outside the workspace.
+    ///
+    /// The action is built by the host, not supplied by the guest, so a
+    /// remembered decision is an exact-action allowance over it.
+    pub(crate) fn approve_host_action(&self, action: &SensitiveAction) -> Result<bool, String> {
+        let principal = self.effective_principal();
+        let workspace_root = self.workspace_root.as_path();
+
+        if let Some(ref store) = self.allowance_store
+            && store
+                .find_matching_and_consume(&principal, action, Some(workspace_root))
+                .is_some()
+        {
+            return Ok(true);
+        }
+
+        let reason = format!(
+            "Capsule '{}' requests approval: {}",
+            self.capsule_id,
+            action.summary()
+        );
+        let Some(decision) = self.await_approval_decision(
+            action.action_type(),
+            &action.summary(),
+            reason,
+            Some(host_action_risk(action, workspace_root)),
+        )?
+        else {
+            return Ok(false);
+        };
+        let approved = is_approval(&decision);
+
+        if approved
+            && decision != "approve"
+            && let Some(ref store) = self.allowance_store
+        {
+            let keypair = KeyPair::generate();
+            let allowance = Allowance {
+                id: AllowanceId::new(),
+                principal,
+                action_pattern: AllowancePattern::exact_action(action, Some(workspace_root)),
+                created_at: Timestamp::now(),
+                expires_at: None,
+                max_uses: None,
+                uses_remaining: None,
+                session_only: decision == "approve_session",
+                workspace_root: Some(workspace_root.to_path_buf()),
+                signature: keypair.sign(b"host-approval"),
+            };
+            if let Err(e) = store.add_allowance(allowance) {
+                tracing::warn!("Failed to add host approval allowance: {e}");
+            }
         }
+        Ok(approved)
     }
 }
 
@@ -416,6 +604,38 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn create_exact_allowance_matches_only_that_command() {
+        let store = AllowanceStore::new();
+        let ws = std::path::PathBuf::from("/work/app");
+        create_exact_allowance(
+            &store,
+            &PrincipalId::default(),
+            "cargo test",
+            Some(ws.clone()),
+        );
+        assert_eq!(store.count(), 1);
+        let principal = PrincipalId::default();
+        assert!(check_allowance(
+            &store,
+            &principal,
+            "cargo  test",
+            Some(&ws)
+        ));
+        assert!(!check_allowance(
+            &store,
+            &principal,
+            "cargo test --release",
+            Some(&ws)
+        ));
+        assert!(!check_allowance(
+            &store,
+            &principal,
+            "cargo test",
+            Some(std::path::Path::new("/work/other"))
+        ));
+    }
+
     #[test]
     fn create_allowance_simple_approve_does_nothing() {
         let store = AllowanceStore::new();
@@ -797,4 +1017,169 @@ mod tests {
         sanitize_guest_field(&mut s, MAX_RESOURCE_LEN, "resource", "test");
         assert!(s.is_empty());
     }
+
+    #[test]
+    fn host_action_risk_is_low_only_for_reads_inside_the_workspace() {
+        let ws = std::path::Path::new("/work");
+        let read = |path: &str| SensitiveAction::FileRead { path: path.into() };
+        assert_eq!(
+            host_action_risk(&read("/work/src/lib.rs"), ws),
+            ApprovalRisk::Low
+        );
+        assert_eq!(
+            host_action_risk(&read("/etc/passwd"), ws),
+            ApprovalRisk::High
+        );
+        assert_eq!(
+            host_action_risk(&read("/work/../etc/passwd"), ws),
+            ApprovalRisk::High
+        );
+        assert_eq!(
+            host_action_risk(&read("src/lib.rs"), ws),
+            ApprovalRisk::High
+        );
+        assert_eq!(
+            host_action_risk(
+                &SensitiveAction::CapsuleFileAccess {
+                    capsule_id: "c".into(),
+                    path: "/work/notes.md".into(),
+                    mode: astrid_core::types::Permission::Write,
+                },
+                ws
+            ),
+            ApprovalRisk::High
+        );
+        assert_eq!(
+            host_action_risk(
+                &SensitiveAction::ShellOutsideWorkspace {
+                    command: "cat /work/a".into(),
+                    paths: vec!["/work/a".into()],
+                },
+                ws
+            ),
+            ApprovalRisk::High
+        );
+    }
+
+    #[test]
+    fn guest_request_risk_checks_the_resource_against_the_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ws = tmp.path().join("ws");
+        std::fs::create_dir_all(ws.join("src")).unwrap();
+        std::fs::write(ws.join("src/lib.rs"), "").unwrap();
+        std::fs::write(tmp.path().join("secret"), "").unwrap();
+        let inside = ws.join("src/lib.rs").display().to_string();
+        let outside = tmp.path().join("secret").display().to_string();
+
+        assert_eq!(
+            guest_request_risk("file_read", &inside, &ws),
+            ApprovalRisk::Low
+        );
+        // The host rates the action, so other verbs stay high.
+        assert_eq!(
+            guest_request_risk("file_delete", &inside, &ws),
+            ApprovalRisk::High
+        );
+        assert_eq!(
+            guest_request_risk("file_read", &outside, &ws),
+            ApprovalRisk::High
+        );
+        assert_eq!(
+            guest_request_risk("file_read", "src/lib.rs", &ws),
+            ApprovalRisk::High
+        );
+        assert_eq!(
+            guest_request_risk("file_read", &ws.join("missing").display().to_string(), &ws),
+            ApprovalRisk::High
+        );
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(tmp.path().join("secret"), ws.join("link")).unwrap();
+            assert_eq!(
+                guest_request_risk("file_read", &ws.join("link").display().to_string(), &ws),
+                ApprovalRisk::High
+            );
+        }
+    }
+
+    /// Answers approval requests like headless `approve-low-risk`,
+    /// recording the risk each request arrived with.
+    fn spawn_low_risk_responder(
+        bus: &astrid_events::EventBus,
+    ) -> tokio::task::JoinHandle<Vec<Option<ApprovalRisk>>> {
+        let mut requests = bus.subscribe_topic("astrid.v1.approval");
+        let bus = bus.clone();
+        tokio::spawn(async move {
+            let mut risks = Vec::new();
+            while let Some(event) = requests.recv().await {
+                let AstridEvent::Ipc { message, .. } = &*event else {
+                    continue;
+                };
+                let IpcPayload::ApprovalRequired {
+                    request_id, risk, ..
+                } = &message.payload
+                else {
+                    continue;
+                };
+                risks.push(*risk);
+                let decision = if *risk == Some(ApprovalRisk::Low) {
+                    "approve"
+                } else {
+                    "deny"
+                };
+                let response = IpcPayload::ApprovalResponse {
+                    request_id: request_id.clone(),
+                    decision: decision.to_string(),
+                    reason: None,
+                };
+                bus.publish(AstridEvent::Ipc {
+                    message: IpcMessage::new(
+                        format!("astrid.v1.approval.response.{request_id}"),
+                        response,
+                        Uuid::nil(),
+                    ),
+                    metadata: astrid_events::EventMetadata::default(),
+                });
+                if risks.len() == 2 {
+                    return risks;
+                }
+            }
+            risks
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn request_approval_sends_the_kernel_assessed_risk() {
+        use crate::engine::wasm::test_fixtures::minimal_host_state;
+
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("notes.md"), "").unwrap();
+        let mut state = minimal_host_state(tokio::runtime::Handle::current());
+        state.workspace_root = tmp.path().to_path_buf();
+        let responder = spawn_low_risk_responder(&state.event_bus);
+        let notes = tmp.path().join("notes.md").display().to_string();
+
+        let approved = tokio::task::spawn_blocking(move || {
+            let mut ask = |action: &str, resource: &str| {
+                approval::Host::request_approval(
+                    &mut state,
+                    ApprovalRequest {
+                        action: action.into(),
+                        target_resource: resource.into(),
+                    },
+                )
+                .expect("request_approval")
+                .approved
+            };
+            (ask("file_read", &notes), ask("file_delete", &notes))
+        })
+        .await
+        .expect("join");
+
+        assert_eq!(approved, (true, false));
+        assert_eq!(
+            responder.await.expect("responder"),
+            vec![Some(ApprovalRisk::Low), Some(ApprovalRisk::High)]
+        );
+    }
 }
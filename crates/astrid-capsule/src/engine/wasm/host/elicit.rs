@@ -7,8 +7,10 @@ use crate::engine::wasm::bindings::astrid::capsule::elicit;
 use crate::engine::wasm::bindings::astrid::capsule::types::ElicitRequest;
 use crate::engine::wasm::host::util;
 use crate::engine::wasm::host_state::HostState;
-use astrid_events::AstridEvent;
+use crate::security::SECRET_RESOURCE_PREFIX;
+use astrid_audit::{AuditAction, AuditOutcome, AuthorizationProof};
 use astrid_events::ipc::{IpcMessage, IpcPayload, OnboardingField, OnboardingFieldType};
+use astrid_events::{AstridEvent, EventMetadata};
 use uuid::Uuid;
 
 /// Maximum timeout for interactive elicitation (120 seconds).
@@ -207,6 +209,81 @@ impl elicit::Host for HostState {
             .exists(&key)
             .map_err(|e| format!("failed to check for secret: {e}"))
     }
+
+    /// Host function: `get_secret(key) -> string`
+    ///
+    /// Reads a secret value declared in the manifest's `capabilities.secrets`.
+    /// Every attempt is written to the audit log as a `SecretRead` entry
+    /// before this returns; if that write fails the value is withheld. The
+    /// attempt is also published as a `CapabilityChecked` event with a
+    /// `secret:{key}` resource. The value itself never appears in either.
+    fn get_secret(&mut self, key: String) -> Result<String, String> {
+        let capsule_id = self.capsule_id.as_str().to_owned();
+
+        // Without a gate there is no manifest to consult: fail closed.
+        let check = match self.security.clone() {
+            Some(gate) => {
+                let pid = capsule_id.clone();
+                let name = key.clone();
+                util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async move {
+                    gate.check_secret_read(&pid, &name).await
+                })
+            },
+            None => Err(format!(
+                "capsule '{capsule_id}' denied: no security gate for secret '{key}'"
+            )),
+        };
+
+        self.event_bus.publish(AstridEvent::CapabilityChecked {
+            metadata: EventMetadata::new(capsule_id.clone()),
+            resource: format!("{SECRET_RESOURCE_PREFIX}{key}"),
+            action: "read".to_string(),
+            allowed: check.is_ok(),
+        });
+
+        let action = AuditAction::SecretRead {
+            capsule_id: capsule_id.clone(),
+            name: key.clone(),
+        };
+        if let Err(reason) = check {
+            let audited = self.append_audit(
+                action,
+                AuthorizationProof::Denied {
+                    reason: reason.clone(),
+                },
+                AuditOutcome::failure("secret read denied"),
+            );
+            if let Err(e) = audited {
+                tracing::warn!(
+                    security_event = true,
+                    capsule_id,
+                    secret = %key,
+                    error = %e,
+                    "Failed to persist secret-read audit entry"
+                );
+            }
+            return Err(format!("security denied get_secret: {reason}"));
+        }
+
+        let value = self
+            .effective_secret_store()
+            .get(&key)
+            .map_err(|e| format!("failed to read secret: {e}"))
+            .and_then(|v| v.ok_or_else(|| format!("secret '{key}' is not set")));
+        let outcome = match &value {
+            Ok(_) => AuditOutcome::success(),
+            Err(e) => AuditOutcome::failure(e.clone()),
+        };
+        self.append_audit(
+            action,
+            AuthorizationProof::System {
+                reason: "secret declared in capsule manifest".to_string(),
+            },
+            outcome,
+        )
+        .map_err(|e| format!("secret '{key}' withheld: audit log write failed: {e}"))?;
+        value
+    }
 }
 
 #[cfg(test)]
@@ -319,6 +396,7 @@ mod secret_chain_tests {
     use crate::engine::wasm::bindings::astrid::capsule::elicit::Host as ElicitHost;
     use crate::engine::wasm::host_state::HostState;
     use crate::engine::wasm::test_fixtures::{mem_secret_store, minimal_host_state};
+    use astrid_audit::{AuditAction, AuditOutcome, AuthorizationProof};
     use astrid_storage::secret::SecretStore;
 
     /// Build a HostState whose load-time `secret_store` points at a fresh,
@@ -412,6 +490,168 @@ mod secret_chain_tests {
         assert_eq!(alice_val.as_deref(), Some("alice-val"));
     }
 
+    /// Install a manifest gate that declares only `api_key` as readable.
+    fn install_secret_gate(state: &mut HostState) {
+        let manifest: crate::manifest::CapsuleManifest = toml::from_str(
+            "[package]\nname = \"test-capsule\"\nversion = \"0.1.0\"\n\n\
+             [capabilities]\nsecrets = [\"api_key\"]\n",
+        )
+        .unwrap();
+        state.security = Some(Arc::new(crate::security::ManifestSecurityGate::new(
+            manifest,
+            std::env::temp_dir(),
+            None,
+        )));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_secret_reads_declared_secret_and_publishes_check() {
+        let rt = tokio::runtime::Handle::current();
+        let (mut state, owner_secret) = make_host_state_with_secret(rt, "capsule:test-owner");
+        install_secret_gate(&mut state);
+        let mut events = state.event_bus.subscribe();
+        {
+            let s = Arc::clone(&owner_secret);
+            blocking(move || s.set("api_key", "sk-live").unwrap()).await;
+        }
+
+        let got = blocking(move || {
+            let mut s = state;
+            s.get_secret("api_key".to_string())
+        })
+        .await;
+        assert_eq!(got.unwrap(), "sk-live");
+
+        let event = events.try_recv().expect("capability check published");
+        let astrid_events::AstridEvent::CapabilityChecked {
+            resource, allowed, ..
+        } = &*event
+        else {
+            panic!("unexpected event: {event:?}");
+        };
+        assert_eq!(resource, "secret:api_key");
+        assert!(allowed);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_secret_denies_undeclared_secret() {
+        let rt = tokio::runtime::Handle::current();
+        let (mut state, owner_secret) = make_host_state_with_secret(rt, "capsule:test-owner");
+        install_secret_gate(&mut state);
+        let mut events = state.event_bus.subscribe();
+        {
+            let s = Arc::clone(&owner_secret);
+            blocking(move || s.set("other_key", "hidden").unwrap()).await;
+        }
+
+        let got = blocking(move || {
+            let mut s = state;
+            s.get_secret("other_key".to_string())
+        })
+        .await;
+        let err = got.unwrap_err();
+        assert!(err.contains("not declared in manifest"), "got: {err}");
+        assert!(!err.contains("hidden"));
+
+        let event = events.try_recv().expect("capability check published");
+        assert!(matches!(
+            &*event,
+            astrid_events::AstridEvent::CapabilityChecked { allowed: false, .. }
+        ));
+    }
+
+    /// Attach an in-memory audit log to `state`, returning it and the
+    /// session its entries land in.
+    fn attach_audit(
+        state: &mut HostState,
+    ) -> (Arc<astrid_audit::AuditLog>, astrid_core::SessionId) {
+        let log = Arc::new(astrid_audit::AuditLog::in_memory(
+            astrid_crypto::KeyPair::generate(),
+        ));
+        let session_id = astrid_core::SessionId::new();
+        state.audit = Some(crate::context::CapsuleAudit {
+            log: Arc::clone(&log),
+            session_id: session_id.clone(),
+            seal_writes_up_to: 0,
+        });
+        (log, session_id)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_secret_is_audited_before_it_returns() {
+        let rt = tokio::runtime::Handle::current();
+        let (mut state, owner_secret) = make_host_state_with_secret(rt, "capsule:test-owner");
+        install_secret_gate(&mut state);
+        let (log, session_id) = attach_audit(&mut state);
+        {
+            let s = Arc::clone(&owner_secret);
+            blocking(move || s.set("api_key", "sk-live").unwrap()).await;
+        }
+
+        let (allowed, denied) = blocking(move || {
+            let mut s = state;
+            (
+                s.get_secret("api_key".to_string()),
+                s.get_secret("other_key".to_string()),
+            )
+        })
+        .await;
+        assert_eq!(allowed.unwrap(), "sk-live");
+        assert!(denied.is_err());
+
+        // No waiting: both entries exist as soon as the calls return.
+        let entries = log.get_session_entries(&session_id).unwrap();
+        let reads: Vec<_> = entries
+            .iter()
+            .map(|e| match &e.action {
+                AuditAction::SecretRead { capsule_id, name } => {
+                    (capsule_id.as_str(), name.as_str(), &e.outcome)
+                },
+                other => panic!("unexpected action: {other:?}"),
+            })
+            .collect();
+        assert!(matches!(
+            reads.as_slice(),
+            [
+                ("test", "api_key", AuditOutcome::Success { .. }),
+                ("test", "other_key", AuditOutcome::Failure { .. }),
+            ]
+        ));
+        assert!(matches!(
+            entries[1].authorization,
+            AuthorizationProof::Denied { .. }
+        ));
+        assert!(
+            entries
+                .iter()
+                .all(|e| !serde_json::to_string(e).unwrap().contains("sk-live"))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn get_secret_errors_when_unset_or_ungated() {
+        let rt = tokio::runtime::Handle::current();
+        let (state, _owner_secret) = make_host_state_with_secret(rt, "capsule:test-owner");
+
+        // No security gate installed: fail closed.
+        let (mut state, got) = blocking(move || {
+            let mut s = state;
+            let got = s.get_secret("api_key".to_string());
+            (s, got)
+        })
+        .await;
+        assert!(got.unwrap_err().contains("no security gate"));
+
+        // Declared but never set.
+        install_secret_gate(&mut state);
+        let got = blocking(move || {
+            let mut s = state;
+            s.get_secret("api_key".to_string())
+        })
+        .await;
+        assert!(got.unwrap_err().contains("is not set"));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn has_secret_falls_back_to_load_time_store() {
         // Regression guard: single-tenant path (no invocation store installed)
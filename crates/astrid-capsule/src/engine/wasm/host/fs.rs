@@ -2,9 +2,10 @@ use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 
 use crate::engine::wasm::bindings::astrid::capsule::fs;
-use crate::engine::wasm::bindings::astrid::capsule::types::FileStat;
+use crate::engine::wasm::bindings::astrid::capsule::types::{DirEntry, FileStat};
 use crate::engine::wasm::host::util;
 use crate::engine::wasm::host_state::HostState;
+use astrid_audit::{AuditAction, AuditOutcome, AuthorizationProof};
 
 /// URI scheme prefix for the principal's home directory.
 const HOME_SCHEME: &str = "home://";
@@ -15,6 +16,18 @@ const CWD_SCHEME: &str = "cwd://";
 /// Path prefix that maps to the principal's tmp directory.
 const TMP_PREFIX: &str = "/tmp/";
 
+/// Maximum number of files a capsule may hold open via `fs_open` at once.
+const MAX_OPEN_FILES: usize = 16;
+
+/// A file opened by the guest via `fs_open`.
+///
+/// Holds the VFS it was opened on so reads and the final close go to the
+/// same layer even after per-invocation mounts are swapped out.
+pub struct OpenFile {
+    vfs: Arc<dyn astrid_vfs::Vfs>,
+    handle: astrid_capabilities::FileHandle,
+}
+
 /// Strip any leading absolute slashes or prefixes (e.g. C:\) from the requested path
 fn make_relative(requested: &str) -> &Path {
     let path = Path::new(requested);
@@ -222,6 +235,41 @@ fn resolve_vfs(state: &HostState, resolved: &ResolvedPath) -> Result<ResolvedVfs
     })
 }
 
+/// Security-checked directory listing shared by `fs-readdir` and
+/// `fs-readdir-entries`.
+fn readdir(state: &HostState, path: &str) -> Result<Vec<astrid_vfs::VfsDirEntry>, String> {
+    let capsule_id = state.capsule_id.as_str().to_owned();
+
+    let resolved = resolve_path(state, path)?;
+
+    let security = state.security.clone();
+    if let Some(gate) = security {
+        let p = resolved.physical.to_string_lossy().to_string();
+        let pid = capsule_id.clone();
+        let home = state.effective_home_root_buf();
+        let check =
+            util::bounded_block_on(&state.runtime_handle, &state.host_semaphore, async move {
+                gate.check_file_read(&pid, &p, home.as_deref()).await
+            });
+        if let Err(reason) = check {
+            return Err(format!("security denied readdir: {reason}"));
+        }
+    }
+
+    let vfs_path = resolve_vfs(state, &resolved)?;
+
+    util::bounded_block_on(&state.runtime_handle, &state.host_semaphore, async {
+        vfs_path
+            .vfs
+            .readdir(
+                &vfs_path.handle,
+                vfs_path.relative.to_string_lossy().as_ref(),
+            )
+            .await
+    })
+    .map_err(|e| format!("readdir failed: {e}"))
+}
+
 impl fs::Host for HostState {
     fn fs_exists(&mut self, path: String) -> Result<bool, String> {
         let capsule_id = self.capsule_id.as_str().to_owned();
@@ -293,40 +341,21 @@ impl fs::Host for HostState {
     }
 
     fn fs_readdir(&mut self, path: String) -> Result<Vec<String>, String> {
-        let capsule_id = self.capsule_id.as_str().to_owned();
-
-        let resolved = resolve_path(self, &path)?;
-
-        let security = self.security.clone();
-        if let Some(gate) = security {
-            let p = resolved.physical.to_string_lossy().to_string();
-            let pid = capsule_id.clone();
-            let home = self.effective_home_root_buf();
-            let check =
-                util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async move {
-                    gate.check_file_read(&pid, &p, home.as_deref()).await
-                });
-            if let Err(reason) = check {
-                return Err(format!("security denied readdir: {reason}"));
-            }
-        }
-
-        let vfs_path = resolve_vfs(self, &resolved)?;
-
-        let entries = util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async {
-            vfs_path
-                .vfs
-                .readdir(
-                    &vfs_path.handle,
-                    vfs_path.relative.to_string_lossy().as_ref(),
-                )
-                .await
-        })
-        .map_err(|e| format!("readdir failed: {e}"))?;
-
+        let entries = readdir(self, &path)?;
         Ok(entries.into_iter().map(|e| e.name).collect())
     }
 
+    fn fs_readdir_entries(&mut self, path: String) -> Result<Vec<DirEntry>, String> {
+        let entries = readdir(self, &path)?;
+        Ok(entries
+            .into_iter()
+            .map(|e| DirEntry {
+                name: e.name,
+                is_dir: e.is_dir,
+            })
+            .collect())
+    }
+
     fn fs_stat(&mut self, path: String) -> Result<FileStat, String> {
         let capsule_id = self.capsule_id.as_str().to_owned();
 
@@ -473,7 +502,7 @@ impl fs::Host for HostState {
 
         let vfs_path = resolve_vfs(self, &resolved)?;
 
-        util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async {
+        let result = util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async {
             // Note: pass truncate=true to emulate standard write behavior
             let handle = vfs_path
                 .vfs
@@ -488,7 +517,260 @@ impl fs::Host for HostState {
             let _ = vfs_path.vfs.close(&handle).await;
             res
         })
-        .map_err(|e| format!("write_file failed: {e}"))
+        .map_err(|e| format!("write_file failed: {e}"));
+        self.audit_file_write(&resolved.physical, &content, result.as_ref().err());
+        result
+    }
+
+    fn fs_append(&mut self, path: String, content: Vec<u8>) -> Result<(), String> {
+        let capsule_id = self.capsule_id.as_str().to_owned();
+
+        let resolved = resolve_path(self, &path)?;
+
+        let security = self.security.clone();
+        if let Some(gate) = security {
+            let p = resolved.physical.to_string_lossy().to_string();
+            let pid = capsule_id.clone();
+            let home = self.effective_home_root_buf();
+            let check =
+                util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async move {
+                    gate.check_file_write(&pid, &p, home.as_deref()).await
+                });
+            if let Err(reason) = check {
+                return Err(format!("security denied fs_append: {reason}"));
+            }
+        }
+
+        let vfs_path = resolve_vfs(self, &resolved)?;
+
+        let result = util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async {
+            let handle = vfs_path
+                .vfs
+                .open_append(
+                    &vfs_path.handle,
+                    vfs_path.relative.to_string_lossy().as_ref(),
+                )
+                .await?;
+            let res = vfs_path.vfs.write(&handle, &content).await;
+            let _ = vfs_path.vfs.close(&handle).await;
+            res
+        })
+        .map_err(|e| format!("fs_append failed: {e}"));
+        self.audit_file_write(&resolved.physical, &content, result.as_ref().err());
+        result
+    }
+
+    fn fs_copy(&mut self, src: String, dst: String) -> Result<(), String> {
+        let capsule_id = self.capsule_id.as_str().to_owned();
+
+        let resolved_src = resolve_path(self, &src)?;
+        let resolved_dst = resolve_path(self, &dst)?;
+
+        let security = self.security.clone();
+        if let Some(gate) = security {
+            let src_p = resolved_src.physical.to_string_lossy().to_string();
+            let dst_p = resolved_dst.physical.to_string_lossy().to_string();
+            let pid = capsule_id.clone();
+            let home = self.effective_home_root_buf();
+            let check =
+                util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async move {
+                    gate.check_file_read(&pid, &src_p, home.as_deref()).await?;
+                    gate.check_file_write(&pid, &dst_p, home.as_deref()).await
+                });
+            if let Err(reason) = check {
+                return Err(format!("security denied fs_copy: {reason}"));
+            }
+        }
+
+        let src_path = resolve_vfs(self, &resolved_src)?;
+        let dst_path = resolve_vfs(self, &resolved_dst)?;
+        let src_rel = src_path.relative.to_string_lossy().into_owned();
+        let dst_rel = dst_path.relative.to_string_lossy().into_owned();
+
+        // The copied bytes are read up front so the write can be audited
+        // like any other, whichever way the copy is made.
+        let (content, result) =
+            util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async {
+                let handle = match src_path
+                    .vfs
+                    .open(&src_path.handle, &src_rel, false, false)
+                    .await
+                {
+                    Ok(handle) => handle,
+                    Err(e) => return (None, Err(e)),
+                };
+                let content = src_path.vfs.read(&handle).await;
+                let _ = src_path.vfs.close(&handle).await;
+                let content = match content {
+                    Ok(content) => content,
+                    Err(e) => return (None, Err(e)),
+                };
+
+                // Within one VFS, let it copy so its write semantics (overlay
+                // copy-on-write) apply. Across VFSes, write the bytes read.
+                if resolved_src.target == resolved_dst.target {
+                    let res = src_path
+                        .vfs
+                        .copy(&src_path.handle, &src_rel, &dst_rel)
+                        .await;
+                    return (Some(content), res);
+                }
+                let handle = match dst_path
+                    .vfs
+                    .open(&dst_path.handle, &dst_rel, true, true)
+                    .await
+                {
+                    Ok(handle) => handle,
+                    Err(e) => return (Some(content), Err(e)),
+                };
+                let res = dst_path.vfs.write(&handle, &content).await;
+                let _ = dst_path.vfs.close(&handle).await;
+                (Some(content), res)
+            });
+        let result = result.map_err(|e| format!("fs_copy failed: {e}"));
+        // Nothing reaches the destination if the source cannot be read.
+        if let Some(content) = content {
+            self.audit_file_write(&resolved_dst.physical, &content, result.as_ref().err());
+        }
+        result
+    }
+
+    fn fs_open(&mut self, path: String) -> Result<u64, String> {
+        let capsule_id = self.capsule_id.as_str().to_owned();
+
+        if self.open_files.len() >= MAX_OPEN_FILES {
+            return Err(format!(
+                "too many open files (limit {MAX_OPEN_FILES}); close a handle first"
+            ));
+        }
+
+        let resolved = resolve_path(self, &path)?;
+
+        let security = self.security.clone();
+        if let Some(gate) = security {
+            let p = resolved.physical.to_string_lossy().to_string();
+            let pid = capsule_id.clone();
+            let home = self.effective_home_root_buf();
+            let check =
+                util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async move {
+                    gate.check_file_read(&pid, &p, home.as_deref()).await
+                });
+            if let Err(reason) = check {
+                return Err(format!("security denied fs_open: {reason}"));
+            }
+        }
+
+        let vfs_path = resolve_vfs(self, &resolved)?;
+
+        let handle = util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async {
+            vfs_path
+                .vfs
+                .open(
+                    &vfs_path.handle,
+                    vfs_path.relative.to_string_lossy().as_ref(),
+                    false,
+                    false,
+                )
+                .await
+        })
+        .map_err(|e| format!("IO error: {e}"))?;
+
+        let id = self.next_file_handle;
+        self.next_file_handle = id.saturating_add(1);
+        self.open_files.insert(
+            id,
+            OpenFile {
+                vfs: vfs_path.vfs,
+                handle,
+            },
+        );
+        Ok(id)
+    }
+
+    fn fs_read_chunk(&mut self, handle: u64, offset: u64, len: u32) -> Result<Vec<u8>, String> {
+        if u64::from(len) > util::MAX_GUEST_PAYLOAD_LEN {
+            return Err(format!(
+                "chunk length {len} exceeds limit of {} bytes",
+                util::MAX_GUEST_PAYLOAD_LEN
+            ));
+        }
+        let file = self
+            .open_files
+            .get(&handle)
+            .ok_or_else(|| format!("unknown file handle {handle}"))?;
+        let len = usize::try_from(len).map_err(|e| e.to_string())?;
+
+        util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async {
+            file.vfs.read_at(&file.handle, offset, len).await
+        })
+        .map_err(|e| format!("IO error: {e}"))
+    }
+
+    fn fs_close(&mut self, handle: u64) -> Result<(), String> {
+        let file = self
+            .open_files
+            .remove(&handle)
+            .ok_or_else(|| format!("unknown file handle {handle}"))?;
+
+        util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async {
+            file.vfs.close(&file.handle).await
+        })
+        .map_err(|e| format!("IO error: {e}"))
+    }
+}
+
+impl HostState {
+    /// Record a guest write of `content` to `path` in the audit log.
+    ///
+    /// The entry carries the content's hash and size. Writes no larger than
+    /// [`seal_writes_up_to`](crate::context::CapsuleAudit::seal_writes_up_to)
+    /// also carry the content sealed to the audit log's runtime key, so an
+    /// investigator holding the reveal capability can recover it. The
+    /// plaintext is never stored. For appends, `content` is the appended
+    /// bytes.
+    fn audit_file_write(&self, path: &Path, content: &[u8], error: Option<&String>) {
+        let Some(audit) = &self.audit else {
+            return;
+        };
+        let size = u64::try_from(content.len()).unwrap_or(u64::MAX);
+        let seal_to = (size <= audit.seal_writes_up_to && audit.seal_writes_up_to > 0)
+            .then(|| audit.log.runtime_public_key());
+        let recorded = AuditAction::file_write(path.to_string_lossy(), content, seal_to.as_ref())
+            .and_then(|action| {
+                let outcome =
+                    error.map_or_else(AuditOutcome::success, |e| AuditOutcome::failure(e.clone()));
+                self.append_audit(
+                    action,
+                    AuthorizationProof::System {
+                        reason: format!("fs_write permitted for capsule {}", self.capsule_id),
+                    },
+                    outcome,
+                )
+            });
+        if let Err(e) = recorded {
+            tracing::warn!(
+                security_event = true,
+                capsule_id = %self.capsule_id,
+                error = %e,
+                "Failed to persist file-write audit entry"
+            );
+        }
+    }
+
+    /// Close every file the guest opened via `fs_open`.
+    ///
+    /// Called when an invocation ends so handles never outlive the call
+    /// that created them.
+    pub(crate) fn close_open_files(&mut self) {
+        if self.open_files.is_empty() {
+            return;
+        }
+        let files: Vec<OpenFile> = self.open_files.drain().map(|(_, f)| f).collect();
+        util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async {
+            for file in files {
+                let _ = file.vfs.close(&file.handle).await;
+            }
+        });
     }
 }
 
@@ -586,6 +868,8 @@ mod tests {
                 ipc_publish: vec![],
                 ipc_subscribe: vec![],
                 identity: vec![],
+                call: vec![],
+                secrets: vec![],
                 allow_prompt_injection: false,
             },
             env: Default::default(),
@@ -596,6 +880,8 @@ mod tests {
             uplinks: vec![],
             interceptors: vec![],
             topics: vec![],
+            cron_jobs: vec![],
+            health: Default::default(),
         }
     }
 
@@ -654,6 +940,7 @@ mod tests {
             config: HashMap::new(),
             ipc_publish_patterns: Vec::new(),
             ipc_subscribe_patterns: Vec::new(),
+            call_targets: Vec::new(),
             security: Some(gate),
             hook_manager: None,
             capsule_registry: None,
@@ -677,7 +964,11 @@ mod tests {
             identity_store: None,
             background_processes: HashMap::new(),
             next_process_id: 1,
+            open_files: HashMap::new(),
+            next_file_handle: 1,
             process_tracker: Arc::new(ProcessTracker::new()),
+            audit: None,
+            workspace_mode: astrid_workspace::WorkspaceMode::Safe,
         }
     }
 
@@ -828,4 +1119,326 @@ mod tests {
             "expected denial, got: {err}"
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn append_accumulates_across_calls() {
+        let tmp = tempfile::tempdir().unwrap();
+        let owner_root = tmp.path().join("home/capsule-owner");
+        std::fs::create_dir_all(&owner_root).unwrap();
+
+        let owner = astrid_core::PrincipalId::new("capsule-owner").unwrap();
+        let mut state = make_host_state(owner, &owner_root, tmp.path().to_path_buf()).await;
+
+        tokio::task::spawn_blocking(move || {
+            for i in 0..1000 {
+                state
+                    .fs_append("home://cron.log".into(), format!("run {i}\n").into_bytes())
+                    .expect("fs_append");
+            }
+        })
+        .await
+        .expect("join");
+
+        let log = std::fs::read_to_string(owner_root.join("cron.log")).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 1000);
+        assert_eq!(lines.first(), Some(&"run 0"));
+        assert_eq!(lines.last(), Some(&"run 999"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn copy_preserves_contents_and_checks_both_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let owner_root = tmp.path().join("home/capsule-owner");
+        std::fs::create_dir_all(&owner_root).unwrap();
+        std::fs::write(owner_root.join("src.bin"), b"\x00binary\xff").unwrap();
+
+        let owner = astrid_core::PrincipalId::new("capsule-owner").unwrap();
+        let mut state = make_host_state(owner, &owner_root, tmp.path().to_path_buf()).await;
+
+        let denied = tokio::task::spawn_blocking(move || {
+            state
+                .fs_copy("home://src.bin".into(), "home://copy.bin".into())
+                .expect("fs_copy");
+            // The manifest grants no workspace write access.
+            state.fs_copy("home://src.bin".into(), "copy.bin".into())
+        })
+        .await
+        .expect("join");
+
+        assert_eq!(
+            std::fs::read(owner_root.join("copy.bin")).unwrap(),
+            b"\x00binary\xff"
+        );
+        let err = denied.expect_err("workspace write must be denied");
+        assert!(err.contains("denied"), "expected denial, got: {err}");
+        assert!(!tmp.path().join("copy.bin").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn readdir_entries_report_entry_type() {
+        let tmp = tempfile::tempdir().unwrap();
+        let owner_root = tmp.path().join("home/capsule-owner");
+        std::fs::create_dir_all(owner_root.join("docs/sub")).unwrap();
+        std::fs::write(owner_root.join("docs/readme.md"), b"x").unwrap();
+
+        let owner = astrid_core::PrincipalId::new("capsule-owner").unwrap();
+        let mut state = make_host_state(owner, &owner_root, tmp.path().to_path_buf()).await;
+
+        let (names, mut entries) = tokio::task::spawn_blocking(move || {
+            (
+                state.fs_readdir("home://docs".into()).expect("fs_readdir"),
+                state
+                    .fs_readdir_entries("home://docs".into())
+                    .expect("fs_readdir_entries"),
+            )
+        })
+        .await
+        .expect("join");
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        let got: Vec<(&str, bool)> = entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.is_dir))
+            .collect();
+        assert_eq!(got, vec![("readme.md", false), ("sub", true)]);
+        assert_eq!(names.len(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn open_reads_large_file_in_chunks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let owner_root = tmp.path().join("home/capsule-owner");
+        std::fs::create_dir_all(&owner_root).unwrap();
+        // Larger than the 10 MB `read_file` limit, with a ragged final chunk.
+        let content: Vec<u8> = (0..12 * 1024 * 1024 + 17u32)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        std::fs::write(owner_root.join("big.log"), &content).unwrap();
+
+        let owner = astrid_core::PrincipalId::new("capsule-owner").unwrap();
+        let mut state = make_host_state(owner, &owner_root, tmp.path().to_path_buf()).await;
+
+        let (whole, reassembled, state) = tokio::task::spawn_blocking(move || {
+            let whole = state.read_file("home://big.log".into());
+            let fh = state.fs_open("home://big.log".into()).expect("fs_open");
+            let mut out = Vec::new();
+            loop {
+                let chunk = state
+                    .fs_read_chunk(fh, out.len() as u64, 1024 * 1024)
+                    .expect("fs_read_chunk");
+                if chunk.is_empty() {
+                    break;
+                }
+                out.extend_from_slice(&chunk);
+            }
+            state.fs_close(fh).expect("fs_close");
+            (whole, out, state)
+        })
+        .await
+        .expect("join");
+
+        assert!(whole.is_err(), "read_file must reject files over 10 MB");
+        assert_eq!(reassembled.len(), content.len());
+        assert!(reassembled == content, "reassembled bytes differ");
+        assert!(state.open_files.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn open_handles_are_capped_and_closed_at_invocation_end() {
+        let tmp = tempfile::tempdir().unwrap();
+        let owner_root = tmp.path().join("home/capsule-owner");
+        std::fs::create_dir_all(&owner_root).unwrap();
+        std::fs::write(owner_root.join("a.txt"), b"abc").unwrap();
+
+        let owner = astrid_core::PrincipalId::new("capsule-owner").unwrap();
+        let mut state = make_host_state(owner, &owner_root, tmp.path().to_path_buf()).await;
+
+        tokio::task::spawn_blocking(move || {
+            let handles: Vec<u64> = (0..super::MAX_OPEN_FILES)
+                .map(|_| state.fs_open("home://a.txt".into()).expect("fs_open"))
+                .collect();
+            let err = state
+                .fs_open("home://a.txt".into())
+                .expect_err("open beyond cap must fail");
+            assert!(err.contains("too many open files"), "got: {err}");
+
+            state.close_open_files();
+            assert!(state.open_files.is_empty());
+            let err = state
+                .fs_read_chunk(handles[0], 0, 3)
+                .expect_err("handle must be invalid after invocation end");
+            assert!(err.contains("unknown file handle"), "got: {err}");
+            assert!(state.fs_close(handles[0]).is_err());
+
+            // Fresh handles are never reused.
+            let fresh = state.fs_open("home://a.txt".into()).expect("fs_open");
+            assert!(!handles.contains(&fresh));
+            assert_eq!(state.fs_read_chunk(fresh, 1, 10).expect("read"), b"bc");
+        })
+        .await
+        .expect("join");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn file_writes_under_the_limit_are_audited_with_sealed_content() {
+        use astrid_audit::{AuditAction, AuditLog, REVEAL_CAPABILITY};
+        use astrid_crypto::{ContentHash, KeyPair};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let owner_root = tmp.path().join("home/capsule-owner");
+        std::fs::create_dir_all(&owner_root).unwrap();
+        let owner = astrid_core::PrincipalId::new("capsule-owner").unwrap();
+        let mut state = make_host_state(owner.clone(), &owner_root, tmp.path().to_path_buf()).await;
+
+        let audit_key = KeyPair::generate();
+        let log = Arc::new(AuditLog::in_memory(
+            KeyPair::from_secret_key(&audit_key.secret_key_bytes()).unwrap(),
+        ));
+        let session_id = astrid_core::SessionId::new();
+        state.audit = Some(crate::context::CapsuleAudit {
+            log: Arc::clone(&log),
+            session_id: session_id.clone(),
+            seal_writes_up_to: 64,
+        });
+
+        let content = b"TOKEN=sk-live-1234";
+        let (_state, read) = write_then_read(state, "home://.env", content).await;
+        assert_eq!(read.expect("read ok"), content);
+
+        let entries = log.get_session_entries(&session_id).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].principal.as_ref(), Some(&owner));
+        let AuditAction::FileWrite {
+            path,
+            content_hash,
+            size,
+            sealed_content,
+        } = &entries[0].action
+        else {
+            panic!("expected a file write, got {:?}", entries[0].action);
+        };
+        assert!(path.ends_with(".env"), "path: {path}");
+        assert_eq!(*content_hash, ContentHash::hash(content));
+        assert_eq!(*size, Some(18));
+        assert!(sealed_content.is_some(), "content must be sealed");
+
+        let profile = astrid_core::profile::PrincipalProfile {
+            grants: vec![REVEAL_CAPABILITY.to_string()],
+            ..Default::default()
+        };
+        let groups = astrid_core::groups::GroupConfig::builtin_only();
+        let investigator = astrid_core::PrincipalId::new("investigator").unwrap();
+        let check = astrid_capabilities::CapabilityCheck::new(&profile, &groups, investigator);
+        assert_eq!(
+            log.reveal(&entries[0].id, &audit_key, &check).unwrap(),
+            content
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn file_writes_over_the_limit_record_only_hash_and_size() {
+        use astrid_audit::{AuditAction, AuditLog};
+        use astrid_crypto::{ContentHash, KeyPair};
+
+        for limit in [0, 8] {
+            let tmp = tempfile::tempdir().unwrap();
+            let owner_root = tmp.path().join("home/capsule-owner");
+            std::fs::create_dir_all(&owner_root).unwrap();
+            let owner = astrid_core::PrincipalId::new("capsule-owner").unwrap();
+            let mut state = make_host_state(owner, &owner_root, tmp.path().to_path_buf()).await;
+            let log = Arc::new(AuditLog::in_memory(KeyPair::generate()));
+            let session_id = astrid_core::SessionId::new();
+            state.audit = Some(crate::context::CapsuleAudit {
+                log: Arc::clone(&log),
+                session_id: session_id.clone(),
+                seal_writes_up_to: limit,
+            });
+
+            let content = b"TOKEN=sk-live-1234";
+            let (_state, read) = write_then_read(state, "home://.env", content).await;
+            assert_eq!(read.expect("read ok"), content);
+
+            let entries = log.get_session_entries(&session_id).unwrap();
+            let AuditAction::FileWrite {
+                content_hash,
+                size,
+                sealed_content,
+                ..
+            } = &entries[0].action
+            else {
+                panic!("expected a file write, got {:?}", entries[0].action);
+            };
+            assert_eq!(*content_hash, ContentHash::hash(content));
+            assert_eq!(*size, Some(18));
+            assert!(sealed_content.is_none(), "limit {limit} must not seal");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn copies_are_audited_as_writes_to_the_destination() {
+        use astrid_audit::{AuditAction, AuditLog, REVEAL_CAPABILITY};
+        use astrid_crypto::{ContentHash, KeyPair};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let owner_root = tmp.path().join("home/capsule-owner");
+        std::fs::create_dir_all(&owner_root).unwrap();
+        let content = b"\x00binary\xff";
+        std::fs::write(owner_root.join("src.bin"), content).unwrap();
+        let owner = astrid_core::PrincipalId::new("capsule-owner").unwrap();
+        let mut state = make_host_state(owner, &owner_root, tmp.path().to_path_buf()).await;
+
+        let audit_key = KeyPair::generate();
+        let log = Arc::new(AuditLog::in_memory(
+            KeyPair::from_secret_key(&audit_key.secret_key_bytes()).unwrap(),
+        ));
+        let session_id = astrid_core::SessionId::new();
+        state.audit = Some(crate::context::CapsuleAudit {
+            log: Arc::clone(&log),
+            session_id: session_id.clone(),
+            seal_writes_up_to: 64,
+        });
+
+        tokio::task::spawn_blocking(move || {
+            state
+                .fs_copy("home://src.bin".into(), "home://copy.bin".into())
+                .expect("fs_copy");
+            // A missing source writes nothing, so nothing is audited.
+            assert!(
+                state
+                    .fs_copy("home://missing.bin".into(), "home://other.bin".into())
+                    .is_err()
+            );
+        })
+        .await
+        .expect("join");
+
+        let entries = log.get_session_entries(&session_id).unwrap();
+        assert_eq!(entries.len(), 1);
+        let AuditAction::FileWrite {
+            path,
+            content_hash,
+            size,
+            sealed_content,
+        } = &entries[0].action
+        else {
+            panic!("expected a file write, got {:?}", entries[0].action);
+        };
+        assert!(path.ends_with("copy.bin"), "path: {path}");
+        assert_eq!(*content_hash, ContentHash::hash(content));
+        assert_eq!(*size, Some(8));
+        assert!(sealed_content.is_some(), "content must be sealed");
+
+        let profile = astrid_core::profile::PrincipalProfile {
+            grants: vec![REVEAL_CAPABILITY.to_string()],
+            ..Default::default()
+        };
+        let groups = astrid_core::groups::GroupConfig::builtin_only();
+        let investigator = astrid_core::PrincipalId::new("investigator").unwrap();
+        let check = astrid_capabilities::CapabilityCheck::new(&profile, &groups, investigator);
+        assert_eq!(
+            log.reveal(&entries[0].id, &audit_key, &check).unwrap(),
+            content
+        );
+    }
 }
@@ -3,8 +3,12 @@ use crate::engine::wasm::bindings::astrid::capsule::types::{
     InterceptorHandle as WitInterceptorHandle, IpcEnvelope as WitIpcEnvelope,
     IpcMessage as WitIpcMessage,
 };
+use std::sync::Arc;
+
+use crate::capsule::{CapsuleId, InterceptResult};
 use crate::engine::wasm::host::util;
 use crate::engine::wasm::host_state::HostState;
+use crate::registry::CapsuleRegistry;
 use astrid_events::AstridEvent;
 use astrid_events::EventMetadata;
 use astrid_events::EventReceiver;
@@ -113,6 +117,23 @@ fn drain_to_wit_envelope(drain: &DrainResult) -> WitIpcEnvelope {
     }
 }
 
+/// Deserialize a guest-published payload into an [`IpcPayload`], falling
+/// back to `Custom` for unrecognised or missing type tags (see
+/// [`IpcPayload::from_json_value`] for the rationale behind the pre-check).
+///
+/// Only the kernel assesses approval risk, so a guest-published
+/// `ApprovalRequired` has its `risk` cleared.
+pub(crate) fn parse_guest_payload(payload_bytes: &[u8]) -> Result<IpcPayload, String> {
+    let mut payload = match serde_json::from_slice::<serde_json::Value>(payload_bytes) {
+        Ok(data) => IpcPayload::from_json_value(data),
+        Err(_) => return Err("IPC payload is not valid JSON".to_string()),
+    };
+    if let IpcPayload::ApprovalRequired { risk, .. } = &mut payload {
+        *risk = None;
+    }
+    Ok(payload)
+}
+
 /// Remove a subscription by handle ID, rejecting runtime-owned interceptor handles.
 ///
 /// Returns `Err` if the handle is protected (auto-subscribed interceptor) or
@@ -137,6 +158,225 @@ pub(crate) fn remove_subscription(
     Ok(())
 }
 
+/// Maximum nesting of `ipc-call` invocations (`a -> b -> c -> d -> e`).
+pub(crate) const MAX_CALL_DEPTH: usize = 4;
+
+/// How long `ipc-call` waits for the target capsule before giving up.
+pub(crate) const CALL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Maximum length of an `ipc-call` export name.
+const MAX_CALL_EXPORT_LEN: usize = 256;
+
+/// `ipc-call`s in progress between the capsules of one registry, as
+/// `(caller, target)` edges: the caller holds its own store while it waits
+/// on the target. Owned by the [`CapsuleRegistry`].
+#[derive(Default)]
+pub(crate) struct InFlightCalls {
+    edges: std::sync::Mutex<Vec<(CapsuleId, CapsuleId)>>,
+}
+
+impl InFlightCalls {
+    fn edges(&self) -> std::sync::MutexGuard<'_, Vec<(CapsuleId, CapsuleId)>> {
+        self.edges
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// An edge in [`InFlightCalls`], removed when dropped.
+struct InFlightCall {
+    calls: Arc<InFlightCalls>,
+    caller: CapsuleId,
+    target: CapsuleId,
+}
+
+impl InFlightCall {
+    /// Record that `caller` waits on `target`, unless `target` is already
+    /// waiting on `caller` through some other chain of calls - two capsules
+    /// calling each other at once would each hold the store the other needs.
+    fn register(
+        calls: &Arc<InFlightCalls>,
+        caller: &CapsuleId,
+        target: &CapsuleId,
+    ) -> Result<Self, String> {
+        let mut edges = calls.edges();
+        if let Some(path) = wait_path(&edges, target, caller) {
+            let path: Vec<&str> = path.iter().map(CapsuleId::as_str).collect();
+            return Err(format!(
+                "call cycle: {caller} -> {} would deadlock",
+                path.join(" -> ")
+            ));
+        }
+        edges.push((caller.clone(), target.clone()));
+        Ok(Self {
+            calls: Arc::clone(calls),
+            caller: caller.clone(),
+            target: target.clone(),
+        })
+    }
+}
+
+impl Drop for InFlightCall {
+    fn drop(&mut self) {
+        let mut edges = self.calls.edges();
+        if let Some(i) = edges
+            .iter()
+            .position(|(c, t)| *c == self.caller && *t == self.target)
+        {
+            edges.swap_remove(i);
+        }
+    }
+}
+
+/// The capsules `from` waits on, transitively, ending at `to`, or `None` if
+/// `from` does not wait on `to`.
+fn wait_path(
+    edges: &[(CapsuleId, CapsuleId)],
+    from: &CapsuleId,
+    to: &CapsuleId,
+) -> Option<Vec<CapsuleId>> {
+    let mut stack = vec![vec![from.clone()]];
+    let mut seen = vec![from.clone()];
+    while let Some(path) = stack.pop() {
+        let last = path.last()?;
+        for (_, next) in edges.iter().filter(|(c, _)| c == last) {
+            let mut extended = path.clone();
+            extended.push(next.clone());
+            if next == to {
+                return Some(extended);
+            }
+            if !seen.contains(next) {
+                seen.push(next.clone());
+                stack.push(extended);
+            }
+        }
+    }
+    None
+}
+
+/// Check whether `capsule_id` may directly call `target` under its declared
+/// `call` capability. Empty means deny all; `"*"` allows any target.
+pub(crate) fn check_call_acl(capsule_id: &str, target: &str, acl: &[String]) -> Result<(), String> {
+    if acl.is_empty() {
+        return Err(format!(
+            "Capsule '{capsule_id}' has no call declarations - direct calls are \
+             denied. Add call targets to Capsule.toml [capabilities]"
+        ));
+    }
+    if !acl.iter().any(|t| t == "*" || t == target) {
+        return Err(format!(
+            "Capsule '{capsule_id}' is not allowed to call '{target}' - \
+             declared call targets: {acl:?}"
+        ));
+    }
+    Ok(())
+}
+
+/// The call chain after `caller` calls `target`, given the chain carried by
+/// the message that triggered the caller.
+///
+/// Refuses calls back into a capsule already in the chain - its store is
+/// locked by the outer call, so the inner call would deadlock - and chains
+/// deeper than [`MAX_CALL_DEPTH`].
+pub(crate) fn extend_call_chain(
+    chain: &[CapsuleId],
+    caller: &CapsuleId,
+    target: &CapsuleId,
+) -> Result<Vec<CapsuleId>, String> {
+    let mut next = chain.to_vec();
+    if next.last() != Some(caller) {
+        next.push(caller.clone());
+    }
+    if next.contains(target) {
+        let path: Vec<&str> = next.iter().map(CapsuleId::as_str).collect();
+        return Err(format!(
+            "call cycle: {} -> {target} would deadlock",
+            path.join(" -> ")
+        ));
+    }
+    next.push(target.clone());
+    if next.len().saturating_sub(1) > MAX_CALL_DEPTH {
+        return Err(format!(
+            "call depth limit ({MAX_CALL_DEPTH}) exceeded calling '{target}'"
+        ));
+    }
+    Ok(next)
+}
+
+/// Synchronously invoke `export` on another loaded capsule.
+///
+/// `caller_message` is the message that triggered the caller; the target
+/// sees it with the extended call chain attached. The target runs on a
+/// blocking thread and the caller waits at most `timeout` for it, after
+/// which the target's guest call is interrupted. Returns the target's
+/// response payload. The capability check is the caller's job
+/// ([`check_call_acl`]).
+#[expect(clippy::too_many_arguments)]
+pub(crate) fn call_capsule(
+    registry: &Arc<tokio::sync::RwLock<CapsuleRegistry>>,
+    in_flight: &Arc<InFlightCalls>,
+    rt_handle: &tokio::runtime::Handle,
+    host_semaphore: &tokio::sync::Semaphore,
+    caller: &CapsuleId,
+    target: &str,
+    export: &str,
+    payload: Vec<u8>,
+    caller_message: IpcMessage,
+    timeout: std::time::Duration,
+) -> Result<Vec<u8>, String> {
+    if export.is_empty() || export.len() > MAX_CALL_EXPORT_LEN {
+        return Err(format!("export name must be 1-{MAX_CALL_EXPORT_LEN} bytes"));
+    }
+    let target_id = CapsuleId::new(target).map_err(|e| e.to_string())?;
+    let chain = caller_message
+        .call_chain
+        .iter()
+        .map(|id| CapsuleId::new(id.as_str()).map_err(|e| format!("bad call chain: {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let chain = extend_call_chain(&chain, caller, &target_id)?;
+    let _in_flight = InFlightCall::register(in_flight, caller, &target_id)?;
+
+    let capsule = util::bounded_block_on(rt_handle, host_semaphore, async {
+        registry.read().await.get(&target_id)
+    })
+    .ok_or_else(|| format!("capsule '{target}' is not loaded"))?;
+    if !matches!(capsule.state(), crate::capsule::CapsuleState::Ready) {
+        return Err(format!("capsule '{target}' is not ready"));
+    }
+
+    let message = caller_message.with_call_chain(chain.iter().map(ToString::to_string).collect());
+    let export_name = export.to_string();
+    let cancel = tokio_util::sync::CancellationToken::new();
+    let task = rt_handle.spawn_blocking({
+        let cancel = cancel.clone();
+        move || {
+            capsule.invoke_interceptor_cancellable(&export_name, &payload, Some(&message), &cancel)
+        }
+    });
+    // No host permit is held while waiting: nested calls would otherwise
+    // each pin one and could exhaust the pool.
+    let outcome =
+        tokio::task::block_in_place(|| rt_handle.block_on(tokio::time::timeout(timeout, task)));
+
+    match outcome {
+        Err(_) => {
+            // Interrupt the target so it releases its store instead of
+            // running on after its caller gave up.
+            cancel.cancel();
+            Err(format!(
+                "call to '{target}' timed out after {}s",
+                timeout.as_secs()
+            ))
+        },
+        Ok(Err(e)) => Err(format!("call to '{target}' failed: {e}")),
+        Ok(Ok(Err(e))) => Err(format!("call to '{target}' failed: {e}")),
+        Ok(Ok(Ok(InterceptResult::Continue(bytes) | InterceptResult::Final(bytes)))) => Ok(bytes),
+        Ok(Ok(Ok(InterceptResult::Deny { reason }))) => {
+            Err(format!("'{target}' denied the call: {reason}"))
+        },
+    }
+}
+
 /// Maximum timeout for blocking IPC receive (60 seconds).
 const MAX_RECV_TIMEOUT_MS: u64 = 60_000;
 
@@ -206,13 +446,7 @@ impl ipc::Host for HostState {
             ));
         }
 
-        // Deserialize the guest payload into an IpcPayload, falling back to
-        // Custom for unrecognised or missing type tags.  See IpcPayload::from_json_value
-        // for the rationale behind the pre-check.
-        let ipc_payload = match serde_json::from_slice::<serde_json::Value>(payload_bytes) {
-            Ok(data) => IpcPayload::from_json_value(data),
-            Err(_) => return Err("IPC payload is not valid JSON".to_string()),
-        };
+        let ipc_payload = parse_guest_payload(payload_bytes)?;
 
         // Propagate the principal to the outgoing message. Capsules never
         // touch the principal — it's invisible. Two cases:
@@ -377,6 +611,50 @@ impl ipc::Host for HostState {
         Ok(drain_to_wit_envelope(&drain))
     }
 
+    fn ipc_call(
+        &mut self,
+        target: String,
+        export_name: String,
+        payload: String,
+    ) -> Result<String, String> {
+        check_call_acl(self.capsule_id.as_str(), &target, &self.call_targets)?;
+        if payload.len() as u64 > util::MAX_GUEST_PAYLOAD_LEN {
+            return Err("Payload too large".to_string());
+        }
+        let registry = self
+            .capsule_registry
+            .clone()
+            .ok_or_else(|| "direct calls are unavailable: no capsule registry".to_string())?;
+
+        // Calls made outside an IPC-triggered invocation still carry a
+        // message, so the target sees who called and the call chain.
+        let caller_message = self.caller_context.clone().unwrap_or_else(|| {
+            IpcMessage::new(
+                format!("{target}.{export_name}"),
+                IpcPayload::RawJson(serde_json::Value::Null),
+                self.capsule_uuid,
+            )
+            .with_principal(self.principal.to_string())
+        });
+
+        let in_flight = util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async {
+            registry.read().await.in_flight_calls()
+        });
+        let response = call_capsule(
+            &registry,
+            &in_flight,
+            &self.runtime_handle,
+            &self.host_semaphore,
+            &self.capsule_id,
+            &target,
+            &export_name,
+            payload.into_bytes(),
+            caller_message,
+            CALL_TIMEOUT,
+        )?;
+        String::from_utf8(response).map_err(|_| format!("'{target}' returned a non-UTF-8 response"))
+    }
+
     /// Return the pre-registered interceptor handle mappings for run-loop capsules.
     ///
     /// Called by the WASM guest at startup to discover which IPC subscription
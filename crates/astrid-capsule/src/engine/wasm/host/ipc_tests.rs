@@ -170,12 +170,10 @@ async fn recv_blocking_wake_plus_drain_burst() {
     );
 }
 
-/// Thin wrapper around the production deserialization path so we can
-/// test it without a full WASM plugin context.
+/// The production deserialization path, tested without a full WASM plugin
+/// context.
 fn deserialize_publish_payload(payload_bytes: &[u8]) -> Result<IpcPayload, String> {
-    serde_json::from_slice::<serde_json::Value>(payload_bytes)
-        .map(IpcPayload::from_json_value)
-        .map_err(|_| "IPC payload is not valid JSON".into())
+    parse_guest_payload(payload_bytes)
 }
 
 #[test]
@@ -262,6 +260,25 @@ fn publish_custom_tag_with_data_unwraps_inner_value() {
     }
 }
 
+#[test]
+fn publish_approval_request_cannot_claim_a_risk() {
+    let input = serde_json::json!({
+        "type": "approval_required",
+        "request_id": "r1",
+        "action": "file_read",
+        "resource": "/etc/shadow",
+        "reason": "trust me",
+        "risk": "low",
+    });
+    let bytes = serde_json::to_vec(&input).unwrap();
+    let payload = deserialize_publish_payload(&bytes).unwrap();
+
+    match payload {
+        IpcPayload::ApprovalRequired { risk, .. } => assert_eq!(risk, None),
+        other => panic!("expected ApprovalRequired, got {other:?}"),
+    }
+}
+
 #[test]
 fn publish_invalid_json_is_error() {
     let result = deserialize_publish_payload(b"not json at all");
@@ -440,3 +457,439 @@ fn subscribe_acl_malformed_pattern_silently_denies() {
         "malformed ACL pattern must not allow subscriptions"
     );
 }
+
+// ── Direct capsule calls ────────────────────────────────────────────
+
+mod direct_call {
+    use super::*;
+
+    use std::path::Path;
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use tokio::sync::{RwLock, Semaphore};
+    use tokio_util::sync::CancellationToken;
+
+    use crate::capsule::{Capsule, CapsuleState};
+    use crate::context::CapsuleContext;
+    use crate::error::CapsuleResult;
+    use crate::manifest::{CapabilitiesDef, CapsuleManifest, PackageDef};
+
+    type Handler = Box<
+        dyn Fn(&[u8], Option<&IpcMessage>, &CancellationToken) -> CapsuleResult<InterceptResult>
+            + Send
+            + Sync,
+    >;
+
+    /// A capsule whose interceptor runs `handler` for every export.
+    struct ScriptedCapsule {
+        id: CapsuleId,
+        manifest: CapsuleManifest,
+        handler: Handler,
+        semaphore: Arc<Semaphore>,
+    }
+
+    impl ScriptedCapsule {
+        fn boxed(name: &str, handler: Handler) -> Box<dyn Capsule> {
+            Box::new(Self {
+                id: CapsuleId::from_static(name),
+                manifest: CapsuleManifest {
+                    package: PackageDef {
+                        name: name.to_string(),
+                        version: "0.0.1".to_string(),
+                        description: None,
+                        authors: Vec::new(),
+                        repository: None,
+                        homepage: None,
+                        documentation: None,
+                        license: None,
+                        license_file: None,
+                        readme: None,
+                        keywords: Vec::new(),
+                        categories: Vec::new(),
+                        astrid_version: None,
+                        publish: None,
+                        include: None,
+                        exclude: None,
+                        metadata: None,
+                    },
+                    components: Vec::new(),
+                    imports: std::collections::HashMap::new(),
+                    exports: std::collections::HashMap::new(),
+                    capabilities: CapabilitiesDef::default(),
+                    env: std::collections::HashMap::new(),
+                    context_files: Vec::new(),
+                    commands: Vec::new(),
+                    mcp_servers: Vec::new(),
+                    skills: Vec::new(),
+                    uplinks: Vec::new(),
+                    interceptors: Vec::new(),
+                    topics: Vec::new(),
+                    cron_jobs: Vec::new(),
+                    health: Default::default(),
+                },
+                handler,
+                semaphore: Arc::new(Semaphore::new(4)),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Capsule for ScriptedCapsule {
+        fn id(&self) -> &CapsuleId {
+            &self.id
+        }
+        fn manifest(&self) -> &CapsuleManifest {
+            &self.manifest
+        }
+        fn state(&self) -> CapsuleState {
+            CapsuleState::Ready
+        }
+        async fn load(&mut self, _ctx: &CapsuleContext) -> CapsuleResult<()> {
+            Ok(())
+        }
+        async fn unload(&mut self) -> CapsuleResult<()> {
+            Ok(())
+        }
+        fn invoke_interceptor(
+            &self,
+            _action: &str,
+            payload: &[u8],
+            caller: Option<&IpcMessage>,
+        ) -> CapsuleResult<InterceptResult> {
+            (self.handler)(payload, caller, &CancellationToken::new())
+        }
+        fn invoke_interceptor_cancellable(
+            &self,
+            _action: &str,
+            payload: &[u8],
+            caller: Option<&IpcMessage>,
+            cancel: &CancellationToken,
+        ) -> CapsuleResult<InterceptResult> {
+            (self.handler)(payload, caller, cancel)
+        }
+        fn source_dir(&self) -> Option<&Path> {
+            None
+        }
+        fn interceptor_semaphore(&self) -> &Arc<Semaphore> {
+            &self.semaphore
+        }
+    }
+
+    type Registry = Arc<RwLock<CapsuleRegistry>>;
+
+    fn call(
+        registry: &Registry,
+        caller: &str,
+        target: &str,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, String> {
+        call_from(registry, caller, target, None, timeout)
+    }
+
+    /// Call `target` from a capsule that was itself triggered by `message`.
+    fn call_from(
+        registry: &Registry,
+        caller: &str,
+        target: &str,
+        message: Option<&IpcMessage>,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, String> {
+        let message = message.cloned().unwrap_or_else(|| {
+            IpcMessage::new(
+                "test.call",
+                IpcPayload::RawJson(serde_json::Value::Null),
+                uuid::Uuid::nil(),
+            )
+        });
+        let in_flight = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current()
+                .block_on(async { registry.read().await.in_flight_calls() })
+        });
+        call_capsule(
+            registry,
+            &in_flight,
+            &tokio::runtime::Handle::current(),
+            &Semaphore::new(2),
+            &CapsuleId::from_static(caller),
+            target,
+            "handle",
+            b"{}".to_vec(),
+            message,
+            timeout,
+        )
+    }
+
+    /// A handler that calls `target` as `me` and returns the outcome as text.
+    fn forwarding(registry: &Registry, me: &'static str, target: &'static str) -> Handler {
+        let registry = Arc::clone(registry);
+        Box::new(move |_, message, _| {
+            let text = match call_from(&registry, me, target, message, Duration::from_secs(5)) {
+                Ok(bytes) => format!("{me}>{}", String::from_utf8_lossy(&bytes)),
+                Err(e) => format!("{me}!{e}"),
+            };
+            Ok(InterceptResult::Final(text.into_bytes()))
+        })
+    }
+
+    async fn register(registry: &Registry, capsule: Box<dyn Capsule>) {
+        registry.write().await.register(capsule).unwrap();
+    }
+
+    #[test]
+    fn call_acl_is_fail_closed() {
+        assert!(check_call_acl("a", "b", &[]).is_err());
+        assert!(check_call_acl("a", "b", &["c".into()]).is_err());
+        assert!(check_call_acl("a", "b", &["c".into(), "b".into()]).is_ok());
+        assert!(check_call_acl("a", "b", &["*".into()]).is_ok());
+    }
+
+    #[test]
+    fn call_chain_refuses_cycles_and_deep_nesting() {
+        let id = CapsuleId::from_static;
+        assert_eq!(
+            extend_call_chain(&[], &id("a"), &id("b")).unwrap(),
+            vec![id("a"), id("b")]
+        );
+        assert!(extend_call_chain(&[], &id("a"), &id("a")).is_err());
+        let chain = [id("a"), id("b")];
+        let err = extend_call_chain(&chain, &id("b"), &id("a")).unwrap_err();
+        assert!(err.contains("a -> b -> a"), "{err}");
+
+        let mut chain = vec![id("c0")];
+        for i in 1..=MAX_CALL_DEPTH {
+            let next = CapsuleId::new(format!("c{i}")).unwrap();
+            let caller = chain.last().unwrap().clone();
+            chain = extend_call_chain(&chain, &caller, &next).unwrap();
+        }
+        let caller = chain.last().unwrap().clone();
+        let err = extend_call_chain(&chain, &caller, &id("too-deep")).unwrap_err();
+        assert!(err.contains("depth limit"), "{err}");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn nested_call_returns_target_response() {
+        let registry: Registry = Arc::default();
+        register(
+            &registry,
+            ScriptedCapsule::boxed("a", forwarding(&registry, "a", "b")),
+        )
+        .await;
+        register(
+            &registry,
+            ScriptedCapsule::boxed(
+                "b",
+                Box::new(|p, _, _| Ok(InterceptResult::Final(p.to_vec()))),
+            ),
+        )
+        .await;
+
+        let out = call(&registry, "router", "a", Duration::from_secs(5)).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "a>{}");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn mutually_calling_capsules_do_not_deadlock() {
+        let registry: Registry = Arc::default();
+        register(
+            &registry,
+            ScriptedCapsule::boxed("a", forwarding(&registry, "a", "b")),
+        )
+        .await;
+        register(
+            &registry,
+            ScriptedCapsule::boxed("b", forwarding(&registry, "b", "a")),
+        )
+        .await;
+
+        // router -> a -> b -> a: the call back into `a` is refused, so `b`
+        // reports the error and the chain unwinds instead of hanging.
+        let out = tokio::time::timeout(
+            Duration::from_secs(10),
+            tokio::task::spawn_blocking({
+                let registry = Arc::clone(&registry);
+                move || call(&registry, "router", "a", Duration::from_secs(5))
+            }),
+        )
+        .await
+        .expect("mutual calls deadlocked")
+        .unwrap()
+        .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(
+            out.starts_with("a>b!call cycle: router -> a -> b -> a"),
+            "{out}"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn chain_past_depth_limit_is_refused() {
+        let registry: Registry = Arc::default();
+        let names = ["c1", "c2", "c3", "c4", "c5"];
+        for pair in names.windows(2) {
+            register(
+                &registry,
+                ScriptedCapsule::boxed(pair[0], forwarding(&registry, pair[0], pair[1])),
+            )
+            .await;
+        }
+        register(
+            &registry,
+            ScriptedCapsule::boxed(
+                "c5",
+                Box::new(|_, _, _| Ok(InterceptResult::Final(b"end".to_vec()))),
+            ),
+        )
+        .await;
+
+        // router -> c1 -> c2 -> c3 -> c4 is four calls; c4 -> c5 is the fifth.
+        let out = call(&registry, "router", "c1", Duration::from_secs(5)).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("c1>c2>c3>c4!call depth limit"), "{out}");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn slow_target_times_out() {
+        let registry: Registry = Arc::default();
+        register(
+            &registry,
+            ScriptedCapsule::boxed(
+                "slow",
+                Box::new(|_, _, _| {
+                    std::thread::sleep(Duration::from_millis(500));
+                    Ok(InterceptResult::Final(Vec::new()))
+                }),
+            ),
+        )
+        .await;
+
+        let err = call(&registry, "router", "slow", Duration::from_millis(50)).unwrap_err();
+        assert!(err.contains("timed out"), "{err}");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn timed_out_target_is_cancelled() {
+        let registry: Registry = Arc::default();
+        let released = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = Arc::clone(&released);
+        register(
+            &registry,
+            ScriptedCapsule::boxed(
+                "stuck",
+                Box::new(move |_, _, cancel| {
+                    let give_up = std::time::Instant::now() + Duration::from_secs(5);
+                    while !cancel.is_cancelled() && std::time::Instant::now() < give_up {
+                        std::thread::sleep(Duration::from_millis(5));
+                    }
+                    flag.store(cancel.is_cancelled(), std::sync::atomic::Ordering::SeqCst);
+                    Ok(InterceptResult::Final(Vec::new()))
+                }),
+            ),
+        )
+        .await;
+
+        let err = call(&registry, "router", "stuck", Duration::from_millis(50)).unwrap_err();
+        assert!(err.contains("timed out"), "{err}");
+        for _ in 0..200 {
+            if released.load(std::sync::atomic::Ordering::SeqCst) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("target was not cancelled after its caller timed out");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn call_chain_travels_in_the_message() {
+        let registry: Registry = Arc::default();
+        register(
+            &registry,
+            ScriptedCapsule::boxed("a", forwarding(&registry, "a", "b")),
+        )
+        .await;
+        register(
+            &registry,
+            ScriptedCapsule::boxed(
+                "b",
+                Box::new(|_, message, _| {
+                    let chain = message.map(|m| m.call_chain.join(",")).unwrap_or_default();
+                    Ok(InterceptResult::Final(chain.into_bytes()))
+                }),
+            ),
+        )
+        .await;
+
+        let out = call(&registry, "router", "a", Duration::from_secs(5)).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "a>router,a,b");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn racing_mutual_calls_are_refused() {
+        let registry: Registry = Arc::default();
+        let barrier = Arc::new(std::sync::Barrier::new(2));
+        for (me, other) in [("a", "b"), ("b", "a")] {
+            let calls = Arc::clone(&registry);
+            let barrier = Arc::clone(&barrier);
+            let handler: Handler = Box::new(move |_, message, _| {
+                let outer = message.is_some_and(|m| m.call_chain.len() == 2);
+                if !outer {
+                    // Hold the call open so the other side's call overlaps it.
+                    std::thread::sleep(Duration::from_millis(200));
+                    return Ok(InterceptResult::Final(b"pong".to_vec()));
+                }
+                barrier.wait();
+                let text = match call_from(&calls, me, other, message, Duration::from_secs(5)) {
+                    Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                    Err(e) => e,
+                };
+                Ok(InterceptResult::Final(text.into_bytes()))
+            });
+            register(&registry, ScriptedCapsule::boxed(me, handler)).await;
+        }
+
+        // router -> a -> b and router -> b -> a start together; `a` and `b`
+        // each hold their own store, so one of the two calls must be refused.
+        let spawn = |target: &'static str| {
+            let registry = Arc::clone(&registry);
+            tokio::task::spawn_blocking(move || {
+                call(&registry, "router", target, Duration::from_secs(5))
+            })
+        };
+        let (a, b) = (spawn("a"), spawn("b"));
+        let (a, b) = tokio::time::timeout(Duration::from_secs(10), async {
+            (a.await.unwrap(), b.await.unwrap())
+        })
+        .await
+        .expect("racing calls deadlocked");
+        let outcomes = [
+            String::from_utf8(a.unwrap()).unwrap(),
+            String::from_utf8(b.unwrap()).unwrap(),
+        ];
+        assert!(
+            outcomes.iter().any(|o| o.starts_with("call cycle:")),
+            "{outcomes:?}"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn deny_and_missing_targets_are_errors() {
+        let registry: Registry = Arc::default();
+        register(
+            &registry,
+            ScriptedCapsule::boxed(
+                "guard",
+                Box::new(|_, _, _| {
+                    Ok(InterceptResult::Deny {
+                        reason: "nope".into(),
+                    })
+                }),
+            ),
+        )
+        .await;
+
+        let err = call(&registry, "router", "guard", Duration::from_secs(5)).unwrap_err();
+        assert!(err.contains("denied the call: nope"), "{err}");
+        let err = call(&registry, "router", "ghost", Duration::from_secs(5)).unwrap_err();
+        assert!(err.contains("not loaded"), "{err}");
+    }
+}
@@ -46,3 +46,43 @@ impl kv::Host for HostState {
         Ok(count)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::wasm::bindings::astrid::capsule::kv::Host as _;
+    use crate::engine::wasm::test_fixtures::{mem_kv, minimal_host_state};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn list_and_delete_stay_in_capsule_scope() {
+        let rt = tokio::runtime::Handle::current();
+        let kv_a = mem_kv("capsule:a");
+        let kv_b = kv_a.with_namespace("capsule:b").unwrap();
+        let mut a = minimal_host_state(rt.clone());
+        a.kv = kv_a;
+        let mut b = minimal_host_state(rt);
+        b.kv = kv_b;
+
+        tokio::task::spawn_blocking(move || {
+            for key in ["session.1", "session.2", "config"] {
+                a.kv_set(key.into(), b"x".to_vec()).unwrap();
+            }
+            b.kv_set("session.9".into(), b"y".to_vec()).unwrap();
+
+            let mut keys = a.kv_list_keys("session.".into()).unwrap();
+            keys.sort();
+            assert_eq!(keys, ["session.1", "session.2"]);
+            assert_eq!(b.kv_list_keys(String::new()).unwrap(), ["session.9"]);
+
+            a.kv_delete("session.1".into()).unwrap();
+            assert_eq!(a.kv_get("session.1".into()).unwrap(), None);
+            // Deleting a key another capsule owns is a no-op for that capsule.
+            a.kv_delete("session.9".into()).unwrap();
+            assert_eq!(
+                b.kv_get("session.9".into()).unwrap().as_deref(),
+                Some(&b"y"[..])
+            );
+        })
+        .await
+        .unwrap();
+    }
+}
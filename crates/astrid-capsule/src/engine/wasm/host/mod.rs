@@ -10,7 +10,7 @@ pub(crate) mod approval;
 /// Elicit lifecycle API (install/upgrade user input collection).
 pub(crate) mod elicit;
 /// File system operations for plugins.
-pub(crate) mod fs;
+pub mod fs;
 /// HTTP network executions for plugins.
 pub mod http;
 /// Identity operations (resolve, link, create user).
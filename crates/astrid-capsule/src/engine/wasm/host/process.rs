@@ -1,15 +1,19 @@
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use tracing::warn;
 
-use astrid_workspace::SandboxCommand;
+use astrid_approval::action::SensitiveAction;
+use astrid_workspace::{SandboxCommand, WorkspaceBoundary, WorkspaceConfig, WorkspaceMode};
 
 use crate::engine::wasm::bindings::astrid::capsule::process;
 use crate::engine::wasm::bindings::astrid::capsule::types::{
-    KillProcessResult, ProcessResult, ReadLogsResult, SpawnBackgroundResult, SpawnRequest,
+    KillProcessResult, ProcessOutput, ProcessResult, ReadLogsResult, SpawnBackgroundResult,
+    SpawnOptions, SpawnRequest,
 };
 use crate::engine::wasm::host::util;
 use crate::engine::wasm::host_state::HostState;
@@ -245,15 +249,22 @@ fn spawn_reader_thread(
 /// Prepare a sandboxed command for background execution.
 ///
 /// Shared between spawn_host (sync) and spawn_background (async). Applies
-/// environment stripping and sandbox wrapping.
+/// the caller's working directory and environment, then environment
+/// stripping and sandbox wrapping.
 fn prepare_sandboxed_command(
     cmd: &str,
     args: &[String],
-    workspace_root: &std::path::Path,
+    workspace_root: &Path,
+    cwd: Option<&Path>,
+    env: &[(String, String)],
 ) -> Result<Command, String> {
     let mut inner_cmd = Command::new(cmd);
     let str_args: Vec<&str> = args.iter().map(String::as_str).collect();
     inner_cmd.args(&str_args);
+    if let Some(dir) = cwd {
+        inner_cmd.current_dir(dir);
+    }
+    inner_cmd.envs(env.iter().map(|(k, v)| (k, v)));
     inner_cmd.env_remove("ASTRID_SOCKET_PATH");
     inner_cmd.env_remove("ASTRID_SESSION_TOKEN");
     inner_cmd.env_remove("ASTRID_HOME");
@@ -262,8 +273,304 @@ fn prepare_sandboxed_command(
         .map_err(|e| format!("failed to wrap command in sandbox: {e}"))
 }
 
-impl process::Host for HostState {
-    fn spawn(&mut self, request: SpawnRequest) -> Result<ProcessResult, String> {
+/// Shells whose `-c` argument is itself a command line.
+const SHELLS: &[&str] = &["sh", "bash", "dash", "zsh", "ksh"];
+
+/// Shell options that consume the following word as their value.
+const SHELL_VALUE_OPTIONS: &[&str] = &["-o", "+o", "-O", "+O", "--rcfile", "--init-file"];
+
+/// The file name of a program, `sh` for `/bin/sh`.
+fn program_name(cmd: &str) -> &str {
+    Path::new(cmd)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(cmd)
+}
+
+/// Quote every word so no shell syntax in it is interpreted.
+fn quoted_line<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+    words
+        .into_iter()
+        .map(|word| format!("'{}'", word.replace('\'', r"'\''")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The script a shell invocation runs inline, and the positional arguments
+/// after it, if `args` carries a `-c` flag (alone or in a cluster such as
+/// `-lc` or `-ec`).
+fn inline_script<'a>(args: &'a [&'a str]) -> Option<(&'a str, &'a [&'a str])> {
+    let mut inline = false;
+    let mut i = 0;
+    while let Some(&word) = args.get(i) {
+        if word == "--" || word == "-" {
+            i += 1;
+            break;
+        }
+        if !word.starts_with(['-', '+']) {
+            break;
+        }
+        if SHELL_VALUE_OPTIONS.contains(&word) {
+            i += 1;
+        } else if word.starts_with('-') && !word.starts_with("--") && word.contains('c') {
+            inline = true;
+        }
+        i += 1;
+    }
+    if !inline {
+        return None;
+    }
+    args.get(i..)?
+        .split_first()
+        .map(|(script, rest)| (*script, rest))
+}
+
+/// The command line a spawn request amounts to, for path extraction.
+///
+/// `env` prefixes are looked through, and a shell run with `-c` yields its
+/// script, so paths inside it are seen; the script's positional arguments
+/// (`$0`, `$1`, ...) follow as words of their own. Otherwise every word is
+/// quoted, since no shell interprets them. The variables in `env` lead as
+/// `NAME=value` words, checked like an `env` prefix's.
+fn spawn_command_line(cmd: &str, args: &[String], env: &[(String, String)]) -> String {
+    let mut words: Vec<&str> = std::iter::once(cmd)
+        .chain(args.iter().map(String::as_str))
+        .collect();
+
+    // `env [OPTION]... [NAME=value]... cmd ...` runs `cmd`. The words in
+    // between are kept so paths in them are still checked.
+    let assignments: Vec<String> = env.iter().map(|(k, v)| format!("{k}={v}")).collect();
+    let mut prefix: Vec<&str> = assignments.iter().map(String::as_str).collect();
+    while words.first().is_some_and(|w| program_name(w) == "env") {
+        let program = words
+            .iter()
+            .skip(1)
+            .position(|w| !w.starts_with('-') && !w.contains('='))
+            .map_or(words.len(), |i| i + 1);
+        prefix.extend(words.drain(..program).skip(1));
+    }
+
+    let line = match words.split_first() {
+        Some((program, rest)) if SHELLS.contains(&program_name(program)) => {
+            match inline_script(rest) {
+                Some((script, positional)) => {
+                    let positional = quoted_line(positional.iter().copied());
+                    format!("{script}\n: {positional}")
+                },
+                None => quoted_line(words.iter().copied()),
+            }
+        },
+        _ => quoted_line(words.iter().copied()),
+    };
+    if prefix.is_empty() {
+        line
+    } else {
+        format!(": {}\n{line}", quoted_line(prefix))
+    }
+}
+
+/// The boundary spawned commands are checked against.
+///
+/// Paths under `allowed` (the principal's home and tmp mounts) pass.
+fn spawn_boundary(
+    workspace_root: &Path,
+    allowed: &[&Path],
+    mode: WorkspaceMode,
+) -> WorkspaceBoundary {
+    let root = workspace_root
+        .canonicalize()
+        .unwrap_or_else(|_| workspace_root.to_path_buf());
+    let config = allowed.iter().fold(
+        WorkspaceConfig::new(root).with_mode(mode),
+        |config, path| config.allow_write(*path),
+    );
+    WorkspaceBoundary::new(config)
+}
+
+/// Check the paths a spawn request names, in its command line or in the
+/// values of the variables it sets, against the workspace.
+///
+/// Relative paths are resolved against `cwd`, the directory the command
+/// runs in. Returns the paths that need the user's approval, empty if none
+/// do. Fails if a path is never allowed, or would need approval in
+/// [`WorkspaceMode::Safe`]. Extraction is best effort, see
+/// [`WorkspaceBoundary::check_command`].
+fn check_spawn_paths(
+    boundary: &WorkspaceBoundary,
+    cwd: &Path,
+    home: Option<&Path>,
+    cmd: &str,
+    args: &[String],
+    env: &[(String, String)],
+) -> Result<Vec<PathBuf>, String> {
+    let cwd = cwd.canonicalize().unwrap_or_else(|_| cwd.to_path_buf());
+    let check = boundary.check_command(&spawn_command_line(cmd, args, env), &cwd, home);
+    if check.verdict.is_allowed() {
+        return Ok(Vec::new());
+    }
+    if check.verdict.needs_approval() {
+        return Ok(check.flagged);
+    }
+    let flagged: Vec<String> = check
+        .flagged
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect();
+    Err(format!(
+        "command names paths outside the workspace: {}",
+        flagged.join(", ")
+    ))
+}
+
+/// Environment variable prefix reserved for the kernel. Capsules may not set
+/// these on spawned processes.
+const RESERVED_ENV_PREFIX: &str = "ASTRID_";
+
+/// Resolve a `spawn-with-options` working directory.
+///
+/// Relative paths are taken from the workspace root; absolute paths must
+/// already lie inside it. The result is expressed under `workspace_root` as
+/// given, which is the path the sandbox binds writable.
+fn resolve_spawn_cwd(workspace_root: &Path, cwd: &str) -> Result<PathBuf, String> {
+    let root = workspace_root
+        .canonicalize()
+        .map_err(|e| format!("invalid workspace root: {e}"))?;
+    let resolved = root
+        .join(cwd)
+        .canonicalize()
+        .map_err(|e| format!("invalid cwd '{cwd}': {e}"))?;
+    let relative = resolved
+        .strip_prefix(&root)
+        .map_err(|_| format!("cwd '{cwd}' is outside the workspace"))?;
+    if !resolved.is_dir() {
+        return Err(format!("cwd '{cwd}' is not a directory"));
+    }
+    Ok(workspace_root.join(relative))
+}
+
+/// Reject environment variables a capsule may not set.
+fn validate_spawn_env(env: &[(String, String)]) -> Result<(), String> {
+    for (key, value) in env {
+        if key.is_empty() || key.contains(['=', '\0']) || value.contains('\0') {
+            return Err(format!("invalid environment variable '{key}'"));
+        }
+        if key.starts_with(RESERVED_ENV_PREFIX) {
+            return Err(format!(
+                "environment variable '{key}' is reserved ({RESERVED_ENV_PREFIX}*)"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// SIGKILL a process group, best-effort.
+fn kill_process_group(pid: u32) {
+    #[cfg(unix)]
+    if let Ok(raw) = i32::try_from(pid) {
+        let _ = nix::sys::signal::killpg(
+            nix::unistd::Pid::from_raw(raw),
+            nix::sys::signal::Signal::SIGKILL,
+        );
+    }
+}
+
+/// Write `stdin` to the child, then wait for it to exit.
+///
+/// If `timeout` elapses first, the child's process group is killed and the
+/// second value is `true`. The child must have been spawned as a process
+/// group leader when a timeout is given.
+async fn wait_for_output(
+    mut child: std::process::Child,
+    stdin: Option<String>,
+    timeout: Option<Duration>,
+) -> std::io::Result<(std::process::Output, bool)> {
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        // Written from its own thread: a child that fills its stdout pipe
+        // before reading stdin would otherwise deadlock against us.
+        std::thread::Builder::new()
+            .name("spawn-stdin".to_string())
+            .spawn(move || {
+                // A child that exits without reading its input is not an error.
+                let _ = pipe.write_all(input.as_bytes());
+            })?;
+    }
+
+    let pid = child.id();
+    let mut wait = tokio::task::spawn_blocking(move || child.wait_with_output());
+    let (joined, timed_out) = match timeout {
+        Some(limit) => match tokio::time::timeout(limit, &mut wait).await {
+            Ok(joined) => (joined, false),
+            Err(_) => {
+                kill_process_group(pid);
+                (wait.await, true)
+            },
+        },
+        None => (wait.await, false),
+    };
+    let output = joined.map_err(std::io::Error::other)??;
+    Ok((output, timed_out))
+}
+
+impl HostState {
+    /// Check the paths a spawn request names against the workspace, asking
+    /// the user once for the whole command if any lie outside it.
+    ///
+    /// `cwd` is the directory the command will run in, the workspace root
+    /// if the request gives none, and `env` the variables it sets.
+    fn check_spawn_paths(
+        &self,
+        request: &SpawnRequest,
+        cwd: Option<&Path>,
+        env: &[(String, String)],
+    ) -> Result<(), String> {
+        let allowed: Vec<&Path> = [self.effective_home(), self.effective_tmp()]
+            .into_iter()
+            .flatten()
+            .map(|mount| mount.root.as_path())
+            .collect();
+        let boundary = spawn_boundary(&self.workspace_root, &allowed, self.workspace_mode);
+        let home = self.effective_home().map(|mount| mount.root.as_path());
+        let flagged = check_spawn_paths(
+            &boundary,
+            cwd.unwrap_or(&self.workspace_root),
+            home,
+            &request.cmd,
+            &request.args,
+            env,
+        )?;
+        if flagged.is_empty() {
+            return Ok(());
+        }
+
+        let paths: Vec<String> = flagged.iter().map(|p| p.display().to_string()).collect();
+        let assignments: Vec<String> = env.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        let action = SensitiveAction::ShellOutsideWorkspace {
+            command: assignments
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(request.cmd.as_str()))
+                .chain(request.args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" "),
+            paths: paths.clone(),
+        };
+        if self.approve_host_action(&action)? {
+            Ok(())
+        } else {
+            Err(format!(
+                "command names paths outside the workspace and was not approved: {}",
+                paths.join(", ")
+            ))
+        }
+    }
+
+    /// Run a process to completion. Shared by `spawn` and
+    /// `spawn-with-options`.
+    fn run_to_completion(
+        &mut self,
+        request: &SpawnRequest,
+        options: SpawnOptions,
+    ) -> Result<ProcessOutput, String> {
         let workspace_root = self.workspace_root.clone();
         let security = self.security.clone();
         let capsule_id = self.capsule_id.as_str().to_owned();
@@ -296,12 +603,38 @@ impl process::Host for HostState {
             );
         }
 
-        let mut sandboxed_cmd =
-            prepare_sandboxed_command(&request.cmd, &request.args, &workspace_root)?;
+        validate_spawn_env(&options.env)?;
+        let cwd = options
+            .cwd
+            .as_deref()
+            .map(|dir| resolve_spawn_cwd(&workspace_root, dir))
+            .transpose()?;
+
+        // Paths the command names must stay inside the workspace.
+        self.check_spawn_paths(request, cwd.as_deref(), &options.env)?;
+        let timeout = options.timeout_ms.map(Duration::from_millis);
+
+        let mut sandboxed_cmd = prepare_sandboxed_command(
+            &request.cmd,
+            &request.args,
+            &workspace_root,
+            cwd.as_deref(),
+            &options.env,
+        )?;
 
         // Spawn the child process (non-blocking) so we can track its PID.
         sandboxed_cmd.stdout(Stdio::piped());
         sandboxed_cmd.stderr(Stdio::piped());
+        if options.stdin.is_some() {
+            sandboxed_cmd.stdin(Stdio::piped());
+        }
+        // A timed-out child is killed with its whole process group, so
+        // grandchildren holding the output pipes die too.
+        #[cfg(unix)]
+        if timeout.is_some() {
+            use std::os::unix::process::CommandExt as _;
+            sandboxed_cmd.process_group(0);
+        }
 
         let child = sandboxed_cmd
             .spawn()
@@ -312,21 +645,28 @@ impl process::Host for HostState {
 
         // Wait for the child on the blocking thread pool so tokio worker threads
         // remain free for the cancel listener and other async tasks.
-        let output_result =
-            util::bounded_block_on_cancellable(&handle, &semaphore, &cancel_token, async move {
-                tokio::task::spawn_blocking(move || child.wait_with_output())
-                    .await
-                    .map_err(std::io::Error::other)
-                    .and_then(|r| r)
-            });
+        let output_result = util::bounded_block_on_cancellable(
+            &handle,
+            &semaphore,
+            &cancel_token,
+            wait_for_output(child, options.stdin, timeout),
+        );
 
         let result = match output_result {
-            Some(Ok(output)) => {
+            Some(Ok((output, timed_out))) => {
                 process_tracker.unregister(pid);
-                ProcessResult {
+                if timed_out {
+                    warn!(capsule_id, pid, ?timeout, "process timed out");
+                }
+                ProcessOutput {
                     stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
                     stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
-                    exit_code: output.status.code().unwrap_or(-1),
+                    exit_code: if timed_out {
+                        -1
+                    } else {
+                        output.status.code().unwrap_or(-1)
+                    },
+                    timed_out,
                 }
             },
             Some(Err(e)) => {
@@ -343,16 +683,42 @@ impl process::Host for HostState {
                     );
                 }
                 process_tracker.unregister(pid);
-                ProcessResult {
+                ProcessOutput {
                     stdout: String::new(),
                     stderr: "process cancelled".to_owned(),
                     exit_code: -1,
+                    timed_out: false,
                 }
             },
         };
 
         Ok(result)
     }
+}
+
+impl process::Host for HostState {
+    fn spawn(&mut self, request: SpawnRequest) -> Result<ProcessResult, String> {
+        let options = SpawnOptions {
+            stdin: None,
+            cwd: None,
+            env: Vec::new(),
+            timeout_ms: None,
+        };
+        let output = self.run_to_completion(&request, options)?;
+        Ok(ProcessResult {
+            stdout: output.stdout,
+            stderr: output.stderr,
+            exit_code: output.exit_code,
+        })
+    }
+
+    fn spawn_with_options(
+        &mut self,
+        request: SpawnRequest,
+        options: SpawnOptions,
+    ) -> Result<ProcessOutput, String> {
+        self.run_to_completion(&request, options)
+    }
 
     fn spawn_background(&mut self, request: SpawnRequest) -> Result<SpawnBackgroundResult, String> {
         // Effective cap = min(profile, per-capsule hard ceiling). The hard
@@ -394,8 +760,11 @@ impl process::Host for HostState {
             );
         }
 
+        // Path check - same as synchronous spawn.
+        self.check_spawn_paths(&request, None, &[])?;
+
         let mut sandboxed_cmd =
-            prepare_sandboxed_command(&request.cmd, &request.args, &workspace_root)?;
+            prepare_sandboxed_command(&request.cmd, &request.args, &workspace_root, None, &[])?;
 
         // Set up as process group leader for clean group kills on Unix.
         #[cfg(unix)]
@@ -703,6 +1072,199 @@ mod tests {
         assert_eq!(bob_after, 2, "bob unaffected by alice draining");
     }
 
+    #[test]
+    fn spawn_cwd_stays_inside_workspace() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("file.txt"), "x").unwrap();
+
+        assert_eq!(resolve_spawn_cwd(root, "sub").unwrap(), root.join("sub"));
+        let inside = root.canonicalize().unwrap().join("sub");
+        assert_eq!(
+            resolve_spawn_cwd(root, inside.to_str().unwrap()).unwrap(),
+            root.join("sub")
+        );
+        assert!(
+            resolve_spawn_cwd(root, "..")
+                .unwrap_err()
+                .contains("outside the workspace")
+        );
+        assert!(resolve_spawn_cwd(root, "/").is_err());
+        assert!(
+            resolve_spawn_cwd(root, "file.txt")
+                .unwrap_err()
+                .contains("not a directory")
+        );
+        assert!(resolve_spawn_cwd(root, "missing").is_err());
+    }
+
+    #[test]
+    fn spawn_env_rejects_reserved_and_malformed_names() {
+        let ok = vec![("LANG".to_string(), "C".to_string())];
+        assert!(validate_spawn_env(&ok).is_ok());
+        for key in ["ASTRID_SESSION_TOKEN", "", "A=B", "A\0B"] {
+            let env = vec![(key.to_string(), "v".to_string())];
+            assert!(validate_spawn_env(&env).is_err(), "accepted {key:?}");
+        }
+    }
+
+    #[test]
+    fn spawn_paths_stay_inside_workspace() {
+        type Env<'a> = &'a [(&'a str, &'a str)];
+
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().join("ws");
+        let scratch = tmp.path().join("tmp");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir(&scratch).unwrap();
+        let scratch = scratch.canonicalize().unwrap();
+        let home = Path::new("/home/someone");
+        let safe = spawn_boundary(&root, &[scratch.as_path()], WorkspaceMode::Safe);
+        let guided = spawn_boundary(&root, &[scratch.as_path()], WorkspaceMode::Guided);
+        let run_with_env =
+            |boundary: &WorkspaceBoundary, cwd: &Path, cmd: &str, args: &[&str], env: Env| {
+                let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+                let env: Vec<(String, String)> = env
+                    .iter()
+                    .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                    .collect();
+                check_spawn_paths(boundary, cwd, Some(home), cmd, &args, &env)
+            };
+        let run = |boundary: &WorkspaceBoundary, cwd: &Path, cmd: &str, args: &[&str]| {
+            run_with_env(boundary, cwd, cmd, args, &[])
+        };
+        let check = |cmd: &str, args: &[&str], env: Env| run_with_env(&safe, &root, cmd, args, env);
+
+        // (command, args, env, allowed in safe mode)
+        let copy = scratch.join("out.txt");
+        let cases: &[(&str, &[&str], Env, bool)] = &[
+            ("cat", &["src/main.rs"], &[], true),
+            ("echo", &["it's fine"], &[], true),
+            ("sh", &["-c", "ls src && echo hi > /dev/null"], &[], true),
+            ("cp", &["src/main.rs", copy.to_str().unwrap()], &[], true),
+            ("bash", &["-lc", "cat src/main.rs"], &[], true),
+            ("sh", &["-c", "cat \"$1\"", "_", "src/main.rs"], &[], true),
+            ("env", &["FOO=1", "sh", "-c", "ls src"], &[], true),
+            ("cat", &["../../etc/passwd"], &[], false),
+            ("/bin/sh", &["-c", "cat /etc/shadow > out.txt"], &[], false),
+            ("bash", &["-c", "cat ~/.ssh/id_rsa"], &[], false),
+            ("sh", &["-c", "cat \"$1\"", "_", "/etc/passwd"], &[], false),
+            ("sh", &["-c", "cat \"$0\"", "/etc/passwd"], &[], false),
+            ("bash", &["-lc", "cat /etc/passwd"], &[], false),
+            ("sh", &["-ec", "cat /etc/passwd"], &[], false),
+            (
+                "bash",
+                &["-o", "pipefail", "-c", "cat /etc/passwd"],
+                &[],
+                false,
+            ),
+            ("bash", &["-x", "-c", "cat /etc/passwd"], &[], false),
+            ("env", &["sh", "-c", "cat /etc/passwd"], &[], false),
+            (
+                "/usr/bin/env",
+                &["-i", "bash", "-c", "cat /etc/passwd"],
+                &[],
+                false,
+            ),
+            ("env", &["CONF=/etc/passwd", "make"], &[], false),
+            ("sh", &["../../outside.sh"], &[], false),
+            ("sh", &["-c", "cat \"$F\""], &[("F", "src/main.rs")], true),
+            ("make", &[], &[("CONF", "./build.conf")], true),
+            ("sh", &["-c", "cat \"$F\""], &[("F", "/etc/passwd")], false),
+            ("make", &[], &[("CONF", "../../etc/passwd")], false),
+            ("cat", &["src/main.rs"], &[("HOME", "~/.ssh")], false),
+        ];
+        for (cmd, args, env, allowed) in cases {
+            let result = check(cmd, args, env);
+            assert_eq!(result.is_ok(), *allowed, "{cmd} {args:?}: {result:?}");
+            if let Ok(flagged) = result {
+                assert!(flagged.is_empty(), "{cmd} {args:?}: {flagged:?}");
+            }
+        }
+
+        let err = check("cat", &["../../etc/passwd"], &[]).unwrap_err();
+        assert!(err.contains("outside the workspace"), "{err}");
+        let err = check("sh", &["-c", "cat \"$F\""], &[("F", "/etc/passwd")]).unwrap_err();
+        assert!(err.contains("/etc/passwd"), "{err}");
+        let err = check("bash", &["-c", "cat ~/.ssh/id_rsa"], &[]).unwrap_err();
+        assert!(err.contains("/home/someone/.ssh/id_rsa"), "{err}");
+
+        // Relative paths resolve against the command's working directory.
+        let src = root.join("src");
+        assert!(run(&safe, &src, "cat", &["../README.md"]).is_ok());
+        assert!(run(&safe, &src, "cat", &["../../secret"]).is_err());
+
+        // Outside Safe mode the flagged paths are returned for approval.
+        let outside = tmp.path().canonicalize().unwrap().join("other.txt");
+        let flagged = run(&guided, &root, "cat", &["../other.txt"]).unwrap();
+        assert_eq!(flagged, vec![outside]);
+        assert!(run(&guided, &root, "cat", &["/etc/passwd"]).is_err());
+        assert!(
+            run(&guided, &root, "cat", &["src/main.rs"])
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn wait_for_output_pipes_stdin() {
+        let child = Command::new("cat")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to spawn cat");
+        let (output, timed_out) =
+            wait_for_output(child, Some("line one\nline two\n".to_string()), None)
+                .await
+                .unwrap();
+        assert!(!timed_out);
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stdout),
+            "line one\nline two\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn wait_for_output_kills_process_group_on_timeout() {
+        use std::os::unix::process::CommandExt as _;
+
+        // The shell's `sleep` child holds stdout open; only a group kill
+        // lets the wait finish.
+        let child = Command::new("sh")
+            .args(["-c", "echo started; sleep 30; echo finished"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .spawn()
+            .expect("failed to spawn sh");
+        let started = std::time::Instant::now();
+        let (output, timed_out) = wait_for_output(child, None, Some(Duration::from_millis(200)))
+            .await
+            .unwrap();
+        assert!(timed_out);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "started\n");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn wait_for_output_within_timeout_reports_exit_code() {
+        let child = Command::new("sh")
+            .args(["-c", "exit 3"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to spawn sh");
+        let (output, timed_out) = wait_for_output(child, None, Some(Duration::from_secs(10)))
+            .await
+            .unwrap();
+        assert!(!timed_out);
+        assert_eq!(output.status.code(), Some(3));
+    }
+
     #[test]
     fn kill_nonexistent_returns_error() {
         let processes: std::collections::HashMap<u64, ManagedProcess> =
@@ -14,19 +14,43 @@ struct TriggerRequest {
     payload: serde_json::Value,
 }
 
+/// One entry of the `cron-list` response.
+#[derive(serde::Serialize)]
+struct CronJobEntry {
+    name: String,
+    schedule: String,
+    next_fire: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A manifest config value as the guest sees it, or `None` if unset.
+///
+/// Strings are returned raw, not JSON-encoded: `serde_json::to_string`
+/// wraps them in quotes (`"\"value\""`), causing double-encoding when the
+/// SDK's `env::var` reads them. Other values are returned as JSON.
+fn config_string(
+    config: &std::collections::HashMap<String, serde_json::Value>,
+    key: &str,
+) -> Option<String> {
+    match config.get(key)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        v => Some(serde_json::to_string(v).unwrap_or_default()),
+    }
+}
+
 impl sys::Host for HostState {
     fn get_config(&mut self, key: String) -> Result<String, String> {
-        let value = self.config.get(&key).cloned();
-
-        // Return the raw string value, not JSON-encoded.
-        // serde_json::to_string wraps strings in quotes ("\"value\""),
-        // causing double-encoding when the SDK's env::var reads it.
-        let result = match value {
-            Some(serde_json::Value::String(s)) => s,
-            Some(v) => serde_json::to_string(&v).unwrap_or_default(),
-            None => String::new(),
-        };
-        Ok(result)
+        Ok(config_string(&self.config, &key).unwrap_or_default())
+    }
+
+    fn get_config_many(&mut self, keys: Vec<String>) -> Result<String, String> {
+        let values: serde_json::Map<String, serde_json::Value> = keys
+            .into_iter()
+            .filter_map(|key| {
+                let value = config_string(&self.config, &key)?;
+                Some((key, serde_json::Value::String(value)))
+            })
+            .collect();
+        serde_json::to_string(&values).map_err(|e| format!("failed to serialize config: {e}"))
     }
 
     fn get_caller(&mut self) -> Result<CallerContext, String> {
@@ -236,6 +260,27 @@ impl sys::Host for HostState {
             .map_or(0u64, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
     }
 
+    fn cron_list(&mut self) -> Result<String, String> {
+        let jobs: Vec<CronJobEntry> = match self.capsule_registry.clone() {
+            Some(registry) => {
+                let capsule_id = self.capsule_id.clone();
+                util::bounded_block_on(&self.runtime_handle, &self.host_semaphore, async move {
+                    registry.read().await.capsule_cron_jobs(&capsule_id)
+                })
+                .into_iter()
+                .map(|job| CronJobEntry {
+                    name: job.name,
+                    schedule: job.schedule,
+                    next_fire: job.next_fire,
+                })
+                .collect()
+            },
+            // No registry available — no jobs can be scheduled.
+            None => Vec::new(),
+        };
+        serde_json::to_string(&jobs).map_err(|e| format!("failed to serialize cron jobs: {e}"))
+    }
+
     fn check_capsule_capability(
         &mut self,
         request: CapabilityCheckRequest,
@@ -396,3 +441,47 @@ mod log_chain_tests {
         state.log(LogLevel::Error, "post-poison line".into());
     }
 }
+
+#[cfg(test)]
+mod config_tests {
+    use crate::engine::wasm::bindings::astrid::capsule::sys::Host as SysHost;
+    use crate::engine::wasm::test_fixtures::minimal_host_state;
+
+    #[tokio::test]
+    async fn get_config_many_omits_missing_keys() {
+        let mut state = minimal_host_state(tokio::runtime::Handle::current());
+        state
+            .config
+            .insert("DISCORD_ALLOWED_USERS".into(), serde_json::json!("123,456"));
+        state
+            .config
+            .insert("DISCORD_ALLOWED_GUILDS".into(), serde_json::json!([7, 8]));
+
+        let got = state
+            .get_config_many(vec![
+                "DISCORD_ALLOWED_USERS".into(),
+                "DISCORD_ALLOWED_GUILDS".into(),
+                "DISCORD_SESSION_SCOPE".into(),
+            ])
+            .unwrap();
+        let got: serde_json::Value = serde_json::from_str(&got).unwrap();
+        assert_eq!(
+            got,
+            serde_json::json!({
+                "DISCORD_ALLOWED_USERS": "123,456",
+                "DISCORD_ALLOWED_GUILDS": "[7,8]",
+            })
+        );
+        assert_eq!(
+            state.get_config("DISCORD_SESSION_SCOPE".into()).unwrap(),
+            ""
+        );
+        assert_eq!(state.get_config_many(Vec::new()).unwrap(), "{}");
+    }
+
+    #[tokio::test]
+    async fn cron_list_is_empty_without_registry() {
+        let mut state = minimal_host_state(tokio::runtime::Handle::current());
+        assert_eq!(state.cron_list().unwrap(), "[]");
+    }
+}
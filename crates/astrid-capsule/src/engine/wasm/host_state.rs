@@ -167,6 +167,9 @@ pub struct HostState {
     /// IPC topic patterns this capsule is allowed to subscribe to.
     /// Empty means DENY ALL (fail-closed).
     pub ipc_subscribe_patterns: Vec<String>,
+    /// Capsule IDs this capsule may invoke with `ipc-call` (`"*"` for any).
+    /// Empty means DENY ALL (fail-closed).
+    pub call_targets: Vec<String>,
     /// Optional security gate for gated operations (HTTP, file I/O).
     pub security: Option<Arc<dyn CapsuleSecurityGate>>,
     /// Hook manager for executing user scripts synchronously via airlock.
@@ -265,12 +268,28 @@ pub struct HostState {
     ///
     /// Starts at 1 so handle 0 is never issued (reserved as sentinel).
     pub next_process_id: u64,
+    /// Files opened by the guest via `astrid_fs_open`, keyed by handle ID.
+    ///
+    /// Closed by `astrid_fs_close` or when the invocation ends.
+    pub open_files: HashMap<u64, crate::engine::wasm::host::fs::OpenFile>,
+    /// Monotonic counter for file handle IDs.
+    /// Starts at 1 (0 reserved as sentinel).
+    pub next_file_handle: u64,
     /// Tracks active child process PIDs for cancellation.
     ///
     /// Shared with the cancel listener background task. The spawn host function
     /// registers/unregisters PIDs; the listener calls `cancel_all()` when a
     /// `tool.v1.request.cancel` event arrives.
     pub process_tracker: Arc<ProcessTracker>,
+    /// Kernel audit log for host functions that record their own entries.
+    ///
+    /// When `None` (tests, hook modules), those host functions skip the
+    /// audit entry.
+    pub audit: Option<crate::context::CapsuleAudit>,
+    /// Workspace mode. In [`Safe`](astrid_workspace::WorkspaceMode::Safe)
+    /// mode a spawned command naming paths outside the workspace is refused;
+    /// otherwise the user is asked once for the whole command.
+    pub workspace_mode: astrid_workspace::WorkspaceMode,
 }
 
 impl wasmtime_wasi::WasiView for HostState {
@@ -454,6 +473,36 @@ impl HostState {
             .unwrap_or_else(|| self.principal.clone())
     }
 
+    /// Append an entry to the kernel audit log under the
+    /// [effective principal](Self::effective_principal).
+    ///
+    /// Returns `Ok(())` without writing anything when no audit log is
+    /// attached.
+    ///
+    /// # Errors
+    ///
+    /// Returns the audit log's error if the entry could not be stored.
+    pub fn append_audit(
+        &self,
+        action: astrid_audit::AuditAction,
+        authorization: astrid_audit::AuthorizationProof,
+        outcome: astrid_audit::AuditOutcome,
+    ) -> astrid_audit::AuditResult<()> {
+        let Some(audit) = &self.audit else {
+            return Ok(());
+        };
+        audit
+            .log
+            .append_with_principal(
+                audit.session_id.clone(),
+                self.effective_principal(),
+                action,
+                authorization,
+                outcome,
+            )
+            .map(drop)
+    }
+
     /// Return the effective quota profile for the current invocation.
     ///
     /// Prefers `invocation_profile` (set by
@@ -496,6 +545,7 @@ impl std::fmt::Debug for HostState {
             .field("has_identity_store", &self.identity_store.is_some())
             .field("active_http_streams", &self.active_http_streams.len())
             .field("process_tracker", &self.process_tracker)
+            .field("has_audit", &self.audit.is_some())
             .finish_non_exhaustive()
     }
 }
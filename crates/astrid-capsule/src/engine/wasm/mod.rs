@@ -170,6 +170,8 @@ pub struct WasmEngine {
     cancel_token: Option<tokio_util::sync::CancellationToken>,
     /// RAII guard that stops the epoch ticker thread on drop.
     epoch_ticker: Option<EpochTickerGuard>,
+    /// Deadline and cancellation checked by the store's epoch callback.
+    guest_deadline: Arc<GuestDeadline>,
     /// Shared per-principal profile cache (Layer 3, issue #666).
     ///
     /// Populated at load time from the kernel-wide cache. `invoke_interceptor`
@@ -211,6 +213,7 @@ impl WasmEngine {
             ready_rx: None,
             cancel_token: None,
             epoch_ticker: None,
+            guest_deadline: Arc::default(),
             profile_cache: None,
             owner_principal: None,
             overlay_registry: None,
@@ -458,6 +461,336 @@ fn spawn_epoch_ticker(engine: &wasmtime::Engine) -> EpochTickerGuard {
     }
 }
 
+/// Wall-clock deadline and cancellation for the guest call in progress.
+///
+/// The store's epoch deadline is one tick ahead at all times, and its
+/// callback asks [`check`](Self::check) whether to keep going. This lets a
+/// caller on another thread interrupt a guest whose store it cannot lock.
+#[derive(Debug, Default)]
+struct GuestDeadline {
+    state: Mutex<GuestDeadlineState>,
+}
+
+#[derive(Debug, Default)]
+struct GuestDeadlineState {
+    /// `None` for long-lived capsules, which never time out.
+    expires_at: Option<std::time::Instant>,
+    cancel: Option<tokio_util::sync::CancellationToken>,
+}
+
+impl GuestDeadline {
+    fn lock(&self) -> std::sync::MutexGuard<'_, GuestDeadlineState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Time out guest calls `timeout` from now, or never.
+    fn arm(&self, timeout: Option<std::time::Duration>) {
+        self.lock().expires_at = timeout.map(|t| std::time::Instant::now() + t);
+    }
+
+    /// Interrupt the guest once `cancel` fires, until cleared with `None`.
+    fn set_cancel(&self, cancel: Option<tokio_util::sync::CancellationToken>) {
+        self.lock().cancel = cancel;
+    }
+
+    /// Epoch callback: trap if the deadline passed or the call was cancelled.
+    fn check(&self) -> wasmtime::UpdateDeadline {
+        let state = self.lock();
+        let expired = state
+            .expires_at
+            .is_some_and(|at| std::time::Instant::now() >= at);
+        let cancelled = state
+            .cancel
+            .as_ref()
+            .is_some_and(tokio_util::sync::CancellationToken::is_cancelled);
+        if expired || cancelled {
+            wasmtime::UpdateDeadline::Interrupt
+        } else {
+            wasmtime::UpdateDeadline::Continue(1)
+        }
+    }
+}
+
+impl WasmEngine {
+    /// Run an interceptor export, interrupting the guest once `cancel` fires.
+    fn invoke(
+        &self,
+        action: &str,
+        payload: &[u8],
+        caller: Option<&astrid_events::ipc::IpcMessage>,
+        cancel: Option<&tokio_util::sync::CancellationToken>,
+    ) -> CapsuleResult<crate::capsule::InterceptResult> {
+        let store = self.store.as_ref().ok_or_else(|| {
+            CapsuleError::NotSupported(
+                "plugin handles interceptors internally via IPC auto-subscribe".into(),
+            )
+        })?;
+        let instance = self
+            .instance
+            .as_ref()
+            .ok_or_else(|| CapsuleError::NotSupported("WASM component not instantiated".into()))?;
+
+        // Layer 3 (#666): resolve the invoking principal's quota profile
+        // BEFORE touching the store — a failed load denies the invocation
+        // without mutating state. Fail-closed: no fallback to the owner's
+        // limits. When the kernel didn't supply a cache (tests, single
+        // tenant), `invocation_profile` stays `None` and the defensive
+        // apply-block below uses the process-global default.
+        //
+        // Layer 6 (#672): if `profile.enabled = false`, refuse the
+        // invocation. The Layer 5 `authorize_request` preamble already
+        // gates the management API on this flag; this gate covers
+        // capsule invocations so `agent.disable` denies *every* surface
+        // a principal can drive, not just the admin IPC. In-flight
+        // invocations finish under the old value (we only check at
+        // entry); new invocations are refused.
+        let invocation_profile: Option<Arc<astrid_core::profile::PrincipalProfile>> = match self
+            .profile_cache
+            .as_ref()
+        {
+            Some(cache) => {
+                // Derive the invoking principal without locking the store —
+                // `owner_principal` captures the immutable `state.principal`
+                // at `load()` time, so the fallback path is allocation- and
+                // lock-free on the hot path.
+                let invoking = caller
+                    .and_then(|msg| msg.principal.as_deref())
+                    .and_then(|p| astrid_core::PrincipalId::new(p).ok())
+                    .or_else(|| self.owner_principal.clone())
+                    .unwrap_or_default();
+                let profile = cache.resolve(&invoking).map_err(|e| {
+                    tracing::error!(principal = %invoking, error = %e,
+                            "profile load failed; denying invocation (issue #666)");
+                    CapsuleError::WasmError(format!("principal '{invoking}' profile invalid: {e}"))
+                })?;
+                check_principal_enabled(
+                    &profile,
+                    &invoking,
+                    self.manifest.package.name.as_str(),
+                    action,
+                )?;
+                Some(profile)
+            },
+            None => None,
+        };
+        // Is the capsule a daemon (uplink / long-lived)? Daemons keep their
+        // load-time `u64::MAX` epoch deadline; only non-daemon capsules
+        // accept a per-invocation timeout from the profile.
+        let is_daemon = !self.manifest.uplinks.is_empty() || self.manifest.capabilities.uplink;
+
+        // Layer 4 (#668): resolve the per-principal overlay VFS. The
+        // resolved Arc is intentionally dropped — no host function reads
+        // through the overlay today, so storing it on HostState would be
+        // dead state. We still make the call for its side effects:
+        //
+        // 1. Fail-closed on resolve error. If the registry is configured
+        //    and tempdir creation or VFS mount registration fails, deny
+        //    the invocation rather than proceeding against a shared
+        //    workspace. Silent fallback would let Agent B observe Agent
+        //    A's writes — the exact invariant this layer upholds.
+        // 2. Warm the cache so the principal's per-isolation tempdir
+        //    exists and is reused across subsequent invocations, and so
+        //    the LRU-eviction accounting reflects actual usage.
+        //
+        // When a future layer routes production VFS operations through
+        // the overlay, that layer will add the field + accessor and
+        // consume the resolved `Arc<OverlayVfs>` here.
+        if let Some(registry) = self.overlay_registry.as_ref() {
+            let invoking = caller
+                .and_then(|msg| msg.principal.as_deref())
+                .and_then(|p| astrid_core::PrincipalId::new(p).ok())
+                .or_else(|| self.owner_principal.clone())
+                .unwrap_or_default();
+            let resolved = tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(registry.resolve(&invoking))
+            });
+            if let Err(e) = resolved {
+                tracing::error!(
+                    principal = %invoking,
+                    error = %e,
+                    "overlay registry resolve failed; denying invocation (issue #668)"
+                );
+                return Err(CapsuleError::WasmError(format!(
+                    "principal '{invoking}' overlay resolve failed: {e}"
+                )));
+            }
+        }
+
+        // Set per-invocation caller context, profile, KV, VFS, and
+        // epoch deadline under a single store lock. Recovers from
+        // poisoned mutex to prevent stale principal context from persisting.
+        {
+            let mut s = match store.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => {
+                    tracing::error!(
+                        "Store lock poisoned during set; recovering to prevent \
+                         principal context leak"
+                    );
+                    poisoned.into_inner()
+                },
+            };
+            // Always apply a profile — when the cache didn't produce one
+            // (tests, no-cache builds), fall back to the process-global
+            // default. Doing this unconditionally keeps limits / deadline
+            // consistent across invocations regardless of cache presence
+            // and prevents a prior invocation's cap from leaking forward
+            // through any future refactor that drops an invocation's
+            // profile mid-flow.
+            let applied_profile: Arc<astrid_core::profile::PrincipalProfile> =
+                invocation_profile.clone().unwrap_or_else(|| {
+                    Arc::new(astrid_core::profile::PrincipalProfile::default_ref().clone())
+                });
+
+            // Per-invocation deadline for non-daemon capsules, and the
+            // caller's cancellation, both checked by the epoch callback.
+            // Set under the store lock so they cannot leak into a
+            // concurrent invocation's window.
+            if !is_daemon {
+                self.guest_deadline.arm(Some(std::time::Duration::from_secs(
+                    applied_profile.quotas.max_timeout_secs,
+                )));
+            }
+            self.guest_deadline.set_cancel(cancel.cloned());
+
+            let state = s.data_mut();
+            state.caller_context = caller.cloned();
+            // Apply per-principal memory cap by rebuilding `StoreLimits`.
+            // The store's `limiter` callback reads this field on each
+            // `memory.grow`, so mutating in place takes effect for the
+            // upcoming call.
+            state.store_limits = wasmtime::StoreLimitsBuilder::new()
+                .memory_size(
+                    usize::try_from(applied_profile.quotas.max_memory_bytes).unwrap_or(usize::MAX),
+                )
+                .build();
+            state.invocation_profile = invocation_profile.clone();
+
+            // Derive the invocation principal once; reused for KV + VFS scoping.
+            let invocation_principal: Option<astrid_core::PrincipalId> = caller
+                .and_then(|msg| msg.principal.as_deref())
+                .and_then(|p| astrid_core::PrincipalId::new(p).ok())
+                .filter(|p| *p != state.principal);
+
+            // Dynamic KV scoping: if the invocation principal differs
+            // from the capsule's default, create a scoped KV store.
+            state.invocation_kv = invocation_principal.as_ref().and_then(|p| {
+                let ns = format!("{}:capsule:{}", p, state.capsule_id);
+                match state.kv.with_namespace(&ns) {
+                    Ok(kv) => Some(kv),
+                    Err(e) => {
+                        tracing::warn!(
+                            principal = %p,
+                            error = %e,
+                            "Failed to create invocation KV scope"
+                        );
+                        None
+                    },
+                }
+            });
+
+            // Dynamic home/tmp VFS scoping. Mirrors the KV pattern above:
+            // build a per-principal bundle if the invocation principal differs
+            // from the capsule's load-time principal, install the VFS + root
+            // handle + physical path on HostState, and clear them after the
+            // call returns. The bundle is intentionally built inline (no
+            // shared registry) because `HostVfs::new` + `DirHandle::new` +
+            // `register_dir` are lightweight; caching can be retrofitted later
+            // behind the same accessors if profiling shows it matters.
+            if let Some(ref p) = invocation_principal {
+                // VFS/log/secret builders below do blocking I/O (VFS
+                // `register_dir` via `block_on`, log `create_dir_all` + `open`,
+                // keychain probe inside `build_secret_store`). `invoke_interceptor`
+                // is called from async tasks (see `trigger_hook` fan-out), so
+                // wrap the blocking work in `block_in_place` to avoid stalling
+                // the tokio worker. Pruning is NOT performed here — that's
+                // load-time only (O(N) scan).
+                tokio::task::block_in_place(|| {
+                    let bundle = build_principal_vfs_bundle(p);
+                    state.invocation_home = bundle.home;
+                    state.invocation_tmp = bundle.tmp;
+
+                    // Per-invocation capsule log: opens (or silently falls
+                    // back to None for unregistered principals) under the
+                    // invoking principal's home. Host `astrid_log` routes
+                    // through `effective_capsule_log()`.
+                    state.invocation_capsule_log =
+                        open_capsule_log(p, state.capsule_id.as_str(), false);
+
+                    // Per-invocation secret store: built against the
+                    // invocation KV scope so both KV and keychain backends
+                    // are principal-isolated. `build_secret_store`'s
+                    // capsule_id is the keychain service name; combining it
+                    // with the principal keeps keychain entries scoped even
+                    // when the same capsule serves multiple principals.
+                    // If the invocation KV scope couldn't be built we leave
+                    // this as `None`, which causes `effective_secret_store`
+                    // to fall back to the load-time store — same
+                    // degrade-safely behavior as the KV scoping above.
+                    state.invocation_secret_store = state.invocation_kv.as_ref().map(|kv| {
+                        astrid_storage::build_secret_store(
+                            &format!("{}:{}", state.capsule_id, p),
+                            kv.clone(),
+                            state.runtime_handle.clone(),
+                        )
+                    });
+                });
+            }
+        }
+
+        // Call the typed Component Model export. The action name and payload
+        // are passed as separate typed parameters (no JSON envelope needed).
+        let result = tokio::task::block_in_place(|| {
+            let mut s = store
+                .lock()
+                .map_err(|e| CapsuleError::WasmError(format!("store lock poisoned: {e}")))?;
+            instance
+                .call_astrid_hook_trigger(&mut *s, action, payload)
+                .map_err(|e| {
+                    if e.downcast_ref::<wasmtime::Trap>().is_some() {
+                        CapsuleError::Trap(format!("astrid_hook_trigger trapped: {e:?}"))
+                    } else {
+                        CapsuleError::WasmError(format!("astrid_hook_trigger failed: {e:?}"))
+                    }
+                })
+        });
+
+        // Clear invocation context after call returns (success or error).
+        // Prevents stale principal/KV from leaking to any subsequent
+        // call path (tool execution, run-loop subscriptions).
+        // Recovers from poisoned mutex — principal isolation is critical.
+        {
+            let mut s = match store.lock() {
+                Ok(guard) => guard,
+                Err(poisoned) => {
+                    tracing::error!(
+                        "Store lock poisoned during post-invocation clear; \
+                         recovering to prevent principal context leak"
+                    );
+                    poisoned.into_inner()
+                },
+            };
+            self.guest_deadline.set_cancel(None);
+            let state = s.data_mut();
+            state.caller_context = None;
+            state.invocation_kv = None;
+            state.invocation_home = None;
+            state.invocation_tmp = None;
+            state.invocation_secret_store = None;
+            state.invocation_capsule_log = None;
+            state.invocation_profile = None;
+            state.close_open_files();
+        }
+
+        // Map the typed CapsuleResult to InterceptResult.
+        result.map(|cr| {
+            crate::capsule::InterceptResult::from_capsule_result(&cr.action, cr.data.as_deref())
+        })
+    }
+}
+
 #[async_trait]
 impl ExecutionEngine for WasmEngine {
     async fn load(&mut self, ctx: &CapsuleContext) -> CapsuleResult<()> {
@@ -489,6 +822,7 @@ impl ExecutionEngine for WasmEngine {
         let kv = ctx.kv.clone();
         let event_bus = astrid_events::EventBus::clone(&ctx.event_bus);
         let manifest = self.manifest.clone();
+        let guest_deadline = Arc::clone(&self.guest_deadline);
 
         let mut wasm_config = std::collections::HashMap::new();
 
@@ -678,6 +1012,7 @@ impl ExecutionEngine for WasmEngine {
                     config: wasm_config,
                     ipc_publish_patterns: manifest.capabilities.ipc_publish.clone(),
                     ipc_subscribe_patterns: manifest.capabilities.ipc_subscribe.clone(),
+                    call_targets: manifest.capabilities.call.clone(),
                     // Only provide the CLI socket listener if the capsule declares net_bind.
                     // This prevents unauthorized capsules from even seeing the listener.
                     cli_socket_listener: if manifest.capabilities.net_bind.is_empty() {
@@ -713,7 +1048,11 @@ impl ExecutionEngine for WasmEngine {
                     identity_store: ctx.identity_store.clone(),
                     background_processes: std::collections::HashMap::new(),
                     next_process_id: 1,
+                    open_files: std::collections::HashMap::new(),
+                    next_file_handle: 1,
                     process_tracker: process_tracker.clone(),
+                    audit: ctx.audit.clone(),
+                    workspace_mode: ctx.workspace_mode,
                 };
 
                 // Pre-scan WASM exports to detect run() before instantiation.
@@ -739,17 +1078,17 @@ impl ExecutionEngine for WasmEngine {
                 // (e.g. LLM providers) while still catching runaways.
                 let is_daemon = !manifest.uplinks.is_empty() || manifest.capabilities.uplink;
                 if !is_daemon && !has_run_export {
-                    // Each epoch tick is EPOCH_TICK_INTERVAL (100ms). Set the
-                    // deadline so total timeout ≈ WASM_CAPSULE_TIMEOUT_SECS.
-                    let deadline =
-                        WASM_CAPSULE_TIMEOUT_SECS * 1000 / EPOCH_TICK_INTERVAL.as_millis() as u64;
-                    store.set_epoch_deadline(deadline);
+                    guest_deadline.arm(Some(std::time::Duration::from_secs(
+                        WASM_CAPSULE_TIMEOUT_SECS,
+                    )));
                 } else {
-                    // Long-lived capsules: set deadline to u64::MAX so the epoch
-                    // ticker doesn't trap them. Without this, the default deadline
-                    // of 0 would cause an immediate trap on the first tick.
-                    store.set_epoch_deadline(u64::MAX);
+                    guest_deadline.arm(None);
                 }
+                // Check the deadline on every epoch tick (EPOCH_TICK_INTERVAL)
+                // so a cancelled call is interrupted promptly.
+                store.set_epoch_deadline(1);
+                let deadline_check = Arc::clone(&guest_deadline);
+                store.epoch_deadline_callback(move |_| Ok(deadline_check.check()));
 
                 let mut linker: Linker<HostState> = Linker::new(&wt_engine);
 
@@ -1023,263 +1362,17 @@ impl ExecutionEngine for WasmEngine {
         payload: &[u8],
         caller: Option<&astrid_events::ipc::IpcMessage>,
     ) -> CapsuleResult<crate::capsule::InterceptResult> {
-        let store = self.store.as_ref().ok_or_else(|| {
-            CapsuleError::NotSupported(
-                "plugin handles interceptors internally via IPC auto-subscribe".into(),
-            )
-        })?;
-        let instance = self
-            .instance
-            .as_ref()
-            .ok_or_else(|| CapsuleError::NotSupported("WASM component not instantiated".into()))?;
-
-        // Layer 3 (#666): resolve the invoking principal's quota profile
-        // BEFORE touching the store — a failed load denies the invocation
-        // without mutating state. Fail-closed: no fallback to the owner's
-        // limits. When the kernel didn't supply a cache (tests, single
-        // tenant), `invocation_profile` stays `None` and the defensive
-        // apply-block below uses the process-global default.
-        //
-        // Layer 6 (#672): if `profile.enabled = false`, refuse the
-        // invocation. The Layer 5 `authorize_request` preamble already
-        // gates the management API on this flag; this gate covers
-        // capsule invocations so `agent.disable` denies *every* surface
-        // a principal can drive, not just the admin IPC. In-flight
-        // invocations finish under the old value (we only check at
-        // entry); new invocations are refused.
-        let invocation_profile: Option<Arc<astrid_core::profile::PrincipalProfile>> = match self
-            .profile_cache
-            .as_ref()
-        {
-            Some(cache) => {
-                // Derive the invoking principal without locking the store —
-                // `owner_principal` captures the immutable `state.principal`
-                // at `load()` time, so the fallback path is allocation- and
-                // lock-free on the hot path.
-                let invoking = caller
-                    .and_then(|msg| msg.principal.as_deref())
-                    .and_then(|p| astrid_core::PrincipalId::new(p).ok())
-                    .or_else(|| self.owner_principal.clone())
-                    .unwrap_or_default();
-                let profile = cache.resolve(&invoking).map_err(|e| {
-                    tracing::error!(principal = %invoking, error = %e,
-                            "profile load failed; denying invocation (issue #666)");
-                    CapsuleError::WasmError(format!("principal '{invoking}' profile invalid: {e}"))
-                })?;
-                check_principal_enabled(
-                    &profile,
-                    &invoking,
-                    self.manifest.package.name.as_str(),
-                    action,
-                )?;
-                Some(profile)
-            },
-            None => None,
-        };
-        // Is the capsule a daemon (uplink / long-lived)? Daemons keep their
-        // load-time `u64::MAX` epoch deadline; only non-daemon capsules
-        // accept a per-invocation timeout from the profile.
-        let is_daemon = !self.manifest.uplinks.is_empty() || self.manifest.capabilities.uplink;
-
-        // Layer 4 (#668): resolve the per-principal overlay VFS. The
-        // resolved Arc is intentionally dropped — no host function reads
-        // through the overlay today, so storing it on HostState would be
-        // dead state. We still make the call for its side effects:
-        //
-        // 1. Fail-closed on resolve error. If the registry is configured
-        //    and tempdir creation or VFS mount registration fails, deny
-        //    the invocation rather than proceeding against a shared
-        //    workspace. Silent fallback would let Agent B observe Agent
-        //    A's writes — the exact invariant this layer upholds.
-        // 2. Warm the cache so the principal's per-isolation tempdir
-        //    exists and is reused across subsequent invocations, and so
-        //    the LRU-eviction accounting reflects actual usage.
-        //
-        // When a future layer routes production VFS operations through
-        // the overlay, that layer will add the field + accessor and
-        // consume the resolved `Arc<OverlayVfs>` here.
-        if let Some(registry) = self.overlay_registry.as_ref() {
-            let invoking = caller
-                .and_then(|msg| msg.principal.as_deref())
-                .and_then(|p| astrid_core::PrincipalId::new(p).ok())
-                .or_else(|| self.owner_principal.clone())
-                .unwrap_or_default();
-            let resolved = tokio::task::block_in_place(|| {
-                tokio::runtime::Handle::current().block_on(registry.resolve(&invoking))
-            });
-            if let Err(e) = resolved {
-                tracing::error!(
-                    principal = %invoking,
-                    error = %e,
-                    "overlay registry resolve failed; denying invocation (issue #668)"
-                );
-                return Err(CapsuleError::WasmError(format!(
-                    "principal '{invoking}' overlay resolve failed: {e}"
-                )));
-            }
-        }
-
-        // Set per-invocation caller context, profile, KV, VFS, and
-        // epoch deadline under a single store lock. Recovers from
-        // poisoned mutex to prevent stale principal context from persisting.
-        {
-            let mut s = match store.lock() {
-                Ok(guard) => guard,
-                Err(poisoned) => {
-                    tracing::error!(
-                        "Store lock poisoned during set; recovering to prevent \
-                         principal context leak"
-                    );
-                    poisoned.into_inner()
-                },
-            };
-            // Always apply a profile — when the cache didn't produce one
-            // (tests, no-cache builds), fall back to the process-global
-            // default. Doing this unconditionally keeps limits / deadline
-            // consistent across invocations regardless of cache presence
-            // and prevents a prior invocation's cap from leaking forward
-            // through any future refactor that drops an invocation's
-            // profile mid-flow.
-            let applied_profile: Arc<astrid_core::profile::PrincipalProfile> =
-                invocation_profile.clone().unwrap_or_else(|| {
-                    Arc::new(astrid_core::profile::PrincipalProfile::default_ref().clone())
-                });
-
-            // Per-invocation epoch deadline for non-daemon capsules. The
-            // store's epoch deadline is configured on the `Store`, not
-            // inside `HostState`, so this call goes through the mutable
-            // store guard above rather than `state`.
-            if !is_daemon {
-                let deadline = applied_profile.quotas.max_timeout_secs.saturating_mul(1000)
-                    / EPOCH_TICK_INTERVAL.as_millis() as u64;
-                s.set_epoch_deadline(deadline);
-            }
-
-            let state = s.data_mut();
-            state.caller_context = caller.cloned();
-            // Apply per-principal memory cap by rebuilding `StoreLimits`.
-            // The store's `limiter` callback reads this field on each
-            // `memory.grow`, so mutating in place takes effect for the
-            // upcoming call.
-            state.store_limits = wasmtime::StoreLimitsBuilder::new()
-                .memory_size(
-                    usize::try_from(applied_profile.quotas.max_memory_bytes).unwrap_or(usize::MAX),
-                )
-                .build();
-            state.invocation_profile = invocation_profile.clone();
-
-            // Derive the invocation principal once; reused for KV + VFS scoping.
-            let invocation_principal: Option<astrid_core::PrincipalId> = caller
-                .and_then(|msg| msg.principal.as_deref())
-                .and_then(|p| astrid_core::PrincipalId::new(p).ok())
-                .filter(|p| *p != state.principal);
-
-            // Dynamic KV scoping: if the invocation principal differs
-            // from the capsule's default, create a scoped KV store.
-            state.invocation_kv = invocation_principal.as_ref().and_then(|p| {
-                let ns = format!("{}:capsule:{}", p, state.capsule_id);
-                match state.kv.with_namespace(&ns) {
-                    Ok(kv) => Some(kv),
-                    Err(e) => {
-                        tracing::warn!(
-                            principal = %p,
-                            error = %e,
-                            "Failed to create invocation KV scope"
-                        );
-                        None
-                    },
-                }
-            });
-
-            // Dynamic home/tmp VFS scoping. Mirrors the KV pattern above:
-            // build a per-principal bundle if the invocation principal differs
-            // from the capsule's load-time principal, install the VFS + root
-            // handle + physical path on HostState, and clear them after the
-            // call returns. The bundle is intentionally built inline (no
-            // shared registry) because `HostVfs::new` + `DirHandle::new` +
-            // `register_dir` are lightweight; caching can be retrofitted later
-            // behind the same accessors if profiling shows it matters.
-            if let Some(ref p) = invocation_principal {
-                // VFS/log/secret builders below do blocking I/O (VFS
-                // `register_dir` via `block_on`, log `create_dir_all` + `open`,
-                // keychain probe inside `build_secret_store`). `invoke_interceptor`
-                // is called from async tasks (see `trigger_hook` fan-out), so
-                // wrap the blocking work in `block_in_place` to avoid stalling
-                // the tokio worker. Pruning is NOT performed here — that's
-                // load-time only (O(N) scan).
-                tokio::task::block_in_place(|| {
-                    let bundle = build_principal_vfs_bundle(p);
-                    state.invocation_home = bundle.home;
-                    state.invocation_tmp = bundle.tmp;
-
-                    // Per-invocation capsule log: opens (or silently falls
-                    // back to None for unregistered principals) under the
-                    // invoking principal's home. Host `astrid_log` routes
-                    // through `effective_capsule_log()`.
-                    state.invocation_capsule_log =
-                        open_capsule_log(p, state.capsule_id.as_str(), false);
-
-                    // Per-invocation secret store: built against the
-                    // invocation KV scope so both KV and keychain backends
-                    // are principal-isolated. `build_secret_store`'s
-                    // capsule_id is the keychain service name; combining it
-                    // with the principal keeps keychain entries scoped even
-                    // when the same capsule serves multiple principals.
-                    // If the invocation KV scope couldn't be built we leave
-                    // this as `None`, which causes `effective_secret_store`
-                    // to fall back to the load-time store — same
-                    // degrade-safely behavior as the KV scoping above.
-                    state.invocation_secret_store = state.invocation_kv.as_ref().map(|kv| {
-                        astrid_storage::build_secret_store(
-                            &format!("{}:{}", state.capsule_id, p),
-                            kv.clone(),
-                            state.runtime_handle.clone(),
-                        )
-                    });
-                });
-            }
-        }
-
-        // Call the typed Component Model export. The action name and payload
-        // are passed as separate typed parameters (no JSON envelope needed).
-        let result = tokio::task::block_in_place(|| {
-            let mut s = store
-                .lock()
-                .map_err(|e| CapsuleError::WasmError(format!("store lock poisoned: {e}")))?;
-            instance
-                .call_astrid_hook_trigger(&mut *s, action, payload)
-                .map_err(|e| CapsuleError::WasmError(format!("astrid_hook_trigger failed: {e:?}")))
-        });
-
-        // Clear invocation context after call returns (success or error).
-        // Prevents stale principal/KV from leaking to any subsequent
-        // call path (tool execution, run-loop subscriptions).
-        // Recovers from poisoned mutex — principal isolation is critical.
-        {
-            let mut s = match store.lock() {
-                Ok(guard) => guard,
-                Err(poisoned) => {
-                    tracing::error!(
-                        "Store lock poisoned during post-invocation clear; \
-                         recovering to prevent principal context leak"
-                    );
-                    poisoned.into_inner()
-                },
-            };
-            let state = s.data_mut();
-            state.caller_context = None;
-            state.invocation_kv = None;
-            state.invocation_home = None;
-            state.invocation_tmp = None;
-            state.invocation_secret_store = None;
-            state.invocation_capsule_log = None;
-            state.invocation_profile = None;
-        }
+        self.invoke(action, payload, caller, None)
+    }
 
-        // Map the typed CapsuleResult to InterceptResult.
-        result.map(|cr| {
-            crate::capsule::InterceptResult::from_capsule_result(&cr.action, cr.data.as_deref())
-        })
+    fn invoke_interceptor_cancellable(
+        &self,
+        action: &str,
+        payload: &[u8],
+        caller: Option<&astrid_events::ipc::IpcMessage>,
+        cancel: &tokio_util::sync::CancellationToken,
+    ) -> CapsuleResult<crate::capsule::InterceptResult> {
+        self.invoke(action, payload, caller, Some(cancel))
     }
 
     fn check_health(&self) -> crate::capsule::CapsuleState {
@@ -1401,6 +1494,7 @@ pub fn run_lifecycle(
         config: cfg.config,
         ipc_publish_patterns: Vec::new(),
         ipc_subscribe_patterns: Vec::new(),
+        call_targets: Vec::new(),
         security: None,
         hook_manager: None,
         capsule_registry: None,
@@ -1424,7 +1518,11 @@ pub fn run_lifecycle(
         identity_store: None,
         background_processes: std::collections::HashMap::new(),
         next_process_id: 1,
+        open_files: std::collections::HashMap::new(),
+        next_file_handle: 1,
         process_tracker: Arc::new(host::process::ProcessTracker::new()),
+        audit: None,
+        workspace_mode: astrid_workspace::WorkspaceMode::Safe,
     };
 
     // Build wasmtime engine and store for lifecycle execution.
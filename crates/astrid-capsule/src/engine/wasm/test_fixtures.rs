@@ -101,6 +101,7 @@ pub(crate) fn minimal_host_state(rt: tokio::runtime::Handle) -> HostState {
         config: HashMap::new(),
         ipc_publish_patterns: Vec::new(),
         ipc_subscribe_patterns: Vec::new(),
+        call_targets: Vec::new(),
         security: None,
         hook_manager: None,
         capsule_registry: None,
@@ -124,6 +125,10 @@ pub(crate) fn minimal_host_state(rt: tokio::runtime::Handle) -> HostState {
         identity_store: None,
         background_processes: HashMap::new(),
         next_process_id: 1,
+        open_files: HashMap::new(),
+        next_file_handle: 1,
         process_tracker: Arc::new(ProcessTracker::new()),
+        audit: None,
+        workspace_mode: astrid_workspace::WorkspaceMode::Safe,
     }
 }
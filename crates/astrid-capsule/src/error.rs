@@ -21,6 +21,11 @@ pub enum CapsuleError {
     /// An error originated inside the WASM VM runtime.
     #[error("WASM error: {0}")]
     WasmError(String),
+    /// The guest trapped while running (e.g. `unreachable`, an out-of-bounds
+    /// access, or fuel exhaustion). Unlike [`WasmError`](Self::WasmError),
+    /// this is always the guest's own fault, never a host-side denial.
+    #[error("WASM trap: {0}")]
+    Trap(String),
     /// The requested capsule was not found in the registry.
     #[error("Not found: {0}")]
     NotFound(String),
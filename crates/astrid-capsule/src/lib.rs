@@ -9,6 +9,7 @@
 
 pub mod capsule;
 pub mod context;
+pub mod cron;
 pub mod discovery;
 pub mod dispatcher;
 pub mod engine;
@@ -61,6 +61,12 @@ pub struct CapsuleManifest {
     /// Topic API declarations describing the payload shape of IPC topics.
     #[serde(default, rename = "topic")]
     pub topics: Vec<TopicDef>,
+    /// Static cron jobs the kernel triggers on a schedule.
+    #[serde(default, rename = "cron")]
+    pub cron_jobs: Vec<CronDef>,
+    /// When the capsule counts as failed and whether the kernel restarts it.
+    #[serde(default)]
+    pub health: HealthDef,
 }
 
 impl CapsuleManifest {
@@ -305,6 +311,19 @@ pub struct CapabilitiesDef {
     /// An empty list means NO identity access (fail-closed).
     #[serde(default)]
     pub identity: Vec<String>,
+    /// Capsules this capsule may invoke directly with `ipc-call`.
+    ///
+    /// Each entry is a target capsule ID; `"*"` allows any loaded capsule.
+    /// An empty list means NO direct calls (fail-closed).
+    #[serde(default)]
+    pub call: Vec<String>,
+    /// Secret names this capsule may read with `get-secret`.
+    ///
+    /// Secrets are kept out of plain config: only names listed here can be
+    /// read back, and every read is audited. An empty list means NO secret
+    /// reads (fail-closed).
+    #[serde(default)]
+    pub secrets: Vec<String>,
     /// Whether the capsule may override or modify the system prompt via the
     /// prompt builder's hook pipeline.
     ///
@@ -431,6 +450,69 @@ const fn default_interceptor_priority() -> u32 {
     100
 }
 
+/// A static cron job registered by the capsule.
+///
+/// Each time `schedule` matches, the kernel invokes `astrid_hook_trigger`
+/// with the action `astrid_cron_trigger` and a JSON payload carrying the
+/// job `name` and its scheduled `fire_time`. See [`crate::cron`] for the
+/// schedule syntax.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CronDef {
+    /// Job name, unique within the capsule (e.g. `poll-gateway`).
+    pub name: String,
+    /// Cron expression, seconds first: `sec min hour day month weekday`.
+    pub schedule: String,
+    /// Whether the job starts enabled. Default `true`.
+    #[serde(default = "default_cron_enabled")]
+    pub enabled: bool,
+}
+
+/// Cron jobs start enabled unless the manifest says otherwise.
+const fn default_cron_enabled() -> bool {
+    true
+}
+
+/// Crash handling for a capsule (`[health]` in `Capsule.toml`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthDef {
+    /// What the kernel does once the capsule has failed. Default
+    /// `on-failure`.
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    /// Consecutive interceptor traps after which the capsule counts as
+    /// failed. Any successful invocation resets the count. Default 3.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+}
+
+impl Default for HealthDef {
+    fn default() -> Self {
+        Self {
+            restart: RestartPolicy::default(),
+            failure_threshold: default_failure_threshold(),
+        }
+    }
+}
+
+/// Three traps in a row mark a capsule failed unless the manifest says
+/// otherwise.
+const fn default_failure_threshold() -> u32 {
+    3
+}
+
+/// Whether the kernel restarts a failed capsule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// Leave the capsule failed.
+    Never,
+    /// Restart with exponential backoff, giving up after a few attempts.
+    #[default]
+    OnFailure,
+    /// Restart with exponential backoff for as long as it keeps failing.
+    Always,
+}
+
 /// Direction a capsule interacts with an IPC topic.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
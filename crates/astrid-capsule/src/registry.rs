@@ -4,14 +4,17 @@
 //! all registered capsules.
 
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, PoisonError};
 
+use chrono::{DateTime, Local, Utc};
 use tracing::{debug, info};
 use uuid::Uuid;
 
 use astrid_core::{UplinkCapabilities, UplinkDescriptor, UplinkId};
 
-use crate::capsule::{Capsule, CapsuleId};
+use crate::capsule::{Capsule, CapsuleId, CapsuleState};
+use crate::cron::{CronJobStatus, CronScheduler, CronTick};
+use crate::engine::wasm::host::ipc::InFlightCalls;
 use crate::error::{CapsuleError, CapsuleResult};
 
 /// Registry of loaded capsules.
@@ -27,6 +30,14 @@ pub struct CapsuleRegistry {
     /// an IPC `source_id` (a UUID stamped by the kernel) back to the
     /// originating capsule for capability checks.
     uuid_map: HashMap<Uuid, CapsuleId>,
+    /// Static `[[cron]]` jobs of registered capsules, in local time.
+    cron: CronScheduler<Local>,
+    /// Consecutive interceptor traps per capsule. Behind a mutex so the
+    /// dispatcher can record outcomes under the registry's read lock.
+    traps: Mutex<HashMap<CapsuleId, u32>>,
+    /// `ipc-call`s in progress between registered capsules. Shared so a
+    /// call can hold its edge without holding the registry lock.
+    in_flight: Arc<InFlightCalls>,
 }
 
 impl CapsuleRegistry {
@@ -37,6 +48,9 @@ impl CapsuleRegistry {
             capsules: HashMap::new(),
             uplinks: HashMap::new(),
             uuid_map: HashMap::new(),
+            cron: CronScheduler::new(Local),
+            traps: Mutex::new(HashMap::new()),
+            in_flight: Arc::default(),
         }
     }
 
@@ -45,7 +59,8 @@ impl CapsuleRegistry {
     /// # Errors
     ///
     /// Returns [`CapsuleError::AlreadyRegistered`] if a capsule with the same
-    /// ID is already in the registry.
+    /// ID is already in the registry, or if one of its `[[cron]]` schedules
+    /// does not parse.
     pub fn register(&mut self, capsule: Box<dyn Capsule>) -> CapsuleResult<()> {
         let capsule: Arc<dyn Capsule> = Arc::from(capsule);
         let id = capsule.id().clone();
@@ -55,6 +70,10 @@ impl CapsuleRegistry {
             )));
         }
 
+        self.cron
+            .add_capsule(&id, &capsule.manifest().cron_jobs, Utc::now())
+            .map_err(|e| CapsuleError::UnsupportedEntryPoint(format!("[[cron]] in {id}: {e}")))?;
+
         // Register the capsule's uplinks (uplinks)
         let mut registered_ids: Vec<UplinkId> = Vec::new();
         for uplink in &capsule.manifest().uplinks {
@@ -75,6 +94,7 @@ impl CapsuleRegistry {
                     for rollback_id in &registered_ids {
                         self.uplinks.remove(rollback_id);
                     }
+                    self.cron.remove_capsule(&id);
                     return Err(e);
                 },
             }
@@ -101,6 +121,8 @@ impl CapsuleRegistry {
 
         // Clean up UUID mapping for this capsule.
         self.uuid_map.retain(|_, cid| cid != id);
+        self.cron.remove_capsule(id);
+        self.traps_mut().remove(id);
 
         info!(capsule_id = %id, "Unregistered capsule");
         Ok(capsule)
@@ -235,14 +257,126 @@ impl CapsuleRegistry {
         self.uplinks.values().map(|(_, desc)| desc).collect()
     }
 
+    // -----------------------------------------------------------------
+    // Cron jobs
+    // -----------------------------------------------------------------
+
+    /// Every `[[cron]]` job of the registered capsules, with its next fire
+    /// time.
+    #[must_use]
+    pub fn cron_jobs(&self) -> Vec<CronJobStatus> {
+        self.cron.jobs()
+    }
+
+    /// The `[[cron]]` jobs declared by one capsule.
+    pub fn capsule_cron_jobs(&self, capsule_id: &CapsuleId) -> Vec<CronJobStatus> {
+        self.cron
+            .jobs()
+            .into_iter()
+            .filter(|job| job.capsule_id == *capsule_id)
+            .collect()
+    }
+
+    /// Enable or disable a capsule's cron job at runtime.
+    ///
+    /// The change lasts until the capsule is next registered, which resets
+    /// the job to its manifest setting.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CapsuleError::NotFound`] if the capsule has no job named
+    /// `name`.
+    pub fn set_cron_enabled(
+        &mut self,
+        capsule_id: &CapsuleId,
+        name: &str,
+        enabled: bool,
+    ) -> CapsuleResult<()> {
+        if self.cron.set_enabled(capsule_id, name, enabled, Utc::now()) {
+            info!(capsule_id = %capsule_id, job = name, enabled, "Updated cron job");
+            Ok(())
+        } else {
+            Err(CapsuleError::NotFound(format!(
+                "cron job {name} in capsule {capsule_id}"
+            )))
+        }
+    }
+
+    /// The earliest upcoming cron fire time, if any job is enabled.
+    #[must_use]
+    pub fn next_cron_fire(&self) -> Option<DateTime<Utc>> {
+        self.cron.next_fire()
+    }
+
+    /// Take every cron job due at `now`, ordered by fire time.
+    pub fn take_due_cron_ticks(&mut self, now: DateTime<Utc>) -> Vec<CronTick> {
+        self.cron.take_due(now)
+    }
+
     /// Remove and return all capsules, clearing uplinks too.
     ///
     /// Used during kernel shutdown to unload everything in one pass.
     pub fn drain(&mut self) -> Vec<Arc<dyn Capsule>> {
         self.uplinks.clear();
         self.uuid_map.clear();
+        self.cron = CronScheduler::new(Local);
+        self.traps_mut().clear();
         self.capsules.drain().map(|(_, c)| c).collect()
     }
+
+    // -----------------------------------------------------------------
+    // Crash tracking
+    // -----------------------------------------------------------------
+
+    /// Record whether an interceptor invocation of a registered capsule
+    /// trapped, returning the capsule's current run of consecutive traps.
+    ///
+    /// Any invocation that did not trap ends the run. Outcomes for
+    /// capsules that are no longer registered are ignored.
+    pub fn record_interceptor_result(&self, capsule_id: &CapsuleId, trapped: bool) -> u32 {
+        if !self.capsules.contains_key(capsule_id) {
+            return 0;
+        }
+        let mut traps = self.traps_mut();
+        if trapped {
+            let count = traps.entry(capsule_id.clone()).or_insert(0);
+            *count = count.saturating_add(1);
+            *count
+        } else {
+            traps.remove(capsule_id);
+            0
+        }
+    }
+
+    /// The capsule's current run of consecutive interceptor traps.
+    pub fn consecutive_traps(&self, capsule_id: &CapsuleId) -> u32 {
+        self.traps_mut().get(capsule_id).copied().unwrap_or(0)
+    }
+
+    /// Health of a registered capsule.
+    ///
+    /// A capsule whose consecutive traps reached the `failure_threshold` of
+    /// its `[health]` section is failed even if its engines look healthy;
+    /// otherwise this is [`Capsule::check_health`].
+    pub fn health(&self, capsule_id: &CapsuleId) -> Option<CapsuleState> {
+        let capsule = self.capsules.get(capsule_id)?;
+        let traps = self.consecutive_traps(capsule_id);
+        if traps >= capsule.manifest().health.failure_threshold {
+            return Some(CapsuleState::Failed(format!(
+                "{traps} consecutive interceptor traps"
+            )));
+        }
+        Some(capsule.check_health())
+    }
+
+    /// The table of `ipc-call`s in progress between registered capsules.
+    pub(crate) fn in_flight_calls(&self) -> Arc<InFlightCalls> {
+        Arc::clone(&self.in_flight)
+    }
+
+    fn traps_mut(&self) -> std::sync::MutexGuard<'_, HashMap<CapsuleId, u32>> {
+        self.traps.lock().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 impl Default for CapsuleRegistry {
@@ -317,6 +451,8 @@ mod tests {
                     uplinks: Vec::new(),
                     interceptors: Vec::new(),
                     topics: Vec::new(),
+                    cron_jobs: Vec::new(),
+                    health: Default::default(),
                 },
                 semaphore: Arc::new(Semaphore::new(4)),
             }
@@ -383,6 +519,100 @@ mod tests {
         }
     }
 
+    #[test]
+    fn consecutive_traps_mark_capsule_failed() {
+        let mut registry = CapsuleRegistry::new();
+        let mut capsule = MockCapsule::new("flaky");
+        capsule.manifest.health.failure_threshold = 2;
+        let id = capsule.id.clone();
+        registry.register(Box::new(capsule)).unwrap();
+
+        assert_eq!(registry.record_interceptor_result(&id, true), 1);
+        assert_eq!(registry.health(&id), Some(CapsuleState::Ready));
+        // A successful call ends the run.
+        assert_eq!(registry.record_interceptor_result(&id, false), 0);
+        assert_eq!(registry.record_interceptor_result(&id, true), 1);
+        assert_eq!(registry.record_interceptor_result(&id, true), 2);
+        assert!(matches!(
+            registry.health(&id),
+            Some(CapsuleState::Failed(reason)) if reason.contains("2 consecutive")
+        ));
+
+        // A reloaded copy starts clean; unknown capsules are not tracked.
+        registry.unregister(&id).unwrap();
+        assert_eq!(registry.consecutive_traps(&id), 0);
+        assert_eq!(registry.record_interceptor_result(&id, true), 0);
+        assert_eq!(registry.health(&id), None);
+    }
+
+    #[test]
+    fn cron_jobs_follow_capsule_registration() {
+        let mut registry = CapsuleRegistry::new();
+        let mut capsule = MockCapsule::new("poller");
+        capsule.manifest.cron_jobs = vec![crate::manifest::CronDef {
+            name: "poll-gateway".into(),
+            schedule: "* * * * * *".into(),
+            enabled: true,
+        }];
+        registry.register(Box::new(capsule)).expect("register");
+
+        let id = CapsuleId::from_static("poller");
+        let jobs = registry.cron_jobs();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].name, "poll-gateway");
+        assert!(jobs[0].next_fire.is_some());
+        assert_eq!(registry.next_cron_fire(), jobs[0].next_fire);
+
+        registry
+            .set_cron_enabled(&id, "poll-gateway", false)
+            .expect("disable");
+        assert_eq!(registry.next_cron_fire(), None);
+        assert!(matches!(
+            registry.set_cron_enabled(&id, "missing", true),
+            Err(CapsuleError::NotFound(_))
+        ));
+
+        registry.unregister(&id).expect("unregister");
+        assert!(registry.cron_jobs().is_empty());
+    }
+
+    #[test]
+    fn capsule_cron_jobs_only_lists_that_capsule() {
+        let mut registry = CapsuleRegistry::new();
+        for (name, job) in [("alpha", "sweep"), ("beta", "sync")] {
+            let mut capsule = MockCapsule::new(name);
+            capsule.manifest.cron_jobs = vec![crate::manifest::CronDef {
+                name: job.into(),
+                schedule: "0 * * * *".into(),
+                enabled: true,
+            }];
+            registry.register(Box::new(capsule)).expect("register");
+        }
+
+        let jobs = registry.capsule_cron_jobs(&CapsuleId::from_static("alpha"));
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].name, "sweep");
+        assert!(
+            registry
+                .capsule_cron_jobs(&CapsuleId::from_static("gamma"))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn register_rejects_invalid_cron_schedule() {
+        let mut registry = CapsuleRegistry::new();
+        let mut capsule = MockCapsule::new("broken");
+        capsule.manifest.cron_jobs = vec![crate::manifest::CronDef {
+            name: "tick".into(),
+            schedule: "every minute".into(),
+            enabled: true,
+        }];
+        let err = registry.register(Box::new(capsule)).unwrap_err();
+        assert!(err.to_string().contains("invalid cron schedule"), "{err}");
+        assert!(registry.is_empty());
+    }
+
     #[test]
     fn uuid_mapping_register_and_find() {
         let mut registry = CapsuleRegistry::new();
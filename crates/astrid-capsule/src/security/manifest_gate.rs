@@ -281,6 +281,22 @@ impl CapsuleSecurityGate for ManifestSecurityGate {
             ))
         }
     }
+
+    async fn check_secret_read(&self, capsule_id: &str, name: &str) -> Result<(), String> {
+        if self
+            .manifest
+            .capabilities
+            .secrets
+            .iter()
+            .any(|declared| declared == name)
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "capsule '{capsule_id}' denied: secret '{name}' not declared in manifest"
+            ))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -325,6 +341,8 @@ mod tests {
                 ipc_publish: vec![],
                 ipc_subscribe: vec![],
                 identity: vec![],
+                call: vec![],
+                secrets: vec![],
                 allow_prompt_injection: false,
             },
             env: Default::default(),
@@ -335,6 +353,8 @@ mod tests {
             uplinks: vec![],
             interceptors: vec![],
             topics: vec![],
+            cron_jobs: vec![],
+            health: Default::default(),
         }
     }
 
@@ -684,6 +704,23 @@ mod tests {
         assert!(gate3.check_net_bind("test").await.is_err());
     }
 
+    #[tokio::test]
+    async fn secret_read_requires_declared_name() {
+        let manifest = make_manifest(vec![], vec![], vec![]);
+        let gate = ManifestSecurityGate::new(manifest, workspace_root(), None);
+        assert!(gate.check_secret_read("test", "api_key").await.is_err());
+
+        let mut manifest2 = make_manifest(vec![], vec![], vec![]);
+        manifest2.capabilities.secrets = vec!["api_key".into()];
+        let gate2 = ManifestSecurityGate::new(manifest2, workspace_root(), None);
+        assert!(gate2.check_secret_read("test", "api_key").await.is_ok());
+        let err = gate2
+            .check_secret_read("test", "other_key")
+            .await
+            .unwrap_err();
+        assert!(err.contains("not declared in manifest"), "got: {err}");
+    }
+
     #[tokio::test]
     async fn identity_gate_deny_by_default() {
         let manifest = make_manifest(vec![], vec![], vec![]);
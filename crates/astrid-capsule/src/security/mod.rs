@@ -13,6 +13,10 @@ mod test_gates;
 
 pub(crate) use manifest_gate::ManifestSecurityGate;
 
+/// Resource prefix on the `CapabilityChecked` events published for secret
+/// reads, e.g. `secret:api_key`.
+pub const SECRET_RESOURCE_PREFIX: &str = "secret:";
+
 /// Identity operations that can be gated by the security gate.
 ///
 /// Typed enum prevents string-matching bugs. Each variant maps to a
@@ -152,4 +156,13 @@ pub trait CapsuleSecurityGate: Send + Sync {
             operation
         ))
     }
+
+    /// Check whether the capsule is allowed to read the named secret.
+    ///
+    /// Default implementation denies all secret reads (fail-closed).
+    async fn check_secret_read(&self, capsule_id: &str, name: &str) -> Result<(), String> {
+        Err(format!(
+            "capsule '{capsule_id}' denied: secret '{name}' not permitted (default)"
+        ))
+    }
 }
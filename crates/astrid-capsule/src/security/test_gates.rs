@@ -65,6 +65,10 @@ impl CapsuleSecurityGate for AllowAllGate {
     ) -> Result<(), String> {
         Ok(())
     }
+
+    async fn check_secret_read(&self, _capsule_id: &str, _name: &str) -> Result<(), String> {
+        Ok(())
+    }
 }
 
 /// Security gate that denies all operations (for testing).
@@ -232,6 +232,8 @@ mod tests {
             uplinks: Vec::new(),
             interceptors: Vec::new(),
             topics: Vec::new(),
+            cron_jobs: Vec::new(),
+            health: Default::default(),
         };
         (m, PathBuf::from(format!("/capsules/{name}")))
     }
@@ -6,6 +6,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use anyhow::{Context, bail};
 use astrid_capsule::discovery::load_manifest;
 use astrid_core::dirs::AstridHome;
+use astrid_crypto::ContentHash;
 
 use super::meta::{BakedTopic, CapsuleMeta, read_meta, write_meta};
 
@@ -949,7 +950,7 @@ fn regenerate_distro_lock(home: &AstridHome) -> anyhow::Result<()> {
 /// Content-address WASM binaries into the shared `bin/` directory.
 ///
 /// Finds `.wasm` files in the capsule target directory, hashes them with
-/// BLAKE3, copies to `bin/{hash}.wasm`, and removes the original from the
+/// BLAKE3 without loading them into memory, copies to `bin/{hash}.wasm`, and removes the original from the
 /// capsule directory. Returns the hash if a WASM binary was processed.
 fn content_address_wasm(
     home: &AstridHome,
@@ -970,16 +971,17 @@ fn content_address_wasm(
         return Ok(None);
     }
 
-    let wasm_bytes = std::fs::read(&wasm_path)
-        .with_context(|| format!("failed to read WASM binary: {}", wasm_path.display()))?;
-
-    let hash = blake3::hash(&wasm_bytes).to_hex().to_string();
+    let wasm_file = std::fs::File::open(&wasm_path)
+        .with_context(|| format!("failed to open WASM binary: {}", wasm_path.display()))?;
+    let hash = ContentHash::hash_file_streaming(std::io::BufReader::new(wasm_file))
+        .with_context(|| format!("failed to read WASM binary: {}", wasm_path.display()))?
+        .to_hex();
     let bin_dir = home.bin_dir();
     std::fs::create_dir_all(&bin_dir)?;
 
     let dest = bin_dir.join(format!("{hash}.wasm"));
     if !dest.exists() {
-        std::fs::write(&dest, &wasm_bytes)?;
+        std::fs::copy(&wasm_path, &dest)?;
     }
 
     // Remove the WASM from the capsule dir — it now lives in bin/
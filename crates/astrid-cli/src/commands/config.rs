@@ -10,6 +10,7 @@ pub(crate) fn show_config(format: &str, section: Option<&str>) -> Result<()> {
 
     let show_format = match format {
         "json" => ShowFormat::Json,
+        "detailed" => ShowFormat::Detailed,
         _ => ShowFormat::Toml,
     };
 
@@ -214,6 +214,20 @@ pub(crate) async fn handle_status() -> Result<()> {
                     for capsule in &status.loaded_capsules {
                         println!("    - {capsule}");
                     }
+                    if !status.kv_ops.is_empty() {
+                        println!("  KV store:");
+                        for op in &status.kv_ops {
+                            println!(
+                                "    {:<9} {} ops, {} errors, {} slow, p50 {}us, p99 {}us",
+                                op.op,
+                                op.count,
+                                op.errors,
+                                op.slow,
+                                op.latency_p50_us,
+                                op.latency_p99_us
+                            );
+                        }
+                    }
                 } else {
                     println!("{}", theme::Theme::error("Unexpected response from daemon"));
                 }
@@ -3,6 +3,7 @@
 use std::io::IsTerminal;
 
 use anyhow::{Context, Result};
+use astrid_types::ApprovalRisk;
 
 use super::daemon;
 use crate::{formatter, socket_client, tui};
@@ -47,19 +48,241 @@ pub(crate) async fn run_snapshot_tui(
     .await
 }
 
+/// Exit code when no daemon message arrived for [`READ_TIMEOUT`].
+const EXIT_TIMED_OUT: i32 = 53;
+
+/// Exit code when the turn stopped at an approval under
+/// [`ApprovalPolicy::Fail`].
+const EXIT_APPROVAL_REQUIRED: i32 = 54;
+
+/// How long headless mode waits for the next daemon message.
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_mins(2);
+
+/// How headless mode answers approval requests, since nobody is there to ask.
+///
+/// Parsed from `--approval-policy` (`deny`, `approve-low-risk`, `fail`,
+/// `approve`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ApprovalPolicy {
+    /// Deny every request and report it in the result.
+    Deny,
+    /// Approve requests the kernel rated low risk (reads inside the
+    /// workspace), deny everything else.
+    ApproveLowRisk,
+    /// Deny the request and end the turn with a non-zero exit code.
+    Fail,
+    /// Approve every request (`--yes`).
+    Approve,
+}
+
+impl ApprovalPolicy {
+    /// Whether this policy approves a request with the kernel-assessed
+    /// `risk`. The action text comes from the requesting capsule, so it
+    /// plays no part; requests without a risk are never low risk.
+    fn approves(self, risk: Option<ApprovalRisk>) -> bool {
+        match self {
+            Self::Approve => true,
+            Self::ApproveLowRisk => risk == Some(ApprovalRisk::Low),
+            Self::Deny | Self::Fail => false,
+        }
+    }
+}
+
+/// How a headless turn ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum OneshotStatus {
+    /// The agent sent its final response.
+    Completed,
+    /// An approval was needed under [`ApprovalPolicy::Fail`].
+    ApprovalRequired,
+    /// The daemon closed the connection before the final response.
+    Disconnected,
+    /// The daemon sent nothing for [`READ_TIMEOUT`].
+    TimedOut,
+}
+
+impl OneshotStatus {
+    /// Process exit code for this status.
+    fn exit_code(self) -> i32 {
+        match self {
+            Self::Completed => 0,
+            Self::ApprovalRequired => EXIT_APPROVAL_REQUIRED,
+            Self::Disconnected => 1,
+            Self::TimedOut => EXIT_TIMED_OUT,
+        }
+    }
+}
+
+/// An approval request answered by the headless policy.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub(crate) struct ApprovalRecord {
+    /// The action that needed approval.
+    pub action: String,
+    /// The resource it targeted.
+    pub resource: String,
+    /// Whether the policy approved it.
+    pub approved: bool,
+}
+
+/// Everything a single headless turn produced, printed by `--format json`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub(crate) struct OneshotResult {
+    /// Final response text.
+    pub response: String,
+    /// Tool calls and results, in order.
+    pub tool_calls: Vec<serde_json::Value>,
+    /// Approval requests and how they were answered.
+    pub approvals: Vec<ApprovalRecord>,
+    /// Token usage reported during the turn.
+    pub usage: astrid_types::llm::Usage,
+    /// How the turn ended.
+    pub status: OneshotStatus,
+    /// Process exit code.
+    pub exit_code: i32,
+}
+
+/// What the collector wants done after a daemon message.
+#[derive(Debug, PartialEq)]
+enum Step {
+    /// Keep reading.
+    Continue,
+    /// Send this approval response and keep reading.
+    Reply(astrid_types::ipc::IpcPayload),
+    /// Send this approval response, then stop.
+    ReplyAndStop(astrid_types::ipc::IpcPayload),
+    /// The turn is over.
+    Done,
+}
+
+/// Accumulates one turn's daemon messages into a [`OneshotResult`].
+struct TurnCollector {
+    policy: ApprovalPolicy,
+    result: OneshotResult,
+}
+
+impl TurnCollector {
+    fn new(policy: ApprovalPolicy) -> Self {
+        Self {
+            policy,
+            result: OneshotResult {
+                response: String::new(),
+                tool_calls: Vec::new(),
+                approvals: Vec::new(),
+                usage: astrid_types::llm::Usage::default(),
+                status: OneshotStatus::Disconnected,
+                exit_code: OneshotStatus::Disconnected.exit_code(),
+            },
+        }
+    }
+
+    fn finish(mut self, status: OneshotStatus) -> OneshotResult {
+        self.result.status = status;
+        self.result.exit_code = status.exit_code();
+        self.result
+    }
+
+    fn handle(&mut self, payload: &astrid_types::ipc::IpcPayload) -> Step {
+        use astrid_types::ipc::IpcPayload;
+        use astrid_types::llm::StreamEvent;
+
+        match payload {
+            IpcPayload::AgentResponse { text, is_final, .. } => {
+                self.result.response.push_str(text);
+                if *is_final {
+                    Step::Done
+                } else {
+                    Step::Continue
+                }
+            },
+            IpcPayload::LlmStreamEvent {
+                event: StreamEvent::ToolCallStart { id, name },
+                ..
+            } => {
+                self.result.tool_calls.push(serde_json::json!({
+                    "type": "tool_call",
+                    "id": id,
+                    "name": name,
+                }));
+                Step::Continue
+            },
+            IpcPayload::LlmStreamEvent {
+                event:
+                    StreamEvent::Usage {
+                        input_tokens,
+                        output_tokens,
+                    },
+                ..
+            } => {
+                let usage = &mut self.result.usage;
+                usage.input_tokens = usage.input_tokens.saturating_add(*input_tokens);
+                usage.output_tokens = usage.output_tokens.saturating_add(*output_tokens);
+                Step::Continue
+            },
+            IpcPayload::ToolExecuteResult { call_id, result } => {
+                self.result.tool_calls.push(serde_json::json!({
+                    "type": "tool_result",
+                    "call_id": call_id,
+                    "content": result.content,
+                    "is_error": result.is_error,
+                }));
+                Step::Continue
+            },
+            IpcPayload::ApprovalRequired {
+                request_id,
+                action,
+                resource,
+                risk,
+                ..
+            } => {
+                let approved = self.policy.approves(*risk);
+                eprintln!(
+                    "[headless] Auto-{} approval for: {action}",
+                    if approved { "approved" } else { "denied" }
+                );
+                self.result.approvals.push(ApprovalRecord {
+                    action: action.clone(),
+                    resource: resource.clone(),
+                    approved,
+                });
+                let response = IpcPayload::ApprovalResponse {
+                    request_id: request_id.clone(),
+                    decision: if approved { "approve" } else { "deny" }.to_string(),
+                    reason: Some(
+                        match self.policy {
+                            ApprovalPolicy::Approve => "headless --yes mode",
+                            ApprovalPolicy::ApproveLowRisk => "headless approve-low-risk policy",
+                            ApprovalPolicy::Fail => "headless fail policy",
+                            ApprovalPolicy::Deny => "headless mode",
+                        }
+                        .to_string(),
+                    ),
+                };
+                if self.policy == ApprovalPolicy::Fail {
+                    Step::ReplyAndStop(response)
+                } else {
+                    Step::Reply(response)
+                }
+            },
+            _ => Step::Continue,
+        }
+    }
+}
+
 /// Headless mode: send a single prompt, stream the response to stdout, exit.
 ///
 /// Connects to the daemon (spawning if needed), sends the prompt as a
 /// `UserInput` IPC message, and reads response events until the final
-/// `AgentResponse` with `is_final = true`.
+/// `AgentResponse` with `is_final = true`. Approval requests are answered
+/// by `policy`; the process exits non-zero if the turn did not complete.
 ///
 /// Output format:
 /// - `Pretty`: prints the raw response text to stdout.
-/// - `Json`: prints a JSON object with `response` and tool call details.
+/// - `Json`: prints the [`OneshotResult`] as JSON.
 pub(crate) async fn run_headless(
     prompt: String,
     format: formatter::OutputFormat,
-    auto_approve: bool,
+    policy: ApprovalPolicy,
     session_name: Option<String>,
     print_session: bool,
 ) -> Result<()> {
@@ -103,22 +326,17 @@ pub(crate) async fn run_headless(
 
     // Send the prompt and collect the streaming response
     client.send_input(full_prompt).await?;
-    let (response_text, tool_calls) =
-        collect_response(&mut client, &session_id, format, auto_approve).await?;
+    let result = collect_response(&mut client, &session_id, format, policy).await?;
 
     // Final output
     match format {
         formatter::OutputFormat::Pretty => {
-            if !response_text.ends_with('\n') {
+            if !result.response.ends_with('\n') {
                 println!();
             }
         },
         formatter::OutputFormat::Json => {
-            let output = serde_json::json!({
-                "response": response_text,
-                "tool_calls": tool_calls,
-            });
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            println!("{}", serde_json::to_string_pretty(&result)?);
         },
     }
 
@@ -132,90 +350,230 @@ pub(crate) async fn run_headless(
     );
     let _ = client.send_message(disconnect).await;
 
+    if result.exit_code != 0 {
+        std::process::exit(result.exit_code);
+    }
     Ok(())
 }
 
 /// Collect the streaming response from the daemon in headless mode.
 ///
-/// Returns `(response_text, tool_calls)`. Auto-denies any approval requests.
-/// Times out after 120 seconds of no data.
+/// Answers approval requests according to `policy`. Ends the turn as
+/// [`OneshotStatus::TimedOut`] after [`READ_TIMEOUT`] without data.
 async fn collect_response(
     client: &mut socket_client::SocketClient,
     session_id: &astrid_core::SessionId,
     format: formatter::OutputFormat,
-    auto_approve: bool,
-) -> Result<(String, Vec<serde_json::Value>)> {
-    let mut response_text = String::new();
-    let mut tool_calls: Vec<serde_json::Value> = Vec::new();
-    let timeout_duration = std::time::Duration::from_secs(120);
+    policy: ApprovalPolicy,
+) -> Result<OneshotResult> {
+    let mut collector = TurnCollector::new(policy);
 
     loop {
-        let message = match tokio::time::timeout(timeout_duration, client.read_message()).await {
+        let message = match tokio::time::timeout(READ_TIMEOUT, client.read_message()).await {
             Ok(Ok(Some(msg))) => msg,
-            Ok(Ok(None)) => break,
+            Ok(Ok(None)) => return Ok(collector.finish(OneshotStatus::Disconnected)),
             Ok(Err(e)) => return Err(e.context("Failed to read from daemon")),
             Err(_) => {
-                eprintln!("[headless] Timed out waiting for response (120s)");
-                std::process::exit(53);
+                eprintln!(
+                    "[headless] Timed out waiting for response ({}s)",
+                    READ_TIMEOUT.as_secs()
+                );
+                return Ok(collector.finish(OneshotStatus::TimedOut));
             },
         };
 
-        match &message.payload {
-            astrid_types::ipc::IpcPayload::AgentResponse { text, is_final, .. } => {
-                if format == formatter::OutputFormat::Pretty {
-                    print!("{text}");
-                    let _ = std::io::Write::flush(&mut std::io::stdout());
-                }
-                response_text.push_str(text);
-                if *is_final {
-                    break;
-                }
-            },
-            astrid_types::ipc::IpcPayload::LlmStreamEvent {
-                event: astrid_types::llm::StreamEvent::ToolCallStart { id, name },
-                ..
-            } => {
-                tool_calls.push(serde_json::json!({
-                    "type": "tool_call",
-                    "id": id,
-                    "name": name,
-                }));
-            },
-            astrid_types::ipc::IpcPayload::ToolExecuteResult { call_id, result } => {
-                tool_calls.push(serde_json::json!({
-                    "type": "tool_result",
-                    "call_id": call_id,
-                    "content": result.content,
-                    "is_error": result.is_error,
-                }));
+        if format == formatter::OutputFormat::Pretty
+            && let astrid_types::ipc::IpcPayload::AgentResponse { text, .. } = &message.payload
+        {
+            print!("{text}");
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+        }
+
+        let (response, stop) = match collector.handle(&message.payload) {
+            Step::Continue => continue,
+            Step::Done => return Ok(collector.finish(OneshotStatus::Completed)),
+            Step::Reply(response) => (response, false),
+            Step::ReplyAndStop(response) => (response, true),
+        };
+
+        if let astrid_types::ipc::IpcPayload::ApprovalResponse { request_id, .. } = &response {
+            let topic = format!("astrid.v1.approval.response.{request_id}");
+            let msg = astrid_types::ipc::IpcMessage::new(topic, response.clone(), session_id.0);
+            client.send_message(msg).await?;
+        }
+        if stop {
+            return Ok(collector.finish(OneshotStatus::ApprovalRequired));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astrid_types::ipc::IpcPayload;
+    use astrid_types::llm::StreamEvent;
+
+    fn approval(id: &str, action: &str, risk: Option<ApprovalRisk>) -> IpcPayload {
+        IpcPayload::ApprovalRequired {
+            request_id: id.into(),
+            action: action.into(),
+            resource: format!("{action} target"),
+            reason: "test".into(),
+            risk,
+        }
+    }
+
+    fn response(text: &str, is_final: bool) -> IpcPayload {
+        IpcPayload::AgentResponse {
+            text: text.into(),
+            is_final,
+            session_id: "s".into(),
+        }
+    }
+
+    /// A scripted turn: a kernel-rated read, a write, and a read described
+    /// only by the capsule, then a final answer.
+    fn script() -> Vec<IpcPayload> {
+        vec![
+            IpcPayload::LlmStreamEvent {
+                request_id: uuid::Uuid::nil(),
+                event: StreamEvent::Usage {
+                    input_tokens: 100,
+                    output_tokens: 20,
+                },
             },
-            astrid_types::ipc::IpcPayload::ApprovalRequired {
-                request_id, action, ..
-            } => {
-                let decision = if auto_approve { "approve" } else { "deny" };
-                eprintln!(
-                    "[headless] Auto-{} approval for: {action}",
-                    if auto_approve { "approved" } else { "denied" }
-                );
-                let response = astrid_types::ipc::IpcPayload::ApprovalResponse {
-                    request_id: request_id.clone(),
-                    decision: decision.to_string(),
-                    reason: Some(
-                        if auto_approve {
-                            "headless --yes mode"
-                        } else {
-                            "headless mode"
-                        }
-                        .to_string(),
-                    ),
-                };
-                let topic = format!("astrid.v1.approval.response.{request_id}");
-                let msg = astrid_types::ipc::IpcMessage::new(topic, response, session_id.0);
-                client.send_message(msg).await?;
+            approval("a1", "file_read", Some(ApprovalRisk::Low)),
+            approval("a2", "write_file", Some(ApprovalRisk::High)),
+            approval("a3", "read_file", None),
+            response("Done", false),
+            IpcPayload::LlmStreamEvent {
+                request_id: uuid::Uuid::nil(),
+                event: StreamEvent::Usage {
+                    input_tokens: 50,
+                    output_tokens: 5,
+                },
             },
-            _ => {},
+            response(".", true),
+        ]
+    }
+
+    /// Feed the script until the collector stops, returning the decisions sent.
+    fn run(policy: ApprovalPolicy) -> (OneshotResult, Vec<String>) {
+        let mut collector = TurnCollector::new(policy);
+        let mut decisions = Vec::new();
+        for payload in script() {
+            match collector.handle(&payload) {
+                Step::Continue => {},
+                Step::Reply(IpcPayload::ApprovalResponse { decision, .. }) => {
+                    decisions.push(decision);
+                },
+                Step::ReplyAndStop(IpcPayload::ApprovalResponse { decision, .. }) => {
+                    decisions.push(decision);
+                    return (collector.finish(OneshotStatus::ApprovalRequired), decisions);
+                },
+                Step::Done => return (collector.finish(OneshotStatus::Completed), decisions),
+                other => panic!("unexpected step: {other:?}"),
+            }
         }
+        (collector.finish(OneshotStatus::Disconnected), decisions)
+    }
+
+    #[test]
+    fn deny_policy_reports_denied_approvals() {
+        let (result, decisions) = run(ApprovalPolicy::Deny);
+        assert_eq!(decisions, vec!["deny", "deny", "deny"]);
+        assert_eq!(result.status, OneshotStatus::Completed);
+        assert_eq!(result.exit_code, 0);
+        assert_eq!(result.response, "Done.");
+        assert_eq!(result.approvals.len(), 3);
+        assert!(result.approvals.iter().all(|a| !a.approved));
+        assert_eq!(result.usage.input_tokens, 150);
+        assert_eq!(result.usage.output_tokens, 25);
+    }
+
+    #[test]
+    fn approve_low_risk_policy_approves_kernel_rated_reads_only() {
+        let (result, decisions) = run(ApprovalPolicy::ApproveLowRisk);
+        assert_eq!(decisions, vec!["approve", "deny", "deny"]);
+        assert_eq!(result.status, OneshotStatus::Completed);
+        assert_eq!(
+            result
+                .approvals
+                .iter()
+                .map(|a| (a.action.as_str(), a.approved))
+                .collect::<Vec<_>>(),
+            vec![
+                ("file_read", true),
+                ("write_file", false),
+                ("read_file", false)
+            ]
+        );
+    }
+
+    #[test]
+    fn fail_policy_stops_at_first_approval() {
+        let (result, decisions) = run(ApprovalPolicy::Fail);
+        assert_eq!(decisions, vec!["deny"]);
+        assert_eq!(result.status, OneshotStatus::ApprovalRequired);
+        assert_eq!(result.exit_code, EXIT_APPROVAL_REQUIRED);
+        assert_eq!(result.approvals.len(), 1);
+        assert!(result.response.is_empty());
+    }
+
+    #[test]
+    fn result_serializes_to_stable_json() {
+        let (result, _) = run(ApprovalPolicy::Fail);
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "response": "",
+                "tool_calls": [],
+                "approvals": [
+                    {"action": "file_read", "resource": "file_read target", "approved": false}
+                ],
+                "usage": {"input_tokens": 100, "output_tokens": 20},
+                "status": "approval_required",
+                "exit_code": 54,
+            })
+        );
+    }
+
+    #[test]
+    fn policy_names_parse() {
+        use clap::ValueEnum;
+        let parse = |name| ApprovalPolicy::from_str(name, false);
+        assert_eq!(parse("deny").unwrap(), ApprovalPolicy::Deny);
+        assert_eq!(
+            parse("approve-low-risk").unwrap(),
+            ApprovalPolicy::ApproveLowRisk
+        );
+        assert_eq!(parse("fail").unwrap(), ApprovalPolicy::Fail);
+        assert_eq!(parse("approve").unwrap(), ApprovalPolicy::Approve);
+        assert!(parse("maybe").is_err());
+    }
+
+    #[test]
+    fn timed_out_turn_keeps_what_was_collected() {
+        let mut collector = TurnCollector::new(ApprovalPolicy::Deny);
+        assert_eq!(
+            collector.handle(&response("partial", false)),
+            Step::Continue
+        );
+        let result = collector.finish(OneshotStatus::TimedOut);
+        assert_eq!(result.response, "partial");
+        assert_eq!(result.exit_code, EXIT_TIMED_OUT);
+        assert_eq!(
+            serde_json::to_value(result.status).unwrap(),
+            serde_json::json!("timed_out")
+        );
     }
 
-    Ok((response_text, tool_calls))
+    #[test]
+    fn low_risk_comes_from_the_kernel_not_the_action_text() {
+        let policy = ApprovalPolicy::ApproveLowRisk;
+        assert!(policy.approves(Some(ApprovalRisk::Low)));
+        assert!(!policy.approves(Some(ApprovalRisk::High)));
+        assert!(!policy.approves(None));
+    }
 }
@@ -4,17 +4,46 @@ use anyhow::{Context, Result};
 use astrid_core::dirs::AstridHome;
 use colored::Colorize;
 use std::fs;
+use std::path::Path;
 
 use crate::{SessionCommands, theme::Theme};
 
+/// File in a session directory holding its title.
+const TITLE_FILE: &str = "title";
+
+/// Longest title kept, in characters.
+const MAX_TITLE_CHARS: usize = 80;
+
 pub(crate) fn handle_session_commands(command: SessionCommands) -> Result<()> {
     match command {
         SessionCommands::List => list_sessions(),
         SessionCommands::Delete { id } => delete_session(&id),
         SessionCommands::Info { id } => session_info(&id),
+        SessionCommands::Rename { id, title } => rename_session(&id, &title),
     }
 }
 
+/// Collapse a title to one line of single-spaced words, capped at
+/// [`MAX_TITLE_CHARS`].
+fn normalize_title(title: &str) -> String {
+    title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_TITLE_CHARS)
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+/// The stored title of a session, if it has one.
+fn read_title(session_dir: &Path) -> Option<String> {
+    let title = normalize_title(&fs::read_to_string(session_dir.join(TITLE_FILE)).ok()?);
+    (!title.is_empty()).then_some(title)
+}
+
 fn list_sessions() -> Result<()> {
     let home = AstridHome::resolve().context("Failed to resolve Astrid home directory")?;
     let sessions_dir = home.run_dir();
@@ -33,7 +62,7 @@ fn list_sessions() -> Result<()> {
             // If it looks like a UUID, count it as a session
             if uuid::Uuid::parse_str(name).is_ok() {
                 let modified = entry.metadata()?.modified()?;
-                sessions.push((name.to_string(), modified));
+                sessions.push((name.to_string(), modified, read_title(&entry.path())));
             }
         }
     }
@@ -46,11 +75,19 @@ fn list_sessions() -> Result<()> {
     sessions.sort_by(|a, b| b.1.cmp(&a.1));
 
     println!("{}", "Active Sessions:".bold());
-    for (id, modified) in sessions {
+    for (id, modified, title) in sessions {
         let time = chrono::DateTime::<chrono::Local>::from(modified)
             .format("%Y-%m-%d %H:%M:%S")
             .to_string();
-        println!("  {} ({})", Theme::session_id(&id), Theme::dimmed(&time));
+        match title {
+            Some(title) => println!(
+                "  {} {} ({})",
+                title.bold(),
+                Theme::session_id(&id),
+                Theme::dimmed(&time)
+            ),
+            None => println!("  {} ({})", Theme::session_id(&id), Theme::dimmed(&time)),
+        }
     }
 
     Ok(())
@@ -84,6 +121,9 @@ fn session_info(id: &str) -> Result<()> {
 
     println!("{}", "Session Information".bold());
     println!("  ID: {}", Theme::session_id(id));
+    if let Some(title) = read_title(&session_dir) {
+        println!("  Title: {title}");
+    }
 
     // The global daemon socket path — shows daemon health, not session-specific status.
     let sock_path = home.socket_path();
@@ -95,3 +135,63 @@ fn session_info(id: &str) -> Result<()> {
 
     Ok(())
 }
+
+fn rename_session(id: &str, title: &str) -> Result<()> {
+    uuid::Uuid::parse_str(id)
+        .map_err(|_| anyhow::anyhow!("Invalid session ID (must be a UUID): {id}"))?;
+    let home = AstridHome::resolve().context("Failed to resolve Astrid home directory")?;
+    let session_dir = home.run_dir().join(id);
+
+    if !session_dir.exists() {
+        anyhow::bail!("Session not found: {id}");
+    }
+
+    let title = normalize_title(title);
+    let path = session_dir.join(TITLE_FILE);
+    if title.is_empty() {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {},
+        }
+        println!(
+            "{}",
+            Theme::success(&format!("Cleared title of session {id}"))
+        );
+    } else {
+        fs::write(&path, &title)?;
+        println!(
+            "{}",
+            Theme::success(&format!("Renamed session {id} to \"{title}\""))
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_are_single_line_and_capped() {
+        assert_eq!(
+            normalize_title("  Fix   the\nflaky test \t"),
+            "Fix the flaky test"
+        );
+        assert_eq!(normalize_title("a\u{7}b"), "ab");
+        assert_eq!(normalize_title(" \n "), "");
+        let long = "word ".repeat(40);
+        let title = normalize_title(&long);
+        assert_eq!(title.chars().count(), MAX_TITLE_CHARS - 1);
+        assert!(!title.ends_with(' '));
+    }
+
+    #[test]
+    fn title_round_trips_through_session_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(read_title(dir.path()), None);
+        fs::write(dir.path().join(TITLE_FILE), "Refactor parser\n").unwrap();
+        assert_eq!(read_title(dir.path()).as_deref(), Some("Refactor parser"));
+        fs::write(dir.path().join(TITLE_FILE), "   ").unwrap();
+        assert_eq!(read_title(dir.path()), None);
+    }
+}
@@ -50,10 +50,17 @@ struct Cli {
     prompt: Option<String>,
 
     /// Auto-approve all tool approval requests in headless mode (autonomous/yolo mode).
-    /// Without this flag, headless mode auto-denies approvals.
+    /// Without this flag, headless mode answers approvals by --approval-policy.
     #[arg(short = 'y', long = "yes", alias = "yolo", alias = "autonomous")]
     auto_approve: bool,
 
+    /// How headless mode answers approval requests: deny (default), approve-low-risk
+    /// (approve requests the kernel rates low risk: reads inside the workspace), or
+    /// fail (stop the turn and exit with code 54).
+    /// Every answered request is listed in `--format json` output.
+    #[arg(long = "approval-policy", value_enum, default_value_t = commands::headless::ApprovalPolicy::Deny)]
+    approval_policy: commands::headless::ApprovalPolicy,
+
     /// Resume or create a named session for multi-turn headless conversations.
     /// Use the same ID across multiple -p calls to maintain context.
     /// If omitted, a fresh session is created each time.
@@ -225,6 +232,13 @@ enum SessionCommands {
         /// The session ID to query
         id: String,
     },
+    /// Set the title shown for a session in `session list`
+    Rename {
+        /// The session ID to rename
+        id: String,
+        /// The new title (empty clears it)
+        title: String,
+    },
 }
 
 // ─── Bootstrap helpers ───────────────────────────────────────────
@@ -271,6 +285,8 @@ fn init_logging(cli: &Cli) {
 
     let log_config = if let Some(cfg) = &unified_cfg {
         let mut lc = astrid_telemetry::log_config_from(cfg);
+        // Only the daemon exports telemetry; the CLI is a thin client.
+        lc.otlp = None;
         if cli.verbose {
             "debug".clone_into(&mut lc.level);
         }
@@ -340,6 +356,13 @@ async fn main() -> Result<()> {
         _ => formatter::OutputFormat::Pretty,
     };
 
+    // Headless approval policy; --yes approves everything.
+    let approval_policy = if cli.auto_approve {
+        commands::headless::ApprovalPolicy::Approve
+    } else {
+        cli.approval_policy
+    };
+
     // Headless mode: -p "prompt" sends a single prompt and exits.
     if let Some(prompt_text) = cli.prompt {
         ensure_global_config().await;
@@ -356,7 +379,7 @@ async fn main() -> Result<()> {
         return commands::headless::run_headless(
             prompt_text,
             output_format,
-            cli.auto_approve,
+            approval_policy,
             cli.session_name,
             cli.print_session,
         )
@@ -372,7 +395,7 @@ async fn main() -> Result<()> {
             return commands::headless::run_headless(
                 stdin_text,
                 output_format,
-                cli.auto_approve,
+                approval_policy,
                 cli.session_name,
                 cli.print_session,
             )
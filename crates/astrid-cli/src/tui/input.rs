@@ -738,6 +738,10 @@ fn handle_approval_input(app: &mut App, key: KeyEvent) {
         KeyCode::Char('y' | 'Y') => {
             app.approve_tool(&id, ApprovalDecisionKind::Once);
         },
+        // Approve this exact action
+        KeyCode::Char('e' | 'E') => {
+            app.approve_tool(&id, ApprovalDecisionKind::Exact);
+        },
         // Approve session
         KeyCode::Char('s' | 'S') => {
             app.approve_tool(&id, ApprovalDecisionKind::Session);
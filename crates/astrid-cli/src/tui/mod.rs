@@ -333,6 +333,7 @@ pub(crate) fn handle_daemon_event(app: &mut App, message: &IpcMessage) {
             action,
             resource,
             reason,
+            ..
         } = &message.payload
         {
             let approval = state::ApprovalRequest {
@@ -500,6 +501,7 @@ async fn handle_pending_actions(
             } => {
                 let decision_str = match decision {
                     state::ApprovalDecisionKind::Once => "approve",
+                    state::ApprovalDecisionKind::Exact => "approve_exact",
                     state::ApprovalDecisionKind::Session => "approve_session",
                     state::ApprovalDecisionKind::Always => "approve_always",
                 };
@@ -1596,6 +1596,11 @@ fn render_approval_overlay(frame: &mut Frame, app: &App, theme: &Theme) {
                 .add_modifier(Modifier::BOLD),
         ),
         Span::raw(" Allow  "),
+        Span::styled(
+            "[e]",
+            Style::default().fg(theme.tool).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" Exact  "),
         Span::styled(
             "[s]",
             Style::default().fg(theme.tool).add_modifier(Modifier::BOLD),
@@ -725,6 +725,8 @@ pub(crate) enum PendingAction {
 #[derive(Debug, Clone)]
 pub(crate) enum ApprovalDecisionKind {
     Once,
+    /// This exact command in this workspace, remembered across sessions.
+    Exact,
     Session,
     Always,
 }
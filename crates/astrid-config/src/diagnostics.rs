@@ -0,0 +1,643 @@
+//! Span-aware configuration diagnostics and JSON Schema.
+//!
+//! [`validate_str`] and [`validate_file`] check a single config file and
+//! report every problem they can find as a [`Diagnostic`] carrying:
+//!
+//! - the file and 1-based line/column of the offending key or value,
+//! - the dotted config path (`model.max_tokens`),
+//! - a did-you-mean suggestion for near-miss key names.
+//!
+//! Unknown keys are detected by walking the file against the JSON Schema
+//! derived from [`Config`] (see [`schema`]). Serde silently ignores them, so
+//! they are reported as warnings rather than errors. The loader runs the
+//! same analysis on every layer so its errors carry the same detail.
+
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+use crate::error::ConfigError;
+use crate::loader::MAX_CONFIG_FILE_SIZE;
+use crate::migrate;
+use crate::types::Config;
+use crate::validate;
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The file cannot be loaded.
+    Error,
+    /// The file loads, but probably not the way its author intended.
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => f.write_str("error"),
+            Self::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// A single problem found in a config file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// Error or warning.
+    pub severity: Severity,
+    /// File the problem was found in, if known.
+    pub file: Option<String>,
+    /// 1-based line of the problem, if known.
+    pub line: Option<usize>,
+    /// 1-based column of the problem, if known.
+    pub column: Option<usize>,
+    /// Dotted config path (e.g. `model.max_tokens`), if known.
+    pub path: Option<String>,
+    /// What is wrong.
+    pub message: String,
+    /// Suggested replacement key for a near-miss key name.
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            file: None,
+            line: None,
+            column: None,
+            path: None,
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    /// Whether this diagnostic prevents the file from loading.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            f.write_str(file)?;
+            if let Some(line) = self.line {
+                write!(f, ":{line}")?;
+                if let Some(column) = self.column {
+                    write!(f, ":{column}")?;
+                }
+            }
+            f.write_str(": ")?;
+        }
+        write!(f, "{}: ", self.severity)?;
+        if let Some(path) = &self.path {
+            write!(f, "{path}: ")?;
+        }
+        f.write_str(&self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean `{suggestion}`?)")?;
+        }
+        Ok(())
+    }
+}
+
+/// JSON Schema for the whole [`Config`] tree, suitable for editor
+/// autocompletion of `config.toml` (e.g. via taplo).
+#[must_use]
+pub fn schema() -> &'static serde_json::Value {
+    static SCHEMA: OnceLock<serde_json::Value> = OnceLock::new();
+    SCHEMA.get_or_init(|| {
+        let mut schema = schemars::schema_for!(Config).to_value();
+        // `include` and `profiles` are handled by the loader, not `Config`
+        // fields. Each profile is itself a partial config.
+        if let Some(properties) = schema
+            .get_mut("properties")
+            .and_then(serde_json::Value::as_object_mut)
+        {
+            properties.insert(
+                "include".to_owned(),
+                serde_json::json!({
+                    "description": "Config files merged beneath this one, in order.",
+                    "type": "array",
+                    "items": { "type": "string" }
+                }),
+            );
+            properties.insert(
+                "profiles".to_owned(),
+                serde_json::json!({
+                    "description": "Named partial configs, selected with ASTRID_PROFILE.",
+                    "type": "object",
+                    "additionalProperties": { "$ref": "#" }
+                }),
+            );
+        }
+        schema
+    })
+}
+
+/// Check a config file and return every diagnostic found.
+///
+/// An empty result means the file is valid on its own. Read failures are
+/// reported as a single error diagnostic.
+#[must_use]
+pub fn validate_file(path: &Path) -> Vec<Diagnostic> {
+    let label = path.display().to_string();
+    let read_error = |message: String| {
+        let mut d = Diagnostic::new(Severity::Error, message);
+        d.file = Some(label.clone());
+        vec![d]
+    };
+    match std::fs::read_to_string(path) {
+        Ok(content) if content.len() as u64 > MAX_CONFIG_FILE_SIZE => read_error(format!(
+            "config file is {} bytes, exceeding the {MAX_CONFIG_FILE_SIZE} byte limit",
+            content.len()
+        )),
+        Ok(content) => validate_str(&content, Some(&label)),
+        Err(e) => read_error(format!("failed to read config file: {e}")),
+    }
+}
+
+/// Check config file contents and return every diagnostic found.
+///
+/// `file` labels the diagnostics; it is not read.
+#[must_use]
+pub fn validate_str(content: &str, file: Option<&str>) -> Vec<Diagnostic> {
+    let analysis = analyze(content, file);
+    let mut diagnostics = analysis.diagnostics;
+    if let Some(config) = analysis.config
+        && let Err(ConfigError::ValidationError { field, message }) = validate::validate(&config)
+    {
+        let source = analysis.source.as_ref();
+        diagnostics.push(locate(
+            Diagnostic::new(Severity::Error, message),
+            Some(field.clone()),
+            source.and_then(|s| s.value_span(&field)),
+            content,
+            file,
+        ));
+    }
+    sort(&mut diagnostics);
+    diagnostics
+}
+
+/// Result of parsing and type-checking one file.
+pub(crate) struct Analysis {
+    /// The parsed tree, if the file is syntactically valid.
+    pub(crate) value: Option<toml::Value>,
+    /// The typed config, if the file also deserializes.
+    pub(crate) config: Option<Config>,
+    /// Syntax, type, and unknown-key diagnostics.
+    pub(crate) diagnostics: Vec<Diagnostic>,
+    source: Option<SourceIndex>,
+}
+
+impl Analysis {
+    /// Add a diagnostic for the config `path`, positioned at its key in
+    /// `content` (or the nearest ancestor present in the file).
+    pub(crate) fn report(
+        &mut self,
+        severity: Severity,
+        path: &str,
+        message: String,
+        content: &str,
+        file: Option<&str>,
+    ) {
+        let span = self.source.as_ref().and_then(|s| s.key_span(path));
+        self.diagnostics.push(locate(
+            Diagnostic::new(severity, message),
+            Some(path.to_owned()),
+            span,
+            content,
+            file,
+        ));
+        sort(&mut self.diagnostics);
+    }
+}
+
+/// Parse, type-check, and look for unknown keys, without semantic
+/// validation (which only makes sense on the merged config).
+pub(crate) fn analyze(content: &str, file: Option<&str>) -> Analysis {
+    let doc = match toml_edit::ImDocument::parse(content) {
+        Ok(doc) => doc,
+        Err(e) => {
+            let d = locate(
+                Diagnostic::new(Severity::Error, e.message().trim().to_owned()),
+                None,
+                e.span(),
+                content,
+                file,
+            );
+            return Analysis {
+                value: None,
+                config: None,
+                diagnostics: vec![d],
+                source: None,
+            };
+        },
+    };
+    let source = SourceIndex::new(&doc);
+    let mut diagnostics = Vec::new();
+
+    let value = toml::from_str::<toml::Value>(content).ok();
+    if let Some(value) = &value {
+        unknown_keys(
+            schema(),
+            schema(),
+            value,
+            "",
+            &mut |path, key, candidates| {
+                // Deprecated keys get a migration warning from the loader.
+                if migrate::is_deprecated(path) {
+                    return;
+                }
+                let mut d = Diagnostic::new(Severity::Warning, format!("unknown key `{key}`"));
+                d.suggestion = suggest(key, candidates);
+                diagnostics.push(locate(
+                    d,
+                    Some(path.to_owned()),
+                    source.key_span(path),
+                    content,
+                    file,
+                ));
+            },
+        );
+    }
+
+    let config = match toml::from_str::<Config>(content) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            let span = e.span();
+            let path = span.as_ref().and_then(|s| source.path_at(s.start));
+            // Tables that deny unknown fields fail on the key we already
+            // warned about; promote that warning instead of reporting twice.
+            if let Some(warning) = diagnostics
+                .iter_mut()
+                .find(|d| d.path.is_some() && d.path == path)
+            {
+                warning.severity = Severity::Error;
+            } else {
+                diagnostics.push(locate(
+                    Diagnostic::new(Severity::Error, e.message().trim().to_owned()),
+                    path,
+                    span,
+                    content,
+                    file,
+                ));
+            }
+            None
+        },
+    };
+
+    sort(&mut diagnostics);
+    Analysis {
+        value,
+        config,
+        diagnostics,
+        source: Some(source),
+    }
+}
+
+/// Explain why a merged (in-memory) config tree fails to deserialize.
+///
+/// The tree is rendered back to TOML so the error span can be mapped to a
+/// config path. Line and column are dropped: they would point into the
+/// rendered text, not any file on disk.
+pub(crate) fn explain_merged(merged: &toml::Value) -> Vec<Diagnostic> {
+    let Ok(rendered) = toml::to_string(merged) else {
+        return vec![Diagnostic::new(
+            Severity::Error,
+            "merged config cannot be rendered",
+        )];
+    };
+    analyze(&rendered, None)
+        .diagnostics
+        .into_iter()
+        .filter(Diagnostic::is_error)
+        .map(|mut d| {
+            d.line = None;
+            d.column = None;
+            d
+        })
+        .collect()
+}
+
+fn sort(diagnostics: &mut [Diagnostic]) {
+    // Positioned diagnostics first, in file order; errors before warnings.
+    diagnostics.sort_by_key(|d| {
+        (
+            d.line.is_none(),
+            d.line,
+            d.column,
+            d.severity == Severity::Warning,
+        )
+    });
+}
+
+fn locate(
+    mut d: Diagnostic,
+    path: Option<String>,
+    span: Option<Range<usize>>,
+    content: &str,
+    file: Option<&str>,
+) -> Diagnostic {
+    d.file = file.map(ToOwned::to_owned);
+    d.path = path;
+    if let Some(span) = span {
+        let (line, column) = line_col(content, span.start);
+        d.line = Some(line);
+        d.column = Some(column);
+    }
+    d
+}
+
+/// 1-based line and column (in characters) of a byte offset.
+fn line_col(content: &str, offset: usize) -> (usize, usize) {
+    let mut end = offset.min(content.len());
+    while !content.is_char_boundary(end) {
+        end = end.saturating_sub(1);
+    }
+    let before = content.get(..end).unwrap_or_default();
+    let line = before.matches('\n').count().saturating_add(1);
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |l| l.chars().count())
+        .saturating_add(1);
+    (line, column)
+}
+
+/// Closest candidate within a small edit distance of `key`.
+fn suggest<'a>(key: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let max = (key.chars().count() / 3).clamp(1, 3);
+    candidates
+        .map(|c| (strsim::levenshtein(key, c), c))
+        .filter(|(distance, _)| *distance <= max)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| c.to_owned())
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Source spans
+// ---------------------------------------------------------------------------
+
+/// Dotted path → key and value spans for every entry in a document.
+struct SourceIndex {
+    entries: Vec<SpanEntry>,
+}
+
+struct SpanEntry {
+    path: String,
+    key: Option<Range<usize>>,
+    value: Option<Range<usize>>,
+}
+
+impl SourceIndex {
+    fn new(doc: &toml_edit::ImDocument<&str>) -> Self {
+        let mut entries = Vec::new();
+        index_table(doc.as_table(), "", &mut entries);
+        Self { entries }
+    }
+
+    /// The entry for `path`, or its nearest ancestor present in the file
+    /// (a missing required key is reported at its enclosing table).
+    fn entry(&self, path: &str) -> Option<&SpanEntry> {
+        let mut current = path;
+        loop {
+            if let Some(entry) = self.entries.iter().find(|e| e.path == current) {
+                return Some(entry);
+            }
+            current = current.rsplit_once('.')?.0;
+        }
+    }
+
+    fn key_span(&self, path: &str) -> Option<Range<usize>> {
+        self.entry(path)
+            .and_then(|e| e.key.clone().or(e.value.clone()))
+    }
+
+    fn value_span(&self, path: &str) -> Option<Range<usize>> {
+        self.entry(path)
+            .and_then(|e| e.value.clone().or(e.key.clone()))
+    }
+
+    /// The most specific entry whose key or value contains `offset`.
+    fn path_at(&self, offset: usize) -> Option<String> {
+        self.entries
+            .iter()
+            .flat_map(|e| [(e, e.key.as_ref()), (e, e.value.as_ref())])
+            .filter_map(|(e, span)| span.filter(|s| s.contains(&offset)).map(|s| (e, s)))
+            .min_by_key(|(_, s)| s.len())
+            .map(|(e, _)| e.path.clone())
+    }
+}
+
+fn index_table(table: &toml_edit::Table, prefix: &str, out: &mut Vec<SpanEntry>) {
+    for (name, _) in table {
+        if let Some((key, item)) = table.get_key_value(name) {
+            index_item(key, item, &join(prefix, name), out);
+        }
+    }
+}
+
+fn index_item(key: &toml_edit::Key, item: &toml_edit::Item, path: &str, out: &mut Vec<SpanEntry>) {
+    out.push(SpanEntry {
+        path: path.to_owned(),
+        key: key.span(),
+        value: item.span(),
+    });
+    match item {
+        toml_edit::Item::Table(table) => index_table(table, path, out),
+        toml_edit::Item::ArrayOfTables(tables) => {
+            for (i, table) in tables.iter().enumerate() {
+                index_table(table, &format!("{path}[{i}]"), out);
+            }
+        },
+        toml_edit::Item::Value(value) => index_value(value, path, out),
+        toml_edit::Item::None => {},
+    }
+}
+
+fn index_value(value: &toml_edit::Value, path: &str, out: &mut Vec<SpanEntry>) {
+    match value {
+        toml_edit::Value::InlineTable(table) => {
+            for (name, _) in table {
+                if let Some((key, item)) = table.get_key_value(name) {
+                    index_item(key, item, &join(path, name), out);
+                }
+            }
+        },
+        toml_edit::Value::Array(array) => {
+            for (i, element) in array.iter().enumerate() {
+                let element_path = format!("{path}[{i}]");
+                out.push(SpanEntry {
+                    path: element_path.clone(),
+                    key: None,
+                    value: element.span(),
+                });
+                index_value(element, &element_path, out);
+            }
+        },
+        _ => {},
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema walk
+// ---------------------------------------------------------------------------
+
+/// Follow `$ref`s and pick the object branch of `Option<T>`-style unions.
+fn resolve<'a>(
+    root: &'a serde_json::Value,
+    schema: &'a serde_json::Value,
+) -> &'a serde_json::Value {
+    let mut current = schema;
+    // Bounded: the config tree has no recursive types.
+    for _ in 0..8 {
+        if current.get("$ref").and_then(serde_json::Value::as_str) == Some("#") {
+            current = root;
+            continue;
+        }
+        if let Some(name) = current
+            .get("$ref")
+            .and_then(serde_json::Value::as_str)
+            .and_then(|r| r.strip_prefix("#/$defs/"))
+            && let Some(target) = root.get("$defs").and_then(|d| d.get(name))
+        {
+            current = target;
+            continue;
+        }
+        let objects: Vec<&serde_json::Value> = ["anyOf", "oneOf"]
+            .iter()
+            .filter_map(|kw| current.get(kw).and_then(serde_json::Value::as_array))
+            .flatten()
+            .map(|s| resolve(root, s))
+            .filter(|s| s.get("properties").is_some() || s.get("additionalProperties").is_some())
+            .collect();
+        match objects.as_slice() {
+            [only] => current = only,
+            _ => break,
+        }
+    }
+    current
+}
+
+/// Report keys in `value` that the schema does not know about.
+fn unknown_keys(
+    root: &serde_json::Value,
+    schema: &serde_json::Value,
+    value: &toml::Value,
+    path: &str,
+    report: &mut dyn FnMut(&str, &str, &mut dyn Iterator<Item = &str>),
+) {
+    let schema = resolve(root, schema);
+    match value {
+        toml::Value::Table(table) => {
+            let properties = schema
+                .get("properties")
+                .and_then(serde_json::Value::as_object);
+            let additional = schema.get("additionalProperties").filter(|a| a.is_object());
+            for (key, child) in table {
+                let child_path = join(path, key);
+                if let Some(sub) = properties.and_then(|p| p.get(key)).or(additional) {
+                    unknown_keys(root, sub, child, &child_path, report);
+                } else if let Some(properties) = properties {
+                    report(&child_path, key, &mut properties.keys().map(String::as_str));
+                }
+            }
+        },
+        toml::Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    unknown_keys(root, item_schema, item, &format!("{path}[{i}]"), report);
+                }
+            }
+        },
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_describes_every_section() {
+        let schema = schema();
+        let properties = schema["properties"].as_object().unwrap();
+        for section in ["model", "security", "budget", "servers", "identity"] {
+            assert!(properties.contains_key(section), "missing {section}");
+        }
+        // Fields hidden from serialization are still valid input.
+        let model = resolve(schema, &schema["properties"]["model"]);
+        assert!(model["properties"].get("api_key").is_some());
+    }
+
+    #[test]
+    fn type_error_carries_path_and_position() {
+        let diagnostics = validate_str("[model]\nmax_tokens = \"lots\"\n", Some("c.toml"));
+        assert_eq!(diagnostics.len(), 1);
+        let d = &diagnostics[0];
+        assert!(d.is_error());
+        assert_eq!(d.path.as_deref(), Some("model.max_tokens"));
+        assert_eq!((d.line, d.column), (Some(2), Some(14)));
+    }
+
+    #[test]
+    fn unknown_keys_get_suggestions() {
+        let diagnostics = validate_str(
+            "[model]\nmax_token = 10\n\n[servers.fs]\ncommand = \"fs\"\nargz = []\n",
+            None,
+        );
+        let suggestions: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.path.as_deref(), d.suggestion.as_deref()))
+            .collect();
+        assert_eq!(
+            suggestions,
+            [
+                (Some("model.max_token"), Some("max_tokens")),
+                (Some("servers.fs.argz"), Some("args")),
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| !d.is_error()));
+    }
+
+    #[test]
+    fn profiles_are_checked_like_the_root() {
+        let diagnostics = validate_str(
+            "include = []\n[profiles.work.model]\nmodel = \"x\"\nmax_token = 1\n",
+            None,
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].path.as_deref(),
+            Some("profiles.work.model.max_token")
+        );
+    }
+
+    #[test]
+    fn defaults_have_no_diagnostics() {
+        let defaults = include_str!("defaults.toml");
+        assert_eq!(validate_str(defaults, None), Vec::new());
+    }
+
+    #[test]
+    fn line_col_counts_characters() {
+        assert_eq!(line_col("a\nbé = 1", 5), (2, 3));
+        assert_eq!(line_col("", 10), (1, 1));
+    }
+}
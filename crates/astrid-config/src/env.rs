@@ -126,6 +126,31 @@ pub fn apply_env_fallbacks<S: ::std::hash::BuildHasher>(
     count
 }
 
+/// The variable [`apply_env_fallbacks`] takes `field_path` from: the first
+/// mapping for that field whose variable is set.
+pub(crate) fn fallback_var<S: ::std::hash::BuildHasher>(
+    field_path: &str,
+    env_vars: &HashMap<String, String, S>,
+) -> Option<&'static str> {
+    ENV_MAPPINGS
+        .iter()
+        .find(|m| m.field_path == field_path && env_vars.contains_key(m.var_name))
+        .map(|m| m.var_name)
+}
+
+/// A `${VAR}` reference that could not be expanded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedReference {
+    /// Dotted config path of the string holding the reference.
+    pub path: String,
+    /// Name of the missing environment variable.
+    pub var_name: String,
+    /// Set for `${VAR:?message}`: the value is unusable without `VAR`.
+    pub required: bool,
+    /// The `message` part of `${VAR:?message}`, if non-empty.
+    pub message: Option<String>,
+}
+
 /// Resolve `${VAR}` references in the workspace layer, restricted to only
 /// `ASTRID_*` and `ANTHROPIC_*` prefixed environment variables.
 ///
@@ -135,84 +160,154 @@ pub fn resolve_env_references_restricted<S: ::std::hash::BuildHasher>(
     val: &mut toml::Value,
     env_vars: &HashMap<String, String, S>,
 ) {
-    let restricted: HashMap<String, String> = env_vars
+    resolve_env_references(val, &restrict_env(env_vars));
+}
+
+/// The subset of `env_vars` a workspace config may reference.
+pub(crate) fn restrict_env<S: ::std::hash::BuildHasher>(
+    env_vars: &HashMap<String, String, S>,
+) -> HashMap<String, String> {
+    env_vars
         .iter()
         .filter(|(k, _)| k.starts_with("ASTRID_") || k.starts_with("ANTHROPIC_"))
         .map(|(k, v)| (k.clone(), v.clone()))
-        .collect();
-    resolve_env_references(val, &restricted);
+        .collect()
 }
 
 /// Resolve `${VAR}` references within string values in the config tree.
 ///
 /// Only string values are processed. References that don't resolve are left
-/// as-is (with a warning logged).
+/// as-is (with a debug log). See [`expand_env_references`] for the syntax.
 pub fn resolve_env_references<S: ::std::hash::BuildHasher>(
     val: &mut toml::Value,
     env_vars: &HashMap<String, String, S>,
+) {
+    for unresolved in expand_env_references(val, env_vars) {
+        debug!(
+            var = unresolved.var_name,
+            path = unresolved.path,
+            "unresolved env var reference in config"
+        );
+    }
+}
+
+/// Expand environment references in every string value of the tree and
+/// return the ones that could not be expanded.
+///
+/// Supported forms, following shell parameter expansion:
+///
+/// - `${VAR}` — the value of `VAR`; left as-is if unset.
+/// - `${VAR:-default}` — `default` if `VAR` is unset or empty.
+/// - `${VAR:?message}` — like `${VAR}`, but a missing `VAR` is reported as
+///   [`required`](UnresolvedReference::required).
+/// - `$${VAR}` — the literal text `${VAR}` (escapes one value).
+pub fn expand_env_references<S: ::std::hash::BuildHasher>(
+    val: &mut toml::Value,
+    env_vars: &HashMap<String, String, S>,
+) -> Vec<UnresolvedReference> {
+    let mut unresolved = Vec::new();
+    expand_value(val, "", env_vars, &mut unresolved);
+    unresolved
+}
+
+fn expand_value<S: ::std::hash::BuildHasher>(
+    val: &mut toml::Value,
+    path: &str,
+    env_vars: &HashMap<String, String, S>,
+    unresolved: &mut Vec<UnresolvedReference>,
 ) {
     match val {
         toml::Value::String(s) => {
-            *s = resolve_string_refs(s, env_vars);
+            *s = resolve_string_refs(s, env_vars, &mut |var_name, required, message| {
+                unresolved.push(UnresolvedReference {
+                    path: path.to_owned(),
+                    var_name: var_name.to_owned(),
+                    required,
+                    message: message.map(ToOwned::to_owned),
+                });
+            });
         },
         toml::Value::Table(table) => {
-            let keys: Vec<String> = table.keys().cloned().collect();
-            for key in keys {
-                if let Some(child) = table.get_mut(&key) {
-                    resolve_env_references(child, env_vars);
-                }
+            for (key, child) in table.iter_mut() {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                expand_value(child, &child_path, env_vars, unresolved);
             }
         },
         toml::Value::Array(arr) => {
-            for child in arr.iter_mut() {
-                resolve_env_references(child, env_vars);
+            for (i, child) in arr.iter_mut().enumerate() {
+                expand_value(child, &format!("{path}[{i}]"), env_vars, unresolved);
             }
         },
         _ => {},
     }
 }
 
-/// Replace `${VAR}` references in a string with their env var values.
+/// Replace `${VAR}` references in a string with their env var values,
+/// calling `missing(var, required, message)` for each one left unresolved.
 fn resolve_string_refs<S: ::std::hash::BuildHasher>(
     input: &str,
     env_vars: &HashMap<String, String, S>,
+    missing: &mut dyn FnMut(&str, bool, Option<&str>),
 ) -> String {
     let mut result = String::with_capacity(input.len());
-    let mut chars = input.chars().peekable();
-
-    while let Some(ch) = chars.next() {
-        if ch == '$' && chars.peek() == Some(&'{') {
-            chars.next(); // consume '{'
-            let mut var_name = String::new();
-            let mut closed = false;
-
-            for ch in chars.by_ref() {
-                if ch == '}' {
-                    closed = true;
-                    break;
-                }
-                var_name.push(ch);
-            }
+    let mut rest = input;
 
-            if closed && !var_name.is_empty() {
-                if let Some(val) = env_vars.get(&var_name) {
-                    result.push_str(val);
-                } else {
-                    debug!(var = var_name, "unresolved env var reference in config");
-                    // Leave the reference as-is.
-                    let _ = write!(result, "${{{var_name}}}");
-                }
+    while let Some(pos) = rest.find('$') {
+        let (before, from_dollar) = rest.split_at(pos);
+        result.push_str(before);
+
+        // `$${` escapes a reference: emit `${` and copy the rest verbatim.
+        if let Some(after) = from_dollar.strip_prefix("$${") {
+            result.push_str("${");
+            rest = after;
+            continue;
+        }
+
+        let Some(body_and_rest) = from_dollar.strip_prefix("${") else {
+            result.push('$');
+            rest = from_dollar.get(1..).unwrap_or_default();
+            continue;
+        };
+        let Some((body, after)) = body_and_rest
+            .split_once('}')
+            .filter(|(body, _)| !body.is_empty())
+        else {
+            // Malformed reference, leave as-is.
+            result.push_str(from_dollar);
+            rest = "";
+            continue;
+        };
+        rest = after;
+
+        let (var_name, modifier) = match body.find(":-").or_else(|| body.find(":?")) {
+            Some(i) => (&body[..i], &body[i..]),
+            None => (body, ""),
+        };
+        let value = env_vars.get(var_name);
+        let non_empty = value.filter(|v| !v.is_empty());
+        if let Some(default) = modifier.strip_prefix(":-") {
+            result.push_str(non_empty.map_or(default, String::as_str));
+        } else if let Some(message) = modifier.strip_prefix(":?") {
+            if let Some(v) = non_empty {
+                result.push_str(v);
             } else {
-                // Malformed reference, leave as-is.
-                result.push('$');
-                result.push('{');
-                result.push_str(&var_name);
+                missing(var_name, true, Some(message).filter(|m| !m.is_empty()));
+                let _ = write!(result, "${{{body}}}");
             }
+        } else if let Some(v) = value {
+            result.push_str(v);
         } else {
-            result.push(ch);
+            missing(var_name, false, None);
+            // Leave the reference as-is.
+            let _ = write!(result, "${{{var_name}}}");
         }
     }
 
+    result.push_str(rest);
     result
 }
 
@@ -449,4 +544,56 @@ api_key = "${ANTHROPIC_API_KEY}""#,
 
         assert_eq!(val["model"]["api_key"].as_str().unwrap(), "sk-ant-test");
     }
+
+    // ---- Expansion syntax ----
+
+    fn expand(input: &str, env: &HashMap<String, String>) -> (String, Vec<UnresolvedReference>) {
+        let mut val = toml::Value::String(input.to_owned());
+        let unresolved = expand_env_references(&mut val, env);
+        (val.as_str().unwrap().to_owned(), unresolved)
+    }
+
+    #[test]
+    fn test_default_used_when_unset_or_empty() {
+        let env = make_env(&[("EMPTY", ""), ("SET", "v")]);
+        assert_eq!(expand("${UNSET:-d}", &env).0, "d");
+        assert_eq!(expand("${EMPTY:-d}", &env).0, "d");
+        assert_eq!(expand("${SET:-d}", &env).0, "v");
+        assert_eq!(expand("a-${UNSET:-}-b", &env).0, "a--b");
+    }
+
+    #[test]
+    fn test_required_reference_reported() {
+        let (out, unresolved) = expand("${NEEDED:?set NEEDED}", &HashMap::new());
+        assert_eq!(out, "${NEEDED:?set NEEDED}");
+        assert_eq!(
+            unresolved,
+            [UnresolvedReference {
+                path: String::new(),
+                var_name: "NEEDED".to_owned(),
+                required: true,
+                message: Some("set NEEDED".to_owned()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_escaped_reference_is_literal() {
+        let env = make_env(&[("X", "1")]);
+        assert_eq!(
+            expand("$${X} and ${X}", &env),
+            ("${X} and 1".to_owned(), vec![])
+        );
+        assert_eq!(expand("cost: $5, $$", &env).0, "cost: $5, $$");
+        assert_eq!(expand("${unclosed", &env).0, "${unclosed");
+    }
+
+    #[test]
+    fn test_unresolved_paths_are_dotted() {
+        let mut val: toml::Value =
+            toml::from_str("[servers.fs]\nargs = [\"ok\", \"${MISSING}\"]\n").unwrap();
+        let unresolved = expand_env_references(&mut val, &HashMap::<String, String>::new());
+        assert_eq!(unresolved[0].path, "servers.fs.args[1]");
+        assert!(!unresolved[0].required);
+    }
 }
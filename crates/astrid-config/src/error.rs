@@ -1,6 +1,8 @@
 use std::io;
 use thiserror::Error;
 
+use crate::diagnostics::Diagnostic;
+
 /// Configuration error type.
 #[derive(Debug, Error)]
 pub enum ConfigError {
@@ -14,6 +16,16 @@ pub enum ConfigError {
         source: io::Error,
     },
 
+    /// Failed to write a configuration file.
+    #[error("Failed to write config file at {path}: {source}")]
+    WriteError {
+        /// Path to the config file that could not be written.
+        path: String,
+        /// Underlying I/O error.
+        #[source]
+        source: io::Error,
+    },
+
     /// Failed to parse TOML configuration.
     #[error("Failed to parse config file at {path}: {source}")]
     ParseError {
@@ -24,6 +36,15 @@ pub enum ConfigError {
         source: toml::de::Error,
     },
 
+    /// Configuration file is invalid; each diagnostic pinpoints one problem.
+    #[error("Invalid config file {path}:{}", render(.diagnostics))]
+    Invalid {
+        /// Path to the config file (or `<merged config>`).
+        path: String,
+        /// Everything wrong with the file, errors first by position.
+        diagnostics: Vec<Diagnostic>,
+    },
+
     /// Configuration validation failed.
     #[error("Validation error in field '{field}': {message}")]
     ValidationError {
@@ -51,10 +72,46 @@ pub enum ConfigError {
         message: String,
     },
 
+    /// The selected profile is not defined in any config file.
+    #[error("Unknown config profile '{name}' (available: {})", list(.available))]
+    UnknownProfile {
+        /// Requested profile name.
+        name: String,
+        /// Profiles that are defined.
+        available: Vec<String>,
+    },
+
+    /// The requested persona has no file in the personas directory.
+    #[error("Unknown persona '{name}' (available: {})", list(.available))]
+    UnknownPersona {
+        /// Requested persona name.
+        name: String,
+        /// Personas that are defined.
+        available: Vec<String>,
+    },
+
     /// Could not determine home directory.
     #[error("Could not determine home directory")]
     NoHomeDir,
 }
 
+fn render(diagnostics: &[Diagnostic]) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::new();
+    for d in diagnostics {
+        let _ = write!(out, "\n  {d}");
+    }
+    out
+}
+
+fn list(names: &[String]) -> String {
+    if names.is_empty() {
+        "none".to_owned()
+    } else {
+        names.join(", ")
+    }
+}
+
 /// Result type for configuration operations.
 pub type ConfigResult<T> = Result<T, ConfigError>;
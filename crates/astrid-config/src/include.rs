@@ -0,0 +1,455 @@
+//! Per-file layer loading: `include` directives and `${VAR}` expansion.
+//!
+//! A config file may pull in shared fragments with a top-level
+//! `include = ["./security.toml", "~/common.toml"]`. Included files are
+//! merged in order *beneath* the including file's own keys: the including
+//! file always wins, and a later include overrides an earlier one. Relative
+//! paths resolve against the including file's directory, `~/` against the
+//! user's home directory. Includes may nest up to [`MAX_INCLUDE_DEPTH`]
+//! levels; a file that (indirectly) includes itself is rejected.
+//!
+//! Every file has its string values expanded (see
+//! [`expand_env_references`]) and its deprecated keys migrated (see
+//! [`migrate`]) before merging, so both are reported against the file that
+//! contains them.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::diagnostics::{self, Analysis, Diagnostic, Severity};
+use crate::env::{UnresolvedReference, expand_env_references};
+use crate::error::{ConfigError, ConfigResult};
+use crate::loader::read_config;
+use crate::merge::{deep_merge, deep_merge_tracking};
+use crate::migrate::{self, MigrationOutcome};
+
+/// Maximum nesting of `include` directives below a layer's root file.
+pub const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// How the files of one config layer are loaded.
+pub(crate) struct LayerOptions<'a> {
+    /// Variables that `${VAR}` references may expand to.
+    pub(crate) env_vars: &'a HashMap<String, String>,
+    /// Includes must resolve inside this (canonical) directory.
+    pub(crate) confine_to: Option<PathBuf>,
+    /// Appended to missing-variable diagnostics, e.g. to explain that the
+    /// layer only sees some variables.
+    pub(crate) env_note: Option<&'static str>,
+}
+
+/// A loaded layer: the merged tree of its root file and includes.
+pub(crate) struct LoadedLayer {
+    /// The layer's config tree, `include` removed.
+    pub(crate) value: toml::Value,
+    /// Dotted leaf path → the file that set it.
+    pub(crate) origins: HashMap<String, PathBuf>,
+    /// Warnings from the root file and its includes.
+    pub(crate) warnings: Vec<Diagnostic>,
+}
+
+impl LoadedLayer {
+    fn empty() -> Self {
+        Self {
+            value: toml::Value::Table(toml::map::Map::new()),
+            origins: HashMap::new(),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// Load the layer rooted at `path` (already read into `content`), with its
+/// includes merged beneath it and `${VAR}` references expanded.
+///
+/// Warnings are logged; any error fails the whole layer.
+pub(crate) fn load_layer(
+    path: &Path,
+    content: &str,
+    options: &LayerOptions<'_>,
+) -> ConfigResult<LoadedLayer> {
+    let mut stack = Vec::new();
+    load_tree(path, content, options, &mut stack)
+}
+
+fn load_tree(
+    path: &Path,
+    content: &str,
+    options: &LayerOptions<'_>,
+    stack: &mut Vec<PathBuf>,
+) -> ConfigResult<LoadedLayer> {
+    let label = path.display().to_string();
+    let mut analysis = diagnostics::analyze(content, Some(&label));
+    let mut value = match analysis.value.take() {
+        Some(value) if analysis.config.is_some() => value,
+        _ => {
+            return Err(ConfigError::Invalid {
+                path: label,
+                diagnostics: analysis.diagnostics,
+            });
+        },
+    };
+
+    // Expand first so include paths may use variables too.
+    for unresolved in expand_env_references(&mut value, options.env_vars) {
+        let (severity, message) = describe(&unresolved, options.env_note);
+        analysis.report(severity, &unresolved.path, message, content, Some(&label));
+    }
+
+    for applied in migrate::apply(&mut value) {
+        let severity = if matches!(applied.outcome, MigrationOutcome::Failed(_)) {
+            Severity::Error
+        } else {
+            Severity::Warning
+        };
+        analysis.report(
+            severity,
+            &applied.from,
+            applied.to_string(),
+            content,
+            Some(&label),
+        );
+    }
+
+    let includes = value.as_table_mut().and_then(|t| t.remove("include"));
+    let mut merged = LoadedLayer::empty();
+    if let Some(includes) = includes {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        stack.push(canonical);
+        let result = merge_includes(&includes, path, content, options, stack, &mut analysis);
+        stack.pop();
+        merged = result?;
+    }
+
+    if analysis.diagnostics.iter().any(Diagnostic::is_error) {
+        return Err(ConfigError::Invalid {
+            path: label,
+            diagnostics: analysis.diagnostics,
+        });
+    }
+    for d in &analysis.diagnostics {
+        tracing::warn!("{d}");
+    }
+    merged.warnings.append(&mut analysis.diagnostics);
+
+    deep_merge_tracking(
+        &mut merged.value,
+        &value,
+        "",
+        &path.to_path_buf(),
+        &mut merged.origins,
+    );
+    Ok(merged)
+}
+
+/// Load each entry of an `include` array and merge them in order. Problems
+/// with an entry itself (bad path, cycle, too deep) are reported on
+/// `analysis`; errors inside an included file fail immediately.
+fn merge_includes(
+    includes: &toml::Value,
+    path: &Path,
+    content: &str,
+    options: &LayerOptions<'_>,
+    stack: &mut Vec<PathBuf>,
+    analysis: &mut Analysis,
+) -> ConfigResult<LoadedLayer> {
+    let label = path.display().to_string();
+    let mut merged = LoadedLayer::empty();
+    let Some(entries) = includes.as_array() else {
+        analysis.report(
+            Severity::Error,
+            "include",
+            "`include` must be an array of file paths".to_owned(),
+            content,
+            Some(&label),
+        );
+        return Ok(merged);
+    };
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    for (i, entry) in entries.iter().enumerate() {
+        let entry_path = format!("include[{i}]");
+        let mut report = |message: String| {
+            analysis.report(Severity::Error, &entry_path, message, content, Some(&label));
+        };
+
+        let Some(raw) = entry.as_str() else {
+            report("`include` entries must be file paths".to_owned());
+            continue;
+        };
+        let target = match resolve_path(raw, base_dir) {
+            Ok(target) => target,
+            Err(message) => {
+                report(message);
+                continue;
+            },
+        };
+        let canonical = match target.canonicalize() {
+            Ok(canonical) => canonical,
+            Err(e) => {
+                report(format!("cannot include `{raw}`: {e}"));
+                continue;
+            },
+        };
+        if let Some(root) = &options.confine_to
+            && !canonical.starts_with(root)
+        {
+            report(format!(
+                "cannot include `{raw}`: it is outside {}",
+                root.display()
+            ));
+            continue;
+        }
+        if stack.contains(&canonical) {
+            let cycle: Vec<String> = stack
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect();
+            report(format!("include cycle: {}", cycle.join(" -> ")));
+            continue;
+        }
+        if stack.len() > MAX_INCLUDE_DEPTH {
+            report(format!(
+                "cannot include `{raw}`: includes are nested more than \
+                 {MAX_INCLUDE_DEPTH} levels deep"
+            ));
+            continue;
+        }
+
+        let Some(source) = read_config(&canonical)? else {
+            report(format!("cannot include `{raw}`: file not found"));
+            continue;
+        };
+        let fragment = load_tree(&target, &source, options, stack)?;
+        deep_merge(&mut merged.value, &fragment.value);
+        merged.origins.extend(fragment.origins);
+        merged.warnings.extend(fragment.warnings);
+    }
+    Ok(merged)
+}
+
+/// Resolve an include entry relative to the including file's directory.
+fn resolve_path(raw: &str, base_dir: &Path) -> Result<PathBuf, String> {
+    if let Some(rest) = raw.strip_prefix("~/") {
+        let home = directories::BaseDirs::new()
+            .ok_or_else(|| format!("cannot include `{raw}`: no home directory"))?;
+        return Ok(home.home_dir().join(rest));
+    }
+    Ok(base_dir.join(raw))
+}
+
+fn describe(unresolved: &UnresolvedReference, note: Option<&str>) -> (Severity, String) {
+    let mut message = format!("environment variable `{}` is not set", unresolved.var_name);
+    if let Some(custom) = &unresolved.message {
+        message = format!("{message}: {custom}");
+    }
+    if let Some(note) = note {
+        message = format!("{message} ({note})");
+    }
+    if unresolved.required {
+        (Severity::Error, message)
+    } else {
+        (
+            Severity::Warning,
+            format!("{message}; the reference is kept as written"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn load(path: &Path, env: &HashMap<String, String>) -> ConfigResult<toml::Value> {
+        let content = std::fs::read_to_string(path).unwrap();
+        let options = LayerOptions {
+            env_vars: env,
+            confine_to: None,
+            env_note: None,
+        };
+        load_layer(path, &content, &options).map(|layer| layer.value)
+    }
+
+    fn messages(err: ConfigError) -> Vec<String> {
+        match err {
+            ConfigError::Invalid { diagnostics, .. } => {
+                diagnostics.iter().map(ToString::to_string).collect()
+            },
+            other => panic!("expected Invalid, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn includes_merge_beneath_own_keys_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "a.toml",
+            "[model]\nmodel = \"from-a\"\nmax_tokens = 1\n",
+        );
+        write(
+            dir.path(),
+            "b.toml",
+            "[model]\nmax_tokens = 2\ntemperature = 0.5\n",
+        );
+        let root = write(
+            dir.path(),
+            "config.toml",
+            "include = [\"a.toml\", \"./b.toml\"]\n[model]\ntemperature = 0.1\n",
+        );
+
+        let value = load(&root, &HashMap::new()).unwrap();
+        assert!(value.get("include").is_none());
+        assert_eq!(value["model"]["model"].as_str(), Some("from-a"));
+        assert_eq!(value["model"]["max_tokens"].as_integer(), Some(2));
+        assert_eq!(value["model"]["temperature"].as_float(), Some(0.1));
+    }
+
+    #[test]
+    fn nested_includes_resolve_relative_to_their_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("shared")).unwrap();
+        write(
+            &dir.path().join("shared"),
+            "inner.toml",
+            "[model]\nmodel = \"inner\"\n",
+        );
+        write(
+            &dir.path().join("shared"),
+            "outer.toml",
+            "include = [\"inner.toml\"]\n",
+        );
+        let root = write(
+            dir.path(),
+            "config.toml",
+            "include = [\"shared/outer.toml\"]\n",
+        );
+
+        let value = load(&root, &HashMap::new()).unwrap();
+        assert_eq!(value["model"]["model"].as_str(), Some("inner"));
+    }
+
+    #[test]
+    fn include_cycle_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "a.toml", "include = [\"config.toml\"]\n");
+        let root = write(dir.path(), "config.toml", "include = [\"a.toml\"]\n");
+
+        let messages = messages(load(&root, &HashMap::new()).unwrap_err());
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("a.toml:1:12: error: include[0]: include cycle"));
+        assert!(messages[0].ends_with("config.toml"));
+    }
+
+    #[test]
+    fn include_depth_is_limited() {
+        let dir = tempfile::tempdir().unwrap();
+        let levels = MAX_INCLUDE_DEPTH.saturating_add(2);
+        for i in 0..levels {
+            write(
+                dir.path(),
+                &format!("{i}.toml"),
+                &format!("include = [\"{}.toml\"]\n", i.saturating_add(1)),
+            );
+        }
+        write(dir.path(), &format!("{levels}.toml"), "");
+
+        let messages = messages(load(&dir.path().join("0.toml"), &HashMap::new()).unwrap_err());
+        assert!(
+            messages[0].contains("nested more than 8 levels deep"),
+            "{messages:?}"
+        );
+    }
+
+    #[test]
+    fn missing_include_names_the_including_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = write(dir.path(), "config.toml", "include = [\"nope.toml\"]\n");
+
+        let messages = messages(load(&root, &HashMap::new()).unwrap_err());
+        assert!(messages[0].contains("config.toml:1:12: error: include[0]: cannot include"));
+    }
+
+    #[test]
+    fn include_outside_confined_root_is_rejected() {
+        let outside = tempfile::tempdir().unwrap();
+        let inside = tempfile::tempdir().unwrap();
+        let fragment = write(outside.path(), "secrets.toml", "[model]\nmodel = \"x\"\n");
+        let root = write(
+            inside.path(),
+            "config.toml",
+            &format!("include = [{:?}]\n", fragment.display().to_string()),
+        );
+        let content = std::fs::read_to_string(&root).unwrap();
+        let env = HashMap::new();
+        let options = LayerOptions {
+            env_vars: &env,
+            confine_to: Some(inside.path().canonicalize().unwrap()),
+            env_note: None,
+        };
+
+        let messages = messages(load_layer(&root, &content, &options).err().unwrap());
+        assert!(messages[0].contains("is outside"), "{messages:?}");
+    }
+
+    #[test]
+    fn variables_expand_in_values_and_include_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "shared.toml",
+            "[model]\nprovider = \"${PROVIDER:-claude}\"\n",
+        );
+        let root = write(
+            dir.path(),
+            "config.toml",
+            "include = [\"${SHARED}.toml\"]\n[model]\napi_key = \"${KEY}\"\nmodel = \"$${KEY}\"\n",
+        );
+        let env: HashMap<String, String> = [("SHARED", "shared"), ("KEY", "sk-1")]
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+            .collect();
+
+        let value = load(&root, &env).unwrap();
+        assert_eq!(value["model"]["provider"].as_str(), Some("claude"));
+        assert_eq!(value["model"]["api_key"].as_str(), Some("sk-1"));
+        assert_eq!(value["model"]["model"].as_str(), Some("${KEY}"));
+    }
+
+    #[test]
+    fn missing_required_variable_names_variable_and_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = write(
+            dir.path(),
+            "config.toml",
+            "[model]\napi_key = \"${ANTHROPIC_API_KEY:?set it in your shell}\"\n",
+        );
+
+        let messages = messages(load(&root, &HashMap::new()).unwrap_err());
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("config.toml:2:1: error: model.api_key:"));
+        assert!(
+            messages[0].contains(
+                "environment variable `ANTHROPIC_API_KEY` is not set: set it in your shell"
+            )
+        );
+    }
+
+    #[test]
+    fn missing_optional_variable_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = write(
+            dir.path(),
+            "config.toml",
+            "[model]\nmodel = \"${UNSET_MODEL}\"\n",
+        );
+
+        let value = load(&root, &HashMap::new()).unwrap();
+        assert_eq!(value["model"]["model"].as_str(), Some("${UNSET_MODEL}"));
+    }
+}
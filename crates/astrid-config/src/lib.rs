@@ -20,15 +20,23 @@
 //! From highest to lowest priority:
 //!
 //! 1. **Workspace** (`{workspace}/.astrid/config.toml`) — can only *tighten* security
-//! 2. **User** (`~/.astrid/config.toml`)
-//! 3. **System** (`/etc/astrid/config.toml`)
-//! 4. **Environment variables** (`ASTRID_*`, `ANTHROPIC_*`) — fallback only
-//! 5. **Embedded defaults** (`defaults.toml` compiled into binary)
+//! 2. **Profile** (`[profiles.<name>]`, selected via `ASTRID_PROFILE` or
+//!    [`Config::load_with_profile`])
+//! 3. **User** (`~/.astrid/config.toml`)
+//! 4. **System** (`/etc/astrid/config.toml`)
+//! 5. **Environment variables** (`ASTRID_*`, `ANTHROPIC_*`) — fallback only
+//! 6. **Embedded defaults** (`defaults.toml` compiled into binary)
+//!
+//! Any config file may list shared fragments in a top-level
+//! `include = [...]`; they are merged beneath the file's own keys, within
+//! the same layer. String values may reference environment variables as
+//! `${VAR}`, `${VAR:-default}` or `${VAR:?message}` (`$${VAR}` escapes).
 //!
 //! # Design
 //!
 //! This crate has **no dependencies on other internal astrid crates**. It only
-//! depends on `serde`, `toml`, `thiserror`, `tracing`, and `directories`.
+//! depends on `serde`, `toml`, `toml_edit` (spans for diagnostics),
+//! `schemars`, `strsim`, `thiserror`, `tracing`, and `directories`.
 //! Conversion from config types to domain types happens at the integration
 //! boundary (CLI startup, gateway init) via bridge modules.
 
@@ -39,14 +47,24 @@
 #![deny(clippy::unwrap_used)]
 #![cfg_attr(test, allow(clippy::unwrap_used))]
 
+/// Span-aware config diagnostics and JSON Schema.
+pub mod diagnostics;
 /// Environment variable fallback resolution.
 pub mod env;
 /// Configuration error types.
 pub mod error;
+/// `include` directives and per-file `${VAR}` expansion.
+pub mod include;
 /// Configuration file discovery and loading.
 pub mod loader;
 /// Layered configuration merging with precedence.
 pub mod merge;
+/// Deprecated keys and their migrations.
+pub mod migrate;
+/// Named personas that override `[spark]` for a session.
+pub mod persona;
+/// Named profiles selected at load time.
+pub mod profile;
 /// Resolved configuration display and serialization.
 pub mod show;
 /// Configuration struct definitions.
@@ -55,6 +73,7 @@ pub mod types;
 pub mod validate;
 
 // Re-export primary types at the crate root.
+pub use diagnostics::{Diagnostic, Severity};
 pub use error::{ConfigError, ConfigResult};
 pub use show::{ResolvedConfig, ShowFormat};
 pub use types::*;
@@ -72,6 +91,20 @@ impl Config {
         loader::load(workspace_root, None)
     }
 
+    /// Load configuration with the named profile applied over the user
+    /// layer, overriding `ASTRID_PROFILE`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigError::UnknownProfile`] if no config file defines the
+    /// profile, or any error [`Config::load`] can return.
+    pub fn load_with_profile(
+        workspace_root: Option<&std::path::Path>,
+        profile: &str,
+    ) -> ConfigResult<ResolvedConfig> {
+        loader::load_with_profile(workspace_root, None, profile)
+    }
+
     /// Load configuration with an explicit home directory override.
     ///
     /// # Errors
@@ -94,4 +127,33 @@ impl Config {
     pub fn load_file(path: &std::path::Path) -> ConfigResult<Self> {
         loader::load_file(path)
     }
+
+    /// JSON Schema for `config.toml`, for editor autocompletion.
+    #[must_use]
+    pub fn schema() -> serde_json::Value {
+        diagnostics::schema().clone()
+    }
+
+    /// Check a single config file and return every problem found, each
+    /// with its line/column, config path, and a did-you-mean suggestion
+    /// for misspelled keys. An empty result means the file is valid.
+    #[must_use]
+    pub fn validate_file(path: &std::path::Path) -> Vec<Diagnostic> {
+        diagnostics::validate_file(path)
+    }
+
+    /// Rewrite deprecated keys in a config file to their current names,
+    /// preserving comments. With `write == false` nothing is written and
+    /// the result shows what would change.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] if the file cannot be read, parsed, or
+    /// written.
+    pub fn migrate_file(
+        path: &std::path::Path,
+        write: bool,
+    ) -> ConfigResult<migrate::FileMigration> {
+        migrate::migrate_file(path, write)
+    }
 }
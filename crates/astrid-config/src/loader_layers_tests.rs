@@ -0,0 +1,385 @@
+//! Layered loading: includes, `${VAR}` expansion, profiles, and
+//! provenance across the full precedence chain.
+
+use super::*;
+use crate::merge::Source;
+use crate::show::ShowFormat;
+
+// ---- Includes and ${VAR} expansion across layers ----
+
+fn env(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| ((*k).to_owned(), (*v).to_owned()))
+        .collect()
+}
+
+#[test]
+fn test_include_precedence_across_layers() {
+    let home = tempfile::tempdir().unwrap();
+    let ws = tempfile::tempdir().unwrap();
+    std::fs::write(
+        home.path().join("common.toml"),
+        "[model]\nmodel = \"from-include\"\nmax_tokens = 1000\n\n[logging]\nlevel = \"trace\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        home.path().join("config.toml"),
+        "include = [\"common.toml\"]\n[model]\nmax_tokens = 2000\n",
+    )
+    .unwrap();
+    std::fs::create_dir(ws.path().join(".astrid")).unwrap();
+    std::fs::write(
+        ws.path().join(".astrid/config.toml"),
+        "[model]\nmodel = \"from-workspace\"\n",
+    )
+    .unwrap();
+
+    let resolved = load_with_env(
+        Some(ws.path()),
+        Some(home.path()),
+        None,
+        &env(&[("ASTRID_LOG_LEVEL", "debug")]),
+    )
+    .unwrap();
+
+    // Own keys beat the include; a later layer beats both.
+    assert_eq!(resolved.config.model.max_tokens, 2000);
+    assert_eq!(resolved.config.model.model, "from-workspace");
+    // Included values belong to the including layer, so env fallbacks
+    // do not replace them.
+    assert_eq!(resolved.config.logging.level, "trace");
+    assert_eq!(
+        resolved.field_sources.get("logging.level"),
+        Some(&ConfigLayer::User)
+    );
+    assert_eq!(resolved.loaded_files.len(), 2);
+}
+
+#[test]
+fn test_workspace_reference_to_unlisted_var_stays_literal() {
+    let ws = tempfile::tempdir().unwrap();
+    let home = tempfile::tempdir().unwrap();
+    std::fs::create_dir(ws.path().join(".astrid")).unwrap();
+    std::fs::write(
+        ws.path().join(".astrid/config.toml"),
+        "[model]\nmodel = \"${HOME}\"\n",
+    )
+    .unwrap();
+
+    let resolved = load_with_env(
+        Some(ws.path()),
+        Some(home.path()),
+        None,
+        &env(&[("HOME", "/home/user")]),
+    )
+    .unwrap();
+
+    assert_eq!(resolved.config.model.model, "${HOME}");
+}
+
+#[test]
+fn test_required_var_missing_fails_load() {
+    let home = tempfile::tempdir().unwrap();
+    std::fs::write(
+        home.path().join("config.toml"),
+        "[model]\napi_key = \"${ASTRID_TEST_KEY:?}\"\n",
+    )
+    .unwrap();
+
+    let err = load_with_env(None, Some(home.path()), None, &HashMap::new()).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("config.toml:2:1: error: model.api_key"));
+    assert!(message.contains("`ASTRID_TEST_KEY` is not set"));
+
+    let resolved = load_with_env(
+        None,
+        Some(home.path()),
+        None,
+        &env(&[("ASTRID_TEST_KEY", "sk-test")]),
+    )
+    .unwrap();
+    assert_eq!(resolved.config.model.api_key.as_deref(), Some("sk-test"));
+}
+
+// ---- Profiles ----
+
+fn write_profile_fixture(home: &Path) {
+    std::fs::write(
+        home.join("config.toml"),
+        r#"
+[model]
+model = "base-model"
+max_tokens = 1000
+
+[budget]
+session_max_usd = 10.0
+
+[profiles.work.model]
+model = "work-model"
+
+[profiles.work.model.pricing]
+input_per_million = 1.5
+
+[profiles.work.budget]
+session_max_usd = 50.0
+
+[profiles.personal.model]
+model = "personal-model"
+"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_profile_overlays_nested_tables() {
+    let home = tempfile::tempdir().unwrap();
+    write_profile_fixture(home.path());
+
+    let resolved = load_with_env(None, Some(home.path()), Some("work"), &HashMap::new()).unwrap();
+
+    assert_eq!(resolved.profile.as_deref(), Some("work"));
+    assert_eq!(resolved.config.model.model, "work-model");
+    // Keys the profile leaves alone keep their base values.
+    assert_eq!(resolved.config.model.max_tokens, 1000);
+    assert!((resolved.config.model.pricing.input_per_million - 1.5).abs() < f64::EPSILON);
+    assert_eq!(
+        resolved.field_sources.get("model.model"),
+        Some(&ConfigLayer::Profile("work".to_owned()))
+    );
+    assert_eq!(
+        resolved.field_sources.get("model.max_tokens"),
+        Some(&ConfigLayer::User)
+    );
+    assert!(
+        resolved
+            .field_sources
+            .keys()
+            .all(|k| !k.starts_with("profiles."))
+    );
+
+    let shown = resolved.show(ShowFormat::Toml, None).unwrap();
+    assert!(shown.contains("# Active profile: work"));
+    assert!(shown.contains("[profile (work)]"));
+}
+
+#[test]
+fn test_profile_selected_by_env_and_overridden_by_argument() {
+    let home = tempfile::tempdir().unwrap();
+    write_profile_fixture(home.path());
+    let vars = env(&[(PROFILE_ENV_VAR, "personal")]);
+
+    let from_env = load_with_env(None, Some(home.path()), None, &vars).unwrap();
+    assert_eq!(from_env.config.model.model, "personal-model");
+
+    let explicit = load_with_env(None, Some(home.path()), Some("work"), &vars).unwrap();
+    assert_eq!(explicit.config.model.model, "work-model");
+
+    let none = load_with_env(None, Some(home.path()), None, &HashMap::new()).unwrap();
+    assert_eq!(none.profile, None);
+    assert_eq!(none.config.model.model, "base-model");
+}
+
+#[test]
+fn test_unknown_profile_errors() {
+    let home = tempfile::tempdir().unwrap();
+    write_profile_fixture(home.path());
+
+    let err = load_with_env(None, Some(home.path()), Some("wrok"), &HashMap::new()).unwrap_err();
+    match err {
+        ConfigError::UnknownProfile { name, available } => {
+            assert_eq!(name, "wrok");
+            assert_eq!(available, ["personal", "work"]);
+        },
+        other => panic!("expected UnknownProfile, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_workspace_tightens_after_profile() {
+    let home = tempfile::tempdir().unwrap();
+    let ws = tempfile::tempdir().unwrap();
+    write_profile_fixture(home.path());
+    std::fs::create_dir(ws.path().join(".astrid")).unwrap();
+    std::fs::write(
+        ws.path().join(".astrid/config.toml"),
+        "[budget]\nsession_max_usd = 500.0\n\n[profiles.work.model]\nmodel = \"ws\"\n",
+    )
+    .unwrap();
+
+    let resolved = load_with_env(
+        Some(ws.path()),
+        Some(home.path()),
+        Some("work"),
+        &HashMap::new(),
+    )
+    .unwrap();
+
+    // The profile raised the budget; the workspace cannot raise it further.
+    assert!((resolved.config.budget.session_max_usd - 50.0).abs() < f64::EPSILON);
+    // Workspace profiles are ignored.
+    assert_eq!(resolved.config.model.model, "work-model");
+}
+
+// ---- Provenance ----
+
+#[test]
+fn test_provenance_for_overridden_default_and_env_keys() {
+    let home = tempfile::tempdir().unwrap();
+    let ws = tempfile::tempdir().unwrap();
+    let common = home.path().join("common.toml");
+    let user = home.path().join("config.toml");
+    std::fs::write(&common, "[model]\ntemperature = 0.2\n").unwrap();
+    std::fs::write(
+        &user,
+        "include = [\"common.toml\"]\n[model]\nmax_tokens = 2000\n\n[budget]\nsession_max_usd = 20.0\n",
+    )
+    .unwrap();
+    std::fs::create_dir(ws.path().join(".astrid")).unwrap();
+    let ws_file = ws.path().join(".astrid/config.toml");
+    // The raise is reverted by tighten-only enforcement; the timeout sticks.
+    std::fs::write(
+        &ws_file,
+        "[budget]\nsession_max_usd = 900.0\n\n[timeouts]\nrequest_secs = 30\n",
+    )
+    .unwrap();
+
+    let resolved = load_with_env(
+        Some(ws.path()),
+        Some(home.path()),
+        None,
+        &env(&[
+            ("ASTRID_LOG_LEVEL", "debug"),
+            ("ASTRID_MODEL_API_URL", "https://proxy.example.com"),
+            ("OPENAI_API_KEY", "sk-x"),
+        ]),
+    )
+    .unwrap();
+    let source = |path: &str| resolved.provenance(path).unwrap();
+
+    assert_eq!(
+        source("model.max_tokens"),
+        Source {
+            layer: ConfigLayer::User,
+            origin: Origin::File(user.clone()),
+        }
+    );
+    // Included values name the fragment, not the including file.
+    assert_eq!(
+        source("model.temperature").origin,
+        Origin::File(home.path().join("common.toml"))
+    );
+    assert_eq!(
+        source("timeouts.request_secs"),
+        Source {
+            layer: ConfigLayer::Workspace,
+            origin: Origin::File(ws_file),
+        }
+    );
+    assert_eq!(
+        source("budget.session_max_usd"),
+        Source {
+            layer: ConfigLayer::User,
+            origin: Origin::File(user),
+        }
+    );
+    assert_eq!(
+        source("model.provider"),
+        Source {
+            layer: ConfigLayer::Defaults,
+            origin: Origin::Embedded,
+        }
+    );
+    // Env vars only fill fields no file (including defaults) set.
+    assert_eq!(source("logging.level").layer, ConfigLayer::Defaults);
+    assert_eq!(
+        source("model.api_url"),
+        Source {
+            layer: ConfigLayer::Environment,
+            origin: Origin::EnvVar("ASTRID_MODEL_API_URL".to_owned()),
+        }
+    );
+    assert_eq!(
+        source("model.api_key").origin,
+        Origin::EnvVar("OPENAI_API_KEY".to_owned())
+    );
+    assert_eq!(resolved.provenance("model.no_such_key"), None);
+}
+
+#[test]
+fn test_provenance_for_profile_names_defining_file() {
+    let home = tempfile::tempdir().unwrap();
+    write_profile_fixture(home.path());
+
+    let resolved = load_with_env(None, Some(home.path()), Some("work"), &HashMap::new()).unwrap();
+
+    assert_eq!(
+        resolved.provenance("model.model"),
+        Some(Source {
+            layer: ConfigLayer::Profile("work".to_owned()),
+            origin: Origin::File(home.path().join("config.toml")),
+        })
+    );
+}
+
+#[test]
+fn test_show_detailed_lists_sources() {
+    let home = tempfile::tempdir().unwrap();
+    std::fs::write(
+        home.path().join("config.toml"),
+        "[model]\nmax_tokens = 2000\n",
+    )
+    .unwrap();
+
+    let resolved = load_with_env(None, Some(home.path()), None, &HashMap::new()).unwrap();
+    let shown = resolved.show(ShowFormat::Detailed, None).unwrap();
+
+    let expected = format!(
+        "model.max_tokens = 2000  # user, from {}",
+        home.path().join("config.toml").display()
+    );
+    assert!(shown.lines().any(|l| l == expected), "{shown}");
+    assert!(shown.contains("model.provider = \"unknown\"  # defaults, from built-in defaults"));
+
+    let section = resolved.show(ShowFormat::Detailed, Some("model")).unwrap();
+    assert!(
+        section.lines().all(|l| l.starts_with("model.")),
+        "{section}"
+    );
+}
+
+// ---- Deprecated and unknown keys ----
+
+#[test]
+fn test_deprecated_keys_migrate_with_warnings() {
+    let home = tempfile::tempdir().unwrap();
+    std::fs::write(
+        home.path().join("config.toml"),
+        "[budget]\nmax_session_usd = 75.0\nsesion_max_usd = 1.0\n\n\
+         [profiles.ci.model]\napi_base = \"https://ci.example.com\"\n",
+    )
+    .unwrap();
+
+    let resolved = load_with_env(None, Some(home.path()), Some("ci"), &HashMap::new()).unwrap();
+
+    assert!((resolved.config.budget.session_max_usd - 75.0).abs() < f64::EPSILON);
+    assert_eq!(
+        resolved.config.model.api_url.as_deref(),
+        Some("https://ci.example.com")
+    );
+    let warnings: Vec<String> = resolved.warnings.iter().map(ToString::to_string).collect();
+    assert_eq!(warnings.len(), 3, "{warnings:?}");
+    assert!(warnings.iter().any(|w| w.contains(
+        "budget.max_session_usd: deprecated key; its value now lives at `budget.session_max_usd`"
+    )));
+    assert!(
+        warnings
+            .iter()
+            .any(|w| w.contains("profiles.ci.model.api_base: deprecated key"))
+    );
+    assert!(
+        warnings
+            .iter()
+            .any(|w| w.contains("unknown key `sesion_max_usd`"))
+    );
+}
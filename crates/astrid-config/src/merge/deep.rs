@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+#[cfg(doc)]
 use super::{ConfigLayer, FieldSources};
 
 /// Recursively deep-merge `overlay` into `base`.
@@ -24,12 +28,15 @@ pub fn deep_merge(base: &mut toml::Value, overlay: &toml::Value) {
 /// Deep-merge `overlay` into `base`, recording which layer set each leaf
 /// field. `prefix` is the dotted path prefix (e.g. `"model"`) and `layer`
 /// identifies where the overlay came from.
-pub fn deep_merge_tracking(
+///
+/// `layer` is usually a [`ConfigLayer`] recorded into [`FieldSources`], but
+/// any tag works (the layer loader records file paths this way).
+pub fn deep_merge_tracking<T: Clone, S: BuildHasher>(
     base: &mut toml::Value,
     overlay: &toml::Value,
     prefix: &str,
-    layer: &ConfigLayer,
-    sources: &mut FieldSources,
+    layer: &T,
+    sources: &mut HashMap<String, T, S>,
 ) {
     match (base, overlay) {
         (toml::Value::Table(base_table), toml::Value::Table(overlay_table)) => {
@@ -61,11 +68,11 @@ pub fn deep_merge_tracking(
 }
 
 /// Walk a value tree and record all leaf paths with their source layer.
-fn record_all_leaves(
+fn record_all_leaves<T: Clone, S: BuildHasher>(
     val: &toml::Value,
     prefix: &str,
-    layer: &ConfigLayer,
-    sources: &mut FieldSources,
+    layer: &T,
+    sources: &mut HashMap<String, T, S>,
 ) {
     if let toml::Value::Table(table) = val {
         for (key, child) in table {
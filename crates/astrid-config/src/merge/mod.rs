@@ -13,7 +13,7 @@ mod types;
 
 pub use deep::{deep_merge, deep_merge_tracking};
 pub use restrict::enforce_restrictions;
-pub use types::{ConfigLayer, FieldSources};
+pub use types::{ConfigLayer, FieldOrigins, FieldSources, Origin, Source};
 
 #[cfg(test)]
 mod tests;
@@ -289,6 +289,24 @@ pub fn enforce_restrictions(
         "timeouts.idle_secs",
     );
 
+    // logging.redaction: workspace cannot turn redaction down.
+    block_workspace_override(
+        merged,
+        baseline,
+        workspace_layer,
+        &["logging", "redaction"],
+        "logging.redaction",
+    );
+
+    // logging.otlp: workspace cannot redirect telemetry to another collector.
+    block_workspace_override(
+        merged,
+        baseline,
+        workspace_layer,
+        &["logging", "otlp"],
+        "logging.otlp",
+    );
+
     // hooks.allow_http_hooks: cannot enable (only disable).
     enforce_bool_only_false(
         merged,
@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
 
 /// Which configuration layer a value came from.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -9,6 +11,8 @@ pub enum ConfigLayer {
     System,
     /// User-level configuration (`~/.astrid/config.toml`).
     User,
+    /// A named profile (`[profiles.<name>]`), applied over the user layer.
+    Profile(String),
     /// Workspace-level configuration (`{workspace}/.astrid/config.toml`).
     Workspace,
     /// Environment variable fallback.
@@ -21,6 +25,7 @@ impl std::fmt::Display for ConfigLayer {
             Self::Defaults => write!(f, "defaults"),
             Self::System => write!(f, "system (/etc/astrid/config.toml)"),
             Self::User => write!(f, "user (~/.astrid/config.toml)"),
+            Self::Profile(name) => write!(f, "profile ({name})"),
             Self::Workspace => write!(f, "workspace (.astrid/config.toml)"),
             Self::Environment => write!(f, "environment variable"),
         }
@@ -29,3 +34,56 @@ impl std::fmt::Display for ConfigLayer {
 
 /// Tracks which layer set each field's value.
 pub type FieldSources = HashMap<String, ConfigLayer>;
+
+/// Where a value was read from, more precisely than its [`ConfigLayer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// The compiled-in `defaults.toml`, or a serde default.
+    Embedded,
+    /// A config file, including files pulled in with `include`.
+    File(PathBuf),
+    /// An environment variable fallback.
+    EnvVar(String),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Embedded => write!(f, "built-in defaults"),
+            Self::File(path) => {
+                let home = directories::BaseDirs::new().map(|d| d.home_dir().to_path_buf());
+                match home.and_then(|h| path.strip_prefix(h).ok().map(PathBuf::from)) {
+                    Some(relative) => write!(f, "~/{}", relative.display()),
+                    None => write!(f, "{}", path.display()),
+                }
+            },
+            Self::EnvVar(name) => write!(f, "${name}"),
+        }
+    }
+}
+
+/// Tracks which file or variable set each field's value.
+pub type FieldOrigins = HashMap<String, Origin>;
+
+/// Provenance of one resolved value: its layer and exact origin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    /// Layer whose value won.
+    pub layer: ConfigLayer,
+    /// File or variable within that layer.
+    pub origin: Origin,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let layer = match &self.layer {
+            ConfigLayer::Defaults => "defaults".to_owned(),
+            ConfigLayer::System => "system".to_owned(),
+            ConfigLayer::User => "user".to_owned(),
+            ConfigLayer::Profile(name) => format!("profile {name}"),
+            ConfigLayer::Workspace => "workspace".to_owned(),
+            ConfigLayer::Environment => "env".to_owned(),
+        };
+        write!(f, "{layer}, from {}", self.origin)
+    }
+}
@@ -0,0 +1,488 @@
+//! Deprecated config keys and their migrations.
+//!
+//! [`MIGRATIONS`] maps each retired key to its replacement. The loader
+//! applies the table to every file before merging and reports one warning
+//! per applied migration, so an old config keeps working instead of
+//! silently losing the setting (serde ignores unknown keys).
+//! [`migrate_file`] applies the same table to a file on disk, editing it
+//! with `toml_edit` so comments and layout survive.
+//!
+//! Paths are dotted; a `*` segment matches any key of a map (e.g. every
+//! entry under `servers`) and is substituted into the target path.
+
+use std::path::Path;
+
+use toml_edit::{DocumentMut, Item, Key, TableLike};
+
+use crate::error::{ConfigError, ConfigResult};
+
+/// Converts a deprecated value into the value for its replacement key.
+pub type Transform = fn(&toml::Value) -> Result<toml::Value, String>;
+
+/// One retired config key.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Deprecated dotted path.
+    pub from: &'static str,
+    /// Dotted path that replaces it.
+    pub to: &'static str,
+    /// Value conversion; `None` moves the value unchanged.
+    pub transform: Option<Transform>,
+}
+
+impl Migration {
+    const fn rename(from: &'static str, to: &'static str) -> Self {
+        Self {
+            from,
+            to,
+            transform: None,
+        }
+    }
+
+    const fn transform(from: &'static str, to: &'static str, transform: Transform) -> Self {
+        Self {
+            from,
+            to,
+            transform: Some(transform),
+        }
+    }
+}
+
+/// Every retired config key, oldest first.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration::rename("model.api_base", "model.api_url"),
+    Migration::rename("runtime.max_context", "runtime.max_context_tokens"),
+    Migration::rename(
+        "security.approval_timeout",
+        "security.approval_timeout_secs",
+    ),
+    Migration::rename("budget.max_session_usd", "budget.session_max_usd"),
+    Migration::rename("budget.max_per_action_usd", "budget.per_action_max_usd"),
+    Migration::rename("servers.*.cmd", "servers.*.command"),
+    Migration::rename("servers.*.working_dir", "servers.*.cwd"),
+    Migration::transform("logging.json", "logging.format", json_flag_to_format),
+];
+
+/// `logging.json = true` became `logging.format = "json"`.
+fn json_flag_to_format(value: &toml::Value) -> Result<toml::Value, String> {
+    match value.as_bool() {
+        Some(true) => Ok(toml::Value::String("json".to_owned())),
+        Some(false) => Ok(toml::Value::String("compact".to_owned())),
+        None => Err("expected `true` or `false`".to_owned()),
+    }
+}
+
+/// A migration applied to one concrete key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    /// Deprecated path found in the file (with `*` resolved).
+    pub from: String,
+    /// Path the value was moved to.
+    pub to: String,
+    /// What happened to the value.
+    pub outcome: MigrationOutcome,
+}
+
+/// Result of applying one migration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MigrationOutcome {
+    /// The value now lives at the new path.
+    Moved,
+    /// The new path was already set; the deprecated value was dropped.
+    Superseded,
+    /// The transform rejected the value; the deprecated key was left alone.
+    Failed(String),
+}
+
+impl std::fmt::Display for AppliedMigration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.outcome {
+            MigrationOutcome::Moved => {
+                write!(f, "deprecated key; its value now lives at `{}`", self.to)
+            },
+            MigrationOutcome::Superseded => write!(
+                f,
+                "deprecated key ignored because `{}` is also set; remove it",
+                self.to
+            ),
+            MigrationOutcome::Failed(reason) => {
+                write!(f, "cannot migrate to `{}`: {reason}", self.to)
+            },
+        }
+    }
+}
+
+/// Whether `path` (as reported by the unknown-key walk) is a deprecated key.
+/// Keys inside `[profiles.<name>]` are matched relative to the profile.
+pub(crate) fn is_deprecated(path: &str) -> bool {
+    let relative = path
+        .strip_prefix("profiles.")
+        .and_then(|rest| rest.split_once('.'))
+        .map_or(path, |(_, rest)| rest);
+    MIGRATIONS
+        .iter()
+        .any(|m| matches(&segments(m.from), &segments(relative)))
+}
+
+/// Apply every migration to a parsed file, including inside each profile.
+pub(crate) fn apply(value: &mut toml::Value) -> Vec<AppliedMigration> {
+    let mut applied = apply_at(value, "");
+    if let Some(profiles) = value
+        .get_mut("profiles")
+        .and_then(toml::Value::as_table_mut)
+    {
+        for (name, profile) in profiles.iter_mut() {
+            applied.extend(apply_at(profile, &format!("profiles.{name}.")));
+        }
+    }
+    applied
+}
+
+fn apply_at(root: &mut toml::Value, prefix: &str) -> Vec<AppliedMigration> {
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS {
+        for (from, to) in expand(migration, |path| keys_at(root, path)) {
+            let Some(old) = take(root, &from) else {
+                continue;
+            };
+            let outcome = if get(root, &to).is_some() {
+                MigrationOutcome::Superseded
+            } else {
+                match convert(migration, &old) {
+                    Ok(new) => {
+                        put(root, &to, new);
+                        MigrationOutcome::Moved
+                    },
+                    Err(reason) => {
+                        put(root, &from, old);
+                        MigrationOutcome::Failed(reason)
+                    },
+                }
+            };
+            applied.push(AppliedMigration {
+                from: format!("{prefix}{}", from.join(".")),
+                to: format!("{prefix}{}", to.join(".")),
+                outcome,
+            });
+        }
+    }
+    applied
+}
+
+fn convert(migration: &Migration, value: &toml::Value) -> Result<toml::Value, String> {
+    match migration.transform {
+        Some(transform) => transform(value),
+        None => Ok(value.clone()),
+    }
+}
+
+/// Result of [`migrate_file`].
+#[derive(Debug, Clone)]
+pub struct FileMigration {
+    /// Migrations that matched a key in the file.
+    pub applied: Vec<AppliedMigration>,
+    /// The migrated file contents (what was, or would be, written).
+    pub content: String,
+}
+
+/// Apply [`MIGRATIONS`] to the TOML file at `path`, preserving comments and
+/// formatting where possible. With `write`, the file is replaced when
+/// anything changed; otherwise this is a dry run.
+///
+/// Keys inside `[profiles.<name>]` are not rewritten; the loader still
+/// migrates them in memory.
+///
+/// # Errors
+///
+/// Returns a [`ConfigError`] if the file cannot be read, parsed, or
+/// written.
+pub fn migrate_file(path: &Path, write: bool) -> ConfigResult<FileMigration> {
+    let label = path.display().to_string();
+    let original = std::fs::read_to_string(path).map_err(|e| ConfigError::ReadError {
+        path: label.clone(),
+        source: e,
+    })?;
+    let mut doc: DocumentMut =
+        original
+            .parse()
+            .map_err(|e: toml_edit::TomlError| ConfigError::ValidationError {
+                field: label.clone(),
+                message: e.message().trim().to_owned(),
+            })?;
+
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS {
+        let pairs = expand(migration, |path| {
+            table_at(doc.as_table(), path)
+                .map(|t| t.iter().map(|(k, _)| k.to_owned()).collect())
+                .unwrap_or_default()
+        });
+        for (from, to) in pairs {
+            if let Some(outcome) = migrate_document(&mut doc, migration, &from, &to) {
+                applied.push(AppliedMigration {
+                    from: from.join("."),
+                    to: to.join("."),
+                    outcome,
+                });
+            }
+        }
+    }
+
+    let content = doc.to_string();
+    if write && content != original {
+        std::fs::write(path, &content).map_err(|e| ConfigError::WriteError {
+            path: label,
+            source: e,
+        })?;
+    }
+    Ok(FileMigration { applied, content })
+}
+
+fn migrate_document(
+    doc: &mut DocumentMut,
+    migration: &Migration,
+    from: &[String],
+    to: &[String],
+) -> Option<MigrationOutcome> {
+    let (from_leaf, from_parent) = from.split_last()?;
+    let (to_leaf, to_parent) = to.split_last()?;
+
+    let parent = table_at_mut(doc.as_table_mut(), from_parent)?;
+    let key = parent.key(from_leaf)?.clone();
+    let old = parent.get(from_leaf)?.as_value()?.clone();
+
+    if table_at(doc.as_table(), to_parent).is_some_and(|t| t.contains_key(to_leaf)) {
+        table_at_mut(doc.as_table_mut(), from_parent)?.remove(from_leaf);
+        return Some(MigrationOutcome::Superseded);
+    }
+    let mut new = match convert(migration, &edit_to_value(&old)?) {
+        Ok(new) => value_to_edit(&new)?,
+        Err(reason) => return Some(MigrationOutcome::Failed(reason)),
+    };
+    // Keep the old key's spacing and trailing comment.
+    *new.decor_mut() = old.decor().clone();
+
+    table_at_mut(doc.as_table_mut(), from_parent)?.remove(from_leaf);
+    let target = ensure_table(doc.as_table_mut(), to_parent)?;
+    let new_key = Key::new(to_leaf.as_str()).with_leaf_decor(key.leaf_decor().clone());
+    target.entry_format(&new_key).or_insert(Item::Value(new));
+    Some(MigrationOutcome::Moved)
+}
+
+// ---------------------------------------------------------------------------
+// Path helpers
+// ---------------------------------------------------------------------------
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('.').collect()
+}
+
+fn matches(pattern: &[&str], path: &[&str]) -> bool {
+    pattern.len() == path.len() && pattern.iter().zip(path).all(|(p, s)| *p == "*" || p == s)
+}
+
+/// Concrete `(from, to)` path pairs for a migration, resolving each `*`
+/// against the keys `keys_at(prefix)` returns.
+fn expand(
+    migration: &Migration,
+    keys_at: impl Fn(&[String]) -> Vec<String>,
+) -> Vec<(Vec<String>, Vec<String>)> {
+    let mut concrete: Vec<(Vec<String>, Vec<String>)> = vec![(Vec::new(), Vec::new())];
+    for segment in segments(migration.from) {
+        concrete = concrete
+            .into_iter()
+            .flat_map(|(path, wildcards)| {
+                let choices = if segment == "*" {
+                    keys_at(&path)
+                } else {
+                    vec![segment.to_owned()]
+                };
+                choices.into_iter().map(move |choice| {
+                    let mut path = path.clone();
+                    let mut wildcards = wildcards.clone();
+                    if segment == "*" {
+                        wildcards.push(choice.clone());
+                    }
+                    path.push(choice);
+                    (path, wildcards)
+                })
+            })
+            .collect();
+    }
+    concrete
+        .into_iter()
+        .map(|(from, wildcards)| {
+            let mut fill = wildcards.into_iter();
+            let to = segments(migration.to)
+                .into_iter()
+                .map(|s| {
+                    if s == "*" {
+                        fill.next().unwrap_or_default()
+                    } else {
+                        s.to_owned()
+                    }
+                })
+                .collect();
+            (from, to)
+        })
+        .collect()
+}
+
+fn keys_at(root: &toml::Value, path: &[String]) -> Vec<String> {
+    get(root, path)
+        .and_then(toml::Value::as_table)
+        .map(|t| t.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn get<'a>(root: &'a toml::Value, path: &[String]) -> Option<&'a toml::Value> {
+    path.iter().try_fold(root, |v, s| v.get(s.as_str()))
+}
+
+fn take(root: &mut toml::Value, path: &[String]) -> Option<toml::Value> {
+    let (leaf, parent) = path.split_last()?;
+    let mut current = root;
+    for segment in parent {
+        current = current.get_mut(segment.as_str())?;
+    }
+    current.as_table_mut()?.remove(leaf)
+}
+
+fn put(root: &mut toml::Value, path: &[String], value: toml::Value) {
+    let Some((leaf, parent)) = path.split_last() else {
+        return;
+    };
+    let mut current = root;
+    for segment in parent {
+        let Some(table) = current.as_table_mut() else {
+            return;
+        };
+        current = table
+            .entry(segment.clone())
+            .or_insert_with(|| toml::Value::Table(toml::map::Map::new()));
+    }
+    if let Some(table) = current.as_table_mut() {
+        table.insert(leaf.clone(), value);
+    }
+}
+
+fn table_at<'a>(root: &'a dyn TableLike, path: &[String]) -> Option<&'a dyn TableLike> {
+    path.iter().try_fold(root, |t, s| t.get(s)?.as_table_like())
+}
+
+fn table_at_mut<'a>(root: &'a mut dyn TableLike, path: &[String]) -> Option<&'a mut dyn TableLike> {
+    let mut current = root;
+    for segment in path {
+        current = current.get_mut(segment)?.as_table_like_mut()?;
+    }
+    Some(current)
+}
+
+/// Like [`table_at_mut`], creating missing tables as implicit `[a.b]`
+/// sections so only the leaf gets a header.
+fn ensure_table<'a>(root: &'a mut dyn TableLike, path: &[String]) -> Option<&'a mut dyn TableLike> {
+    let mut current = root;
+    for segment in path {
+        if !current.contains_key(segment) {
+            let mut table = toml_edit::Table::new();
+            table.set_implicit(true);
+            current.insert(segment, Item::Table(table));
+        }
+        current = current.get_mut(segment)?.as_table_like_mut()?;
+    }
+    Some(current)
+}
+
+fn edit_to_value(value: &toml_edit::Value) -> Option<toml::Value> {
+    let doc: toml::Table = toml::from_str(&format!("v = {value}")).ok()?;
+    doc.get("v").cloned()
+}
+
+fn value_to_edit(value: &toml::Value) -> Option<toml_edit::Value> {
+    value.to_string().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_target_current_keys() {
+        // Every target must be a key the current schema knows about, and no
+        // deprecated key may shadow a current one.
+        for m in MIGRATIONS {
+            let to = m.to.replace('*', "x");
+            let from = m.from.replace('*', "x");
+            let target = dotted_to_toml(&format!("{to} = 1"));
+            assert!(
+                !crate::diagnostics::validate_str(&target, None)
+                    .iter()
+                    .any(|d| d.message.starts_with("unknown key")),
+                "{} is not a current key",
+                m.to
+            );
+            assert!(
+                !is_deprecated(&to),
+                "{} is both current and deprecated",
+                m.to
+            );
+            assert!(is_deprecated(&from));
+        }
+    }
+
+    fn dotted_to_toml(line: &str) -> String {
+        let (path, value) = line.split_once(" = ").unwrap();
+        let (parent, leaf) = path.rsplit_once('.').unwrap();
+        format!("[{parent}]\n{leaf} = {value}\n")
+    }
+
+    #[test]
+    fn apply_moves_renames_and_transforms() {
+        let mut value: toml::Value = toml::from_str(
+            "[model]\napi_base = \"https://x\"\n[logging]\njson = true\n[servers.fs]\ncmd = \"fs\"\n",
+        )
+        .unwrap();
+        let applied = apply(&mut value);
+
+        assert_eq!(value["model"]["api_url"].as_str(), Some("https://x"));
+        assert_eq!(value["logging"]["format"].as_str(), Some("json"));
+        assert_eq!(value["servers"]["fs"]["command"].as_str(), Some("fs"));
+        assert!(value["model"].get("api_base").is_none());
+        assert_eq!(applied.len(), 3);
+        assert!(applied.iter().all(|a| a.outcome == MigrationOutcome::Moved));
+    }
+
+    #[test]
+    fn apply_prefers_the_current_key() {
+        let mut value: toml::Value =
+            toml::from_str("[model]\napi_base = \"old\"\napi_url = \"new\"\n").unwrap();
+        let applied = apply(&mut value);
+
+        assert_eq!(value["model"]["api_url"].as_str(), Some("new"));
+        assert!(value["model"].get("api_base").is_none());
+        assert_eq!(applied[0].outcome, MigrationOutcome::Superseded);
+    }
+
+    #[test]
+    fn failed_transform_leaves_value_in_place() {
+        let mut value: toml::Value = toml::from_str("[logging]\njson = \"yes\"\n").unwrap();
+        let applied = apply(&mut value);
+
+        assert_eq!(value["logging"]["json"].as_str(), Some("yes"));
+        assert!(matches!(applied[0].outcome, MigrationOutcome::Failed(_)));
+    }
+
+    #[test]
+    fn profiles_are_migrated() {
+        let mut value: toml::Value =
+            toml::from_str("[profiles.work.budget]\nmax_session_usd = 5.0\n").unwrap();
+        let applied = apply(&mut value);
+
+        assert_eq!(
+            value["profiles"]["work"]["budget"]["session_max_usd"].as_float(),
+            Some(5.0)
+        );
+        assert_eq!(applied[0].from, "profiles.work.budget.max_session_usd");
+        assert!(is_deprecated("profiles.work.budget.max_session_usd"));
+    }
+}
@@ -0,0 +1,164 @@
+//! Named personas.
+//!
+//! A persona is a `personas/<name>.toml` file under the Astrid home
+//! directory (`~/.astrid/personas/`) holding the same fields as the
+//! `[spark]` section. A session may switch to a persona by name without
+//! editing `spark.toml`; the name is always checked against the files that
+//! actually exist, so it can never be used to read outside the directory.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::{ConfigError, ConfigResult};
+use crate::loader::read_config;
+use crate::types::SparkSection;
+
+/// Directory under the Astrid home that holds persona files.
+pub const PERSONAS_DIR: &str = "personas";
+
+/// File extension of persona files.
+const PERSONA_EXT: &str = "toml";
+
+/// Path of the persona directory under `astrid_home`.
+#[must_use]
+pub fn personas_dir(astrid_home: &Path) -> PathBuf {
+    astrid_home.join(PERSONAS_DIR)
+}
+
+/// Names of the personas defined under `astrid_home`, sorted.
+///
+/// A missing persona directory means no personas. Files whose stem is not
+/// a valid persona name are skipped.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::ReadError`] if the directory exists but cannot
+/// be listed.
+pub fn list_personas(astrid_home: &Path) -> ConfigResult<Vec<String>> {
+    let dir = personas_dir(astrid_home);
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(ConfigError::ReadError {
+                path: dir.display().to_string(),
+                source: e,
+            });
+        },
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| path.extension().is_some_and(|ext| ext == PERSONA_EXT))
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_owned))
+        .filter(|name| is_valid_persona_name(name))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Load persona `name` from `astrid_home`.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::UnknownPersona`] if no such persona is defined,
+/// and [`ConfigError::ParseError`] if its file is not a valid spark table.
+pub fn load_persona(astrid_home: &Path, name: &str) -> ConfigResult<SparkSection> {
+    let available = list_personas(astrid_home)?;
+    if !available.iter().any(|p| p == name) {
+        return Err(ConfigError::UnknownPersona {
+            name: name.to_owned(),
+            available,
+        });
+    }
+
+    let path = personas_dir(astrid_home).join(format!("{name}.{PERSONA_EXT}"));
+    let content = read_config(&path)?.ok_or_else(|| ConfigError::UnknownPersona {
+        name: name.to_owned(),
+        available: Vec::new(),
+    })?;
+    toml::from_str(&content).map_err(|source| ConfigError::ParseError {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+/// Persona names are plain identifiers: ASCII letters, digits, `-` and `_`.
+fn is_valid_persona_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_persona(home: &Path, file: &str, content: &str) {
+        let dir = personas_dir(home);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(file), content).unwrap();
+    }
+
+    #[test]
+    fn missing_dir_has_no_personas() {
+        let home = tempfile::tempdir().unwrap();
+        assert!(list_personas(home.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn lists_only_valid_toml_personas() {
+        let home = tempfile::tempdir().unwrap();
+        write_persona(home.path(), "terse-reviewer.toml", "signal = \"concise\"");
+        write_persona(home.path(), "mentor.toml", "aura = \"warm\"");
+        write_persona(home.path(), "notes.md", "not a persona");
+        write_persona(home.path(), "bad name.toml", "aura = \"x\"");
+
+        assert_eq!(
+            list_personas(home.path()).unwrap(),
+            ["mentor", "terse-reviewer"]
+        );
+    }
+
+    #[test]
+    fn loads_spark_fields() {
+        let home = tempfile::tempdir().unwrap();
+        write_persona(
+            home.path(),
+            "terse-reviewer.toml",
+            "class = \"reviewer\"\nsignal = \"concise\"",
+        );
+
+        let spark = load_persona(home.path(), "terse-reviewer").unwrap();
+        assert_eq!(spark.class, "reviewer");
+        assert_eq!(spark.signal, "concise");
+        assert!(spark.callsign.is_empty());
+    }
+
+    #[test]
+    fn unknown_persona_lists_available() {
+        let home = tempfile::tempdir().unwrap();
+        write_persona(home.path(), "mentor.toml", "aura = \"warm\"");
+
+        match load_persona(home.path(), "pirate") {
+            Err(ConfigError::UnknownPersona { name, available }) => {
+                assert_eq!(name, "pirate");
+                assert_eq!(available, ["mentor"]);
+            },
+            other => panic!("expected UnknownPersona, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn traversal_names_are_unknown() {
+        let home = tempfile::tempdir().unwrap();
+        std::fs::write(home.path().join("config.toml"), "aura = \"x\"").unwrap();
+
+        assert!(matches!(
+            load_persona(home.path(), "../config"),
+            Err(ConfigError::UnknownPersona { .. })
+        ));
+    }
+}
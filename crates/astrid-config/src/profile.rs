@@ -0,0 +1,71 @@
+//! Named config profiles.
+//!
+//! A system or user config file may define `[profiles.<name>]` tables, each
+//! a partial config. When a profile is selected (explicitly, or through
+//! `ASTRID_PROFILE`), it is deep-merged over the system and user layers and
+//! beneath the workspace layer, so workspace tighten-only rules still apply
+//! on top of it.
+
+use tracing::{info, warn};
+
+use crate::error::{ConfigError, ConfigResult};
+use crate::merge::{ConfigLayer, FieldSources, deep_merge_tracking};
+
+/// Environment variable that selects a profile when none is given explicitly.
+pub const PROFILE_ENV_VAR: &str = "ASTRID_PROFILE";
+
+/// Top-level key holding the profile tables.
+const PROFILES_KEY: &str = "profiles";
+
+/// Remove the `profiles` table from a merged tree, returning it.
+pub(crate) fn take_profiles(merged: &mut toml::Value) -> Option<toml::Value> {
+    merged.as_table_mut()?.remove(PROFILES_KEY)
+}
+
+/// Drop any `profiles` table from a workspace overlay: profiles are chosen by
+/// the user, so a workspace may not define or redefine them.
+pub(crate) fn strip_workspace_profiles(overlay: &mut toml::Value) {
+    if take_profiles(overlay).is_some() {
+        warn!("ignoring [profiles] in workspace config; define profiles in user config");
+    }
+}
+
+/// Overlay profile `name` from `profiles` onto `merged`, recording the
+/// profile as the source of every field it sets.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::UnknownProfile`] if no such profile is defined,
+/// and [`ConfigError::ValidationError`] if the profile is not a table.
+pub(crate) fn apply_profile(
+    merged: &mut toml::Value,
+    profiles: Option<&toml::Value>,
+    name: &str,
+    sources: &mut FieldSources,
+) -> ConfigResult<()> {
+    let table = profiles.and_then(toml::Value::as_table);
+    let Some(profile) = table.and_then(|t| t.get(name)) else {
+        return Err(ConfigError::UnknownProfile {
+            name: name.to_owned(),
+            available: table
+                .map(|t| t.keys().cloned().collect())
+                .unwrap_or_default(),
+        });
+    };
+    if !profile.is_table() {
+        return Err(ConfigError::ValidationError {
+            field: format!("{PROFILES_KEY}.{name}"),
+            message: "a profile must be a table of config sections".to_owned(),
+        });
+    }
+
+    deep_merge_tracking(
+        merged,
+        profile,
+        "",
+        &ConfigLayer::Profile(name.to_owned()),
+        sources,
+    );
+    info!(profile = name, "applied config profile");
+    Ok(())
+}
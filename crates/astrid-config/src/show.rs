@@ -5,7 +5,8 @@
 
 use std::fmt::{self, Write as _};
 
-use crate::merge::FieldSources;
+use crate::diagnostics::Diagnostic;
+use crate::merge::{FieldOrigins, FieldSources, Origin, Source};
 use crate::types::Config;
 
 /// A resolved configuration together with source annotations.
@@ -15,8 +16,16 @@ pub struct ResolvedConfig {
     pub config: Config,
     /// Dotted field path → which layer set the value.
     pub field_sources: FieldSources,
+    /// Dotted field path → which file or env var set the value. Fields set
+    /// by the embedded defaults have no entry.
+    pub field_origins: FieldOrigins,
     /// Config file paths that were loaded (in precedence order).
     pub loaded_files: Vec<String>,
+    /// Name of the profile applied over the user layer, if any.
+    pub profile: Option<String>,
+    /// Non-fatal problems found while loading, such as unknown or
+    /// deprecated keys.
+    pub warnings: Vec<Diagnostic>,
 }
 
 /// Output format for `config show`.
@@ -26,6 +35,9 @@ pub enum ShowFormat {
     Toml,
     /// JSON (for programmatic consumption).
     Json,
+    /// One `path = value` line per field, with the layer and file (or env
+    /// var) it came from.
+    Detailed,
 }
 
 impl ResolvedConfig {
@@ -38,6 +50,58 @@ impl ResolvedConfig {
         match format {
             ShowFormat::Toml => self.show_toml(section),
             ShowFormat::Json => self.show_json(section),
+            ShowFormat::Detailed => self.show_detailed(section),
+        }
+    }
+
+    /// Where the value at dotted `path` (e.g. `model.max_tokens`) came from.
+    ///
+    /// Returns `None` for paths no layer set, including fields that only
+    /// have a serde default.
+    #[must_use]
+    pub fn provenance(&self, path: &str) -> Option<Source> {
+        let layer = self.field_sources.get(path)?.clone();
+        let origin = self
+            .field_origins
+            .get(path)
+            .cloned()
+            .unwrap_or(Origin::Embedded);
+        Some(Source { layer, origin })
+    }
+
+    fn show_detailed(&self, section: Option<&str>) -> Result<String, fmt::Error> {
+        let val = toml::Value::try_from(&self.config).map_err(|_| fmt::Error)?;
+        let (root, prefix) = match section {
+            Some(name) => (val.get(name).ok_or(fmt::Error)?, name),
+            None => (&val, ""),
+        };
+
+        let mut output = String::new();
+        if let Some(profile) = &self.profile {
+            let _ = writeln!(output, "# Active profile: {profile}");
+        }
+        self.write_detailed(root, prefix, &mut output);
+        Ok(output)
+    }
+
+    fn write_detailed(&self, val: &toml::Value, path: &str, output: &mut String) {
+        match val {
+            toml::Value::Table(table) => {
+                for (key, child) in table {
+                    let child_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    self.write_detailed(child, &child_path, output);
+                }
+            },
+            leaf => {
+                let source = self
+                    .provenance(path)
+                    .map_or_else(|| "built-in default".to_owned(), |s| s.to_string());
+                let _ = writeln!(output, "{path} = {leaf}  # {source}");
+            },
         }
     }
 
@@ -56,7 +120,13 @@ impl ResolvedConfig {
 
         // Header.
         output.push_str("# Resolved Astrid Configuration\n");
-        output.push_str("# Source annotations: [defaults] [system] [user] [workspace] [env]\n");
+        output.push_str(
+            "# Source annotations: [defaults] [system] [user] [profile] [workspace] [env]\n",
+        );
+
+        if let Some(profile) = &self.profile {
+            let _ = writeln!(output, "# Active profile: {profile}");
+        }
 
         if !self.loaded_files.is_empty() {
             output.push_str("#\n# Loaded files (in precedence order):\n");
@@ -67,11 +137,25 @@ impl ResolvedConfig {
 
         output.push('\n');
 
-        // Annotate each line.
-        let prefix = section.unwrap_or("");
+        // Annotate each line, tracking the enclosing `[table]` header.
+        let root = section.unwrap_or("");
+        let mut prefix = root.to_owned();
         for line in toml_str.lines() {
+            if let Some(header) = line
+                .trim()
+                .strip_prefix('[')
+                .and_then(|h| h.strip_suffix(']'))
+            {
+                // `[[array]]` entries have no per-field sources.
+                let header = header.trim_matches(|c| c == '[' || c == ']');
+                prefix = if root.is_empty() {
+                    header.to_owned()
+                } else {
+                    format!("{root}.{header}")
+                };
+            }
             // Try to identify the field from the line.
-            if let Some(annotation) = self.annotate_line(line, prefix) {
+            if let Some(annotation) = self.annotate_line(line, &prefix) {
                 let _ = writeln!(output, "{line}  # {annotation}");
             } else {
                 output.push_str(line);
@@ -150,7 +234,10 @@ mod tests {
         let resolved = ResolvedConfig {
             config: Config::default(),
             field_sources: FieldSources::new(),
+            field_origins: FieldOrigins::new(),
             loaded_files: Vec::new(),
+            profile: None,
+            warnings: Vec::new(),
         };
 
         let output = resolved.show(ShowFormat::Toml, None).unwrap();
@@ -163,7 +250,10 @@ mod tests {
         let resolved = ResolvedConfig {
             config: Config::default(),
             field_sources: FieldSources::new(),
+            field_origins: FieldOrigins::new(),
             loaded_files: Vec::new(),
+            profile: None,
+            warnings: Vec::new(),
         };
 
         let output = resolved.show(ShowFormat::Json, None).unwrap();
@@ -176,7 +266,10 @@ mod tests {
         let resolved = ResolvedConfig {
             config: Config::default(),
             field_sources: FieldSources::new(),
+            field_origins: FieldOrigins::new(),
             loaded_files: Vec::new(),
+            profile: None,
+            warnings: Vec::new(),
         };
 
         let output = resolved.show(ShowFormat::Toml, Some("model")).unwrap();
@@ -8,6 +8,7 @@
 
 use std::collections::HashMap;
 
+use schemars::JsonSchema;
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
 
@@ -19,7 +20,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// Loaded from layered TOML files (global, project, local) with environment
 /// variable overrides. Every section defaults to safe, production-ready values.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct Config {
     /// LLM model selection and pricing.
@@ -69,7 +70,7 @@ pub struct Config {
 // ---------------------------------------------------------------------------
 
 /// LLM provider selection, endpoint, and token pricing.
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct ModelConfig {
     /// Provider identifier (e.g. `"claude"`, `"openai"`).
@@ -143,7 +144,7 @@ impl Default for ModelConfig {
 // ---------------------------------------------------------------------------
 
 /// Per-token pricing used to compute spend against budget limits.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct PricingConfig {
     /// USD cost per 1 million input tokens.
@@ -166,7 +167,7 @@ impl Default for PricingConfig {
 // ---------------------------------------------------------------------------
 
 /// Runtime behaviour settings (context management, summarisation).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct RuntimeSection {
     /// Maximum context window size in tokens before summarisation kicks in.
@@ -196,7 +197,7 @@ impl Default for RuntimeSection {
 // ---------------------------------------------------------------------------
 
 /// Top-level security settings (signatures, approval timeout, policy).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct SecurityConfig {
     /// Require ed25519 signatures for capability tokens and audit entries.
@@ -224,7 +225,7 @@ impl Default for SecurityConfig {
 
 /// Fine-grained security policy controlling which tools, paths, and hosts are
 /// permitted, denied, or require explicit approval.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct PolicySection {
     /// Tool invocations that are unconditionally blocked.
@@ -289,7 +290,7 @@ impl Default for PolicySection {
 // ---------------------------------------------------------------------------
 
 /// Spending limits that prevent runaway costs.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct BudgetSection {
     /// Maximum USD spend allowed for a single session.
@@ -319,7 +320,7 @@ impl Default for BudgetSection {
 // ---------------------------------------------------------------------------
 
 /// Rate-limiting settings to prevent server abuse and request floods.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct RateLimitsConfig {
     /// Maximum elicitation requests allowed per MCP server per minute.
@@ -347,7 +348,7 @@ impl Default for RateLimitsConfig {
 /// This mirrors the domain `RestartPolicy` from `astrid-mcp` so that the
 /// config crate stays dependency-free. The runtime config bridge converts
 /// this into the domain type.
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RestartPolicyConfig {
     /// Never restart (default).
@@ -368,7 +369,7 @@ fn default_max_retries() -> u32 {
 }
 
 /// Configuration for a single MCP server.
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct ServerSection {
     /// Transport type (`"stdio"`, `"sse"`, `"streamable-http"`).
@@ -396,6 +397,8 @@ pub struct ServerSection {
     pub trusted: bool,
     /// Restart policy when the server process dies.
     pub restart_policy: RestartPolicyConfig,
+    /// Tools that are safe to run twice, whose timed-out calls are retried.
+    pub idempotent_tools: Vec<String>,
 }
 
 impl std::fmt::Debug for ServerSection {
@@ -413,6 +416,7 @@ impl std::fmt::Debug for ServerSection {
             .field("description", &self.description)
             .field("trusted", &self.trusted)
             .field("restart_policy", &self.restart_policy)
+            .field("idempotent_tools", &self.idempotent_tools)
             .finish()
     }
 }
@@ -431,6 +435,7 @@ impl Default for ServerSection {
             description: None,
             trusted: false,
             restart_policy: RestartPolicyConfig::default(),
+            idempotent_tools: Vec::new(),
         }
     }
 }
@@ -440,13 +445,17 @@ impl Default for ServerSection {
 // ---------------------------------------------------------------------------
 
 /// Audit log storage settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct AuditConfig {
     /// Path to the on-disk audit log. `None` means in-memory only.
     pub path: Option<String>,
     /// Maximum size of the audit log in megabytes before rotation.
     pub max_size_mb: u64,
+    /// Largest capsule file write, in bytes, whose content is sealed into
+    /// the audit log for later reveal. Every write records its hash and
+    /// size; `0` (the default) seals nothing.
+    pub seal_file_writes_max_bytes: u64,
 }
 
 impl Default for AuditConfig {
@@ -454,6 +463,7 @@ impl Default for AuditConfig {
         Self {
             path: None,
             max_size_mb: 100,
+            seal_file_writes_max_bytes: 0,
         }
     }
 }
@@ -463,7 +473,7 @@ impl Default for AuditConfig {
 // ---------------------------------------------------------------------------
 
 /// Paths to cryptographic key material used for signatures and verification.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct KeysConfig {
     /// Path to the user's ed25519 private key file.
@@ -480,7 +490,7 @@ pub struct KeysConfig {
 ///
 /// The workspace defines where the agent is allowed to operate by default.
 /// Accesses outside the workspace boundary are governed by `escape_policy`.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct WorkspaceSection {
     /// Workspace mode: `"safe"` (default, ask for everything outside
@@ -527,7 +537,7 @@ impl Default for WorkspaceSection {
 // ---------------------------------------------------------------------------
 
 /// Git integration settings controlling how completed work is delivered.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct GitConfig {
     /// Completion strategy: `"merge"` (merge into target branch), `"pr"`
@@ -556,7 +566,7 @@ impl Default for GitConfig {
 /// Hook execution policy. Controls which kinds of hooks are permitted and
 /// global limits on hook execution.
 #[expect(clippy::struct_excessive_bools)]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct HooksSection {
     /// Master switch: when `false`, no hooks run at all.
@@ -597,7 +607,7 @@ impl Default for HooksSection {
 // ---------------------------------------------------------------------------
 
 /// Logging and tracing configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct LoggingSection {
     /// Global log level filter (`"trace"`, `"debug"`, `"info"`, `"warn"`,
@@ -609,6 +619,15 @@ pub struct LoggingSection {
     /// Per-crate tracing directives (e.g. `["astrid_mcp=debug",
     /// "hyper=warn"]`).
     pub directives: Vec<String>,
+    /// Redaction of sensitive values: `"off"`, `"standard"` (secret fields
+    /// and prompts) or `"strict"` (also hashes paths).
+    pub redaction: String,
+    /// Log lines kept per session for diagnostics (0 disables capture).
+    pub session_log_entries: usize,
+    /// Bytes of log lines kept per session.
+    pub session_log_bytes: usize,
+    /// OpenTelemetry export of spans and metrics.
+    pub otlp: OtlpSection,
 }
 
 impl Default for LoggingSection {
@@ -617,6 +636,58 @@ impl Default for LoggingSection {
             level: "info".to_owned(),
             format: "compact".to_owned(),
             directives: Vec::new(),
+            redaction: "standard".to_owned(),
+            session_log_entries: 1000,
+            session_log_bytes: 256 * 1024,
+            otlp: OtlpSection::default(),
+        }
+    }
+}
+
+/// OTLP/HTTP export settings (`[logging.otlp]`).
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct OtlpSection {
+    /// Whether to export spans and metrics. Requires a build with the
+    /// `otlp` feature.
+    pub enabled: bool,
+    /// Collector base URL; `/v1/traces` and `/v1/metrics` are appended.
+    pub endpoint: String,
+    /// Value of the `service.name` resource attribute.
+    pub service_name: String,
+    /// Extra resource attributes (e.g. `deployment.environment`).
+    pub resource_attributes: HashMap<String, String>,
+    /// HTTP headers sent with every export, typically authentication.
+    #[serde(skip_serializing)]
+    pub headers: HashMap<String, String>,
+    /// Whether to export token, tool call and approval counters.
+    pub metrics: bool,
+}
+
+impl std::fmt::Debug for OtlpSection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redacted_headers: HashMap<&String, &str> =
+            self.headers.keys().map(|k| (k, "***")).collect();
+        f.debug_struct("OtlpSection")
+            .field("enabled", &self.enabled)
+            .field("endpoint", &self.endpoint)
+            .field("service_name", &self.service_name)
+            .field("resource_attributes", &self.resource_attributes)
+            .field("headers", &redacted_headers)
+            .field("metrics", &self.metrics)
+            .finish()
+    }
+}
+
+impl Default for OtlpSection {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "http://localhost:4318".to_owned(),
+            service_name: "astrid".to_owned(),
+            resource_attributes: HashMap::new(),
+            headers: HashMap::new(),
+            metrics: true,
         }
     }
 }
@@ -626,7 +697,7 @@ impl Default for LoggingSection {
 // ---------------------------------------------------------------------------
 
 /// Gateway daemon configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct GatewaySection {
     /// Directory for gateway runtime state (PID file, socket). `None` uses
@@ -676,7 +747,7 @@ impl Default for GatewaySection {
 // ---------------------------------------------------------------------------
 
 /// Timeout budgets for various operations. All values are in seconds.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct TimeoutsSection {
     /// Maximum time for a single LLM request.
@@ -711,7 +782,7 @@ impl Default for TimeoutsSection {
 // ---------------------------------------------------------------------------
 
 /// Session management limits and persistence settings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct SessionsSection {
     /// Maximum number of concurrent sessions per user.
@@ -740,7 +811,7 @@ impl Default for SessionsSection {
 // ---------------------------------------------------------------------------
 
 /// Sub-agent pool limits and defaults.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct SubagentsSection {
     /// Maximum number of sub-agents running concurrently.
@@ -768,7 +839,7 @@ impl Default for SubagentsSection {
 // ---------------------------------------------------------------------------
 
 /// Retry behaviour for transient failures (LLM and MCP requests).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct RetrySection {
     /// Maximum retry attempts for LLM requests.
@@ -803,7 +874,7 @@ impl Default for RetrySection {
 /// `spark.toml` takes priority.
 ///
 /// All fields default to empty strings (no identity configured).
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct SparkSection {
     /// Agent's name (e.g. "Stellar", "Nova", "Orion").
@@ -840,7 +911,7 @@ impl SparkSection {
 /// available and which behavioural profile they should expose. At startup, the
 /// daemon validates that each declared plugin is loaded and exposes a uplink
 /// with the expected profile.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct UplinkConfig {
     /// Plugin ID (e.g. `"openclaw-telegram"`).
@@ -859,7 +930,7 @@ pub struct UplinkConfig {
 /// Entries in `[[identity.links]]` are applied on every daemon startup, making
 /// config-driven identity links effectively persistent across restarts without
 /// requiring manual re-pairing.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct IdentitySection {
     /// Identity links to apply on startup.
@@ -870,7 +941,7 @@ pub struct IdentitySection {
 ///
 /// Maps a platform-specific user ID to a canonical Astrid user identity.
 /// Applied at daemon startup via admin linking.
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct IdentityLinkConfig {
     /// Platform identifier (e.g. `"telegram"`, `"discord"`).
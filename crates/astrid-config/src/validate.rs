@@ -324,6 +324,31 @@ fn validate_logging(config: &Config) -> ConfigResult<()> {
         });
     }
 
+    let valid_redaction = ["off", "standard", "strict"];
+    if !valid_redaction.contains(&config.logging.redaction.as_str()) {
+        return Err(ConfigError::ValidationError {
+            field: "logging.redaction".to_owned(),
+            message: format!(
+                "unsupported redaction mode '{}'; expected one of: {}",
+                config.logging.redaction,
+                valid_redaction.join(", ")
+            ),
+        });
+    }
+
+    let otlp = &config.logging.otlp;
+    if otlp.enabled
+        && !(otlp.endpoint.starts_with("http://") || otlp.endpoint.starts_with("https://"))
+    {
+        return Err(ConfigError::ValidationError {
+            field: "logging.otlp.endpoint".to_owned(),
+            message: format!(
+                "OTLP endpoint '{}' must be an http:// or https:// URL",
+                otlp.endpoint
+            ),
+        });
+    }
+
     Ok(())
 }
 
@@ -429,6 +454,22 @@ mod tests {
         assert!(validate(&config).is_err());
     }
 
+    #[test]
+    fn test_invalid_redaction_mode() {
+        let mut config = Config::default();
+        config.logging.redaction = "lenient".to_owned();
+        assert!(validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_invalid_otlp_endpoint() {
+        let mut config = Config::default();
+        config.logging.otlp.endpoint = "localhost:4317".to_owned();
+        assert!(validate(&config).is_ok(), "only checked when enabled");
+        config.logging.otlp.enabled = true;
+        assert!(validate(&config).is_err());
+    }
+
     #[test]
     fn test_zero_max_tokens() {
         let mut config = Config::default();
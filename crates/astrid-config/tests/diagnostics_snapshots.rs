@@ -0,0 +1,65 @@
+//! Snapshot tests: every file in `tests/fixtures/invalid/` is validated and
+//! its rendered diagnostics compared against the sibling `.snap` file.
+//!
+//! Run with `ASTRID_UPDATE_SNAPSHOTS=1` to rewrite the snapshots after an
+//! intentional change, then review the diff.
+
+use std::path::{Path, PathBuf};
+
+use astrid_config::Config;
+
+fn fixtures() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/invalid");
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn invalid_config_corpus_matches_snapshots() {
+    let update = std::env::var_os("ASTRID_UPDATE_SNAPSHOTS").is_some();
+    let mut mismatches = Vec::new();
+
+    for fixture in fixtures() {
+        let name = fixture.file_name().unwrap().to_string_lossy().into_owned();
+        let content = std::fs::read_to_string(&fixture).unwrap();
+        let diagnostics = astrid_config::diagnostics::validate_str(&content, Some(&name));
+        assert!(!diagnostics.is_empty(), "{name}: expected diagnostics");
+
+        let mut rendered = diagnostics
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        rendered.push('\n');
+        let snap = fixture.with_extension("snap");
+        if update {
+            std::fs::write(&snap, &rendered).unwrap();
+            continue;
+        }
+        let expected = std::fs::read_to_string(&snap).unwrap_or_default();
+        if rendered != expected {
+            mismatches.push(format!(
+                "{name}:\n--- expected\n{expected}--- actual\n{rendered}"
+            ));
+        }
+    }
+
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+#[test]
+fn validate_file_matches_validate_str() {
+    let fixture = &fixtures()[0];
+    let from_file = Config::validate_file(fixture);
+    let label = fixture.display().to_string();
+    let content = std::fs::read_to_string(fixture).unwrap();
+    assert_eq!(
+        from_file,
+        astrid_config::diagnostics::validate_str(&content, Some(&label))
+    );
+}
@@ -0,0 +1,65 @@
+//! Migration of a 0.1-era config: every deprecated key in
+//! `tests/fixtures/migrate/v1.toml` must be rewritten to its current name,
+//! comments intact, and load to the same config as the rewritten file.
+
+use std::path::Path;
+
+use astrid_config::Config;
+use astrid_config::migrate::MigrationOutcome;
+
+fn fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/migrate")
+        .join(name);
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn v1_config_fully_migrates() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, fixture("v1.toml")).unwrap();
+
+    let dry_run = Config::migrate_file(&path, false).unwrap();
+    assert_eq!(dry_run.applied.len(), 8);
+    assert!(
+        dry_run
+            .applied
+            .iter()
+            .all(|a| a.outcome == MigrationOutcome::Moved)
+    );
+    assert_eq!(dry_run.content, fixture("v1.migrated.toml"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), fixture("v1.toml"));
+
+    let migrated = Config::migrate_file(&path, true).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), migrated.content);
+    assert!(Config::validate_file(&path).is_empty());
+    assert!(
+        Config::migrate_file(&path, true)
+            .unwrap()
+            .applied
+            .is_empty()
+    );
+}
+
+#[test]
+fn v1_config_loads_like_its_migration() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = dir.path().join("old.toml");
+    let new = dir.path().join("new.toml");
+    std::fs::write(&old, fixture("v1.toml")).unwrap();
+    std::fs::write(&new, fixture("v1.migrated.toml")).unwrap();
+
+    let old = Config::load_file(&old).unwrap();
+    let new = Config::load_file(&new).unwrap();
+    assert_eq!(
+        toml::to_string(&old).unwrap(),
+        toml::to_string(&new).unwrap()
+    );
+    assert_eq!(
+        new.model.api_url.as_deref(),
+        Some("https://proxy.example.com")
+    );
+    assert_eq!(new.logging.format, "json");
+    assert_eq!(new.servers["filesystem"].cwd.as_deref(), Some("/srv/data"));
+}
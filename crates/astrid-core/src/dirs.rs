@@ -263,6 +263,33 @@ impl AstridHome {
         self.var_dir().join("state.db")
     }
 
+    /// Trust decisions on workspace hooks (`var/hook-trust.json`).
+    ///
+    /// Kept outside every workspace so a repository cannot pre-trust the
+    /// hooks it ships.
+    #[must_use]
+    pub fn hook_trust_path(&self) -> PathBuf {
+        self.var_dir().join("hook-trust.json")
+    }
+
+    /// KV state snapshot directory (`backups/`).
+    ///
+    /// Rotating snapshots of `var/state.db/` are written here by the
+    /// kernel's backup job. Created on first backup, not by [`Self::ensure`].
+    #[must_use]
+    pub fn backups_dir(&self) -> PathBuf {
+        self.root.join("backups")
+    }
+
+    /// Diagnostic bundle directory (`diagnostics/`).
+    ///
+    /// Written by the kernel when a turn fails and bundles are enabled.
+    /// Created on first bundle, not by [`Self::ensure`].
+    #[must_use]
+    pub fn diagnostics_dir(&self) -> PathBuf {
+        self.root.join("diagnostics")
+    }
+
     /// Ephemeral runtime directory (`run/`).
     #[must_use]
     pub fn run_dir(&self) -> PathBuf {
@@ -737,6 +764,11 @@ mod tests {
             home.state_db_path(),
             PathBuf::from(format!("{r}/var/state.db"))
         );
+        assert_eq!(
+            home.hook_trust_path(),
+            PathBuf::from(format!("{r}/var/hook-trust.json"))
+        );
+        assert_eq!(home.backups_dir(), PathBuf::from(format!("{r}/backups")));
         assert_eq!(home.run_dir(), PathBuf::from(format!("{r}/run")));
         assert_eq!(
             home.socket_path(),
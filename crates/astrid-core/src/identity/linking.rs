@@ -0,0 +1,587 @@
+//! Linking a platform account to an existing Astrid user with a one-time code.
+//!
+//! The user asks for a code on a frontend where they are already known
+//! ([`LinkingService::start_link`]) and sends it from the account they want
+//! to link. [`LinkingService::complete_link`] checks the code and persists
+//! the [`FrontendLink`] through a [`LinkStore`].
+//!
+//! Codes are single-use, bound to the platform they were issued for, and
+//! expire after [`DEFAULT_CODE_TTL`]. A platform account that sends
+//! [`DEFAULT_MAX_ATTEMPTS`] wrong codes is locked out until
+//! [`DEFAULT_ATTEMPT_WINDOW`] has passed since its first failure. At most
+//! 1024 accounts are tracked at once; while that many are, untracked
+//! accounts are locked out as well.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use super::types::{AstridUserId, FrontendLink, normalize_platform};
+
+/// How long an issued code stays valid.
+pub const DEFAULT_CODE_TTL: Duration = Duration::from_mins(10);
+/// Wrong codes a platform account may send per attempt window.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+/// Window over which wrong codes are counted.
+pub const DEFAULT_ATTEMPT_WINDOW: Duration = Duration::from_mins(15);
+/// Verification method recorded on links created by this service.
+pub const LINK_METHOD_CODE: &str = "code";
+
+/// Codes waiting to be completed, across all users.
+const MAX_PENDING_CODES: usize = 1024;
+/// Platform accounts whose failed attempts are tracked at once.
+const MAX_TRACKED_FAILURES: usize = 1024;
+/// Length of an issued code.
+const CODE_LEN: usize = 8;
+/// 32 symbols without the look-alikes `0`, `1`, `I` and `O`, so every
+/// random byte maps to a symbol without bias.
+const CODE_ALPHABET: &[u8; 32] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+/// Errors from the linking flow.
+#[derive(Debug, thiserror::Error)]
+pub enum LinkError {
+    /// The code does not match any pending link for this platform.
+    #[error("invalid link code")]
+    InvalidCode,
+
+    /// The code matched but its lifetime has passed.
+    #[error("link code expired")]
+    Expired,
+
+    /// The platform account sent too many wrong codes and is locked out.
+    #[error("too many failed link attempts, retry in {retry_after_secs}s")]
+    TooManyAttempts {
+        /// Seconds until the account may try again.
+        retry_after_secs: u64,
+    },
+
+    /// Too many codes are outstanding to issue another.
+    #[error("too many pending link codes")]
+    TooManyPending,
+
+    /// Input validation failed.
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+
+    /// The backing [`LinkStore`] failed.
+    #[error("link store error: {0}")]
+    Store(String),
+}
+
+/// Storage for platform links, implemented by the identity store.
+#[async_trait]
+pub trait LinkStore: Send + Sync {
+    /// Resolve a platform identity to its linked user, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinkError::Store`] if the lookup fails.
+    async fn resolve_link(
+        &self,
+        platform: &str,
+        platform_user_id: &str,
+    ) -> Result<Option<AstridUserId>, LinkError>;
+
+    /// Persist a link, replacing any existing link for the platform identity.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinkError::Store`] if the user is unknown or the write fails.
+    async fn save_link(
+        &self,
+        platform: &str,
+        platform_user_id: &str,
+        astrid_user_id: Uuid,
+        method: &str,
+    ) -> Result<FrontendLink, LinkError>;
+
+    /// Remove a link. Returns `true` if it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinkError::Store`] if the delete fails.
+    async fn remove_link(&self, platform: &str, platform_user_id: &str) -> Result<bool, LinkError>;
+}
+
+/// A code issued by [`LinkingService::start_link`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkCode {
+    /// The code the user sends from the account being linked.
+    pub code: String,
+    /// Normalized platform the code can be completed on.
+    pub platform: String,
+    /// When the code stops being accepted.
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug)]
+struct PendingLink {
+    code: String,
+    astrid_user_id: Uuid,
+    platform: String,
+    expires_at: Instant,
+}
+
+#[derive(Debug)]
+struct FailedAttempts {
+    count: u32,
+    window_start: Instant,
+}
+
+/// Issues and verifies link codes on top of a [`LinkStore`].
+///
+/// Pending codes and attempt counters live in memory, so a daemon restart
+/// invalidates outstanding codes.
+#[derive(Debug)]
+pub struct LinkingService<S: ?Sized> {
+    store: Arc<S>,
+    code_ttl: Duration,
+    max_attempts: u32,
+    attempt_window: Duration,
+    pending: Mutex<Vec<PendingLink>>,
+    failures: Mutex<HashMap<(String, String), FailedAttempts>>,
+}
+
+impl<S: LinkStore + ?Sized> LinkingService<S> {
+    /// Create a service with the default expiry and attempt limits.
+    #[must_use]
+    pub fn new(store: Arc<S>) -> Self {
+        Self {
+            store,
+            code_ttl: DEFAULT_CODE_TTL,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            attempt_window: DEFAULT_ATTEMPT_WINDOW,
+            pending: Mutex::new(Vec::new()),
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set how long issued codes stay valid.
+    #[must_use]
+    pub fn with_code_ttl(mut self, ttl: Duration) -> Self {
+        self.code_ttl = ttl;
+        self
+    }
+
+    /// Set how many wrong codes a platform account may send per `window`.
+    #[must_use]
+    pub fn with_attempt_limit(mut self, max_attempts: u32, window: Duration) -> Self {
+        self.max_attempts = max_attempts;
+        self.attempt_window = window;
+        self
+    }
+
+    /// Issue a code that links an account on `platform` to `user`.
+    ///
+    /// Replaces any code still pending for the same user and platform.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinkError::InvalidInput`] if `platform` is empty, or
+    /// [`LinkError::TooManyPending`] if the pending-code limit is reached.
+    pub async fn start_link(
+        &self,
+        user: &AstridUserId,
+        platform: &str,
+    ) -> Result<LinkCode, LinkError> {
+        let platform = normalize_platform(platform);
+        if platform.is_empty() {
+            return Err(LinkError::InvalidInput("platform is empty".into()));
+        }
+
+        let now = Instant::now();
+        let mut pending = self.pending.lock().await;
+        pending.retain(|p| {
+            p.expires_at > now && !(p.astrid_user_id == user.id && p.platform == platform)
+        });
+        if pending.len() >= MAX_PENDING_CODES {
+            return Err(LinkError::TooManyPending);
+        }
+
+        let code = generate_code();
+        pending.push(PendingLink {
+            code: code.clone(),
+            astrid_user_id: user.id,
+            platform: platform.clone(),
+            expires_at: now.checked_add(self.code_ttl).unwrap_or(now),
+        });
+        let ttl = chrono::Duration::from_std(self.code_ttl).unwrap_or(chrono::Duration::MAX);
+        Ok(LinkCode {
+            code,
+            platform,
+            expires_at: Utc::now()
+                .checked_add_signed(ttl)
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        })
+    }
+
+    /// Verify `code` sent by `platform_user_id` on `platform` and persist the link.
+    ///
+    /// Codes are case-insensitive and consumed on success.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinkError::TooManyAttempts`] while the account is locked out,
+    /// [`LinkError::InvalidCode`] or [`LinkError::Expired`] if the code is not
+    /// accepted, and [`LinkError::Store`] if persisting the link fails.
+    pub async fn complete_link(
+        &self,
+        platform: &str,
+        platform_user_id: &str,
+        code: &str,
+    ) -> Result<FrontendLink, LinkError> {
+        let platform = normalize_platform(platform);
+        if platform.is_empty() || platform_user_id.is_empty() {
+            return Err(LinkError::InvalidInput(
+                "platform and platform_user_id must not be empty".into(),
+            ));
+        }
+        let key = (platform.clone(), platform_user_id.to_string());
+        self.check_lockout(&key).await?;
+
+        let code = code.trim().to_ascii_uppercase();
+        let now = Instant::now();
+        let astrid_user_id = {
+            let mut pending = self.pending.lock().await;
+            let found = pending
+                .iter()
+                .position(|p| bool::from(p.code.as_bytes().ct_eq(code.as_bytes())));
+            match found {
+                Some(i) if pending[i].expires_at <= now => {
+                    pending.swap_remove(i);
+                    return Err(LinkError::Expired);
+                },
+                Some(i) if pending[i].platform == platform => pending.swap_remove(i).astrid_user_id,
+                _ => {
+                    drop(pending);
+                    self.record_failure(key).await;
+                    return Err(LinkError::InvalidCode);
+                },
+            }
+        };
+
+        let link = self
+            .store
+            .save_link(
+                &platform,
+                platform_user_id,
+                astrid_user_id,
+                LINK_METHOD_CODE,
+            )
+            .await?;
+        self.failures.lock().await.remove(&key);
+        Ok(link)
+    }
+
+    /// Resolve a platform identity to the Astrid user it is linked to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinkError::Store`] if the lookup fails.
+    pub async fn resolve(
+        &self,
+        platform: &str,
+        platform_user_id: &str,
+    ) -> Result<Option<AstridUserId>, LinkError> {
+        self.store
+            .resolve_link(&normalize_platform(platform), platform_user_id)
+            .await
+    }
+
+    /// Remove the link for a platform identity. Returns `true` if it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LinkError::Store`] if the delete fails.
+    pub async fn revoke(&self, platform: &str, platform_user_id: &str) -> Result<bool, LinkError> {
+        self.store
+            .remove_link(&normalize_platform(platform), platform_user_id)
+            .await
+    }
+
+    /// Withdraw the code pending for `user` on `platform`, if any.
+    pub async fn cancel_link(&self, user: &AstridUserId, platform: &str) -> bool {
+        let platform = normalize_platform(platform);
+        let mut pending = self.pending.lock().await;
+        let before = pending.len();
+        pending.retain(|p| !(p.astrid_user_id == user.id && p.platform == platform));
+        pending.len() < before
+    }
+
+    async fn check_lockout(&self, key: &(String, String)) -> Result<(), LinkError> {
+        let mut failures = self.failures.lock().await;
+        self.prune_failures(&mut failures);
+        match failures.get(key) {
+            Some(entry) if entry.count >= self.max_attempts => Err(LinkError::TooManyAttempts {
+                retry_after_secs: self.retry_after(entry.window_start),
+            }),
+            // Every slot is taken, so a failure here could not be counted:
+            // refuse until the oldest window ends rather than allow
+            // unlimited guesses.
+            None if failures.len() >= MAX_TRACKED_FAILURES => {
+                let oldest = failures.values().map(|f| f.window_start).min();
+                Err(LinkError::TooManyAttempts {
+                    retry_after_secs: oldest.map_or(1, |start| self.retry_after(start)),
+                })
+            },
+            _ => Ok(()),
+        }
+    }
+
+    async fn record_failure(&self, key: (String, String)) {
+        let mut failures = self.failures.lock().await;
+        self.prune_failures(&mut failures);
+        if !failures.contains_key(&key) && failures.len() >= MAX_TRACKED_FAILURES {
+            return;
+        }
+        let entry = failures.entry(key).or_insert_with(|| FailedAttempts {
+            count: 0,
+            window_start: Instant::now(),
+        });
+        entry.count = entry.count.saturating_add(1);
+    }
+
+    /// Drop attempt counters whose window has ended.
+    fn prune_failures(&self, failures: &mut HashMap<(String, String), FailedAttempts>) {
+        failures.retain(|_, f| f.window_start.elapsed() < self.attempt_window);
+    }
+
+    /// Seconds until the window that started at `window_start` ends.
+    fn retry_after(&self, window_start: Instant) -> u64 {
+        self.attempt_window
+            .saturating_sub(window_start.elapsed())
+            .as_secs()
+            .max(1)
+    }
+}
+
+fn generate_code() -> String {
+    let mut bytes = [0u8; CODE_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes
+        .iter()
+        .map(|b| char::from(CODE_ALPHABET[usize::from(b % 32)]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct MemoryLinks {
+        links: std::sync::Mutex<HashMap<(String, String), Uuid>>,
+    }
+
+    #[async_trait]
+    impl LinkStore for MemoryLinks {
+        async fn resolve_link(
+            &self,
+            platform: &str,
+            platform_user_id: &str,
+        ) -> Result<Option<AstridUserId>, LinkError> {
+            let links = self.links.lock().unwrap();
+            Ok(links
+                .get(&(platform.to_string(), platform_user_id.to_string()))
+                .map(|id| AstridUserId {
+                    id: *id,
+                    ..AstridUserId::new()
+                }))
+        }
+
+        async fn save_link(
+            &self,
+            platform: &str,
+            platform_user_id: &str,
+            astrid_user_id: Uuid,
+            method: &str,
+        ) -> Result<FrontendLink, LinkError> {
+            self.links.lock().unwrap().insert(
+                (platform.to_string(), platform_user_id.to_string()),
+                astrid_user_id,
+            );
+            Ok(FrontendLink {
+                platform: platform.to_string(),
+                platform_user_id: platform_user_id.to_string(),
+                astrid_user_id,
+                linked_at: Utc::now(),
+                method: method.to_string(),
+            })
+        }
+
+        async fn remove_link(
+            &self,
+            platform: &str,
+            platform_user_id: &str,
+        ) -> Result<bool, LinkError> {
+            Ok(self
+                .links
+                .lock()
+                .unwrap()
+                .remove(&(platform.to_string(), platform_user_id.to_string()))
+                .is_some())
+        }
+    }
+
+    fn service() -> LinkingService<MemoryLinks> {
+        LinkingService::new(Arc::new(MemoryLinks::default()))
+    }
+
+    #[tokio::test]
+    async fn code_links_account_and_resolves() {
+        let svc = service();
+        let user = AstridUserId::new();
+        let code = svc.start_link(&user, "Discord").await.unwrap();
+        assert_eq!(code.platform, "discord");
+        assert_eq!(code.code.len(), CODE_LEN);
+        assert!(code.expires_at > Utc::now());
+
+        let link = svc
+            .complete_link("discord", "42", &code.code.to_ascii_lowercase())
+            .await
+            .unwrap();
+        assert_eq!(link.astrid_user_id, user.id);
+        assert_eq!(link.method, LINK_METHOD_CODE);
+        assert_eq!(
+            svc.resolve("discord", "42").await.unwrap().unwrap().id,
+            user.id
+        );
+
+        // Single use.
+        assert!(matches!(
+            svc.complete_link("discord", "43", &code.code).await,
+            Err(LinkError::InvalidCode)
+        ));
+    }
+
+    #[tokio::test]
+    async fn code_is_bound_to_its_platform() {
+        let svc = service();
+        let user = AstridUserId::new();
+        let code = svc.start_link(&user, "discord").await.unwrap();
+        assert!(matches!(
+            svc.complete_link("telegram", "42", &code.code).await,
+            Err(LinkError::InvalidCode)
+        ));
+        assert!(svc.complete_link("discord", "42", &code.code).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn expired_code_is_rejected() {
+        let svc = service().with_code_ttl(Duration::from_millis(10));
+        let user = AstridUserId::new();
+        let code = svc.start_link(&user, "discord").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(matches!(
+            svc.complete_link("discord", "42", &code.code).await,
+            Err(LinkError::Expired)
+        ));
+        assert!(svc.resolve("discord", "42").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn wrong_codes_lock_the_account_out() {
+        let svc = service().with_attempt_limit(3, Duration::from_millis(100));
+        let user = AstridUserId::new();
+        let code = svc.start_link(&user, "discord").await.unwrap();
+        for _ in 0..3 {
+            assert!(matches!(
+                svc.complete_link("discord", "42", "WRONG").await,
+                Err(LinkError::InvalidCode)
+            ));
+        }
+        // Even the right code is refused while locked out.
+        assert!(matches!(
+            svc.complete_link("discord", "42", &code.code).await,
+            Err(LinkError::TooManyAttempts { .. })
+        ));
+        // Other accounts are unaffected.
+        let other = svc.start_link(&user, "telegram").await.unwrap();
+        assert!(
+            svc.complete_link("telegram", "7", &other.code)
+                .await
+                .is_ok()
+        );
+
+        tokio::time::sleep(Duration::from_millis(120)).await;
+        assert!(svc.complete_link("discord", "42", &code.code).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn failure_tracking_is_bounded_and_fails_closed() {
+        let svc = service().with_attempt_limit(3, Duration::from_millis(500));
+        for id in 0..MAX_TRACKED_FAILURES {
+            assert!(matches!(
+                svc.complete_link("discord", &id.to_string(), "WRONG").await,
+                Err(LinkError::InvalidCode)
+            ));
+        }
+        assert_eq!(svc.failures.lock().await.len(), MAX_TRACKED_FAILURES);
+
+        // A new account cannot be counted, so it is refused outright.
+        assert!(matches!(
+            svc.complete_link("discord", "new", "WRONG").await,
+            Err(LinkError::TooManyAttempts { .. })
+        ));
+        // Tracked accounts keep their own budget.
+        assert!(matches!(
+            svc.complete_link("discord", "0", "WRONG").await,
+            Err(LinkError::InvalidCode)
+        ));
+        assert_eq!(svc.failures.lock().await.len(), MAX_TRACKED_FAILURES);
+
+        // Expired windows are pruned, freeing the slots.
+        tokio::time::sleep(Duration::from_millis(550)).await;
+        assert!(matches!(
+            svc.complete_link("discord", "new", "WRONG").await,
+            Err(LinkError::InvalidCode)
+        ));
+        assert_eq!(svc.failures.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn revoke_removes_link_and_cancel_withdraws_code() {
+        let svc = service();
+        let user = AstridUserId::new();
+        let code = svc.start_link(&user, "discord").await.unwrap();
+        svc.complete_link("discord", "42", &code.code)
+            .await
+            .unwrap();
+
+        assert!(svc.revoke("discord", "42").await.unwrap());
+        assert!(svc.resolve("discord", "42").await.unwrap().is_none());
+        assert!(!svc.revoke("discord", "42").await.unwrap());
+
+        let code = svc.start_link(&user, "discord").await.unwrap();
+        assert!(svc.cancel_link(&user, "discord").await);
+        assert!(matches!(
+            svc.complete_link("discord", "42", &code.code).await,
+            Err(LinkError::InvalidCode)
+        ));
+    }
+
+    #[tokio::test]
+    async fn new_code_replaces_pending_one() {
+        let svc = service();
+        let user = AstridUserId::new();
+        let first = svc.start_link(&user, "discord").await.unwrap();
+        let second = svc.start_link(&user, "discord").await.unwrap();
+        assert!(matches!(
+            svc.complete_link("discord", "42", &first.code).await,
+            Err(LinkError::InvalidCode)
+        ));
+        assert!(
+            svc.complete_link("discord", "42", &second.code)
+                .await
+                .is_ok()
+        );
+    }
+}
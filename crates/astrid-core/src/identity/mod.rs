@@ -2,11 +2,15 @@
 //!
 //! Provides [`AstridUserId`], the canonical internal user identity across all
 //! platforms, and [`FrontendLink`], a mapping from platform-specific identities
-//! to Astrid users.
+//! to Astrid users. [`LinkingService`] links new platform accounts with
+//! one-time codes.
 
+/// Code-verified account linking.
+pub mod linking;
 /// Core identity types.
 pub mod types;
 
+pub use linking::{LinkCode, LinkError, LinkStore, LinkingService};
 pub use types::{AstridUserId, FrontendLink, normalize_platform};
 
 #[cfg(test)]
@@ -6,6 +6,7 @@
 //! - Approval and elicitation primitives
 //! - Common types used throughout the runtime
 //! - Retry configuration with exponential backoff
+//! - Version-gated migration of persisted state
 
 #![deny(unsafe_code)]
 #![deny(missing_docs)]
@@ -22,6 +23,7 @@ pub mod elicitation;
 pub mod env_policy;
 pub mod groups;
 pub mod identity;
+pub mod migration;
 pub mod principal;
 pub mod profile;
 pub mod retry;
@@ -41,6 +43,7 @@ pub use groups::{
     BUILTIN_ADMIN, BUILTIN_AGENT, BUILTIN_RESTRICTED, Group, GroupConfig, GroupConfigError,
     GroupConfigResult,
 };
+pub use migration::{Migrated, Migration, MigrationError, MigrationResult, Migrator};
 pub use principal::{PrincipalId, PrincipalIdError};
 pub use profile::{
     AuthConfig, AuthMethod, BACKGROUND_PROCESSES_UPPER_BOUND, CURRENT_PROFILE_VERSION,
@@ -48,7 +51,9 @@ pub use profile::{
     DEFAULT_MAX_STORAGE_BYTES, DEFAULT_MAX_TIMEOUT_SECS, MAX_GROUP_NAME_LEN, NetworkConfig,
     PrincipalProfile, ProcessConfig, ProfileError, ProfileResult, Quotas, TIMEOUT_SECS_UPPER_BOUND,
 };
-pub use retry::RetryConfig;
+pub use retry::{
+    ErrorClass, Jitter, RetryConfig, RetryPolicy, retry_with_policy, retry_with_policy_rng,
+};
 pub use types::{
     AgentId, ApprovalDecision, ApprovalOption, ApprovalRequest, Permission, SessionId, Timestamp,
     TokenId,
@@ -56,10 +61,12 @@ pub use types::{
 pub use utils::truncate_to_boundary;
 
 // Identity types
-pub use identity::{AstridUserId, FrontendLink, normalize_platform};
+pub use identity::{
+    AstridUserId, FrontendLink, LinkCode, LinkError, LinkStore, LinkingService, normalize_platform,
+};
 
 // Uplink types
 pub use uplink::{
-    InboundMessage, MAX_UPLINKS_PER_CAPSULE, UplinkCapabilities, UplinkDescriptor, UplinkError,
-    UplinkId, UplinkProfile, UplinkResult, UplinkSource,
+    InboundDedup, InboundMessage, MAX_UPLINKS_PER_CAPSULE, UplinkCapabilities, UplinkDescriptor,
+    UplinkError, UplinkId, UplinkProfile, UplinkResult, UplinkSource,
 };
@@ -0,0 +1,442 @@
+//! Version-gated migration of persisted JSON state.
+//!
+//! A store declares its current schema version and registers ordered
+//! [`Migration`]s with a [`Migrator`]. Documents are stored as
+//! `{"version": N, "data": ...}`; a bare document without that envelope is
+//! treated as version 0.
+//!
+//! [`Migrator::load_file`] runs every pending migration, keeps a copy of the
+//! original file as `<file>.v<N>.bak`, and writes the migrated document back.
+//! Data written by a newer binary is refused instead of being truncated to
+//! the fields this one understands.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+/// Errors from loading or migrating versioned state.
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    /// The data was written by a newer version than this binary supports.
+    #[error("{store} data is version {found}, newer than supported version {supported}")]
+    TooNew {
+        /// The store being loaded.
+        store: String,
+        /// Version found in the data.
+        found: u32,
+        /// Newest version this binary understands.
+        supported: u32,
+    },
+
+    /// No registered migration starts at this version.
+    #[error("{store} has no migration from version {from}")]
+    MissingMigration {
+        /// The store being loaded.
+        store: String,
+        /// The version with no outgoing migration.
+        from: u32,
+    },
+
+    /// Registered migrations are not a gap-free chain.
+    #[error("invalid migration {from} -> {to}: {reason}")]
+    InvalidMigration {
+        /// Start version of the offending migration.
+        from: u32,
+        /// End version of the offending migration.
+        to: u32,
+        /// What is wrong with it.
+        reason: &'static str,
+    },
+
+    /// A migration function rejected the data.
+    #[error("{store} migration {from} -> {to} failed: {reason}")]
+    Failed {
+        /// The store being loaded.
+        store: String,
+        /// Start version of the failed migration.
+        from: u32,
+        /// End version of the failed migration.
+        to: u32,
+        /// Why the migration failed.
+        reason: String,
+    },
+
+    /// The document is not valid JSON or has a malformed envelope.
+    #[error("malformed versioned data: {0}")]
+    Malformed(String),
+
+    /// Reading, backing up, or writing the file failed.
+    #[error("migration io error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Result alias for migration operations.
+pub type MigrationResult<T> = Result<T, MigrationError>;
+
+/// One schema step, from version `from` to version `to`.
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    /// Version the migration reads.
+    pub from: u32,
+    /// Version the migration produces. Must be `from + 1`.
+    pub to: u32,
+    /// Rewrites the `data` of a document. Errors are reported as
+    /// [`MigrationError::Failed`].
+    pub migrate: fn(Value) -> Result<Value, String>,
+}
+
+/// The outcome of [`Migrator::migrate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migrated {
+    /// The data at the current version.
+    pub data: Value,
+    /// The version the data was stored at.
+    pub from_version: u32,
+}
+
+impl Migrated {
+    /// Whether any migration ran.
+    #[must_use]
+    pub fn changed(&self, migrator: &Migrator) -> bool {
+        self.from_version != migrator.current_version()
+    }
+}
+
+/// Ordered migrations for one store.
+#[derive(Debug, Clone)]
+pub struct Migrator {
+    store: String,
+    current: u32,
+    migrations: Vec<Migration>,
+}
+
+impl Migrator {
+    /// Create a migrator for `store` whose current schema is `current`.
+    #[must_use]
+    pub fn new(store: impl Into<String>, current: u32) -> Self {
+        Self {
+            store: store.into(),
+            current,
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Register the next migration in the chain.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MigrationError::InvalidMigration`] if the migration does not
+    /// advance exactly one version, does not continue the chain registered so
+    /// far, or goes past the current version.
+    pub fn register(mut self, migration: Migration) -> MigrationResult<Self> {
+        let invalid = |reason| MigrationError::InvalidMigration {
+            from: migration.from,
+            to: migration.to,
+            reason,
+        };
+        if migration.from.checked_add(1) != Some(migration.to) {
+            return Err(invalid("must advance exactly one version"));
+        }
+        if migration.to > self.current {
+            return Err(invalid("goes past the current version"));
+        }
+        let expected_from = self.migrations.last().map_or(migration.from, |m| m.to);
+        if migration.from != expected_from {
+            return Err(invalid("does not continue the registered chain"));
+        }
+        self.migrations.push(migration);
+        Ok(self)
+    }
+
+    /// The current schema version.
+    #[must_use]
+    pub fn current_version(&self) -> u32 {
+        self.current
+    }
+
+    /// Bring `data`, stored at `version`, up to the current version.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MigrationError::TooNew`] if `version` is above the current
+    /// version, [`MigrationError::MissingMigration`] if the chain has a gap,
+    /// and [`MigrationError::Failed`] if a migration rejects the data.
+    pub fn migrate(&self, version: u32, mut data: Value) -> MigrationResult<Migrated> {
+        if version > self.current {
+            return Err(MigrationError::TooNew {
+                store: self.store.clone(),
+                found: version,
+                supported: self.current,
+            });
+        }
+        let mut at = version;
+        while at < self.current {
+            let step = self
+                .migrations
+                .iter()
+                .find(|m| m.from == at)
+                .ok_or_else(|| MigrationError::MissingMigration {
+                    store: self.store.clone(),
+                    from: at,
+                })?;
+            data = (step.migrate)(data).map_err(|reason| MigrationError::Failed {
+                store: self.store.clone(),
+                from: step.from,
+                to: step.to,
+                reason,
+            })?;
+            at = step.to;
+        }
+        Ok(Migrated {
+            data,
+            from_version: version,
+        })
+    }
+
+    /// Migrate a whole document: an envelope, or a bare version-0 document.
+    ///
+    /// # Errors
+    ///
+    /// As for [`migrate`](Self::migrate), plus [`MigrationError::Malformed`]
+    /// for an envelope whose `version` is not a `u32`.
+    pub fn migrate_document(&self, document: Value) -> MigrationResult<Migrated> {
+        let (version, data) = split_envelope(document)?;
+        self.migrate(version, data)
+    }
+
+    /// Wrap `data` in an envelope at the current version.
+    #[must_use]
+    pub fn envelope(&self, data: Value) -> Value {
+        let mut map = Map::new();
+        map.insert("version".into(), Value::from(self.current));
+        map.insert("data".into(), data);
+        Value::Object(map)
+    }
+
+    /// Load and migrate the JSON file at `path`, returning its data.
+    ///
+    /// Returns `Ok(None)` if the file does not exist. When migrations ran,
+    /// the original file is copied to [`backup_path`] first and the migrated
+    /// document is then written back atomically.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MigrationError::Io`] if the file cannot be read, backed up,
+    /// or rewritten, and the errors of [`migrate_document`](Self::migrate_document).
+    /// A refused file is left untouched.
+    pub fn load_file(&self, path: &Path) -> MigrationResult<Option<Value>> {
+        let raw = match std::fs::read(path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let document: Value =
+            serde_json::from_slice(&raw).map_err(|e| MigrationError::Malformed(e.to_string()))?;
+        let migrated = self.migrate_document(document)?;
+        if migrated.changed(self) {
+            std::fs::write(backup_path(path, migrated.from_version), &raw)?;
+            write_atomic(path, &self.envelope(migrated.data.clone()))?;
+        }
+        Ok(Some(migrated.data))
+    }
+}
+
+/// Where [`Migrator::load_file`] keeps the pre-migration copy of `path`.
+#[must_use]
+pub fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".v{version}.bak"));
+    path.with_file_name(name)
+}
+
+fn split_envelope(document: Value) -> MigrationResult<(u32, Value)> {
+    let Value::Object(mut map) = document else {
+        return Ok((0, document));
+    };
+    if !(map.len() == 2 && map.contains_key("version") && map.contains_key("data")) {
+        return Ok((0, Value::Object(map)));
+    }
+    let version = map
+        .get("version")
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| MigrationError::Malformed("version is not a u32".into()))?;
+    Ok((version, map.remove("data").unwrap_or(Value::Null)))
+}
+
+fn write_atomic(path: &Path, document: &Value) -> MigrationResult<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+    let bytes = serde_json::to_vec_pretty(document)
+        .map_err(|e| MigrationError::Malformed(e.to_string()))?;
+    std::fs::write(&tmp, bytes)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    /// v0 -> v1: `name` becomes `display_name`.
+    fn rename_name(mut data: Value) -> Result<Value, String> {
+        let obj = data.as_object_mut().ok_or("not an object")?;
+        let name = obj.remove("name").ok_or("missing name")?;
+        obj.insert("display_name".into(), name);
+        Ok(data)
+    }
+
+    /// v1 -> v2: `tags` string becomes a list.
+    fn split_tags(mut data: Value) -> Result<Value, String> {
+        let obj = data.as_object_mut().ok_or("not an object")?;
+        let tags = obj
+            .get("tags")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .split(',')
+            .filter(|t| !t.is_empty())
+            .map(|t| Value::from(t.trim()))
+            .collect::<Vec<_>>();
+        obj.insert("tags".into(), Value::Array(tags));
+        Ok(data)
+    }
+
+    /// v2 -> v3: add `enabled`, defaulting to true.
+    fn add_enabled(mut data: Value) -> Result<Value, String> {
+        let obj = data.as_object_mut().ok_or("not an object")?;
+        obj.entry("enabled").or_insert(Value::Bool(true));
+        Ok(data)
+    }
+
+    fn migrator() -> Migrator {
+        Migrator::new("fixture", 3)
+            .register(Migration {
+                from: 0,
+                to: 1,
+                migrate: rename_name,
+            })
+            .and_then(|m| {
+                m.register(Migration {
+                    from: 1,
+                    to: 2,
+                    migrate: split_tags,
+                })
+            })
+            .and_then(|m| {
+                m.register(Migration {
+                    from: 2,
+                    to: 3,
+                    migrate: add_enabled,
+                })
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn chains_three_migrations_over_fixture() {
+        let fixture = json!({ "name": "alice", "tags": "a, b" });
+        let migrated = migrator().migrate_document(fixture).unwrap();
+        assert_eq!(migrated.from_version, 0);
+        assert_eq!(
+            migrated.data,
+            json!({ "display_name": "alice", "tags": ["a", "b"], "enabled": true })
+        );
+
+        // Starting part-way runs only the remaining steps.
+        let v2 = json!({ "version": 2, "data": { "display_name": "bob", "tags": [] } });
+        let migrated = migrator().migrate_document(v2).unwrap();
+        assert_eq!(
+            migrated.data,
+            json!({ "display_name": "bob", "tags": [], "enabled": true })
+        );
+    }
+
+    #[test]
+    fn refuses_data_from_a_newer_version() {
+        let future = json!({ "version": 4, "data": { "display_name": "x" } });
+        let err = migrator().migrate_document(future).unwrap_err();
+        assert!(matches!(
+            err,
+            MigrationError::TooNew {
+                found: 4,
+                supported: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_broken_chains() {
+        let gap = Migrator::new("s", 3).register(Migration {
+            from: 0,
+            to: 2,
+            migrate: add_enabled,
+        });
+        assert!(matches!(gap, Err(MigrationError::InvalidMigration { .. })));
+
+        let missing = Migrator::new("s", 2)
+            .register(Migration {
+                from: 1,
+                to: 2,
+                migrate: add_enabled,
+            })
+            .unwrap();
+        assert!(matches!(
+            missing.migrate(0, json!({})),
+            Err(MigrationError::MissingMigration { from: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn failed_migration_names_the_step() {
+        let err = migrator().migrate(0, json!({ "tags": "" })).unwrap_err();
+        assert!(matches!(err, MigrationError::Failed { from: 0, to: 1, .. }));
+    }
+
+    #[test]
+    fn load_file_writes_back_and_keeps_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let original = r#"{"name":"alice","tags":"a"}"#;
+        std::fs::write(&path, original).unwrap();
+
+        let data = migrator().load_file(&path).unwrap().unwrap();
+        assert_eq!(data["display_name"], "alice");
+
+        let backup = backup_path(&path, 0);
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), original);
+        let stored: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(stored["version"], 3);
+        assert_eq!(stored["data"], data);
+
+        // Already current: nothing is rewritten.
+        std::fs::remove_file(&backup).unwrap();
+        assert_eq!(migrator().load_file(&path).unwrap().unwrap(), data);
+        assert!(!backup.exists());
+
+        assert!(
+            migrator()
+                .load_file(&dir.path().join("missing.json"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn load_file_leaves_newer_data_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let future = r#"{"version":9,"data":{}}"#;
+        std::fs::write(&path, future).unwrap();
+
+        assert!(matches!(
+            migrator().load_file(&path),
+            Err(MigrationError::TooNew { found: 9, .. })
+        ));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), future);
+    }
+}
@@ -26,7 +26,7 @@ pub use crate::{
 };
 
 // Retry utilities
-pub use crate::RetryConfig;
+pub use crate::{ErrorClass, RetryConfig, RetryPolicy, retry_with_policy};
 
 // Uplink
 pub use crate::{
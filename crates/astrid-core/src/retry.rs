@@ -2,11 +2,32 @@
 //!
 //! This module provides configurable retry logic for transient failures,
 //! commonly used for network operations and external service calls.
+//!
+//! [`retry_with_policy`] runs an async operation under a [`RetryPolicy`]:
+//! each failure is classified into an [`ErrorClass`], which picks the
+//! backoff to use. Rate-limited errors wait for the server-provided delay,
+//! permanent errors are returned at once, and every delay is jittered so
+//! that many clients failing together do not retry in lockstep.
 
+use std::future::Future;
 use std::time::Duration;
 
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
+/// How random jitter is applied to a backoff delay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Jitter {
+    /// `delay * (1 ± jitter_factor)`.
+    #[default]
+    Proportional,
+    /// Uniform in `[0, delay]`.
+    Full,
+    /// Uniform in `[delay / 2, delay]`.
+    Equal,
+}
+
 /// Configuration for retry behavior with exponential backoff.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RetryConfig {
@@ -21,6 +42,13 @@ pub struct RetryConfig {
     /// Optional jitter factor (0.0 to 1.0) to randomize delays.
     #[serde(default)]
     pub jitter_factor: f64,
+    /// How jitter is applied. Only [`Jitter::Proportional`] uses `jitter_factor`.
+    #[serde(default)]
+    pub jitter: Jitter,
+    /// Retry budget: the most time one operation may spend waiting between
+    /// attempts. `None` leaves only `max_attempts` as the limit.
+    #[serde(default)]
+    pub max_total_delay: Option<Duration>,
 }
 
 impl RetryConfig {
@@ -38,9 +66,25 @@ impl RetryConfig {
             max_delay,
             exponential_base,
             jitter_factor: 0.0,
+            jitter: Jitter::Proportional,
+            max_total_delay: None,
         }
     }
 
+    /// Set how jitter is applied to delays.
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the retry budget of one operation.
+    #[must_use]
+    pub fn with_max_total_delay(mut self, budget: Duration) -> Self {
+        self.max_total_delay = Some(budget);
+        self
+    }
+
     /// Creates a configuration with no retries.
     #[must_use]
     #[allow(dead_code)]
@@ -51,6 +95,8 @@ impl RetryConfig {
             max_delay: Duration::ZERO,
             exponential_base: 2.0,
             jitter_factor: 0.0,
+            jitter: Jitter::Proportional,
+            max_total_delay: None,
         }
     }
 
@@ -74,6 +120,8 @@ impl RetryConfig {
             max_delay: Duration::from_secs(10),
             exponential_base: 2.0,
             jitter_factor: 0.1,
+            jitter: Jitter::Proportional,
+            max_total_delay: None,
         }
     }
 
@@ -86,6 +134,8 @@ impl RetryConfig {
             max_delay: Duration::from_secs(30),
             exponential_base: 2.0,
             jitter_factor: 0.2,
+            jitter: Jitter::Proportional,
+            max_total_delay: None,
         }
     }
 
@@ -119,42 +169,49 @@ impl RetryConfig {
     /// Calculates the delay for a given attempt with jitter applied.
     ///
     /// Jitter helps prevent thundering herd problems when many clients
-    /// retry simultaneously.
+    /// retry simultaneously. `random_factor` is a uniform sample in
+    /// `[0.0, 1.0]`; see [`jittered_delay`](Self::jittered_delay) to draw it
+    /// from an RNG.
     #[must_use]
     #[expect(
         clippy::cast_precision_loss,
         clippy::cast_possible_truncation,
         clippy::cast_sign_loss
     )]
-    #[allow(dead_code)]
-    pub(crate) fn delay_for_attempt_with_jitter(
-        &self,
-        attempt: u32,
-        random_factor: f64,
-    ) -> Duration {
+    pub fn delay_for_attempt_with_jitter(&self, attempt: u32, random_factor: f64) -> Duration {
         let base_delay = self.delay_for_attempt(attempt);
-
-        if self.jitter_factor <= 0.0 {
-            return base_delay;
-        }
+        let base_ms = base_delay.as_millis() as f64;
 
         // random_factor should be between 0.0 and 1.0
         let random_factor = random_factor.clamp(0.0, 1.0);
 
-        // Apply jitter: delay * (1 - jitter_factor + 2 * jitter_factor * random)
-        // This gives a range of [delay * (1 - jitter), delay * (1 + jitter)]
-        let jitter_multiplier =
-            1.0 - self.jitter_factor + (2.0 * self.jitter_factor * random_factor);
-
-        let jittered_ms = base_delay.as_millis() as f64 * jitter_multiplier;
+        let jittered_ms = match self.jitter {
+            Jitter::Proportional => {
+                if self.jitter_factor <= 0.0 {
+                    return base_delay;
+                }
+                // Apply jitter: delay * (1 - jitter_factor + 2 * jitter_factor * random)
+                // This gives a range of [delay * (1 - jitter), delay * (1 + jitter)]
+                let jitter_multiplier =
+                    1.0 - self.jitter_factor + (2.0 * self.jitter_factor * random_factor);
+                base_ms * jitter_multiplier
+            },
+            Jitter::Full => base_ms * random_factor,
+            Jitter::Equal => base_ms / 2.0 + base_ms / 2.0 * random_factor,
+        };
 
         // Safe: jittered delays are always positive
         Duration::from_millis(jittered_ms.max(0.0) as u64)
     }
 
+    /// Calculates the jittered delay for a given attempt, sampling from `rng`.
+    #[must_use]
+    pub fn jittered_delay<R: Rng + ?Sized>(&self, attempt: u32, rng: &mut R) -> Duration {
+        self.delay_for_attempt_with_jitter(attempt, rng.r#gen::<f64>())
+    }
+
     /// Returns true if more attempts are allowed given the current attempt count.
     #[must_use]
-    #[allow(dead_code)]
     pub(crate) fn should_retry(&self, current_attempt: u32) -> bool {
         current_attempt < self.max_attempts
     }
@@ -172,6 +229,152 @@ impl Default for RetryConfig {
     }
 }
 
+/// Why an operation failed, as far as retrying is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Connection-level failure (refused, reset, DNS).
+    Network,
+    /// The operation timed out.
+    Timeout,
+    /// The remote side failed (e.g. HTTP 5xx).
+    Server,
+    /// The remote side asked the caller to slow down (e.g. HTTP 429).
+    RateLimited {
+        /// Server-provided delay before the next attempt, if any.
+        retry_after: Option<Duration>,
+    },
+    /// Retrying cannot help (bad request, authentication failure).
+    Permanent,
+}
+
+/// Per-[`ErrorClass`] retry configuration.
+///
+/// Each class counts its retries separately against its own
+/// `max_attempts`; the retry budget applies to the total time waited.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Backoff for network errors and timeouts.
+    pub network: RetryConfig,
+    /// Backoff for server errors.
+    pub server: RetryConfig,
+    /// Backoff for rate-limit errors. A server-provided delay replaces the
+    /// backoff delay but still counts against the budget.
+    pub rate_limited: RetryConfig,
+}
+
+impl RetryPolicy {
+    /// Use the same configuration for every retryable class.
+    #[must_use]
+    pub fn uniform(config: RetryConfig) -> Self {
+        Self {
+            network: config.clone(),
+            server: config.clone(),
+            rate_limited: config,
+        }
+    }
+
+    /// The configuration for `class`, or `None` if it must not be retried.
+    #[must_use]
+    pub fn for_class(&self, class: ErrorClass) -> Option<&RetryConfig> {
+        match class {
+            ErrorClass::Network | ErrorClass::Timeout => Some(&self.network),
+            ErrorClass::Server => Some(&self.server),
+            ErrorClass::RateLimited { .. } => Some(&self.rate_limited),
+            ErrorClass::Permanent => None,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Network-style backoff with full jitter; rate limits back off like
+    /// external API calls when the server gives no delay.
+    fn default() -> Self {
+        Self {
+            network: RetryConfig::network().with_jitter(Jitter::Full),
+            server: RetryConfig::network().with_jitter(Jitter::Full),
+            rate_limited: RetryConfig::api().with_jitter(Jitter::Equal),
+        }
+    }
+}
+
+/// Run `op` until it succeeds or `policy` gives up, returning the last error.
+///
+/// `classify` maps each error to an [`ErrorClass`]. Jitter is drawn from a
+/// freshly seeded RNG; use [`retry_with_policy_rng`] for deterministic delays.
+///
+/// # Errors
+///
+/// Returns the last error of `op` once its class is [`ErrorClass::Permanent`],
+/// its class has used `max_attempts` retries, or the next delay would exceed
+/// the retry budget.
+pub async fn retry_with_policy<T, E, Fut, Op, C>(
+    policy: &RetryPolicy,
+    classify: C,
+    op: Op,
+) -> Result<T, E>
+where
+    Op: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    C: Fn(&E) -> ErrorClass,
+{
+    let mut rng = rand::rngs::StdRng::from_entropy();
+    retry_with_policy_rng(policy, &mut rng, classify, op).await
+}
+
+/// [`retry_with_policy`] drawing jitter from `rng`.
+///
+/// # Errors
+///
+/// As for [`retry_with_policy`].
+pub async fn retry_with_policy_rng<T, E, Fut, Op, C, R>(
+    policy: &RetryPolicy,
+    rng: &mut R,
+    classify: C,
+    mut op: Op,
+) -> Result<T, E>
+where
+    Op: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    C: Fn(&E) -> ErrorClass,
+    R: Rng + ?Sized,
+{
+    let mut network_retries = 0u32;
+    let mut server_retries = 0u32;
+    let mut rate_limited_retries = 0u32;
+    let mut waited = Duration::ZERO;
+
+    loop {
+        let err = match op().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        let class = classify(&err);
+        let (config, retries) = match class {
+            ErrorClass::Network | ErrorClass::Timeout => (&policy.network, &mut network_retries),
+            ErrorClass::Server => (&policy.server, &mut server_retries),
+            ErrorClass::RateLimited { .. } => (&policy.rate_limited, &mut rate_limited_retries),
+            ErrorClass::Permanent => return Err(err),
+        };
+        if !config.should_retry(*retries) {
+            return Err(err);
+        }
+        *retries = retries.saturating_add(1);
+
+        let delay = match class {
+            ErrorClass::RateLimited {
+                retry_after: Some(delay),
+            } => delay,
+            _ => config.jittered_delay(*retries, rng),
+        };
+        let total = waited.saturating_add(delay);
+        if config.max_total_delay.is_some_and(|budget| total > budget) {
+            return Err(err);
+        }
+        waited = total;
+        tokio::time::sleep(delay).await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,4 +436,135 @@ mod tests {
         assert!(jittered_low < base_delay);
         assert!(jittered_high > base_delay);
     }
+
+    #[test]
+    fn full_and_equal_jitter_stay_in_range_and_are_seeded() {
+        use rand::rngs::StdRng;
+
+        let full = RetryConfig::new(5, Duration::from_millis(100), Duration::from_secs(10), 2.0)
+            .with_jitter(Jitter::Full);
+        let equal = full.clone().with_jitter(Jitter::Equal);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut replay = StdRng::seed_from_u64(7);
+        let mut distinct = std::collections::HashSet::new();
+        for _ in 0..100 {
+            let delay = full.jittered_delay(3, &mut rng);
+            assert!(delay <= Duration::from_millis(400));
+            assert_eq!(delay, full.jittered_delay(3, &mut replay));
+            distinct.insert(delay);
+
+            let delay = equal.jittered_delay(3, &mut rng);
+            assert!(delay >= Duration::from_millis(200) && delay <= Duration::from_millis(400));
+            assert_eq!(delay, equal.jittered_delay(3, &mut replay));
+        }
+        // Jittered clients do not retry in lockstep.
+        assert!(distinct.len() > 50);
+    }
+
+    #[test]
+    fn policy_picks_config_by_class() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.for_class(ErrorClass::Timeout), Some(&policy.network));
+        assert_eq!(policy.for_class(ErrorClass::Server), Some(&policy.server));
+        assert_eq!(
+            policy.for_class(ErrorClass::RateLimited { retry_after: None }),
+            Some(&policy.rate_limited)
+        );
+        assert_eq!(policy.for_class(ErrorClass::Permanent), None);
+    }
+
+    /// Runs `classes` as the errors of successive attempts, then succeeds.
+    /// Returns the outcome, the number of attempts, and the virtual time spent.
+    async fn run(
+        policy: &RetryPolicy,
+        classes: &[ErrorClass],
+    ) -> (Result<(), ErrorClass>, usize, Duration) {
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let start = tokio::time::Instant::now();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        let result = retry_with_policy_rng(
+            policy,
+            &mut rng,
+            |class| *class,
+            || {
+                let n = calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                let outcome = classes.get(n).map_or(Ok(()), |class| Err(*class));
+                async move { outcome }
+            },
+        )
+        .await;
+        (result, calls.into_inner(), start.elapsed())
+    }
+
+    fn exact(max_attempts: u32) -> RetryConfig {
+        RetryConfig::new(
+            max_attempts,
+            Duration::from_millis(100),
+            Duration::from_secs(10),
+            2.0,
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limit_waits_for_server_delay() {
+        let policy = RetryPolicy::uniform(exact(3));
+        let limited = ErrorClass::RateLimited {
+            retry_after: Some(Duration::from_secs(7)),
+        };
+        let (result, calls, elapsed) = run(&policy, &[limited, ErrorClass::Network]).await;
+        assert!(result.is_ok());
+        assert_eq!(calls, 3);
+        // 7 s from the server, then 100 ms of network backoff.
+        assert_eq!(elapsed, Duration::from_millis(7100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn classes_count_retries_separately() {
+        let policy = RetryPolicy {
+            network: exact(2),
+            server: exact(1),
+            rate_limited: exact(0),
+        };
+        let (result, calls, elapsed) = run(
+            &policy,
+            &[ErrorClass::Network, ErrorClass::Server, ErrorClass::Network],
+        )
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(calls, 4);
+        assert_eq!(elapsed, Duration::from_millis(100 + 100 + 200));
+
+        let (result, calls, _) = run(&policy, &[ErrorClass::Server, ErrorClass::Server]).await;
+        assert_eq!(result, Err(ErrorClass::Server));
+        assert_eq!(calls, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn permanent_errors_are_not_retried() {
+        let (result, calls, elapsed) = run(&RetryPolicy::default(), &[ErrorClass::Permanent]).await;
+        assert_eq!(result, Err(ErrorClass::Permanent));
+        assert_eq!(calls, 1);
+        assert_eq!(elapsed, Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn budget_stops_retries_before_overspending() {
+        let policy =
+            RetryPolicy::uniform(exact(10).with_max_total_delay(Duration::from_millis(250)));
+        let (result, calls, elapsed) = run(&policy, &[ErrorClass::Timeout; 5]).await;
+        assert_eq!(result, Err(ErrorClass::Timeout));
+        // 100 ms fits, the next 200 ms would exceed the budget.
+        assert_eq!(calls, 2);
+        assert_eq!(elapsed, Duration::from_millis(100));
+
+        // A server delay longer than the budget is not waited for.
+        let limited = ErrorClass::RateLimited {
+            retry_after: Some(Duration::from_mins(1)),
+        };
+        let (result, calls, elapsed) = run(&policy, &[limited]).await;
+        assert_eq!(result, Err(limited));
+        assert_eq!(calls, 1);
+        assert_eq!(elapsed, Duration::ZERO);
+    }
 }
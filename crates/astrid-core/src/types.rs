@@ -223,6 +223,7 @@ impl ApprovalRequest {
             resource: None,
             options: vec![
                 ApprovalOption::AllowOnce,
+                ApprovalOption::AllowThisExactAction,
                 ApprovalOption::AllowSession,
                 ApprovalOption::AllowWorkspace,
                 ApprovalOption::AllowAlways,
@@ -252,6 +253,8 @@ impl ApprovalRequest {
 pub enum ApprovalOption {
     /// Allow this one time
     AllowOnce,
+    /// Allow exactly this action (same arguments) in the current workspace
+    AllowThisExactAction,
     /// Allow for the current session
     AllowSession,
     /// Allow for the current workspace (persists in workspace state.db)
@@ -266,6 +269,7 @@ impl fmt::Display for ApprovalOption {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::AllowOnce => write!(f, "Allow Once"),
+            Self::AllowThisExactAction => write!(f, "Allow This Exact Action"),
             Self::AllowSession => write!(f, "Allow Session"),
             Self::AllowWorkspace => write!(f, "Allow Workspace"),
             Self::AllowAlways => write!(f, "Allow Always"),
@@ -323,6 +327,12 @@ impl ApprovalDecision {
     pub fn creates_workspace_allowance(&self) -> bool {
         matches!(self.decision, ApprovalOption::AllowWorkspace)
     }
+
+    /// Check if this remembers the exact action for the workspace.
+    #[must_use]
+    pub fn creates_exact_action_allowance(&self) -> bool {
+        matches!(self.decision, ApprovalOption::AllowThisExactAction)
+    }
 }
 
 #[cfg(test)]
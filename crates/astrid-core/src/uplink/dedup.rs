@@ -0,0 +1,149 @@
+//! Duplicate suppression for inbound messages.
+//!
+//! Gateway reconnects and overlapping long-polls can deliver the same
+//! platform message twice. [`InboundDedup`] remembers recently seen
+//! [`InboundMessage::message_id`]s per platform so the second copy can be
+//! dropped. It is serializable so the owner can persist it and keep
+//! suppressing duplicates across a restart; platform names are used as keys
+//! because uplink IDs are reissued on every registration.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::types::InboundMessage;
+
+/// Default number of message IDs remembered per platform.
+pub const DEFAULT_DEDUP_CAPACITY: usize = 1024;
+
+/// Default time a message ID is remembered.
+pub const DEFAULT_DEDUP_TTL: Duration = Duration::from_mins(10);
+
+/// Bounded, time-limited record of recently processed inbound message IDs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboundDedup {
+    /// Maximum IDs kept per platform; the oldest is evicted first.
+    capacity: usize,
+    /// How long an ID is remembered.
+    ttl: Duration,
+    /// Seen IDs per platform, oldest first.
+    seen: HashMap<String, VecDeque<(String, DateTime<Utc>)>>,
+    /// Duplicates dropped since creation.
+    duplicates: u64,
+}
+
+impl Default for InboundDedup {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUP_CAPACITY, DEFAULT_DEDUP_TTL)
+    }
+}
+
+impl InboundDedup {
+    /// Create an empty cache keeping up to `capacity` IDs per platform for
+    /// `ttl`.
+    #[must_use]
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            seen: HashMap::new(),
+            duplicates: 0,
+        }
+    }
+
+    /// Record `message` and report whether it should be processed.
+    ///
+    /// Returns `false` if a message with the same platform and message ID was
+    /// seen within the TTL. Messages without a message ID always pass.
+    pub fn check(&mut self, message: &InboundMessage, now: DateTime<Utc>) -> bool {
+        let Some(id) = message.message_id.as_deref() else {
+            return true;
+        };
+        if self.capacity == 0 {
+            return true;
+        }
+        let ttl = chrono::Duration::from_std(self.ttl).unwrap_or(chrono::Duration::MAX);
+        let cutoff = now
+            .checked_sub_signed(ttl)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+
+        let seen = self.seen.entry(message.platform.clone()).or_default();
+        while seen.front().is_some_and(|(_, at)| *at <= cutoff) {
+            seen.pop_front();
+        }
+        if seen.iter().any(|(seen_id, _)| seen_id == id) {
+            self.duplicates = self.duplicates.saturating_add(1);
+            return false;
+        }
+        if seen.len() >= self.capacity {
+            seen.pop_front();
+        }
+        seen.push_back((id.to_string(), now));
+        true
+    }
+
+    /// Number of duplicates dropped since this cache was created.
+    #[must_use]
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uplink::UplinkId;
+
+    fn message(platform: &str, id: Option<&str>) -> InboundMessage {
+        let builder = InboundMessage::builder(UplinkId::new(), platform, "user-1", "hello");
+        match id {
+            Some(id) => builder.message_id(id).build(),
+            None => builder.build(),
+        }
+    }
+
+    #[test]
+    fn duplicate_burst_is_dropped_across_restart() {
+        let now = Utc::now();
+        let mut dedup = InboundDedup::default();
+        assert!(dedup.check(&message("discord", Some("m1")), now));
+        assert!(!dedup.check(&message("discord", Some("m1")), now));
+        assert!(dedup.check(&message("discord", Some("m2")), now));
+
+        // Simulated restart: persist, reload, replay the same burst.
+        let saved = serde_json::to_string(&dedup).unwrap();
+        let mut dedup: InboundDedup = serde_json::from_str(&saved).unwrap();
+        assert!(!dedup.check(&message("discord", Some("m1")), now));
+        assert!(!dedup.check(&message("discord", Some("m2")), now));
+        assert!(dedup.check(&message("discord", Some("m3")), now));
+        assert_eq!(dedup.duplicates(), 3);
+    }
+
+    #[test]
+    fn ids_are_scoped_per_platform_and_optional() {
+        let now = Utc::now();
+        let mut dedup = InboundDedup::default();
+        assert!(dedup.check(&message("discord", Some("42")), now));
+        assert!(dedup.check(&message("telegram", Some("42")), now));
+        assert!(dedup.check(&message("discord", None), now));
+        assert!(dedup.check(&message("discord", None), now));
+        assert_eq!(dedup.duplicates(), 0);
+    }
+
+    #[test]
+    fn ids_expire_after_ttl_and_capacity() {
+        let now = Utc::now();
+        let mut dedup = InboundDedup::new(2, Duration::from_mins(1));
+        assert!(dedup.check(&message("discord", Some("a")), now));
+        let later = now + chrono::Duration::seconds(61);
+        assert!(dedup.check(&message("discord", Some("a")), later));
+
+        assert!(dedup.check(&message("discord", Some("b")), later));
+        assert!(dedup.check(&message("discord", Some("c")), later));
+        // "a" was evicted to make room for "c".
+        assert!(dedup.check(&message("discord", Some("a")), later));
+        assert!(!dedup.check(&message("discord", Some("c")), later));
+    }
+}
@@ -11,11 +11,14 @@
 
 // ---------------------------------------------------------------------------
 
+/// Duplicate suppression for inbound messages.
+pub(crate) mod dedup;
 /// Error types for uplinks.
 pub(crate) mod error;
 /// Core types for uplinks.
 pub(crate) mod types;
 
+pub use dedup::{DEFAULT_DEDUP_CAPACITY, DEFAULT_DEDUP_TTL, InboundDedup};
 pub use error::{UplinkError, UplinkResult};
 pub use types::*;
 
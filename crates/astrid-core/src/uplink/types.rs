@@ -433,6 +433,10 @@ pub struct InboundMessage {
     pub context: serde_json::Value,
     /// Thread identifier, if threaded.
     pub thread_id: Option<String>,
+    /// Platform message identifier (e.g. Discord message snowflake), used
+    /// to drop redelivered duplicates.
+    #[serde(default)]
+    pub message_id: Option<String>,
     /// When the message was created.
     pub timestamp: DateTime<Utc>,
 }
@@ -446,6 +450,7 @@ pub struct InboundMessageBuilder {
     content: String,
     context: serde_json::Value,
     thread_id: Option<String>,
+    message_id: Option<String>,
     timestamp: DateTime<Utc>,
 }
 
@@ -465,6 +470,7 @@ impl InboundMessage {
             content: content.into(),
             context: serde_json::Value::Null,
             thread_id: None,
+            message_id: None,
             timestamp: Utc::now(),
         }
     }
@@ -485,6 +491,13 @@ impl InboundMessageBuilder {
         self
     }
 
+    /// Set the platform message ID.
+    #[must_use]
+    pub fn message_id(mut self, message_id: impl Into<String>) -> Self {
+        self.message_id = Some(message_id.into());
+        self
+    }
+
     /// Override the timestamp (defaults to now).
     #[must_use]
     pub fn timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
@@ -502,6 +515,7 @@ impl InboundMessageBuilder {
             content: self.content,
             context: self.context,
             thread_id: self.thread_id,
+            message_id: self.message_id,
             timestamp: self.timestamp,
         }
     }
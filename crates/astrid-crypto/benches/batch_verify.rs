@@ -0,0 +1,42 @@
+//! Batch vs individual signature verification.
+//!
+//! Run with `cargo bench -p astrid-crypto --bench batch_verify`.
+
+#![allow(missing_docs)]
+
+use astrid_crypto::{BatchItem, KeyPair, Signature, SignatureVerifier};
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+
+fn verification(c: &mut Criterion) {
+    let keypair = KeyPair::generate();
+    let key = keypair.export_public_key();
+    let mut group = c.benchmark_group("verify");
+
+    for count in [64_usize, 1_024, 8_192] {
+        let messages: Vec<Vec<u8>> = (0..count)
+            .map(|i| format!("audit entry {i}").into_bytes())
+            .collect();
+        let signatures: Vec<Signature> = messages.iter().map(|m| keypair.sign(m)).collect();
+        let items: Vec<BatchItem<'_>> = messages
+            .iter()
+            .zip(&signatures)
+            .map(|(message, signature)| (message.as_slice(), signature, &key))
+            .collect();
+
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::new("individual", count), &items, |b, items| {
+            b.iter(|| {
+                items
+                    .iter()
+                    .all(|(message, signature, key)| key.verify(message, signature).is_ok())
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("batch", count), &items, |b, items| {
+            b.iter(|| SignatureVerifier::new().verify_batch(items).is_ok());
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, verification);
+criterion_main!(benches);
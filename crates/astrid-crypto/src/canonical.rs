@@ -0,0 +1,256 @@
+//! Canonical JSON (RFC 8785, the JSON Canonicalization Scheme).
+//!
+//! Hashing `serde_json::to_vec` output is not reproducible: key order
+//! depends on how the map was built and on serde feature flags, and float
+//! formatting is an implementation detail. The canonical form fixes every
+//! choice:
+//!
+//! - No whitespace between tokens.
+//! - Object members sorted by key, comparing keys as UTF-16 code units.
+//! - Strings escape only `"`, `\` and control characters. `\b`, `\t`,
+//!   `\n`, `\f` and `\r` use their short forms; other control characters
+//!   use `\u00xx` with lowercase hex. Everything else, including non-ASCII,
+//!   is written as is.
+//! - Numbers are IEEE 754 doubles written the way `Number.prototype.toString`
+//!   (ECMA-262) writes them: `4.50` becomes `4.5`, `1E30` becomes `1e+30`,
+//!   `-0` becomes `0`. Integers beyond 2^53 lose precision,
+//!   exactly as they would in any RFC 8785 implementation.
+//!
+//! The golden vectors in `tests/fixtures/canonical_json.json` pin this
+//! output. Changing it changes every hash derived from it.
+
+use std::fmt::Write as _;
+
+use serde_json::Value;
+
+/// Serialize `value` in RFC 8785 canonical form.
+#[must_use]
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value);
+    out
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => write_number(out, n.as_f64().unwrap_or(0.0)),
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item);
+            }
+            out.push(']');
+        },
+        Value::Object(map) => {
+            let mut members: Vec<(&String, &Value)> = map.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, item)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, key);
+                out.push(':');
+                write_value(out, item);
+            }
+            out.push('}');
+        },
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\r' => out.push_str("\\r"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            },
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// `Number.prototype.toString` (ECMA-262) for a finite double.
+fn write_number(out: &mut String, x: f64) {
+    if x == 0.0 {
+        out.push('0');
+        return;
+    }
+    if x < 0.0 {
+        out.push('-');
+    }
+
+    let (digits, exponent) = shortest_digits(x.abs());
+    // `digits` × 10^(n - k) = x, with k digits.
+    let k = i32::try_from(digits.len()).unwrap_or(i32::MAX);
+    let n = exponent.saturating_add(1);
+
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n(
+            '0',
+            usize::try_from(n.saturating_sub(k)).unwrap_or(0),
+        ));
+    } else if 0 < n && n <= 21 {
+        let (int, frac) = digits.split_at(usize::try_from(n).unwrap_or(0));
+        out.push_str(int);
+        out.push('.');
+        out.push_str(frac);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n(
+            '0',
+            usize::try_from(n.unsigned_abs()).unwrap_or(0),
+        ));
+        out.push_str(&digits);
+    } else {
+        let (first, rest) = digits.split_at(1);
+        out.push_str(first);
+        if !rest.is_empty() {
+            out.push('.');
+            out.push_str(rest);
+        }
+        let sign = if exponent < 0 { '-' } else { '+' };
+        let _ = write!(out, "e{sign}{}", exponent.unsigned_abs());
+    }
+}
+
+/// The shortest digit string that round-trips to `x`, and its decimal
+/// exponent (`d.ddd × 10^exponent`).
+///
+/// `{:e}` finds the shortest length, but when two strings of that length
+/// are equally close to `x` it rounds up, where ECMA-262 picks the even
+/// one. So round the exact expansion of `x` to the same length, half to
+/// even, and prefer that when it still round-trips.
+fn shortest_digits(x: f64) -> (String, i32) {
+    let (digits, exponent) = split_sci(&format!("{x:e}"));
+    // 800 digits covers the exact expansion of every double.
+    let (exact, exact_exponent) = split_sci(&format!("{x:.800e}"));
+    let (kept, rest) = exact.split_at(digits.len().min(exact.len()));
+    let round_up = match rest.as_bytes().first() {
+        Some(b'6'..=b'9') => true,
+        Some(b'5') if rest[1..].bytes().any(|b| b != b'0') => true,
+        // ASCII digits have the parity of their value.
+        Some(b'5') => kept.bytes().last().is_some_and(|b| b % 2 == 1),
+        _ => false,
+    };
+    let (mut even, mut even_exponent) = (kept.as_bytes().to_vec(), exact_exponent);
+    if round_up && increment(&mut even) {
+        even.insert(0, b'1');
+        even.pop();
+        even_exponent = even_exponent.saturating_add(1);
+    }
+    while even.len() > 1 && even.last() == Some(&b'0') {
+        even.pop();
+    }
+    let even = String::from_utf8(even).unwrap_or_default();
+    if even != digits || even_exponent != exponent {
+        let candidate = format!("0.{even}e{}", even_exponent.saturating_add(1));
+        if candidate
+            .parse::<f64>()
+            .is_ok_and(|parsed| parsed.to_bits() == x.to_bits())
+        {
+            return (even, even_exponent);
+        }
+    }
+    (digits, exponent)
+}
+
+/// Split `d.ddde<exp>` into its digits and exponent.
+fn split_sci(sci: &str) -> (String, i32) {
+    let (mantissa, exponent) = sci.split_once('e').unwrap_or((sci, "0"));
+    let digits = mantissa.chars().filter(char::is_ascii_digit).collect();
+    (digits, exponent.parse().unwrap_or(0))
+}
+
+/// Add one to a decimal digit string in place. Returns `true` on overflow
+/// (all nines), leaving the digits as zeros.
+fn increment(digits: &mut [u8]) -> bool {
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit = digit.saturating_add(1);
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn number(bits: u64) -> String {
+        let mut out = String::new();
+        write_number(&mut out, f64::from_bits(bits));
+        out
+    }
+
+    #[test]
+    fn rfc8785_number_vectors() {
+        // RFC 8785, Appendix B.
+        let vectors: &[(u64, &str)] = &[
+            (0x0000_0000_0000_0000, "0"),
+            (0x8000_0000_0000_0000, "0"),
+            (0x0000_0000_0000_0001, "5e-324"),
+            (0x8000_0000_0000_0001, "-5e-324"),
+            (0x7fef_ffff_ffff_ffff, "1.7976931348623157e+308"),
+            (0xffef_ffff_ffff_ffff, "-1.7976931348623157e+308"),
+            (0x4340_0000_0000_0000, "9007199254740992"),
+            (0xc340_0000_0000_0000, "-9007199254740992"),
+            (0x4430_0000_0000_0000, "295147905179352830000"),
+            (0x44b5_2d02_c7e1_4af5, "9.999999999999997e+22"),
+            (0x44b5_2d02_c7e1_4af6, "1e+23"),
+            (0x44b5_2d02_c7e1_4af7, "1.0000000000000001e+23"),
+            (0x444b_1ae4_d6e2_ef4e, "999999999999999700000"),
+            (0x444b_1ae4_d6e2_ef4f, "999999999999999900000"),
+            (0x444b_1ae4_d6e2_ef50, "1e+21"),
+            (0x3eb0_c6f7_a0b5_ed8c, "9.999999999999997e-7"),
+            (0x3eb0_c6f7_a0b5_ed8d, "0.000001"),
+            (0x41b3_de43_5555_5553, "333333333.3333332"),
+            (0x41b3_de43_5555_5554, "333333333.33333325"),
+            (0x41b3_de43_5555_5555, "333333333.3333333"),
+            (0x41b3_de43_5555_5556, "333333333.3333334"),
+            (0x41b3_de43_5555_5557, "333333333.33333343"),
+            (0xbecb_f647_612f_3696, "-0.0000033333333333333333"),
+            (0x4314_3ff3_c1cb_0959, "1424953923781206.2"),
+        ];
+        for (bits, expected) in vectors {
+            assert_eq!(number(*bits), *expected, "bits {bits:#018x}");
+        }
+    }
+
+    #[test]
+    fn sorts_keys_by_utf16_code_units() {
+        // U+FB33 sorts after U+1F600 in UTF-16 (surrogates are 0xD8xx) but
+        // before it in UTF-8; RFC 8785 requires the UTF-16 order.
+        let value = json!({"\u{fb33}": 1, "\u{1f600}": 2, "a": 3, "A": 4});
+        assert_eq!(
+            canonical_json(&value),
+            "{\"A\":4,\"a\":3,\"\u{1f600}\":2,\"\u{fb33}\":1}"
+        );
+    }
+
+    #[test]
+    fn escapes_only_what_it_must() {
+        let value = json!("\u{20ac}/\u{0f}\u{08}\"\\é");
+        assert_eq!(canonical_json(&value), "\"\u{20ac}/\\u000f\\b\\\"\\\\é\"");
+    }
+}
@@ -42,6 +42,38 @@ pub enum CryptoError {
     /// I/O error (e.g. reading/writing key files).
     #[error("I/O error: {0}")]
     IoError(String),
+
+    /// Decryption failed: wrong passphrase or key, or the data was modified.
+    #[error("decryption failed: wrong passphrase or key, or the data was modified")]
+    DecryptionFailed,
+
+    /// Data is not a sealed box this version can open.
+    #[error("invalid sealed box: {0}")]
+    InvalidEnvelope(String),
+
+    /// A key file is malformed or a key name is invalid.
+    #[error("invalid key file: {0}")]
+    InvalidKeyFile(String),
+
+    /// A key file can be accessed by users other than its owner.
+    #[error("insecure permissions {mode:o} on {path}: key files must be owner-only (0600)")]
+    InsecurePermissions {
+        /// The key file.
+        path: String,
+        /// Its permission bits.
+        mode: u32,
+    },
+
+    /// Batch verification found invalid signatures.
+    #[error("{} of the batch's signatures failed verification", invalid.len())]
+    BatchVerificationFailed {
+        /// Indices of the failing items, in ascending order.
+        invalid: Vec<usize>,
+    },
+
+    /// The OS keychain failed.
+    #[error("keychain error: {0}")]
+    Keychain(String),
 }
 
 /// Result type for cryptographic operations.
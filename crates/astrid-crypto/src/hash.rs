@@ -22,6 +22,30 @@ impl ContentHash {
         Self(*blake3::hash(data).as_bytes())
     }
 
+    /// Hash a JSON value in its RFC 8785 canonical form.
+    ///
+    /// Equal values hash equally regardless of key order, whitespace or
+    /// number spelling (`4.50` and `4.5`), and across crate versions. Use
+    /// this, not `serde_json::to_vec`, whenever a JSON hash is stored or
+    /// compared. See [`canonical_json`](crate::canonical_json).
+    #[must_use]
+    pub fn hash_canonical_json(value: &serde_json::Value) -> Self {
+        Self::hash(crate::canonical_json(value).as_bytes())
+    }
+
+    /// Hash everything `reader` yields without buffering it all in memory.
+    ///
+    /// Produces the same hash as [`hash`](Self::hash) over the same bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from reading.
+    pub fn hash_file_streaming(reader: impl std::io::Read) -> std::io::Result<Self> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update_reader(reader)?;
+        Ok(Self(*hasher.finalize().as_bytes()))
+    }
+
     /// Create a zero hash (used for genesis entries).
     #[must_use]
     pub const fn zero() -> Self {
@@ -195,4 +219,23 @@ mod tests {
         let decoded: ContentHash = serde_json::from_str(&json).unwrap();
         assert_eq!(hash, decoded);
     }
+
+    #[test]
+    fn test_canonical_json_ignores_spelling() {
+        let a: serde_json::Value =
+            serde_json::from_str(r#"{"b": [4.50, 1E2], "a": null}"#).unwrap();
+        let b: serde_json::Value = serde_json::from_str(r#"{"a":null,"b":[4.5,100]}"#).unwrap();
+        assert_eq!(
+            ContentHash::hash_canonical_json(&a),
+            ContentHash::hash_canonical_json(&b)
+        );
+    }
+
+    #[test]
+    fn test_streaming_matches_one_shot() {
+        // Larger than blake3's internal read buffer.
+        let data: Vec<u8> = (0..200_000u32).map(|i| i.to_le_bytes()[0]).collect();
+        let streamed = ContentHash::hash_file_streaming(data.as_slice()).unwrap();
+        assert_eq!(streamed, ContentHash::hash(&data));
+    }
 }
@@ -7,7 +7,7 @@
 use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 use crate::error::{CryptoError, CryptoResult};
 use crate::signature::Signature;
@@ -106,6 +106,15 @@ impl KeyPair {
         PublicKey::from_bytes(*self.public_key_bytes())
     }
 
+    /// The X25519 secret matching this key pair, for sealed boxes.
+    ///
+    /// This is the unclamped scalar half of the expanded Ed25519 secret;
+    /// X25519 clamps it on use, which makes the matching X25519 public key
+    /// the Montgomery form of our Ed25519 public key.
+    pub(crate) fn x25519_secret(&self) -> [u8; 32] {
+        self.signing_key.to_scalar_bytes()
+    }
+
     /// Export the secret key bytes (careful - sensitive!).
     ///
     /// This should only be used for secure storage.
@@ -113,6 +122,17 @@ impl KeyPair {
     pub fn secret_key_bytes(&self) -> [u8; 32] {
         self.signing_key.to_bytes()
     }
+
+    /// Derive a 32-byte symmetric key for `context` from the secret key.
+    ///
+    /// Uses BLAKE3 key derivation, so keys derived for different contexts
+    /// are independent and none of them reveals the secret key. Used to
+    /// turn a stored root key into e.g. the storage encryption key.
+    #[must_use]
+    pub fn derive_key(&self, context: &str) -> Zeroizing<[u8; 32]> {
+        let secret = Zeroizing::new(self.signing_key.to_bytes());
+        Zeroizing::new(blake3::derive_key(context, secret.as_ref()))
+    }
 }
 
 impl std::fmt::Debug for KeyPair {
@@ -270,6 +290,14 @@ impl AsRef<[u8]> for PublicKey {
 mod tests {
     use super::*;
 
+    #[test]
+    fn derive_key_is_stable_per_context() {
+        let kp = KeyPair::generate();
+        assert_eq!(*kp.derive_key("a"), *kp.derive_key("a"));
+        assert_ne!(*kp.derive_key("a"), *kp.derive_key("b"));
+        assert_ne!(*kp.derive_key("a"), kp.secret_key_bytes());
+    }
+
     #[test]
     fn test_keypair_generation() {
         let kp1 = KeyPair::generate();
@@ -0,0 +1,529 @@
+//! Persistent key storage.
+//!
+//! Keys live as files under one directory (normally `~/.astrid/keys`), one
+//! per name: `runtime.key` holds the raw 32-byte secret, and
+//! `runtime.key.enc` the same secret encrypted with a passphrase. Files are
+//! created owner-only and refused on load if group or others can access
+//! them. Secret material read from disk is zeroized after use.
+//!
+//! The encrypted format is a fixed header followed by the ciphertext:
+//!
+//! | Bytes | Field |
+//! |---|---|
+//! | 8 | Magic `ASTRIDK1` |
+//! | 12 | Argon2id memory in `KiB`, iterations, parallelism, each `u32` LE |
+//! | 16 | Argon2id salt |
+//! | 24 | XChaCha20-Poly1305 nonce |
+//! | 48 | Encrypted secret key and tag |
+//!
+//! The header is authenticated, so any change to the file fails decryption.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use zeroize::Zeroizing;
+
+use crate::error::{CryptoError, CryptoResult};
+use crate::keypair::KeyPair;
+
+const MAGIC: &[u8; 8] = b"ASTRIDK1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = 8 + 12 + SALT_LEN + NONCE_LEN;
+const FILE_LEN: usize = HEADER_LEN + 32 + 16;
+
+/// Upper bound on the Argon2 memory a key file may ask for (1 `GiB`), so a
+/// tampered header cannot exhaust memory before the tag check fails.
+const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+
+impl KeyPair {
+    /// Write the secret key to `path`, encrypted with `passphrase`.
+    ///
+    /// The key is derived with Argon2id and the secret sealed with
+    /// XChaCha20-Poly1305. The file is created owner-only.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CryptoError::IoError`] if the file cannot be written.
+    pub fn save_encrypted(&self, path: &Path, passphrase: &str) -> CryptoResult<()> {
+        let params = Params::default();
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(MAGIC);
+        header.extend_from_slice(&params.m_cost().to_le_bytes());
+        header.extend_from_slice(&params.t_cost().to_le_bytes());
+        header.extend_from_slice(&params.p_cost().to_le_bytes());
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        header.extend_from_slice(&salt);
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        header.extend_from_slice(&nonce);
+
+        let key = derive_key(passphrase, &salt, params)?;
+        let secret = Zeroizing::new(self.secret_key_bytes());
+        let ciphertext = XChaCha20Poly1305::new(key.as_ref().into())
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: secret.as_slice(),
+                    aad: &header,
+                },
+            )
+            .map_err(|_| CryptoError::InvalidKeyFile("encryption failed".to_string()))?;
+
+        header.extend_from_slice(&ciphertext);
+        write_private(path, &header)
+    }
+
+    /// Read a key pair written by [`save_encrypted`](Self::save_encrypted).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CryptoError::DecryptionFailed`] if the passphrase is wrong
+    /// or the file was modified, [`CryptoError::InvalidKeyFile`] if it is
+    /// not an encrypted key file, [`CryptoError::InsecurePermissions`] if
+    /// others can access it, and [`CryptoError::IoError`] if it cannot be
+    /// read.
+    pub fn load_encrypted(path: &Path, passphrase: &str) -> CryptoResult<Self> {
+        let data = read_private(path)?;
+        if data.len() != FILE_LEN || !data.starts_with(MAGIC) {
+            return Err(CryptoError::InvalidKeyFile(format!(
+                "{} is not an encrypted Astrid key",
+                path.display()
+            )));
+        }
+        let (header, ciphertext) = data.split_at(HEADER_LEN);
+        let (params, rest) = header[MAGIC.len()..].split_at(12);
+        let (salt, nonce) = rest.split_at(SALT_LEN);
+        let mut words = params.chunks_exact(4).map(|chunk| {
+            let mut bytes = [0u8; 4];
+            bytes.copy_from_slice(chunk);
+            u32::from_le_bytes(bytes)
+        });
+        let (Some(m_cost), Some(t_cost), Some(p_cost)) = (words.next(), words.next(), words.next())
+        else {
+            return Err(CryptoError::InvalidKeyFile("truncated header".to_string()));
+        };
+        if m_cost > MAX_MEMORY_KIB {
+            return Err(CryptoError::InvalidKeyFile(format!(
+                "{} asks for {m_cost} KiB of key derivation memory",
+                path.display()
+            )));
+        }
+        let params = Params::new(m_cost, t_cost, p_cost, None)
+            .map_err(|e| CryptoError::InvalidKeyFile(e.to_string()))?;
+
+        let key = derive_key(passphrase, salt, params)?;
+        let secret = Zeroizing::new(
+            XChaCha20Poly1305::new(key.as_ref().into())
+                .decrypt(
+                    XNonce::from_slice(nonce),
+                    Payload {
+                        msg: ciphertext,
+                        aad: header,
+                    },
+                )
+                .map_err(|_| CryptoError::DecryptionFailed)?,
+        );
+        Self::from_secret_key(&secret)
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: Params) -> CryptoResult<Zeroizing<[u8; 32]>> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| CryptoError::InvalidKeyFile(format!("key derivation failed: {e}")))?;
+    Ok(key)
+}
+
+/// Named keys stored under one directory.
+#[derive(Debug, Clone)]
+pub struct KeyStore {
+    dir: PathBuf,
+}
+
+impl KeyStore {
+    /// Name of the runtime signing key.
+    pub const RUNTIME: &'static str = "runtime";
+    /// Name of the audit signing key.
+    pub const AUDIT: &'static str = "audit";
+    /// Name of the root key that storage encryption keys are derived from.
+    pub const STORAGE: &'static str = "storage";
+
+    /// A key store rooted at `dir` (e.g. `AstridHome::keys_dir()`).
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Directory the keys live in.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the unencrypted key file for `name`.
+    #[must_use]
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.key"))
+    }
+
+    /// Path of the encrypted key file for `name`.
+    #[must_use]
+    pub fn encrypted_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.key.enc"))
+    }
+
+    /// Load the key `name`, or `None` if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is invalid, or the file is unreadable,
+    /// malformed, or accessible to others.
+    pub fn load(&self, name: &str) -> CryptoResult<Option<KeyPair>> {
+        validate_name(name)?;
+        let path = self.path(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let secret = read_private(&path)?;
+        KeyPair::from_secret_key(&secret)
+            .map(Some)
+            .map_err(|e| CryptoError::InvalidKeyFile(format!("{}: {e}", path.display())))
+    }
+
+    /// Store `keypair` as `name`, replacing any existing key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is invalid or the file cannot be
+    /// written.
+    pub fn save(&self, name: &str, keypair: &KeyPair) -> CryptoResult<()> {
+        validate_name(name)?;
+        let secret = Zeroizing::new(keypair.secret_key_bytes());
+        write_private(&self.path(name), secret.as_slice())
+    }
+
+    /// Load the key `name`, generating and storing a new one if it does not
+    /// exist.
+    ///
+    /// # Errors
+    ///
+    /// See [`load`](Self::load) and [`save`](Self::save).
+    pub fn load_or_generate(&self, name: &str) -> CryptoResult<KeyPair> {
+        if let Some(keypair) = self.load(name)? {
+            return Ok(keypair);
+        }
+        let keypair = KeyPair::generate();
+        self.save(name, &keypair)?;
+        Ok(keypair)
+    }
+
+    /// Load the encrypted key `name`, or `None` if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// See [`KeyPair::load_encrypted`].
+    pub fn load_encrypted(&self, name: &str, passphrase: &str) -> CryptoResult<Option<KeyPair>> {
+        validate_name(name)?;
+        let path = self.encrypted_path(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        KeyPair::load_encrypted(&path, passphrase).map(Some)
+    }
+
+    /// Store `keypair` as `name`, encrypted with `passphrase`.
+    ///
+    /// # Errors
+    ///
+    /// See [`KeyPair::save_encrypted`].
+    pub fn save_encrypted(
+        &self,
+        name: &str,
+        keypair: &KeyPair,
+        passphrase: &str,
+    ) -> CryptoResult<()> {
+        validate_name(name)?;
+        keypair.save_encrypted(&self.encrypted_path(name), passphrase)
+    }
+}
+
+/// Key names become file names, so keep them to a safe alphabet.
+fn validate_name(name: &str) -> CryptoResult<()> {
+    let valid = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_');
+    if valid {
+        Ok(())
+    } else {
+        Err(CryptoError::InvalidKeyFile(format!(
+            "invalid key name {name:?}: use lowercase letters, digits, '-' and '_'"
+        )))
+    }
+}
+
+/// Read a secret file, refusing it if group or others can access it.
+fn read_private(path: &Path) -> CryptoResult<Zeroizing<Vec<u8>>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)
+            .map_err(|e| CryptoError::IoError(format!("{}: {e}", path.display())))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            return Err(CryptoError::InsecurePermissions {
+                path: path.display().to_string(),
+                mode: mode & 0o777,
+            });
+        }
+    }
+    std::fs::read(path)
+        .map(Zeroizing::new)
+        .map_err(|e| CryptoError::IoError(format!("{}: {e}", path.display())))
+}
+
+/// Write a secret file owner-only, through a temporary file so a crash
+/// never leaves a truncated key behind.
+fn write_private(path: &Path, data: &[u8]) -> CryptoResult<()> {
+    let io_error = |e: std::io::Error| CryptoError::IoError(format!("{}: {e}", path.display()));
+    if let Some(dir) = path.parent() {
+        create_private_dir(dir).map_err(io_error)?;
+    }
+    let mut tmp_name = path.as_os_str().to_owned();
+    tmp_name.push(".tmp");
+    let tmp = PathBuf::from(tmp_name);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp).map_err(io_error)?;
+    file.write_all(data).map_err(io_error)?;
+    file.sync_all().map_err(io_error)?;
+    std::fs::rename(&tmp, path).map_err(io_error)
+}
+
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    if dir.exists() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// Keys stored in the OS keychain instead of files.
+#[cfg(feature = "keychain")]
+#[derive(Debug, Clone)]
+pub struct KeychainKeyStore {
+    service: String,
+}
+
+#[cfg(feature = "keychain")]
+impl KeychainKeyStore {
+    /// A keychain store whose entries are filed under `service`.
+    #[must_use]
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+        }
+    }
+
+    /// Load the key `name`, or `None` if the keychain has no such entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CryptoError::Keychain`] if the keychain is unavailable,
+    /// and [`CryptoError::InvalidKeyFile`] if the entry is malformed.
+    pub fn load(&self, name: &str) -> CryptoResult<Option<KeyPair>> {
+        validate_name(name)?;
+        match self.entry(name)?.get_password() {
+            Ok(encoded) => {
+                let encoded = Zeroizing::new(encoded);
+                let secret = Zeroizing::new(hex::decode(encoded.as_str()).map_err(|_| {
+                    CryptoError::InvalidKeyFile(format!("keychain entry {name} is not hex"))
+                })?);
+                KeyPair::from_secret_key(&secret).map(Some)
+            },
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(CryptoError::Keychain(e.to_string())),
+        }
+    }
+
+    /// Store `keypair` as `name`, replacing any existing entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CryptoError::Keychain`] if the keychain is unavailable.
+    pub fn save(&self, name: &str, keypair: &KeyPair) -> CryptoResult<()> {
+        validate_name(name)?;
+        let secret = Zeroizing::new(keypair.secret_key_bytes());
+        let encoded = Zeroizing::new(hex::encode(secret.as_slice()));
+        self.entry(name)?
+            .set_password(&encoded)
+            .map_err(|e| CryptoError::Keychain(e.to_string()))
+    }
+
+    /// Load the key `name`, generating and storing a new one if it does not
+    /// exist.
+    ///
+    /// # Errors
+    ///
+    /// See [`load`](Self::load) and [`save`](Self::save).
+    pub fn load_or_generate(&self, name: &str) -> CryptoResult<KeyPair> {
+        if let Some(keypair) = self.load(name)? {
+            return Ok(keypair);
+        }
+        let keypair = KeyPair::generate();
+        self.save(name, &keypair)?;
+        Ok(keypair)
+    }
+
+    fn entry(&self, name: &str) -> CryptoResult<keyring::Entry> {
+        keyring::Entry::new(&self.service, name).map_err(|e| CryptoError::Keychain(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn encrypted_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("k.key.enc");
+        let keypair = KeyPair::generate();
+
+        keypair.save_encrypted(&path, "correct horse").unwrap();
+        let loaded = KeyPair::load_encrypted(&path, "correct horse").unwrap();
+
+        assert_eq!(loaded.public_key_bytes(), keypair.public_key_bytes());
+        let raw = std::fs::read(&path).unwrap();
+        assert_eq!(raw.len(), FILE_LEN);
+        assert!(
+            !raw.windows(32)
+                .any(|w| w == keypair.secret_key_bytes().as_slice())
+        );
+    }
+
+    #[test]
+    fn wrong_passphrase_fails() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("k.key.enc");
+        KeyPair::generate().save_encrypted(&path, "right").unwrap();
+
+        let result = KeyPair::load_encrypted(&path, "wrong");
+
+        assert!(matches!(result, Err(CryptoError::DecryptionFailed)));
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("k.key.enc");
+        KeyPair::generate().save_encrypted(&path, "pw").unwrap();
+        let original = std::fs::read(&path).unwrap();
+
+        // Flip one bit in the salt, the nonce, and the ciphertext in turn.
+        for offset in [20, HEADER_LEN - 1, FILE_LEN - 1] {
+            let mut data = original.clone();
+            data[offset] ^= 1;
+            write_private(&path, &data).unwrap();
+            assert!(
+                matches!(
+                    KeyPair::load_encrypted(&path, "pw"),
+                    Err(CryptoError::DecryptionFailed)
+                ),
+                "tampering at byte {offset} went unnoticed"
+            );
+        }
+
+        write_private(&path, &original[..FILE_LEN - 1]).unwrap();
+        assert!(matches!(
+            KeyPair::load_encrypted(&path, "pw"),
+            Err(CryptoError::InvalidKeyFile(_))
+        ));
+    }
+
+    #[test]
+    fn key_store_generates_once() {
+        let dir = TempDir::new().unwrap();
+        let store = KeyStore::new(dir.path().join("keys"));
+
+        let first = store.load_or_generate(KeyStore::RUNTIME).unwrap();
+        let second = store.load_or_generate(KeyStore::RUNTIME).unwrap();
+        let audit = store.load_or_generate(KeyStore::AUDIT).unwrap();
+
+        assert_eq!(first.public_key_bytes(), second.public_key_bytes());
+        assert_ne!(first.public_key_bytes(), audit.public_key_bytes());
+        assert!(store.path("runtime").is_file());
+    }
+
+    #[test]
+    fn key_store_encrypted_keys() {
+        let dir = TempDir::new().unwrap();
+        let store = KeyStore::new(dir.path());
+        let keypair = KeyPair::generate();
+
+        assert!(store.load_encrypted("audit", "pw").unwrap().is_none());
+        store.save_encrypted("audit", &keypair, "pw").unwrap();
+        let loaded = store.load_encrypted("audit", "pw").unwrap().unwrap();
+
+        assert_eq!(loaded.public_key_bytes(), keypair.public_key_bytes());
+    }
+
+    #[test]
+    fn key_store_rejects_bad_names() {
+        let store = KeyStore::new("/nonexistent");
+        for name in ["", "../runtime", "Runtime", "a/b"] {
+            assert!(
+                matches!(store.load(name), Err(CryptoError::InvalidKeyFile(_))),
+                "{name:?} accepted"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn permissions_are_private_and_checked() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let keys = dir.path().join("keys");
+        let store = KeyStore::new(&keys);
+        store.load_or_generate(KeyStore::RUNTIME).unwrap();
+
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&keys), 0o700);
+        assert_eq!(mode(&store.path("runtime")), 0o600);
+
+        std::fs::set_permissions(
+            store.path("runtime"),
+            std::fs::Permissions::from_mode(0o644),
+        )
+        .unwrap();
+        assert!(matches!(
+            store.load(KeyStore::RUNTIME),
+            Err(CryptoError::InsecurePermissions { mode: 0o644, .. })
+        ));
+    }
+}
@@ -2,8 +2,13 @@
 //!
 //! This crate provides:
 //! - Ed25519 key pairs with secure memory handling
-//! - Signatures for capability tokens and audit entries
-//! - BLAKE3 content hashing for audit chains and verification
+//! - Signatures for capability tokens and audit entries, with batch
+//!   verification for checking audit chains
+//! - BLAKE3 content hashing for audit chains and verification, including
+//!   RFC 8785 canonical JSON and streaming file hashing
+//! - Sealed boxes: encryption to a recipient's public key
+//! - Key storage: owner-only key files, passphrase encryption, and an
+//!   optional OS keychain backend (`keychain` feature)
 //!
 //! # Security Philosophy
 //!
@@ -38,13 +43,22 @@
 #![cfg_attr(test, allow(clippy::unwrap_used))]
 
 pub mod prelude;
+pub mod sealed;
 
+mod canonical;
 mod error;
 mod hash;
 mod keypair;
+mod keystore;
 mod signature;
+mod verifier;
 
+pub use canonical::canonical_json;
 pub use error::{CryptoError, CryptoResult};
 pub use hash::ContentHash;
 pub use keypair::{KeyPair, PublicKey};
+pub use keystore::KeyStore;
+#[cfg(feature = "keychain")]
+pub use keystore::KeychainKeyStore;
 pub use signature::Signature;
+pub use verifier::{BatchItem, SignatureVerifier};
@@ -23,10 +23,10 @@
 pub use crate::{CryptoError, CryptoResult};
 
 // Key types
-pub use crate::{KeyPair, PublicKey};
+pub use crate::{KeyPair, KeyStore, PublicKey};
 
 // Signature
-pub use crate::Signature;
+pub use crate::{Signature, SignatureVerifier};
 
 // Hashing
 pub use crate::ContentHash;
@@ -0,0 +1,229 @@
+//! Batch signature verification.
+//!
+//! Checking an audit chain means verifying one signature per entry. Ed25519
+//! batch verification checks many signatures in a single multiscalar
+//! multiplication, which is several times faster than checking them one by
+//! one. A batch only says whether *every* signature is valid, so when a
+//! batch fails each of its items is verified individually to find the
+//! culprits. Batches are split into chunks so a single bad signature only
+//! costs one chunk's worth of individual checks.
+
+use std::collections::HashMap;
+
+use ed25519_dalek::{Signature as DalekSignature, Verifier, VerifyingKey};
+
+use crate::error::{CryptoError, CryptoResult};
+use crate::keypair::PublicKey;
+use crate::signature::Signature;
+
+/// One signature to check: the signed message, the signature, and the
+/// signer's public key.
+pub type BatchItem<'a> = (&'a [u8], &'a Signature, &'a PublicKey);
+
+/// Default number of signatures verified per batch.
+const DEFAULT_CHUNK_SIZE: usize = 256;
+
+/// Verifies many signatures at once.
+///
+/// # Example
+///
+/// ```
+/// use astrid_crypto::{KeyPair, SignatureVerifier};
+///
+/// let keypair = KeyPair::generate();
+/// let key = keypair.export_public_key();
+/// let messages: [&[u8]; 2] = [b"first", b"second"];
+/// let signatures: Vec<_> = messages.iter().map(|m| keypair.sign(m)).collect();
+///
+/// let items: Vec<_> = messages
+///     .iter()
+///     .zip(&signatures)
+///     .map(|(message, signature)| (*message, signature, &key))
+///     .collect();
+/// assert!(SignatureVerifier::new().verify_batch(&items).is_ok());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SignatureVerifier {
+    chunk_size: usize,
+}
+
+impl Default for SignatureVerifier {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+}
+
+impl SignatureVerifier {
+    /// Create a verifier with the default chunk size.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how many signatures are verified per batch (at least 1).
+    ///
+    /// Larger chunks are faster when everything is valid; smaller chunks
+    /// re-verify fewer signatures individually when something is not.
+    #[must_use]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Verify every item.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CryptoError::BatchVerificationFailed`] listing the index of
+    /// every item whose signature is invalid or whose public key is malformed.
+    pub fn verify_batch(&self, items: &[BatchItem<'_>]) -> CryptoResult<()> {
+        // Audit chains are signed by a handful of keys; decompress each once.
+        let mut keys: HashMap<[u8; 32], Option<VerifyingKey>> = HashMap::new();
+        let mut invalid = Vec::new();
+        let mut offset: usize = 0;
+
+        for chunk in items.chunks(self.chunk_size) {
+            let mut messages = Vec::with_capacity(chunk.len());
+            let mut signatures = Vec::with_capacity(chunk.len());
+            let mut verifying_keys = Vec::with_capacity(chunk.len());
+            let mut indices = Vec::with_capacity(chunk.len());
+
+            for (i, (message, signature, key)) in chunk.iter().enumerate() {
+                let index = offset.saturating_add(i);
+                let verifying_key = *keys
+                    .entry(*key.as_bytes())
+                    .or_insert_with(|| VerifyingKey::from_bytes(key.as_bytes()).ok());
+                match verifying_key {
+                    Some(verifying_key) => {
+                        messages.push(*message);
+                        signatures.push(signature.to_dalek());
+                        verifying_keys.push(verifying_key);
+                        indices.push(index);
+                    },
+                    None => invalid.push(index),
+                }
+            }
+
+            if !messages.is_empty()
+                && ed25519_dalek::verify_batch(&messages, &signatures, &verifying_keys).is_err()
+            {
+                invalid.extend(individually_invalid(
+                    &messages,
+                    &signatures,
+                    &verifying_keys,
+                    &indices,
+                ));
+            }
+            offset = offset.saturating_add(chunk.len());
+        }
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            invalid.sort_unstable();
+            Err(CryptoError::BatchVerificationFailed { invalid })
+        }
+    }
+}
+
+/// Indices of the items in a failed batch that do not verify on their own.
+fn individually_invalid(
+    messages: &[&[u8]],
+    signatures: &[DalekSignature],
+    verifying_keys: &[VerifyingKey],
+    indices: &[usize],
+) -> Vec<usize> {
+    messages
+        .iter()
+        .zip(signatures)
+        .zip(verifying_keys)
+        .zip(indices)
+        .filter(|(((message, signature), key), _)| key.verify(message, signature).is_err())
+        .map(|(_, index)| *index)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyPair;
+
+    fn signed(keypair: &KeyPair, count: usize) -> (Vec<Vec<u8>>, Vec<Signature>) {
+        let messages: Vec<Vec<u8>> = (0..count)
+            .map(|i| format!("entry {i}").into_bytes())
+            .collect();
+        let signatures = messages.iter().map(|m| keypair.sign(m)).collect();
+        (messages, signatures)
+    }
+
+    fn items<'a>(
+        messages: &'a [Vec<u8>],
+        signatures: &'a [Signature],
+        key: &'a PublicKey,
+    ) -> Vec<BatchItem<'a>> {
+        messages
+            .iter()
+            .zip(signatures)
+            .map(|(message, signature)| (message.as_slice(), signature, key))
+            .collect()
+    }
+
+    #[test]
+    fn empty_batch_verifies() {
+        assert!(SignatureVerifier::new().verify_batch(&[]).is_ok());
+    }
+
+    #[test]
+    fn identifies_the_one_invalid_signature_in_10k() {
+        let keypair = KeyPair::generate();
+        let key = keypair.export_public_key();
+        let (messages, mut signatures) = signed(&keypair, 10_000);
+        assert!(
+            SignatureVerifier::new()
+                .verify_batch(&items(&messages, &signatures, &key))
+                .is_ok()
+        );
+
+        signatures[7_321] = keypair.sign(b"something else");
+        let err = SignatureVerifier::new()
+            .verify_batch(&items(&messages, &signatures, &key))
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CryptoError::BatchVerificationFailed { ref invalid } if invalid == &[7_321]
+        ));
+    }
+
+    #[test]
+    fn mixed_keys_and_malformed_key() {
+        let alice = KeyPair::generate();
+        let bob = KeyPair::generate();
+        let alice_key = alice.export_public_key();
+        let bob_key = bob.export_public_key();
+        // Not a valid curve point.
+        let bad_key = PublicKey::from_bytes([
+            0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0, 0, 0x80,
+        ]);
+        assert!(ed25519_dalek::VerifyingKey::from_bytes(bad_key.as_bytes()).is_err());
+        let sig_a = alice.sign(b"a");
+        let sig_b = bob.sign(b"b");
+
+        let batch: [BatchItem<'_>; 4] = [
+            (b"a", &sig_a, &alice_key),
+            (b"b", &sig_b, &bob_key),
+            (b"b", &sig_b, &alice_key),
+            (b"a", &sig_a, &bad_key),
+        ];
+        let err = SignatureVerifier::new()
+            .with_chunk_size(2)
+            .verify_batch(&batch)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CryptoError::BatchVerificationFailed { ref invalid } if invalid == &[2, 3]
+        ));
+    }
+}
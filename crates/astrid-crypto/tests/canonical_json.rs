@@ -0,0 +1,43 @@
+//! Golden vectors for canonical JSON hashing.
+//!
+//! Every vector in `tests/fixtures/canonical_json.json` gives a JSON input,
+//! its RFC 8785 canonical form, and the BLAKE3 hash of that form. These are
+//! stored in audit logs and lockfiles, so any change to the output is a
+//! breaking change: fix the code, do not regenerate the vectors.
+
+use std::path::Path;
+
+use astrid_crypto::{ContentHash, canonical_json};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Vector {
+    name: String,
+    input: String,
+    canonical: String,
+    blake3: String,
+}
+
+#[test]
+fn canonical_json_golden_vectors() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/canonical_json.json");
+    let vectors: Vec<Vector> =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    assert!(!vectors.is_empty());
+
+    for vector in vectors {
+        let value: serde_json::Value = serde_json::from_str(&vector.input).unwrap();
+        assert_eq!(
+            canonical_json(&value),
+            vector.canonical,
+            "canonical form of {}",
+            vector.name
+        );
+        assert_eq!(
+            ContentHash::hash_canonical_json(&value).to_hex(),
+            vector.blake3,
+            "hash of {}",
+            vector.name
+        );
+    }
+}
@@ -65,6 +65,25 @@ fn init_logging(verbose: bool) {
     }
 }
 
+/// Re-read `[logging]` from the config files and apply its level and
+/// directives to the running daemon.
+#[cfg(unix)]
+fn reload_logging(verbose: bool) {
+    let workspace_root = std::env::current_dir().ok();
+    let cfg = match astrid_config::Config::load(workspace_root.as_deref()) {
+        Ok(resolved) => resolved.config,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to reload config, log filter unchanged");
+            return;
+        },
+    };
+    let level = if verbose { "debug" } else { &cfg.logging.level };
+    match astrid_telemetry::reload_log_filter(level, &cfg.logging.directives) {
+        Ok(filter) => tracing::info!(%filter, "Reloaded log filter from config"),
+        Err(e) => tracing::warn!(error = %e, "Invalid log filter in config, keeping current"),
+    }
+}
+
 /// Run the Astrid daemon with the given arguments.
 ///
 /// This is the shared entry point used by both the standalone `astrid-daemon`
@@ -141,18 +160,29 @@ pub async fn run() -> Result<()> {
 
     #[cfg(unix)]
     {
-        let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
-            .context("failed to register SIGTERM handler")?;
-        tokio::select! {
-            _ = tokio::signal::ctrl_c() => {
-                tracing::info!("Received SIGINT, shutting down");
-            }
-            _ = sigterm.recv() => {
-                tracing::info!("Received SIGTERM, shutting down");
-            }
-            _ = shutdown_rx.wait_for(|v| *v) => {
-                tracing::info!("Received API shutdown request, shutting down");
+        use tokio::signal::unix::{SignalKind, signal};
+        let mut sigterm =
+            signal(SignalKind::terminate()).context("failed to register SIGTERM handler")?;
+        let mut sighup =
+            signal(SignalKind::hangup()).context("failed to register SIGHUP handler")?;
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    tracing::info!("Received SIGINT, shutting down");
+                }
+                _ = sigterm.recv() => {
+                    tracing::info!("Received SIGTERM, shutting down");
+                }
+                _ = sighup.recv() => {
+                    // SIGHUP re-reads the log filter instead of stopping.
+                    reload_logging(args.verbose);
+                    continue;
+                }
+                _ = shutdown_rx.wait_for(|v| *v) => {
+                    tracing::info!("Received API shutdown request, shutting down");
+                }
             }
+            break;
         }
     }
     #[cfg(not(unix))]
@@ -169,5 +199,12 @@ pub async fn run() -> Result<()> {
 
     kernel.shutdown(Some("signal".to_string())).await;
 
+    // Flush buffered spans and metrics; the exporter blocks on HTTP.
+    match tokio::task::spawn_blocking(astrid_telemetry::shutdown_telemetry).await {
+        Ok(Err(e)) => eprintln!("Failed to flush telemetry: {e}"),
+        Err(e) => eprintln!("Telemetry flush task failed: {e}"),
+        Ok(Ok(())) => {},
+    }
+
     Ok(())
 }
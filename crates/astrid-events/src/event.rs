@@ -389,6 +389,18 @@ pub enum AstridEvent {
         reason: Option<String>,
     },
 
+    /// An MCP server's tool list changed mid-session.
+    McpToolsChanged {
+        /// Event metadata.
+        metadata: EventMetadata,
+        /// Server name.
+        server_name: String,
+        /// Tools the server added.
+        added: Vec<String>,
+        /// Tools the server removed.
+        removed: Vec<String>,
+    },
+
     /// MCP tool called.
     McpToolCalled {
         /// Event metadata.
@@ -751,6 +763,7 @@ impl AstridEvent {
             | Self::ToolCallFailed { metadata, .. }
             | Self::McpServerConnected { metadata, .. }
             | Self::McpServerDisconnected { metadata, .. }
+            | Self::McpToolsChanged { metadata, .. }
             | Self::McpToolCalled { metadata, .. }
             | Self::McpToolCompleted { metadata, .. }
             | Self::SubAgentSpawned { metadata, .. }
@@ -827,6 +840,7 @@ impl AstridEvent {
             // MCP
             Self::McpServerConnected { .. } => "astrid.v1.lifecycle.mcp_server_connected",
             Self::McpServerDisconnected { .. } => "astrid.v1.lifecycle.mcp_server_disconnected",
+            Self::McpToolsChanged { .. } => "astrid.v1.lifecycle.mcp_tools_changed",
             Self::McpToolCalled { .. } => "astrid.v1.lifecycle.mcp_tool_called",
             Self::McpToolCompleted { .. } => "astrid.v1.lifecycle.mcp_tool_completed",
             // SubAgent
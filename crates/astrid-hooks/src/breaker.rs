@@ -0,0 +1,177 @@
+//! Per-hook circuit breaker.
+//!
+//! A hook that fails [`BreakerConfig::failure_threshold`] times in a row is
+//! *opened*: it is not run, and its failure policy applies immediately, so a
+//! dead webhook stops adding its timeout to every event. After
+//! [`BreakerConfig::cooldown`] the breaker goes *half-open* and lets one
+//! call through; success closes it, failure reopens it for another cooldown.
+//!
+//! Opening and closing publish a custom `hooks.circuit_opened` /
+//! `hooks.circuit_closed` event on the configured [`EventBus`].
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use astrid_events::{AstridEvent, EventBus, EventMetadata};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::hook::Hook;
+
+/// Circuit breaker tuning.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BreakerConfig {
+    /// Consecutive failures that open the breaker.
+    pub failure_threshold: u32,
+    /// How long an open breaker rejects calls before a half-open retry.
+    pub cooldown: Duration,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Closed { failures: u32 },
+    Open { since: Instant, failures: u32 },
+    HalfOpen { failures: u32 },
+}
+
+/// Breaker state for every hook an executor has run.
+#[derive(Debug, Default)]
+pub(crate) struct CircuitBreakers {
+    config: BreakerConfig,
+    states: Mutex<HashMap<Uuid, State>>,
+    events: Option<EventBus>,
+}
+
+impl CircuitBreakers {
+    /// Breakers with the given tuning.
+    #[must_use]
+    pub(crate) fn new(config: BreakerConfig) -> Self {
+        Self {
+            config,
+            states: Mutex::new(HashMap::new()),
+            events: None,
+        }
+    }
+
+    /// Publish open/close transitions on `bus`.
+    #[must_use]
+    pub(crate) fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.events = Some(bus);
+        self
+    }
+
+    /// Whether `hook` may run now. `Err` carries the consecutive failure
+    /// count of an open breaker.
+    pub(crate) fn admit(&self, hook: &Hook) -> Result<(), u32> {
+        let mut states = self.lock();
+        let state = states
+            .entry(hook.id)
+            .or_insert(State::Closed { failures: 0 });
+        match *state {
+            State::Closed { .. } => Ok(()),
+            State::Open { since, failures } if since.elapsed() >= self.config.cooldown => {
+                info!(hook_id = %hook.id, "hook circuit half-open, retrying");
+                *state = State::HalfOpen { failures };
+                Ok(())
+            },
+            // One trial call at a time while half-open.
+            State::Open { failures, .. } | State::HalfOpen { failures } => Err(failures),
+        }
+    }
+
+    /// Record the outcome of a call that [`admit`](Self::admit) let through.
+    pub(crate) fn record(&self, hook: &Hook, success: bool) {
+        let transition = {
+            let mut states = self.lock();
+            let state = states
+                .entry(hook.id)
+                .or_insert(State::Closed { failures: 0 });
+            let (next, transition) = match (*state, success) {
+                (State::HalfOpen { .. }, true) => (State::Closed { failures: 0 }, Some(false)),
+                (_, true) => (State::Closed { failures: 0 }, None),
+                (State::HalfOpen { failures } | State::Open { failures, .. }, false) => (
+                    State::Open {
+                        since: Instant::now(),
+                        failures: failures.saturating_add(1),
+                    },
+                    None,
+                ),
+                (State::Closed { failures }, false) => {
+                    let failures = failures.saturating_add(1);
+                    if failures >= self.config.failure_threshold {
+                        (
+                            State::Open {
+                                since: Instant::now(),
+                                failures,
+                            },
+                            Some(true),
+                        )
+                    } else {
+                        (State::Closed { failures }, None)
+                    }
+                },
+            };
+            *state = next;
+            transition.map(|opened| (opened, failures_of(next)))
+        };
+
+        if let Some((opened, failures)) = transition {
+            self.announce(hook, opened, failures);
+        }
+    }
+
+    /// Whether the breaker for `hook` is currently open or half-open.
+    #[must_use]
+    pub(crate) fn is_open(&self, hook_id: Uuid) -> bool {
+        matches!(
+            self.lock().get(&hook_id),
+            Some(State::Open { .. } | State::HalfOpen { .. })
+        )
+    }
+
+    fn announce(&self, hook: &Hook, opened: bool, failures: u32) {
+        let name = if opened {
+            warn!(hook_id = %hook.id, hook_name = ?hook.name, failures, "hook circuit opened");
+            "hooks.circuit_opened"
+        } else {
+            info!(hook_id = %hook.id, hook_name = ?hook.name, "hook circuit closed");
+            "hooks.circuit_closed"
+        };
+        if let Some(bus) = &self.events {
+            bus.publish(AstridEvent::Custom {
+                metadata: EventMetadata::new("astrid-hooks"),
+                name: name.to_string(),
+                data: serde_json::json!({
+                    "hook_id": hook.id,
+                    "hook_name": hook.name,
+                    "consecutive_failures": failures,
+                }),
+            });
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, State>> {
+        // A poisoned map only loses breaker bookkeeping; keep going.
+        self.states
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+fn failures_of(state: State) -> u32 {
+    match state {
+        State::Closed { failures }
+        | State::Open { failures, .. }
+        | State::HalfOpen { failures } => failures,
+    }
+}
@@ -4,7 +4,8 @@ use std::path::{Path, PathBuf};
 use thiserror::Error;
 use tracing::{debug, info, warn};
 
-use crate::hook::Hook;
+use crate::hook::{Hook, HookHandler};
+use crate::trust::{HookTrustStore, TrustPrompt};
 
 /// Errors that can occur during hook discovery.
 #[derive(Debug, Error)]
@@ -43,29 +44,15 @@ pub(crate) type DiscoveryResult<T> = Result<T, DiscoveryError>;
 /// Standard hook file names.
 pub(crate) const HOOK_FILE_NAMES: &[&str] = &["HOOK.toml", "hook.toml", "hooks.toml"];
 
-/// Discover hooks from standard locations.
+/// Discover hooks from trusted locations.
 ///
-/// This function looks for hooks in:
-/// 1. `.astrid/hooks/` in the current directory (workspace-level)
-/// 2. Any additional paths provided in `extra_paths`
-///
-/// Callers should pass the user-level hooks directory (e.g.
-/// `AstridHome::hooks_dir()`) via `extra_paths` rather than relying
-/// on hard-coded platform paths.
+/// Callers pass the user-level hooks directory (e.g.
+/// `AstridHome::hooks_dir()`) and any configured extra directories in
+/// `extra_paths`. Workspace hooks are not loaded here: they go through
+/// [`discover_workspace_hooks`] and its trust prompt.
 pub(crate) fn discover_hooks(extra_paths: Option<&[PathBuf]>) -> Vec<Hook> {
     let mut hooks = Vec::new();
 
-    // Look in local .astrid/hooks directory
-    let local_hooks_dir = PathBuf::from(".astrid/hooks");
-    if local_hooks_dir.exists() {
-        info!(path = %local_hooks_dir.display(), "Discovering hooks from local directory");
-        match load_hooks_from_dir(&local_hooks_dir) {
-            Ok(found) => hooks.extend(found),
-            Err(e) => warn!(error = %e, "Failed to load hooks from local directory"),
-        }
-    }
-
-    // Look in extra paths
     if let Some(paths) = extra_paths {
         for path in paths {
             if path.exists() {
@@ -82,6 +69,57 @@ pub(crate) fn discover_hooks(extra_paths: Option<&[PathBuf]>) -> Vec<Hook> {
     hooks
 }
 
+/// Discover hooks committed to a workspace in `.astrid/hooks/`.
+///
+/// Loads every `*.toml` file in that directory, and hook files in its
+/// subdirectories. Each one must be trusted through `store` first; new or
+/// changed files are put to `prompt`. See [`crate::trust`].
+pub(crate) fn discover_workspace_hooks(
+    workspace_root: &Path,
+    store: &mut HookTrustStore,
+    prompt: &dyn TrustPrompt,
+) -> Vec<Hook> {
+    let dir = workspace_hooks_dir(workspace_root);
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    info!(path = %dir.display(), "Discovering workspace hooks");
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.is_dir() {
+                HOOK_FILE_NAMES
+                    .iter()
+                    .map(|name| path.join(name))
+                    .find(|file| file.is_file())
+            } else {
+                (path.extension().is_some_and(|ext| ext == "toml")).then_some(path)
+            }
+        })
+        .collect();
+    files.sort();
+
+    let mut hooks = Vec::new();
+    for file in files {
+        let hook = match load_hook(&file) {
+            Ok(hook) => hook,
+            Err(e) => {
+                warn!(path = %file.display(), error = %e, "Failed to load workspace hook");
+                continue;
+            },
+        };
+        match store.admit(workspace_root, &file, hook, prompt) {
+            Ok(Some(hook)) => hooks.push(hook),
+            Ok(None) => debug!(path = %file.display(), "Workspace hook not trusted, skipping"),
+            Err(e) => warn!(path = %file.display(), error = %e, "Failed to check hook trust"),
+        }
+    }
+
+    info!(count = hooks.len(), "Discovered workspace hooks");
+    hooks
+}
+
 /// Load hooks from a directory.
 ///
 /// This function looks for:
@@ -158,11 +196,19 @@ pub(crate) fn load_hook(path: &Path) -> DiscoveryResult<Hook> {
         message: e.to_string(),
     })?;
 
-    let hook: Hook = toml::from_str(&content).map_err(|e| DiscoveryError::Parse {
+    let mut hook: Hook = toml::from_str(&content).map_err(|e| DiscoveryError::Parse {
         path: path.to_path_buf(),
         message: e.to_string(),
     })?;
 
+    // A WASM module shipped next to its hook file is referenced relative to it.
+    if let HookHandler::Wasm { module_path, .. } = &mut hook.handler
+        && Path::new(module_path.as_str()).is_relative()
+        && let Some(dir) = path.parent()
+    {
+        *module_path = dir.join(&*module_path).to_string_lossy().into_owned();
+    }
+
     Ok(hook)
 }
 
@@ -194,7 +240,7 @@ pub(crate) fn workspace_hooks_dir(workspace_root: &Path) -> PathBuf {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::hook::{HookEvent, HookHandler};
+    use crate::hook::HookEvent;
     use tempfile::TempDir;
 
     #[test]
@@ -237,11 +283,71 @@ mod tests {
         assert_eq!(hooks[0].name, Some("sub-hook".to_string()));
     }
 
+    #[test]
+    fn test_wasm_module_path_resolves_against_hook_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let hook_path = temp_dir.path().join("HOOK.toml");
+        std::fs::write(
+            &hook_path,
+            "id = \"00000000-0000-0000-0000-000000000001\"\n\
+             event = \"pre_tool_call\"\n\
+             [handler]\ntype = \"wasm\"\nmodule_path = \"guard.wasm\"\n",
+        )
+        .unwrap();
+
+        let hook = load_hook(&hook_path).unwrap();
+
+        let HookHandler::Wasm { module_path, .. } = hook.handler else {
+            panic!("expected a WASM handler");
+        };
+        assert_eq!(
+            PathBuf::from(module_path),
+            temp_dir.path().join("guard.wasm")
+        );
+    }
+
     #[test]
     fn test_discover_hooks_empty() {
-        // Should not panic even with no hooks
-        let hooks = discover_hooks(None);
-        // May find system hooks, so just check it doesn't panic
-        let _ = hooks;
+        assert!(discover_hooks(None).is_empty());
+    }
+
+    #[test]
+    fn test_discover_workspace_hooks_requires_trust() {
+        use crate::trust::{TrustDecision, TrustRequest};
+
+        struct TrustNamed(&'static str);
+        impl TrustPrompt for TrustNamed {
+            fn ask(&self, request: &TrustRequest<'_>) -> TrustDecision {
+                if request.hook.name.as_deref() == Some(self.0) {
+                    TrustDecision::Trust {
+                        allow_mutations: false,
+                    }
+                } else {
+                    TrustDecision::Deny
+                }
+            }
+        }
+
+        let workspace = TempDir::new().unwrap();
+        let home = TempDir::new().unwrap();
+        let dir = workspace_hooks_dir(workspace.path());
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        for (file, name) in [
+            ("lint.toml", "lint"),
+            ("deploy.toml", "deploy"),
+            ("nested/HOOK.toml", "nested"),
+        ] {
+            let hook = Hook::new(HookEvent::PreToolCall)
+                .with_name(name)
+                .with_handler(HookHandler::command("echo"));
+            save_hook(&hook, &dir.join(file)).unwrap();
+        }
+        let mut store = HookTrustStore::open(home.path().join("hook-trust.json")).unwrap();
+
+        let hooks = discover_workspace_hooks(workspace.path(), &mut store, &TrustNamed("lint"));
+
+        assert_eq!(hooks.len(), 1);
+        assert_eq!(hooks[0].name.as_deref(), Some("lint"));
+        assert!(hooks[0].observe_only);
     }
 }
@@ -5,18 +5,22 @@ use std::time::Duration;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use crate::handler::{AgentHandler, CommandHandler, HttpHandler, WasmHandler};
+use crate::breaker::CircuitBreakers;
+use crate::handler::{AgentHandler, CommandHandler, HandlerError, HttpHandler, WasmHandler};
 use crate::hook::{FailAction, Hook, HookHandler, HookMatcher};
 use crate::result::{HookContext, HookExecution, HookExecutionResult, HookResult};
 
 /// Executes hooks using the appropriate handler.
+///
+/// Every execution is bounded by the hook's `timeout_secs`, and a hook that
+/// keeps failing is short-circuited by its [`CircuitBreakers`] entry.
 #[derive(Debug)]
-#[expect(clippy::struct_field_names)]
-pub(crate) struct HookExecutor {
+pub struct HookExecutor {
     command_handler: CommandHandler,
     http_handler: HttpHandler,
     wasm_handler: WasmHandler,
     agent_handler: AgentHandler,
+    breakers: CircuitBreakers,
 }
 
 impl Default for HookExecutor {
@@ -28,14 +32,8 @@ impl Default for HookExecutor {
 impl HookExecutor {
     /// Create a new hook executor with default workspace root (current directory).
     #[must_use]
-    pub(crate) fn new() -> Self {
-        let workspace_root = std::env::current_dir().unwrap_or_default();
-        Self {
-            command_handler: CommandHandler::new(),
-            http_handler: HttpHandler::new(),
-            wasm_handler: WasmHandler::new(workspace_root),
-            agent_handler: AgentHandler::new(),
-        }
+    pub fn new() -> Self {
+        Self::with_workspace_root(std::env::current_dir().unwrap_or_default())
     }
 
     /// Create a new hook executor with a specific workspace root for WASM handlers.
@@ -46,13 +44,52 @@ impl HookExecutor {
             http_handler: HttpHandler::new(),
             wasm_handler: WasmHandler::new(workspace_root),
             agent_handler: AgentHandler::new(),
+            breakers: CircuitBreakers::default(),
         }
     }
 
+    /// Replace the circuit breakers (e.g. to tune thresholds or attach an
+    /// event bus).
+    #[must_use]
+    pub(crate) fn with_breakers(mut self, breakers: CircuitBreakers) -> Self {
+        self.breakers = breakers;
+        self
+    }
+
+    /// Replace the agent handler, e.g. to attach a judge model.
+    #[must_use]
+    pub fn with_agent_handler(mut self, handler: AgentHandler) -> Self {
+        self.agent_handler = handler;
+        self
+    }
+
+    /// The executor's circuit breakers.
+    #[must_use]
+    pub(crate) fn breakers(&self) -> &CircuitBreakers {
+        &self.breakers
+    }
+
     /// Execute a single hook.
     pub(crate) async fn execute(&self, hook: &Hook, context: &HookContext) -> HookExecution {
         let started_at = Utc::now();
         let timeout = Duration::from_secs(hook.timeout_secs);
+        let finish = |result: HookExecutionResult| {
+            let completed_at = Utc::now();
+            HookExecution {
+                hook_id: hook.id,
+                invocation_id: context.invocation_id,
+                started_at,
+                completed_at,
+                duration_ms: u64::try_from(
+                    completed_at
+                        .signed_duration_since(started_at)
+                        .num_milliseconds(),
+                )
+                .unwrap_or(0),
+                fail_action: hook.fail_action,
+                result,
+            }
+        };
 
         debug!(
             hook_id = %hook.id,
@@ -63,36 +100,77 @@ impl HookExecutor {
 
         // Check if hook should be skipped
         if !hook.enabled {
-            return HookExecution {
-                hook_id: hook.id,
-                invocation_id: context.invocation_id,
-                started_at,
-                completed_at: Utc::now(),
-                duration_ms: 0,
-                result: HookExecutionResult::Skipped {
-                    reason: "hook is disabled".to_string(),
-                },
-            };
+            return finish(HookExecutionResult::Skipped {
+                reason: "hook is disabled".to_string(),
+            });
         }
 
         // Check matcher
         if let Some(ref matcher) = hook.matcher
             && !matches_context(matcher, context)
         {
-            return HookExecution {
-                hook_id: hook.id,
-                invocation_id: context.invocation_id,
-                started_at,
-                completed_at: Utc::now(),
-                duration_ms: 0,
-                result: HookExecutionResult::Skipped {
-                    reason: "matcher did not match".to_string(),
-                },
-            };
+            return finish(HookExecutionResult::Skipped {
+                reason: "matcher did not match".to_string(),
+            });
+        }
+
+        if let Err(consecutive_failures) = self.breakers.admit(hook) {
+            debug!(hook_id = %hook.id, "Hook circuit open, not running");
+            return finish(HookExecutionResult::CircuitOpen {
+                consecutive_failures,
+            });
         }
 
-        // Execute the appropriate handler
-        let result = match &hook.handler {
+        // Handlers enforce the timeout themselves; this is the backstop for
+        // ones that overrun it.
+        let result = tokio::time::timeout(timeout, self.dispatch(hook, context, timeout))
+            .await
+            .unwrap_or(Err(HandlerError::Timeout(timeout)));
+
+        let mut execution_result = match result {
+            Ok(result) => result,
+            Err(HandlerError::Timeout(elapsed)) => HookExecutionResult::Timeout {
+                timeout_secs: elapsed.as_secs(),
+            },
+            Err(e) => HookExecutionResult::Failure {
+                error: e.to_string(),
+                stderr: None,
+            },
+        };
+        if hook.observe_only
+            && let HookExecutionResult::Success { result, .. } = &mut execution_result
+            && matches!(result, HookResult::ContinueWith { .. })
+        {
+            warn!(hook_id = %hook.id, "Observe-only hook returned modifications, discarding");
+            *result = HookResult::Continue;
+        }
+        self.breakers.record(hook, !execution_result.is_failure());
+
+        let execution = finish(execution_result);
+        match execution.result.failure_reason() {
+            None => info!(
+                hook_id = %hook.id,
+                duration_ms = execution.duration_ms,
+                "Hook executed successfully"
+            ),
+            Some(reason) => error!(
+                hook_id = %hook.id,
+                duration_ms = execution.duration_ms,
+                error = %reason,
+                "Hook execution failed"
+            ),
+        }
+        execution
+    }
+
+    /// Run `hook` with the handler for its kind.
+    async fn dispatch(
+        &self,
+        hook: &Hook,
+        context: &HookContext,
+        timeout: Duration,
+    ) -> Result<HookExecutionResult, HandlerError> {
+        match &hook.handler {
             HookHandler::Command { .. } => {
                 self.command_handler
                     .execute(&hook.handler, context, timeout)
@@ -113,47 +191,15 @@ impl HookExecutor {
                     .execute(&hook.handler, context, timeout)
                     .await
             },
-        };
-
-        let completed_at = Utc::now();
-        #[expect(clippy::cast_sign_loss)]
-        // Safety: chrono DateTime subtraction cannot overflow for reasonable time values
-        #[expect(clippy::arithmetic_side_effects)]
-        let duration_ms = (completed_at - started_at).num_milliseconds().max(0) as u64;
-
-        let execution_result = match result {
-            Ok(result) => {
-                info!(
-                    hook_id = %hook.id,
-                    duration_ms = duration_ms,
-                    "Hook executed successfully"
-                );
-                result
-            },
-            Err(e) => {
-                error!(
-                    hook_id = %hook.id,
-                    error = %e,
-                    "Hook execution failed"
-                );
-                HookExecutionResult::Failure {
-                    error: e.to_string(),
-                    stderr: None,
-                }
-            },
-        };
-
-        HookExecution {
-            hook_id: hook.id,
-            invocation_id: context.invocation_id,
-            started_at,
-            completed_at,
-            duration_ms,
-            result: execution_result,
         }
     }
 
-    /// Execute multiple hooks in sequence.
+    /// Execute hooks in priority order.
+    ///
+    /// Consecutive observe-only hooks run concurrently; the others run one
+    /// at a time and see the results of those before them. The chain stops
+    /// at the first `Block` result or at a failure under
+    /// [`FailAction::Block`].
     pub(crate) async fn execute_all(
         &self,
         hooks: &[Hook],
@@ -161,51 +207,32 @@ impl HookExecutor {
     ) -> Vec<HookExecution> {
         let mut executions = Vec::with_capacity(hooks.len());
 
-        for hook in hooks {
-            let execution = self.execute(hook, &context).await;
-
-            // Add result to context for next hook
-            if let Some(result) = execution.result.hook_result() {
-                context.add_previous_result(result.clone());
-            }
-
-            // Handle fail action
-            if !execution.result.is_success() {
-                match hook.fail_action {
-                    FailAction::Block => {
-                        warn!(
-                            hook_id = %hook.id,
-                            "Hook failed with Block action, stopping chain"
-                        );
-                        executions.push(execution);
-                        break;
-                    },
-                    FailAction::Warn => {
-                        warn!(
-                            hook_id = %hook.id,
-                            "Hook failed with Warn action, continuing"
-                        );
-                    },
-                    FailAction::Ignore => {
-                        debug!(
-                            hook_id = %hook.id,
-                            "Hook failed with Ignore action, continuing silently"
-                        );
-                    },
+        let mut remaining = hooks;
+        while let Some(first) = remaining.first() {
+            let batch_len = if first.observe_only {
+                remaining.iter().take_while(|h| h.observe_only).count()
+            } else {
+                1
+            };
+            let (batch, rest) = remaining.split_at(batch_len);
+            remaining = rest;
+
+            let results =
+                futures::future::join_all(batch.iter().map(|hook| self.execute(hook, &context)))
+                    .await;
+
+            let mut stop = false;
+            for (hook, execution) in batch.iter().zip(results) {
+                // Add result to context for next hook
+                if let Some(result) = execution.result.hook_result() {
+                    context.add_previous_result(result.clone());
                 }
-            }
-
-            // Check if any hook blocked
-            if let Some(HookResult::Block { .. }) = execution.result.hook_result() {
-                info!(
-                    hook_id = %hook.id,
-                    "Hook returned Block result, stopping chain"
-                );
+                stop |= should_stop(hook, &execution);
                 executions.push(execution);
+            }
+            if stop {
                 break;
             }
-
-            executions.push(execution);
         }
 
         executions
@@ -214,7 +241,7 @@ impl HookExecutor {
     /// Combine multiple hook results into a single result.
     ///
     /// Rules:
-    /// - Any Block result → Block
+    /// - Any Block result, or failure under [`FailAction::Block`] → Block
     /// - Any Ask result → Ask (if no Block)
     /// - `ContinueWith` modifications are merged
     /// - Otherwise → Continue
@@ -224,6 +251,13 @@ impl HookExecutor {
         let mut ask_question = None;
 
         for execution in executions {
+            if execution.fail_action == FailAction::Block
+                && let Some(reason) = execution.result.failure_reason()
+            {
+                return HookResult::Block {
+                    reason: format!("hook {} failed: {reason}", execution.hook_id),
+                };
+            }
             match execution.result.hook_result() {
                 Some(HookResult::Block { reason }) => {
                     return HookResult::Block {
@@ -256,6 +290,44 @@ impl HookExecutor {
     }
 }
 
+/// Whether the chain stops after this execution, logging why.
+fn should_stop(hook: &Hook, execution: &HookExecution) -> bool {
+    if let Some(reason) = execution.result.failure_reason() {
+        match hook.fail_action {
+            FailAction::Block => {
+                warn!(
+                    hook_id = %hook.id,
+                    error = %reason,
+                    "Hook failed with Block action, stopping chain"
+                );
+                return true;
+            },
+            FailAction::Warn => {
+                warn!(
+                    hook_id = %hook.id,
+                    error = %reason,
+                    "Hook failed with Warn action, continuing"
+                );
+            },
+            FailAction::Ignore => {
+                debug!(
+                    hook_id = %hook.id,
+                    "Hook failed with Ignore action, continuing silently"
+                );
+            },
+        }
+    }
+
+    if let Some(HookResult::Block { .. }) = execution.result.hook_result() {
+        info!(
+            hook_id = %hook.id,
+            "Hook returned Block result, stopping chain"
+        );
+        return true;
+    }
+    false
+}
+
 /// Check if a matcher matches the context.
 fn matches_context(matcher: &HookMatcher, context: &HookContext) -> bool {
     match matcher {
@@ -331,6 +403,7 @@ impl HookExecutionBuilder {
             started_at: now,
             completed_at: now,
             duration_ms: 0,
+            fail_action: FailAction::default(),
             result: self.result,
         }
     }
@@ -369,6 +442,7 @@ mod tests {
                 command: "echo".to_string(),
                 args: vec!["continue".to_string()],
                 env: std::collections::HashMap::default(),
+                env_passthrough: Vec::new(),
                 working_dir: None,
             })
             .with_timeout(5);
@@ -380,6 +454,183 @@ mod tests {
         assert!(execution.result.is_success());
     }
 
+    fn sh_hook(script: &str) -> Hook {
+        Hook::new(HookEvent::PreToolCall).with_handler(HookHandler::Command {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
+            working_dir: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_timeout_applies_block_policy() {
+        let executor = HookExecutor::new();
+        let hook = sh_hook("sleep 5")
+            .with_timeout(1)
+            .with_fail_action(FailAction::Block);
+        let context = HookContext::new(HookEvent::PreToolCall);
+
+        let executions = executor
+            .execute_all(&[hook, sh_hook("echo continue")], context)
+            .await;
+
+        // The chain stopped at the failed hook.
+        assert_eq!(executions.len(), 1);
+        assert!(matches!(
+            executions[0].result,
+            HookExecutionResult::Timeout { timeout_secs: 1 }
+        ));
+        assert!(executions[0].duration_ms >= 1000);
+        assert!(matches!(
+            HookExecutor::combine_results(&executions),
+            HookResult::Block { reason } if reason.contains("timed out after 1s")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_warn_policy_failure_does_not_block() {
+        let executor = HookExecutor::new();
+        let context = HookContext::new(HookEvent::PreToolCall);
+
+        let executions = executor
+            .execute_all(&[sh_hook("exit 3"), sh_hook("echo continue")], context)
+            .await;
+
+        assert_eq!(executions.len(), 2);
+        assert!(executions[0].result.is_failure());
+        assert!(matches!(
+            HookExecutor::combine_results(&executions),
+            HookResult::Continue
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_skipped_hook_is_not_a_failure() {
+        let executor = HookExecutor::new();
+        let disabled = sh_hook("echo continue")
+            .disabled()
+            .with_fail_action(FailAction::Block);
+        let context = HookContext::new(HookEvent::PreToolCall);
+
+        let executions = executor
+            .execute_all(&[disabled, sh_hook("echo continue")], context)
+            .await;
+
+        assert_eq!(executions.len(), 2);
+        assert!(matches!(
+            HookExecutor::combine_results(&executions),
+            HookResult::Continue
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_breaker_opens_and_recovers() {
+        let dir = tempfile::tempdir().unwrap();
+        let flag = dir.path().join("healthy");
+        let bus = astrid_events::EventBus::new();
+        let mut events = bus.subscribe();
+        let executor = HookExecutor::new().with_breakers(
+            CircuitBreakers::new(crate::breaker::BreakerConfig {
+                failure_threshold: 2,
+                cooldown: Duration::from_millis(200),
+            })
+            .with_event_bus(bus.clone()),
+        );
+        let hook = sh_hook(&format!("test -f {} && echo continue", flag.display()));
+        let context = HookContext::new(HookEvent::PreToolCall);
+
+        for _ in 0..2 {
+            let execution = executor.execute(&hook, &context).await;
+            assert!(matches!(
+                execution.result,
+                HookExecutionResult::Failure { .. }
+            ));
+        }
+        assert!(executor.breakers().is_open(hook.id));
+        let opened = events.recv().await.unwrap();
+        assert!(
+            matches!(&*opened, astrid_events::AstridEvent::Custom { name, .. } if name == "hooks.circuit_opened")
+        );
+
+        // Open: the handler is not run at all.
+        std::fs::write(&flag, "").unwrap();
+        let rejected = executor.execute(&hook, &context).await;
+        assert!(matches!(
+            rejected.result,
+            HookExecutionResult::CircuitOpen {
+                consecutive_failures: 2
+            }
+        ));
+
+        // After the cooldown a half-open trial succeeds and closes it.
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let retried = executor.execute(&hook, &context).await;
+        assert!(retried.result.is_success());
+        assert!(!executor.breakers().is_open(hook.id));
+        let closed = events.recv().await.unwrap();
+        assert!(
+            matches!(&*closed, astrid_events::AstridEvent::Custom { name, .. } if name == "hooks.circuit_closed")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_half_open_trial_reopens() {
+        let executor = HookExecutor::new().with_breakers(CircuitBreakers::new(
+            crate::breaker::BreakerConfig {
+                failure_threshold: 1,
+                cooldown: Duration::from_millis(100),
+            },
+        ));
+        let hook = sh_hook("exit 1");
+        let context = HookContext::new(HookEvent::PreToolCall);
+
+        executor.execute(&hook, &context).await;
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        let trial = executor.execute(&hook, &context).await;
+        assert!(matches!(trial.result, HookExecutionResult::Failure { .. }));
+
+        let rejected = executor.execute(&hook, &context).await;
+        assert!(matches!(
+            rejected.result,
+            HookExecutionResult::CircuitOpen {
+                consecutive_failures: 2
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_observe_only_hooks_run_concurrently() {
+        let executor = HookExecutor::new();
+        let hooks: Vec<Hook> = (0..3)
+            .map(|_| sh_hook("sleep 1; echo continue").observe_only())
+            .collect();
+        let context = HookContext::new(HookEvent::PreToolCall);
+
+        let started = std::time::Instant::now();
+        let executions = executor.execute_all(&hooks, context).await;
+
+        assert_eq!(executions.len(), 3);
+        assert!(executions.iter().all(|e| e.result.is_success()));
+        assert!(started.elapsed() < Duration::from_millis(2500));
+    }
+
+    #[tokio::test]
+    async fn test_observe_only_modifications_are_discarded() {
+        let executor = HookExecutor::new();
+        let hook =
+            sh_hook(r#"echo '{"action":"continue_with","modifications":{"x":1}}'"#).observe_only();
+        let context = HookContext::new(HookEvent::PreToolCall);
+
+        let execution = executor.execute(&hook, &context).await;
+
+        assert!(matches!(
+            execution.result.hook_result(),
+            Some(HookResult::Continue)
+        ));
+    }
+
     #[test]
     fn test_combine_results_continue() {
         let executions = vec![
@@ -1,45 +1,156 @@
-//! Agent hook handler (stubbed).
+//! Agent hook handler - asks an LLM judge for a decision.
 //!
-//! This module provides the interface for LLM-based hook handlers.
-//! TODO: implement real agent handler when the LLM integration is more mature.
+//! The judge gets the hook's instruction and the (redacted) event context,
+//! and must answer with a JSON object:
+//!
+//! ```json
+//! {"decision": "allow" | "deny" | "modify", "reason": "...", "modifications": {}}
+//! ```
+//!
+//! `allow` maps to `Continue`, `deny` to `Block`, and `modify` to
+//! `ContinueWith`, whose modifications go through the mutation contract
+//! like any other hook's. When the judge times out, errors, costs more than
+//! the hook's `max_cost_usd`, or answers outside the schema, the hook's
+//! `fallback` decision applies.
+//!
+//! Every decision, fallbacks included, is written to the audit log as a
+//! `JudgeDecision` entry with the judge's stated reason, and published as a
+//! `hooks.judge_decision` event.
 
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::warn;
 
-use super::{HandlerError, HandlerResult};
-use crate::hook::HookHandler;
-use crate::result::{HookContext, HookExecutionResult};
+use astrid_audit::{AuditAction, AuditLog, AuditOutcome, AuthorizationProof};
+use astrid_core::SessionId;
+use astrid_events::{AstridEvent, EventBus, EventMetadata};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
 
-/// Handler for LLM-based agents (stubbed).
+use super::{HandlerError, HandlerResult, template};
+use crate::hook::{HookHandler, JudgeFallback};
+use crate::result::{HookContext, HookExecutionResult, HookResult};
+
+/// A model the agent handler can ask for a judgement.
 ///
-/// This handler will invoke an LLM to process hook events
-/// TODO: implement real agent handler. For now, it returns a stub response.
+/// The runtime implements this over its LLM provider. A request without a
+/// model should be routed to the runtime's cheap model.
+#[async_trait]
+pub trait JudgeModel: fmt::Debug + Send + Sync {
+    /// Run one completion.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the failure if the model could not answer.
+    async fn complete(&self, request: JudgeRequest) -> Result<JudgeReply, String>;
+}
+
+/// One judgement request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JudgeRequest {
+    /// Model requested by the hook, if any.
+    pub model: Option<String>,
+    /// System prompt describing the decision schema.
+    pub system: String,
+    /// Rendered instruction followed by the event context.
+    pub prompt: String,
+    /// Maximum tokens for the reply.
+    pub max_tokens: u32,
+    /// Sampling temperature.
+    pub temperature: f64,
+    /// Cost cap for this request, in USD.
+    pub max_cost_usd: Option<f64>,
+}
+
+/// A judge's raw reply.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JudgeReply {
+    /// Model that answered.
+    pub model: String,
+    /// Reply text, expected to hold the decision JSON.
+    pub text: String,
+    /// What the call cost, in USD.
+    pub cost_usd: f64,
+}
+
+/// Decision in the judge's output schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Decision {
+    Allow,
+    Deny,
+    Modify,
+}
+
+/// The judge's answer, parsed.
+#[derive(Debug, Deserialize)]
+struct Verdict {
+    decision: Decision,
+    reason: String,
+    #[serde(default)]
+    modifications: HashMap<String, serde_json::Value>,
+}
+
+/// Handler for LLM judge hooks.
 #[derive(Debug, Clone, Default)]
-pub(crate) struct AgentHandler;
+pub struct AgentHandler {
+    config: AgentConfig,
+    model: Option<Arc<dyn JudgeModel>>,
+    events: Option<EventBus>,
+    /// Audit log for decisions, and the session used when the hook
+    /// context carries none.
+    audit: Option<(Arc<AuditLog>, SessionId)>,
+}
 
 impl AgentHandler {
-    /// Create a new agent handler.
+    /// Create an agent handler with no judge model attached. Agent hooks
+    /// are skipped until one is.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use `model` to answer judgements.
+    #[must_use]
+    pub fn with_model(mut self, model: Arc<dyn JudgeModel>) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Publish judge decisions on `bus`.
     #[must_use]
-    pub(crate) fn new() -> Self {
-        Self
+    pub fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.events = Some(bus);
+        self
     }
 
-    /// Execute an agent handler (stubbed).
+    /// Record judge decisions in `log`, under the hook context's session or
+    /// `session_id` when the context has none.
+    #[must_use]
+    pub fn with_audit_log(mut self, log: Arc<AuditLog>, session_id: SessionId) -> Self {
+        self.audit = Some((log, session_id));
+        self
+    }
+
+    /// Execute an agent handler.
     ///
     /// # Errors
     ///
     /// Returns an error if the handler configuration is invalid.
-    #[expect(clippy::unused_async)]
     pub(crate) async fn execute(
         &self,
         handler: &HookHandler,
-        _context: &HookContext,
-        _timeout: Duration,
+        context: &HookContext,
+        timeout: Duration,
     ) -> HandlerResult<HookExecutionResult> {
         let HookHandler::Agent {
             prompt_template,
             model,
             max_tokens,
+            max_cost_usd,
+            fallback,
         } = handler
         else {
             return Err(HandlerError::InvalidConfiguration(
@@ -47,53 +158,219 @@ impl AgentHandler {
             ));
         };
 
-        warn!(
-            prompt_template = %prompt_template,
-            model = ?model,
-            max_tokens = ?max_tokens,
-            "Agent handler is stubbed - not yet implemented"
-        );
+        let Some(judge) = &self.model else {
+            return Ok(HookExecutionResult::Skipped {
+                reason: "no judge model is configured for agent hooks".to_string(),
+            });
+        };
 
-        // For now, return a skipped result
-        Ok(HookExecutionResult::Skipped {
-            reason: format!(
-                "Agent handlers are not yet implemented (model: {})",
-                model.as_deref().unwrap_or("default")
+        let request = JudgeRequest {
+            model: model.clone(),
+            system: self.config.system_prompt.clone(),
+            prompt: format!(
+                "{}\n\nEvent:\n{}",
+                template::render(prompt_template, context),
+                context.to_json()
             ),
+            max_tokens: max_tokens.unwrap_or(self.config.max_tokens),
+            temperature: self.config.temperature,
+            max_cost_usd: *max_cost_usd,
+        };
+        debug!(model = ?model, "Asking judge");
+
+        let reply = match tokio::time::timeout(timeout, judge.complete(request)).await {
+            Ok(Ok(reply)) => reply,
+            Ok(Err(e)) => {
+                return Ok(self.fall_back(context, *fallback, &format!("judge failed: {e}"), None));
+            },
+            Err(_) => {
+                let why = format!("judge timed out after {}s", timeout.as_secs());
+                return Ok(self.fall_back(context, *fallback, &why, None));
+            },
+        };
+
+        if let Some(cap) = max_cost_usd
+            && reply.cost_usd > *cap
+        {
+            let why = format!(
+                "judge cost ${:.4} exceeds the ${cap:.4} cap",
+                reply.cost_usd
+            );
+            return Ok(self.fall_back(context, *fallback, &why, Some(&reply)));
+        }
+
+        let Some(verdict) = parse_verdict(&reply.text) else {
+            let why = "judge reply did not match the decision schema";
+            return Ok(self.fall_back(context, *fallback, why, Some(&reply)));
+        };
+
+        self.announce(
+            context,
+            verdict.decision,
+            &verdict.reason,
+            false,
+            Some(&reply),
+        );
+        let result = match verdict.decision {
+            Decision::Allow => HookResult::Continue,
+            Decision::Deny => HookResult::Block {
+                reason: verdict.reason,
+            },
+            Decision::Modify => HookResult::ContinueWith {
+                modifications: verdict.modifications,
+            },
+        };
+        Ok(HookExecutionResult::Success {
+            result,
+            stdout: Some(reply.text),
         })
     }
 
-    /// Check if the agent runtime is available.
-    ///
-    /// Always returns `false` until the agent handler is implemented.
-    #[must_use]
-    pub(crate) fn is_available() -> bool {
-        false
+    /// Apply the hook's fallback decision.
+    fn fall_back(
+        &self,
+        context: &HookContext,
+        fallback: JudgeFallback,
+        why: &str,
+        reply: Option<&JudgeReply>,
+    ) -> HookExecutionResult {
+        warn!(reason = %why, fallback = ?fallback, "Agent hook falling back");
+        let (decision, result) = match fallback {
+            JudgeFallback::Allow => (Decision::Allow, HookResult::Continue),
+            JudgeFallback::Deny => (
+                Decision::Deny,
+                HookResult::Block {
+                    reason: why.to_string(),
+                },
+            ),
+        };
+        self.announce(context, decision, why, true, reply);
+        HookExecutionResult::Success {
+            result,
+            stdout: reply.map(|reply| reply.text.clone()),
+        }
+    }
+
+    fn announce(
+        &self,
+        context: &HookContext,
+        decision: Decision,
+        reason: &str,
+        fallback: bool,
+        reply: Option<&JudgeReply>,
+    ) {
+        info!(
+            invocation_id = %context.invocation_id,
+            decision = ?decision,
+            fallback,
+            reason,
+            "Judge decision"
+        );
+        self.audit(context, decision, reason, fallback, reply);
+        if let Some(bus) = &self.events {
+            bus.publish(AstridEvent::Custom {
+                metadata: EventMetadata::new("astrid-hooks"),
+                name: "hooks.judge_decision".to_string(),
+                data: serde_json::json!({
+                    "invocation_id": context.invocation_id,
+                    "event": context.event,
+                    "decision": decision,
+                    "reason": reason,
+                    "fallback": fallback,
+                    "model": reply.map(|reply| reply.model.as_str()),
+                    "cost_usd": reply.map(|reply| reply.cost_usd),
+                }),
+            });
+        }
     }
 }
 
+impl AgentHandler {
+    fn audit(
+        &self,
+        context: &HookContext,
+        decision: Decision,
+        reason: &str,
+        fallback: bool,
+        reply: Option<&JudgeReply>,
+    ) {
+        let Some((log, default_session)) = &self.audit else {
+            return;
+        };
+        let session_id = context
+            .session_id
+            .map_or_else(|| default_session.clone(), SessionId);
+        let (authorization, outcome) = if decision == Decision::Deny {
+            (
+                AuthorizationProof::Denied {
+                    reason: reason.to_string(),
+                },
+                AuditOutcome::failure(reason),
+            )
+        } else {
+            (
+                AuthorizationProof::System {
+                    reason: "agent hook judgement".to_string(),
+                },
+                AuditOutcome::success(),
+            )
+        };
+        let action = AuditAction::JudgeDecision {
+            event: context.event.to_string(),
+            decision: decision.as_str().to_string(),
+            reason: reason.to_string(),
+            fallback,
+            model: reply.map(|reply| reply.model.clone()),
+        };
+        if let Err(e) = log.append(session_id, action, authorization, outcome) {
+            warn!(
+                invocation_id = %context.invocation_id,
+                error = %e,
+                "Failed to persist judge decision audit entry"
+            );
+        }
+    }
+}
+
+impl Decision {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+            Self::Modify => "modify",
+        }
+    }
+}
+
+/// Parse the decision object out of a reply, tolerating prose or a code
+/// fence around it.
+fn parse_verdict(text: &str) -> Option<Verdict> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    serde_json::from_str(text.get(start..=end)?).ok()
+}
+
 /// Configuration for agent execution.
 #[derive(Debug, Clone)]
 pub(crate) struct AgentConfig {
-    /// Default model to use.
-    pub default_model: String,
     /// Maximum tokens for responses.
     pub max_tokens: u32,
     /// Temperature for sampling.
     pub temperature: f64,
-    /// System prompt prefix.
+    /// System prompt describing the decision schema.
     pub system_prompt: String,
 }
 
 impl Default for AgentConfig {
     fn default() -> Self {
         Self {
-            default_model: "claude-3-haiku".to_string(),
             max_tokens: 1024,
             temperature: 0.0,
-            system_prompt: "You are a hook handler for the Astrid agent runtime. \
-                Analyze the event and return a JSON response with an 'action' field \
-                (continue, block, or ask) and appropriate additional fields."
+            system_prompt: "You are a policy judge for the Astrid agent runtime. \
+                Decide whether the operation in the event may proceed. Answer with \
+                only a JSON object: {\"decision\": \"allow\" | \"deny\" | \"modify\", \
+                \"reason\": string, \"modifications\": object}. Include \
+                \"modifications\" only for \"modify\"."
                 .to_string(),
         }
     }
@@ -101,36 +378,258 @@ impl Default for AgentConfig {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
     use super::*;
     use crate::hook::HookEvent;
 
-    #[tokio::test]
-    async fn test_agent_handler_stubbed() {
-        let handler = AgentHandler::new();
-        let hook_handler = HookHandler::Agent {
-            prompt_template: "Analyze this event: {{event}}".to_string(),
-            model: Some("claude-3-haiku".to_string()),
-            max_tokens: Some(512),
+    /// Judge that replays scripted replies and records requests.
+    #[derive(Debug, Default)]
+    struct ScriptedJudge {
+        replies: Mutex<VecDeque<Result<JudgeReply, String>>>,
+        requests: Mutex<Vec<JudgeRequest>>,
+        delay: Option<Duration>,
+    }
+
+    impl ScriptedJudge {
+        fn replying(text: &str, cost_usd: f64) -> Arc<Self> {
+            Arc::new(Self {
+                replies: Mutex::new(VecDeque::from([Ok(JudgeReply {
+                    model: "cheap".to_string(),
+                    text: text.to_string(),
+                    cost_usd,
+                })])),
+                ..Self::default()
+            })
+        }
+    }
+
+    #[async_trait]
+    impl JudgeModel for ScriptedJudge {
+        async fn complete(&self, request: JudgeRequest) -> Result<JudgeReply, String> {
+            self.requests.lock().unwrap().push(request);
+            if let Some(delay) = self.delay {
+                tokio::time::sleep(delay).await;
+            }
+            self.replies
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or_else(|| Err("script exhausted".to_string()))
+        }
+    }
+
+    fn judge_hook(fallback: JudgeFallback, max_cost_usd: Option<f64>) -> HookHandler {
+        HookHandler::Agent {
+            prompt_template: "May {{tool_name}} run?".to_string(),
+            model: None,
+            max_tokens: None,
+            max_cost_usd,
+            fallback,
+        }
+    }
+
+    fn context() -> HookContext {
+        HookContext::new(HookEvent::PreToolCall)
+            .with_data("tool_name", serde_json::json!("write_file"))
+            .with_sensitive_data("token", serde_json::json!("sk-secret"))
+    }
+
+    async fn run(judge: Arc<ScriptedJudge>, handler: &HookHandler) -> HookResult {
+        let result = AgentHandler::new()
+            .with_model(judge)
+            .execute(handler, &context(), Duration::from_secs(1))
+            .await
+            .unwrap();
+        let HookExecutionResult::Success { result, .. } = result else {
+            panic!("expected success, got {result:?}");
         };
-        let context = HookContext::new(HookEvent::PreToolCall);
+        result
+    }
 
+    #[tokio::test]
+    async fn allow_continues() {
+        let judge = ScriptedJudge::replying(r#"{"decision":"allow","reason":"read only"}"#, 0.0);
+        let result = run(judge.clone(), &judge_hook(JudgeFallback::Deny, None)).await;
+
+        assert!(matches!(result, HookResult::Continue));
+        let requests = judge.requests.lock().unwrap();
+        assert!(requests[0].prompt.starts_with("May write_file run?"));
+        assert!(!requests[0].prompt.contains("sk-secret"));
+        assert_eq!(requests[0].model, None);
+    }
+
+    #[tokio::test]
+    async fn deny_blocks_with_reason() {
+        let judge = ScriptedJudge::replying(
+            "Sure.\n```json\n{\"decision\":\"deny\",\"reason\":\"writes to /etc\"}\n```",
+            0.0,
+        );
+        let result = run(judge, &judge_hook(JudgeFallback::Allow, None)).await;
+
+        assert!(matches!(result, HookResult::Block { reason } if reason == "writes to /etc"));
+    }
+
+    #[tokio::test]
+    async fn modify_feeds_the_mutation_contract() {
+        let judge = ScriptedJudge::replying(
+            r#"{"decision":"modify","reason":"stay in workspace","modifications":{"arguments":{"path":"./out.txt"}}}"#,
+            0.0,
+        );
+        let handler = AgentHandler::new().with_model(judge);
         let result = handler
-            .execute(&hook_handler, &context, Duration::from_secs(30))
+            .execute(
+                &judge_hook(JudgeFallback::Deny, None),
+                &context(),
+                Duration::from_secs(1),
+            )
             .await
             .unwrap();
+        let execution = crate::result::HookExecution {
+            hook_id: uuid::Uuid::new_v4(),
+            invocation_id: uuid::Uuid::new_v4(),
+            started_at: chrono::Utc::now(),
+            completed_at: chrono::Utc::now(),
+            duration_ms: 0,
+            fail_action: crate::hook::FailAction::Warn,
+            result,
+        };
 
-        assert!(matches!(result, HookExecutionResult::Skipped { .. }));
+        let applied = crate::mutation::apply_pre_tool_call(
+            &[execution],
+            serde_json::json!({"path": "/etc/passwd"}),
+        );
+
+        assert_eq!(
+            applied.outcome,
+            crate::mutation::ToolCallDecision::Proceed {
+                arguments: serde_json::json!({"path": "./out.txt"})
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn unparseable_reply_uses_fallback() {
+        let judge = ScriptedJudge::replying("I think it's fine", 0.0);
+        let denied = run(judge, &judge_hook(JudgeFallback::Deny, None)).await;
+        assert!(matches!(denied, HookResult::Block { reason } if reason.contains("schema")));
+
+        let judge = ScriptedJudge::replying(r#"{"decision":"maybe","reason":"?"}"#, 0.0);
+        let allowed = run(judge, &judge_hook(JudgeFallback::Allow, None)).await;
+        assert!(matches!(allowed, HookResult::Continue));
+    }
+
+    #[tokio::test]
+    async fn timeout_uses_fallback() {
+        let judge = Arc::new(ScriptedJudge {
+            delay: Some(Duration::from_secs(5)),
+            ..ScriptedJudge::default()
+        });
+        let result = AgentHandler::new()
+            .with_model(judge)
+            .execute(
+                &judge_hook(JudgeFallback::Deny, None),
+                &context(),
+                Duration::from_millis(50),
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            result.hook_result(),
+            Some(HookResult::Block { reason }) if reason.contains("timed out")
+        ));
+    }
+
+    #[tokio::test]
+    async fn cost_cap_uses_fallback() {
+        let judge = ScriptedJudge::replying(r#"{"decision":"allow","reason":"ok"}"#, 0.05);
+        let result = run(judge.clone(), &judge_hook(JudgeFallback::Deny, Some(0.01))).await;
+
+        assert!(matches!(result, HookResult::Block { reason } if reason.contains("cap")));
+        assert_eq!(judge.requests.lock().unwrap()[0].max_cost_usd, Some(0.01));
+    }
+
+    #[tokio::test]
+    async fn decisions_are_published() {
+        let bus = EventBus::new();
+        let mut events = bus.subscribe();
+        let judge = ScriptedJudge::replying(r#"{"decision":"deny","reason":"no"}"#, 0.002);
+
+        AgentHandler::new()
+            .with_model(judge)
+            .with_event_bus(bus.clone())
+            .execute(
+                &judge_hook(JudgeFallback::Allow, None),
+                &context(),
+                Duration::from_secs(1),
+            )
+            .await
+            .unwrap();
+
+        let event = events.recv().await.unwrap();
+        let AstridEvent::Custom { name, data, .. } = &*event else {
+            panic!("unexpected event {event:?}");
+        };
+        assert_eq!(name, "hooks.judge_decision");
+        assert_eq!(data["decision"], "deny");
+        assert_eq!(data["reason"], "no");
+        assert_eq!(data["fallback"], false);
+        assert_eq!(data["model"], "cheap");
     }
 
-    #[test]
-    fn test_agent_not_available() {
-        assert!(!AgentHandler::is_available());
+    #[tokio::test]
+    async fn decisions_are_audited() {
+        let log = Arc::new(AuditLog::in_memory(astrid_crypto::KeyPair::generate()));
+        let session_id = SessionId::new();
+        let judge = ScriptedJudge::replying(r#"{"decision":"deny","reason":"no"}"#, 0.0);
+
+        AgentHandler::new()
+            .with_model(judge)
+            .with_audit_log(Arc::clone(&log), session_id.clone())
+            .execute(
+                &judge_hook(JudgeFallback::Allow, None),
+                &context(),
+                Duration::from_secs(1),
+            )
+            .await
+            .unwrap();
+
+        let entries = log.get_session_entries(&session_id).unwrap();
+        assert_eq!(entries.len(), 1);
+        let AuditAction::JudgeDecision {
+            decision,
+            reason,
+            fallback,
+            model,
+            ..
+        } = &entries[0].action
+        else {
+            panic!("unexpected action {:?}", entries[0].action);
+        };
+        assert_eq!(decision, "deny");
+        assert_eq!(reason, "no");
+        assert!(!fallback);
+        assert_eq!(model.as_deref(), Some("cheap"));
+        assert!(matches!(
+            entries[0].authorization,
+            AuthorizationProof::Denied { .. }
+        ));
+        assert!(log.verify_chain(&session_id).unwrap().valid);
     }
 
-    #[test]
-    fn test_agent_config_default() {
-        let config = AgentConfig::default();
-        assert_eq!(config.max_tokens, 1024);
-        assert!(config.temperature.abs() < f64::EPSILON);
+    #[tokio::test]
+    async fn skipped_without_model() {
+        let result = AgentHandler::new()
+            .execute(
+                &judge_hook(JudgeFallback::Deny, None),
+                &context(),
+                Duration::from_secs(1),
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(result, HookExecutionResult::Skipped { .. }));
     }
 }
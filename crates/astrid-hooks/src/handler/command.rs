@@ -6,7 +6,10 @@
 //! - Environment variable clearing (inherits only from allowlist)
 //! - PATH restriction to safe directories
 //! - Working directory isolation
+//! - Argument templates rendered without a shell (see [`super::template`])
+//! - Sensitive context values redacted from args, env and stdin
 
+use astrid_telemetry::Sensitive;
 use std::process::Stdio;
 use std::time::Duration;
 use tokio::io::AsyncWriteExt;
@@ -14,7 +17,7 @@ use tokio::process::Command;
 use tokio::time::timeout;
 use tracing::{debug, warn};
 
-use super::{HandlerError, HandlerResult, parse_hook_result};
+use super::{HandlerError, HandlerResult, parse_hook_result, template};
 use crate::hook::HookHandler;
 use crate::result::{HookContext, HookExecutionResult, HookResult};
 
@@ -81,6 +84,7 @@ impl CommandHandler {
         &self,
         cmd: &mut Command,
         custom_env: &std::collections::HashMap<String, String>,
+        passthrough: &[String],
         context: &HookContext,
     ) {
         if self.sandboxed {
@@ -109,6 +113,18 @@ impl CommandHandler {
             }
         }
 
+        if self.sandboxed {
+            for key in passthrough {
+                if is_blocked_hook_env(key) {
+                    warn!(key = %key, "Refusing to pass blocked env var to sandboxed hook");
+                    continue;
+                }
+                if let Ok(value) = std::env::var(key) {
+                    cmd.env(key, value);
+                }
+            }
+        }
+
         for (key, value) in custom_env {
             if self.sandboxed {
                 if ALLOWED_ENV_VARS.iter().any(|k| k.eq_ignore_ascii_case(key)) {
@@ -164,6 +180,7 @@ impl CommandHandler {
             command,
             args,
             env,
+            env_passthrough,
             working_dir,
         } = handler
         else {
@@ -174,9 +191,12 @@ impl CommandHandler {
 
         debug!(command = %command, args = ?args, sandboxed = %self.sandboxed, "Executing command hook");
 
+        // Templates render into whole arguments; nothing goes through a shell.
+        let args = template::render_args(command, args, context)?;
+
         // Build the command
         let mut cmd = Command::new(command);
-        cmd.args(args);
+        cmd.args(&args);
         cmd.stdin(Stdio::piped());
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
@@ -187,9 +207,9 @@ impl CommandHandler {
         }
 
         // Apply sandboxing, custom env vars, and context env vars.
-        self.apply_env(&mut cmd, env, context);
+        self.apply_env(&mut cmd, env, env_passthrough, context);
 
-        // Serialize context JSON for stdin delivery
+        // Serialize context JSON for stdin delivery, sensitive values redacted
         let context_json = context.to_json().to_string();
 
         // Execute with timeout, piping context JSON on stdin
@@ -228,7 +248,7 @@ impl CommandHandler {
             warn!(
                 command = %command,
                 exit_code = exit_code,
-                stderr = %stderr,
+                stderr = %Sensitive(&stderr),
                 "Command hook failed"
             );
 
@@ -263,6 +283,7 @@ mod tests {
             command: "echo".to_string(),
             args: vec!["continue".to_string()],
             env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
             working_dir: None,
         };
         let context = HookContext::new(HookEvent::SessionStart);
@@ -285,6 +306,7 @@ mod tests {
             command: "sh".to_string(),
             args: vec!["-c".to_string(), "echo $ASTRID_HOOK_EVENT".to_string()],
             env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
             working_dir: None,
         };
         let context = HookContext::new(HookEvent::PreToolCall);
@@ -306,6 +328,7 @@ mod tests {
             command: "sleep".to_string(),
             args: vec!["10".to_string()],
             env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
             working_dir: None,
         };
         let context = HookContext::new(HookEvent::SessionStart);
@@ -325,6 +348,7 @@ mod tests {
             command: "sh".to_string(),
             args: vec!["-c".to_string(), "exit 1".to_string()],
             env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
             working_dir: None,
         };
         let context = HookContext::new(HookEvent::SessionStart);
@@ -345,6 +369,7 @@ mod tests {
             command: "sh".to_string(),
             args: vec!["-c".to_string(), "echo $HOME".to_string()],
             env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
             working_dir: None,
         };
         let context = HookContext::new(HookEvent::SessionStart);
@@ -370,6 +395,7 @@ mod tests {
             command: "echo".to_string(),
             args: vec!["continue".to_string()],
             env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
             working_dir: None,
         };
         let context = HookContext::new(HookEvent::SessionStart);
@@ -393,6 +419,7 @@ mod tests {
             command: "sh".to_string(),
             args: vec!["-c".to_string(), "echo $CUSTOM_VAR".to_string()],
             env: custom_env,
+            env_passthrough: Vec::new(),
             working_dir: None,
         };
         let context = HookContext::new(HookEvent::SessionStart);
@@ -420,6 +447,7 @@ mod tests {
                 r#"INPUT=$(cat); echo "$INPUT" | grep -o '"event":"[^"]*"' | head -1"#.to_string(),
             ],
             env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
             working_dir: None,
         };
         let context = HookContext::new(HookEvent::PreToolCall)
@@ -440,6 +468,117 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_command_handler_never_interprets_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("pwned");
+        let payload = format!(
+            "$(touch {}) `touch {}`; touch {}",
+            marker.display(),
+            marker.display(),
+            marker.display()
+        );
+        let handler = CommandHandler::new();
+        let hook_handler = HookHandler::Command {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                r#"cat >/dev/null; printf '%s' "$1""#.to_string(),
+                "hook".to_string(),
+                "{{command}}".to_string(),
+            ],
+            env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
+            working_dir: None,
+        };
+        let context = HookContext::new(HookEvent::PreToolCall)
+            .with_data("command", serde_json::json!(payload));
+
+        let result = handler
+            .execute(&hook_handler, &context, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        let HookExecutionResult::Success { stdout, .. } = result else {
+            panic!("expected success, got {result:?}");
+        };
+        assert_eq!(stdout.unwrap_or_default(), payload);
+        assert!(!marker.exists(), "payload was executed");
+    }
+
+    #[tokio::test]
+    async fn test_command_handler_rejects_template_in_shell_script() {
+        let handler = CommandHandler::new();
+        let hook_handler = HookHandler::Command {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), "echo {{command}}".to_string()],
+            env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
+            working_dir: None,
+        };
+        let context = HookContext::new(HookEvent::PreToolCall);
+
+        let result = handler
+            .execute(&hook_handler, &context, Duration::from_secs(5))
+            .await;
+
+        assert!(matches!(result, Err(HandlerError::InvalidConfiguration(_))));
+    }
+
+    #[tokio::test]
+    async fn test_command_handler_redacts_sensitive_values() {
+        let handler = CommandHandler::new();
+        let hook_handler = HookHandler::Command {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                r#"cat; echo; echo "$ASTRID_HOOK_DATA"; echo "$1""#.to_string(),
+                "hook".to_string(),
+                "{{api_key}}".to_string(),
+            ],
+            env: std::collections::HashMap::default(),
+            env_passthrough: Vec::new(),
+            working_dir: None,
+        };
+        let context = HookContext::new(HookEvent::PreToolCall)
+            .with_sensitive_data("api_key", serde_json::json!("sk-secret"));
+
+        let result = handler
+            .execute(&hook_handler, &context, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        let HookExecutionResult::Success { stdout, .. } = result else {
+            panic!("expected success, got {result:?}");
+        };
+        let stdout = stdout.unwrap_or_default();
+        assert!(!stdout.contains("sk-secret"), "leaked: {stdout}");
+        assert_eq!(stdout.matches("[REDACTED]").count(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_command_handler_env_passthrough_respects_blocklist() {
+        let handler = CommandHandler::new();
+        let hook_handler = HookHandler::Command {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), r#"echo "[$LD_PRELOAD]""#.to_string()],
+            env: std::collections::HashMap::default(),
+            env_passthrough: vec!["LD_PRELOAD".to_string()],
+            working_dir: None,
+        };
+        let context = HookContext::new(HookEvent::SessionStart);
+
+        let result = handler
+            .execute(&hook_handler, &context, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        let HookExecutionResult::Success { stdout, .. } = result else {
+            panic!("expected success, got {result:?}");
+        };
+        assert_eq!(stdout.unwrap_or_default().trim(), "[]");
+    }
+
     #[test]
     fn test_safe_path() {
         let path = CommandHandler::safe_path();
@@ -3,6 +3,7 @@
 //! Note: This is a minimal implementation that uses `curl` or similar
 //! system commands. For production, consider using `reqwest` or similar.
 
+use astrid_telemetry::Sensitive;
 use std::process::Stdio;
 use std::time::Duration;
 use tokio::process::Command;
@@ -107,7 +108,7 @@ impl HttpHandler {
         if !output.status.success() {
             warn!(
                 url = %url,
-                stderr = %stderr,
+                stderr = %Sensitive(&stderr),
                 "HTTP hook failed"
             );
 
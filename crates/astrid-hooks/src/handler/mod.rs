@@ -5,6 +5,7 @@
 pub(crate) mod agent;
 pub(crate) mod command;
 pub(crate) mod http;
+pub(crate) mod template;
 pub(crate) mod wasm;
 
 pub(crate) use agent::AgentHandler;
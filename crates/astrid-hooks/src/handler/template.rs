@@ -0,0 +1,200 @@
+//! Argument templates for command hooks.
+//!
+//! `{{name}}` in a command hook's `args` is replaced with a context value:
+//!
+//! | Template | Value |
+//! |---|---|
+//! | `{{event}}`, `{{invocation_id}}`, `{{session_id}}`, `{{user_id}}`, `{{timestamp}}` | Context fields |
+//! | `{{tool_name}}`, or any other event data key | That data value: strings as is, anything else as JSON |
+//! | `{{json payload}}` | All event data as a JSON object |
+//! | `{{json <key>}}` | One data value as JSON |
+//!
+//! Missing values render as an empty string. Sensitive values render as
+//! `[REDACTED]`.
+//!
+//! Each argument is rendered on its own and handed straight to `exec`: a
+//! value is always part of exactly one argument and no shell sees it. The
+//! one way to undo that is to put a template inside a `sh -c` script, so
+//! that is rejected. Pass the value as a positional parameter instead:
+//! `args = ["-c", "echo \"$1\"", "hook", "{{tool_name}}"]`.
+
+use serde_json::Value;
+
+use super::{HandlerError, HandlerResult};
+use crate::result::{HookContext, REDACTED};
+
+/// Shells whose `-c` argument is a script.
+const SHELLS: &[&str] = &["sh", "bash", "dash", "zsh", "ksh", "fish"];
+
+/// Render every template in `args`.
+///
+/// # Errors
+///
+/// Returns [`HandlerError::InvalidConfiguration`] if a template appears in
+/// the script of a `<shell> -c` invocation.
+pub(crate) fn render_args(
+    command: &str,
+    args: &[String],
+    context: &HookContext,
+) -> HandlerResult<Vec<String>> {
+    let script = shell_script_index(command, args);
+    args.iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            if Some(index) == script && arg.contains("{{") {
+                return Err(HandlerError::InvalidConfiguration(format!(
+                    "template in `{command} -c` script; pass values as positional \
+                     parameters (\"$1\") instead"
+                )));
+            }
+            Ok(render(arg, context))
+        })
+        .collect()
+}
+
+/// Index of the script argument when `command` is a shell run with `-c`.
+fn shell_script_index(command: &str, args: &[String]) -> Option<usize> {
+    let name = std::path::Path::new(command).file_name()?.to_str()?;
+    if !SHELLS.contains(&name) {
+        return None;
+    }
+    args.iter()
+        .position(|arg| arg.starts_with('-') && !arg.starts_with("--") && arg.contains('c'))
+        .map(|flag| flag.saturating_add(1))
+}
+
+/// Replace every `{{...}}` in `template`. An unterminated `{{` is kept.
+pub(crate) fn render(template: &str, context: &HookContext) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let (before, after) = rest.split_at(start);
+        out.push_str(before);
+        let Some(end) = after.find("}}") else {
+            out.push_str(after);
+            return out;
+        };
+        out.push_str(&lookup(after[2..end].trim(), context));
+        rest = &after[end.saturating_add(2)..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn lookup(expr: &str, context: &HookContext) -> String {
+    if let Some(key) = expr.strip_prefix("json ") {
+        return match key.trim() {
+            "payload" => serde_json::to_string(&context.redacted_data()).unwrap_or_default(),
+            key => data(key, context).map_or_else(String::new, |value| value.to_string()),
+        };
+    }
+    match expr {
+        "event" => context.event.to_string(),
+        "invocation_id" => context.invocation_id.to_string(),
+        "session_id" => context
+            .session_id
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        "user_id" => context.user_id.map(|id| id.to_string()).unwrap_or_default(),
+        "timestamp" => context.timestamp.to_rfc3339(),
+        key => match data(key, context) {
+            Some(Value::String(text)) => text,
+            Some(value) => value.to_string(),
+            None => String::new(),
+        },
+    }
+}
+
+fn data(key: &str, context: &HookContext) -> Option<Value> {
+    if context.is_sensitive(key) {
+        return Some(Value::String(REDACTED.to_string()));
+    }
+    context.get_data(key).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::hook::HookEvent;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn renders_context_and_data() {
+        let session = Uuid::new_v4();
+        let context = HookContext::new(HookEvent::PreToolCall)
+            .with_session(session)
+            .with_data("tool_name", json!("read_file"))
+            .with_data("limit", json!(3));
+
+        let rendered = render_args(
+            "notify",
+            &args(&[
+                "--tool={{tool_name}}",
+                "{{ session_id }}",
+                "{{limit}}",
+                "{{missing}}",
+                "{{event}}:{{tool_name}}",
+            ]),
+            &context,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            [
+                "--tool=read_file".to_string(),
+                session.to_string(),
+                "3".to_string(),
+                String::new(),
+                "pre_tool_call:read_file".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_json_and_redacts_sensitive_values() {
+        let context = HookContext::new(HookEvent::PreToolCall)
+            .with_data("arguments", json!({"path": "/tmp/a"}))
+            .with_sensitive_data("token", json!("sk-secret"));
+
+        let rendered = render_args(
+            "notify",
+            &args(&["{{json arguments}}", "{{json payload}}", "{{token}}"]),
+            &context,
+        )
+        .unwrap();
+
+        assert_eq!(rendered[0], r#"{"path":"/tmp/a"}"#);
+        assert!(rendered[1].contains(r#""token":"[REDACTED]""#));
+        assert!(!rendered[1].contains("sk-secret"));
+        assert_eq!(rendered[2], REDACTED);
+    }
+
+    #[test]
+    fn unterminated_template_is_literal() {
+        let context = HookContext::new(HookEvent::PreToolCall);
+        let rendered = render_args("echo", &args(&["a {{b", "}}"]), &context).unwrap();
+        assert_eq!(rendered, args(&["a {{b", "}}"]));
+    }
+
+    #[test]
+    fn template_in_shell_script_is_rejected() {
+        let context = HookContext::new(HookEvent::PreToolCall);
+
+        let err = render_args("/bin/sh", &args(&["-c", "echo {{tool_name}}"]), &context);
+        assert!(matches!(err, Err(HandlerError::InvalidConfiguration(_))));
+
+        let ok = render_args(
+            "bash",
+            &args(&["-ec", "echo \"$1\"", "hook", "{{tool_name}}"]),
+            &context,
+        );
+        assert!(ok.is_ok());
+    }
+}
@@ -4,6 +4,10 @@
 //! serialized [`HookAbiContext`] as `list<u8>` and interpreting the returned
 //! bytes as a [`HookAbiResult`].
 //!
+//! Each invocation runs under the hook's timeout (capped by
+//! [`WasmConfig::max_execution_time`]) and [`WasmConfig::max_memory_bytes`].
+//! A handler with a `hash` pin refuses a module whose BLAKE3 hash differs.
+//!
 //! Host functions are provided via `Capsule::add_to_linker` (wasmtime bindgen)
 //! and `wasmtime_wasi::p2::add_to_linker_sync`.
 
@@ -48,7 +52,8 @@ struct HookAbiResult {
 pub(crate) struct WasmHandler {
     /// Cached wasmtime engine (shared across all components).
     engine: wasmtime::Engine,
-    /// Cached compiled components (lazy-loaded, keyed by module path).
+    /// Cached compiled components (lazy-loaded, keyed by module path and
+    /// pinned hash).
     cached_components: Mutex<HashMap<String, Arc<Component>>>,
     /// Configuration for WASM execution.
     config: WasmConfig,
@@ -125,11 +130,12 @@ impl WasmHandler {
         &self,
         handler: &HookHandler,
         context: &HookContext,
-        _timeout: Duration,
+        timeout: Duration,
     ) -> HandlerResult<HookExecutionResult> {
         let HookHandler::Wasm {
             module_path,
             function,
+            hash,
         } = handler
         else {
             return Err(HandlerError::InvalidConfiguration(
@@ -141,7 +147,7 @@ impl WasmHandler {
 
         // Get or compile cached component
         let component = self
-            .get_or_compile_component(module_path)
+            .get_or_compile_component(module_path, hash.as_deref())
             .map_err(|e| HandlerError::WasmFailed(format!("failed to load WASM module: {e}")))?;
 
         // Build CapsuleAbiContext from HookContext
@@ -154,7 +160,7 @@ impl WasmHandler {
             data: if context.data.is_empty() {
                 None
             } else {
-                serde_json::to_string(&context.data).ok()
+                serde_json::to_string(&context.redacted_data()).ok()
             },
         };
 
@@ -164,11 +170,12 @@ impl WasmHandler {
         // Build a fresh Store + HostState for this invocation
         let host_state = self.build_host_state(module_path)?;
         let mut store = Store::new(&self.engine, host_state);
+        store.limiter(|state| &mut state.store_limits);
 
         // Set epoch deadline for timeout enforcement.
-        // Epoch ticks at 100ms intervals; convert max_execution_time to ticks.
-        let deadline_ticks =
-            u64::try_from(self.config.max_execution_time.as_millis() / 100).unwrap_or(u64::MAX);
+        // Epoch ticks at 100ms intervals; convert the time limit to ticks.
+        let time_limit = timeout.min(self.config.max_execution_time);
+        let deadline_ticks = u64::try_from(time_limit.as_millis() / 100).unwrap_or(u64::MAX);
         store.set_epoch_deadline(deadline_ticks.max(1));
 
         // Build linker with WASI + Astrid host interfaces
@@ -219,14 +226,20 @@ impl WasmHandler {
         true
     }
 
-    /// Get a cached compiled component or compile it from disk.
-    fn get_or_compile_component(&self, module_path: &str) -> Result<Arc<Component>, HandlerError> {
+    /// Get a cached compiled component or compile it from disk, verifying
+    /// the pinned hash (if any) first.
+    fn get_or_compile_component(
+        &self,
+        module_path: &str,
+        expected_hash: Option<&str>,
+    ) -> Result<Arc<Component>, HandlerError> {
         let mut cache = self
             .cached_components
             .lock()
             .map_err(|e| HandlerError::WasmFailed(format!("cache lock poisoned: {e}")))?;
 
-        if let Some(component) = cache.get(module_path) {
+        let cache_key = format!("{module_path}#{}", expected_hash.unwrap_or_default());
+        if let Some(component) = cache.get(&cache_key) {
             return Ok(Arc::clone(component));
         }
 
@@ -245,13 +258,23 @@ impl WasmHandler {
             ))
         })?;
 
+        if let Some(expected) = expected_hash {
+            let actual = format!("blake3:{}", blake3::hash(&wasm_bytes).to_hex());
+            if actual != expected {
+                return Err(HandlerError::WasmFailed(format!(
+                    "integrity check failed for {}: expected {expected}, got {actual}",
+                    resolved.display()
+                )));
+            }
+        }
+
         // Compile the WASM component
         let component = Component::from_binary(&self.engine, &wasm_bytes).map_err(|e| {
             HandlerError::WasmFailed(format!("failed to compile WASM component: {e}"))
         })?;
 
         let component_arc = Arc::new(component);
-        cache.insert(module_path.to_string(), Arc::clone(&component_arc));
+        cache.insert(cache_key, Arc::clone(&component_arc));
 
         Ok(component_arc)
     }
@@ -321,6 +344,7 @@ impl WasmHandler {
             config: HashMap::new(),
             ipc_publish_patterns: vec!["hook.v1.result.*".into()],
             ipc_subscribe_patterns: Vec::new(),
+            call_targets: Vec::new(),
             security: None,
             hook_manager: None,
             capsule_registry: None,
@@ -347,9 +371,13 @@ impl WasmHandler {
             identity_store: None,
             background_processes: HashMap::new(),
             next_process_id: 1,
+            open_files: HashMap::new(),
+            next_file_handle: 1,
             process_tracker: Arc::new(
                 astrid_capsule::engine::wasm::host::process::ProcessTracker::new(),
             ),
+            audit: None,
+            workspace_mode: astrid_workspace::WorkspaceMode::Safe,
         })
     }
 }
@@ -482,6 +510,89 @@ mod tests {
         assert!(matches!(hook, HookResult::Continue));
     }
 
+    /// Compile the fixture component that blocks `dangerous_tool` calls
+    /// into `dir`, returning its path and `blake3:` hash.
+    fn write_block_tool_fixture(dir: &std::path::Path) -> (PathBuf, String) {
+        let wat = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/block_tool.wat"
+        ));
+        let bytes = wat::parse_str(wat).unwrap();
+        let path = dir.join("block_tool.wasm");
+        std::fs::write(&path, &bytes).unwrap();
+        (path, format!("blake3:{}", blake3::hash(&bytes).to_hex()))
+    }
+
+    fn tool_call(tool: &str) -> HookContext {
+        HookContext::new(HookEvent::PreToolCall).with_data("tool_name", serde_json::json!(tool))
+    }
+
+    fn wasm_hook(path: &std::path::Path, hash: Option<String>) -> HookHandler {
+        HookHandler::Wasm {
+            module_path: path.display().to_string(),
+            function: "handle".to_string(),
+            hash,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_fixture_blocks_specific_tool() {
+        let dir = tempfile::tempdir().unwrap();
+        let (path, _) = write_block_tool_fixture(dir.path());
+        let handler = WasmHandler::new(dir.path().to_path_buf());
+        let hook = wasm_hook(&path, None);
+
+        let blocked = handler
+            .execute(&hook, &tool_call("dangerous_tool"), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(matches!(
+            blocked.hook_result(),
+            Some(HookResult::Block { reason }) if reason == "dangerous_tool is not allowed"
+        ));
+
+        let allowed = handler
+            .execute(&hook, &tool_call("read_file"), Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(matches!(allowed.hook_result(), Some(HookResult::Continue)));
+
+        // Both calls shared one compiled component.
+        assert_eq!(handler.cached_components.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_pinned_hash_is_enforced() {
+        let dir = tempfile::tempdir().unwrap();
+        let (path, hash) = write_block_tool_fixture(dir.path());
+        let handler = WasmHandler::new(dir.path().to_path_buf());
+
+        let pinned = handler
+            .execute(
+                &wasm_hook(&path, Some(hash)),
+                &tool_call("dangerous_tool"),
+                Duration::from_secs(5),
+            )
+            .await;
+        assert!(
+            pinned
+                .unwrap()
+                .hook_result()
+                .is_some_and(HookResult::is_blocking)
+        );
+
+        let wrong = format!("blake3:{}", "0".repeat(64));
+        let err = handler
+            .execute(
+                &wasm_hook(&path, Some(wrong)),
+                &tool_call("dangerous_tool"),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("integrity check failed"), "{err}");
+    }
+
     #[tokio::test]
     async fn test_wasm_handler_invalid_handler_type() {
         let handler = WasmHandler::new(PathBuf::from("/tmp"));
@@ -21,6 +21,11 @@ pub enum HookHandler {
         /// Environment variables to set.
         #[serde(default)]
         env: HashMap<String, String>,
+        /// Names of parent-process environment variables to pass through
+        /// to a sandboxed command. Variables blocked by
+        /// `astrid_core::env_policy` are never passed.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        env_passthrough: Vec<String>,
         /// Working directory for the command.
         #[serde(default)]
         working_dir: Option<String>,
@@ -39,27 +44,53 @@ pub enum HookHandler {
         #[serde(default)]
         body_template: Option<String>,
     },
-    /// Execute a WASM module via Extism.
+    /// Run a WASM component's `astrid-hook-trigger` export.
     Wasm {
-        /// Path to the WASM module.
+        /// Path to the WASM component. Relative paths in a discovered hook
+        /// file resolve against that file's directory.
         module_path: String,
         /// Function to call in the module.
         #[serde(default = "default_wasm_function")]
         function: String,
+        /// Expected BLAKE3 hash of the module (`blake3:<hex>`). When set, a
+        /// module whose contents differ is refused.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hash: Option<String>,
     },
-    /// Invoke an LLM-based agent handler (stubbed).
+    /// Ask an LLM judge to allow, deny, or modify the operation.
     Agent {
-        /// Agent prompt template.
+        /// Instruction for the judge. Supports the command argument
+        /// templates, e.g. `{{tool_name}}`.
         prompt_template: String,
-        /// Model to use.
+        /// Model to use. When unset, the runtime's cheap model is used.
         #[serde(default)]
         model: Option<String>,
         /// Maximum tokens for response.
         #[serde(default)]
         max_tokens: Option<u32>,
+        /// Most a single judgement may cost, in USD. A reply that costs
+        /// more is discarded and `fallback` applies.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_cost_usd: Option<f64>,
+        /// Decision to use when the judge times out, fails, or answers
+        /// outside the decision schema.
+        #[serde(default)]
+        fallback: JudgeFallback,
     },
 }
 
+/// Decision an agent hook falls back to when its judge gives no usable
+/// answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JudgeFallback {
+    /// Let the operation proceed unchanged.
+    Allow,
+    /// Block the operation.
+    #[default]
+    Deny,
+}
+
 fn default_http_method() -> String {
     "POST".to_string()
 }
@@ -77,6 +108,7 @@ impl HookHandler {
             command: command.into(),
             args: Vec::new(),
             env: HashMap::new(),
+            env_passthrough: Vec::new(),
             working_dir: None,
         }
     }
@@ -98,24 +130,21 @@ impl HookHandler {
         Self::Wasm {
             module_path: module_path.into(),
             function: "handle".to_string(),
+            hash: None,
         }
     }
 
-    /// Create a new agent handler (stubbed).
+    /// Create a new agent handler.
     #[must_use]
     pub(crate) fn agent(prompt_template: impl Into<String>) -> Self {
         Self::Agent {
             prompt_template: prompt_template.into(),
             model: None,
             max_tokens: None,
+            max_cost_usd: None,
+            fallback: JudgeFallback::default(),
         }
     }
-
-    /// Check if this handler is stubbed (not yet implemented).
-    #[must_use]
-    pub(crate) fn is_stubbed(&self) -> bool {
-        matches!(self, Self::Agent { .. })
-    }
 }
 
 /// Action to take when a hook fails.
@@ -168,6 +197,10 @@ pub struct Hook {
     /// Run asynchronously (don't wait for completion).
     #[serde(default)]
     pub async_mode: bool,
+    /// The hook only observes: its modifications are discarded, so it may
+    /// run concurrently with the event's other observe-only hooks.
+    #[serde(default)]
+    pub observe_only: bool,
     /// Whether the hook is enabled.
     #[serde(default = "default_enabled")]
     pub enabled: bool,
@@ -203,6 +236,7 @@ impl Hook {
             timeout_secs: 30,
             fail_action: FailAction::Warn,
             async_mode: false,
+            observe_only: false,
             enabled: true,
             priority: 100,
         }
@@ -257,6 +291,13 @@ impl Hook {
         self
     }
 
+    /// Mark the hook observe-only.
+    #[must_use]
+    pub(crate) fn observe_only(mut self) -> Self {
+        self.observe_only = true;
+        self
+    }
+
     /// Disable the hook.
     #[must_use]
     pub(crate) fn disabled(mut self) -> Self {
@@ -355,13 +396,19 @@ mod tests {
     #[test]
     fn test_hook_handler_creation() {
         let cmd = HookHandler::command("echo");
-        assert!(!cmd.is_stubbed());
+        assert!(matches!(cmd, HookHandler::Command { .. }));
 
         let wasm = HookHandler::wasm("/path/to/module.wasm");
-        assert!(!wasm.is_stubbed());
+        assert!(matches!(wasm, HookHandler::Wasm { .. }));
 
         let agent = HookHandler::agent("Analyze this event: {{event}}");
-        assert!(agent.is_stubbed());
+        assert!(matches!(
+            agent,
+            HookHandler::Agent {
+                fallback: JudgeFallback::Deny,
+                ..
+            }
+        ));
     }
 
     #[test]
@@ -18,7 +18,7 @@
 //! - **Command**: Execute shell commands
 //! - **HTTP**: Call webhooks
 //! - **WASM**: Run WebAssembly modules
-//! - **Agent**: Invoke LLM-based handlers (stubbed)
+//! - **Agent**: Ask an LLM judge to allow, deny, or modify the operation
 //!
 //! # Example
 //!
@@ -46,6 +46,8 @@
 
 pub mod prelude;
 
+#[allow(dead_code)]
+pub(crate) mod breaker;
 #[allow(dead_code)]
 pub(crate) mod config;
 #[allow(dead_code)]
@@ -59,10 +61,18 @@ pub mod hook_event;
 #[allow(dead_code)]
 pub(crate) mod manager;
 #[allow(dead_code)]
+pub(crate) mod mutation;
+#[allow(dead_code)]
 pub(crate) mod profiles;
 #[allow(dead_code)]
 pub(crate) mod result;
+#[allow(dead_code)]
+pub(crate) mod trust;
 
+pub use executor::HookExecutor;
+pub use handler::agent::{AgentHandler, JudgeModel, JudgeReply, JudgeRequest};
 pub use hook::{Hook, HookHandler};
 pub use hook_event::HookEvent;
+pub use manager::HookManager;
 pub use result::HookResult;
+pub use trust::{TrustDecision, TrustPrompt, TrustRequest};
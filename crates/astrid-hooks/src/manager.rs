@@ -1,18 +1,29 @@
 //! Hook manager - manages hook registration and triggering.
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use astrid_core::dirs::AstridHome;
+
+use crate::breaker::CircuitBreakers;
+use crate::discovery::discover_workspace_hooks;
 use crate::executor::HookExecutor;
+use crate::handler::AgentHandler;
 use crate::hook::{Hook, HookEvent};
+use crate::mutation::{
+    Mutated, PromptDecision, ToolCallDecision, apply_post_tool_call, apply_pre_tool_call,
+    apply_user_prompt,
+};
 use crate::result::{HookContext, HookExecution, HookResult};
+use crate::trust::{HookTrustStore, TrustPrompt};
 
 /// Manages hooks and their execution.
 #[derive(Debug)]
-pub(crate) struct HookManager {
+pub struct HookManager {
     /// Registered hooks, indexed by ID.
     hooks: Arc<RwLock<HashMap<Uuid, Hook>>>,
     /// Hooks grouped by event type.
@@ -24,7 +35,7 @@ pub(crate) struct HookManager {
 impl HookManager {
     /// Create a new hook manager.
     #[must_use]
-    pub(crate) fn new() -> Self {
+    pub fn new() -> Self {
         Self {
             hooks: Arc::new(RwLock::new(HashMap::new())),
             hooks_by_event: Arc::new(RwLock::new(HashMap::new())),
@@ -32,8 +43,23 @@ impl HookManager {
         }
     }
 
+    /// Use `breakers` for the hooks' circuit breakers, e.g. to tune the
+    /// threshold or publish open/close events.
+    #[must_use]
+    pub(crate) fn with_breakers(mut self, breakers: CircuitBreakers) -> Self {
+        self.executor = self.executor.with_breakers(breakers);
+        self
+    }
+
+    /// Use `handler` for agent hooks, e.g. to attach a judge model.
+    #[must_use]
+    pub fn with_agent_handler(mut self, handler: AgentHandler) -> Self {
+        self.executor = self.executor.with_agent_handler(handler);
+        self
+    }
+
     /// Register a hook.
-    pub(crate) async fn register(&self, hook: Hook) {
+    pub async fn register(&self, hook: Hook) {
         let hook_id = hook.id;
         let event = hook.event;
 
@@ -58,12 +84,40 @@ impl HookManager {
     }
 
     /// Register multiple hooks.
-    pub(crate) async fn register_all(&self, hooks: Vec<Hook>) {
+    pub async fn register_all(&self, hooks: Vec<Hook>) {
         for hook in hooks {
             self.register(hook).await;
         }
     }
 
+    /// Register the hooks committed to the workspace at `workspace_root`
+    /// (`.astrid/hooks/`).
+    ///
+    /// A hook file loads only once the user trusts it: new or changed files
+    /// are put to `prompt`, and each answer is pinned to the file's hash in
+    /// `home`'s [`AstridHome::hook_trust_path`]. Trusted hooks are
+    /// observe-only unless the user also allowed mutations. If the trust
+    /// store cannot be read, no workspace hook is loaded.
+    ///
+    /// Returns the number of hooks registered.
+    pub async fn load_workspace_hooks(
+        &self,
+        workspace_root: &Path,
+        home: &AstridHome,
+        prompt: &dyn TrustPrompt,
+    ) -> usize {
+        let hooks = match HookTrustStore::open(home.hook_trust_path()) {
+            Ok(mut store) => discover_workspace_hooks(workspace_root, &mut store, prompt),
+            Err(e) => {
+                warn!(error = %e, "Failed to open hook trust store, skipping workspace hooks");
+                return 0;
+            },
+        };
+        let count = hooks.len();
+        self.register_all(hooks).await;
+        count
+    }
+
     /// Unregister a hook by ID.
     pub(crate) async fn unregister(&self, hook_id: Uuid) -> Option<Hook> {
         info!(hook_id = %hook_id, "Unregistering hook");
@@ -179,6 +233,42 @@ impl HookManager {
         result
     }
 
+    /// Run `pre_tool_call` hooks (which see the call's `arguments`) and
+    /// apply their mutations. See [`crate::mutation`] for the contract.
+    pub(crate) async fn pre_tool_call(
+        &self,
+        context: HookContext,
+        arguments: serde_json::Value,
+    ) -> Mutated<ToolCallDecision> {
+        let context = context.with_data("arguments", arguments.clone());
+        let (executions, _) = self.trigger(HookEvent::PreToolCall, context).await;
+        apply_pre_tool_call(&executions, arguments)
+    }
+
+    /// Run `user_prompt` hooks (which see the `prompt`) and apply their
+    /// mutations.
+    pub(crate) async fn user_prompt(
+        &self,
+        context: HookContext,
+        prompt: &str,
+    ) -> Mutated<PromptDecision> {
+        let context = context.with_data("prompt", serde_json::json!(prompt));
+        let (executions, _) = self.trigger(HookEvent::UserPrompt, context).await;
+        apply_user_prompt(&executions, prompt)
+    }
+
+    /// Run `post_tool_call` hooks (which see the tool's `output`) and apply
+    /// their mutations before the output reaches the model.
+    pub(crate) async fn post_tool_call(
+        &self,
+        context: HookContext,
+        output: serde_json::Value,
+    ) -> Mutated<serde_json::Value> {
+        let context = context.with_data("output", output.clone());
+        let (executions, _) = self.trigger(HookEvent::PostToolCall, context).await;
+        apply_post_tool_call(&executions, output)
+    }
+
     /// Get statistics about registered hooks.
     pub(crate) async fn stats(&self) -> HookStats {
         let hooks = self.hooks.read().await;
@@ -302,6 +392,7 @@ mod tests {
                 command: "echo".to_string(),
                 args: vec!["continue".to_string()],
                 env: std::collections::HashMap::default(),
+                env_passthrough: Vec::new(),
                 working_dir: None,
             })
             .with_timeout(5);
@@ -315,6 +406,57 @@ mod tests {
         assert!(matches!(result, HookResult::Continue));
     }
 
+    fn echo_hook(event: HookEvent, output: &str, priority: i32) -> Hook {
+        Hook::new(event)
+            .with_handler(HookHandler::Command {
+                command: "echo".to_string(),
+                args: vec![output.to_string()],
+                env: std::collections::HashMap::default(),
+                env_passthrough: Vec::new(),
+                working_dir: None,
+            })
+            .with_priority(priority)
+            .with_timeout(5)
+    }
+
+    #[tokio::test]
+    async fn test_manager_pre_tool_call_rewrites_then_vetoes() {
+        let manager = HookManager::new();
+        manager
+            .register(echo_hook(
+                HookEvent::PreToolCall,
+                r#"{"action":"continue_with","modifications":{"arguments":{"path":"/safe"}}}"#,
+                1,
+            ))
+            .await;
+
+        let context = HookContext::new(HookEvent::PreToolCall);
+        let rewritten = manager
+            .pre_tool_call(context.clone(), serde_json::json!({"path": "/etc"}))
+            .await;
+        assert_eq!(
+            rewritten.outcome,
+            ToolCallDecision::Proceed {
+                arguments: serde_json::json!({"path": "/safe"})
+            }
+        );
+        assert_eq!(rewritten.mutations.len(), 1);
+
+        manager
+            .register(echo_hook(HookEvent::PreToolCall, "block: no", 2))
+            .await;
+        let vetoed = manager
+            .pre_tool_call(context, serde_json::json!({"path": "/etc"}))
+            .await;
+        assert_eq!(
+            vetoed.outcome,
+            ToolCallDecision::Veto {
+                reason: "no".to_string()
+            }
+        );
+        assert_eq!(vetoed.mutations.len(), 2);
+    }
+
     #[tokio::test]
     async fn test_manager_stats() {
         let manager = HookManager::new();
@@ -331,4 +473,68 @@ mod tests {
         assert_eq!(stats.disabled, 1);
         assert_eq!(stats.events_with_hooks, 3);
     }
+
+    /// Trust prompt that gives one answer and counts how often it is asked.
+    struct CountingPrompt(crate::TrustDecision, std::sync::atomic::AtomicUsize);
+
+    impl TrustPrompt for CountingPrompt {
+        fn ask(&self, _request: &crate::TrustRequest<'_>) -> crate::TrustDecision {
+            self.1.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn test_workspace_hooks_load_after_trust_on_first_use() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let workspace = dir.path().join("project");
+        let home = AstridHome::from_path(dir.path().join("astrid-home"));
+        let hook_file = crate::discovery::workspace_hooks_dir(&workspace).join("guard.toml");
+        std::fs::create_dir_all(hook_file.parent().unwrap()).unwrap();
+        let hook = Hook::new(HookEvent::PreToolCall)
+            .with_name("guard")
+            .with_handler(HookHandler::command("echo"));
+        crate::discovery::save_hook(&hook, &hook_file).unwrap();
+
+        let trust = CountingPrompt(
+            crate::TrustDecision::Trust {
+                allow_mutations: false,
+            },
+            std::sync::atomic::AtomicUsize::new(0),
+        );
+        let manager = HookManager::new();
+        assert_eq!(
+            manager
+                .load_workspace_hooks(&workspace, &home, &trust)
+                .await,
+            1
+        );
+        let loaded = manager.hooks_for_event(HookEvent::PreToolCall).await;
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded[0].observe_only);
+        assert!(home.hook_trust_path().is_file());
+
+        // The decision is pinned under the home: a later load does not ask.
+        let again = HookManager::new();
+        assert_eq!(
+            again.load_workspace_hooks(&workspace, &home, &trust).await,
+            1
+        );
+        assert_eq!(trust.1.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // An untrusted hook is not registered.
+        let deny = CountingPrompt(
+            crate::TrustDecision::Deny,
+            std::sync::atomic::AtomicUsize::new(0),
+        );
+        let other_home = AstridHome::from_path(dir.path().join("other-home"));
+        let denied = HookManager::new();
+        assert_eq!(
+            denied
+                .load_workspace_hooks(&workspace, &other_home, &deny)
+                .await,
+            0
+        );
+        assert!(denied.all().await.is_empty());
+    }
 }
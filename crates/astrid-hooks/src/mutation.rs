@@ -0,0 +1,397 @@
+//! Mutation contract: how hook results rewrite the operation that fired them.
+//!
+//! | Event | `continue_with` keys | `block` |
+//! |---|---|---|
+//! | `pre_tool_call` | `arguments`: object merged over the call's arguments | Veto the call; the reason becomes the tool result |
+//! | `user_prompt` | `prepend`, `append`: text added around the prompt | Cancel the turn |
+//! | `post_tool_call` | `output`: replaces the output (e.g. redacted) | Withhold the output; the reason replaces it |
+//!
+//! Mutations apply in execution order, which is hook priority order. When
+//! two hooks set the same key (for `arguments`, the same argument), the
+//! later hook wins. Other keys are ignored. Every applied mutation produces
+//! a [`MutationRecord`] for the caller to write to the audit log.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::hook::HookEvent;
+use crate::result::{HookExecution, HookResult};
+
+/// One mutation a hook applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MutationRecord {
+    /// Hook that produced the mutation.
+    pub hook_id: Uuid,
+    /// Event the hook ran for.
+    pub event: HookEvent,
+    /// What was changed: `arguments.<name>`, `prepend`, `append`, `output`,
+    /// or `block`.
+    pub field: String,
+    /// The value the hook set (the reason, for `block`).
+    pub value: Value,
+}
+
+/// An operation after its hooks ran, with the mutations that shaped it.
+#[derive(Debug, Clone)]
+pub(crate) struct Mutated<T> {
+    /// The resulting operation.
+    pub outcome: T,
+    /// Mutations in the order they were applied.
+    pub mutations: Vec<MutationRecord>,
+}
+
+/// What to do with a tool call after its `pre_tool_call` hooks.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ToolCallDecision {
+    /// Run the tool with these (possibly rewritten) arguments.
+    Proceed {
+        /// Arguments to call the tool with.
+        arguments: Value,
+    },
+    /// Do not run the tool; return `reason` as its result.
+    Veto {
+        /// Why the call was vetoed.
+        reason: String,
+    },
+}
+
+/// What to do with a prompt after its `user_prompt` hooks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PromptDecision {
+    /// Submit this (possibly extended) prompt.
+    Submit {
+        /// Prompt text to send.
+        prompt: String,
+    },
+    /// Cancel the turn.
+    Cancel {
+        /// Why the turn was cancelled.
+        reason: String,
+    },
+}
+
+/// Apply `pre_tool_call` results to a tool call's arguments.
+pub(crate) fn apply_pre_tool_call(
+    executions: &[HookExecution],
+    arguments: Value,
+) -> Mutated<ToolCallDecision> {
+    let mut arguments = arguments;
+    let mut mutations = Vec::new();
+    for (hook_id, result) in results(executions) {
+        match result {
+            HookResult::Block { reason } => {
+                mutations.push(record(
+                    hook_id,
+                    HookEvent::PreToolCall,
+                    "block",
+                    reason.as_str(),
+                ));
+                return Mutated {
+                    outcome: ToolCallDecision::Veto {
+                        reason: reason.clone(),
+                    },
+                    mutations,
+                };
+            },
+            HookResult::ContinueWith { modifications } => {
+                for (key, value) in sorted(modifications) {
+                    match (key, value, arguments.as_object_mut()) {
+                        ("arguments", Value::Object(changes), Some(current)) => {
+                            for (name, value) in changes {
+                                current.insert(name.clone(), value.clone());
+                                mutations.push(MutationRecord {
+                                    hook_id,
+                                    event: HookEvent::PreToolCall,
+                                    field: format!("arguments.{name}"),
+                                    value: value.clone(),
+                                });
+                            }
+                        },
+                        _ => ignored(hook_id, HookEvent::PreToolCall, key),
+                    }
+                }
+            },
+            HookResult::Continue | HookResult::Ask { .. } => {},
+        }
+    }
+    Mutated {
+        outcome: ToolCallDecision::Proceed { arguments },
+        mutations,
+    }
+}
+
+/// Apply `user_prompt` results to the submitted prompt.
+pub(crate) fn apply_user_prompt(
+    executions: &[HookExecution],
+    prompt: &str,
+) -> Mutated<PromptDecision> {
+    let mut prepend = None;
+    let mut append = None;
+    let mut mutations = Vec::new();
+    for (hook_id, result) in results(executions) {
+        match result {
+            HookResult::Block { reason } => {
+                mutations.push(record(
+                    hook_id,
+                    HookEvent::UserPrompt,
+                    "block",
+                    reason.as_str(),
+                ));
+                return Mutated {
+                    outcome: PromptDecision::Cancel {
+                        reason: reason.clone(),
+                    },
+                    mutations,
+                };
+            },
+            HookResult::ContinueWith { modifications } => {
+                for (key, value) in sorted(modifications) {
+                    let slot = match key {
+                        "prepend" => &mut prepend,
+                        "append" => &mut append,
+                        _ => {
+                            ignored(hook_id, HookEvent::UserPrompt, key);
+                            continue;
+                        },
+                    };
+                    let Some(text) = value.as_str() else {
+                        ignored(hook_id, HookEvent::UserPrompt, key);
+                        continue;
+                    };
+                    *slot = Some(text.to_owned());
+                    mutations.push(record(hook_id, HookEvent::UserPrompt, key, text));
+                }
+            },
+            HookResult::Continue | HookResult::Ask { .. } => {},
+        }
+    }
+    let prompt = [prepend.as_deref(), Some(prompt), append.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Mutated {
+        outcome: PromptDecision::Submit { prompt },
+        mutations,
+    }
+}
+
+/// Apply `post_tool_call` results to a tool's output before the model sees
+/// it.
+pub(crate) fn apply_post_tool_call(executions: &[HookExecution], output: Value) -> Mutated<Value> {
+    let mut output = output;
+    let mut mutations = Vec::new();
+    for (hook_id, result) in results(executions) {
+        match result {
+            HookResult::Block { reason } => {
+                mutations.push(record(
+                    hook_id,
+                    HookEvent::PostToolCall,
+                    "block",
+                    reason.as_str(),
+                ));
+                return Mutated {
+                    outcome: Value::String(reason.clone()),
+                    mutations,
+                };
+            },
+            HookResult::ContinueWith { modifications } => {
+                for (key, value) in sorted(modifications) {
+                    if key == "output" {
+                        output = value.clone();
+                        mutations.push(record(
+                            hook_id,
+                            HookEvent::PostToolCall,
+                            key,
+                            value.clone(),
+                        ));
+                    } else {
+                        ignored(hook_id, HookEvent::PostToolCall, key);
+                    }
+                }
+            },
+            HookResult::Continue | HookResult::Ask { .. } => {},
+        }
+    }
+    Mutated {
+        outcome: output,
+        mutations,
+    }
+}
+
+/// Successful hook results in execution order.
+fn results(executions: &[HookExecution]) -> impl Iterator<Item = (Uuid, &HookResult)> {
+    executions
+        .iter()
+        .filter_map(|execution| Some((execution.hook_id, execution.result.hook_result()?)))
+}
+
+/// Modification entries sorted by key, so records are deterministic.
+fn sorted(modifications: &HashMap<String, Value>) -> Vec<(&str, &Value)> {
+    let mut entries: Vec<_> = modifications
+        .iter()
+        .map(|(key, value)| (key.as_str(), value))
+        .collect();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+fn record(hook_id: Uuid, event: HookEvent, field: &str, value: impl Into<Value>) -> MutationRecord {
+    MutationRecord {
+        hook_id,
+        event,
+        field: field.to_owned(),
+        value: value.into(),
+    }
+}
+
+fn ignored(hook_id: Uuid, event: HookEvent, key: &str) {
+    warn!(hook_id = %hook_id, event = %event, key, "ignoring unsupported hook modification");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::Utc;
+    use serde_json::json;
+
+    use super::*;
+    use crate::result::HookExecutionResult;
+
+    fn execution(result: HookResult) -> HookExecution {
+        HookExecution {
+            hook_id: Uuid::new_v4(),
+            invocation_id: Uuid::new_v4(),
+            started_at: Utc::now(),
+            completed_at: Utc::now(),
+            duration_ms: 0,
+            fail_action: crate::hook::FailAction::Warn,
+            result: HookExecutionResult::Success {
+                result,
+                stdout: None,
+            },
+        }
+    }
+
+    fn modify(pairs: &[(&str, Value)]) -> HookExecution {
+        let modifications: HashMap<String, Value> = pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_owned(), v.clone()))
+            .collect();
+        execution(HookResult::ContinueWith { modifications })
+    }
+
+    #[test]
+    fn pre_tool_call_merges_arguments_last_writer_wins() {
+        let first = modify(&[("arguments", json!({"path": "/a", "mode": "r"}))]);
+        let second = modify(&[("arguments", json!({"path": "/b"}))]);
+        let second_id = second.hook_id;
+
+        let applied = apply_pre_tool_call(
+            &[first, second],
+            json!({"path": "/orig", "recursive": true}),
+        );
+
+        assert_eq!(
+            applied.outcome,
+            ToolCallDecision::Proceed {
+                arguments: json!({"path": "/b", "mode": "r", "recursive": true})
+            }
+        );
+        let fields: Vec<&str> = applied.mutations.iter().map(|m| m.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["arguments.mode", "arguments.path", "arguments.path"]
+        );
+        assert_eq!(applied.mutations[2].hook_id, second_id);
+    }
+
+    #[test]
+    fn pre_tool_call_block_vetoes_with_reason() {
+        let applied = apply_pre_tool_call(
+            &[
+                modify(&[("arguments", json!({"path": "/a"}))]),
+                execution(HookResult::block("writes outside the workspace")),
+            ],
+            json!({"path": "/orig"}),
+        );
+
+        assert_eq!(
+            applied.outcome,
+            ToolCallDecision::Veto {
+                reason: "writes outside the workspace".to_owned()
+            }
+        );
+        assert_eq!(applied.mutations.last().unwrap().field, "block");
+    }
+
+    #[test]
+    fn unsupported_keys_are_ignored() {
+        let applied = apply_pre_tool_call(
+            &[modify(&[
+                ("tool_name", json!("other")),
+                ("arguments", json!("x")),
+            ])],
+            json!({"path": "/orig"}),
+        );
+
+        assert_eq!(
+            applied.outcome,
+            ToolCallDecision::Proceed {
+                arguments: json!({"path": "/orig"})
+            }
+        );
+        assert!(applied.mutations.is_empty());
+    }
+
+    #[test]
+    fn user_prompt_prepend_and_append_last_writer_wins() {
+        let applied = apply_user_prompt(
+            &[
+                modify(&[("prepend", json!("first")), ("append", json!("tail"))]),
+                modify(&[("prepend", json!("second"))]),
+            ],
+            "hello",
+        );
+
+        assert_eq!(
+            applied.outcome,
+            PromptDecision::Submit {
+                prompt: "second\n\nhello\n\ntail".to_owned()
+            }
+        );
+        assert_eq!(applied.mutations.len(), 3);
+    }
+
+    #[test]
+    fn user_prompt_block_cancels_turn() {
+        let applied = apply_user_prompt(&[execution(HookResult::block("off topic"))], "hello");
+
+        assert_eq!(
+            applied.outcome,
+            PromptDecision::Cancel {
+                reason: "off topic".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn post_tool_call_redacts_output() {
+        let applied = apply_post_tool_call(
+            &[
+                modify(&[("output", json!("token=[REDACTED]"))]),
+                execution(HookResult::Continue),
+            ],
+            json!("token=sk-123"),
+        );
+
+        assert_eq!(applied.outcome, json!("token=[REDACTED]"));
+        assert_eq!(applied.mutations[0].field, "output");
+        assert_eq!(applied.mutations[0].event, HookEvent::PostToolCall);
+    }
+}
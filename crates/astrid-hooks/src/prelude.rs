@@ -20,7 +20,10 @@
 //! ```
 
 // Core hook types
-pub use crate::{Hook, HookEvent, HookHandler};
+pub use crate::{Hook, HookEvent, HookHandler, HookManager};
+
+// Agent hooks
+pub use crate::{AgentHandler, JudgeModel};
 
 // Result type
 pub use crate::HookResult;
@@ -48,8 +48,9 @@ impl HookProfile {
                     .with_name("log-session-start")
                     .with_handler(HookHandler::Command {
                         command: "echo".to_string(),
-                        args: vec!["[ASTRID] Session started: $ASTRID_SESSION_ID".to_string()],
+                        args: vec!["[ASTRID] Session started: {{session_id}}".to_string()],
                         env: std::collections::HashMap::new(),
+                        env_passthrough: Vec::new(),
                         working_dir: None,
                     })
                     .with_fail_action(FailAction::Ignore)
@@ -60,8 +61,9 @@ impl HookProfile {
                     .with_name("log-session-end")
                     .with_handler(HookHandler::Command {
                         command: "echo".to_string(),
-                        args: vec!["[ASTRID] Session ended: $ASTRID_SESSION_ID".to_string()],
+                        args: vec!["[ASTRID] Session ended: {{session_id}}".to_string()],
                         env: std::collections::HashMap::new(),
+                        env_passthrough: Vec::new(),
                         working_dir: None,
                     })
                     .with_fail_action(FailAction::Ignore)
@@ -72,8 +74,9 @@ impl HookProfile {
                     .with_name("log-tool-call")
                     .with_handler(HookHandler::Command {
                         command: "echo".to_string(),
-                        args: vec!["[ASTRID] Tool call: $ASTRID_HOOK_DATA".to_string()],
+                        args: vec!["[ASTRID] Tool call: {{json payload}}".to_string()],
                         env: std::collections::HashMap::new(),
+                        env_passthrough: Vec::new(),
                         working_dir: None,
                     })
                     .with_fail_action(FailAction::Ignore)
@@ -110,6 +113,7 @@ impl HookProfile {
                         .to_string(),
                     ],
                     env: std::collections::HashMap::new(),
+                    env_passthrough: Vec::new(),
                     working_dir: None,
                 })
                 .with_fail_action(FailAction::Block)
@@ -173,6 +177,7 @@ impl HookProfile {
                         r#"echo "[DEBUG] Tool call at $(date): $ASTRID_HOOK_DATA" >> /tmp/astrid-debug.log"#.to_string(),
                     ],
                     env: std::collections::HashMap::new(),
+                    env_passthrough: Vec::new(),
                     working_dir: None,
                 })
                 .with_fail_action(FailAction::Ignore)
@@ -188,6 +193,7 @@ impl HookProfile {
                         r#"echo "[ERROR] Tool error at $(date): $ASTRID_HOOK_DATA" >> /tmp/astrid-debug.log"#.to_string(),
                     ],
                     env: std::collections::HashMap::new(),
+                    env_passthrough: Vec::new(),
                     working_dir: None,
                 })
                 .with_fail_action(FailAction::Ignore)
@@ -2,10 +2,10 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
-use crate::hook::HookEvent;
+use crate::hook::{FailAction, HookEvent};
 
 /// Result of hook execution.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -77,6 +77,9 @@ impl HookResult {
     }
 }
 
+/// Placeholder handlers see instead of a sensitive value.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
 /// Context provided to hooks during execution.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct HookContext {
@@ -98,6 +101,10 @@ pub(crate) struct HookContext {
     /// Previous hook results in the chain.
     #[serde(default)]
     pub previous_results: Vec<HookResult>,
+    /// Data keys whose values are secrets. Handlers see `[REDACTED]` in
+    /// their place.
+    #[serde(skip)]
+    pub sensitive: HashSet<String>,
 }
 
 impl HookContext {
@@ -112,6 +119,7 @@ impl HookContext {
             timestamp: Utc::now(),
             data: HashMap::new(),
             previous_results: Vec::new(),
+            sensitive: HashSet::new(),
         }
     }
 
@@ -136,6 +144,41 @@ impl HookContext {
         self
     }
 
+    /// Add data that must not reach hook handlers, e.g. a credential.
+    #[must_use]
+    pub(crate) fn with_sensitive_data(
+        mut self,
+        key: impl Into<String>,
+        value: serde_json::Value,
+    ) -> Self {
+        let key = key.into();
+        self.sensitive.insert(key.clone());
+        self.data.insert(key, value);
+        self
+    }
+
+    /// Whether the data value under `key` is sensitive.
+    #[must_use]
+    pub(crate) fn is_sensitive(&self, key: &str) -> bool {
+        self.sensitive.contains(key)
+    }
+
+    /// Event data as handlers see it, with sensitive values redacted.
+    #[must_use]
+    pub(crate) fn redacted_data(&self) -> HashMap<String, serde_json::Value> {
+        self.data
+            .iter()
+            .map(|(key, value)| {
+                let value = if self.is_sensitive(key) {
+                    serde_json::Value::String(REDACTED.to_string())
+                } else {
+                    value.clone()
+                };
+                (key.clone(), value)
+            })
+            .collect()
+    }
+
     /// Add a previous hook result.
     pub(crate) fn add_previous_result(&mut self, result: HookResult) {
         self.previous_results.push(result);
@@ -164,7 +207,11 @@ impl HookContext {
     /// Convert context to JSON for passing to handlers.
     #[must_use]
     pub(crate) fn to_json(&self) -> serde_json::Value {
-        serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+        let mut json = serde_json::to_value(self).unwrap_or(serde_json::Value::Null);
+        if let Some(data) = json.get_mut("data") {
+            *data = serde_json::to_value(self.redacted_data()).unwrap_or_default();
+        }
+        json
     }
 
     /// Convert context to environment variables.
@@ -189,7 +236,7 @@ impl HookContext {
 
         // Add data as JSON
         if !self.data.is_empty()
-            && let Ok(json) = serde_json::to_string(&self.data)
+            && let Ok(json) = serde_json::to_string(&self.redacted_data())
         {
             env.insert("ASTRID_HOOK_DATA".to_string(), json);
         }
@@ -211,6 +258,9 @@ pub(crate) struct HookExecution {
     pub completed_at: DateTime<Utc>,
     /// Duration in milliseconds.
     pub duration_ms: u64,
+    /// The hook's failure policy, applied if the execution failed.
+    #[serde(default)]
+    pub fail_action: FailAction,
     /// Result of the execution.
     pub result: HookExecutionResult,
 }
@@ -245,6 +295,11 @@ pub(crate) enum HookExecutionResult {
         /// Reason for skipping.
         reason: String,
     },
+    /// Hook was not run because its circuit breaker is open.
+    CircuitOpen {
+        /// Consecutive failures that opened the breaker.
+        consecutive_failures: u32,
+    },
 }
 
 impl HookExecutionResult {
@@ -254,6 +309,31 @@ impl HookExecutionResult {
         matches!(self, Self::Success { .. })
     }
 
+    /// Check if the hook failed: an error, a timeout, or an open breaker.
+    /// A skipped hook did not fail.
+    #[must_use]
+    pub(crate) fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Self::Failure { .. } | Self::Timeout { .. } | Self::CircuitOpen { .. }
+        )
+    }
+
+    /// Describe a failure for logs and block reasons.
+    #[must_use]
+    pub(crate) fn failure_reason(&self) -> Option<String> {
+        match self {
+            Self::Failure { error, .. } => Some(error.clone()),
+            Self::Timeout { timeout_secs } => Some(format!("timed out after {timeout_secs}s")),
+            Self::CircuitOpen {
+                consecutive_failures,
+            } => Some(format!(
+                "circuit open after {consecutive_failures} consecutive failures"
+            )),
+            Self::Success { .. } | Self::Skipped { .. } => None,
+        }
+    }
+
     /// Get the hook result if successful.
     #[must_use]
     pub(crate) fn hook_result(&self) -> Option<&HookResult> {
@@ -315,6 +395,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sensitive_data_is_redacted() {
+        let ctx = HookContext::new(HookEvent::PreToolCall)
+            .with_data("tool_name", serde_json::json!("http"))
+            .with_sensitive_data("api_key", serde_json::json!("sk-secret"));
+
+        let json = ctx.to_json().to_string();
+        let env = ctx.to_env_vars();
+
+        assert!(!json.contains("sk-secret"));
+        assert!(json.contains(REDACTED));
+        assert!(!env["ASTRID_HOOK_DATA"].contains("sk-secret"));
+        assert!(env["ASTRID_HOOK_DATA"].contains("http"));
+        // The caller still has the real value.
+        assert_eq!(
+            ctx.get_data("api_key"),
+            Some(&serde_json::json!("sk-secret"))
+        );
+    }
+
     #[test]
     fn test_hook_execution_result() {
         let success = HookExecutionResult::Success {
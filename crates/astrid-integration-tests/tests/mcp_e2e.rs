@@ -3,7 +3,9 @@ use std::sync::Arc;
 
 use astrid_capsule::context::CapsuleContext;
 use astrid_capsule::loader::CapsuleLoader;
-use astrid_capsule::manifest::{CapabilitiesDef, CapsuleManifest, McpServerDef, PackageDef};
+use astrid_capsule::manifest::{
+    CapabilitiesDef, CapsuleManifest, HealthDef, McpServerDef, PackageDef,
+};
 use astrid_events::EventBus;
 use astrid_mcp::testing::test_secure_mcp_client;
 use astrid_storage::{MemoryKvStore, ScopedKvStore};
@@ -44,6 +46,8 @@ async fn test_mcp_host_engine_capability_validation() {
             ipc_publish: vec![],
             ipc_subscribe: vec![],
             identity: vec![],
+            call: vec![],
+            secrets: vec![],
             allow_prompt_injection: false,
         },
         env: std::collections::HashMap::default(),
@@ -60,6 +64,8 @@ async fn test_mcp_host_engine_capability_validation() {
         uplinks: vec![],
         interceptors: vec![],
         topics: vec![],
+        cron_jobs: vec![],
+        health: HealthDef::default(),
     };
 
     let loader = CapsuleLoader::new(test_secure_mcp_client());
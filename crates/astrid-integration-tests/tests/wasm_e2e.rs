@@ -3,7 +3,9 @@ use std::sync::Arc;
 
 use astrid_capsule::capsule::CapsuleState;
 use astrid_capsule::loader::CapsuleLoader;
-use astrid_capsule::manifest::{CapabilitiesDef, CapsuleManifest, ComponentDef, PackageDef};
+use astrid_capsule::manifest::{
+    CapabilitiesDef, CapsuleManifest, ComponentDef, HealthDef, PackageDef,
+};
 use astrid_events::EventBus;
 use astrid_mcp::testing::test_secure_mcp_client;
 use astrid_storage::{MemoryKvStore, ScopedKvStore};
@@ -69,6 +71,8 @@ async fn setup_test_capsule(
             ipc_publish: vec![],
             ipc_subscribe: vec!["test.*".into()],
             identity: vec![],
+            call: vec![],
+            secrets: vec![],
             allow_prompt_injection: false,
         },
         env: std::collections::HashMap::default(),
@@ -79,6 +83,8 @@ async fn setup_test_capsule(
         uplinks: vec![],
         interceptors: vec![],
         topics: vec![],
+        cron_jobs: vec![],
+        health: HealthDef::default(),
     };
 
     let loader = CapsuleLoader::new(test_secure_mcp_client());
@@ -170,6 +176,8 @@ async fn setup_test_capsule_with_home(
             ipc_publish: vec![],
             ipc_subscribe: vec![],
             identity: vec![],
+            call: vec![],
+            secrets: vec![],
             allow_prompt_injection: false,
         },
         env: std::collections::HashMap::default(),
@@ -180,6 +188,8 @@ async fn setup_test_capsule_with_home(
         uplinks: vec![],
         interceptors: vec![],
         topics: vec![],
+        cron_jobs: vec![],
+        health: HealthDef::default(),
     };
 
     let loader = CapsuleLoader::new(test_secure_mcp_client());
@@ -3,7 +3,9 @@ use std::sync::Arc;
 
 use astrid_capsule::context::CapsuleContext;
 use astrid_capsule::loader::CapsuleLoader;
-use astrid_capsule::manifest::{CapabilitiesDef, CapsuleManifest, ComponentDef, PackageDef};
+use astrid_capsule::manifest::{
+    CapabilitiesDef, CapsuleManifest, ComponentDef, HealthDef, PackageDef,
+};
 use astrid_events::EventBus;
 use astrid_mcp::testing::test_secure_mcp_client;
 use astrid_storage::{MemoryKvStore, ScopedKvStore};
@@ -91,6 +93,8 @@ async fn test_wasm_capsule_e2e_env_config_injection() {
             ipc_publish: vec![],
             ipc_subscribe: vec![],
             identity: vec![],
+            call: vec![],
+            secrets: vec![],
             allow_prompt_injection: false,
         },
         env,
@@ -101,6 +105,8 @@ async fn test_wasm_capsule_e2e_env_config_injection() {
         uplinks: vec![],
         interceptors: vec![],
         topics: vec![],
+        cron_jobs: vec![],
+        health: HealthDef::default(),
     };
 
     let loader = CapsuleLoader::new(test_secure_mcp_client());
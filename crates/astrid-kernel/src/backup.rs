@@ -0,0 +1,158 @@
+//! Rotating snapshots of the kernel KV store under `~/.astrid/backups/`.
+//!
+//! A scheduled job writes one snapshot every `ASTRID_KV_BACKUP_INTERVAL_SECS`
+//! (default 24 hours, `0` disables it) and keeps the newest
+//! `ASTRID_KV_BACKUP_KEEP` files (default 7). The `BackupState` management
+//! request takes an on-demand snapshot through the same path.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use astrid_storage::KvStore;
+use tracing::{info, warn};
+
+/// File extension of KV snapshot files.
+const SNAPSHOT_EXTENSION: &str = "kvsnap";
+/// Default interval between scheduled snapshots.
+const BACKUP_DEFAULT_INTERVAL: Duration = Duration::from_hours(24);
+/// Default number of snapshots kept on disk.
+const BACKUP_DEFAULT_KEEP: usize = 7;
+/// Delay before the first scheduled snapshot so it never competes with boot.
+const BACKUP_INITIAL_DELAY: Duration = Duration::from_mins(1);
+
+/// Number of snapshots to keep, from `ASTRID_KV_BACKUP_KEEP` (minimum 1).
+pub(crate) fn backup_keep() -> usize {
+    std::env::var("ASTRID_KV_BACKUP_KEEP")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map_or(BACKUP_DEFAULT_KEEP, |n: usize| n.max(1))
+}
+
+/// Interval between scheduled snapshots, or `None` if disabled.
+fn backup_interval() -> Option<Duration> {
+    let interval = std::env::var("ASTRID_KV_BACKUP_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map_or(BACKUP_DEFAULT_INTERVAL, Duration::from_secs);
+    (!interval.is_zero()).then_some(interval)
+}
+
+/// Write a snapshot of `kv` into `dir` and prune all but the newest `keep`.
+///
+/// The snapshot is written to a temporary file and renamed into place, so a
+/// crash mid-write never leaves a truncated `.kvsnap` behind. Returns the
+/// snapshot path and the number of entries it holds.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be created, the snapshot fails,
+/// or the file cannot be persisted.
+pub(crate) async fn write_backup(
+    kv: &dyn KvStore,
+    dir: &Path,
+    keep: usize,
+) -> std::io::Result<(PathBuf, u64)> {
+    std::fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis());
+    // Zero-padded so lexical order is chronological order.
+    let path = dir.join(format!("state-{millis:016}.{SNAPSHOT_EXTENSION}"));
+
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    let entries = {
+        let mut writer = std::io::BufWriter::new(tmp.as_file_mut());
+        let entries = kv
+            .snapshot(&mut writer)
+            .await
+            .map_err(|e| std::io::Error::other(format!("KV snapshot failed: {e}")))?;
+        writer.flush()?;
+        entries
+    };
+    tmp.as_file().sync_all()?;
+    tmp.persist(&path).map_err(|e| e.error)?;
+
+    prune_backups(dir, keep)?;
+    Ok((path, entries))
+}
+
+/// Delete all but the newest `keep` snapshot files in `dir`.
+fn prune_backups(dir: &Path, keep: usize) -> std::io::Result<()> {
+    let mut snapshots: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == SNAPSHOT_EXTENSION))
+        .collect();
+    snapshots.sort();
+    let excess = snapshots.len().saturating_sub(keep);
+    for old in snapshots.into_iter().take(excess) {
+        std::fs::remove_file(&old)?;
+    }
+    Ok(())
+}
+
+/// Spawns the scheduled backup job, unless disabled by configuration.
+pub(crate) fn spawn_backup_job(
+    kv: Arc<dyn KvStore>,
+    dir: PathBuf,
+) -> Option<tokio::task::JoinHandle<()>> {
+    let interval = backup_interval()?;
+    Some(tokio::spawn(async move {
+        tokio::time::sleep(BACKUP_INITIAL_DELAY).await;
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match write_backup(kv.as_ref(), &dir, backup_keep()).await {
+                Ok((path, entries)) => {
+                    info!(path = %path.display(), entries, "Wrote KV state snapshot");
+                },
+                Err(e) => warn!(error = %e, "Scheduled KV state snapshot failed"),
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn write_backup_round_trips_and_rotates() {
+        let dir = tempfile::tempdir().unwrap();
+        let kv = astrid_storage::MemoryKvStore::new();
+        kv.set("system:test", "k", b"v".to_vec()).await.unwrap();
+
+        let mut paths = Vec::new();
+        for _ in 0..4 {
+            let (path, entries) = write_backup(&kv, dir.path(), 2).await.unwrap();
+            assert_eq!(entries, 1);
+            paths.push(path);
+            // Distinct millisecond timestamps.
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+
+        let mut remaining: Vec<PathBuf> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, paths[2..].to_vec());
+
+        let restored = astrid_storage::MemoryKvStore::new();
+        let bytes = std::fs::read(&paths[3]).unwrap();
+        assert_eq!(restored.restore(&mut bytes.as_slice()).await.unwrap(), 1);
+        assert_eq!(
+            restored.get("system:test", "k").await.unwrap(),
+            Some(b"v".to_vec())
+        );
+    }
+}
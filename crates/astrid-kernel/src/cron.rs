@@ -0,0 +1,73 @@
+//! Drives the static `[[cron]]` jobs declared by loaded capsules.
+//!
+//! The schedules live in the capsule registry. This task sleeps until the
+//! next fire time, takes the due jobs, and invokes each capsule's
+//! `astrid_cron_trigger` action with the job name and fire time.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use astrid_capsule::cron::{CRON_TRIGGER_ACTION, CronTick};
+use tracing::{debug, warn};
+
+use crate::Kernel;
+
+/// Longest the task sleeps before checking the registry again, so jobs of
+/// newly loaded or re-enabled capsules are picked up promptly.
+const CRON_MAX_SLEEP: Duration = Duration::from_secs(1);
+
+/// Spawns the task that fires capsule cron jobs.
+pub(crate) fn spawn_cron_scheduler(kernel: Arc<Kernel>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let next = kernel.capsules.read().await.next_cron_fire();
+            let wait = next.map_or(CRON_MAX_SLEEP, |t| {
+                // A fire time already past yields a negative span: don't wait.
+                t.signed_duration_since(chrono::Utc::now())
+                    .to_std()
+                    .unwrap_or(Duration::ZERO)
+                    .min(CRON_MAX_SLEEP)
+            });
+            tokio::time::sleep(wait).await;
+
+            let ticks = kernel
+                .capsules
+                .write()
+                .await
+                .take_due_cron_ticks(chrono::Utc::now());
+            for tick in ticks {
+                fire(&kernel, tick).await;
+            }
+        }
+    })
+}
+
+/// Invoke one tick on its own task, bounded by the capsule's interceptor
+/// semaphore. The registry lock is released before the capsule runs.
+async fn fire(kernel: &Kernel, tick: CronTick) {
+    let Some(capsule) = kernel.capsules.read().await.get(&tick.capsule_id) else {
+        return;
+    };
+    tokio::spawn(async move {
+        let Ok(_permit) = Arc::clone(capsule.interceptor_semaphore())
+            .acquire_owned()
+            .await
+        else {
+            return;
+        };
+        debug!(
+            capsule_id = %tick.capsule_id,
+            job = %tick.name,
+            fire_time = %tick.fire_time,
+            "Firing cron job"
+        );
+        if let Err(e) = capsule.invoke_interceptor(CRON_TRIGGER_ACTION, &tick.payload(), None) {
+            warn!(
+                capsule_id = %tick.capsule_id,
+                job = %tick.name,
+                error = %e,
+                "Cron job failed"
+            );
+        }
+    });
+}
@@ -0,0 +1,163 @@
+//! Diagnostic bundles written when a turn fails, under `~/.astrid/diagnostics/`.
+//!
+//! Off by default. With `ASTRID_DIAGNOSTIC_BUNDLES=1`, every `ErrorOccurred`
+//! event that names a session writes a JSON bundle holding the error and the
+//! log lines captured for that session (see
+//! [`astrid_telemetry::session_logs`]). The newest
+//! `ASTRID_DIAGNOSTIC_BUNDLES_KEEP` bundles are kept (default 20).
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use astrid_events::{AstridEvent, EventBus};
+use serde::Serialize;
+use tracing::{info, warn};
+
+/// File extension of diagnostic bundles.
+const BUNDLE_EXTENSION: &str = "json";
+/// Default number of bundles kept on disk.
+const BUNDLE_DEFAULT_KEEP: usize = 20;
+
+/// Whether bundles are enabled by `ASTRID_DIAGNOSTIC_BUNDLES`.
+fn bundles_enabled() -> bool {
+    std::env::var("ASTRID_DIAGNOSTIC_BUNDLES")
+        .is_ok_and(|v| matches!(v.as_str(), "1" | "true" | "yes"))
+}
+
+/// Number of bundles to keep, from `ASTRID_DIAGNOSTIC_BUNDLES_KEEP` (minimum 1).
+fn bundle_keep() -> usize {
+    std::env::var("ASTRID_DIAGNOSTIC_BUNDLES_KEEP")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map_or(BUNDLE_DEFAULT_KEEP, |n: usize| n.max(1))
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Contents of one bundle file.
+#[derive(Debug, Serialize)]
+struct Bundle<'a> {
+    session_id: String,
+    /// Milliseconds since the Unix epoch.
+    created_at_ms: u64,
+    version: &'static str,
+    code: &'a str,
+    message: &'a str,
+    stack_trace: Option<&'a str>,
+    /// Captured log lines of the session, oldest first.
+    logs: Vec<String>,
+}
+
+/// Write `bundle` into `dir` and prune all but the newest `keep`.
+fn write_bundle(dir: &Path, bundle: &Bundle<'_>, keep: usize) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+
+    let millis = bundle.created_at_ms;
+    // Zero-padded so lexical order is chronological order.
+    let path = dir.join(format!(
+        "turn-{millis:016}-{}.{BUNDLE_EXTENSION}",
+        bundle.session_id
+    ));
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)?;
+    serde_json::to_writer_pretty(tmp.as_file_mut(), bundle)?;
+    tmp.as_file_mut().flush()?;
+    tmp.persist(&path).map_err(|e| e.error)?;
+
+    let mut bundles: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == BUNDLE_EXTENSION))
+        .collect();
+    bundles.sort();
+    let excess = bundles.len().saturating_sub(keep);
+    for old in bundles.into_iter().take(excess) {
+        std::fs::remove_file(&old)?;
+    }
+    Ok(path)
+}
+
+/// Spawns the task that writes bundles on turn failure, unless disabled.
+///
+/// Subscribes before returning, so the subscription is counted by the time
+/// the kernel checks `INTERNAL_SUBSCRIBER_COUNT`.
+pub(crate) fn spawn_bundle_writer(
+    event_bus: &Arc<EventBus>,
+    dir: PathBuf,
+) -> Option<tokio::task::JoinHandle<()>> {
+    if !bundles_enabled() {
+        return None;
+    }
+    let mut receiver = event_bus.subscribe();
+    Some(tokio::spawn(async move {
+        while let Some(event) = receiver.recv().await {
+            let AstridEvent::ErrorOccurred {
+                metadata,
+                code,
+                message,
+                stack_trace,
+            } = &*event
+            else {
+                continue;
+            };
+            let Some(session_id) = metadata.session_id else {
+                continue;
+            };
+            let session_id = session_id.to_string();
+            let bundle = Bundle {
+                logs: astrid_telemetry::session_logs(&session_id),
+                session_id,
+                created_at_ms: now_millis(),
+                version: env!("CARGO_PKG_VERSION"),
+                code,
+                message,
+                stack_trace: stack_trace.as_deref(),
+            };
+            match write_bundle(&dir, &bundle, bundle_keep()) {
+                Ok(path) => info!(path = %path.display(), "Wrote diagnostic bundle"),
+                Err(e) => warn!(error = %e, "Failed to write diagnostic bundle"),
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_bundle_includes_logs_and_rotates() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut paths = Vec::new();
+        for i in 0..3 {
+            let bundle = Bundle {
+                session_id: format!("s{i}"),
+                created_at_ms: now_millis(),
+                version: "test",
+                code: "llm_error",
+                message: "provider timed out",
+                stack_trace: None,
+                logs: vec![format!("WARN astrid: retrying {i}")],
+            };
+            paths.push(write_bundle(dir.path(), &bundle, 2).unwrap());
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        assert!(!paths[0].exists());
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&paths[2]).unwrap()).unwrap();
+        assert_eq!(json["session_id"], "s2");
+        assert_eq!(json["message"], "provider timed out");
+        assert_eq!(json["logs"][0], "WARN astrid: retrying 2");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
@@ -0,0 +1,380 @@
+//! Bridge from MCP server elicitations to frontends over IPC.
+//!
+//! An MCP server may ask the user for input in the middle of a tool call.
+//! [`IpcElicitation`] publishes each request as an
+//! [`IpcPayload::ElicitRequest`] on the topic the capsule `elicit` host
+//! function uses, so every frontend that answers capsule prompts answers
+//! these too. Only the tool call that asked waits for the reply; other tool
+//! calls keep running. A request nobody answers within [`ELICIT_TIMEOUT`]
+//! gets the default response: cancelled when the server marked it required,
+//! dismissed otherwise.
+//!
+//! URL elicitations (OAuth, payments) are shown as a choice whose prompt
+//! carries the link. The server only hears that the flow completed when the
+//! user explicitly picks [`URL_DONE`]; the preselected option is
+//! [`URL_CANCEL`].
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use astrid_core::{
+    ElicitationRequest, ElicitationResponse, ElicitationSchema, UrlElicitationRequest,
+    UrlElicitationResponse,
+};
+use astrid_events::ipc::{IpcMessage, IpcPayload, OnboardingField, OnboardingFieldType};
+use astrid_events::{AstridEvent, EventBus, EventMetadata};
+use astrid_mcp::{ElicitationHandler, UrlElicitationHandler};
+use async_trait::async_trait;
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Topic elicitation requests are published on.
+const ELICIT_TOPIC: &str = "astrid.v1.elicit";
+
+/// How long a server waits for the user before the default response.
+const ELICIT_TIMEOUT: Duration = Duration::from_mins(2);
+
+/// Option confirming a URL flow was completed.
+const URL_DONE: &str = "done";
+
+/// Option abandoning a URL flow.
+const URL_CANCEL: &str = "cancel";
+
+/// Confirm-schema options.
+const CONFIRM_YES: &str = "yes";
+const CONFIRM_NO: &str = "no";
+
+/// What a frontend sent back in an `ElicitResponse`.
+struct Answer {
+    value: Option<String>,
+    values: Option<Vec<String>>,
+}
+
+impl Answer {
+    /// Both fields empty means the user cancelled.
+    fn is_cancel(&self) -> bool {
+        self.value.is_none() && self.values.is_none()
+    }
+}
+
+/// Forwards MCP elicitations to frontends over the event bus.
+#[derive(Clone)]
+pub(crate) struct IpcElicitation {
+    event_bus: Arc<EventBus>,
+    timeout: Duration,
+}
+
+impl IpcElicitation {
+    /// Create a bridge publishing on `event_bus`.
+    pub(crate) fn new(event_bus: Arc<EventBus>) -> Self {
+        Self {
+            event_bus,
+            timeout: ELICIT_TIMEOUT,
+        }
+    }
+
+    /// Override the answer timeout.
+    #[cfg(test)]
+    fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Publish `field` and wait for its answer. `None` on timeout.
+    async fn ask(
+        &self,
+        request_id: Uuid,
+        server_name: &str,
+        field: OnboardingField,
+    ) -> Option<Answer> {
+        // Subscribe before publishing so a fast answer is not missed.
+        let mut receiver = self
+            .event_bus
+            .subscribe_topic(format!("{ELICIT_TOPIC}.response.{request_id}"));
+
+        let message = IpcMessage::new(
+            ELICIT_TOPIC,
+            IpcPayload::ElicitRequest {
+                request_id,
+                capsule_id: format!("mcp:{server_name}"),
+                field,
+            },
+            Uuid::nil(),
+        );
+        self.event_bus.publish(AstridEvent::Ipc {
+            message,
+            metadata: EventMetadata::new("kernel"),
+        });
+        tracing::debug!(server = %server_name, %request_id, "Forwarded MCP elicitation to frontends");
+
+        let wait = async {
+            while let Some(event) = receiver.recv().await {
+                if let AstridEvent::Ipc { message, .. } = &*event
+                    && let IpcPayload::ElicitResponse { value, values, .. } = &message.payload
+                {
+                    return Some(Answer {
+                        value: value.clone(),
+                        values: values.clone(),
+                    });
+                }
+            }
+            None
+        };
+        let answer = tokio::time::timeout(self.timeout, wait)
+            .await
+            .ok()
+            .flatten();
+        if answer.is_none() {
+            tracing::warn!(server = %server_name, %request_id, "MCP elicitation was not answered");
+        }
+        answer
+    }
+}
+
+/// Describe a form elicitation as an onboarding field.
+fn form_field(request: &ElicitationRequest) -> OnboardingField {
+    let (field_type, placeholder, default) = match &request.schema {
+        ElicitationSchema::Text { placeholder, .. } => {
+            (OnboardingFieldType::Text, placeholder.clone(), None)
+        },
+        ElicitationSchema::Secret { placeholder } => {
+            (OnboardingFieldType::Secret, placeholder.clone(), None)
+        },
+        ElicitationSchema::Select {
+            options,
+            multiple: false,
+        } => (
+            OnboardingFieldType::Enum(options.iter().map(|o| o.value.clone()).collect()),
+            None,
+            None,
+        ),
+        ElicitationSchema::Select {
+            options,
+            multiple: true,
+        } => {
+            let values: Vec<&str> = options.iter().map(|o| o.value.as_str()).collect();
+            (OnboardingFieldType::Array, Some(values.join(", ")), None)
+        },
+        ElicitationSchema::Confirm { default } => (
+            OnboardingFieldType::Enum(vec![CONFIRM_YES.to_string(), CONFIRM_NO.to_string()]),
+            None,
+            Some(if *default { CONFIRM_YES } else { CONFIRM_NO }.to_string()),
+        ),
+    };
+    OnboardingField {
+        key: "input".to_string(),
+        prompt: request.message.clone(),
+        description: Some(format!("Requested by MCP server '{}'", request.server_name)),
+        field_type,
+        default,
+        placeholder,
+    }
+}
+
+/// Convert a frontend answer into the value the form schema expects.
+fn form_value(schema: &ElicitationSchema, answer: Answer) -> Value {
+    match schema {
+        ElicitationSchema::Confirm { .. } => {
+            Value::Bool(answer.value.as_deref() == Some(CONFIRM_YES))
+        },
+        ElicitationSchema::Select { multiple: true, .. } => {
+            Value::from(answer.values.unwrap_or_default())
+        },
+        _ => Value::from(answer.value.unwrap_or_default()),
+    }
+}
+
+/// Describe a URL elicitation as a done/cancel choice.
+fn url_field(request: &UrlElicitationRequest) -> OnboardingField {
+    OnboardingField {
+        key: "url".to_string(),
+        prompt: format!("{}\n{}", request.message, request.url),
+        description: Some(format!(
+            "MCP server '{}' asks you to open this link. Choose '{URL_DONE}' once you have \
+             finished there.",
+            request.server_name
+        )),
+        field_type: OnboardingFieldType::Enum(vec![URL_DONE.to_string(), URL_CANCEL.to_string()]),
+        default: Some(URL_CANCEL.to_string()),
+        placeholder: None,
+    }
+}
+
+#[async_trait]
+impl ElicitationHandler for IpcElicitation {
+    async fn handle_elicitation(&self, request: ElicitationRequest) -> ElicitationResponse {
+        let field = form_field(&request);
+        let answer = self
+            .ask(request.request_id, &request.server_name, field)
+            .await;
+        match answer {
+            Some(answer) if !answer.is_cancel() => {
+                ElicitationResponse::submit(request.request_id, form_value(&request.schema, answer))
+            },
+            Some(_) => ElicitationResponse::cancel(request.request_id),
+            None if request.required => ElicitationResponse::cancel(request.request_id),
+            None => ElicitationResponse::dismiss(request.request_id),
+        }
+    }
+}
+
+#[async_trait]
+impl UrlElicitationHandler for IpcElicitation {
+    async fn handle_url_elicitation(
+        &self,
+        request: UrlElicitationRequest,
+    ) -> UrlElicitationResponse {
+        let field = url_field(&request);
+        let answer = self
+            .ask(request.request_id, &request.server_name, field)
+            .await;
+        if answer.is_some_and(|a| a.value.as_deref() == Some(URL_DONE)) {
+            UrlElicitationResponse::completed(request.request_id)
+        } else {
+            UrlElicitationResponse::not_completed(request.request_id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astrid_core::{ElicitationAction, SelectOption};
+
+    /// Answer the next elicit request on `bus` with `reply`, returning the
+    /// field that was shown.
+    fn frontend(
+        bus: &Arc<EventBus>,
+        reply: impl FnOnce(&OnboardingField) -> (Option<String>, Option<Vec<String>>) + Send + 'static,
+    ) -> tokio::task::JoinHandle<OnboardingField> {
+        let mut requests = bus.subscribe_topic(ELICIT_TOPIC);
+        let bus = Arc::clone(bus);
+        tokio::spawn(async move {
+            loop {
+                let event = requests.recv().await.unwrap();
+                if let AstridEvent::Ipc { message, .. } = &*event
+                    && let IpcPayload::ElicitRequest {
+                        request_id, field, ..
+                    } = &message.payload
+                {
+                    let (value, values) = reply(field);
+                    let response = IpcMessage::new(
+                        format!("{ELICIT_TOPIC}.response.{request_id}"),
+                        IpcPayload::ElicitResponse {
+                            request_id: *request_id,
+                            value,
+                            values,
+                        },
+                        Uuid::nil(),
+                    );
+                    bus.publish(AstridEvent::Ipc {
+                        message: response,
+                        metadata: EventMetadata::new("test"),
+                    });
+                    return field.clone();
+                }
+            }
+        })
+    }
+
+    fn submitted(response: ElicitationResponse) -> Value {
+        match response.action {
+            ElicitationAction::Submit { value } => value,
+            other => panic!("expected submit, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn text_answer_is_submitted() {
+        let bus = Arc::new(EventBus::new());
+        let shown = frontend(&bus, |_| (Some("Ada".to_string()), None));
+        let bridge = IpcElicitation::new(Arc::clone(&bus));
+
+        let response = bridge
+            .handle_elicitation(ElicitationRequest::new("github", "Your name?"))
+            .await;
+        assert_eq!(submitted(response), Value::from("Ada"));
+
+        let field = shown.await.unwrap();
+        assert_eq!(field.prompt, "Your name?");
+        assert_eq!(field.field_type, OnboardingFieldType::Text);
+    }
+
+    #[tokio::test]
+    async fn confirm_and_multi_select_answers_are_typed() {
+        let bus = Arc::new(EventBus::new());
+        let bridge = IpcElicitation::new(Arc::clone(&bus));
+
+        let shown = frontend(&bus, |_| (Some(CONFIRM_YES.to_string()), None));
+        let request = ElicitationRequest::new("s", "Proceed?")
+            .with_schema(ElicitationSchema::Confirm { default: false });
+        assert_eq!(
+            submitted(bridge.handle_elicitation(request).await),
+            Value::Bool(true)
+        );
+        assert_eq!(shown.await.unwrap().default.as_deref(), Some(CONFIRM_NO));
+
+        let frontend_reply = frontend(&bus, |_| {
+            (None, Some(vec!["a".to_string(), "c".to_string()]))
+        });
+        let request =
+            ElicitationRequest::new("s", "Pick some").with_schema(ElicitationSchema::Select {
+                options: vec![
+                    SelectOption::new("a", "A"),
+                    SelectOption::new("b", "B"),
+                    SelectOption::new("c", "C"),
+                ],
+                multiple: true,
+            });
+        assert_eq!(
+            submitted(bridge.handle_elicitation(request).await),
+            serde_json::json!(["a", "c"])
+        );
+        assert_eq!(
+            frontend_reply.await.unwrap().placeholder.as_deref(),
+            Some("a, b, c")
+        );
+    }
+
+    #[tokio::test]
+    async fn cancel_and_timeout_use_default_responses() {
+        let bus = Arc::new(EventBus::new());
+        let bridge = IpcElicitation::new(Arc::clone(&bus)).with_timeout(Duration::from_millis(50));
+
+        let _cancel = frontend(&bus, |_| (None, None));
+        let response = bridge
+            .handle_elicitation(ElicitationRequest::new("s", "x").optional())
+            .await;
+        assert!(matches!(response.action, ElicitationAction::Cancel));
+
+        // Nobody answers.
+        let response = bridge
+            .handle_elicitation(ElicitationRequest::new("s", "x"))
+            .await;
+        assert!(matches!(response.action, ElicitationAction::Cancel));
+        let response = bridge
+            .handle_elicitation(ElicitationRequest::new("s", "x").optional())
+            .await;
+        assert!(matches!(response.action, ElicitationAction::Dismiss));
+    }
+
+    #[tokio::test]
+    async fn url_flow_needs_explicit_confirmation() {
+        let bus = Arc::new(EventBus::new());
+        let bridge = IpcElicitation::new(Arc::clone(&bus)).with_timeout(Duration::from_millis(50));
+        let request =
+            || UrlElicitationRequest::new("auth", "https://auth.example.com/login", "Sign in");
+
+        let shown = frontend(&bus, |_| (Some(URL_DONE.to_string()), None));
+        assert!(bridge.handle_url_elicitation(request()).await.completed);
+        let field = shown.await.unwrap();
+        assert!(field.prompt.contains("https://auth.example.com/login"));
+        assert_eq!(field.default.as_deref(), Some(URL_CANCEL));
+
+        let _cancel = frontend(&bus, |field| (field.default.clone(), None));
+        assert!(!bridge.handle_url_elicitation(request()).await.completed);
+
+        // Unanswered flows never count as completed.
+        assert!(!bridge.handle_url_elicitation(request()).await.completed);
+    }
+}
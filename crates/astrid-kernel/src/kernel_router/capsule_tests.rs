@@ -0,0 +1,139 @@
+//! Per-capsule management requests driven through the request router.
+//!
+//! `GetCapsuleLogs` and `WipeCapsuleState` act on the caller's own slice
+//! of a capsule: the caller's `log/<capsule>/` directory and the caller's
+//! `{principal}:capsule:{id}` KV namespace. These tests publish requests
+//! on `astrid.v1.request.*` the way the socket bridge does, with the
+//! connecting principal stamped on the message.
+
+use std::sync::Arc;
+
+use astrid_core::dirs::AstridHome;
+use astrid_core::principal::PrincipalId;
+use astrid_core::profile::PrincipalProfile;
+use astrid_events::ipc::{IpcMessage, IpcPayload};
+use astrid_events::kernel_api::KernelRequest;
+use astrid_storage::KvStore as _;
+use tempfile::TempDir;
+
+use crate::Kernel;
+
+async fn fixture() -> (TempDir, Arc<Kernel>) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let home = AstridHome::from_path(dir.path());
+    let kernel = crate::test_kernel_with_home(home).await;
+    drop(super::spawn_kernel_router(Arc::clone(&kernel)));
+    for name in ["alice", "bob"] {
+        let profile = PrincipalProfile {
+            groups: vec!["admin".to_string()],
+            ..PrincipalProfile::default()
+        };
+        let path = PrincipalProfile::path_for(&kernel.astrid_home, &pid(name));
+        profile.save_to_path(&path).expect("seed profile");
+    }
+    (dir, kernel)
+}
+
+fn pid(name: &str) -> PrincipalId {
+    PrincipalId::new(name).unwrap()
+}
+
+/// Publish `req` on the request topic as `caller` and wait for the
+/// response.
+async fn send_request(
+    kernel: &Arc<Kernel>,
+    caller: &PrincipalId,
+    req: &KernelRequest,
+) -> serde_json::Value {
+    let topic = "astrid.v1.request.capsule";
+    let mut rx = kernel
+        .event_bus
+        .subscribe_topic("astrid.v1.response.capsule");
+
+    let payload = serde_json::to_value(req).expect("serialize request");
+    let mut msg = IpcMessage::new(topic, IpcPayload::RawJson(payload), kernel.session_id.0);
+    msg.principal = Some(caller.as_str().to_string());
+    let _ = kernel.event_bus.publish(astrid_events::AstridEvent::Ipc {
+        metadata: astrid_events::EventMetadata::new("test"),
+        message: msg,
+    });
+
+    tokio::time::timeout(std::time::Duration::from_secs(2), async {
+        loop {
+            let event = rx.recv().await.expect("response event");
+            if let astrid_events::AstridEvent::Ipc { message, .. } = &*event
+                && let IpcPayload::RawJson(val) = &message.payload
+            {
+                return val.clone();
+            }
+        }
+    })
+    .await
+    .expect("response within 2s")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn wipe_capsule_state_only_clears_the_callers_namespace() {
+    let (_dir, kernel) = fixture().await;
+    for name in ["alice", "bob", PrincipalId::default().as_str()] {
+        kernel
+            .kv
+            .set(&format!("{name}:capsule:demo"), "k", b"v".to_vec())
+            .await
+            .unwrap();
+    }
+
+    let resp = send_request(
+        &kernel,
+        &pid("alice"),
+        &KernelRequest::WipeCapsuleState {
+            name: "demo".to_string(),
+        },
+    )
+    .await;
+    assert_eq!(resp["data"]["removed"], 1, "unexpected response: {resp}");
+
+    assert!(
+        kernel
+            .kv
+            .list_keys("alice:capsule:demo")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        kernel.kv.list_keys("bob:capsule:demo").await.unwrap(),
+        vec!["k"]
+    );
+    let default_ns = format!("{}:capsule:demo", PrincipalId::default());
+    assert_eq!(kernel.kv.list_keys(&default_ns).await.unwrap(), vec!["k"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn capsule_logs_reads_the_callers_log_dir() {
+    let (_dir, kernel) = fixture().await;
+    for name in ["alice", "bob"] {
+        let dir = kernel
+            .astrid_home
+            .principal_home(&pid(name))
+            .log_dir()
+            .join("demo");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("2026-01-01.log"), format!("{name} line\n")).unwrap();
+    }
+
+    let resp = send_request(
+        &kernel,
+        &pid("bob"),
+        &KernelRequest::GetCapsuleLogs {
+            name: "demo".to_string(),
+            lines: None,
+        },
+    )
+    .await;
+    assert_eq!(
+        resp["data"]["lines"],
+        serde_json::json!(["bob line"]),
+        "unexpected response: {resp}"
+    );
+}
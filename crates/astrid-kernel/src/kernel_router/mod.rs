@@ -1,5 +1,7 @@
 /// Admin management API dispatcher (issue #672, Layer 6).
 pub mod admin;
+#[cfg(test)]
+mod capsule_tests;
 
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
@@ -253,6 +255,61 @@ async fn handle_request(
             kernel.load_all_capsules().await;
             KernelResponse::Success(serde_json::json!({"status": "reloaded"}))
         },
+        KernelRequest::ReloadCapsule { name } => match parse_capsule_id(&name) {
+            Ok(id) => match kernel.restart_capsule(&id).await {
+                Ok(()) => {
+                    info!(capsule_id = %id, "Reloaded capsule on request");
+                    KernelResponse::Success(serde_json::json!({"status": "reloaded"}))
+                },
+                Err(e) => KernelResponse::Error(format!("Reload failed: {e}")),
+            },
+            Err(res) => res,
+        },
+        KernelRequest::UnloadCapsule { name } => match kernel.unload_capsule(&name).await {
+            Ok(()) => {
+                info!(capsule = %name, "Unloaded capsule on request");
+                KernelResponse::Success(serde_json::json!({"status": "unloaded"}))
+            },
+            Err(e) => KernelResponse::Error(format!("Unload failed: {e}")),
+        },
+        KernelRequest::GetCapsuleLogs { name, lines } => match parse_capsule_id(&name) {
+            Ok(id) => {
+                let lines = lines
+                    .unwrap_or(DEFAULT_CAPSULE_LOG_LINES)
+                    .min(MAX_CAPSULE_LOG_LINES);
+                match kernel.capsule_logs(&caller, &id, lines) {
+                    Ok(lines) => KernelResponse::Success(serde_json::json!({
+                        "name": name,
+                        "lines": lines,
+                    })),
+                    Err(e) => KernelResponse::Error(format!("Reading capsule log failed: {e}")),
+                }
+            },
+            Err(res) => res,
+        },
+        KernelRequest::WipeCapsuleState { name } => match parse_capsule_id(&name) {
+            Ok(id) => match kernel.wipe_capsule_state(&caller, &id).await {
+                Ok(removed) => KernelResponse::Success(serde_json::json!({ "removed": removed })),
+                Err(e) => KernelResponse::Error(format!("Wipe failed: {e}")),
+            },
+            Err(res) => res,
+        },
+        KernelRequest::ListCronJobs => {
+            let jobs = kernel.capsules.read().await.cron_jobs();
+            KernelResponse::Success(serde_json::json!({ "jobs": jobs }))
+        },
+        KernelRequest::SetCronJobEnabled { name, job, enabled } => match parse_capsule_id(&name) {
+            Ok(id) => match kernel
+                .capsules
+                .write()
+                .await
+                .set_cron_enabled(&id, &job, enabled)
+            {
+                Ok(()) => KernelResponse::Success(serde_json::json!({ "enabled": enabled })),
+                Err(e) => KernelResponse::Error(format!("Cron update failed: {e}")),
+            },
+            Err(res) => res,
+        },
         KernelRequest::Shutdown { reason } => {
             info!(
                 reason = reason.as_deref().unwrap_or("none"),
@@ -281,9 +338,87 @@ async fn handle_request(
                 connected_clients: u32::try_from(kernel.total_connection_count())
                     .unwrap_or(u32::MAX),
                 loaded_capsules: loaded,
+                kv_ops: kernel
+                    .kv
+                    .metrics()
+                    .ops
+                    .into_iter()
+                    .map(|m| astrid_events::kernel_api::KvOpStats {
+                        op: m.op.as_str().to_string(),
+                        count: m.count,
+                        errors: m.errors,
+                        slow: m.slow,
+                        latency_p50_us: m.latency_p50_us,
+                        latency_p95_us: m.latency_p95_us,
+                        latency_p99_us: m.latency_p99_us,
+                        bytes_total: m.bytes_total,
+                    })
+                    .collect(),
             };
             KernelResponse::Status(status)
         },
+        KernelRequest::BackupState => {
+            let dir = kernel.astrid_home.backups_dir();
+            match crate::backup::write_backup(
+                kernel.kv.as_ref(),
+                &dir,
+                crate::backup::backup_keep(),
+            )
+            .await
+            {
+                Ok((path, entries)) => {
+                    info!(path = %path.display(), entries, "Wrote KV state snapshot on request");
+                    KernelResponse::Success(serde_json::json!({
+                        "path": path.display().to_string(),
+                        "entries": entries,
+                    }))
+                },
+                Err(e) => KernelResponse::Error(format!("Backup failed: {e}")),
+            }
+        },
+        KernelRequest::GetSessionLogs { session_id } => {
+            let lines = kernel.session_logs(&session_id);
+            KernelResponse::Success(serde_json::json!({
+                "session_id": session_id,
+                "lines": lines,
+            }))
+        },
+        KernelRequest::SetLogLevel { directive } => match astrid_telemetry::set_level(&directive) {
+            Ok(filter) => {
+                info!(%filter, "Log filter changed on request");
+                KernelResponse::Success(serde_json::json!({ "filter": filter }))
+            },
+            Err(e) => KernelResponse::Error(format!("Log filter unchanged: {e}")),
+        },
+        KernelRequest::StartIdentityLink { user_id, platform } => {
+            start_identity_link(kernel, &user_id, &platform).await
+        },
+        KernelRequest::CompleteIdentityLink {
+            platform,
+            platform_user_id,
+            code,
+        } => match kernel
+            .linking
+            .complete_link(&platform, &platform_user_id, &code)
+            .await
+        {
+            Ok(link) => {
+                info!(
+                    platform = %link.platform,
+                    user = %link.astrid_user_id,
+                    "Linked platform account"
+                );
+                KernelResponse::Success(serde_json::json!(link))
+            },
+            Err(e) => KernelResponse::Error(format!("Link failed: {e}")),
+        },
+        KernelRequest::RevokeIdentityLink {
+            platform,
+            platform_user_id,
+        } => match kernel.linking.revoke(&platform, &platform_user_id).await {
+            Ok(revoked) => KernelResponse::Success(serde_json::json!({ "revoked": revoked })),
+            Err(e) => KernelResponse::Error(format!("Unlink failed: {e}")),
+        },
         KernelRequest::GetCapsuleMetadata => {
             let reg = kernel.capsules.read().await;
             let mut entries = Vec::new();
@@ -291,6 +426,8 @@ async fn handle_request(
                 let manifest = capsule.manifest();
                 entries.push(astrid_events::kernel_api::CapsuleMetadataEntry {
                     name: manifest.package.name.clone(),
+                    version: manifest.package.version.clone(),
+                    state: capsule.state().to_string(),
                     interceptor_events: manifest
                         .interceptors
                         .iter()
@@ -368,16 +505,61 @@ fn rate_limit_for_request(req: &KernelRequest) -> (&'static str, Option<u32>) {
     (kernel_request_method(req), rate_limit_max(req))
 }
 
+/// Lines returned by `GetCapsuleLogs` when the request does not say.
+const DEFAULT_CAPSULE_LOG_LINES: usize = 100;
+
+/// Upper bound on the lines a single `GetCapsuleLogs` returns.
+const MAX_CAPSULE_LOG_LINES: usize = 10_000;
+
+/// Validate a capsule name from a request, or build the error response.
+fn parse_capsule_id(name: &str) -> Result<astrid_capsule::capsule::CapsuleId, KernelResponse> {
+    astrid_capsule::capsule::CapsuleId::new(name)
+        .map_err(|e| KernelResponse::Error(format!("Invalid capsule name: {e}")))
+}
+
+/// Issue a link code for the Astrid user with UUID `user_id`.
+async fn start_identity_link(
+    kernel: &crate::Kernel,
+    user_id: &str,
+    platform: &str,
+) -> KernelResponse {
+    let Ok(id) = uuid::Uuid::parse_str(user_id) else {
+        return KernelResponse::Error(format!("Invalid user id: {user_id}"));
+    };
+    let user = match kernel.identity_store.get_user(id).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return KernelResponse::Error(format!("User not found: {id}")),
+        Err(e) => return KernelResponse::Error(format!("Identity lookup failed: {e}")),
+    };
+    match kernel.linking.start_link(&user, platform).await {
+        Ok(code) => KernelResponse::Success(serde_json::json!(code)),
+        Err(e) => KernelResponse::Error(format!("Link failed: {e}")),
+    }
+}
+
 /// Return the max-per-minute rate limit for a request type, if any.
 fn rate_limit_max(req: &KernelRequest) -> Option<u32> {
     match req {
-        KernelRequest::ReloadCapsules => Some(5),
-        KernelRequest::InstallCapsule { .. } | KernelRequest::ApproveCapability { .. } => Some(10),
+        KernelRequest::ReloadCapsules
+        | KernelRequest::ReloadCapsule { .. }
+        | KernelRequest::UnloadCapsule { .. }
+        | KernelRequest::WipeCapsuleState { .. } => Some(5),
+        KernelRequest::InstallCapsule { .. }
+        | KernelRequest::ApproveCapability { .. }
+        | KernelRequest::SetLogLevel { .. }
+        | KernelRequest::SetCronJobEnabled { .. }
+        | KernelRequest::StartIdentityLink { .. }
+        | KernelRequest::RevokeIdentityLink { .. } => Some(10),
         KernelRequest::Shutdown { .. } => Some(1),
+        KernelRequest::BackupState => Some(2),
+        KernelRequest::CompleteIdentityLink { .. } => Some(30),
         KernelRequest::ListCapsules
         | KernelRequest::GetCommands
         | KernelRequest::GetCapsuleMetadata
-        | KernelRequest::GetStatus => None,
+        | KernelRequest::GetStatus
+        | KernelRequest::GetSessionLogs { .. }
+        | KernelRequest::GetCapsuleLogs { .. }
+        | KernelRequest::ListCronJobs => None,
     }
 }
 
@@ -418,20 +600,46 @@ pub fn required_capability(req: &KernelRequest, scope: AuthorityScope) -> &'stat
     match (req, scope) {
         (KernelRequest::Shutdown { .. }, _) => "system:shutdown",
         (KernelRequest::GetStatus, _) => "system:status",
-        (KernelRequest::ReloadCapsules, AuthorityScope::Self_) => "self:capsule:reload",
-        (KernelRequest::ReloadCapsules, _) => "capsule:reload",
+        (KernelRequest::BackupState, _) => "system:backup",
+        (KernelRequest::GetSessionLogs { .. } | KernelRequest::GetCapsuleLogs { .. }, _) => {
+            "system:logs"
+        },
+        (KernelRequest::SetLogLevel { .. }, _) => "system:log_level",
+        (
+            KernelRequest::StartIdentityLink { .. } | KernelRequest::CompleteIdentityLink { .. },
+            _,
+        ) => "identity:link",
+        (KernelRequest::RevokeIdentityLink { .. }, _) => "identity:unlink",
+        (
+            KernelRequest::ReloadCapsules
+            | KernelRequest::ReloadCapsule { .. }
+            | KernelRequest::UnloadCapsule { .. },
+            AuthorityScope::Self_,
+        ) => "self:capsule:reload",
+        (
+            KernelRequest::ReloadCapsules
+            | KernelRequest::ReloadCapsule { .. }
+            | KernelRequest::UnloadCapsule { .. },
+            _,
+        ) => "capsule:reload",
+        (KernelRequest::WipeCapsuleState { .. }, AuthorityScope::Self_) => "self:capsule:wipe",
+        (KernelRequest::WipeCapsuleState { .. }, _) => "capsule:wipe",
+        (KernelRequest::SetCronJobEnabled { .. }, AuthorityScope::Self_) => "self:capsule:cron",
+        (KernelRequest::SetCronJobEnabled { .. }, _) => "capsule:cron",
         (KernelRequest::InstallCapsule { .. }, AuthorityScope::Self_) => "self:capsule:install",
         (KernelRequest::InstallCapsule { .. }, _) => "capsule:install",
         (
             KernelRequest::ListCapsules
             | KernelRequest::GetCommands
-            | KernelRequest::GetCapsuleMetadata,
+            | KernelRequest::GetCapsuleMetadata
+            | KernelRequest::ListCronJobs,
             AuthorityScope::Self_,
         ) => "self:capsule:list",
         (
             KernelRequest::ListCapsules
             | KernelRequest::GetCommands
-            | KernelRequest::GetCapsuleMetadata,
+            | KernelRequest::GetCapsuleMetadata
+            | KernelRequest::ListCronJobs,
             _,
         ) => "capsule:list",
         (KernelRequest::ApproveCapability { .. }, _) => "self:approval:respond",
@@ -444,6 +652,12 @@ pub fn required_capability(req: &KernelRequest, scope: AuthorityScope) -> &'stat
 pub fn kernel_request_method(req: &KernelRequest) -> &'static str {
     match req {
         KernelRequest::ReloadCapsules => "ReloadCapsules",
+        KernelRequest::ReloadCapsule { .. } => "ReloadCapsule",
+        KernelRequest::UnloadCapsule { .. } => "UnloadCapsule",
+        KernelRequest::GetCapsuleLogs { .. } => "GetCapsuleLogs",
+        KernelRequest::WipeCapsuleState { .. } => "WipeCapsuleState",
+        KernelRequest::ListCronJobs => "ListCronJobs",
+        KernelRequest::SetCronJobEnabled { .. } => "SetCronJobEnabled",
         KernelRequest::InstallCapsule { .. } => "InstallCapsule",
         KernelRequest::ApproveCapability { .. } => "ApproveCapability",
         KernelRequest::ListCapsules => "ListCapsules",
@@ -451,6 +665,12 @@ pub fn kernel_request_method(req: &KernelRequest) -> &'static str {
         KernelRequest::GetCapsuleMetadata => "GetCapsuleMetadata",
         KernelRequest::Shutdown { .. } => "Shutdown",
         KernelRequest::GetStatus => "GetStatus",
+        KernelRequest::BackupState => "BackupState",
+        KernelRequest::GetSessionLogs { .. } => "GetSessionLogs",
+        KernelRequest::SetLogLevel { .. } => "SetLogLevel",
+        KernelRequest::StartIdentityLink { .. } => "StartIdentityLink",
+        KernelRequest::CompleteIdentityLink { .. } => "CompleteIdentityLink",
+        KernelRequest::RevokeIdentityLink { .. } => "RevokeIdentityLink",
     }
 }
 
@@ -665,7 +885,46 @@ mod tests {
         vec![
             KernelRequest::Shutdown { reason: None },
             KernelRequest::GetStatus,
+            KernelRequest::BackupState,
+            KernelRequest::GetSessionLogs {
+                session_id: "s".to_string(),
+            },
+            KernelRequest::SetLogLevel {
+                directive: "debug".to_string(),
+            },
+            KernelRequest::StartIdentityLink {
+                user_id: "u".to_string(),
+                platform: "discord".to_string(),
+            },
+            KernelRequest::CompleteIdentityLink {
+                platform: "discord".to_string(),
+                platform_user_id: "42".to_string(),
+                code: "c".to_string(),
+            },
+            KernelRequest::RevokeIdentityLink {
+                platform: "discord".to_string(),
+                platform_user_id: "42".to_string(),
+            },
             KernelRequest::ReloadCapsules,
+            KernelRequest::ReloadCapsule {
+                name: "c".to_string(),
+            },
+            KernelRequest::UnloadCapsule {
+                name: "c".to_string(),
+            },
+            KernelRequest::GetCapsuleLogs {
+                name: "c".to_string(),
+                lines: None,
+            },
+            KernelRequest::WipeCapsuleState {
+                name: "c".to_string(),
+            },
+            KernelRequest::ListCronJobs,
+            KernelRequest::SetCronJobEnabled {
+                name: "c".to_string(),
+                job: "j".to_string(),
+                enabled: false,
+            },
             KernelRequest::InstallCapsule {
                 source: "x".to_string(),
                 workspace: false,
@@ -704,10 +963,90 @@ mod tests {
             required_capability(&KernelRequest::GetStatus, AuthorityScope::Self_),
             "system:status"
         );
+        assert_eq!(
+            required_capability(&KernelRequest::BackupState, AuthorityScope::Self_),
+            "system:backup"
+        );
+        assert_eq!(
+            required_capability(
+                &KernelRequest::GetSessionLogs {
+                    session_id: String::new()
+                },
+                AuthorityScope::Self_
+            ),
+            "system:logs"
+        );
+        assert_eq!(
+            required_capability(
+                &KernelRequest::SetLogLevel {
+                    directive: String::new()
+                },
+                AuthorityScope::Self_
+            ),
+            "system:log_level"
+        );
+        assert_eq!(
+            required_capability(
+                &KernelRequest::CompleteIdentityLink {
+                    platform: String::new(),
+                    platform_user_id: String::new(),
+                    code: String::new(),
+                },
+                AuthorityScope::Self_
+            ),
+            "identity:link"
+        );
+        assert_eq!(
+            required_capability(
+                &KernelRequest::RevokeIdentityLink {
+                    platform: String::new(),
+                    platform_user_id: String::new(),
+                },
+                AuthorityScope::Self_
+            ),
+            "identity:unlink"
+        );
         assert_eq!(
             required_capability(&KernelRequest::ReloadCapsules, AuthorityScope::Self_),
             "self:capsule:reload"
         );
+        assert_eq!(
+            required_capability(
+                &KernelRequest::ReloadCapsule {
+                    name: String::new()
+                },
+                AuthorityScope::Self_
+            ),
+            "self:capsule:reload"
+        );
+        assert_eq!(
+            required_capability(
+                &KernelRequest::UnloadCapsule {
+                    name: String::new()
+                },
+                AuthorityScope::Self_
+            ),
+            "self:capsule:reload"
+        );
+        assert_eq!(
+            required_capability(
+                &KernelRequest::GetCapsuleLogs {
+                    name: String::new(),
+                    lines: None,
+                },
+                AuthorityScope::Self_
+            ),
+            "system:logs"
+        );
+        assert_eq!(
+            required_capability(
+                &KernelRequest::WipeCapsuleState {
+                    name: String::new()
+                },
+                AuthorityScope::Self_
+            ),
+            "self:capsule:wipe"
+        );
         assert_eq!(
             required_capability(
                 &KernelRequest::InstallCapsule {
@@ -730,6 +1069,21 @@ mod tests {
             required_capability(&KernelRequest::GetCapsuleMetadata, AuthorityScope::Self_),
             "self:capsule:list"
         );
+        assert_eq!(
+            required_capability(&KernelRequest::ListCronJobs, AuthorityScope::Self_),
+            "self:capsule:list"
+        );
+        assert_eq!(
+            required_capability(
+                &KernelRequest::SetCronJobEnabled {
+                    name: String::new(),
+                    job: String::new(),
+                    enabled: true,
+                },
+                AuthorityScope::Self_
+            ),
+            "self:capsule:cron"
+        );
         assert_eq!(
             required_capability(
                 &KernelRequest::ApproveCapability {
@@ -11,20 +11,26 @@
 //! is to instantiate `astrid_events::EventBus`, load `.capsule` files into
 //! the Extism sandbox, and route IPC bytes between them.
 
+mod backup;
+mod cron;
+mod diagnostics;
+mod elicitation;
 /// The Management API router listening to the `EventBus`.
 pub mod kernel_router;
+mod metrics;
 /// The Unix Domain Socket manager.
 pub mod socket;
 
 use arc_swap::ArcSwap;
 use astrid_audit::AuditLog;
-use astrid_capabilities::{CapabilityStore, DirHandle};
+use astrid_capabilities::{CapabilityStore, DirHandle, workspace_key};
+use astrid_capsule::manifest::RestartPolicy;
 use astrid_capsule::profile_cache::PrincipalProfileCache;
 use astrid_capsule::registry::CapsuleRegistry;
 use astrid_core::SessionId;
 use astrid_core::groups::GroupConfig;
 use astrid_core::principal::PrincipalId;
-use astrid_crypto::KeyPair;
+use astrid_crypto::{KeyPair, KeyStore};
 use astrid_events::EventBus;
 use astrid_mcp::{McpClient, SecureMcpClient, ServerManager, ServersConfig};
 use astrid_vfs::{HostVfs, OverlayVfsRegistry, Vfs};
@@ -34,6 +40,12 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use tokio::sync::{Mutex, RwLock};
 
+/// The kernel's persistent KV store: `SurrealKV`, encrypted at rest, with
+/// per-operation metrics.
+pub type KernelKvStore = astrid_storage::InstrumentedKvStore<
+    astrid_storage::EncryptedKvStore<astrid_storage::SurrealKvStore>,
+>;
+
 /// The core Operating System Kernel.
 pub struct Kernel {
     /// The unique identifier for this kernel session.
@@ -65,6 +77,13 @@ pub struct Kernel {
     pub vfs_root_handle: DirHandle,
     /// The physical path the VFS is mounted to.
     pub workspace_root: PathBuf,
+    /// Workspace mode from `workspace.mode` in config, handed to every
+    /// capsule so spawned commands naming paths outside the workspace are
+    /// refused (safe) or sent for approval (guided, autonomous).
+    workspace_mode: astrid_workspace::WorkspaceMode,
+    /// `audit.seal_file_writes_max_bytes` from config: capsule file writes
+    /// up to this size have their content sealed into the audit log.
+    audit_seal_writes_up_to: u64,
     /// The principal home resources directory (`~/.astrid/home/{principal}/`).
     /// Capsules declaring `fs_read = ["home://"]` can read files under this
     /// root. Scoped to the principal's home so that keys, databases, and
@@ -76,7 +95,13 @@ pub struct Kernel {
     /// The natively bound Unix Socket for the CLI proxy.
     pub cli_socket_listener: Option<Arc<tokio::sync::Mutex<tokio::net::UnixListener>>>,
     /// Shared KV store backing all capsule-scoped stores and kernel state.
-    pub kv: Arc<astrid_storage::SurrealKvStore>,
+    ///
+    /// Wrapped in [`astrid_storage::InstrumentedKvStore`] so `GetStatus` can
+    /// report per-operation latency and error metrics.
+    pub kv: Arc<KernelKvStore>,
+    /// Background task purging expired TTL entries from [`kv`](Self::kv).
+    /// Aborted on shutdown before the store is closed.
+    ttl_sweeper: tokio::task::JoinHandle<()>,
     /// Chain-linked cryptographic audit log with persistent storage.
     pub audit_log: Arc<AuditLog>,
     /// Per-principal active connection counters (Layer 4, issue #668).
@@ -106,6 +131,8 @@ pub struct Kernel {
     pub allowance_store: Arc<astrid_approval::AllowanceStore>,
     /// System-wide identity store for platform user resolution.
     identity_store: Arc<dyn astrid_storage::IdentityStore>,
+    /// Code-verified linking of platform accounts, backed by `identity_store`.
+    linking: astrid_core::LinkingService<dyn astrid_storage::IdentityStore>,
     /// System-wide per-principal profile cache (Layer 3 quota enforcement).
     ///
     /// One instance per kernel boot. Every capsule load plumbs this into
@@ -141,6 +168,10 @@ pub struct Kernel {
     /// cover reads. Tokio's `Mutex` is not poisonable — no
     /// `PoisonError::into_inner` dance required.
     pub(crate) admin_write_lock: Mutex<()>,
+    /// Held for writing while a capsule is unloaded or reloaded. The event
+    /// dispatcher holds it for reading per event, so events published
+    /// mid-swap wait for the new copy instead of being dropped.
+    capsule_swap: Arc<RwLock<()>>,
 }
 
 impl Kernel {
@@ -190,10 +221,22 @@ impl Kernel {
         let home_root = Some(principal_home.root().to_path_buf());
 
         // 1. Open the persistent KV store (needed by capability store below).
-        let kv_path = home.state_db_path();
-        let kv = Arc::new(
-            astrid_storage::SurrealKvStore::open(&kv_path)
-                .map_err(|e| std::io::Error::other(format!("Failed to open KV store: {e}")))?,
+        //    Values are encrypted at rest under the storage root key; a
+        //    store written before encryption is migrated on first boot.
+        let storage_key = load_or_generate_storage_key(&home.keys_dir())?;
+        let encrypted_kv =
+            astrid_storage::EncryptedKvStore::open_persistent(&home.state_db_path(), &storage_key)
+                .await
+                .map_err(|e| std::io::Error::other(format!("Failed to open KV store: {e}")))?;
+        drop(storage_key);
+        let mut instrumented = astrid_storage::InstrumentedKvStore::new(encrypted_kv);
+        if let Some(threshold) = kv_slow_op_threshold() {
+            instrumented = instrumented.with_slow_threshold(threshold);
+        }
+        let kv = Arc::new(instrumented);
+        let ttl_sweeper = astrid_storage::spawn_ttl_sweeper(
+            Arc::clone(&kv) as Arc<dyn astrid_storage::KvStore>,
+            KV_TTL_SWEEP_INTERVAL,
         );
         // TODO: clear ephemeral keys (e: prefix) on boot when the key
         // lifecycle tier convention is established.
@@ -204,7 +247,19 @@ impl Kernel {
         let mcp_manager = ServerManager::new(mcp_config)
             .with_workspace_root(workspace_root.clone())
             .with_capsule_log_dir(principal_home.log_dir());
-        let mcp_client = McpClient::new(mcp_manager);
+        // Server elicitations mid tool call are forwarded to frontends.
+        let elicitation = elicitation::IpcElicitation::new(Arc::clone(&event_bus));
+        let mcp_client =
+            McpClient::new(mcp_manager).with_elicitation(elicitation.clone(), elicitation);
+        let tools_bus = Arc::clone(&event_bus);
+        mcp_client.on_tools_changed(move |change| {
+            let _ = tools_bus.publish(astrid_events::AstridEvent::McpToolsChanged {
+                metadata: astrid_events::EventMetadata::new("kernel"),
+                server_name: change.server_name.clone(),
+                added: change.added.clone(),
+                removed: change.removed.clone(),
+            });
+        });
 
         // 3. Bootstrap capability store (persistent) and audit log.
         //    Key rotation invalidates persisted tokens (fail-secure by design).
@@ -220,7 +275,8 @@ impl Kernel {
             Arc::clone(&capabilities),
             Arc::clone(&audit_log),
             session_id.clone(),
-        );
+        )
+        .with_workspace(workspace_key(&workspace_root));
 
         // 4. Establish the physical security boundary (sandbox handle)
         let root_handle = DirHandle::new();
@@ -276,6 +332,9 @@ impl Kernel {
 
         // Apply pre-configured identity links from config.
         apply_identity_config(&identity_store, &workspace_root).await;
+        let config = load_kernel_config(&workspace_root);
+        let workspace_mode = workspace_mode(&config);
+        let audit_seal_writes_up_to = config.audit.seal_file_writes_max_bytes;
 
         let kernel = Arc::new(Self {
             session_id,
@@ -287,9 +346,12 @@ impl Kernel {
             overlay_registry,
             vfs_root_handle: root_handle,
             workspace_root,
+            workspace_mode,
+            audit_seal_writes_up_to,
             home_root,
             cli_socket_listener: Some(Arc::new(tokio::sync::Mutex::new(listener))),
             kv,
+            ttl_sweeper,
             audit_log,
             active_connections: DashMap::new(),
             ephemeral: AtomicBool::new(false),
@@ -298,17 +360,29 @@ impl Kernel {
             session_token: Arc::new(session_token),
             token_path,
             allowance_store,
+            linking: astrid_core::LinkingService::new(Arc::clone(&identity_store)),
             identity_store,
             profile_cache: Arc::new(PrincipalProfileCache::with_home(home.clone())),
             groups,
             astrid_home: home,
             admin_write_lock: Mutex::new(()),
+            capsule_swap: Arc::new(RwLock::new(())),
         });
 
         drop(kernel_router::spawn_kernel_router(Arc::clone(&kernel)));
         drop(spawn_idle_monitor(Arc::clone(&kernel)));
         drop(spawn_react_watchdog(Arc::clone(&kernel.event_bus)));
+        drop(metrics::spawn_metrics_recorder(&kernel.event_bus));
         drop(spawn_capsule_health_monitor(Arc::clone(&kernel)));
+        drop(cron::spawn_cron_scheduler(Arc::clone(&kernel)));
+        drop(backup::spawn_backup_job(
+            Arc::clone(&kernel.kv) as Arc<dyn astrid_storage::KvStore>,
+            kernel.astrid_home.backups_dir(),
+        ));
+        let bundle_writer = diagnostics::spawn_bundle_writer(
+            &kernel.event_bus,
+            kernel.astrid_home.diagnostics_dir(),
+        );
 
         // Spawn the event dispatcher — routes EventBus events to capsule interceptors.
         // Wire the identity store so auto-provisioning is gated.
@@ -316,12 +390,13 @@ impl Kernel {
             Arc::clone(&kernel.capsules),
             Arc::clone(&kernel.event_bus),
         )
-        .with_identity_store(Arc::clone(&kernel.identity_store));
+        .with_identity_store(Arc::clone(&kernel.identity_store))
+        .with_swap_gate(Arc::clone(&kernel.capsule_swap));
         tokio::spawn(dispatcher.run());
 
         debug_assert_eq!(
             kernel.event_bus.subscriber_count(),
-            INTERNAL_SUBSCRIBER_COUNT,
+            INTERNAL_SUBSCRIBER_COUNT.saturating_add(usize::from(bundle_writer.is_some())),
             "INTERNAL_SUBSCRIBER_COUNT is stale; update it when adding permanent subscribers"
         );
 
@@ -396,7 +471,13 @@ impl Kernel {
         .with_allowance_store(Arc::clone(&self.allowance_store))
         .with_identity_store(Arc::clone(&self.identity_store))
         .with_profile_cache(Arc::clone(&self.profile_cache))
-        .with_overlay_registry(Arc::clone(&self.overlay_registry));
+        .with_overlay_registry(Arc::clone(&self.overlay_registry))
+        .with_audit_log(
+            Arc::clone(&self.audit_log),
+            self.session_id.clone(),
+            self.audit_seal_writes_up_to,
+        )
+        .with_workspace_mode(self.workspace_mode);
 
         capsule.load(&ctx).await?;
 
@@ -408,61 +489,59 @@ impl Kernel {
         Ok(())
     }
 
-    /// Restart a capsule by unloading it and re-loading from its source directory.
+    /// Unload a capsule without restarting the kernel.
+    ///
+    /// Unregistering drops the capsule's cron jobs, uplinks and IPC source
+    /// UUIDs, and the dispatcher stops matching its interceptors. The engine
+    /// is then unloaded, which cancels its run loop and drops the IPC
+    /// subscriptions it holds.
     ///
     /// # Errors
     ///
-    /// Returns an error if the capsule has no source directory, cannot be
-    /// unregistered, or fails to reload.
-    async fn restart_capsule(
-        &self,
-        id: &astrid_capsule::capsule::CapsuleId,
-    ) -> Result<(), anyhow::Error> {
-        // Get source directory before unregistering.
-        let source_dir = {
-            let registry = self.capsules.read().await;
-            let capsule = registry
-                .get(id)
-                .ok_or_else(|| anyhow::anyhow!("capsule '{id}' not found in registry"))?;
-            capsule
-                .source_dir()
-                .map(std::path::Path::to_path_buf)
-                .ok_or_else(|| anyhow::anyhow!("capsule '{id}' has no source directory"))?
-        };
-
-        // Unregister and explicitly unload. There is no Drop impl that
-        // calls unload() (it's async), so we must do it here to avoid
-        // leaking MCP subprocesses and other engine resources.
-        let old_capsule = {
+    /// Returns an error if `id` is not a valid capsule name or no capsule
+    /// with that name is loaded.
+    pub async fn unload_capsule(&self, id: &str) -> Result<(), anyhow::Error> {
+        let id = astrid_capsule::capsule::CapsuleId::new(id)
+            .map_err(|e| anyhow::anyhow!("invalid capsule name: {e}"))?;
+        let _swap = self.capsule_swap.write().await;
+        let capsule = {
             let mut registry = self.capsules.write().await;
             registry
-                .unregister(id)
+                .unregister(&id)
                 .map_err(|e| anyhow::anyhow!("failed to unregister capsule '{id}': {e}"))?
         };
-        // Explicitly unload the old capsule. There is no Drop impl that
-        // calls unload() (it's async), so we must do it here to avoid
-        // leaking MCP subprocesses and other engine resources.
-        // Arc::get_mut requires exclusive ownership (strong_count == 1).
-        {
-            let mut old = old_capsule;
-            if let Some(capsule) = std::sync::Arc::get_mut(&mut old) {
-                if let Err(e) = capsule.unload().await {
-                    tracing::warn!(
-                        capsule_id = %id,
-                        error = %e,
-                        "Capsule unload failed during restart"
-                    );
-                }
-            } else {
-                tracing::warn!(
-                    capsule_id = %id,
-                    "Cannot call unload during restart - Arc still held by in-flight task"
-                );
-            }
-        }
+        release_capsule(&id, capsule).await;
+        Ok(())
+    }
 
-        // Re-load from disk.
-        self.load_capsule(source_dir).await?;
+    /// Load the capsule in `dir`, replacing the loaded capsule of the same
+    /// name if there is one.
+    ///
+    /// The swap happens behind the dispatcher's swap gate: IPC events
+    /// published while the old copy is unloaded and the new one loaded are
+    /// held back and delivered to the new copy, so a changed interceptor or
+    /// tool list takes effect without losing messages. If the new copy
+    /// fails to load, the capsule stays unloaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest in `dir` cannot be read or the
+    /// capsule fails to load.
+    pub async fn reload_capsule(&self, dir: PathBuf) -> Result<(), anyhow::Error> {
+        let manifest = astrid_capsule::discovery::load_manifest(&dir.join("Capsule.toml"))
+            .map_err(|e| anyhow::anyhow!(e))?;
+        let id = astrid_capsule::capsule::CapsuleId::from_static(&manifest.package.name);
+
+        let replaced = {
+            let _swap = self.capsule_swap.write().await;
+            let old = self.capsules.write().await.unregister(&id).ok();
+            let replaced = old.is_some();
+            if let Some(old) = old {
+                release_capsule(&id, old).await;
+            }
+            self.load_capsule(dir).await?;
+            replaced
+        };
 
         // Signal the newly loaded capsule to clean up ephemeral state
         // from the previous incarnation. Capsules that don't implement
@@ -472,9 +551,10 @@ impl Kernel {
         // guard before invoke_interceptor which calls block_in_place.
         // Holding the RwLock across block_in_place parks the worker thread
         // and starves registry writers (health monitor, capsule loading).
-        let capsule = {
-            let registry = self.capsules.read().await;
-            registry.get(id)
+        let capsule = if replaced {
+            self.capsules.read().await.get(&id)
+        } else {
+            None
         };
         if let Some(capsule) = capsule
             && let Err(e) = capsule.invoke_interceptor("handle_lifecycle_restart", &[], None)
@@ -489,6 +569,29 @@ impl Kernel {
         Ok(())
     }
 
+    /// Restart a capsule by reloading it from its source directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the capsule is not loaded, has no source
+    /// directory, or fails to reload.
+    async fn restart_capsule(
+        &self,
+        id: &astrid_capsule::capsule::CapsuleId,
+    ) -> Result<(), anyhow::Error> {
+        let source_dir = {
+            let registry = self.capsules.read().await;
+            let capsule = registry
+                .get(id)
+                .ok_or_else(|| anyhow::anyhow!("capsule '{id}' not found in registry"))?;
+            capsule
+                .source_dir()
+                .map(std::path::Path::to_path_buf)
+                .ok_or_else(|| anyhow::anyhow!("capsule '{id}' has no source directory"))?
+        };
+        self.reload_capsule(source_dir).await
+    }
+
     /// Auto-discover and load all capsules from the standard directories (`~/.astrid/capsules` and `.astrid/capsules`).
     ///
     /// Capsules are loaded in dependency order (topological sort) with
@@ -663,6 +766,85 @@ impl Kernel {
         self.ephemeral.store(val, Ordering::Relaxed);
     }
 
+    /// Log lines captured for a session, oldest first.
+    ///
+    /// Empty unless the daemon's logging has per-session capture enabled.
+    #[must_use]
+    pub fn session_logs(&self, session_id: &str) -> Vec<String> {
+        astrid_telemetry::session_logs(session_id)
+    }
+
+    /// The last `lines` lines a capsule wrote to `principal`'s log, oldest
+    /// first.
+    ///
+    /// Reads the newest daily file under the principal's `log/<capsule>/`
+    /// directory. Empty if the capsule has never logged for them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log directory or file cannot be read.
+    pub fn capsule_logs(
+        &self,
+        principal: &astrid_core::PrincipalId,
+        id: &astrid_capsule::capsule::CapsuleId,
+        lines: usize,
+    ) -> std::io::Result<Vec<String>> {
+        let dir = self
+            .astrid_home
+            .principal_home(principal)
+            .log_dir()
+            .join(id.as_str());
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        // Daily files are named `YYYY-MM-DD.log`, so the newest sorts last.
+        let newest = entries
+            .filter_map(Result::ok)
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "log"))
+            .max();
+        let Some(path) = newest else {
+            return Ok(Vec::new());
+        };
+        let content = std::fs::read_to_string(path)?;
+        let all: Vec<&str> = content.lines().collect();
+        let start = all.len().saturating_sub(lines);
+        Ok(all
+            .get(start..)
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    /// Delete every key in a capsule's KV namespace for `principal`,
+    /// returning how many were removed. Other principals' state for the
+    /// same capsule is untouched.
+    ///
+    /// For the default principal this also drops the env config
+    /// pre-loaded at load time; reload the capsule afterwards to restore
+    /// it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the KV store fails.
+    pub async fn wipe_capsule_state(
+        &self,
+        principal: &astrid_core::PrincipalId,
+        id: &astrid_capsule::capsule::CapsuleId,
+    ) -> astrid_storage::StorageResult<u64> {
+        use astrid_storage::KvStore as _;
+
+        let removed = self
+            .kv
+            .clear_namespace(&format!("{principal}:capsule:{id}"))
+            .await?;
+        tracing::info!(capsule_id = %id, %principal, removed, "Wiped capsule KV state");
+        Ok(removed)
+    }
+
     /// Total number of active client connections across all principals.
     ///
     /// Used by the ephemeral-shutdown gate: the kernel shuts down only
@@ -678,8 +860,9 @@ impl Kernel {
     ///
     /// 1. Publish `KernelShutdown` event on the bus.
     /// 2. Drain and unload all capsules (stops MCP child processes, WASM engines).
-    /// 3. Flush and close the persistent KV store.
+    /// 3. Stop the TTL sweeper, then flush and close the persistent KV store.
     /// 4. Remove the Unix socket file.
+    /// 5. Close the audit log.
     pub async fn shutdown(&self, reason: Option<String>) {
         tracing::info!(reason = ?reason, "Kernel shutting down");
 
@@ -746,8 +929,9 @@ impl Kernel {
             drop(arc);
         }
 
-        // 3. Flush the persistent KV store.
-        if let Err(e) = self.kv.close().await {
+        // 3. Stop the expiry sweeper, then flush the persistent KV store.
+        self.ttl_sweeper.abort();
+        if let Err(e) = self.kv.inner().inner().close().await {
             tracing::warn!(error = %e, "Failed to flush KV store during shutdown");
         }
 
@@ -762,6 +946,16 @@ impl Kernel {
         let _ = std::fs::remove_file(&self.token_path);
         crate::socket::remove_readiness_file();
 
+        // 5. Close the audit log last so shutdown itself stays audited. The
+        // close is synchronous and waits on the store's background tasks, so
+        // keep it off the runtime thread.
+        let audit_log = Arc::clone(&self.audit_log);
+        match tokio::task::spawn_blocking(move || audit_log.close()).await {
+            Ok(Ok(())) => {},
+            Ok(Err(e)) => tracing::warn!(error = %e, "Failed to close audit log during shutdown"),
+            Err(e) => tracing::warn!(error = %e, "Audit log close task failed during shutdown"),
+        }
+
         tracing::info!("Kernel shutdown complete");
     }
 
@@ -853,8 +1047,16 @@ pub(crate) async fn test_kernel_with_home(home: astrid_core::dirs::AstridHome) -
     let capsules = Arc::new(RwLock::new(CapsuleRegistry::new()));
 
     // Persistent KV backing capabilities + identity store.
-    let kv = Arc::new(
-        astrid_storage::SurrealKvStore::open(&home.state_db_path()).expect("test kernel: open kv"),
+    let storage_key =
+        load_or_generate_storage_key(&home.keys_dir()).expect("test kernel: storage key");
+    let kv = Arc::new(astrid_storage::InstrumentedKvStore::new(
+        astrid_storage::EncryptedKvStore::open_persistent(&home.state_db_path(), &storage_key)
+            .await
+            .expect("test kernel: open kv"),
+    ));
+    let ttl_sweeper = astrid_storage::spawn_ttl_sweeper(
+        Arc::clone(&kv) as Arc<dyn astrid_storage::KvStore>,
+        KV_TTL_SWEEP_INTERVAL,
     );
     let capabilities = Arc::new(
         CapabilityStore::with_kv_store(Arc::clone(&kv) as Arc<dyn astrid_storage::KvStore>)
@@ -920,9 +1122,12 @@ pub(crate) async fn test_kernel_with_home(home: astrid_core::dirs::AstridHome) -
         overlay_registry,
         vfs_root_handle: root_handle,
         workspace_root: home.root().to_path_buf(),
+        workspace_mode: astrid_workspace::WorkspaceMode::default(),
+        audit_seal_writes_up_to: 0,
         home_root: Some(principal_home.root().to_path_buf()),
         cli_socket_listener: None,
         kv,
+        ttl_sweeper,
         audit_log,
         active_connections: DashMap::new(),
         ephemeral: AtomicBool::new(false),
@@ -931,11 +1136,13 @@ pub(crate) async fn test_kernel_with_home(home: astrid_core::dirs::AstridHome) -
         session_token: Arc::new(astrid_core::session_token::SessionToken::generate()),
         token_path: home.token_path(),
         allowance_store,
+        linking: astrid_core::LinkingService::new(Arc::clone(&identity_store)),
         identity_store,
         profile_cache: Arc::new(PrincipalProfileCache::with_home(home.clone())),
         groups,
         astrid_home: home,
         admin_write_lock: Mutex::new(()),
+        capsule_swap: Arc::new(RwLock::new(())),
     });
     // Spawn the Layer 6 admin dispatcher so IPC-driven tests can drive
     // the full publish → response loop. State-mutating tests that call
@@ -1011,32 +1218,86 @@ fn open_audit_log() -> std::io::Result<Arc<AuditLog>> {
 
 /// Load the runtime ed25519 signing key from disk, or generate and persist a new one.
 ///
-/// The key file is 32 bytes of raw secret key material at `{keys_dir}/runtime.key`.
+/// The key is stored through [`KeyStore`] as `{keys_dir}/runtime.key`: 32
+/// bytes of raw secret key material, owner-only.
 fn load_or_generate_runtime_key(keys_dir: &Path) -> std::io::Result<KeyPair> {
-    let key_path = keys_dir.join("runtime.key");
+    let store = KeyStore::new(keys_dir);
+    let existed = store.path(KeyStore::RUNTIME).exists();
+    let keypair = store.load_or_generate(KeyStore::RUNTIME).map_err(|e| {
+        std::io::Error::other(format!(
+            "invalid runtime key at {}: {e}",
+            store.path(KeyStore::RUNTIME).display()
+        ))
+    })?;
+    if !existed {
+        tracing::info!(key_id = %keypair.key_id_hex(), "Generated new runtime signing key");
+    }
+    Ok(keypair)
+}
 
-    if key_path.exists() {
-        let bytes = std::fs::read(&key_path)?;
-        KeyPair::from_secret_key(&bytes).map_err(|e| {
-            std::io::Error::other(format!(
-                "invalid runtime key at {}: {e}",
-                key_path.display()
-            ))
-        })
-    } else {
-        let keypair = KeyPair::generate();
-        std::fs::create_dir_all(keys_dir)?;
-        std::fs::write(&key_path, keypair.secret_key_bytes())?;
+/// Load the root key that KV encryption keys are derived from, or generate
+/// and persist a new one as `{keys_dir}/storage.key`.
+///
+/// Losing this key makes the encrypted state store unreadable.
+fn load_or_generate_storage_key(keys_dir: &Path) -> std::io::Result<KeyPair> {
+    let store = KeyStore::new(keys_dir);
+    store.load_or_generate(KeyStore::STORAGE).map_err(|e| {
+        std::io::Error::other(format!(
+            "invalid storage key at {}: {e}",
+            store.path(KeyStore::STORAGE).display()
+        ))
+    })
+}
 
-        // Secure permissions (owner-only) on Unix.
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o600))?;
-        }
+/// Default latency above which a KV operation is logged as slow.
+const KV_SLOW_OP_DEFAULT: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Slow KV operation threshold from `ASTRID_KV_SLOW_OP_MS`, or `None` if
+/// set to `0` (logging disabled).
+fn kv_slow_op_threshold() -> Option<std::time::Duration> {
+    let threshold = std::env::var("ASTRID_KV_SLOW_OP_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map_or(KV_SLOW_OP_DEFAULT, std::time::Duration::from_millis);
+    (!threshold.is_zero()).then_some(threshold)
+}
 
-        tracing::info!(key_id = %keypair.key_id_hex(), "Generated new runtime signing key");
-        Ok(keypair)
+/// How often expired TTL entries are purged from the KV store.
+const KV_TTL_SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_mins(1);
+
+/// How long an unload waits for in-flight interceptor calls to release a
+/// capsule before giving up on unloading its engine.
+const UNLOAD_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Poll interval while waiting for a capsule to be released.
+const UNLOAD_DRAIN_POLL: std::time::Duration = std::time::Duration::from_millis(20);
+
+/// Unload the engine of a capsule that has just been unregistered.
+///
+/// There is no Drop impl that calls `unload()` (it's async), so it must be
+/// called here to avoid leaking MCP subprocesses, WASM run loops and their
+/// IPC subscriptions. `Arc::get_mut` needs exclusive ownership, so wait for
+/// interceptor calls already queued to the old copy to finish first.
+async fn release_capsule(
+    id: &astrid_capsule::capsule::CapsuleId,
+    mut capsule: Arc<dyn astrid_capsule::capsule::Capsule>,
+) {
+    let started = std::time::Instant::now();
+    loop {
+        if let Some(capsule) = Arc::get_mut(&mut capsule) {
+            if let Err(e) = capsule.unload().await {
+                tracing::warn!(capsule_id = %id, error = %e, "Capsule unload failed");
+            }
+            return;
+        }
+        if started.elapsed() >= UNLOAD_DRAIN_TIMEOUT {
+            tracing::warn!(
+                capsule_id = %id,
+                "Cannot call unload - Arc still held by in-flight task"
+            );
+            return;
+        }
+        tokio::time::sleep(UNLOAD_DRAIN_POLL).await;
     }
 }
 
@@ -1054,9 +1315,9 @@ fn load_or_generate_runtime_key(keys_dir: &Path) -> std::io::Result<KeyPair> {
 /// Configurable via `ASTRID_IDLE_TIMEOUT_SECS` (default 300 = 5 minutes).
 /// Number of permanent internal event bus subscribers that are not client
 /// connections: `KernelRouter` (`kernel.request.*`), `AdminRouter`
-/// (`kernel.admin.*`), `ConnectionTracker` (`client.*`), and
-/// `EventDispatcher` (all events).
-const INTERNAL_SUBSCRIBER_COUNT: usize = 4;
+/// (`kernel.admin.*`), `ConnectionTracker` (`client.*`), the metrics
+/// recorder (all events), and `EventDispatcher` (all events).
+const INTERNAL_SUBSCRIBER_COUNT: usize = 5;
 
 /// Initial grace period before idle checking begins.
 const IDLE_INITIAL_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
@@ -1153,6 +1414,7 @@ fn spawn_idle_monitor(kernel: Arc<Kernel>) -> tokio::task::JoinHandle<()> {
 
 /// Tracks restart attempts for a single capsule with exponential backoff.
 struct RestartTracker {
+    policy: RestartPolicy,
     attempts: u32,
     last_attempt: std::time::Instant,
     backoff: std::time::Duration,
@@ -1163,8 +1425,9 @@ impl RestartTracker {
     const INITIAL_BACKOFF: std::time::Duration = std::time::Duration::from_secs(2);
     const MAX_BACKOFF: std::time::Duration = std::time::Duration::from_secs(120);
 
-    fn new() -> Self {
+    fn new(policy: RestartPolicy) -> Self {
         Self {
+            policy,
             attempts: 0,
             last_attempt: std::time::Instant::now(),
             backoff: Self::INITIAL_BACKOFF,
@@ -1173,7 +1436,7 @@ impl RestartTracker {
 
     /// Returns `true` if a restart should be attempted now.
     fn should_restart(&self) -> bool {
-        self.attempts < Self::MAX_ATTEMPTS && self.last_attempt.elapsed() >= self.backoff
+        !self.exhausted() && self.last_attempt.elapsed() >= self.backoff
     }
 
     /// Record a restart attempt and advance the backoff.
@@ -1183,22 +1446,24 @@ impl RestartTracker {
         self.backoff = self.backoff.saturating_mul(2).min(Self::MAX_BACKOFF);
     }
 
-    /// Returns `true` if all retry attempts have been exhausted.
+    /// Returns `true` if the capsule's restart policy allows no further
+    /// attempts.
     fn exhausted(&self) -> bool {
-        self.attempts >= Self::MAX_ATTEMPTS
+        match self.policy {
+            RestartPolicy::Never => true,
+            RestartPolicy::OnFailure => self.attempts >= Self::MAX_ATTEMPTS,
+            RestartPolicy::Always => false,
+        }
     }
 }
 
 /// Attempts to restart a failed capsule, respecting backoff and max retries.
 ///
-/// Returns `true` if the tracker should be removed (successful restart).
-async fn attempt_capsule_restart(
-    kernel: &Kernel,
-    id_str: &str,
-    tracker: &mut RestartTracker,
-) -> bool {
+/// The tracker outlives a successful restart, so a capsule that fails again
+/// right after coming back waits out a longer backoff each time.
+async fn attempt_capsule_restart(kernel: &Kernel, id_str: &str, tracker: &mut RestartTracker) {
     if tracker.exhausted() {
-        return false;
+        return;
     }
 
     if !tracker.should_restart() {
@@ -1207,7 +1472,7 @@ async fn attempt_capsule_restart(
             next_attempt_in = ?tracker.backoff.saturating_sub(tracker.last_attempt.elapsed()),
             "Waiting for backoff before next restart attempt"
         );
-        return false;
+        return;
     }
 
     tracker.record_attempt();
@@ -1216,7 +1481,7 @@ async fn attempt_capsule_restart(
     tracing::warn!(
         capsule_id = %id_str,
         attempt,
-        max_attempts = RestartTracker::MAX_ATTEMPTS,
+        policy = ?tracker.policy,
         "Attempting capsule restart"
     );
 
@@ -1224,27 +1489,37 @@ async fn attempt_capsule_restart(
     match kernel.restart_capsule(&capsule_id).await {
         Ok(()) => {
             tracing::info!(capsule_id = %id_str, attempt, "Capsule restarted successfully");
-            true
+            let msg = astrid_events::ipc::IpcMessage::new(
+                "astrid.v1.health.restarted",
+                astrid_events::ipc::IpcPayload::Custom {
+                    data: serde_json::json!({
+                        "capsule_id": id_str,
+                        "attempt": attempt,
+                    }),
+                },
+                uuid::Uuid::new_v4(),
+            );
+            let _ = kernel.event_bus.publish(astrid_events::AstridEvent::Ipc {
+                metadata: astrid_events::EventMetadata::new("kernel"),
+                message: msg,
+            });
         },
         Err(e) => {
             tracing::error!(capsule_id = %id_str, attempt, error = %e, "Capsule restart failed");
             if tracker.exhausted() {
                 tracing::error!(
                     capsule_id = %id_str,
-                    "All restart attempts exhausted - capsule will remain down"
+                    policy = ?tracker.policy,
+                    "Restart policy allows no more attempts - capsule will remain down"
                 );
             }
-            false
         },
     }
 }
 
 /// Spawns a background task that periodically probes capsule health.
 ///
-/// Every 10 seconds, reads the capsule registry and calls `check_health()` on
-/// each capsule that is currently in `Ready` state. If a capsule reports
-/// `Failed`, attempts to restart it with exponential backoff (max 5 attempts).
-/// Publishes `astrid.v1.health.failed` IPC events for each detected failure.
+/// Every 10 seconds, runs [`check_capsule_health`] over the registry.
 fn spawn_capsule_health_monitor(kernel: Arc<Kernel>) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(std::time::Duration::from_secs(10));
@@ -1255,93 +1530,102 @@ fn spawn_capsule_health_monitor(kernel: Arc<Kernel>) -> tokio::task::JoinHandle<
 
         loop {
             interval.tick().await;
+            check_capsule_health(&kernel, &mut restart_trackers).await;
+        }
+    })
+}
 
-            // Collect ready capsules under a brief read lock, then drop
-            // the lock before calling check_health() or publishing events.
-            let ready_capsules: Vec<std::sync::Arc<dyn astrid_capsule::capsule::Capsule>> = {
-                let registry = kernel.capsules.read().await;
-                registry
-                    .list()
-                    .into_iter()
-                    .filter_map(|id| {
-                        let capsule = registry.get(id)?;
-                        if capsule.state() == astrid_capsule::capsule::CapsuleState::Ready {
-                            Some(capsule)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect()
-            };
-
-            // Probe health once per capsule, collect failures, then drop
-            // the Arc Vec before restarting. This ensures restart_capsule's
-            // Arc::get_mut can succeed (no other strong references held).
-            let mut failures: Vec<(String, String)> = Vec::new();
-            for capsule in &ready_capsules {
-                let health = capsule.check_health();
-                if let astrid_capsule::capsule::CapsuleState::Failed(reason) = health {
-                    let id_str = capsule.id().to_string();
-                    tracing::error!(capsule_id = %id_str, reason = %reason, "Capsule health check failed");
-
-                    let msg = astrid_events::ipc::IpcMessage::new(
-                        "astrid.v1.health.failed",
-                        astrid_events::ipc::IpcPayload::Custom {
-                            data: serde_json::json!({
-                                "capsule_id": &id_str,
-                                "reason": &reason,
-                            }),
-                        },
-                        uuid::Uuid::new_v4(),
-                    );
-                    let _ = kernel.event_bus.publish(astrid_events::AstridEvent::Ipc {
-                        metadata: astrid_events::EventMetadata::new("kernel"),
-                        message: msg,
-                    });
-                    failures.push((id_str, reason));
+/// One health-monitor tick.
+///
+/// Reads the health of each capsule that is currently in `Ready` state
+/// from the registry. A capsule is `Failed` when its engine says so or when
+/// its interceptors trapped `failure_threshold` times in a row. Failed
+/// capsules are restarted with exponential backoff as their `[health]
+/// restart` policy allows (`on-failure`: max 5 attempts). Publishes
+/// `astrid.v1.health.failed` IPC events for each detected failure and
+/// `astrid.v1.health.restarted` after each successful restart.
+async fn check_capsule_health(
+    kernel: &Kernel,
+    restart_trackers: &mut std::collections::HashMap<String, RestartTracker>,
+) {
+    // Collect ready capsules with their health under a brief read lock,
+    // then drop the lock before publishing events. The registry folds runs
+    // of interceptor traps into the engine's own health probe.
+    let ready_capsules: Vec<(
+        std::sync::Arc<dyn astrid_capsule::capsule::Capsule>,
+        astrid_capsule::capsule::CapsuleState,
+    )> = {
+        let registry = kernel.capsules.read().await;
+        registry
+            .list()
+            .into_iter()
+            .filter_map(|id| {
+                let capsule = registry.get(id)?;
+                if capsule.state() == astrid_capsule::capsule::CapsuleState::Ready {
+                    Some((capsule, registry.health(id)?))
+                } else {
+                    None
                 }
-            }
+            })
+            .collect()
+    };
 
-            // Drop all Arc clones so restart_capsule's Arc::get_mut can
-            // obtain exclusive access for calling unload().
-            drop(ready_capsules);
+    // Collect failures, then drop the Arc Vec before restarting. This
+    // ensures restart_capsule's Arc::get_mut can succeed (no other strong
+    // references held).
+    let mut failures: Vec<(String, RestartPolicy)> = Vec::new();
+    for (capsule, health) in &ready_capsules {
+        if let astrid_capsule::capsule::CapsuleState::Failed(reason) = health {
+            let id_str = capsule.id().to_string();
+            let policy = capsule.manifest().health.restart;
+            tracing::error!(capsule_id = %id_str, reason = %reason, "Capsule health check failed");
 
-            let failed_this_tick: std::collections::HashSet<&str> =
-                failures.iter().map(|(id, _)| id.as_str()).collect();
+            let msg = astrid_events::ipc::IpcMessage::new(
+                "astrid.v1.health.failed",
+                astrid_events::ipc::IpcPayload::Custom {
+                    data: serde_json::json!({
+                        "capsule_id": &id_str,
+                        "reason": reason,
+                        "restart": policy,
+                    }),
+                },
+                uuid::Uuid::new_v4(),
+            );
+            let _ = kernel.event_bus.publish(astrid_events::AstridEvent::Ipc {
+                metadata: astrid_events::EventMetadata::new("kernel"),
+                message: msg,
+            });
+            failures.push((id_str, policy));
+        }
+    }
 
-            let mut restarted = Vec::new();
-            for (id_str, _reason) in &failures {
-                let tracker = restart_trackers
-                    .entry(id_str.clone())
-                    .or_insert_with(RestartTracker::new);
+    // Drop all Arc clones so restart_capsule's Arc::get_mut can obtain
+    // exclusive access for calling unload().
+    drop(ready_capsules);
 
-                if attempt_capsule_restart(&kernel, id_str, tracker).await {
-                    restarted.push(id_str.clone());
-                }
-            }
+    let failed_this_tick: std::collections::HashSet<&str> =
+        failures.iter().map(|(id, _)| id.as_str()).collect();
 
-            // Remove trackers for successfully restarted capsules.
-            for id in &restarted {
-                restart_trackers.remove(id);
-            }
+    for (id_str, policy) in &failures {
+        let tracker = restart_trackers
+            .entry(id_str.clone())
+            .or_insert_with(|| RestartTracker::new(*policy));
+        attempt_capsule_restart(kernel, id_str, tracker).await;
+    }
 
-            // Prune trackers for capsules that recovered (healthy this tick).
-            // Keep exhausted trackers and trackers still in their backoff
-            // window (capsule may have been unregistered by a failed restart
-            // attempt and won't appear in ready_capsules next tick).
-            restart_trackers.retain(|id, tracker| {
-                if tracker.exhausted() {
-                    return true;
-                }
-                // Keep if still within backoff - the capsule may be absent
-                // from the registry after a failed reload.
-                if tracker.last_attempt.elapsed() < tracker.backoff {
-                    return true;
-                }
-                failed_this_tick.contains(id.as_str())
-            });
+    // Prune trackers for capsules that stayed healthy past their backoff
+    // window. Keep exhausted trackers and trackers still in their backoff
+    // window (the capsule may have just been restarted, or unregistered by
+    // a failed restart attempt and absent from ready_capsules next tick).
+    restart_trackers.retain(|id, tracker| {
+        if tracker.exhausted() {
+            return true;
         }
-    })
+        if tracker.last_attempt.elapsed() < tracker.backoff {
+            return true;
+        }
+        failed_this_tick.contains(id.as_str())
+    });
 }
 
 /// Spawns a periodic watchdog that publishes `astrid.v1.watchdog.tick` events every 5 seconds.
@@ -1422,16 +1706,45 @@ mod tests {
 
         // Write a key file with wrong length.
         std::fs::write(keys_dir.join("runtime.key"), [0u8; 16]).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(
+                keys_dir.join("runtime.key"),
+                std::fs::Permissions::from_mode(0o600),
+            )
+            .unwrap();
+        }
 
         let result = load_or_generate_runtime_key(&keys_dir);
         assert!(result.is_err());
         let err = result.unwrap_err().to_string();
         assert!(
-            err.contains("invalid runtime key"),
+            err.contains("invalid runtime key") && err.contains("invalid key length"),
             "expected 'invalid runtime key' error, got: {err}"
         );
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_load_or_generate_rejects_world_readable_key() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let keys_dir = dir.path().join("keys");
+        load_or_generate_runtime_key(&keys_dir).unwrap();
+        std::fs::set_permissions(
+            keys_dir.join("runtime.key"),
+            std::fs::Permissions::from_mode(0o644),
+        )
+        .unwrap();
+
+        let err = load_or_generate_runtime_key(&keys_dir)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("insecure permissions"), "got: {err}");
+    }
+
     #[test]
     fn test_connection_counter_increment_decrement() {
         let counter = AtomicUsize::new(0);
@@ -1580,7 +1893,7 @@ mod tests {
 
     #[test]
     fn restart_tracker_initial_state() {
-        let tracker = RestartTracker::new();
+        let tracker = RestartTracker::new(RestartPolicy::OnFailure);
         assert!(!tracker.exhausted());
         // Should not restart immediately (backoff hasn't elapsed).
         assert!(!tracker.should_restart());
@@ -1588,7 +1901,7 @@ mod tests {
 
     #[test]
     fn restart_tracker_allows_restart_after_backoff() {
-        let mut tracker = RestartTracker::new();
+        let mut tracker = RestartTracker::new(RestartPolicy::OnFailure);
         // Simulate time passing by setting last_attempt in the past.
         tracker.last_attempt = std::time::Instant::now()
             - RestartTracker::INITIAL_BACKOFF
@@ -1598,7 +1911,7 @@ mod tests {
 
     #[test]
     fn restart_tracker_doubles_backoff() {
-        let mut tracker = RestartTracker::new();
+        let mut tracker = RestartTracker::new(RestartPolicy::OnFailure);
         assert_eq!(tracker.backoff, RestartTracker::INITIAL_BACKOFF);
 
         tracker.record_attempt();
@@ -1618,7 +1931,7 @@ mod tests {
 
     #[test]
     fn restart_tracker_backoff_caps_at_max() {
-        let mut tracker = RestartTracker::new();
+        let mut tracker = RestartTracker::new(RestartPolicy::OnFailure);
         for _ in 0..20 {
             tracker.record_attempt();
         }
@@ -1627,7 +1940,7 @@ mod tests {
 
     #[test]
     fn restart_tracker_exhausted_at_max_attempts() {
-        let mut tracker = RestartTracker::new();
+        let mut tracker = RestartTracker::new(RestartPolicy::OnFailure);
         for _ in 0..RestartTracker::MAX_ATTEMPTS {
             assert!(!tracker.exhausted());
             tracker.record_attempt();
@@ -1637,7 +1950,7 @@ mod tests {
 
     #[test]
     fn restart_tracker_should_restart_false_when_exhausted() {
-        let mut tracker = RestartTracker::new();
+        let mut tracker = RestartTracker::new(RestartPolicy::OnFailure);
         for _ in 0..RestartTracker::MAX_ATTEMPTS {
             tracker.record_attempt();
         }
@@ -1646,6 +1959,32 @@ mod tests {
         assert!(!tracker.should_restart());
     }
 
+    #[test]
+    fn restart_tracker_never_policy_does_not_restart() {
+        let mut tracker = RestartTracker::new(RestartPolicy::Never);
+        tracker.last_attempt = std::time::Instant::now()
+            .checked_sub(RestartTracker::MAX_BACKOFF)
+            .unwrap();
+        assert!(tracker.exhausted());
+        assert!(!tracker.should_restart());
+    }
+
+    #[test]
+    fn restart_tracker_always_policy_keeps_backing_off() {
+        let mut tracker = RestartTracker::new(RestartPolicy::Always);
+        let mut sequence = Vec::new();
+        for _ in 0..RestartTracker::MAX_ATTEMPTS.saturating_mul(2) {
+            tracker.record_attempt();
+            sequence.push(tracker.backoff.as_secs());
+        }
+        assert_eq!(sequence, [4, 8, 16, 32, 64, 120, 120, 120, 120, 120]);
+        assert!(!tracker.exhausted());
+        tracker.last_attempt = std::time::Instant::now()
+            .checked_sub(RestartTracker::MAX_BACKOFF)
+            .unwrap();
+        assert!(tracker.should_restart());
+    }
+
     // ── Bootstrap admin-group seeding (issue #670) ───────────────────
 
     fn scratch_home() -> (tempfile::TempDir, astrid_core::dirs::AstridHome) {
@@ -1799,6 +2138,371 @@ mod tests {
         let result = astrid_core::PrincipalProfile::load_from_path(&new_path).unwrap();
         assert_eq!(result.groups, vec!["canonical".to_string()]);
     }
+
+    // ── Capsule management ───────────────────────────────────────────
+
+    #[tokio::test]
+    async fn capsule_logs_returns_tail_of_newest_file() {
+        let (_d, home) = scratch_home();
+        let kernel = test_kernel_with_home(home.clone()).await;
+        let id = astrid_capsule::capsule::CapsuleId::new("demo").unwrap();
+        let principal = astrid_core::PrincipalId::default();
+        assert!(kernel.capsule_logs(&principal, &id, 10).unwrap().is_empty());
+
+        let dir = home.principal_home(&principal).log_dir().join("demo");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("2026-01-01.log"), "old\n").unwrap();
+        std::fs::write(dir.join("2026-01-02.log"), "a\nb\nc\n").unwrap();
+
+        assert_eq!(
+            kernel.capsule_logs(&principal, &id, 2).unwrap(),
+            vec!["b", "c"]
+        );
+        assert_eq!(
+            kernel.capsule_logs(&principal, &id, 10).unwrap(),
+            vec!["a", "b", "c"]
+        );
+    }
+
+    #[tokio::test]
+    async fn wipe_capsule_state_clears_only_that_namespace() {
+        use astrid_storage::KvStore as _;
+
+        let (_d, home) = scratch_home();
+        let kernel = test_kernel_with_home(home).await;
+        let principal = astrid_core::PrincipalId::default();
+        let ns = format!("{principal}:capsule:demo");
+        let other = format!("{principal}:capsule:other");
+        kernel.kv.set(&ns, "a", b"1".to_vec()).await.unwrap();
+        kernel.kv.set(&ns, "b", b"2".to_vec()).await.unwrap();
+        kernel.kv.set(&other, "a", b"3".to_vec()).await.unwrap();
+
+        let id = astrid_capsule::capsule::CapsuleId::new("demo").unwrap();
+        assert_eq!(kernel.wipe_capsule_state(&principal, &id).await.unwrap(), 2);
+        assert!(kernel.kv.list_keys(&ns).await.unwrap().is_empty());
+        assert_eq!(kernel.kv.list_keys(&other).await.unwrap(), vec!["a"]);
+    }
+
+    fn write_tool_capsule(dir: &Path, tool: &str, cron: bool) {
+        let mut manifest = format!(
+            "[package]\nname = \"tools\"\nversion = \"0.1.0\"\n\n\
+             [[interceptor]]\nevent = \"tool.v1.execute.{tool}\"\naction = \"execute_{tool}\"\n"
+        );
+        if cron {
+            manifest.push_str("\n[[cron]]\nname = \"refresh\"\nschedule = \"0 0 * * * *\"\n");
+        }
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join("Capsule.toml"), manifest).unwrap();
+    }
+
+    fn interceptor_events(registry: &CapsuleRegistry, id: &str) -> Vec<String> {
+        let capsule = registry
+            .get(&astrid_capsule::capsule::CapsuleId::from_static(id))
+            .unwrap();
+        capsule
+            .manifest()
+            .interceptors
+            .iter()
+            .map(|i| i.event.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn reload_capsule_picks_up_changed_tool_list() {
+        let (_d, home) = scratch_home();
+        let kernel = test_kernel_with_home(home.clone()).await;
+        let dir = home.root().join("dev").join("tools");
+
+        write_tool_capsule(&dir, "search", true);
+        kernel.reload_capsule(dir.clone()).await.unwrap();
+        {
+            let registry = kernel.capsules.read().await;
+            assert_eq!(
+                interceptor_events(&registry, "tools"),
+                ["tool.v1.execute.search"]
+            );
+            assert_eq!(registry.cron_jobs().len(), 1);
+        }
+
+        write_tool_capsule(&dir, "fetch", false);
+        kernel.reload_capsule(dir).await.unwrap();
+        let registry = kernel.capsules.read().await;
+        assert_eq!(registry.len(), 1);
+        assert_eq!(
+            interceptor_events(&registry, "tools"),
+            ["tool.v1.execute.fetch"]
+        );
+        assert!(registry.cron_jobs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unload_capsule_deregisters_it() {
+        let (_d, home) = scratch_home();
+        let kernel = test_kernel_with_home(home.clone()).await;
+        let dir = home.root().join("dev").join("tools");
+        write_tool_capsule(&dir, "search", true);
+        kernel.reload_capsule(dir).await.unwrap();
+
+        kernel.unload_capsule("tools").await.unwrap();
+        {
+            let registry = kernel.capsules.read().await;
+            assert!(registry.is_empty());
+            assert!(registry.cron_jobs().is_empty());
+        }
+        assert!(kernel.unload_capsule("tools").await.is_err());
+        assert!(kernel.unload_capsule("../tools").await.is_err());
+    }
+
+    /// A component whose `astrid-hook-trigger` export executes `unreachable`.
+    const TRAPPING_COMPONENT: &str = r#"
+        (component
+            (core module $m
+                (memory (export "memory") 1)
+                (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                    i32.const 16)
+                (func (export "trigger") (param i32 i32 i32 i32) (result i32)
+                    unreachable)
+                (func (export "noop")))
+            (core instance $i (instantiate $m))
+            (type $result (record (field "action" string) (field "data" (option string))))
+            (export $result-export "capsule-result" (type $result))
+            (func (export "astrid-hook-trigger")
+                (param "action" string) (param "payload" (list u8)) (result $result-export)
+                (canon lift (core func $i "trigger")
+                    (memory (core memory $i "memory"))
+                    (realloc (core func $i "realloc"))))
+            (func (export "run") (canon lift (core func $i "noop")))
+            (func (export "astrid-install") (canon lift (core func $i "noop")))
+            (func (export "astrid-upgrade") (canon lift (core func $i "noop"))))
+    "#;
+
+    fn write_trapping_capsule(dir: &Path) {
+        let wasm = wat::parse_str(TRAPPING_COMPONENT).unwrap();
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("meta.json"),
+            serde_json::json!({ "wasm_hash": blake3::hash(&wasm).to_hex().to_string() })
+                .to_string(),
+        )
+        .unwrap();
+        std::fs::write(dir.join("trap.wasm"), wasm).unwrap();
+        std::fs::write(
+            dir.join("Capsule.toml"),
+            "[package]\nname = \"trapper\"\nversion = \"0.1.0\"\n\n\
+             [[component]]\nid = \"default\"\nfile = \"trap.wasm\"\n\n\
+             [[interceptor]]\nevent = \"test.trap\"\naction = \"boom\"\n\n\
+             [health]\nfailure_threshold = 2\n",
+        )
+        .unwrap();
+    }
+
+    /// Publish events the trapping capsule intercepts until the registry
+    /// reports it failed.
+    async fn trap_until_failed(kernel: &Kernel, id: &astrid_capsule::capsule::CapsuleId) {
+        for _ in 0..100 {
+            let msg = astrid_events::ipc::IpcMessage::new(
+                "test.trap",
+                astrid_events::ipc::IpcPayload::Custom {
+                    data: serde_json::json!({}),
+                },
+                uuid::Uuid::new_v4(),
+            );
+            let _ = kernel.event_bus.publish(astrid_events::AstridEvent::Ipc {
+                metadata: astrid_events::EventMetadata::new("test"),
+                message: msg,
+            });
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            if matches!(
+                kernel.capsules.read().await.health(id),
+                Some(astrid_capsule::capsule::CapsuleState::Failed(_))
+            ) {
+                return;
+            }
+        }
+        panic!("capsule '{id}' never failed");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trapping_capsule_restarts_with_growing_backoff() {
+        let (_d, home) = scratch_home();
+        let kernel = test_kernel_with_home(home.clone()).await;
+        let dispatcher = astrid_capsule::dispatcher::EventDispatcher::new(
+            Arc::clone(&kernel.capsules),
+            Arc::clone(&kernel.event_bus),
+        )
+        .with_swap_gate(Arc::clone(&kernel.capsule_swap));
+        let dispatch = tokio::spawn(dispatcher.run());
+
+        let dir = home.root().join("dev").join("trapper");
+        write_trapping_capsule(&dir);
+        kernel.reload_capsule(dir).await.unwrap();
+        let id = astrid_capsule::capsule::CapsuleId::from_static("trapper");
+
+        let mut trackers = std::collections::HashMap::new();
+        let mut backoff = RestartTracker::INITIAL_BACKOFF;
+        for attempt in 1..=3 {
+            trap_until_failed(&kernel, &id).await;
+
+            // Inside the backoff window the capsule stays down.
+            check_capsule_health(&kernel, &mut trackers).await;
+            assert_eq!(trackers["trapper"].attempts, attempt - 1);
+            assert_eq!(trackers["trapper"].backoff, backoff);
+
+            // Once it elapses the capsule is restarted and the next
+            // window is twice as long.
+            let tracker = trackers.get_mut("trapper").unwrap();
+            tracker.last_attempt = std::time::Instant::now()
+                .checked_sub(tracker.backoff)
+                .unwrap();
+            check_capsule_health(&kernel, &mut trackers).await;
+            backoff = backoff.saturating_mul(2);
+            assert_eq!(trackers["trapper"].attempts, attempt);
+            assert_eq!(trackers["trapper"].backoff, backoff);
+
+            let registry = kernel.capsules.read().await;
+            assert_eq!(registry.consecutive_traps(&id), 0);
+            assert_eq!(
+                registry.health(&id),
+                Some(astrid_capsule::capsule::CapsuleState::Ready)
+            );
+        }
+
+        dispatch.abort();
+    }
+
+    #[tokio::test]
+    async fn ttl_sweeper_runs_until_shutdown() {
+        let (_d, home) = scratch_home();
+        let kernel = test_kernel_with_home(home).await;
+        assert!(!kernel.ttl_sweeper.is_finished());
+
+        kernel.shutdown(None).await;
+        for _ in 0..50 {
+            if kernel.ttl_sweeper.is_finished() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(kernel.ttl_sweeper.is_finished());
+    }
+
+    /// A component that persists its hook payload under `__state` on
+    /// `store` and returns the stored value as a final result on `load`.
+    const STATEFUL_COMPONENT: &str = r#"
+        (component
+            (import "astrid:capsule/kv@0.1.0" (instance $kv
+                (export "kv-get" (func (param "key" string)
+                    (result (result (option (list u8)) (error string)))))
+                (export "kv-set" (func (param "key" string) (param "value" (list u8))
+                    (result (result (error string)))))))
+            (core module $mem
+                (memory (export "memory") 1)
+                (global $next (mut i32) (i32.const 1024))
+                (func (export "realloc") (param i32 i32 i32 i32) (result i32)
+                    global.get $next
+                    global.get $next
+                    local.get 3
+                    i32.add
+                    global.set $next))
+            (core instance $mem (instantiate $mem))
+            (alias core export $mem "memory" (core memory $memory))
+            (alias core export $mem "realloc" (core func $realloc))
+            (alias export $kv "kv-get" (func $kv-get))
+            (alias export $kv "kv-set" (func $kv-set))
+            (core func $get (canon lower (func $kv-get) (memory $memory) (realloc $realloc)))
+            (core func $set (canon lower (func $kv-set) (memory $memory) (realloc $realloc)))
+            (core module $m
+                (import "env" "memory" (memory 1))
+                (import "kv" "get" (func $get (param i32 i32 i32)))
+                (import "kv" "set" (func $set (param i32 i32 i32 i32 i32)))
+                (data (i32.const 0) "__state")
+                (data (i32.const 16) "final")
+                (func (export "trigger") (param i32 i32 i32 i32) (result i32)
+                    (i32.store (i32.const 64) (i32.const 16))
+                    (i32.store (i32.const 68) (i32.const 5))
+                    (if (i32.eq (local.get 1) (i32.const 5))
+                        (then
+                            (call $set (i32.const 0) (i32.const 7)
+                                (local.get 2) (local.get 3) (i32.const 96))
+                            (i32.store8 (i32.const 72) (i32.const 0)))
+                        (else
+                            (call $get (i32.const 0) (i32.const 7) (i32.const 96))
+                            (i32.store8 (i32.const 72) (i32.load8_u (i32.const 100)))
+                            (i32.store (i32.const 76) (i32.load (i32.const 104)))
+                            (i32.store (i32.const 80) (i32.load (i32.const 108)))))
+                    i32.const 64)
+                (func (export "noop")))
+            (core instance $i (instantiate $m
+                (with "env" (instance (export "memory" (memory $memory))))
+                (with "kv" (instance (export "get" (func $get)) (export "set" (func $set))))))
+            (type $result (record (field "action" string) (field "data" (option string))))
+            (export $result-export "capsule-result" (type $result))
+            (func (export "astrid-hook-trigger")
+                (param "action" string) (param "payload" (list u8)) (result $result-export)
+                (canon lift (core func $i "trigger")
+                    (memory $memory)
+                    (realloc $realloc)))
+            (func (export "run") (canon lift (core func $i "noop")))
+            (func (export "astrid-install") (canon lift (core func $i "noop")))
+            (func (export "astrid-upgrade") (canon lift (core func $i "noop"))))
+    "#;
+
+    fn write_stateful_capsule(dir: &Path) {
+        let wasm = wat::parse_str(STATEFUL_COMPONENT).unwrap();
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(
+            dir.join("meta.json"),
+            serde_json::json!({ "wasm_hash": blake3::hash(&wasm).to_hex().to_string() })
+                .to_string(),
+        )
+        .unwrap();
+        std::fs::write(dir.join("keeper.wasm"), wasm).unwrap();
+        std::fs::write(
+            dir.join("Capsule.toml"),
+            "[package]\nname = \"keeper\"\nversion = \"0.1.0\"\n\n\
+             [[component]]\nid = \"default\"\nfile = \"keeper.wasm\"\n\n\
+             [[interceptor]]\nevent = \"test.store\"\naction = \"store\"\n\n\
+             [[interceptor]]\nevent = \"test.load\"\naction = \"load\"\n",
+        )
+        .unwrap();
+    }
+
+    async fn keeper(kernel: &Kernel) -> Arc<dyn astrid_capsule::capsule::Capsule> {
+        kernel
+            .capsules
+            .read()
+            .await
+            .get(&astrid_capsule::capsule::CapsuleId::from_static("keeper"))
+            .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn capsule_state_survives_kernel_restart() {
+        use astrid_capsule::capsule::InterceptResult;
+
+        let (_d, home) = scratch_home();
+        let dir = home.root().join("dev").join("keeper");
+        write_stateful_capsule(&dir);
+
+        let kernel = test_kernel_with_home(home.clone()).await;
+        kernel.reload_capsule(dir.clone()).await.unwrap();
+        let stored = keeper(&kernel)
+            .await
+            .invoke_interceptor("store", b"session-42", None)
+            .unwrap();
+        assert!(matches!(stored, InterceptResult::Final(_)));
+        kernel.shutdown(None).await;
+        drop(kernel);
+
+        let kernel = test_kernel_with_home(home).await;
+        kernel.reload_capsule(dir).await.unwrap();
+        let loaded = keeper(&kernel)
+            .await
+            .invoke_interceptor("load", b"", None)
+            .unwrap();
+        assert!(matches!(loaded, InterceptResult::Final(ref v) if v == b"session-42"));
+        kernel.shutdown(None).await;
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -2023,6 +2727,30 @@ fn seed_default_principal_admin_profile(
     Ok(())
 }
 
+/// Load the config for the host-side settings the kernel hands capsules,
+/// falling back to the defaults when no config loads.
+fn load_kernel_config(workspace_root: &std::path::Path) -> astrid_config::Config {
+    match astrid_config::Config::load(Some(workspace_root)) {
+        Ok(resolved) => resolved.config,
+        Err(e) => {
+            tracing::debug!(error = %e, "No config loaded, using defaults");
+            astrid_config::Config::default()
+        },
+    }
+}
+
+/// `workspace.mode` from the config.
+///
+/// Falls back to [`WorkspaceMode::Safe`](astrid_workspace::WorkspaceMode::Safe)
+/// when the mode is unknown, so a broken config never loosens the boundary.
+fn workspace_mode(config: &astrid_config::Config) -> astrid_workspace::WorkspaceMode {
+    let mode = &config.workspace.mode;
+    astrid_workspace::WorkspaceMode::from_name(mode).unwrap_or_else(|| {
+        tracing::warn!(%mode, "Unknown workspace mode, using safe");
+        astrid_workspace::WorkspaceMode::Safe
+    })
+}
+
 /// Apply pre-configured identity links from the config file.
 ///
 /// For each `[[identity.links]]` entry, resolves or creates the referenced
@@ -0,0 +1,246 @@
+//! Token, tool call and approval counters for OTLP export.
+//!
+//! LLM providers, the tool router and approval prompts all run in capsules,
+//! but their results cross the event bus, so the kernel counts them there.
+//! Counters are tracing events on [`METRICS_TARGET`]; they reach a collector
+//! only when `[logging.otlp]` export is enabled and are dropped otherwise.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use astrid_events::ipc::IpcPayload;
+use astrid_events::llm::StreamEvent;
+use astrid_events::{AstridEvent, EventBus};
+use astrid_telemetry::METRICS_TARGET;
+
+/// In-flight tool calls remembered for naming their results. Calls whose
+/// result never arrives are forgotten when this many are outstanding.
+const MAX_PENDING_TOOL_CALLS: usize = 1024;
+
+/// One counter increment.
+#[derive(Debug, PartialEq, Eq)]
+enum Sample {
+    /// LLM tokens consumed; `kind` is `input` or `output`.
+    Tokens { kind: &'static str, count: u64 },
+    /// A tool call finished.
+    ToolCall { tool: String, outcome: &'static str },
+    /// An approval request was answered.
+    Approval { outcome: &'static str },
+}
+
+/// Turns IPC payloads into samples.
+#[derive(Default)]
+struct Recorder {
+    /// Tool name by call ID, from requests awaiting their result.
+    pending_tools: HashMap<String, String>,
+}
+
+impl Recorder {
+    fn observe(&mut self, payload: &IpcPayload) -> Vec<Sample> {
+        match payload {
+            IpcPayload::LlmResponse { response, .. } => {
+                tokens(response.usage.input_tokens, response.usage.output_tokens)
+            },
+            IpcPayload::LlmStreamEvent {
+                event:
+                    StreamEvent::Usage {
+                        input_tokens,
+                        output_tokens,
+                    },
+                ..
+            } => tokens(*input_tokens, *output_tokens),
+            IpcPayload::ToolExecuteRequest {
+                call_id, tool_name, ..
+            } => {
+                if self.pending_tools.len() >= MAX_PENDING_TOOL_CALLS {
+                    self.pending_tools.clear();
+                }
+                self.pending_tools
+                    .insert(call_id.clone(), tool_name.clone());
+                Vec::new()
+            },
+            IpcPayload::ToolExecuteResult { call_id, result } => {
+                let tool = self
+                    .pending_tools
+                    .remove(call_id)
+                    .unwrap_or_else(|| "unknown".to_string());
+                let outcome = if result.is_error { "error" } else { "success" };
+                vec![Sample::ToolCall { tool, outcome }]
+            },
+            IpcPayload::ApprovalResponse { decision, .. } => {
+                let outcome = match decision.as_str() {
+                    "deny" => "denied",
+                    d if d.starts_with("approve") => "approved",
+                    _ => "other",
+                };
+                vec![Sample::Approval { outcome }]
+            },
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn tokens(input: usize, output: usize) -> Vec<Sample> {
+    [("input", input), ("output", output)]
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| Sample::Tokens {
+            kind,
+            count: u64::try_from(count).unwrap_or(u64::MAX),
+        })
+        .collect()
+}
+
+fn emit(sample: Sample) {
+    match sample {
+        Sample::Tokens { kind, count } => tracing::info!(
+            target: METRICS_TARGET,
+            kind,
+            monotonic_counter.astrid.llm.tokens = count
+        ),
+        Sample::ToolCall { tool, outcome } => tracing::info!(
+            target: METRICS_TARGET,
+            tool,
+            outcome,
+            monotonic_counter.astrid.tool_calls = 1_u64
+        ),
+        Sample::Approval { outcome } => tracing::info!(
+            target: METRICS_TARGET,
+            outcome,
+            monotonic_counter.astrid.approvals = 1_u64
+        ),
+    }
+}
+
+/// Spawn the task that records counters from IPC traffic.
+///
+/// Subscribes before returning, so the subscription is counted by the time
+/// the kernel checks `INTERNAL_SUBSCRIBER_COUNT`.
+pub(crate) fn spawn_metrics_recorder(event_bus: &Arc<EventBus>) -> tokio::task::JoinHandle<()> {
+    let mut receiver = event_bus.subscribe();
+    tokio::spawn(async move {
+        let mut recorder = Recorder::default();
+        while let Some(event) = receiver.recv().await {
+            if let AstridEvent::Ipc { message, .. } = &*event {
+                recorder
+                    .observe(&message.payload)
+                    .into_iter()
+                    .for_each(emit);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use astrid_events::llm::{LlmResponse, Message, StopReason, ToolCallResult, Usage};
+
+    use super::*;
+
+    #[test]
+    fn counts_tokens_from_responses_and_streams() {
+        let mut recorder = Recorder::default();
+        let response = IpcPayload::LlmResponse {
+            request_id: uuid::Uuid::new_v4(),
+            response: LlmResponse {
+                message: Message::assistant("hi"),
+                has_tool_calls: false,
+                stop_reason: StopReason::EndTurn,
+                usage: Usage {
+                    input_tokens: 120,
+                    output_tokens: 7,
+                },
+            },
+        };
+        assert_eq!(
+            recorder.observe(&response),
+            [
+                Sample::Tokens {
+                    kind: "input",
+                    count: 120
+                },
+                Sample::Tokens {
+                    kind: "output",
+                    count: 7
+                },
+            ]
+        );
+
+        let stream = IpcPayload::LlmStreamEvent {
+            request_id: uuid::Uuid::new_v4(),
+            event: StreamEvent::Usage {
+                input_tokens: 0,
+                output_tokens: 3,
+            },
+        };
+        assert_eq!(
+            recorder.observe(&stream),
+            [Sample::Tokens {
+                kind: "output",
+                count: 3
+            }]
+        );
+    }
+
+    #[test]
+    fn names_tool_results_after_their_request() {
+        let mut recorder = Recorder::default();
+        let request = IpcPayload::ToolExecuteRequest {
+            call_id: "c1".to_string(),
+            tool_name: "read_file".to_string(),
+            arguments: serde_json::json!({}),
+        };
+        assert!(recorder.observe(&request).is_empty());
+
+        let result = IpcPayload::ToolExecuteResult {
+            call_id: "c1".to_string(),
+            result: ToolCallResult::error("c1", "not found"),
+        };
+        assert_eq!(
+            recorder.observe(&result),
+            [Sample::ToolCall {
+                tool: "read_file".to_string(),
+                outcome: "error"
+            }]
+        );
+        assert!(recorder.pending_tools.is_empty());
+
+        let orphan = IpcPayload::ToolExecuteResult {
+            call_id: "c2".to_string(),
+            result: ToolCallResult::success("c2", "ok"),
+        };
+        assert_eq!(
+            recorder.observe(&orphan),
+            [Sample::ToolCall {
+                tool: "unknown".to_string(),
+                outcome: "success"
+            }]
+        );
+    }
+
+    #[test]
+    fn classifies_approval_decisions() {
+        let mut recorder = Recorder::default();
+        let outcome = |recorder: &mut Recorder, decision: &str| {
+            recorder.observe(&IpcPayload::ApprovalResponse {
+                request_id: "r".to_string(),
+                decision: decision.to_string(),
+                reason: None,
+            })
+        };
+        assert_eq!(
+            outcome(&mut recorder, "approve_session"),
+            [Sample::Approval {
+                outcome: "approved"
+            }]
+        );
+        assert_eq!(
+            outcome(&mut recorder, "deny"),
+            [Sample::Approval { outcome: "denied" }]
+        );
+        assert_eq!(
+            outcome(&mut recorder, "maybe"),
+            [Sample::Approval { outcome: "other" }]
+        );
+    }
+}
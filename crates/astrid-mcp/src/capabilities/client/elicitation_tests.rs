@@ -0,0 +1,185 @@
+//! End-to-end elicitation tests: a real MCP handshake over an in-memory pipe
+//! with a fake server whose tools ask the user for input mid-call.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use astrid_core::{
+    ElicitationRequest, ElicitationResponse, ElicitationSchema, UrlElicitationRequest,
+    UrlElicitationResponse,
+};
+use async_trait::async_trait;
+use rmcp::model::{
+    CallToolRequestParams, CallToolResult, Content, CreateElicitationRequestParams,
+    ElicitationAction,
+};
+use rmcp::service::{RequestContext, RunningService};
+use rmcp::{ErrorData, RoleClient, RoleServer, ServerHandler, ServiceExt};
+use tokio::sync::Notify;
+
+use super::AstridClientHandler;
+use crate::capabilities::{CapabilitiesHandler, ElicitationHandler, UrlElicitationHandler};
+
+/// Fake MCP server. `ask_name` and `login` elicit before answering, `ping`
+/// answers immediately.
+struct FakeServer;
+
+impl ServerHandler for FakeServer {
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let params = match request.name.as_ref() {
+            "ping" => return Ok(CallToolResult::success(vec![Content::text("pong")])),
+            "ask_name" => CreateElicitationRequestParams::FormElicitationParams {
+                meta: None,
+                message: "What is your name?".into(),
+                requested_schema: rmcp::model::ElicitationSchema::builder()
+                    .required_string("name")
+                    .build()
+                    .map_err(|e| ErrorData::internal_error(e, None))?,
+            },
+            "login" => CreateElicitationRequestParams::UrlElicitationParams {
+                meta: None,
+                message: "Sign in to continue".into(),
+                url: "https://auth.example.com/login".into(),
+                elicitation_id: "login-1".into(),
+            },
+            other => {
+                return Err(ErrorData::invalid_params(
+                    format!("unknown tool: {other}"),
+                    None,
+                ));
+            },
+        };
+        let result = context
+            .peer
+            .create_elicitation(params)
+            .await
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        let text = match (result.action, result.content) {
+            (ElicitationAction::Accept, Some(answer)) => answer.to_string(),
+            (action, _) => format!("{action:?}"),
+        };
+        Ok(CallToolResult::success(vec![Content::text(text)]))
+    }
+}
+
+/// Answers form elicitations with `answer` once `release` is notified.
+struct FormHandler {
+    answer: &'static str,
+    release: Arc<Notify>,
+}
+
+#[async_trait]
+impl ElicitationHandler for FormHandler {
+    async fn handle_elicitation(&self, request: ElicitationRequest) -> ElicitationResponse {
+        assert_eq!(request.server_name, "fake");
+        assert_eq!(request.message, "What is your name?");
+        assert!(request.required);
+        assert!(matches!(request.schema, ElicitationSchema::Text { .. }));
+        self.release.notified().await;
+        ElicitationResponse::submit(request.request_id, serde_json::json!(self.answer))
+    }
+}
+
+/// Completes URL elicitations when `complete` is set.
+struct UrlHandler {
+    complete: bool,
+}
+
+#[async_trait]
+impl UrlElicitationHandler for UrlHandler {
+    async fn handle_url_elicitation(
+        &self,
+        request: UrlElicitationRequest,
+    ) -> UrlElicitationResponse {
+        assert_eq!(request.server_name, "fake");
+        assert_eq!(request.url, "https://auth.example.com/login");
+        if self.complete {
+            UrlElicitationResponse::completed(request.request_id)
+        } else {
+            UrlElicitationResponse::not_completed(request.request_id)
+        }
+    }
+}
+
+async fn connect(
+    release: Arc<Notify>,
+    complete_url: bool,
+) -> RunningService<RoleClient, AstridClientHandler> {
+    let mut capabilities = CapabilitiesHandler::new();
+    capabilities.elicitation = Some(Box::new(FormHandler {
+        answer: "Ada",
+        release,
+    }));
+    capabilities.url_elicitation = Some(Box::new(UrlHandler {
+        complete: complete_url,
+    }));
+
+    let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        if let Ok(server) = FakeServer.serve(server_io).await {
+            let _ = server.waiting().await;
+        }
+    });
+    AstridClientHandler::new("fake", Arc::new(capabilities))
+        .serve(client_io)
+        .await
+        .unwrap()
+}
+
+async fn call(client: &RunningService<RoleClient, AstridClientHandler>, tool: &str) -> String {
+    let result = client
+        .call_tool(CallToolRequestParams {
+            meta: None,
+            name: tool.to_string().into(),
+            arguments: None,
+            task: None,
+        })
+        .await
+        .unwrap();
+    result
+        .content
+        .first()
+        .unwrap()
+        .as_text()
+        .unwrap()
+        .text
+        .clone()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn form_elicitation_resumes_tool_call_with_answer() {
+    let release = Arc::new(Notify::new());
+    let client = Arc::new(connect(Arc::clone(&release), true).await);
+
+    let pending = tokio::spawn({
+        let client = Arc::clone(&client);
+        async move { call(&client, "ask_name").await }
+    });
+
+    // The elicitation is waiting on the user; other calls still complete.
+    let pong = tokio::time::timeout(Duration::from_secs(5), call(&client, "ping"))
+        .await
+        .expect("parallel call blocked by pending elicitation");
+    assert_eq!(pong, "pong");
+    assert!(!pending.is_finished());
+
+    release.notify_one();
+    let answer = tokio::time::timeout(Duration::from_secs(5), pending)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(answer, r#"{"name":"Ada"}"#);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn url_elicitation_reports_completion() {
+    let client = connect(Arc::new(Notify::new()), true).await;
+    assert_eq!(call(&client, "login").await, "Accept");
+
+    let client = connect(Arc::new(Notify::new()), false).await;
+    assert_eq!(call(&client, "login").await, "Decline");
+}
@@ -15,7 +15,8 @@ use super::helpers::{
     estimate_json_size, extract_inbound_content, extract_platform_user_id, normalize_platform_name,
 };
 use super::notice::{
-    MAX_CONTEXT_BYTES, MAX_NOTIFICATION_PAYLOAD_BYTES, MAX_PLATFORM_USER_ID_BYTES, ServerNotice,
+    MAX_CONTEXT_BYTES, MAX_MESSAGE_ID_BYTES, MAX_NOTIFICATION_PAYLOAD_BYTES,
+    MAX_PLATFORM_USER_ID_BYTES, ServerNotice,
 };
 
 /// Bridge between astrid capability handlers and the rmcp `ClientHandler` trait.
@@ -191,10 +192,23 @@ impl AstridClientHandler {
             }
         };
 
+        // Optional platform message ID, used downstream to drop redeliveries
+        let message_id = params.get("messageId").and_then(Value::as_str);
+        if message_id.is_some_and(|id| id.len() > MAX_MESSAGE_ID_BYTES) {
+            warn!(
+                max = MAX_MESSAGE_ID_BYTES,
+                "inboundMessage: messageId too long, ignoring it"
+            );
+        }
+        let message_id = message_id.filter(|id| !id.is_empty() && id.len() <= MAX_MESSAGE_ID_BYTES);
+
         // Build inbound message
-        let message = InboundMessage::builder(uplink_id, platform, platform_user_id, content)
-            .context(msg_context)
-            .build();
+        let mut builder = InboundMessage::builder(uplink_id, platform, platform_user_id, content)
+            .context(msg_context);
+        if let Some(id) = message_id {
+            builder = builder.message_id(id);
+        }
+        let message = builder.build();
 
         // Send via bounded channel
         if let Err(e) = tx.try_send(message) {
@@ -13,6 +13,8 @@ mod helpers;
 mod notice;
 mod rmcp_impl;
 
+#[cfg(test)]
+mod elicitation_tests;
 #[cfg(test)]
 mod tests;
 
@@ -19,6 +19,10 @@ pub(super) const MAX_PLATFORM_USER_ID_BYTES: usize = 512;
 /// Maximum size for the opaque context JSON payload in inbound messages (64 KB).
 pub(super) const MAX_CONTEXT_BYTES: usize = 64 * 1024;
 
+/// Maximum length for a platform message ID. Longer IDs are ignored and the
+/// message is delivered without one.
+pub(super) const MAX_MESSAGE_ID_BYTES: usize = 256;
+
 /// Notification from a running MCP server about a state change.
 ///
 /// Sent over an internal channel from `AstridClientHandler` to `McpClient`
@@ -15,7 +15,8 @@ use super::super::handler::CapabilitiesHandler;
 use super::bridge::{MAX_CHANNEL_NAME_LEN, MAX_CHANNELS_PER_CAPSULE};
 use super::handler::AstridClientHandler;
 use super::notice::{
-    MAX_CONTEXT_BYTES, MAX_NOTIFICATION_PAYLOAD_BYTES, MAX_PLATFORM_USER_ID_BYTES,
+    MAX_CONTEXT_BYTES, MAX_MESSAGE_ID_BYTES, MAX_NOTIFICATION_PAYLOAD_BYTES,
+    MAX_PLATFORM_USER_ID_BYTES,
 };
 
 // ─── Test helpers ─────────────────────────────────────────────────────────────
@@ -86,6 +87,29 @@ fn test_inbound_message_notification() {
     assert_eq!(msg.content, "Hello from Telegram");
 }
 
+#[test]
+fn test_inbound_message_carries_message_id() {
+    let (handler, mut rx, shared) = test_handler("test-plugin");
+    register_test_uplink(&shared, "discord", "discord", "test-plugin");
+
+    let params = |id: &str| {
+        serde_json::json!({
+            "capsuleId": "test-plugin",
+            "content": "hi",
+            "messageId": id,
+            "context": { "channel": "discord" }
+        })
+    };
+    handler.handle_inbound_message(Some(params("1234567890")));
+    handler.handle_inbound_message(Some(params(&"9".repeat(MAX_MESSAGE_ID_BYTES + 1))));
+
+    let msg = rx.try_recv().expect("should receive message");
+    assert_eq!(msg.message_id.as_deref(), Some("1234567890"));
+    // An oversized ID is dropped, but the message is still delivered.
+    let msg = rx.try_recv().expect("should receive message");
+    assert_eq!(msg.message_id, None);
+}
+
 #[test]
 fn test_inbound_message_oversized_rejected() {
     let (handler, mut rx, _) = test_handler("test-plugin");
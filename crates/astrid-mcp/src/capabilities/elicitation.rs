@@ -1,7 +1,11 @@
 //! Elicitation capability handler traits.
 //!
 //! These traits use canonical elicitation types from `astrid-core` (single
-//! source of truth). No MCP-local duplicates exist.
+//! source of truth). No MCP-local duplicates exist. Register implementations
+//! with [`McpClient::with_elicitation`](crate::McpClient::with_elicitation).
+//!
+//! A handler runs while the server's tool call is still in flight, so it may
+//! take as long as the user does; other tool calls are not blocked.
 
 use async_trait::async_trait;
 
@@ -15,7 +19,7 @@ use astrid_core::{
 /// Implementations receive canonical [`ElicitationRequest`] from `astrid-core`
 /// and should return an [`ElicitationResponse`] after collecting user input.
 #[async_trait]
-pub(crate) trait ElicitationHandler: Send + Sync {
+pub trait ElicitationHandler: Send + Sync {
     /// Handle an elicitation request from a server.
     ///
     /// The implementation should:
@@ -30,7 +34,7 @@ pub(crate) trait ElicitationHandler: Send + Sync {
 /// Implementations receive canonical [`UrlElicitationRequest`] from `astrid-core`
 /// and should return a [`UrlElicitationResponse`] after the user completes the flow.
 #[async_trait]
-pub(crate) trait UrlElicitationHandler: Send + Sync {
+pub trait UrlElicitationHandler: Send + Sync {
     /// Handle a URL elicitation request from a server.
     ///
     /// The implementation should:
@@ -14,4 +14,5 @@ mod roots;
 mod sampling;
 
 pub(crate) use client::{AstridClientHandler, ServerNotice};
+pub use elicitation::{ElicitationHandler, UrlElicitationHandler};
 pub(crate) use handler::CapabilitiesHandler;
@@ -2,21 +2,41 @@
 //!
 //! Provides a high-level interface for interacting with MCP servers.
 
-use rmcp::model::CallToolRequestParams;
+use rmcp::model::{CallToolRequestParams, GetPromptRequestParams};
 use serde_json::Value;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
-use crate::capabilities::{CapabilitiesHandler, ServerNotice};
+use crate::capabilities::{
+    CapabilitiesHandler, ElicitationHandler, ServerNotice, UrlElicitationHandler,
+};
 use crate::config::{ServerConfig, ServersConfig};
 use crate::error::{McpError, McpResult};
 use crate::server::ServerManager;
-use crate::types::{ToolDefinition, ToolResult};
+use crate::types::{PromptDefinition, PromptResult, ToolDefinition, ToolResult};
 
 use tokio::sync::mpsc;
 
+/// A server's tool list changed after a `tools/list_changed` notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolsChanged {
+    /// Server whose tools changed.
+    pub server_name: String,
+    /// Tools the server now offers that it did not before.
+    pub added: Vec<String>,
+    /// Tools the server no longer offers.
+    pub removed: Vec<String>,
+}
+
+/// Callback registered with [`McpClient::on_tools_changed`].
+pub type ToolsChangedCallback = Arc<dyn Fn(&ToolsChanged) + Send + Sync>;
+
+/// Registered tools-changed callbacks, shared by all clones of a client.
+type ToolsChangedCallbacks = Arc<std::sync::RwLock<Vec<ToolsChangedCallback>>>;
+
 /// MCP client for interacting with MCP servers.
 pub struct McpClient {
     /// Server manager.
@@ -30,6 +50,8 @@ pub struct McpClient {
     /// Cloned into every `AstridClientHandler` so that `on_tool_list_changed`
     /// can push refreshed tools back here.
     notice_tx: mpsc::UnboundedSender<ServerNotice>,
+    /// Callbacks run after a server's tool list is refreshed.
+    tools_changed: ToolsChangedCallbacks,
 }
 
 impl McpClient {
@@ -39,6 +61,8 @@ impl McpClient {
         let servers = Arc::new(servers);
         let tools_cache = Arc::new(RwLock::new(Vec::new()));
 
+        let tools_changed: ToolsChangedCallbacks = Arc::default();
+
         let (notice_tx, notice_rx) = mpsc::unbounded_channel();
 
         let client = Self {
@@ -46,10 +70,11 @@ impl McpClient {
             tools_cache: Arc::clone(&tools_cache),
             capabilities: Arc::new(CapabilitiesHandler::new()),
             notice_tx,
+            tools_changed: Arc::clone(&tools_changed),
         };
 
         // Spawn the background listener that processes server notifications.
-        Self::spawn_notice_listener(notice_rx, Arc::clone(&servers), tools_cache);
+        Self::spawn_notice_listener(notice_rx, Arc::clone(&servers), tools_cache, tools_changed);
 
         client
     }
@@ -71,17 +96,50 @@ impl McpClient {
         Self::new(servers)
     }
 
+    /// Route server `elicitation/create` requests to the given handlers.
+    ///
+    /// Servers learn during the handshake whether the client supports
+    /// elicitation, so call this before connecting any server. Without
+    /// handlers, elicitation requests fail and the tool call that made them
+    /// sees an error.
+    #[must_use]
+    pub fn with_elicitation(
+        mut self,
+        form: impl ElicitationHandler + 'static,
+        url: impl UrlElicitationHandler + 'static,
+    ) -> Self {
+        let mut capabilities = CapabilitiesHandler::new();
+        capabilities.elicitation = Some(Box::new(form));
+        capabilities.url_elicitation = Some(Box::new(url));
+        self.capabilities = Arc::new(capabilities);
+        self
+    }
+
+    /// Register a callback to run whenever a server's tool list changes.
+    ///
+    /// The callback runs on the notice listener task after the tools cache
+    /// has been rebuilt, so [`list_tools`](Self::list_tools) already returns
+    /// the new list. It should not block.
+    pub fn on_tools_changed(&self, callback: impl Fn(&ToolsChanged) + Send + Sync + 'static) {
+        self.tools_changed
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(Arc::new(callback));
+    }
+
     /// Spawn a background task that listens for `ServerNotice` messages and
     /// updates the server manager + tools cache accordingly.
     fn spawn_notice_listener(
         mut rx: mpsc::UnboundedReceiver<ServerNotice>,
         servers: Arc<ServerManager>,
         tools_cache: Arc<RwLock<Vec<ToolDefinition>>>,
+        tools_changed: ToolsChangedCallbacks,
     ) {
         tokio::spawn(async move {
             while let Some(notice) = rx.recv().await {
                 match notice {
                     ServerNotice::ToolsRefreshed { server_name, tools } => {
+                        let new_names: Vec<String> = tools.iter().map(|t| t.name.clone()).collect();
                         // Update the individual server's tool list.
                         if let Err(e) = servers.set_server_tools(&server_name, tools).await {
                             warn!(
@@ -91,14 +149,43 @@ impl McpClient {
                             );
                             continue;
                         }
-                        // Rebuild the global tools cache.
+                        // Rebuild the global tools cache, remembering what
+                        // this server offered before.
                         let all = servers.all_tools().await;
-                        let mut cache = tools_cache.write().await;
-                        *cache = all;
+                        let old_names: Vec<String> = {
+                            let mut cache = tools_cache.write().await;
+                            let old = cache
+                                .iter()
+                                .filter(|t| t.server == server_name)
+                                .map(|t| t.name.clone())
+                                .collect();
+                            *cache = all;
+                            old
+                        };
                         info!(
                             server = %server_name,
                             "Tools cache refreshed from server notification"
                         );
+
+                        let change = ToolsChanged {
+                            added: new_names
+                                .iter()
+                                .filter(|n| !old_names.contains(n))
+                                .cloned()
+                                .collect(),
+                            removed: old_names
+                                .into_iter()
+                                .filter(|n| !new_names.contains(n))
+                                .collect(),
+                            server_name,
+                        };
+                        let callbacks = tools_changed
+                            .read()
+                            .unwrap_or_else(std::sync::PoisonError::into_inner)
+                            .clone();
+                        for callback in callbacks {
+                            callback(&change);
+                        }
                     },
                     ServerNotice::UplinksRegistered { server_name, .. } => {
                         // Uplink registrations are handled by McpPlugin
@@ -285,20 +372,95 @@ impl McpClient {
             task: None,
         };
 
-        let result = peer
-            .call_tool(params)
-            .await
-            .map_err(|e| McpError::ToolCallFailed {
+        let result = peer.call_tool(params).await.map_err(|e| match e {
+            // Keep transport failures distinguishable so callers can retry them.
+            rmcp::ServiceError::Timeout { .. } => McpError::Timeout,
+            rmcp::ServiceError::TransportSend(_) | rmcp::ServiceError::TransportClosed => {
+                McpError::TransportError(e.to_string())
+            },
+            e => McpError::ToolCallFailed {
                 server: server.to_string(),
                 tool: tool.to_string(),
                 reason: e.to_string(),
-            })?;
+            },
+        })?;
 
         info!(server = server, tool = tool, "Tool call completed");
 
         Ok(ToolResult::from(result))
     }
 
+    /// List prompt templates from every running server that offers them.
+    ///
+    /// Prompts are fetched live rather than cached. A server that fails to
+    /// answer is logged and skipped so one broken server does not hide the
+    /// prompts of the others.
+    pub async fn list_prompts(&self) -> Vec<PromptDefinition> {
+        let mut prompts = Vec::new();
+        for server in self.servers.prompt_servers().await {
+            let listed = match self.servers.get_peer(&server).await {
+                Ok(peer) => peer.list_all_prompts().await.map_err(McpError::from),
+                Err(e) => Err(e),
+            };
+            match listed {
+                Ok(listed) => prompts.extend(
+                    listed
+                        .iter()
+                        .map(|p| PromptDefinition::from_rmcp(p, &server)),
+                ),
+                Err(e) => warn!(server = %server, error = %e, "Failed to list MCP prompts"),
+            }
+        }
+        prompts
+    }
+
+    /// Render a prompt template on a server.
+    ///
+    /// Required arguments are validated by the server; use
+    /// [`PromptDefinition::missing_arguments`] to collect them beforehand.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server is not running or rendering fails.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        prompt: &str,
+        args: HashMap<String, String>,
+    ) -> McpResult<PromptResult> {
+        if !self.servers.is_running(server).await {
+            return Err(McpError::ServerNotRunning {
+                name: server.to_string(),
+            });
+        }
+
+        debug!(server = server, prompt = prompt, "Getting MCP prompt");
+
+        let peer = self.servers.get_peer(server).await?;
+
+        let arguments = (!args.is_empty()).then(|| {
+            args.into_iter()
+                .map(|(k, v)| (k, Value::String(v)))
+                .collect()
+        });
+        let params = GetPromptRequestParams {
+            meta: None,
+            name: prompt.to_string(),
+            arguments,
+        };
+
+        let result = peer
+            .get_prompt(params)
+            .await
+            .map_err(|e| McpError::PromptFailed {
+                server: server.to_string(),
+                prompt: prompt.to_string(),
+                reason: e.to_string(),
+            })?;
+
+        Ok(PromptResult::from(result))
+    }
+
     /// Refresh the tools cache from all running servers.
     async fn refresh_tools_cache(&self) -> McpResult<()> {
         let tools = self.servers.all_tools().await;
@@ -406,7 +568,8 @@ impl McpClient {
 
 /// `McpClient` is cheaply cloneable — all fields are `Arc`-wrapped (or
 /// cloneable senders), so clones share the same underlying `ServerManager`,
-/// tools cache, capabilities handler, and notice channel.
+/// tools cache, capabilities handler, notice channel, and tools-changed
+/// callbacks.
 impl Clone for McpClient {
     fn clone(&self) -> Self {
         Self {
@@ -414,6 +577,7 @@ impl Clone for McpClient {
             tools_cache: Arc::clone(&self.tools_cache),
             capabilities: Arc::clone(&self.capabilities),
             notice_tx: self.notice_tx.clone(),
+            tools_changed: Arc::clone(&self.tools_changed),
         }
     }
 }
@@ -447,4 +611,79 @@ mod tests {
         let result = client.get_tool("server", "tool").await.unwrap();
         assert!(result.is_none());
     }
+
+    #[tokio::test]
+    async fn test_tools_changed_notification_refreshes_cache() {
+        let client = McpClient::with_config(ServersConfig::default());
+        client
+            .server_manager()
+            .add_server("fake", ServerConfig::stdio("fake", "true"))
+            .await
+            .unwrap();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        client.on_tools_changed(move |change| {
+            let _ = tx.send(change.clone());
+        });
+
+        let tools = |names: &[&str]| {
+            names
+                .iter()
+                .map(|n| ToolDefinition::new(*n, "fake"))
+                .collect::<Vec<_>>()
+        };
+
+        client
+            .notice_tx
+            .send(ServerNotice::ToolsRefreshed {
+                server_name: "fake".into(),
+                tools: tools(&["read", "write"]),
+            })
+            .unwrap();
+        let change = rx.recv().await.unwrap();
+        assert_eq!(change.server_name, "fake");
+        assert_eq!(change.added, vec!["read", "write"]);
+        assert!(change.removed.is_empty());
+        assert_eq!(client.list_tools().await.unwrap().len(), 2);
+
+        // Dropping a tool mid-session removes it from the cache; a late
+        // call for it fails cleanly instead of panicking.
+        client
+            .notice_tx
+            .send(ServerNotice::ToolsRefreshed {
+                server_name: "fake".into(),
+                tools: tools(&["read", "search"]),
+            })
+            .unwrap();
+        let change = rx.recv().await.unwrap();
+        assert_eq!(change.added, vec!["search"]);
+        assert_eq!(change.removed, vec!["write"]);
+        assert!(client.get_tool("fake", "write").await.unwrap().is_none());
+        assert!(
+            client
+                .call_tool("fake", "write", Value::Null)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tools_changed_for_unknown_server_is_ignored() {
+        let client = McpClient::with_config(ServersConfig::default());
+        let (tx, mut rx) = mpsc::unbounded_channel::<ToolsChanged>();
+        client.on_tools_changed(move |change| {
+            let _ = tx.send(change.clone());
+        });
+
+        client
+            .notice_tx
+            .send(ServerNotice::ToolsRefreshed {
+                server_name: "ghost".into(),
+                tools: vec![ToolDefinition::new("x", "ghost")],
+            })
+            .unwrap();
+        let waited = tokio::time::timeout(std::time::Duration::from_millis(100), rx.recv()).await;
+        assert!(waited.is_err());
+        assert!(client.list_tools().await.unwrap().is_empty());
+    }
 }
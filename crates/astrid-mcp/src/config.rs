@@ -108,6 +108,12 @@ pub struct ServerConfig {
     /// Restart policy when the server process dies.
     #[serde(default)]
     pub restart_policy: RestartPolicy,
+    /// Tools that are safe to run twice, whose timed-out calls are retried.
+    ///
+    /// A timed-out call may still have run on the server, so other tools
+    /// are never retried after a timeout.
+    #[serde(default)]
+    pub idempotent_tools: Vec<String>,
 }
 
 impl ServerConfig {
@@ -130,6 +136,7 @@ impl ServerConfig {
             allowed_read_paths: Vec::new(),
             allowed_write_paths: Vec::new(),
             restart_policy: RestartPolicy::Never,
+            idempotent_tools: Vec::new(),
         }
     }
 
@@ -152,6 +159,7 @@ impl ServerConfig {
             allowed_read_paths: Vec::new(),
             allowed_write_paths: Vec::new(),
             restart_policy: RestartPolicy::Never,
+            idempotent_tools: Vec::new(),
         }
     }
 
@@ -1,5 +1,6 @@
 //! MCP-related error types.
 
+use astrid_core::ErrorClass;
 use thiserror::Error;
 
 /// Errors that can occur with MCP operations.
@@ -59,6 +60,17 @@ pub enum McpError {
         reason: String,
     },
 
+    /// Prompt retrieval failed.
+    #[error("Prompt failed: {server}:{prompt} - {reason}")]
+    PromptFailed {
+        /// Server name.
+        server: String,
+        /// Prompt name.
+        prompt: String,
+        /// Reason for failure.
+        reason: String,
+    },
+
     /// Authorization required.
     #[error("Authorization required for {server}:{tool}")]
     AuthorizationRequired {
@@ -113,6 +125,43 @@ pub enum McpError {
     /// MCP initialization failed.
     #[error("MCP initialization failed: {0}")]
     InitializationFailed(String),
+
+    /// A transient failure persisted through every retry.
+    ///
+    /// The message carries a hint block so a model reading it can tell a
+    /// flaky server from a broken call and pick another approach.
+    #[error(
+        "{last}\n[retry] class={class} attempts={attempts} hint=the server kept failing; \
+         try again later or use a different tool"
+    )]
+    RetriesExhausted {
+        /// Total attempts made, including the first.
+        attempts: u32,
+        /// Error class of the last failure (`network` or `timeout`).
+        class: &'static str,
+        /// The last failure.
+        last: Box<McpError>,
+    },
+}
+
+impl McpError {
+    /// Classify this error for retrying.
+    ///
+    /// Only failures that happen before a request reaches the server
+    /// (server not running, connection refused) are [`ErrorClass::Network`].
+    /// Timeouts are [`ErrorClass::Timeout`]; the call may still have run,
+    /// so callers retry them only for tools known to be idempotent.
+    /// Transport errors mid-call are [`ErrorClass::Permanent`] for the same
+    /// reason, as is everything else.
+    #[must_use]
+    pub fn retry_class(&self) -> ErrorClass {
+        match self {
+            Self::ServerNotRunning { .. } | Self::ConnectionFailed(_) => ErrorClass::Network,
+            Self::Timeout => ErrorClass::Timeout,
+            Self::IoError(e) if e.kind() == std::io::ErrorKind::TimedOut => ErrorClass::Timeout,
+            _ => ErrorClass::Permanent,
+        }
+    }
 }
 
 impl From<rmcp::ServiceError> for McpError {
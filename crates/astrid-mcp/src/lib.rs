@@ -72,12 +72,16 @@ mod secure;
 mod server;
 mod types;
 
-pub use client::McpClient;
+pub use capabilities::{ElicitationHandler, UrlElicitationHandler};
+pub use client::{McpClient, ToolsChanged, ToolsChangedCallback};
 pub use config::{RestartPolicy, ServerConfig, ServersConfig, Transport, validate_server_name};
 pub use error::{McpError, McpResult};
 pub use secure::{SecureMcpClient, ToolAuthorization};
 pub use server::ServerManager;
-pub use types::{ToolContent, ToolDefinition, ToolResult};
+pub use types::{
+    PromptArgument, PromptDefinition, PromptMessage, PromptResult, PromptRole, ToolContent,
+    ToolDefinition, ToolResult,
+};
 
 // Re-export canonical elicitation types from astrid-core for convenience.
 // These are the single source of truth — no duplicates in astrid-mcp.
@@ -41,6 +41,7 @@ pub use crate::{ServerConfig, ServersConfig};
 pub use crate::{ToolContent, ToolDefinition, ToolResult};
 
 // Canonical elicitation types from astrid-core
+pub use crate::{ElicitationHandler, UrlElicitationHandler};
 pub use crate::{
     ElicitationRequest, ElicitationResponse, ElicitationSchema, UrlElicitationRequest,
     UrlElicitationResponse, UrlElicitationType,
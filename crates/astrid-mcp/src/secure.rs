@@ -7,10 +7,12 @@
 
 use astrid_audit::{AuditAction, AuditLog, AuditOutcome, AuthorizationProof};
 use astrid_capabilities::{CapabilityError, CapabilityStore, CapabilityValidator};
-use astrid_core::{Permission, SessionId};
+use astrid_core::{ErrorClass, Jitter, Permission, RetryConfig, RetryPolicy, SessionId};
 use astrid_crypto::ContentHash;
 use serde_json::Value;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
 use tracing::{debug, warn};
 
 use crate::client::McpClient;
@@ -39,6 +41,15 @@ pub enum ToolAuthorization {
     },
 }
 
+/// Default backoff for tool calls that fail transiently: two retries,
+/// 200 ms then up to 400 ms.
+fn default_tool_retry() -> RetryPolicy {
+    RetryPolicy::uniform(
+        RetryConfig::new(2, Duration::from_millis(200), Duration::from_secs(2), 2.0)
+            .with_jitter(Jitter::Full),
+    )
+}
+
 /// Secure MCP client with capability-based authorization.
 pub struct SecureMcpClient {
     /// Underlying MCP client.
@@ -49,6 +60,10 @@ pub struct SecureMcpClient {
     audit: Arc<AuditLog>,
     /// Current session ID.
     session_id: SessionId,
+    /// Backoff for retrying transient tool call failures.
+    retry: RetryPolicy,
+    /// Active workspace; tokens bound to other workspaces do not authorize.
+    workspace: Option<String>,
 }
 
 impl SecureMcpClient {
@@ -65,9 +80,30 @@ impl SecureMcpClient {
             capabilities,
             audit,
             session_id,
+            retry: default_tool_retry(),
+            workspace: None,
         }
     }
 
+    /// Scope capability checks to `workspace` (see
+    /// [`astrid_capabilities::workspace_key`]).
+    ///
+    /// Without a workspace only unbound tokens authorize tool calls.
+    #[must_use]
+    pub fn with_workspace(mut self, workspace: impl Into<String>) -> Self {
+        self.workspace = Some(workspace.into());
+        self
+    }
+
+    /// Set the backoff used to retry tool calls that fail transiently.
+    ///
+    /// See [`McpError::retry_class`] for which failures are retried.
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Check authorization for a tool call.
     ///
     /// This is a read-only validation - no audit entry is written. The audit
@@ -88,8 +124,11 @@ impl SecureMcpClient {
     ) -> McpResult<ToolAuthorization> {
         let resource = format!("mcp://{server}:{tool}");
 
-        let validator = CapabilityValidator::new(&self.capabilities)
+        let mut validator = CapabilityValidator::new(&self.capabilities)
             .trust_issuer(self.audit.runtime_public_key());
+        if let Some(workspace) = &self.workspace {
+            validator = validator.in_workspace(workspace.clone());
+        }
 
         // Layer 4 (#668): tokens are filtered by principal before expiry
         // and signature checks. A token minted for Alice cannot authorize
@@ -164,9 +203,16 @@ impl SecureMcpClient {
     /// Returns an error if not authorized. Use `check_authorization` first
     /// to determine if approval is needed.
     ///
+    /// Failures where the call never reached the server (server not
+    /// running, connection refused) are retried with backoff. Timeouts are
+    /// retried only for tools listed in the server's `idempotent_tools`,
+    /// since a timed-out call may already have run. Every attempt is
+    /// audited separately. When the retries run out, the error is
+    /// [`McpError::RetriesExhausted`].
+    ///
     /// # Errors
     ///
-    /// Returns an error if the tool call fails or arguments cannot be serialized.
+    /// Returns an error if the tool call fails.
     pub async fn call_tool(
         &self,
         server: &str,
@@ -174,26 +220,95 @@ impl SecureMcpClient {
         args: Value,
         authorization: AuthorizationProof,
     ) -> McpResult<ToolResult> {
-        // Compute args hash
-        let args_json =
-            serde_json::to_vec(&args).map_err(|e| McpError::SerializationError(e.to_string()))?;
-        let args_hash = ContentHash::hash(&args_json);
+        // Canonical, so the same arguments hash the same in every version.
+        let args_hash = ContentHash::hash_canonical_json(&args);
+
+        self.retry_tool_call(server, tool, |attempt| {
+            let args = args.clone();
+            let authorization = authorization.clone();
+            async move {
+                self.call_tool_once(server, tool, args, args_hash, authorization, attempt)
+                    .await
+            }
+        })
+        .await
+    }
 
-        // Log the tool call
-        let audit_result = {
-            self.audit.append(
-                self.session_id.clone(),
-                AuditAction::McpToolCall {
-                    server: server.to_string(),
-                    tool: tool.to_string(),
-                    args_hash,
-                },
-                authorization,
-                AuditOutcome::success_with("tool call started"),
-            )
+    /// Run `attempt` (given the 1-based attempt number) under the retry
+    /// policy for `server`'s `tool`.
+    async fn retry_tool_call<T, F, Fut>(
+        &self,
+        server: &str,
+        tool: &str,
+        mut attempt: F,
+    ) -> McpResult<T>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = McpResult<T>>,
+    {
+        let retry_timeouts = self
+            .client
+            .server_manager()
+            .get_config(server)
+            .is_some_and(|config| config.idempotent_tools.iter().any(|t| t == tool));
+        let classify = |e: &McpError| match e.retry_class() {
+            ErrorClass::Timeout if !retry_timeouts => ErrorClass::Permanent,
+            class => class,
         };
+        let attempts = AtomicU32::new(0);
 
-        if let Err(e) = audit_result {
+        let result = astrid_core::retry_with_policy(&self.retry, &classify, || {
+            attempt(attempts.fetch_add(1, Ordering::Relaxed).saturating_add(1))
+        })
+        .await;
+
+        let attempts = attempts.into_inner();
+        match result {
+            Err(last) if attempts > 1 => {
+                let class = match classify(&last) {
+                    ErrorClass::Permanent => return Err(last),
+                    ErrorClass::Timeout => "timeout",
+                    _ => "network",
+                };
+                warn!(server, tool, attempts, error = %last, "Tool call retries exhausted");
+                Err(McpError::RetriesExhausted {
+                    attempts,
+                    class,
+                    last: Box::new(last),
+                })
+            },
+            other => other,
+        }
+    }
+
+    /// Make one audited attempt at a tool call.
+    async fn call_tool_once(
+        &self,
+        server: &str,
+        tool: &str,
+        args: Value,
+        args_hash: ContentHash,
+        authorization: AuthorizationProof,
+        attempt: u32,
+    ) -> McpResult<ToolResult> {
+        let action = || AuditAction::McpToolCall {
+            server: server.to_string(),
+            tool: tool.to_string(),
+            args_hash,
+        };
+        let started = if attempt == 1 {
+            "tool call started".to_string()
+        } else {
+            format!("tool call started (attempt {attempt})")
+        };
+
+        // Log the tool call
+        if let Err(e) = self.audit.append(
+            self.session_id.clone(),
+            action(),
+            authorization,
+            AuditOutcome::success_with(started),
+        ) {
             warn!(error = %e, "Failed to log tool call start");
         }
 
@@ -201,27 +316,20 @@ impl SecureMcpClient {
         let result = self.client.call_tool(server, tool, args).await;
 
         // Log the result
-        {
-            let outcome = match &result {
-                Ok(r) if r.success => AuditOutcome::success_with(r.text_content()),
-                Ok(r) => AuditOutcome::failure(r.error.as_deref().unwrap_or("unknown error")),
-                Err(e) => AuditOutcome::failure(e.to_string()),
-            };
-
-            if let Err(e) = self.audit.append(
-                self.session_id.clone(),
-                AuditAction::McpToolCall {
-                    server: server.to_string(),
-                    tool: tool.to_string(),
-                    args_hash,
-                },
-                AuthorizationProof::System {
-                    reason: "result logging".to_string(),
-                },
-                outcome,
-            ) {
-                warn!(error = %e, "Failed to log tool call result");
-            }
+        let outcome = match &result {
+            Ok(r) if r.success => AuditOutcome::success_with(r.text_content()),
+            Ok(r) => AuditOutcome::failure(r.error.as_deref().unwrap_or("unknown error")),
+            Err(e) => AuditOutcome::failure(e.to_string()),
+        };
+        if let Err(e) = self.audit.append(
+            self.session_id.clone(),
+            action(),
+            AuthorizationProof::System {
+                reason: "result logging".to_string(),
+            },
+            outcome,
+        ) {
+            warn!(error = %e, "Failed to log tool call result");
         }
 
         result
@@ -508,6 +616,8 @@ impl Clone for SecureMcpClient {
             capabilities: Arc::clone(&self.capabilities),
             audit: Arc::clone(&self.audit),
             session_id: self.session_id.clone(),
+            retry: self.retry.clone(),
+            workspace: self.workspace.clone(),
         }
     }
 }
@@ -517,6 +627,7 @@ impl std::fmt::Debug for SecureMcpClient {
         f.debug_struct("SecureMcpClient")
             .field("client", &self.client)
             .field("session_id", &self.session_id)
+            .field("workspace", &self.workspace)
             .finish_non_exhaustive()
     }
 }
@@ -710,4 +821,198 @@ mod tests {
         // shutdown on empty client succeeds
         assert!(secure.shutdown().await.is_ok());
     }
+
+    fn quick_retry(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy::uniform(RetryConfig::new(
+            max_attempts,
+            Duration::from_millis(1),
+            Duration::from_millis(1),
+            2.0,
+        ))
+    }
+
+    fn system_proof() -> AuthorizationProof {
+        AuthorizationProof::System {
+            reason: "test".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transient_failure_retried_and_each_attempt_audited() {
+        let secure = make_secure_client().with_retry_policy(quick_retry(2));
+
+        let err = secure
+            .call_tool("restarting", "read", Value::Null, system_proof())
+            .await
+            .unwrap_err();
+
+        match &err {
+            McpError::RetriesExhausted {
+                attempts,
+                class,
+                last,
+            } => {
+                assert_eq!(*attempts, 3);
+                assert_eq!(*class, "network");
+                assert!(matches!(**last, McpError::ServerNotRunning { .. }));
+            },
+            other => panic!("expected RetriesExhausted, got {other:?}"),
+        }
+        assert!(
+            err.to_string()
+                .contains("[retry] class=network attempts=3 hint=")
+        );
+
+        // A start and a result entry for every attempt.
+        let entries = secure
+            .audit()
+            .get_session_entries(secure.session_id())
+            .unwrap();
+        assert_eq!(entries.len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_returns_original_error() {
+        let secure = make_secure_client().with_retry_policy(quick_retry(0));
+
+        let err = secure
+            .call_tool("restarting", "read", Value::Null, system_proof())
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, McpError::ServerNotRunning { .. }));
+        assert_eq!(
+            secure.audit().count_session(secure.session_id()).unwrap(),
+            2
+        );
+    }
+
+    /// A client whose config marks `server`'s `tool` idempotent.
+    fn make_secure_client_with_idempotent(server: &str, tool: &str) -> SecureMcpClient {
+        let mut config = crate::config::ServersConfig::default();
+        let mut server_config = ServerConfig::stdio(server, "true");
+        server_config.idempotent_tools.push(tool.to_string());
+        config.servers.insert(server.to_string(), server_config);
+        SecureMcpClient::new(
+            McpClient::with_config(config),
+            Arc::new(CapabilityStore::in_memory()),
+            Arc::new(AuditLog::in_memory(KeyPair::generate())),
+            SessionId::new(),
+        )
+    }
+
+    /// An attempt that fails with `error()` `failures` times, then succeeds.
+    fn flaky(
+        calls: &AtomicU32,
+        failures: u32,
+        error: fn() -> McpError,
+    ) -> impl FnMut(u32) -> std::future::Ready<McpResult<u32>> + '_ {
+        move |attempt| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            std::future::ready(if attempt > failures {
+                Ok(attempt)
+            } else {
+                Err(error())
+            })
+        }
+    }
+
+    fn not_running() -> McpError {
+        McpError::ServerNotRunning {
+            name: "restarting".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fails_twice_then_succeeds() {
+        let secure = make_secure_client().with_retry_policy(quick_retry(2));
+        let calls = AtomicU32::new(0);
+
+        let result = secure
+            .retry_tool_call("restarting", "read", flaky(&calls, 2, not_running))
+            .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.into_inner(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_not_retried_by_default() {
+        let secure = make_secure_client().with_retry_policy(quick_retry(2));
+        let calls = AtomicU32::new(0);
+
+        let err = secure
+            .retry_tool_call("srv", "send_email", flaky(&calls, 1, || McpError::Timeout))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, McpError::Timeout));
+        assert_eq!(calls.into_inner(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_transport_error_not_retried() {
+        let secure = make_secure_client().with_retry_policy(quick_retry(2));
+        let calls = AtomicU32::new(0);
+
+        let err = secure
+            .retry_tool_call(
+                "srv",
+                "send_email",
+                flaky(&calls, 1, || McpError::TransportError("closed".into())),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, McpError::TransportError(_)));
+        assert_eq!(calls.into_inner(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_retried_for_idempotent_tool() {
+        let secure =
+            make_secure_client_with_idempotent("srv", "read").with_retry_policy(quick_retry(2));
+        let calls = AtomicU32::new(0);
+
+        let result = secure
+            .retry_tool_call("srv", "read", flaky(&calls, 2, || McpError::Timeout))
+            .await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.into_inner(), 3);
+
+        // Only the listed tool opts in.
+        let calls = AtomicU32::new(0);
+        let err = secure
+            .retry_tool_call("srv", "write", flaky(&calls, 1, || McpError::Timeout))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, McpError::Timeout));
+        assert_eq!(calls.into_inner(), 1);
+    }
+
+    #[test]
+    fn test_retry_class() {
+        assert_eq!(McpError::Timeout.retry_class(), ErrorClass::Timeout);
+        assert_eq!(
+            McpError::ConnectionFailed("refused".into()).retry_class(),
+            ErrorClass::Network
+        );
+        assert_eq!(
+            McpError::TransportError("closed".into()).retry_class(),
+            ErrorClass::Permanent
+        );
+        assert_eq!(
+            McpError::ToolCallFailed {
+                server: "s".into(),
+                tool: "t".into(),
+                reason: "bad args".into(),
+            }
+            .retry_class(),
+            ErrorClass::Permanent
+        );
+        assert_eq!(
+            McpError::IoError(std::io::ErrorKind::ConnectionReset.into()).retry_class(),
+            ErrorClass::Permanent
+        );
+    }
 }
@@ -9,7 +9,7 @@ use std::time::Instant;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
-use astrid_core::retry::RetryConfig;
+use astrid_core::retry::{Jitter, RetryConfig};
 
 use rmcp::ServiceExt;
 use rmcp::service::{Peer, RoleClient, RunningService};
@@ -42,6 +42,9 @@ pub(crate) struct RunningServer {
     pub restart_count: u32,
     /// When the last restart attempt was made (for backoff calculations).
     pub last_restart_attempt: Option<Instant>,
+    /// Jittered cooldown before the next restart, drawn at the last restart.
+    /// Falls back to the un-jittered backoff delay when unset.
+    pub restart_cooldown: Option<std::time::Duration>,
 }
 
 impl RunningServer {
@@ -55,6 +58,7 @@ impl RunningServer {
             ready: false,
             restart_count: 0,
             last_restart_attempt: None,
+            restart_cooldown: None,
         }
     }
 
@@ -809,6 +813,19 @@ impl ServerManager {
         running.values().flat_map(|s| s.tools.clone()).collect()
     }
 
+    /// Names of ready servers that advertised the prompts capability.
+    pub async fn prompt_servers(&self) -> Vec<String> {
+        let running = self.running.read().await;
+        let mut names: Vec<String> = running
+            .iter()
+            .filter(|(_, s)| s.ready)
+            .filter(|(_, s)| s.info.as_ref().is_some_and(|i| i.capabilities.prompts))
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
     /// Check health of all running servers.
     pub async fn health_check(&self) -> HashMap<String, bool> {
         let running = self.running.read().await;
@@ -823,7 +840,8 @@ impl ServerManager {
 
     /// Backoff configuration for restart attempts.
     ///
-    /// Uses 30 s base delay, 5 min cap, exponential base 2.
+    /// Uses 30 s base delay, 5 min cap, exponential base 2, with equal
+    /// jitter so servers that died together do not restart in lockstep.
     fn restart_backoff() -> RetryConfig {
         RetryConfig::new(
             u32::MAX, // max_attempts handled by RestartPolicy, not RetryConfig
@@ -831,6 +849,7 @@ impl ServerManager {
             std::time::Duration::from_secs(300),
             2.0,
         )
+        .with_jitter(Jitter::Equal)
     }
 
     /// Check whether a dead server should be restarted based on its `RestartPolicy`.
@@ -846,11 +865,11 @@ impl ServerManager {
             return false;
         };
 
-        let (restart_count, last_attempt) = {
+        let (restart_count, last_attempt, cooldown) = {
             let running = self.running.read().await;
-            running
-                .get(name)
-                .map_or((0, None), |s| (s.restart_count, s.last_restart_attempt))
+            running.get(name).map_or((0, None, None), |s| {
+                (s.restart_count, s.last_restart_attempt, s.restart_cooldown)
+            })
         };
 
         let allowed = match &config.restart_policy {
@@ -865,11 +884,11 @@ impl ServerManager {
 
         // Check backoff cooldown.
         if let Some(last) = last_attempt {
-            let backoff = Self::restart_backoff();
             // restart_count is 0-indexed for attempts that already happened,
             // but delay_for_attempt(0) = ZERO, so use restart_count directly
             // (it represents the next attempt number).
-            let required_delay = backoff.delay_for_attempt(restart_count);
+            let required_delay = cooldown
+                .unwrap_or_else(|| Self::restart_backoff().delay_for_attempt(restart_count));
             if last.elapsed() < required_delay {
                 return false;
             }
@@ -905,9 +924,9 @@ impl ServerManager {
         // Atomic: check policy + backoff + remove server under a single write lock.
         let prev_count = {
             let mut running = self.running.write().await;
-            let (count, last_attempt) = running
-                .get(name)
-                .map_or((0, None), |s| (s.restart_count, s.last_restart_attempt));
+            let (count, last_attempt, cooldown) = running.get(name).map_or((0, None, None), |s| {
+                (s.restart_count, s.last_restart_attempt, s.restart_cooldown)
+            });
 
             let allowed = match &config.restart_policy {
                 RestartPolicy::Never => false,
@@ -922,7 +941,7 @@ impl ServerManager {
             // Check backoff cooldown: if the required delay has not elapsed
             // since the last restart attempt, skip this restart.
             if let Some(last) = last_attempt {
-                let required_delay = backoff.delay_for_attempt(count);
+                let required_delay = cooldown.unwrap_or_else(|| backoff.delay_for_attempt(count));
                 if last.elapsed() < required_delay {
                     return Ok(false);
                 }
@@ -954,6 +973,8 @@ impl ServerManager {
             if let Some(server) = running.get_mut(name) {
                 server.restart_count = new_count;
                 server.last_restart_attempt = Some(Instant::now());
+                server.restart_cooldown =
+                    Some(backoff.jittered_delay(new_count, &mut rand::thread_rng()));
             }
         }
 
@@ -1061,6 +1082,14 @@ mod tests {
             backoff.delay_for_attempt(10),
             std::time::Duration::from_secs(300)
         );
+
+        // Cooldowns are drawn from the upper half of the delay.
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let cooldown = backoff.jittered_delay(2, &mut rng);
+            assert!(cooldown >= std::time::Duration::from_secs(30));
+            assert!(cooldown <= std::time::Duration::from_mins(1));
+        }
     }
 
     #[tokio::test]
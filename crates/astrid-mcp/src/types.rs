@@ -1,4 +1,6 @@
-//! MCP types for tools, resources, and results.
+//! MCP types for tools, prompts, resources, and results.
+
+use std::collections::HashMap;
 
 use rmcp::model::{self as rmcp_model, RawContent};
 use serde::{Deserialize, Serialize};
@@ -178,27 +180,7 @@ impl ToolContent {
                 data: image.data.clone(),
                 mime_type: image.mime_type.clone(),
             },
-            RawContent::Resource(embedded) => {
-                let (uri, data, mime_type) = match &embedded.resource {
-                    rmcp_model::ResourceContents::TextResourceContents {
-                        uri,
-                        mime_type,
-                        text,
-                        ..
-                    } => (uri.clone(), Some(text.clone()), mime_type.clone()),
-                    rmcp_model::ResourceContents::BlobResourceContents {
-                        uri,
-                        mime_type,
-                        blob,
-                        ..
-                    } => (uri.clone(), Some(blob.clone()), mime_type.clone()),
-                };
-                Self::Resource {
-                    uri,
-                    data,
-                    mime_type,
-                }
-            },
+            RawContent::Resource(embedded) => Self::from_embedded(embedded),
             // Audio and ResourceLink variants map to text fallbacks
             RawContent::Audio(_) => Self::Text {
                 text: "[audio content]".to_string(),
@@ -210,6 +192,159 @@ impl ToolContent {
             },
         }
     }
+
+    /// Convert from the content of an rmcp `PromptMessage`.
+    fn from_rmcp_prompt(content: &rmcp_model::PromptMessageContent) -> Self {
+        match content {
+            rmcp_model::PromptMessageContent::Text { text } => Self::Text { text: text.clone() },
+            rmcp_model::PromptMessageContent::Image { image } => Self::Image {
+                data: image.data.clone(),
+                mime_type: image.mime_type.clone(),
+            },
+            rmcp_model::PromptMessageContent::Resource { resource } => {
+                Self::from_embedded(resource)
+            },
+            rmcp_model::PromptMessageContent::ResourceLink { link } => Self::Resource {
+                uri: link.uri.clone(),
+                data: None,
+                mime_type: link.mime_type.clone(),
+            },
+        }
+    }
+
+    /// Convert an embedded resource, keeping its text or base64 blob.
+    fn from_embedded(embedded: &rmcp_model::RawEmbeddedResource) -> Self {
+        let (uri, data, mime_type) = match &embedded.resource {
+            rmcp_model::ResourceContents::TextResourceContents {
+                uri,
+                mime_type,
+                text,
+                ..
+            } => (uri.clone(), Some(text.clone()), mime_type.clone()),
+            rmcp_model::ResourceContents::BlobResourceContents {
+                uri,
+                mime_type,
+                blob,
+                ..
+            } => (uri.clone(), Some(blob.clone()), mime_type.clone()),
+        };
+        Self::Resource {
+            uri,
+            data,
+            mime_type,
+        }
+    }
+}
+
+/// Definition of an MCP prompt template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptDefinition {
+    /// Prompt name.
+    pub name: String,
+    /// Server this prompt belongs to.
+    pub server: String,
+    /// Human-readable description.
+    pub description: Option<String>,
+    /// Arguments the template accepts.
+    pub arguments: Vec<PromptArgument>,
+}
+
+impl PromptDefinition {
+    /// Create from an rmcp `Prompt` and server name.
+    #[must_use]
+    pub fn from_rmcp(prompt: &rmcp_model::Prompt, server: &str) -> Self {
+        Self {
+            name: prompt.name.clone(),
+            server: server.to_string(),
+            description: prompt.description.clone(),
+            arguments: prompt
+                .arguments
+                .iter()
+                .flatten()
+                .map(|arg| PromptArgument {
+                    name: arg.name.clone(),
+                    description: arg.description.clone(),
+                    required: arg.required.unwrap_or(false),
+                })
+                .collect(),
+        }
+    }
+
+    /// Get the full prompt identifier (server:prompt).
+    #[must_use]
+    pub fn full_name(&self) -> String {
+        format!("{}:{}", self.server, self.name)
+    }
+
+    /// Required arguments not present in `provided`, in declaration order.
+    ///
+    /// Frontends use this to decide which values to collect from the user
+    /// before calling [`McpClient::get_prompt`](crate::McpClient::get_prompt).
+    #[must_use]
+    pub fn missing_arguments(&self, provided: &HashMap<String, String>) -> Vec<&PromptArgument> {
+        self.arguments
+            .iter()
+            .filter(|arg| arg.required && !provided.contains_key(&arg.name))
+            .collect()
+    }
+}
+
+/// An argument accepted by an MCP prompt template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArgument {
+    /// Argument name.
+    pub name: String,
+    /// Human-readable description.
+    pub description: Option<String>,
+    /// Whether the prompt cannot be rendered without it.
+    pub required: bool,
+}
+
+/// Who a rendered prompt message is attributed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptRole {
+    /// The user.
+    User,
+    /// The assistant.
+    Assistant,
+}
+
+/// One message of a rendered prompt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptMessage {
+    /// Who the message is attributed to.
+    pub role: PromptRole,
+    /// Message content.
+    pub content: ToolContent,
+}
+
+/// A prompt rendered by its server via `prompts/get`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptResult {
+    /// Description of the rendered prompt, if the server sent one.
+    pub description: Option<String>,
+    /// The rendered messages, in order.
+    pub messages: Vec<PromptMessage>,
+}
+
+impl From<rmcp_model::GetPromptResult> for PromptResult {
+    fn from(result: rmcp_model::GetPromptResult) -> Self {
+        Self {
+            description: result.description,
+            messages: result
+                .messages
+                .iter()
+                .map(|m| PromptMessage {
+                    role: match m.role {
+                        rmcp_model::PromptMessageRole::User => PromptRole::User,
+                        rmcp_model::PromptMessageRole::Assistant => PromptRole::Assistant,
+                    },
+                    content: ToolContent::from_rmcp_prompt(&m.content),
+                })
+                .collect(),
+        }
+    }
 }
 
 /// Server capabilities.
@@ -308,4 +443,73 @@ mod tests {
         assert!(result.is_error);
         assert_eq!(result.error, Some("Something went wrong".to_string()));
     }
+
+    fn review_prompt() -> PromptDefinition {
+        let prompt: rmcp_model::Prompt = serde_json::from_value(serde_json::json!({
+            "name": "review",
+            "description": "Review a file",
+            "arguments": [
+                {"name": "path", "required": true},
+                {"name": "focus", "description": "What to look for"}
+            ]
+        }))
+        .unwrap();
+        PromptDefinition::from_rmcp(&prompt, "git")
+    }
+
+    #[test]
+    fn test_prompt_definition_from_rmcp() {
+        let prompt = review_prompt();
+        assert_eq!(prompt.full_name(), "git:review");
+        assert_eq!(prompt.description.as_deref(), Some("Review a file"));
+        let args: Vec<(&str, bool)> = prompt
+            .arguments
+            .iter()
+            .map(|a| (a.name.as_str(), a.required))
+            .collect();
+        assert_eq!(args, [("path", true), ("focus", false)]);
+    }
+
+    #[test]
+    fn test_prompt_missing_arguments() {
+        let prompt = review_prompt();
+        let missing: Vec<&str> = prompt
+            .missing_arguments(&HashMap::new())
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        assert_eq!(missing, ["path"]);
+
+        let provided = HashMap::from([("path".to_string(), "src/lib.rs".to_string())]);
+        assert!(prompt.missing_arguments(&provided).is_empty());
+    }
+
+    #[test]
+    fn test_prompt_result_from_rmcp() {
+        let result: rmcp_model::GetPromptResult = serde_json::from_value(serde_json::json!({
+            "description": "Review of src/lib.rs",
+            "messages": [
+                {"role": "user", "content": {"type": "text", "text": "Review src/lib.rs"}},
+                {"role": "assistant", "content": {"type": "image", "data": "aGk=", "mimeType": "image/png"}}
+            ]
+        }))
+        .unwrap();
+
+        let rendered = PromptResult::from(result);
+        assert_eq!(
+            rendered.description.as_deref(),
+            Some("Review of src/lib.rs")
+        );
+        assert_eq!(rendered.messages.len(), 2);
+        assert_eq!(rendered.messages[0].role, PromptRole::User);
+        assert!(matches!(
+            &rendered.messages[0].content,
+            ToolContent::Text { text } if text == "Review src/lib.rs"
+        ));
+        assert_eq!(rendered.messages[1].role, PromptRole::Assistant);
+        assert!(matches!(
+            &rendered.messages[1].content,
+            ToolContent::Image { mime_type, .. } if mime_type == "image/png"
+        ));
+    }
 }
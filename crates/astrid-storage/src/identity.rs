@@ -19,6 +19,7 @@ use async_trait::async_trait;
 use chrono::Utc;
 use uuid::Uuid;
 
+use astrid_core::identity::linking::{LinkError, LinkStore};
 use astrid_core::identity::types::{AstridUserId, FrontendLink, normalize_platform};
 
 use crate::kv::ScopedKvStore;
@@ -453,6 +454,43 @@ impl IdentityStore for KvIdentityStore {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Linking
+// ---------------------------------------------------------------------------
+
+/// Lets a [`LinkingService`](astrid_core::LinkingService) persist links in
+/// any identity store.
+#[async_trait]
+impl LinkStore for dyn IdentityStore {
+    async fn resolve_link(
+        &self,
+        platform: &str,
+        platform_user_id: &str,
+    ) -> Result<Option<AstridUserId>, LinkError> {
+        self.resolve(platform, platform_user_id)
+            .await
+            .map_err(|e| LinkError::Store(e.to_string()))
+    }
+
+    async fn save_link(
+        &self,
+        platform: &str,
+        platform_user_id: &str,
+        astrid_user_id: Uuid,
+        method: &str,
+    ) -> Result<FrontendLink, LinkError> {
+        self.link(platform, platform_user_id, astrid_user_id, method)
+            .await
+            .map_err(|e| LinkError::Store(e.to_string()))
+    }
+
+    async fn remove_link(&self, platform: &str, platform_user_id: &str) -> Result<bool, LinkError> {
+        self.unlink(platform, platform_user_id)
+            .await
+            .map_err(|e| LinkError::Store(e.to_string()))
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -806,4 +844,22 @@ mod tests {
         assert_eq!(users.len(), 1);
         assert_eq!(users[0].id, b.id);
     }
+
+    #[tokio::test]
+    async fn linking_service_persists_links_in_identity_store() {
+        let store: Arc<dyn IdentityStore> = Arc::new(make_store());
+        let user = store.create_user(Some("alice")).await.unwrap();
+        let svc = astrid_core::LinkingService::new(Arc::clone(&store));
+
+        let code = svc.start_link(&user, "discord").await.unwrap();
+        svc.complete_link("discord", "42", &code.code)
+            .await
+            .unwrap();
+        let links = store.list_links(user.id).await.unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].method, "code");
+
+        assert!(svc.revoke("discord", "42").await.unwrap());
+        assert!(store.resolve("discord", "42").await.unwrap().is_none());
+    }
 }
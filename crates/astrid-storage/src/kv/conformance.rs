@@ -0,0 +1,415 @@
+//! Shared conformance suite for [`KvStore`] backends.
+//!
+//! Each check takes a `&dyn KvStore`; [`kv_conformance_suite!`] expands to one
+//! `#[tokio::test]` per check for a given backend constructor, so every
+//! backend is held to identical scan, batch and expiry semantics.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::StreamExt;
+
+use super::{KvEntry, KvOp, KvStore, MemoryKvStore};
+use crate::error::{StorageError, StorageResult};
+
+const SHORT_TTL: Duration = Duration::from_millis(20);
+const LONG_TTL: Duration = Duration::from_hours(1);
+
+/// Sleep long enough for [`SHORT_TTL`] entries to expire.
+async fn wait_for_expiry() {
+    tokio::time::sleep(Duration::from_millis(80)).await;
+}
+
+async fn scan(store: &dyn KvStore, namespace: &str, prefix: &str) -> StorageResult<Vec<KvEntry>> {
+    store
+        .scan_prefix(namespace, prefix)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect()
+}
+
+async fn scan_keys(store: &dyn KvStore, namespace: &str, prefix: &str) -> Vec<String> {
+    scan(store, namespace, prefix)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.key)
+        .collect()
+}
+
+fn set(key: &str, value: &[u8]) -> KvOp {
+    KvOp::Set {
+        key: key.to_string(),
+        value: value.to_vec(),
+    }
+}
+
+async fn check_scan_prefix_sorted_and_filtered(store: &dyn KvStore) {
+    for key in ["b/2", "a/1", "b/1", "c", "b"] {
+        store.set("ns", key, key.as_bytes().to_vec()).await.unwrap();
+    }
+
+    let entries = scan(store, "ns", "b/").await.unwrap();
+    assert_eq!(
+        entries,
+        vec![
+            KvEntry {
+                namespace: "ns".into(),
+                key: "b/1".into(),
+                value: b"b/1".to_vec(),
+            },
+            KvEntry {
+                namespace: "ns".into(),
+                key: "b/2".into(),
+                value: b"b/2".to_vec(),
+            },
+        ]
+    );
+    assert_eq!(
+        scan_keys(store, "ns", "").await,
+        vec!["a/1", "b", "b/1", "b/2", "c"]
+    );
+    assert!(scan_keys(store, "ns", "z").await.is_empty());
+}
+
+async fn check_scan_prefix_namespace_isolation(store: &dyn KvStore) {
+    store.set("user", "k", b"mine".to_vec()).await.unwrap();
+    store.set("user:x", "k", b"theirs".to_vec()).await.unwrap();
+    store.set("use", "rk", b"near".to_vec()).await.unwrap();
+
+    let entries = scan(store, "user", "").await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].namespace, "user");
+    assert_eq!(entries[0].value, b"mine");
+
+    // The separator cannot be smuggled in through the prefix.
+    let err = scan(store, "user", "\0k").await.unwrap_err();
+    assert!(matches!(err, StorageError::InvalidKey(_)));
+}
+
+async fn check_scan_prefix_many_entries(store: &dyn KvStore) {
+    let ops = (0..600).map(|i| set(&format!("k{i:04}"), b"v")).collect();
+    store.batch("ns", ops).await.unwrap();
+    store.set("ns", "other", b"v".to_vec()).await.unwrap();
+
+    let keys = scan_keys(store, "ns", "k").await;
+    assert_eq!(keys.len(), 600);
+    assert!(keys.windows(2).all(|w| w[0] < w[1]));
+}
+
+async fn check_batch_applies_in_order(store: &dyn KvStore) {
+    store
+        .batch(
+            "ns",
+            vec![
+                set("a", b"1"),
+                set("b", b"2"),
+                KvOp::Delete { key: "a".into() },
+                set("c", b"3"),
+                set("b", b"4"),
+                KvOp::Delete {
+                    key: "missing".into(),
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(store.get("ns", "a").await.unwrap(), None);
+    assert_eq!(store.get("ns", "b").await.unwrap(), Some(b"4".to_vec()));
+    assert_eq!(store.get("ns", "c").await.unwrap(), Some(b"3".to_vec()));
+}
+
+async fn check_batch_rejects_invalid_key_atomically(store: &dyn KvStore) {
+    store.set("ns", "existing", b"old".to_vec()).await.unwrap();
+
+    for bad in ["", "bad\0key"] {
+        let err = store
+            .batch(
+                "ns",
+                vec![set("a", b"1"), set("existing", b"new"), set(bad, b"x")],
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::InvalidKey(_)));
+    }
+
+    assert_eq!(store.get("ns", "a").await.unwrap(), None);
+    assert_eq!(
+        store.get("ns", "existing").await.unwrap(),
+        Some(b"old".to_vec())
+    );
+}
+
+async fn check_ttl_entries_expire(store: &dyn KvStore) {
+    store
+        .set_with_ttl("ns", "short", b"s".to_vec(), SHORT_TTL)
+        .await
+        .unwrap();
+    store
+        .set_with_ttl("ns", "long", b"l".to_vec(), LONG_TTL)
+        .await
+        .unwrap();
+    assert_eq!(store.get("ns", "short").await.unwrap(), Some(b"s".to_vec()));
+
+    wait_for_expiry().await;
+
+    assert_eq!(store.get("ns", "short").await.unwrap(), None);
+    assert!(!store.exists("ns", "short").await.unwrap());
+    assert_eq!(store.list_keys("ns").await.unwrap(), vec!["long"]);
+    assert_eq!(scan_keys(store, "ns", "").await, vec!["long"]);
+    assert!(!store.delete("ns", "short").await.unwrap());
+    assert_eq!(store.get("ns", "long").await.unwrap(), Some(b"l".to_vec()));
+}
+
+async fn check_plain_set_clears_ttl(store: &dyn KvStore) {
+    store
+        .set_with_ttl("ns", "k", b"v1".to_vec(), SHORT_TTL)
+        .await
+        .unwrap();
+    store.set("ns", "k", b"v2".to_vec()).await.unwrap();
+
+    wait_for_expiry().await;
+
+    assert_eq!(store.get("ns", "k").await.unwrap(), Some(b"v2".to_vec()));
+}
+
+async fn check_batch_with_ttl(store: &dyn KvStore) {
+    store
+        .batch(
+            "ns",
+            vec![
+                KvOp::SetWithTtl {
+                    key: "temp".into(),
+                    value: b"t".to_vec(),
+                    ttl: SHORT_TTL,
+                },
+                set("kept", b"k"),
+            ],
+        )
+        .await
+        .unwrap();
+
+    wait_for_expiry().await;
+
+    assert_eq!(store.get("ns", "temp").await.unwrap(), None);
+    assert_eq!(store.get("ns", "kept").await.unwrap(), Some(b"k".to_vec()));
+}
+
+async fn check_purge_expired(store: &dyn KvStore) {
+    store
+        .set_with_ttl("ns1", "a", b"a".to_vec(), SHORT_TTL)
+        .await
+        .unwrap();
+    store
+        .set_with_ttl("ns2", "b", b"b".to_vec(), SHORT_TTL)
+        .await
+        .unwrap();
+    store
+        .set_with_ttl("ns1", "c", b"c".to_vec(), LONG_TTL)
+        .await
+        .unwrap();
+    store.set("ns1", "d", b"d".to_vec()).await.unwrap();
+
+    wait_for_expiry().await;
+
+    assert_eq!(store.purge_expired().await.unwrap(), 2);
+    assert_eq!(store.purge_expired().await.unwrap(), 0);
+    assert_eq!(store.list_keys("ns1").await.unwrap(), vec!["c", "d"]);
+    assert!(store.list_keys("ns2").await.unwrap().is_empty());
+}
+
+async fn check_clear_ignores_expired(store: &dyn KvStore) {
+    store.set("ns", "live", b"v".to_vec()).await.unwrap();
+    store
+        .set_with_ttl("ns", "dead", b"v".to_vec(), SHORT_TTL)
+        .await
+        .unwrap();
+
+    wait_for_expiry().await;
+
+    assert_eq!(store.clear_namespace("ns").await.unwrap(), 1);
+    assert!(store.list_keys("ns").await.unwrap().is_empty());
+    assert_eq!(store.purge_expired().await.unwrap(), 0);
+}
+
+// -- Snapshot checks (source store + empty target store) --
+
+async fn snapshot_bytes(store: &dyn KvStore) -> Vec<u8> {
+    let mut buf = Vec::new();
+    store.snapshot(&mut buf).await.unwrap();
+    buf
+}
+
+async fn check_snapshot_round_trip(source: Arc<dyn KvStore>, target: &dyn KvStore) {
+    source.set("ns1", "a", b"1".to_vec()).await.unwrap();
+    source.set("ns1", "b/c", vec![0, 255, 7]).await.unwrap();
+    source.set("ns2", "a", Vec::new()).await.unwrap();
+    source
+        .set_with_ttl("ns2", "soon", b"s".to_vec(), Duration::from_millis(300))
+        .await
+        .unwrap();
+    source
+        .set_with_ttl("ns2", "gone", b"g".to_vec(), SHORT_TTL)
+        .await
+        .unwrap();
+    wait_for_expiry().await;
+
+    let bytes = snapshot_bytes(source.as_ref()).await;
+    assert_eq!(target.restore(&mut bytes.as_slice()).await.unwrap(), 4);
+
+    for ns in ["ns1", "ns2"] {
+        assert_eq!(
+            scan(target, ns, "").await.unwrap(),
+            scan(source.as_ref(), ns, "").await.unwrap()
+        );
+    }
+    assert_eq!(target.get("ns2", "gone").await.unwrap(), None);
+
+    // The expiry deadline travels with the entry.
+    tokio::time::sleep(Duration::from_millis(400)).await;
+    assert_eq!(target.get("ns2", "soon").await.unwrap(), None);
+}
+
+async fn check_restore_rejects_corrupt_and_non_empty(
+    source: Arc<dyn KvStore>,
+    target: &dyn KvStore,
+) {
+    source.set("ns", "k", b"value".to_vec()).await.unwrap();
+    let bytes = snapshot_bytes(source.as_ref()).await;
+
+    let mut flipped = bytes.clone();
+    let mid = flipped.len() / 2;
+    flipped[mid] ^= 0x01;
+    let truncated = &bytes[..bytes.len().saturating_sub(1)];
+    for bad in [flipped.as_slice(), truncated, b"not a snapshot"] {
+        let err = target.restore(&mut &bad[..]).await.unwrap_err();
+        assert!(matches!(err, StorageError::Serialization(_)), "{err:?}");
+    }
+    assert!(target.list_keys("ns").await.unwrap().is_empty());
+
+    target.set("ns", "other", b"x".to_vec()).await.unwrap();
+    let err = target.restore(&mut bytes.as_slice()).await.unwrap_err();
+    assert!(matches!(err, StorageError::Internal(_)), "{err:?}");
+    assert_eq!(target.get("ns", "k").await.unwrap(), None);
+}
+
+async fn check_snapshot_under_concurrent_writes(source: Arc<dyn KvStore>, target: &dyn KvStore) {
+    const WRITES: u32 = 2000;
+    let seed = (0..200)
+        .map(|i| set(&format!("seed/{i:03}"), b"v"))
+        .collect();
+    source.batch("ns", seed).await.unwrap();
+
+    let writer = {
+        let store = Arc::clone(&source);
+        tokio::spawn(async move {
+            for i in 0..WRITES {
+                store
+                    .set("ns", &format!("seq/{i:05}"), b"v".to_vec())
+                    .await
+                    .unwrap();
+                tokio::task::yield_now().await;
+            }
+        })
+    };
+    while source
+        .list_keys_with_prefix("ns", "seq/")
+        .await
+        .unwrap()
+        .is_empty()
+    {
+        tokio::task::yield_now().await;
+    }
+
+    let bytes = snapshot_bytes(source.as_ref()).await;
+    writer.await.unwrap();
+    target.restore(&mut bytes.as_slice()).await.unwrap();
+
+    // Writes land strictly in order, so a point-in-time view holds an
+    // unbroken prefix of them — never a later write without an earlier one.
+    let seq = scan_keys(target, "ns", "seq/").await;
+    let expected: Vec<String> = (0..seq.len()).map(|i| format!("seq/{i:05}")).collect();
+    assert!(!seq.is_empty());
+    assert_eq!(seq, expected);
+    assert_eq!(scan_keys(target, "ns", "seed/").await.len(), 200);
+    assert_eq!(
+        source
+            .list_keys_with_prefix("ns", "seq/")
+            .await
+            .unwrap()
+            .len(),
+        WRITES as usize
+    );
+}
+
+/// Expand to one `#[tokio::test]` per conformance check.
+///
+/// `$make` is an expression returning `(store, guard)`; the guard is held
+/// for the duration of the test (e.g. a temp directory). Snapshot checks
+/// get two independent stores.
+macro_rules! kv_conformance_suite {
+    ($make:expr; $($check:ident),+; $($pair:ident),+ $(,)?) => {
+        $(
+            #[tokio::test]
+            async fn $check() {
+                let (store, _guard) = $make;
+                super::$check(&store).await;
+            }
+        )+
+        $(
+            #[tokio::test(flavor = "multi_thread")]
+            async fn $pair() {
+                let (source, _source_guard) = $make;
+                let (target, _target_guard) = $make;
+                super::$pair(std::sync::Arc::new(source), &target).await;
+            }
+        )+
+    };
+    ($make:expr) => {
+        kv_conformance_suite!(
+            $make;
+            check_scan_prefix_sorted_and_filtered,
+            check_scan_prefix_namespace_isolation,
+            check_scan_prefix_many_entries,
+            check_batch_applies_in_order,
+            check_batch_rejects_invalid_key_atomically,
+            check_ttl_entries_expire,
+            check_plain_set_clears_ttl,
+            check_batch_with_ttl,
+            check_purge_expired,
+            check_clear_ignores_expired;
+            check_snapshot_round_trip,
+            check_restore_rejects_corrupt_and_non_empty,
+            check_snapshot_under_concurrent_writes,
+        );
+    };
+}
+
+mod memory {
+    use super::MemoryKvStore;
+
+    kv_conformance_suite!((MemoryKvStore::new(), ()));
+}
+
+#[cfg(feature = "kv")]
+mod surreal {
+    use super::super::SurrealKvStore;
+
+    fn make_store() -> (SurrealKvStore, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SurrealKvStore::open(dir.path()).unwrap();
+        (store, dir)
+    }
+
+    kv_conformance_suite!(make_store());
+}
+
+/// The metrics decorator must be semantically transparent.
+mod instrumented {
+    use super::super::InstrumentedKvStore;
+    use super::MemoryKvStore;
+
+    kv_conformance_suite!((InstrumentedKvStore::new(MemoryKvStore::new()), ()));
+}
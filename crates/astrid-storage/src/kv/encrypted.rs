@@ -0,0 +1,550 @@
+// ---------------------------------------------------------------------------
+// Encryption at rest
+// ---------------------------------------------------------------------------
+//
+// Every value is sealed with XChaCha20-Poly1305 before it reaches the inner
+// store:
+//
+//   value    "ASTRIDE1" (8 bytes) | nonce (24 bytes) | ciphertext + tag
+//
+// The associated data is the magic followed by the length-prefixed
+// namespace and the key, so a ciphertext copied to another key or
+// namespace fails authentication instead of decrypting there. Namespaces,
+// keys, and expiry deadlines stay in the clear so listings, prefix scans,
+// and TTL sweeps keep working on the inner store.
+
+use std::time::Duration;
+
+use astrid_crypto::KeyPair;
+use async_trait::async_trait;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use futures::StreamExt;
+
+use super::snapshot::{SnapshotWriter, read_snapshot};
+use super::{KvEntry, KvEntryStream, KvOp, KvStore};
+use crate::error::{StorageError, StorageResult};
+
+const MAGIC: &[u8; 8] = b"ASTRIDE1";
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+const HEADER_LEN: usize = MAGIC.len() + NONCE_LEN;
+/// Magic plus the `u32` namespace length.
+const AAD_PREFIX_LEN: usize = MAGIC.len() + 4;
+
+/// Key derivation context for the storage key; see [`KeyPair::derive_key`].
+const KEY_CONTEXT: &str = "astrid kv encryption at rest v1";
+
+/// A [`KvStore`] wrapper that encrypts every value at rest.
+///
+/// Values are sealed with XChaCha20-Poly1305 under a key derived from a
+/// root [`KeyPair`] (normally [`astrid_crypto::KeyStore::STORAGE`], kept in
+/// a `0600` key file or the OS keychain). The namespace and key are bound
+/// into each ciphertext, so swapping values between keys is detected on
+/// read. The cipher zeroizes its key when the store is dropped.
+///
+/// Namespaces, keys and expiry deadlines are not encrypted. Snapshots
+/// carry the ciphertexts and restore into any store opened with the same
+/// root key.
+pub struct EncryptedKvStore<S> {
+    inner: S,
+    cipher: XChaCha20Poly1305,
+}
+
+impl<S: KvStore> EncryptedKvStore<S> {
+    /// Wrap `inner`, encrypting with a key derived from `root`.
+    #[must_use]
+    pub fn new(inner: S, root: &KeyPair) -> Self {
+        let key = root.derive_key(KEY_CONTEXT);
+        Self {
+            inner,
+            cipher: XChaCha20Poly1305::new(key.as_ref().into()),
+        }
+    }
+
+    /// The wrapped store, which holds only ciphertexts.
+    #[must_use]
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Copy every entry of a plaintext store into this one, encrypting as
+    /// it goes. Returns the number of entries migrated.
+    ///
+    /// This is the one-time migration for an existing unencrypted store:
+    /// open a fresh backend, wrap it, import the old one, then switch over.
+    /// Expiry deadlines carry across unchanged. The import is atomic.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Internal`] if this store is not empty or
+    /// either backend fails.
+    pub async fn import_plaintext(&self, source: &dyn KvStore) -> StorageResult<u64> {
+        let mut plaintext = Vec::new();
+        source.snapshot(&mut plaintext).await?;
+        let entries = read_snapshot(&mut plaintext.as_slice())?;
+
+        let mut sealed = Vec::new();
+        let mut writer = SnapshotWriter::begin(&mut sealed)?;
+        for e in &entries {
+            let value = self.seal(&e.entry.namespace, &e.entry.key, &e.entry.value)?;
+            writer.entry(
+                e.entry.namespace.as_bytes(),
+                e.entry.key.as_bytes(),
+                &value,
+                e.expires_at,
+            )?;
+        }
+        writer.finish()?;
+        self.inner.restore(&mut sealed.as_slice()).await
+    }
+
+    fn seal(&self, namespace: &str, key: &str, plaintext: &[u8]) -> StorageResult<Vec<u8>> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad: &aad(namespace, key),
+                },
+            )
+            .map_err(|_| StorageError::Internal("value encryption failed".into()))?;
+        let mut out = Vec::with_capacity(HEADER_LEN.saturating_add(ciphertext.len()));
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    fn open(&self, namespace: &str, key: &str, sealed: &[u8]) -> StorageResult<Vec<u8>> {
+        let body = sealed
+            .strip_prefix(MAGIC.as_slice())
+            .filter(|b| b.len() >= NONCE_LEN + TAG_LEN)
+            .ok_or_else(|| {
+                StorageError::Serialization(format!(
+                    "value for '{namespace}/{key}' is not encrypted"
+                ))
+            })?;
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad(namespace, key),
+                },
+            )
+            .map_err(|_| {
+                StorageError::Serialization(format!(
+                    "value for '{namespace}/{key}' failed authentication"
+                ))
+            })
+    }
+
+    fn seal_ops(&self, namespace: &str, ops: Vec<KvOp>) -> StorageResult<Vec<KvOp>> {
+        ops.into_iter()
+            .map(|op| {
+                Ok(match op {
+                    KvOp::Set { key, value } => KvOp::Set {
+                        value: self.seal(namespace, &key, &value)?,
+                        key,
+                    },
+                    KvOp::SetWithTtl { key, value, ttl } => KvOp::SetWithTtl {
+                        value: self.seal(namespace, &key, &value)?,
+                        key,
+                        ttl,
+                    },
+                    op @ KvOp::Delete { .. } => op,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Namespace and key of the marker that records a store as encrypted.
+const MARKER_NAMESPACE: &str = "system:kv";
+const MARKER_KEY: &str = "encrypted";
+
+#[cfg(feature = "kv")]
+impl EncryptedKvStore<super::SurrealKvStore> {
+    /// Open the persistent store at `path`, encrypting it in place first if
+    /// it still holds plaintext.
+    ///
+    /// The first open of an unencrypted store imports it into a staging
+    /// store next to `path` (`*.encrypting`), swaps the two, and deletes the
+    /// plaintext copy. A marker entry records that the store is encrypted,
+    /// so later opens skip the scan; it is also read back on every open, so
+    /// the wrong root key fails here rather than on first use. An import
+    /// interrupted by a crash is discarded and redone on the next open.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Connection`] if a backend cannot be opened,
+    /// [`StorageError::Serialization`] if the store was encrypted under
+    /// another root key, and [`StorageError::Internal`] if the migration
+    /// fails.
+    pub async fn open_persistent(path: &std::path::Path, root: &KeyPair) -> StorageResult<Self> {
+        let staging = path.with_extension("encrypting");
+        let backup = path.with_extension("plaintext");
+        // Leftovers of an interrupted migration: the staging store is
+        // incomplete, and a backup without `path` is a half-done swap.
+        remove_store(&staging)?;
+        if backup.exists() {
+            if path.exists() {
+                remove_store(&backup)?;
+            } else {
+                std::fs::rename(&backup, path).map_err(|ref e| io_err(e))?;
+            }
+        }
+
+        let store = Self::new(super::SurrealKvStore::open(path)?, root);
+        if store
+            .inner
+            .get(MARKER_NAMESPACE, MARKER_KEY)
+            .await?
+            .is_some()
+        {
+            store.get(MARKER_NAMESPACE, MARKER_KEY).await?;
+            return Ok(store);
+        }
+        if !store.holds_plaintext().await? {
+            store.set(MARKER_NAMESPACE, MARKER_KEY, Vec::new()).await?;
+            return Ok(store);
+        }
+
+        let encrypted = Self::new(super::SurrealKvStore::open(&staging)?, root);
+        let migrated = encrypted.import_plaintext(&store.inner).await?;
+        encrypted
+            .set(MARKER_NAMESPACE, MARKER_KEY, Vec::new())
+            .await?;
+        encrypted.inner.close().await?;
+        store.inner.close().await?;
+        drop((encrypted, store));
+
+        std::fs::rename(path, &backup).map_err(|ref e| io_err(e))?;
+        std::fs::rename(&staging, path).map_err(|ref e| io_err(e))?;
+        remove_store(&backup)?;
+        tracing::info!(entries = migrated, path = %path.display(), "Encrypted KV store at rest");
+
+        Ok(Self::new(super::SurrealKvStore::open(path)?, root))
+    }
+
+    /// Whether the inner store holds any value that is not sealed.
+    async fn holds_plaintext(&self) -> StorageResult<bool> {
+        let mut raw = Vec::new();
+        self.inner.snapshot(&mut raw).await?;
+        Ok(read_snapshot(&mut raw.as_slice())?
+            .iter()
+            .any(|e| !e.entry.value.starts_with(MAGIC)))
+    }
+}
+
+/// Remove a store directory (or file) if it exists.
+#[cfg(feature = "kv")]
+fn remove_store(path: &std::path::Path) -> StorageResult<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path).map_err(|ref e| io_err(e))
+    } else if path.exists() {
+        std::fs::remove_file(path).map_err(|ref e| io_err(e))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "kv")]
+fn io_err(e: &std::io::Error) -> StorageError {
+    StorageError::Internal(e.to_string())
+}
+
+/// Associated data binding a ciphertext to its namespace and key.
+fn aad(namespace: &str, key: &str) -> Vec<u8> {
+    let ns_len = u32::try_from(namespace.len()).unwrap_or(u32::MAX);
+    let mut aad = Vec::with_capacity(
+        AAD_PREFIX_LEN
+            .saturating_add(namespace.len())
+            .saturating_add(key.len()),
+    );
+    aad.extend_from_slice(MAGIC);
+    aad.extend_from_slice(&ns_len.to_be_bytes());
+    aad.extend_from_slice(namespace.as_bytes());
+    aad.extend_from_slice(key.as_bytes());
+    aad
+}
+
+#[async_trait]
+impl<S: KvStore> KvStore for EncryptedKvStore<S> {
+    async fn get(&self, namespace: &str, key: &str) -> StorageResult<Option<Vec<u8>>> {
+        match self.inner.get(namespace, key).await? {
+            Some(sealed) => self.open(namespace, key, &sealed).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn set(&self, namespace: &str, key: &str, value: Vec<u8>) -> StorageResult<()> {
+        super::validate_namespace(namespace)?;
+        super::validate_key(key)?;
+        let sealed = self.seal(namespace, key, &value)?;
+        self.inner.set(namespace, key, sealed).await
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        self.inner.delete(namespace, key).await
+    }
+
+    async fn exists(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        self.inner.exists(namespace, key).await
+    }
+
+    async fn list_keys(&self, namespace: &str) -> StorageResult<Vec<String>> {
+        self.inner.list_keys(namespace).await
+    }
+
+    async fn list_keys_with_prefix(
+        &self,
+        namespace: &str,
+        prefix: &str,
+    ) -> StorageResult<Vec<String>> {
+        self.inner.list_keys_with_prefix(namespace, prefix).await
+    }
+
+    async fn clear_namespace(&self, namespace: &str) -> StorageResult<u64> {
+        self.inner.clear_namespace(namespace).await
+    }
+
+    async fn clear_prefix(&self, namespace: &str, prefix: &str) -> StorageResult<u64> {
+        self.inner.clear_prefix(namespace, prefix).await
+    }
+
+    async fn set_with_ttl(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> StorageResult<()> {
+        super::validate_namespace(namespace)?;
+        super::validate_key(key)?;
+        let sealed = self.seal(namespace, key, &value)?;
+        self.inner.set_with_ttl(namespace, key, sealed, ttl).await
+    }
+
+    fn scan_prefix<'a>(&'a self, namespace: &'a str, prefix: &'a str) -> KvEntryStream<'a> {
+        Box::pin(self.inner.scan_prefix(namespace, prefix).map(move |entry| {
+            let entry = entry?;
+            let value = self.open(&entry.namespace, &entry.key, &entry.value)?;
+            Ok(KvEntry { value, ..entry })
+        }))
+    }
+
+    async fn batch(&self, namespace: &str, ops: Vec<KvOp>) -> StorageResult<()> {
+        super::validate_ops(namespace, &ops)?;
+        let ops = self.seal_ops(namespace, ops)?;
+        self.inner.batch(namespace, ops).await
+    }
+
+    async fn purge_expired(&self) -> StorageResult<u64> {
+        self.inner.purge_expired().await
+    }
+
+    async fn snapshot(&self, writer: &mut (dyn std::io::Write + Send)) -> StorageResult<u64> {
+        self.inner.snapshot(writer).await
+    }
+
+    async fn restore(&self, reader: &mut (dyn std::io::Read + Send)) -> StorageResult<u64> {
+        self.inner.restore(reader).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kv::MemoryKvStore;
+
+    fn store() -> (KeyPair, EncryptedKvStore<MemoryKvStore>) {
+        let root = KeyPair::generate();
+        let store = EncryptedKvStore::new(MemoryKvStore::new(), &root);
+        (root, store)
+    }
+
+    #[tokio::test]
+    async fn values_round_trip_and_are_not_stored_in_the_clear() {
+        let (_root, store) = store();
+        store.set("ns", "k", b"transcript".to_vec()).await.unwrap();
+        assert_eq!(
+            store.get("ns", "k").await.unwrap(),
+            Some(b"transcript".to_vec())
+        );
+
+        let raw = store.inner().get("ns", "k").await.unwrap().unwrap();
+        assert!(raw.starts_with(MAGIC));
+        assert!(!raw.windows(10).any(|w| w == b"transcript"));
+    }
+
+    #[tokio::test]
+    async fn tampered_values_fail_authentication() {
+        let (_root, store) = store();
+        store.set("ns", "k", b"value".to_vec()).await.unwrap();
+        let mut raw = store.inner().get("ns", "k").await.unwrap().unwrap();
+        let last = raw.len() - 1;
+        raw[last] ^= 1;
+        store.inner().set("ns", "k", raw).await.unwrap();
+
+        assert!(matches!(
+            store.get("ns", "k").await,
+            Err(StorageError::Serialization(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn ciphertexts_cannot_be_swapped_between_keys() {
+        let (_root, store) = store();
+        store.set("ns", "a", b"alpha".to_vec()).await.unwrap();
+        store.set("other", "b", b"beta".to_vec()).await.unwrap();
+
+        let a = store.inner().get("ns", "a").await.unwrap().unwrap();
+        store.inner().set("ns", "b", a.clone()).await.unwrap();
+        store.inner().set("other", "a", a).await.unwrap();
+
+        assert!(store.get("ns", "b").await.is_err());
+        assert!(store.get("other", "a").await.is_err());
+        assert_eq!(store.get("ns", "a").await.unwrap(), Some(b"alpha".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn another_root_key_cannot_read() {
+        let (root, store) = store();
+        store.set("ns", "k", b"value".to_vec()).await.unwrap();
+        let same = EncryptedKvStore::new(MemoryKvStore::new(), &root);
+        let other = EncryptedKvStore::new(MemoryKvStore::new(), &KeyPair::generate());
+        let raw = store.inner().get("ns", "k").await.unwrap().unwrap();
+        same.inner().set("ns", "k", raw.clone()).await.unwrap();
+        other.inner().set("ns", "k", raw).await.unwrap();
+
+        assert_eq!(same.get("ns", "k").await.unwrap(), Some(b"value".to_vec()));
+        assert!(other.get("ns", "k").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn scans_and_batches_decrypt() {
+        let (_root, store) = store();
+        store
+            .batch(
+                "ns",
+                vec![
+                    KvOp::Set {
+                        key: "p/1".into(),
+                        value: b"one".to_vec(),
+                    },
+                    KvOp::Set {
+                        key: "p/2".into(),
+                        value: b"two".to_vec(),
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+        let entries: Vec<_> = store
+            .scan_prefix("ns", "p/")
+            .map(|e| e.unwrap().value)
+            .collect()
+            .await;
+        assert_eq!(entries, vec![b"one".to_vec(), b"two".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn import_plaintext_migrates_a_seeded_store() {
+        let plain = MemoryKvStore::new();
+        plain
+            .set("session:1", "transcript", b"hello".to_vec())
+            .await
+            .unwrap();
+        plain
+            .set("capsule:x", "state", b"42".to_vec())
+            .await
+            .unwrap();
+        plain
+            .set_with_ttl("cache", "tmp", b"t".to_vec(), Duration::from_hours(1))
+            .await
+            .unwrap();
+
+        let (_root, store) = store();
+        assert_eq!(store.import_plaintext(&plain).await.unwrap(), 3);
+
+        assert_eq!(
+            store.get("session:1", "transcript").await.unwrap(),
+            Some(b"hello".to_vec())
+        );
+        assert_eq!(
+            store.get("capsule:x", "state").await.unwrap(),
+            Some(b"42".to_vec())
+        );
+        assert_eq!(
+            store.get("cache", "tmp").await.unwrap(),
+            Some(b"t".to_vec())
+        );
+        let raw = store
+            .inner()
+            .get("session:1", "transcript")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(raw.starts_with(MAGIC));
+
+        // The import is one-time: a populated store refuses a second run.
+        assert!(store.import_plaintext(&plain).await.is_err());
+    }
+
+    #[cfg(feature = "kv")]
+    #[tokio::test]
+    async fn open_persistent_encrypts_a_plaintext_store_once() {
+        use crate::kv::SurrealKvStore;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.db");
+        let plain = SurrealKvStore::open(&path).unwrap();
+        plain
+            .set("session:1", "transcript", b"hello".to_vec())
+            .await
+            .unwrap();
+        plain.close().await.unwrap();
+        drop(plain);
+
+        let root = KeyPair::generate();
+        let store = EncryptedKvStore::open_persistent(&path, &root)
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get("session:1", "transcript").await.unwrap(),
+            Some(b"hello".to_vec())
+        );
+        let raw = store
+            .inner()
+            .get("session:1", "transcript")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(raw.starts_with(MAGIC));
+        assert!(!path.with_extension("plaintext").exists());
+        assert!(!path.with_extension("encrypting").exists());
+        store.inner().close().await.unwrap();
+        drop(store);
+
+        // Reopening skips the migration; another root key is refused.
+        let store = EncryptedKvStore::open_persistent(&path, &root)
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get("session:1", "transcript").await.unwrap(),
+            Some(b"hello".to_vec())
+        );
+        store.inner().close().await.unwrap();
+        drop(store);
+        assert!(
+            EncryptedKvStore::open_persistent(&path, &KeyPair::generate())
+                .await
+                .is_err()
+        );
+    }
+}
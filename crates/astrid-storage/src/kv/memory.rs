@@ -0,0 +1,321 @@
+// ---------------------------------------------------------------------------
+// In-memory implementation (always available)
+// ---------------------------------------------------------------------------
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::snapshot::{SnapshotWriter, read_snapshot};
+use super::{
+    KvEntry, KvEntryStream, KvOp, KvStore, expiry_deadline, now_millis, validate_ops,
+    validate_prefix,
+};
+use crate::error::{StorageError, StorageResult};
+
+/// In-memory key-value store for tests and ephemeral data.
+///
+/// Keys are stored as `"{namespace}\0{key}"` in an ordered map so prefix
+/// scans yield entries in key order, matching the persistent backend.
+/// Expiry deadlines live alongside the values under the same lock, so a
+/// batch updates both atomically.
+#[derive(Debug, Default)]
+pub struct MemoryKvStore {
+    data: std::sync::RwLock<MemoryData>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryData {
+    values: BTreeMap<String, Vec<u8>>,
+    /// Absolute expiry deadlines (ms since epoch), keyed like `values`.
+    expiries: HashMap<String, u64>,
+}
+
+impl MemoryData {
+    fn is_live(&self, full_key: &str, now: u64) -> bool {
+        self.values.contains_key(full_key) && self.expiries.get(full_key).is_none_or(|&at| at > now)
+    }
+
+    fn live_keys_with_prefix(&self, full_prefix: &str, now: u64) -> Vec<String> {
+        self.values
+            .range(full_prefix.to_string()..)
+            .map(|(k, _)| k)
+            .take_while(|k| k.starts_with(full_prefix))
+            .filter(|k| self.is_live(k, now))
+            .cloned()
+            .collect()
+    }
+
+    fn remove_matching(&mut self, full_prefix: &str, now: u64) -> u64 {
+        let keys: Vec<String> = self
+            .values
+            .range(full_prefix.to_string()..)
+            .map(|(k, _)| k)
+            .take_while(|k| k.starts_with(full_prefix))
+            .cloned()
+            .collect();
+        let mut live: u64 = 0;
+        for key in keys {
+            if self.is_live(&key, now) {
+                live = live.saturating_add(1);
+            }
+            self.values.remove(&key);
+            self.expiries.remove(&key);
+        }
+        live
+    }
+
+    fn apply(&mut self, full_key: String, op: KvOp) {
+        match op {
+            KvOp::Set { value, .. } => {
+                self.expiries.remove(&full_key);
+                self.values.insert(full_key, value);
+            },
+            KvOp::SetWithTtl { value, ttl, .. } => {
+                self.expiries.insert(full_key.clone(), expiry_deadline(ttl));
+                self.values.insert(full_key, value);
+            },
+            KvOp::Delete { .. } => {
+                self.values.remove(&full_key);
+                self.expiries.remove(&full_key);
+            },
+        }
+    }
+}
+
+impl MemoryKvStore {
+    /// Create a new empty in-memory KV store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn full_key(namespace: &str, key: &str) -> String {
+        format!("{namespace}\0{key}")
+    }
+
+    fn read(&self) -> StorageResult<std::sync::RwLockReadGuard<'_, MemoryData>> {
+        self.data
+            .read()
+            .map_err(|e| StorageError::Internal(e.to_string()))
+    }
+
+    fn write(&self) -> StorageResult<std::sync::RwLockWriteGuard<'_, MemoryData>> {
+        self.data
+            .write()
+            .map_err(|e| StorageError::Internal(e.to_string()))
+    }
+
+    /// Serialize every live entry in the [snapshot](KvStore::snapshot)
+    /// format, e.g. to carry test state between phases.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store lock is poisoned.
+    pub fn dump(&self) -> StorageResult<Vec<u8>> {
+        let mut out = Vec::new();
+        self.write_snapshot(&mut out)?;
+        Ok(out)
+    }
+
+    /// Build a store from bytes produced by [`dump`](Self::dump) or
+    /// [`KvStore::snapshot`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Serialization`] if the bytes are not a valid
+    /// snapshot.
+    pub fn load(mut bytes: &[u8]) -> StorageResult<Self> {
+        let store = Self::new();
+        store.restore_snapshot(&mut bytes)?;
+        Ok(store)
+    }
+
+    fn write_snapshot(&self, writer: &mut (dyn std::io::Write + Send)) -> StorageResult<u64> {
+        // Copy the live entries under the read lock, then release it before
+        // touching the writer so slow I/O never blocks other callers.
+        let entries: Vec<(String, Vec<u8>, Option<u64>)> = {
+            let data = self.read()?;
+            let now = now_millis();
+            data.values
+                .iter()
+                .filter(|(k, _)| data.is_live(k, now))
+                .map(|(k, v)| (k.clone(), v.clone(), data.expiries.get(k).copied()))
+                .collect()
+        };
+
+        let mut out = SnapshotWriter::begin(writer)?;
+        for (full_key, value, expires_at) in &entries {
+            if let Some((namespace, key)) = full_key.split_once('\0') {
+                out.entry(namespace.as_bytes(), key.as_bytes(), value, *expires_at)?;
+            }
+        }
+        out.finish()
+    }
+
+    fn restore_snapshot(&self, reader: &mut (dyn std::io::Read + Send)) -> StorageResult<u64> {
+        let entries = read_snapshot(reader)?;
+        let mut data = self.write()?;
+        if !data.values.is_empty() {
+            return Err(StorageError::Internal("restore target is not empty".into()));
+        }
+        let count = u64::try_from(entries.len()).unwrap_or(u64::MAX);
+        for e in entries {
+            let full_key = Self::full_key(&e.entry.namespace, &e.entry.key);
+            if let Some(at) = e.expires_at {
+                data.expiries.insert(full_key.clone(), at);
+            }
+            data.values.insert(full_key, e.entry.value);
+        }
+        Ok(count)
+    }
+}
+
+#[async_trait]
+impl KvStore for MemoryKvStore {
+    async fn get(&self, namespace: &str, key: &str) -> StorageResult<Option<Vec<u8>>> {
+        let data = self.read()?;
+        let full_key = Self::full_key(namespace, key);
+        if !data.is_live(&full_key, now_millis()) {
+            return Ok(None);
+        }
+        Ok(data.values.get(&full_key).cloned())
+    }
+
+    async fn set(&self, namespace: &str, key: &str, value: Vec<u8>) -> StorageResult<()> {
+        let mut data = self.write()?;
+        let full_key = Self::full_key(namespace, key);
+        data.apply(
+            full_key,
+            KvOp::Set {
+                key: key.to_string(),
+                value,
+            },
+        );
+        Ok(())
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        let mut data = self.write()?;
+        let full_key = Self::full_key(namespace, key);
+        let existed = data.is_live(&full_key, now_millis());
+        data.values.remove(&full_key);
+        data.expiries.remove(&full_key);
+        Ok(existed)
+    }
+
+    async fn exists(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        let data = self.read()?;
+        Ok(data.is_live(&Self::full_key(namespace, key), now_millis()))
+    }
+
+    async fn list_keys(&self, namespace: &str) -> StorageResult<Vec<String>> {
+        self.list_keys_with_prefix(namespace, "").await
+    }
+
+    async fn list_keys_with_prefix(
+        &self,
+        namespace: &str,
+        prefix: &str,
+    ) -> StorageResult<Vec<String>> {
+        let data = self.read()?;
+        let full_prefix = Self::full_key(namespace, prefix);
+        let ns_prefix_len = namespace.len().saturating_add(1);
+        Ok(data
+            .live_keys_with_prefix(&full_prefix, now_millis())
+            .into_iter()
+            .filter_map(|k| k.get(ns_prefix_len..).map(String::from))
+            .collect())
+    }
+
+    async fn clear_namespace(&self, namespace: &str) -> StorageResult<u64> {
+        let mut data = self.write()?;
+        Ok(data.remove_matching(&format!("{namespace}\0"), now_millis()))
+    }
+
+    async fn clear_prefix(&self, namespace: &str, prefix: &str) -> StorageResult<u64> {
+        validate_prefix(prefix)?;
+        let mut data = self.write()?;
+        Ok(data.remove_matching(&Self::full_key(namespace, prefix), now_millis()))
+    }
+
+    async fn set_with_ttl(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> StorageResult<()> {
+        let mut data = self.write()?;
+        data.apply(
+            Self::full_key(namespace, key),
+            KvOp::SetWithTtl {
+                key: key.to_string(),
+                value,
+                ttl,
+            },
+        );
+        Ok(())
+    }
+
+    fn scan_prefix<'a>(&'a self, namespace: &'a str, prefix: &'a str) -> KvEntryStream<'a> {
+        let entries = validate_prefix(prefix).and_then(|()| {
+            let data = self.read()?;
+            let full_prefix = Self::full_key(namespace, prefix);
+            let ns_prefix_len = namespace.len().saturating_add(1);
+            Ok(data
+                .live_keys_with_prefix(&full_prefix, now_millis())
+                .into_iter()
+                .filter_map(|full_key| {
+                    let value = data.values.get(&full_key)?.clone();
+                    Some(KvEntry {
+                        namespace: namespace.to_string(),
+                        key: full_key.get(ns_prefix_len..)?.to_string(),
+                        value,
+                    })
+                })
+                .collect::<Vec<_>>())
+        });
+        let items: Vec<StorageResult<KvEntry>> = match entries {
+            Ok(entries) => entries.into_iter().map(Ok).collect(),
+            Err(e) => vec![Err(e)],
+        };
+        Box::pin(futures::stream::iter(items))
+    }
+
+    async fn batch(&self, namespace: &str, ops: Vec<KvOp>) -> StorageResult<()> {
+        validate_ops(namespace, &ops)?;
+        let mut data = self.write()?;
+        for op in ops {
+            let full_key = Self::full_key(namespace, op.key());
+            data.apply(full_key, op);
+        }
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> StorageResult<u64> {
+        let mut data = self.write()?;
+        let now = now_millis();
+        let expired: Vec<String> = data
+            .expiries
+            .iter()
+            .filter(|&(_, &at)| at <= now)
+            .map(|(k, _)| k.clone())
+            .collect();
+        let count = u64::try_from(expired.len()).unwrap_or(u64::MAX);
+        for key in expired {
+            data.values.remove(&key);
+            data.expiries.remove(&key);
+        }
+        Ok(count)
+    }
+
+    async fn snapshot(&self, writer: &mut (dyn std::io::Write + Send)) -> StorageResult<u64> {
+        self.write_snapshot(writer)
+    }
+
+    async fn restore(&self, reader: &mut (dyn std::io::Read + Send)) -> StorageResult<u64> {
+        self.restore_snapshot(reader)
+    }
+}
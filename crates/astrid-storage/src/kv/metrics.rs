@@ -0,0 +1,557 @@
+// ---------------------------------------------------------------------------
+// Instrumentation wrapper
+// ---------------------------------------------------------------------------
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::Serialize;
+
+use super::{KvEntry, KvEntryStream, KvOp, KvStore};
+use crate::error::StorageResult;
+
+/// The [`KvStore`] operation a metric refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KvOpKind {
+    /// [`KvStore::get`].
+    Get,
+    /// [`KvStore::set`] and [`KvStore::set_with_ttl`].
+    Set,
+    /// [`KvStore::delete`].
+    Delete,
+    /// [`KvStore::exists`].
+    Exists,
+    /// [`KvStore::list_keys`] and [`KvStore::list_keys_with_prefix`].
+    List,
+    /// [`KvStore::clear_namespace`] and [`KvStore::clear_prefix`].
+    Clear,
+    /// [`KvStore::scan_prefix`], measured until the stream is dropped.
+    Scan,
+    /// [`KvStore::batch`].
+    Batch,
+    /// [`KvStore::purge_expired`].
+    Purge,
+    /// [`KvStore::snapshot`] and [`KvStore::restore`].
+    Snapshot,
+}
+
+impl KvOpKind {
+    const ALL: [Self; 10] = [
+        Self::Get,
+        Self::Set,
+        Self::Delete,
+        Self::Exists,
+        Self::List,
+        Self::Clear,
+        Self::Scan,
+        Self::Batch,
+        Self::Purge,
+        Self::Snapshot,
+    ];
+
+    /// Stable lowercase name, used in logs and status output.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "get",
+            Self::Set => "set",
+            Self::Delete => "delete",
+            Self::Exists => "exists",
+            Self::List => "list",
+            Self::Clear => "clear",
+            Self::Scan => "scan",
+            Self::Batch => "batch",
+            Self::Purge => "purge",
+            Self::Snapshot => "snapshot",
+        }
+    }
+}
+
+/// Point-in-time metrics for one operation kind.
+///
+/// Percentiles come from power-of-two buckets, so they are upper bounds
+/// accurate to within a factor of two. Latency and size percentiles cover
+/// the sampled operations only (see
+/// [`with_sample_interval`](InstrumentedKvStore::with_sample_interval));
+/// `count`, `errors` and `bytes_total` cover every operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KvOpMetrics {
+    /// Which operation these numbers describe.
+    pub op: KvOpKind,
+    /// Started operations (including failures and any still in flight).
+    pub count: u64,
+    /// Operations that returned an error.
+    pub errors: u64,
+    /// Sampled operations slower than the configured slow-op threshold.
+    pub slow: u64,
+    /// Median latency in microseconds.
+    pub latency_p50_us: u64,
+    /// 95th percentile latency in microseconds.
+    pub latency_p95_us: u64,
+    /// 99th percentile latency in microseconds.
+    pub latency_p99_us: u64,
+    /// Slowest sampled latency in microseconds.
+    pub latency_max_us: u64,
+    /// Total value bytes read or written.
+    pub bytes_total: u64,
+    /// Median value bytes per operation.
+    pub bytes_p50: u64,
+    /// 99th percentile value bytes per operation.
+    pub bytes_p99: u64,
+}
+
+/// Snapshot of every operation kind that has run at least once.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct KvMetrics {
+    /// Per-operation metrics, in [`KvOpKind`] order.
+    pub ops: Vec<KvOpMetrics>,
+}
+
+impl KvMetrics {
+    /// Metrics for a single operation kind, if it has run.
+    #[must_use]
+    pub fn op(&self, op: KvOpKind) -> Option<&KvOpMetrics> {
+        self.ops.iter().find(|m| m.op == op)
+    }
+}
+
+/// Lock-free histogram with one bucket per power of two.
+struct Histogram {
+    /// Bucket `b` counts values in `[2^(b-1), 2^b)`; bucket 0 counts zeros.
+    buckets: [AtomicU64; 65],
+    sum: AtomicU64,
+    max: AtomicU64,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            sum: AtomicU64::new(0),
+            max: AtomicU64::new(0),
+        }
+    }
+
+    fn record(&self, value: u64) {
+        let bucket = value.checked_ilog2().map_or(0, |b| b.saturating_add(1)) as usize;
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(value, Ordering::Relaxed);
+        self.max.fetch_max(value, Ordering::Relaxed);
+    }
+
+    /// Upper bound of the bucket holding the `per_mille`/1000 quantile.
+    fn quantile(&self, per_mille: u64) -> u64 {
+        let counts: Vec<u64> = self
+            .buckets
+            .iter()
+            .map(|b| b.load(Ordering::Relaxed))
+            .collect();
+        let total = counts.iter().fold(0u64, |acc, c| acc.saturating_add(*c));
+        if total == 0 {
+            return 0;
+        }
+        let rank = total.saturating_mul(per_mille).div_ceil(1000).max(1);
+        let mut seen = 0u64;
+        for (bucket, count) in counts.into_iter().enumerate() {
+            seen = seen.saturating_add(count);
+            if seen >= rank {
+                let upper = u32::try_from(bucket)
+                    .ok()
+                    .and_then(|b| 1u64.checked_shl(b))
+                    .map_or(u64::MAX, |v| v.saturating_sub(1));
+                return upper.min(self.max.load(Ordering::Relaxed));
+            }
+        }
+        self.max.load(Ordering::Relaxed)
+    }
+}
+
+struct OpStats {
+    count: AtomicU64,
+    errors: AtomicU64,
+    slow: AtomicU64,
+    latency_us: Histogram,
+    bytes: Histogram,
+}
+
+impl OpStats {
+    fn new() -> Self {
+        Self {
+            count: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            slow: AtomicU64::new(0),
+            latency_us: Histogram::new(),
+            bytes: Histogram::new(),
+        }
+    }
+}
+
+/// A [`KvStore`] decorator that records per-operation counters, latency
+/// and value-size histograms, and logs operations slower than a threshold.
+///
+/// Slow-op log lines carry the operation, namespace, and key (or prefix)
+/// length — never the key itself or the value.
+///
+/// Reading the clock twice costs about as much as a cached `get`'s
+/// bookkeeping, so by default only one operation in 16 per kind is
+/// timed and sized. Counters stay exact; a slowdown that lasts
+/// more than a handful of operations still shows up in the percentiles and
+/// the slow-op log.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let store = InstrumentedKvStore::new(SurrealKvStore::open(path)?)
+///     .with_slow_threshold(Duration::from_millis(200));
+/// store.set("ns", "k", b"v".to_vec()).await?;
+/// let metrics = store.metrics();
+/// ```
+pub struct InstrumentedKvStore<S> {
+    inner: S,
+    stats: [OpStats; KvOpKind::ALL.len()],
+    slow_threshold: Option<Duration>,
+    sample_interval: u64,
+}
+
+/// One operation in this many (per kind) is timed by default.
+const DEFAULT_SAMPLE_INTERVAL: u64 = 16;
+
+impl<S> std::fmt::Debug for InstrumentedKvStore<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InstrumentedKvStore")
+            .field("slow_threshold", &self.slow_threshold)
+            .field("sample_interval", &self.sample_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: KvStore> InstrumentedKvStore<S> {
+    /// Wrap `inner` with metrics collection. Slow-op logging is off.
+    #[must_use]
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            stats: std::array::from_fn(|_| OpStats::new()),
+            slow_threshold: None,
+            sample_interval: DEFAULT_SAMPLE_INTERVAL,
+        }
+    }
+
+    /// Time and size one operation in `every` per kind (`1` samples all
+    /// of them, `0` is treated as `1`).
+    #[must_use]
+    pub fn with_sample_interval(mut self, every: u64) -> Self {
+        self.sample_interval = every.max(1);
+        self
+    }
+
+    /// Log a warning for every operation that takes longer than `threshold`.
+    #[must_use]
+    pub fn with_slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = Some(threshold);
+        self
+    }
+
+    /// The wrapped store.
+    #[must_use]
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Snapshot the current metrics.
+    #[must_use]
+    pub fn metrics(&self) -> KvMetrics {
+        let ops = KvOpKind::ALL
+            .iter()
+            .zip(&self.stats)
+            .filter_map(|(&op, s)| {
+                let count = s.count.load(Ordering::Relaxed);
+                (count > 0).then(|| KvOpMetrics {
+                    op,
+                    count,
+                    errors: s.errors.load(Ordering::Relaxed),
+                    slow: s.slow.load(Ordering::Relaxed),
+                    latency_p50_us: s.latency_us.quantile(500),
+                    latency_p95_us: s.latency_us.quantile(950),
+                    latency_p99_us: s.latency_us.quantile(990),
+                    latency_max_us: s.latency_us.max.load(Ordering::Relaxed),
+                    bytes_total: s.bytes.sum.load(Ordering::Relaxed),
+                    bytes_p50: s.bytes.quantile(500),
+                    bytes_p99: s.bytes.quantile(990),
+                })
+            })
+            .collect();
+        KvMetrics { ops }
+    }
+
+    fn stats(&self, op: KvOpKind) -> &OpStats {
+        &self.stats[op as usize]
+    }
+
+    /// Count a new `op` and start its clock if it is sampled.
+    fn begin(&self, op: KvOpKind) -> Option<Instant> {
+        let seq = self.stats(op).count.fetch_add(1, Ordering::Relaxed);
+        seq.is_multiple_of(self.sample_interval).then(Instant::now)
+    }
+
+    fn record(
+        &self,
+        op: KvOpKind,
+        namespace: &str,
+        key_len: usize,
+        start: Option<Instant>,
+        bytes: usize,
+        failed: bool,
+    ) {
+        let stats = self.stats(op);
+        if failed {
+            stats.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        let bytes = u64::try_from(bytes).unwrap_or(u64::MAX);
+        let Some(start) = start else {
+            stats.bytes.sum.fetch_add(bytes, Ordering::Relaxed);
+            return;
+        };
+        let elapsed = start.elapsed();
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        stats.latency_us.record(micros);
+        stats.bytes.record(bytes);
+
+        if self.slow_threshold.is_some_and(|t| elapsed > t) {
+            stats.slow.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                op = op.as_str(),
+                namespace,
+                key_len,
+                elapsed_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+                failed,
+                "Slow KV operation"
+            );
+        }
+    }
+
+    async fn timed<T: Send>(
+        &self,
+        op: KvOpKind,
+        namespace: &str,
+        key_len: usize,
+        bytes: impl FnOnce(&T) -> usize + Send,
+        fut: impl Future<Output = StorageResult<T>> + Send,
+    ) -> StorageResult<T> {
+        let start = self.begin(op);
+        let result = fut.await;
+        let n = result.as_ref().map_or(0, bytes);
+        self.record(op, namespace, key_len, start, n, result.is_err());
+        result
+    }
+}
+
+/// Records a scan when its stream is dropped (finished or abandoned).
+struct ScanGuard<'a, S: KvStore> {
+    store: &'a InstrumentedKvStore<S>,
+    namespace: &'a str,
+    prefix_len: usize,
+    start: Option<Instant>,
+    bytes: usize,
+    failed: bool,
+}
+
+impl<S: KvStore> ScanGuard<'_, S> {
+    fn observe(&mut self, item: &StorageResult<KvEntry>) {
+        match item {
+            Ok(entry) => self.bytes = self.bytes.saturating_add(entry.value.len()),
+            Err(_) => self.failed = true,
+        }
+    }
+}
+
+impl<S: KvStore> Drop for ScanGuard<'_, S> {
+    fn drop(&mut self) {
+        self.store.record(
+            KvOpKind::Scan,
+            self.namespace,
+            self.prefix_len,
+            self.start,
+            self.bytes,
+            self.failed,
+        );
+    }
+}
+
+#[async_trait]
+impl<S: KvStore> KvStore for InstrumentedKvStore<S> {
+    async fn get(&self, namespace: &str, key: &str) -> StorageResult<Option<Vec<u8>>> {
+        self.timed(
+            KvOpKind::Get,
+            namespace,
+            key.len(),
+            |v: &Option<Vec<u8>>| v.as_ref().map_or(0, Vec::len),
+            self.inner.get(namespace, key),
+        )
+        .await
+    }
+
+    async fn set(&self, namespace: &str, key: &str, value: Vec<u8>) -> StorageResult<()> {
+        let len = value.len();
+        self.timed(
+            KvOpKind::Set,
+            namespace,
+            key.len(),
+            |()| len,
+            self.inner.set(namespace, key, value),
+        )
+        .await
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        self.timed(
+            KvOpKind::Delete,
+            namespace,
+            key.len(),
+            |_| 0,
+            self.inner.delete(namespace, key),
+        )
+        .await
+    }
+
+    async fn exists(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        self.timed(
+            KvOpKind::Exists,
+            namespace,
+            key.len(),
+            |_| 0,
+            self.inner.exists(namespace, key),
+        )
+        .await
+    }
+
+    async fn list_keys(&self, namespace: &str) -> StorageResult<Vec<String>> {
+        self.timed(
+            KvOpKind::List,
+            namespace,
+            0,
+            |_| 0,
+            self.inner.list_keys(namespace),
+        )
+        .await
+    }
+
+    async fn list_keys_with_prefix(
+        &self,
+        namespace: &str,
+        prefix: &str,
+    ) -> StorageResult<Vec<String>> {
+        self.timed(
+            KvOpKind::List,
+            namespace,
+            prefix.len(),
+            |_| 0,
+            self.inner.list_keys_with_prefix(namespace, prefix),
+        )
+        .await
+    }
+
+    async fn clear_namespace(&self, namespace: &str) -> StorageResult<u64> {
+        self.timed(
+            KvOpKind::Clear,
+            namespace,
+            0,
+            |_| 0,
+            self.inner.clear_namespace(namespace),
+        )
+        .await
+    }
+
+    async fn clear_prefix(&self, namespace: &str, prefix: &str) -> StorageResult<u64> {
+        self.timed(
+            KvOpKind::Clear,
+            namespace,
+            prefix.len(),
+            |_| 0,
+            self.inner.clear_prefix(namespace, prefix),
+        )
+        .await
+    }
+
+    async fn set_with_ttl(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> StorageResult<()> {
+        let len = value.len();
+        self.timed(
+            KvOpKind::Set,
+            namespace,
+            key.len(),
+            |()| len,
+            self.inner.set_with_ttl(namespace, key, value, ttl),
+        )
+        .await
+    }
+
+    fn scan_prefix<'a>(&'a self, namespace: &'a str, prefix: &'a str) -> KvEntryStream<'a> {
+        let mut guard = ScanGuard {
+            store: self,
+            namespace,
+            prefix_len: prefix.len(),
+            start: self.begin(KvOpKind::Scan),
+            bytes: 0,
+            failed: false,
+        };
+        Box::pin(
+            self.inner
+                .scan_prefix(namespace, prefix)
+                .inspect(move |item| guard.observe(item)),
+        )
+    }
+
+    async fn batch(&self, namespace: &str, ops: Vec<KvOp>) -> StorageResult<()> {
+        let len = ops
+            .iter()
+            .map(|op| match op {
+                KvOp::Set { value, .. } | KvOp::SetWithTtl { value, .. } => value.len(),
+                KvOp::Delete { .. } => 0,
+            })
+            .fold(0usize, usize::saturating_add);
+        self.timed(
+            KvOpKind::Batch,
+            namespace,
+            0,
+            |()| len,
+            self.inner.batch(namespace, ops),
+        )
+        .await
+    }
+
+    async fn purge_expired(&self) -> StorageResult<u64> {
+        self.timed(KvOpKind::Purge, "*", 0, |_| 0, self.inner.purge_expired())
+            .await
+    }
+
+    async fn snapshot(&self, writer: &mut (dyn std::io::Write + Send)) -> StorageResult<u64> {
+        self.timed(
+            KvOpKind::Snapshot,
+            "*",
+            0,
+            |_| 0,
+            self.inner.snapshot(writer),
+        )
+        .await
+    }
+
+    async fn restore(&self, reader: &mut (dyn std::io::Read + Send)) -> StorageResult<u64> {
+        self.timed(
+            KvOpKind::Snapshot,
+            "*",
+            0,
+            |_| 0,
+            self.inner.restore(reader),
+        )
+        .await
+    }
+}
@@ -0,0 +1,500 @@
+//! Raw key-value store trait and implementations.
+//!
+//! The [`KvStore`] trait provides byte-level `get`/`set`/`delete` operations
+//! with namespaced keys. Implementations:
+//!
+//! - **In-memory** (always available): For tests and ephemeral data
+//! - **`SurrealKV`** (behind `kv` feature): Persistent, versioned, ACID-compliant
+//!
+//! # Namespacing
+//!
+//! All operations are scoped to a namespace. WASM guests receive a namespace
+//! like `wasm:{plugin_id}` and cannot access keys outside their namespace.
+//! The runtime uses `system:*` namespaces for internal state.
+//!
+//! # Ergonomic Access
+//!
+//! Use [`ScopedKvStore`] to pre-bind a namespace. This is the primary API
+//! for WASM guests — they receive a scoped store and never handle namespaces
+//! directly. It also provides typed [`get_json`](ScopedKvStore::get_json) /
+//! [`set_json`](ScopedKvStore::set_json) convenience methods.
+//!
+//! # Scans, Batches, and Expiry
+//!
+//! Beyond point operations, every backend supports:
+//!
+//! - [`scan_prefix`](KvStore::scan_prefix): stream `(key, value)` entries
+//!   under a prefix without maintaining manual index keys.
+//! - [`batch`](KvStore::batch): apply a list of [`KvOp`]s atomically — either
+//!   every op lands or none do.
+//! - [`set_with_ttl`](KvStore::set_with_ttl): per-key expiry. Expired keys
+//!   are hidden lazily on read and physically removed by
+//!   [`purge_expired`](KvStore::purge_expired), which
+//!   [`spawn_ttl_sweeper`] calls on an interval.
+//!
+//! # Snapshots
+//!
+//! [`snapshot`](KvStore::snapshot) writes a consistent point-in-time export
+//! of every namespace in a length-prefixed binary format with a BLAKE3
+//! checksum trailer. [`restore`](KvStore::restore) verifies the checksum
+//! before loading the export into an empty store. Snapshots taken on one
+//! backend restore into any other.
+//!
+//! `MemoryKvStore` and `SurrealKvStore` share one conformance suite so the
+//! semantics of these operations cannot drift between backends.
+//!
+//! # Metrics
+//!
+//! [`InstrumentedKvStore`] wraps any backend and records per-operation
+//! counts, error counts, and latency and value-size percentiles, and can log
+//! operations slower than a threshold. Call
+//! [`metrics`](InstrumentedKvStore::metrics) for a [`KvMetrics`] snapshot.
+//!
+//! # Encryption at Rest
+//!
+//! [`EncryptedKvStore`] wraps any backend and seals every value with
+//! XChaCha20-Poly1305, binding the namespace and key into each ciphertext.
+//! [`import_plaintext`](EncryptedKvStore::import_plaintext) migrates an
+//! existing unencrypted store, and `open_persistent` opens a `SurrealKV`
+//! store with that migration run once on first open.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+
+use crate::error::{StorageError, StorageResult};
+
+mod encrypted;
+mod memory;
+mod metrics;
+mod scoped;
+mod snapshot;
+#[cfg(feature = "kv")]
+mod surreal;
+
+pub use encrypted::EncryptedKvStore;
+pub use memory::MemoryKvStore;
+pub use metrics::{InstrumentedKvStore, KvMetrics, KvOpKind, KvOpMetrics};
+pub use scoped::ScopedKvStore;
+#[cfg(feature = "kv")]
+pub use surreal::SurrealKvStore;
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// Validate that a namespace is safe for use as a key prefix.
+///
+/// Namespaces must be non-empty and must not contain the null byte
+/// (used internally as the namespace/key separator).
+pub(crate) fn validate_namespace(namespace: &str) -> StorageResult<()> {
+    if namespace.is_empty() {
+        return Err(StorageError::InvalidKey(
+            "namespace must not be empty".into(),
+        ));
+    }
+    if namespace.contains('\0') {
+        return Err(StorageError::InvalidKey(
+            "namespace must not contain null bytes".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate that a prefix is safe for range operations.
+///
+/// Prefixes may be empty (clears all keys) but must not contain the null byte.
+pub(crate) fn validate_prefix(prefix: &str) -> StorageResult<()> {
+    if prefix.contains('\0') {
+        return Err(StorageError::InvalidKey(
+            "prefix must not contain null bytes".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate that a key is safe for storage.
+///
+/// Keys must be non-empty and must not contain the null byte.
+pub(crate) fn validate_key(key: &str) -> StorageResult<()> {
+    if key.is_empty() {
+        return Err(StorageError::InvalidKey("key must not be empty".into()));
+    }
+    if key.contains('\0') {
+        return Err(StorageError::InvalidKey(
+            "key must not contain null bytes".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Build the composite key `"{namespace}\0{key}"` as bytes.
+#[cfg(feature = "kv")]
+pub(crate) fn composite_key(namespace: &str, key: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(namespace.len().saturating_add(1).saturating_add(key.len()));
+    buf.extend_from_slice(namespace.as_bytes());
+    buf.push(0);
+    buf.extend_from_slice(key.as_bytes());
+    buf
+}
+
+/// Build the start of the namespace range (inclusive): `"{namespace}\0"`.
+#[cfg(feature = "kv")]
+pub(crate) fn namespace_range_start(namespace: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(namespace.len().saturating_add(1));
+    buf.extend_from_slice(namespace.as_bytes());
+    buf.push(0);
+    buf
+}
+
+/// Build the end of the namespace range (exclusive): `"{namespace}\x01"`.
+///
+/// Since `\0` is the separator, any key in the namespace has the form
+/// `"{namespace}\0{key}"`. The byte `\x01` immediately follows `\0`,
+/// so the range `["{namespace}\0", "{namespace}\x01")` captures exactly
+/// all keys in the namespace.
+#[cfg(feature = "kv")]
+pub(crate) fn namespace_range_end(namespace: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(namespace.len().saturating_add(1));
+    buf.extend_from_slice(namespace.as_bytes());
+    buf.push(1);
+    buf
+}
+
+/// Build the exclusive upper bound for a prefix range scan within a namespace.
+///
+/// For prefix "foo" in namespace "ns", this returns the key just after all
+/// keys starting with "ns\0foo". Works by incrementing the last byte of
+/// the prefix. If the prefix is empty, falls back to the full namespace
+/// range end.
+#[cfg(feature = "kv")]
+pub(crate) fn prefix_range_end(namespace: &str, prefix: &str) -> Vec<u8> {
+    if prefix.is_empty() {
+        return namespace_range_end(namespace);
+    }
+    let mut buf = composite_key(namespace, prefix);
+    // Increment the last byte to form the exclusive upper bound.
+    // If the last byte is 0xFF, pop and try the next one up.
+    while let Some(&last) = buf.last() {
+        if let Some(next) = last.checked_add(1) {
+            // Safety: we just confirmed `buf.last()` is `Some`.
+            if let Some(slot) = buf.last_mut() {
+                *slot = next;
+            }
+            return buf;
+        }
+        buf.pop();
+    }
+    // All bytes were 0xFF - fall back to namespace end.
+    namespace_range_end(namespace)
+}
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// A key-value entry with its namespace and key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KvEntry {
+    /// The namespace this entry belongs to.
+    pub namespace: String,
+    /// The key within the namespace.
+    pub key: String,
+    /// The raw value bytes.
+    pub value: Vec<u8>,
+}
+
+/// A single write in a [`KvStore::batch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KvOp {
+    /// Set a value. Clears any expiry previously attached to the key.
+    Set {
+        /// The key to write.
+        key: String,
+        /// The raw value bytes.
+        value: Vec<u8>,
+    },
+    /// Set a value that expires after `ttl`.
+    SetWithTtl {
+        /// The key to write.
+        key: String,
+        /// The raw value bytes.
+        value: Vec<u8>,
+        /// Time until the key expires, measured from when the batch is applied.
+        ttl: Duration,
+    },
+    /// Delete a key (and its expiry). Deleting a missing key is not an error.
+    Delete {
+        /// The key to delete.
+        key: String,
+    },
+}
+
+impl KvOp {
+    /// The key this op writes.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Set { key, .. } | Self::SetWithTtl { key, .. } | Self::Delete { key } => key,
+        }
+    }
+}
+
+/// Stream of entries produced by [`KvStore::scan_prefix`].
+pub type KvEntryStream<'a> = BoxStream<'a, StorageResult<KvEntry>>;
+
+/// Current wall-clock time in milliseconds since the Unix epoch.
+///
+/// Expiry deadlines are stored as absolute wall-clock milliseconds so they
+/// survive restarts of a persistent backend.
+pub(crate) fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Absolute expiry deadline (ms since epoch) for a key written now with `ttl`.
+pub(crate) fn expiry_deadline(ttl: Duration) -> u64 {
+    let ttl_ms = u64::try_from(ttl.as_millis()).unwrap_or(u64::MAX);
+    now_millis().saturating_add(ttl_ms)
+}
+
+/// Validate every key in a batch before any op is applied.
+pub(crate) fn validate_ops(namespace: &str, ops: &[KvOp]) -> StorageResult<()> {
+    validate_namespace(namespace)?;
+    ops.iter().try_for_each(|op| validate_key(op.key()))
+}
+
+// ---------------------------------------------------------------------------
+// Trait
+// ---------------------------------------------------------------------------
+
+/// Raw key-value store trait.
+///
+/// Provides namespaced byte-level storage. All operations are scoped
+/// to a namespace for isolation.
+#[async_trait]
+pub trait KvStore: Send + Sync {
+    /// Get a value by namespace and key.
+    ///
+    /// Returns `None` if the key does not exist.
+    async fn get(&self, namespace: &str, key: &str) -> StorageResult<Option<Vec<u8>>>;
+
+    /// Set a value for a namespace and key.
+    ///
+    /// Overwrites any existing value.
+    async fn set(&self, namespace: &str, key: &str, value: Vec<u8>) -> StorageResult<()>;
+
+    /// Delete a key from a namespace.
+    ///
+    /// Returns `true` if the key existed and was deleted.
+    async fn delete(&self, namespace: &str, key: &str) -> StorageResult<bool>;
+
+    /// Check if a key exists in a namespace.
+    async fn exists(&self, namespace: &str, key: &str) -> StorageResult<bool>;
+
+    /// List all keys in a namespace.
+    async fn list_keys(&self, namespace: &str) -> StorageResult<Vec<String>>;
+
+    /// List keys matching a prefix within a namespace.
+    ///
+    /// Default implementation filters `list_keys` output. Backends
+    /// should override with a native range scan when available.
+    async fn list_keys_with_prefix(
+        &self,
+        namespace: &str,
+        prefix: &str,
+    ) -> StorageResult<Vec<String>> {
+        let all = self.list_keys(namespace).await?;
+        Ok(all.into_iter().filter(|k| k.starts_with(prefix)).collect())
+    }
+
+    /// Delete all keys in a namespace.
+    async fn clear_namespace(&self, namespace: &str) -> StorageResult<u64>;
+
+    /// Delete all keys matching a prefix within a namespace.
+    ///
+    /// Returns the number of keys that matched the prefix.
+    ///
+    /// Default implementation lists then deletes one-by-one (non-atomic).
+    /// On error, some keys may already have been deleted. Backends should
+    /// override with an atomic implementation.
+    async fn clear_prefix(&self, namespace: &str, prefix: &str) -> StorageResult<u64> {
+        validate_prefix(prefix)?;
+        let keys = self.list_keys_with_prefix(namespace, prefix).await?;
+        let count = u64::try_from(keys.len()).unwrap_or(u64::MAX);
+        for key in &keys {
+            self.delete(namespace, key).await?;
+        }
+        Ok(count)
+    }
+
+    /// Set a value that expires after `ttl`.
+    ///
+    /// Once the deadline passes the key behaves as if it were deleted: reads
+    /// return `None`, listings and scans skip it. Storage is reclaimed by
+    /// [`purge_expired`](Self::purge_expired). A later plain
+    /// [`set`](Self::set) makes the key persistent again.
+    ///
+    /// Default implementation fails with [`StorageError::Internal`] for
+    /// backends without expiry support.
+    async fn set_with_ttl(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> StorageResult<()> {
+        let _ = (namespace, key, value, ttl);
+        Err(unsupported())
+    }
+
+    /// Stream all live entries whose key starts with `prefix`.
+    ///
+    /// Entries are yielded in ascending key order. An empty prefix scans
+    /// the whole namespace. The scan never crosses into another namespace:
+    /// the namespace separator cannot appear in a prefix, so no crafted
+    /// prefix can reach a sibling namespace's keys.
+    ///
+    /// Default implementation lists matching keys then fetches each value.
+    /// Backends should override with a native range scan.
+    fn scan_prefix<'a>(&'a self, namespace: &'a str, prefix: &'a str) -> KvEntryStream<'a> {
+        Box::pin(futures::stream::unfold(
+            None::<std::vec::IntoIter<String>>,
+            move |keys| async move {
+                let mut keys = if let Some(keys) = keys {
+                    keys
+                } else {
+                    if let Err(e) = validate_prefix(prefix) {
+                        return Some((Err(e), Some(Vec::new().into_iter())));
+                    }
+                    match self.list_keys_with_prefix(namespace, prefix).await {
+                        Ok(mut keys) => {
+                            keys.sort();
+                            keys.into_iter()
+                        },
+                        Err(e) => return Some((Err(e), Some(Vec::new().into_iter()))),
+                    }
+                };
+                for key in keys.by_ref() {
+                    match self.get(namespace, &key).await {
+                        Ok(Some(value)) => {
+                            let entry = KvEntry {
+                                namespace: namespace.to_string(),
+                                key,
+                                value,
+                            };
+                            return Some((Ok(entry), Some(keys)));
+                        },
+                        // Deleted or expired between list and get.
+                        Ok(None) => {},
+                        Err(e) => return Some((Err(e), Some(keys))),
+                    }
+                }
+                None
+            },
+        ))
+    }
+
+    /// Apply a list of writes to a namespace as one unit.
+    ///
+    /// All keys are validated before anything is written. Ops apply in
+    /// order, so a later op on the same key wins.
+    ///
+    /// Default implementation applies ops one-by-one (non-atomic). On error,
+    /// earlier ops may already have been applied. Backends should override
+    /// with a transactional implementation.
+    async fn batch(&self, namespace: &str, ops: Vec<KvOp>) -> StorageResult<()> {
+        validate_ops(namespace, &ops)?;
+        for op in ops {
+            match op {
+                KvOp::Set { key, value } => self.set(namespace, &key, value).await?,
+                KvOp::SetWithTtl { key, value, ttl } => {
+                    self.set_with_ttl(namespace, &key, value, ttl).await?;
+                },
+                KvOp::Delete { key } => {
+                    self.delete(namespace, &key).await?;
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Physically remove every expired key across all namespaces.
+    ///
+    /// Returns the number of keys removed. Reads already hide expired keys,
+    /// so this only reclaims space. Default implementation is a no-op for
+    /// backends without expiry support.
+    async fn purge_expired(&self) -> StorageResult<u64> {
+        Ok(0)
+    }
+
+    /// Write a consistent point-in-time export of every namespace.
+    ///
+    /// Expired keys are skipped; live keys keep their expiry deadline.
+    /// Writers are not blocked for the duration of the export. Returns the
+    /// number of entries written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Internal`] if the backend or `writer` fails,
+    /// or, from the default implementation, if the backend has no export.
+    async fn snapshot(&self, writer: &mut (dyn std::io::Write + Send)) -> StorageResult<u64> {
+        let _ = writer;
+        Err(unsupported())
+    }
+
+    /// Load a snapshot produced by [`snapshot`](Self::snapshot).
+    ///
+    /// The whole stream is read and its checksum verified before anything
+    /// is written, and the load itself is atomic. Entries whose expiry has
+    /// passed since the snapshot was taken are dropped. Returns the number
+    /// of entries restored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Serialization`] if the snapshot is truncated,
+    /// corrupted, or from an unsupported format version, and
+    /// [`StorageError::Internal`] if the store is not empty or, from the
+    /// default implementation, if the backend has no import.
+    async fn restore(&self, reader: &mut (dyn std::io::Read + Send)) -> StorageResult<u64> {
+        let _ = reader;
+        Err(unsupported())
+    }
+}
+
+/// The error the optional [`KvStore`] operations return by default.
+fn unsupported() -> StorageError {
+    StorageError::Internal("unsupported".to_string())
+}
+
+/// Spawn a background task that calls [`KvStore::purge_expired`] every
+/// `interval`.
+///
+/// Errors are logged and the sweep is retried on the next tick. Abort the
+/// returned handle to stop the sweeper.
+#[must_use]
+pub fn spawn_ttl_sweeper(
+    store: Arc<dyn KvStore>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            match store.purge_expired().await {
+                Ok(0) => {},
+                Ok(purged) => tracing::debug!(purged, "Purged expired KV entries"),
+                Err(e) => tracing::warn!(error = %e, "KV expiry sweep failed"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod conformance;
+#[cfg(test)]
+mod tests;
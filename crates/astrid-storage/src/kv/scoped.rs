@@ -0,0 +1,236 @@
+// ---------------------------------------------------------------------------
+// Scoped store (namespace pre-bound)
+// ---------------------------------------------------------------------------
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{KvEntryStream, KvOp, KvStore, validate_key, validate_namespace};
+use crate::error::{StorageError, StorageResult};
+
+/// A namespace-scoped view into a [`KvStore`].
+///
+/// This is the primary API for WASM guests. The host creates a `ScopedKvStore`
+/// per plugin with `namespace = "wasm:{plugin_id}"`, giving the guest simple
+/// `get` / `set` / `delete` without ever seeing namespaces.
+///
+/// Also provides typed convenience via [`get_json`](Self::get_json) /
+/// [`set_json`](Self::set_json).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use astrid_storage::kv::{ScopedKvStore, MemoryKvStore};
+/// use std::sync::Arc;
+///
+/// let store = Arc::new(MemoryKvStore::new());
+/// let scoped = ScopedKvStore::new(store, "wasm:my-plugin")?;
+///
+/// scoped.set("config", b"{}".to_vec()).await?;
+/// let val = scoped.get("config").await?;
+/// ```
+#[derive(Clone)]
+pub struct ScopedKvStore {
+    inner: Arc<dyn KvStore>,
+    namespace: String,
+}
+
+impl std::fmt::Debug for ScopedKvStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScopedKvStore")
+            .field("namespace", &self.namespace)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ScopedKvStore {
+    /// Create a scoped view into the given store for `namespace`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::InvalidKey`] if the namespace is empty
+    /// or contains null bytes.
+    pub fn new(store: Arc<dyn KvStore>, namespace: impl Into<String>) -> StorageResult<Self> {
+        let namespace = namespace.into();
+        validate_namespace(&namespace)?;
+        Ok(Self {
+            inner: store,
+            namespace,
+        })
+    }
+
+    /// The namespace this store is scoped to.
+    #[must_use]
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Create a new scoped view sharing the same underlying store but with
+    /// a different namespace. Used for per-invocation principal scoping.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::InvalidKey`] if the namespace is empty
+    /// or contains null bytes.
+    pub fn with_namespace(&self, namespace: impl Into<String>) -> StorageResult<Self> {
+        Self::new(Arc::clone(&self.inner), namespace)
+    }
+
+    /// Get a raw byte value by key.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::InvalidKey`] if the key is empty or invalid.
+    pub async fn get(&self, key: &str) -> StorageResult<Option<Vec<u8>>> {
+        validate_key(key)?;
+        self.inner.get(&self.namespace, key).await
+    }
+
+    /// Set a raw byte value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::InvalidKey`] if the key is empty or invalid.
+    pub async fn set(&self, key: &str, value: Vec<u8>) -> StorageResult<()> {
+        validate_key(key)?;
+        self.inner.set(&self.namespace, key, value).await
+    }
+
+    /// Delete a key.
+    ///
+    /// Returns `true` if the key existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::InvalidKey`] if the key is empty or invalid.
+    pub async fn delete(&self, key: &str) -> StorageResult<bool> {
+        validate_key(key)?;
+        self.inner.delete(&self.namespace, key).await
+    }
+
+    /// Check if a key exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::InvalidKey`] if the key is empty or invalid.
+    pub async fn exists(&self, key: &str) -> StorageResult<bool> {
+        validate_key(key)?;
+        self.inner.exists(&self.namespace, key).await
+    }
+
+    /// List all keys in this namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying store operation fails.
+    pub async fn list_keys(&self) -> StorageResult<Vec<String>> {
+        self.inner.list_keys(&self.namespace).await
+    }
+
+    /// Delete all keys in this namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying store operation fails.
+    pub async fn clear(&self) -> StorageResult<u64> {
+        self.inner.clear_namespace(&self.namespace).await
+    }
+
+    // -- Prefix operations --
+
+    /// List all keys matching a given prefix within this namespace.
+    ///
+    /// Returns an empty vec if no keys match.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying store operation fails.
+    pub async fn list_keys_with_prefix(&self, prefix: &str) -> StorageResult<Vec<String>> {
+        self.inner
+            .list_keys_with_prefix(&self.namespace, prefix)
+            .await
+    }
+
+    /// Delete all keys matching a given prefix within this namespace.
+    ///
+    /// Returns the number of keys deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying store operation fails.
+    pub async fn clear_prefix(&self, prefix: &str) -> StorageResult<u64> {
+        self.inner.clear_prefix(&self.namespace, prefix).await
+    }
+
+    /// Stream all entries whose key starts with `prefix`, in key order.
+    ///
+    /// Errors (including an invalid prefix) are yielded as stream items.
+    #[must_use]
+    pub fn scan_prefix<'a>(&'a self, prefix: &'a str) -> KvEntryStream<'a> {
+        self.inner.scan_prefix(&self.namespace, prefix)
+    }
+
+    // -- Batches and expiry --
+
+    /// Apply a list of writes atomically.
+    ///
+    /// Either every op is applied or none are. Ops apply in order, so a
+    /// later op on the same key wins.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::InvalidKey`] if any key is empty or invalid,
+    /// in which case nothing is written.
+    pub async fn batch(&self, ops: Vec<KvOp>) -> StorageResult<()> {
+        self.inner.batch(&self.namespace, ops).await
+    }
+
+    /// Set a raw byte value that expires after `ttl`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::InvalidKey`] if the key is empty or invalid.
+    pub async fn set_with_ttl(
+        &self,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> StorageResult<()> {
+        validate_key(key)?;
+        self.inner
+            .set_with_ttl(&self.namespace, key, value, ttl)
+            .await
+    }
+
+    // -- Typed convenience (JSON) --
+
+    /// Deserialize a JSON value from the store.
+    ///
+    /// Returns `None` if the key does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Serialization`] if deserialization fails.
+    pub async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> StorageResult<Option<T>> {
+        let bytes = self.get(key).await?;
+        bytes
+            .map(|b| {
+                serde_json::from_slice(&b).map_err(|e| StorageError::Serialization(e.to_string()))
+            })
+            .transpose()
+    }
+
+    /// Serialize a value as JSON and store it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Serialization`] if serialization fails.
+    pub async fn set_json<T: serde::Serialize>(&self, key: &str, value: &T) -> StorageResult<()> {
+        let bytes =
+            serde_json::to_vec(value).map_err(|e| StorageError::Serialization(e.to_string()))?;
+        self.set(key, bytes).await
+    }
+}
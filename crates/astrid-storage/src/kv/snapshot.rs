@@ -0,0 +1,212 @@
+// ---------------------------------------------------------------------------
+// Snapshot format (shared by every backend)
+// ---------------------------------------------------------------------------
+//
+// A snapshot is a flat, length-prefixed binary stream:
+//
+//   header   "ASTRIDKV" (8 bytes) | format version (u16 BE)
+//   record*  0x01 | ns_len (u32 BE) | ns | key_len (u32 BE) | key
+//                 | expires_at (u64 BE, 0 = never) | value_len (u32 BE) | value
+//   end      0x00 | record count (u64 BE)
+//   trailer  BLAKE3 hash of every byte above (32 bytes)
+//
+// Restores read and verify the whole stream before writing anything, so a
+// truncated or corrupted file never leaves a half-restored store behind.
+
+use std::io::{Read, Write};
+
+use super::{KvEntry, now_millis, validate_key, validate_namespace};
+use crate::error::{StorageError, StorageResult};
+
+const MAGIC: &[u8; 8] = b"ASTRIDKV";
+const FORMAT_VERSION: u16 = 1;
+const TAG_END: u8 = 0;
+const TAG_RECORD: u8 = 1;
+
+/// One live entry captured in a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SnapshotEntry {
+    pub(crate) entry: KvEntry,
+    /// Absolute expiry deadline (ms since epoch), if the key has a TTL.
+    pub(crate) expires_at: Option<u64>,
+}
+
+fn io_err(e: &std::io::Error) -> StorageError {
+    StorageError::Internal(format!("snapshot I/O failed: {e}"))
+}
+
+fn corrupt(reason: &str) -> StorageError {
+    StorageError::Serialization(format!("invalid snapshot: {reason}"))
+}
+
+fn len_u32(len: usize, what: &str) -> StorageResult<[u8; 4]> {
+    u32::try_from(len)
+        .map(u32::to_be_bytes)
+        .map_err(|_| StorageError::Internal(format!("snapshot {what} exceeds 4 GiB")))
+}
+
+/// Streaming snapshot encoder that hashes everything it writes.
+pub(crate) struct SnapshotWriter<'w> {
+    inner: &'w mut (dyn Write + Send),
+    hasher: blake3::Hasher,
+    count: u64,
+}
+
+impl<'w> SnapshotWriter<'w> {
+    /// Start a snapshot by writing the header.
+    pub(crate) fn begin(inner: &'w mut (dyn Write + Send)) -> StorageResult<Self> {
+        let mut writer = Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+            count: 0,
+        };
+        writer.put(MAGIC)?;
+        writer.put(&FORMAT_VERSION.to_be_bytes())?;
+        Ok(writer)
+    }
+
+    fn put(&mut self, bytes: &[u8]) -> StorageResult<()> {
+        self.hasher.update(bytes);
+        self.inner.write_all(bytes).map_err(|ref e| io_err(e))
+    }
+
+    /// Append one entry.
+    pub(crate) fn entry(
+        &mut self,
+        namespace: &[u8],
+        key: &[u8],
+        value: &[u8],
+        expires_at: Option<u64>,
+    ) -> StorageResult<()> {
+        self.put(&[TAG_RECORD])?;
+        self.put(&len_u32(namespace.len(), "namespace")?)?;
+        self.put(namespace)?;
+        self.put(&len_u32(key.len(), "key")?)?;
+        self.put(key)?;
+        self.put(&expires_at.unwrap_or(0).to_be_bytes())?;
+        self.put(&len_u32(value.len(), "value")?)?;
+        self.put(value)?;
+        self.count = self.count.saturating_add(1);
+        Ok(())
+    }
+
+    /// Write the end marker and checksum trailer. Returns the entry count.
+    pub(crate) fn finish(mut self) -> StorageResult<u64> {
+        let count = self.count;
+        self.put(&[TAG_END])?;
+        self.put(&count.to_be_bytes())?;
+        let digest = self.hasher.finalize();
+        self.inner
+            .write_all(digest.as_bytes())
+            .map_err(|ref e| io_err(e))?;
+        self.inner.flush().map_err(|ref e| io_err(e))?;
+        Ok(count)
+    }
+}
+
+/// Reader that hashes everything it consumes.
+struct HashingReader<'r> {
+    inner: &'r mut (dyn Read + Send),
+    hasher: blake3::Hasher,
+}
+
+impl HashingReader<'_> {
+    fn bytes(&mut self, len: usize) -> StorageResult<Vec<u8>> {
+        // Read through `take` rather than pre-allocating `len` bytes so a
+        // corrupted length field cannot trigger a huge allocation.
+        let mut buf = Vec::new();
+        let want = u64::try_from(len).unwrap_or(u64::MAX);
+        (&mut *self.inner)
+            .take(want)
+            .read_to_end(&mut buf)
+            .map_err(|ref e| io_err(e))?;
+        if buf.len() != len {
+            return Err(corrupt("unexpected end of stream"));
+        }
+        self.hasher.update(&buf);
+        Ok(buf)
+    }
+
+    fn array<const N: usize>(&mut self) -> StorageResult<[u8; N]> {
+        let bytes = self.bytes(N)?;
+        <[u8; N]>::try_from(bytes).map_err(|_| corrupt("unexpected end of stream"))
+    }
+
+    fn u8(&mut self) -> StorageResult<u8> {
+        let [byte] = self.array::<1>()?;
+        Ok(byte)
+    }
+
+    fn u32(&mut self) -> StorageResult<usize> {
+        usize::try_from(u32::from_be_bytes(self.array()?))
+            .map_err(|_| corrupt("length does not fit in memory"))
+    }
+
+    fn u64(&mut self) -> StorageResult<u64> {
+        Ok(u64::from_be_bytes(self.array()?))
+    }
+
+    fn string(&mut self, what: &str) -> StorageResult<String> {
+        let len = self.u32()?;
+        String::from_utf8(self.bytes(len)?).map_err(|_| corrupt(&format!("{what} is not UTF-8")))
+    }
+}
+
+/// Decode and verify a complete snapshot.
+///
+/// Entries whose deadline has already passed are dropped.
+pub(crate) fn read_snapshot(reader: &mut (dyn Read + Send)) -> StorageResult<Vec<SnapshotEntry>> {
+    let mut r = HashingReader {
+        inner: reader,
+        hasher: blake3::Hasher::new(),
+    };
+    if &r.array::<8>()? != MAGIC {
+        return Err(corrupt("bad magic"));
+    }
+    let version = u16::from_be_bytes(r.array()?);
+    if version != FORMAT_VERSION {
+        return Err(corrupt(&format!("unsupported format version {version}")));
+    }
+
+    let mut entries = Vec::new();
+    loop {
+        match r.u8()? {
+            TAG_RECORD => {
+                let namespace = r.string("namespace")?;
+                let key = r.string("key")?;
+                let expires_at = Some(r.u64()?).filter(|&at| at != 0);
+                let len = r.u32()?;
+                let value = r.bytes(len)?;
+                validate_namespace(&namespace)?;
+                validate_key(&key)?;
+                entries.push(SnapshotEntry {
+                    entry: KvEntry {
+                        namespace,
+                        key,
+                        value,
+                    },
+                    expires_at,
+                });
+            },
+            TAG_END => break,
+            tag => return Err(corrupt(&format!("unknown record tag {tag}"))),
+        }
+    }
+
+    let count = r.u64()?;
+    if u64::try_from(entries.len()).ok() != Some(count) {
+        return Err(corrupt("record count mismatch"));
+    }
+    let expected = r.hasher.finalize();
+    let mut trailer = [0u8; 32];
+    r.inner
+        .read_exact(&mut trailer)
+        .map_err(|_| corrupt("missing checksum"))?;
+    if expected != trailer {
+        return Err(corrupt("checksum mismatch"));
+    }
+
+    let now = now_millis();
+    entries.retain(|e| e.expires_at.is_none_or(|at| at > now));
+    Ok(entries)
+}
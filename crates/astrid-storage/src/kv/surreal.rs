@@ -0,0 +1,573 @@
+// ---------------------------------------------------------------------------
+// SurrealKV implementation (behind `kv` feature)
+// ---------------------------------------------------------------------------
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use super::snapshot::{SnapshotWriter, read_snapshot};
+use super::{
+    KvEntry, KvEntryStream, KvOp, KvStore, composite_key, expiry_deadline, namespace_range_end,
+    namespace_range_start, now_millis, prefix_range_end, validate_key, validate_namespace,
+    validate_ops, validate_prefix,
+};
+use crate::error::{StorageError, StorageResult};
+
+/// Leading bytes of every expiry record: `"\0ttl\0{namespace}\0{key}"`.
+///
+/// User keys always start with a non-empty namespace, so a key starting with
+/// `\0` can never collide with one.
+const TTL_PREFIX: &[u8] = b"\0ttl\0";
+
+/// Number of entries fetched per read transaction in [`SurrealKvStore::scan_prefix`].
+const SCAN_PAGE_SIZE: usize = 256;
+
+/// Persistent key-value store backed by `SurrealKV`.
+///
+/// ACID-compliant, versioned, embedded LSM-tree storage.
+/// All operations use transactions internally.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use astrid_storage::kv::SurrealKvStore;
+///
+/// let store = SurrealKvStore::open("./data/kv")?;
+/// store.set("wasm:my-plugin", "config", b"{}".to_vec()).await?;
+/// ```
+pub struct SurrealKvStore {
+    tree: surrealkv::Tree,
+}
+
+impl std::fmt::Debug for SurrealKvStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SurrealKvStore").finish_non_exhaustive()
+    }
+}
+
+impl SurrealKvStore {
+    /// Open a persistent KV store at the given directory path.
+    ///
+    /// Creates the directory if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Connection`] if the store cannot be opened.
+    pub fn open(path: impl AsRef<std::path::Path>) -> StorageResult<Self> {
+        let tree = surrealkv::TreeBuilder::new()
+            .with_path(path.as_ref().to_path_buf())
+            .build()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(Self { tree })
+    }
+
+    /// Open a persistent KV store with custom options.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Connection`] if the store cannot be opened.
+    pub fn open_with_options(opts: surrealkv::Options) -> StorageResult<Self> {
+        let tree = surrealkv::TreeBuilder::with_options(opts)
+            .build()
+            .map_err(|e| StorageError::Connection(e.to_string()))?;
+        Ok(Self { tree })
+    }
+
+    /// Close the store, flushing any pending writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StorageError::Internal`] if the flush fails.
+    pub async fn close(&self) -> StorageResult<()> {
+        self.tree
+            .close()
+            .await
+            .map_err(|e| StorageError::Internal(e.to_string()))
+    }
+}
+
+fn map_kv_err(e: &surrealkv::Error) -> StorageError {
+    StorageError::Internal(e.to_string())
+}
+
+/// Expiry record key for a composite key (or range bound) `"{namespace}\0{key}"`.
+fn ttl_key(ck: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(TTL_PREFIX.len().saturating_add(ck.len()));
+    buf.extend_from_slice(TTL_PREFIX);
+    buf.extend_from_slice(ck);
+    buf
+}
+
+/// Decode an expiry deadline (big-endian ms since epoch).
+fn decode_deadline(raw: &[u8]) -> Option<u64> {
+    <[u8; 8]>::try_from(raw).ok().map(u64::from_be_bytes)
+}
+
+/// Collect every key in `[start, end)`.
+fn collect_keys(
+    tx: &surrealkv::Transaction,
+    start: &[u8],
+    end: &[u8],
+) -> StorageResult<Vec<Vec<u8>>> {
+    let mut iter = tx.range(start, end).map_err(|ref e| map_kv_err(e))?;
+    iter.seek_first().map_err(|ref e| map_kv_err(e))?;
+    let mut keys = Vec::new();
+    while iter.valid() {
+        keys.push(iter.key());
+        iter.next().map_err(|ref e| map_kv_err(e))?;
+    }
+    Ok(keys)
+}
+
+/// Composite keys in `[start, end)` whose expiry deadline has passed.
+fn expired_in_range(
+    tx: &surrealkv::Transaction,
+    start: &[u8],
+    end: &[u8],
+    now: u64,
+) -> StorageResult<HashSet<Vec<u8>>> {
+    let mut iter = tx
+        .range(ttl_key(start), ttl_key(end))
+        .map_err(|ref e| map_kv_err(e))?;
+    iter.seek_first().map_err(|ref e| map_kv_err(e))?;
+    let mut expired = HashSet::new();
+    while iter.valid() {
+        let deadline = iter.value().map_err(|ref e| map_kv_err(e))?;
+        if deadline
+            .as_deref()
+            .and_then(decode_deadline)
+            .is_some_and(|at| at <= now)
+        {
+            expired.insert(iter.key().split_off(TTL_PREFIX.len()));
+        }
+        iter.next().map_err(|ref e| map_kv_err(e))?;
+    }
+    Ok(expired)
+}
+
+/// Whether the composite key `ck` carries an expiry deadline that has passed.
+fn is_expired(tx: &surrealkv::Transaction, ck: &[u8], now: u64) -> StorageResult<bool> {
+    Ok(tx
+        .get(ttl_key(ck))
+        .map_err(|ref e| map_kv_err(e))?
+        .as_deref()
+        .and_then(decode_deadline)
+        .is_some_and(|at| at <= now))
+}
+
+/// List the user keys in `[start, end)`, skipping expired ones.
+fn list_live_keys(
+    tx: &surrealkv::Transaction,
+    start: &[u8],
+    end: &[u8],
+    prefix_len: usize,
+) -> StorageResult<Vec<String>> {
+    let expired = expired_in_range(tx, start, end, now_millis())?;
+    Ok(collect_keys(tx, start, end)?
+        .into_iter()
+        .filter(|raw_key| raw_key.len() > prefix_len && !expired.contains(raw_key))
+        .filter_map(|raw_key| {
+            std::str::from_utf8(&raw_key[prefix_len..])
+                .ok()
+                .map(String::from)
+        })
+        .collect())
+}
+
+/// Delete every value and expiry record in `[start, end)`.
+///
+/// Returns `(live, touched)`: the number of unexpired values removed and
+/// whether anything was written at all (so the caller knows to commit).
+fn delete_range(
+    tx: &mut surrealkv::Transaction,
+    start: &[u8],
+    end: &[u8],
+) -> StorageResult<(u64, bool)> {
+    // Collect keys first, then delete (iterators borrow tx immutably).
+    let expired = expired_in_range(tx, start, end, now_millis())?;
+    let keys = collect_keys(tx, start, end)?;
+    let ttl_keys = collect_keys(tx, &ttl_key(start), &ttl_key(end))?;
+
+    let live = keys.iter().filter(|k| !expired.contains(*k)).count();
+    let touched = !keys.is_empty() || !ttl_keys.is_empty();
+    for key in keys.iter().chain(&ttl_keys) {
+        tx.delete(key).map_err(|ref e| map_kv_err(e))?;
+    }
+    Ok((u64::try_from(live).unwrap_or(u64::MAX), touched))
+}
+
+/// Stage a single batch op (value and expiry record) in `tx`.
+fn apply_op(tx: &mut surrealkv::Transaction, namespace: &str, op: KvOp) -> StorageResult<()> {
+    let ck = composite_key(namespace, op.key());
+    let tk = ttl_key(&ck);
+    match op {
+        KvOp::Set { value, .. } => {
+            tx.set(&ck, &value).map_err(|ref e| map_kv_err(e))?;
+            // Only write a tombstone when there is an expiry to clear.
+            if tx.get(&tk).map_err(|ref e| map_kv_err(e))?.is_some() {
+                tx.delete(&tk).map_err(|ref e| map_kv_err(e))?;
+            }
+        },
+        KvOp::SetWithTtl { value, ttl, .. } => {
+            tx.set(&ck, &value).map_err(|ref e| map_kv_err(e))?;
+            tx.set(&tk, &expiry_deadline(ttl).to_be_bytes())
+                .map_err(|ref e| map_kv_err(e))?;
+        },
+        KvOp::Delete { .. } => {
+            tx.delete(&ck).map_err(|ref e| map_kv_err(e))?;
+            tx.delete(&tk).map_err(|ref e| map_kv_err(e))?;
+        },
+    }
+    Ok(())
+}
+
+impl SurrealKvStore {
+    /// Read one page of a prefix scan starting at `start` (inclusive).
+    ///
+    /// Returns the live entries on the page and the cursor for the next
+    /// page, or `None` once the range is exhausted. Each page uses its own
+    /// read transaction so a long scan never pins a snapshot.
+    fn scan_page(
+        &self,
+        namespace: &str,
+        prefix: &str,
+        start: &[u8],
+    ) -> StorageResult<(Vec<KvEntry>, Option<Vec<u8>>)> {
+        let end = prefix_range_end(namespace, prefix);
+        let prefix_len = namespace.len().saturating_add(1); // namespace + \0
+        let now = now_millis();
+
+        let tx = self
+            .tree
+            .begin_with_mode(surrealkv::Mode::ReadOnly)
+            .map_err(|ref e| map_kv_err(e))?;
+        let mut iter = tx
+            .range(start, end.as_slice())
+            .map_err(|ref e| map_kv_err(e))?;
+        iter.seek_first().map_err(|ref e| map_kv_err(e))?;
+
+        let mut entries = Vec::new();
+        let mut last_key = None;
+        let mut read: usize = 0;
+        while iter.valid() && read < SCAN_PAGE_SIZE {
+            let raw_key = iter.key();
+            read = read.saturating_add(1);
+            if !is_expired(&tx, &raw_key, now)?
+                && let Some(value) = iter.value().map_err(|ref e| map_kv_err(e))?
+                && let Some(key) = raw_key
+                    .get(prefix_len..)
+                    .and_then(|k| std::str::from_utf8(k).ok())
+                    .filter(|k| !k.is_empty())
+            {
+                entries.push(KvEntry {
+                    namespace: namespace.to_string(),
+                    key: key.to_string(),
+                    value,
+                });
+            }
+            last_key = Some(raw_key);
+            iter.next().map_err(|ref e| map_kv_err(e))?;
+        }
+
+        // The smallest key strictly greater than the last one read.
+        let next = iter.valid().then_some(last_key).flatten().map(|mut k| {
+            k.push(0);
+            k
+        });
+        Ok((entries, next))
+    }
+}
+
+#[async_trait]
+impl KvStore for SurrealKvStore {
+    async fn get(&self, namespace: &str, key: &str) -> StorageResult<Option<Vec<u8>>> {
+        validate_namespace(namespace)?;
+        validate_key(key)?;
+        let ck = composite_key(namespace, key);
+        let tx = self
+            .tree
+            .begin_with_mode(surrealkv::Mode::ReadOnly)
+            .map_err(|ref e| map_kv_err(e))?;
+        if is_expired(&tx, &ck, now_millis())? {
+            return Ok(None);
+        }
+        tx.get(&ck).map_err(|ref e| map_kv_err(e))
+    }
+
+    async fn set(&self, namespace: &str, key: &str, value: Vec<u8>) -> StorageResult<()> {
+        validate_namespace(namespace)?;
+        validate_key(key)?;
+        let mut tx = self.tree.begin().map_err(|ref e| map_kv_err(e))?;
+        apply_op(
+            &mut tx,
+            namespace,
+            KvOp::Set {
+                key: key.to_string(),
+                value,
+            },
+        )?;
+        tx.commit().await.map_err(|ref e| map_kv_err(e))
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        validate_namespace(namespace)?;
+        validate_key(key)?;
+        let ck = composite_key(namespace, key);
+        let mut tx = self.tree.begin().map_err(|ref e| map_kv_err(e))?;
+        let present = tx.get(&ck).map_err(|ref e| map_kv_err(e))?.is_some();
+        if present {
+            let existed = !is_expired(&tx, &ck, now_millis())?;
+            apply_op(
+                &mut tx,
+                namespace,
+                KvOp::Delete {
+                    key: key.to_string(),
+                },
+            )?;
+            tx.commit().await.map_err(|ref e| map_kv_err(e))?;
+            return Ok(existed);
+        }
+        Ok(false)
+    }
+
+    async fn exists(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        validate_namespace(namespace)?;
+        validate_key(key)?;
+        let ck = composite_key(namespace, key);
+        let tx = self
+            .tree
+            .begin_with_mode(surrealkv::Mode::ReadOnly)
+            .map_err(|ref e| map_kv_err(e))?;
+        if is_expired(&tx, &ck, now_millis())? {
+            return Ok(false);
+        }
+        Ok(tx.get(&ck).map_err(|ref e| map_kv_err(e))?.is_some())
+    }
+
+    async fn list_keys(&self, namespace: &str) -> StorageResult<Vec<String>> {
+        validate_namespace(namespace)?;
+        let start = namespace_range_start(namespace);
+        let end = namespace_range_end(namespace);
+        let prefix_len = namespace.len().saturating_add(1); // namespace + \0
+
+        let tx = self
+            .tree
+            .begin_with_mode(surrealkv::Mode::ReadOnly)
+            .map_err(|ref e| map_kv_err(e))?;
+        list_live_keys(&tx, &start, &end, prefix_len)
+    }
+
+    async fn list_keys_with_prefix(
+        &self,
+        namespace: &str,
+        prefix: &str,
+    ) -> StorageResult<Vec<String>> {
+        validate_namespace(namespace)?;
+        let start = composite_key(namespace, prefix);
+        let end = prefix_range_end(namespace, prefix);
+        let prefix_len = namespace.len().saturating_add(1); // namespace + \0
+
+        let tx = self
+            .tree
+            .begin_with_mode(surrealkv::Mode::ReadOnly)
+            .map_err(|ref e| map_kv_err(e))?;
+        list_live_keys(&tx, &start, &end, prefix_len)
+    }
+
+    async fn clear_namespace(&self, namespace: &str) -> StorageResult<u64> {
+        validate_namespace(namespace)?;
+        let start = namespace_range_start(namespace);
+        let end = namespace_range_end(namespace);
+
+        let mut tx = self.tree.begin().map_err(|ref e| map_kv_err(e))?;
+        let (count, touched) = delete_range(&mut tx, &start, &end)?;
+        if touched {
+            tx.commit().await.map_err(|ref e| map_kv_err(e))?;
+        }
+        Ok(count)
+    }
+
+    async fn clear_prefix(&self, namespace: &str, prefix: &str) -> StorageResult<u64> {
+        validate_namespace(namespace)?;
+        validate_prefix(prefix)?;
+        let start = composite_key(namespace, prefix);
+        let end = prefix_range_end(namespace, prefix);
+
+        let mut tx = self.tree.begin().map_err(|ref e| map_kv_err(e))?;
+        let (count, touched) = delete_range(&mut tx, &start, &end)?;
+        if touched {
+            tx.commit().await.map_err(|ref e| map_kv_err(e))?;
+        }
+        // When nothing was touched, tx is dropped without commit. SurrealKV's
+        // MVCC model aborts uncommitted transactions on Drop.
+        Ok(count)
+    }
+
+    async fn set_with_ttl(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> StorageResult<()> {
+        validate_namespace(namespace)?;
+        validate_key(key)?;
+        let mut tx = self.tree.begin().map_err(|ref e| map_kv_err(e))?;
+        apply_op(
+            &mut tx,
+            namespace,
+            KvOp::SetWithTtl {
+                key: key.to_string(),
+                value,
+                ttl,
+            },
+        )?;
+        tx.commit().await.map_err(|ref e| map_kv_err(e))
+    }
+
+    fn scan_prefix<'a>(&'a self, namespace: &'a str, prefix: &'a str) -> KvEntryStream<'a> {
+        use futures::StreamExt as _;
+
+        if let Err(e) = validate_namespace(namespace).and_then(|()| validate_prefix(prefix)) {
+            return Box::pin(futures::stream::iter([Err(e)]));
+        }
+        let pages = futures::stream::unfold(
+            Some(composite_key(namespace, prefix)),
+            move |cursor| async move {
+                let start = cursor?;
+                Some(match self.scan_page(namespace, prefix, &start) {
+                    Ok((entries, next)) => (Ok(entries), next),
+                    Err(e) => (Err(e), None),
+                })
+            },
+        );
+        Box::pin(pages.flat_map(|page| {
+            let items: Vec<StorageResult<KvEntry>> = match page {
+                Ok(entries) => entries.into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            };
+            futures::stream::iter(items)
+        }))
+    }
+
+    async fn batch(&self, namespace: &str, ops: Vec<KvOp>) -> StorageResult<()> {
+        validate_ops(namespace, &ops)?;
+        if ops.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.tree.begin().map_err(|ref e| map_kv_err(e))?;
+        for op in ops {
+            apply_op(&mut tx, namespace, op)?;
+        }
+        // A failed commit leaves every op unapplied.
+        tx.commit().await.map_err(|ref e| map_kv_err(e))
+    }
+
+    async fn purge_expired(&self) -> StorageResult<u64> {
+        // Every expiry record lives in ["\0ttl\0", "\0ttl\x01").
+        let mut end = TTL_PREFIX.to_vec();
+        if let Some(last) = end.last_mut() {
+            *last = 1;
+        }
+        let now = now_millis();
+
+        let mut tx = self.tree.begin().map_err(|ref e| map_kv_err(e))?;
+        let expired = {
+            let mut iter = tx
+                .range(TTL_PREFIX, end.as_slice())
+                .map_err(|ref e| map_kv_err(e))?;
+            iter.seek_first().map_err(|ref e| map_kv_err(e))?;
+            let mut keys = Vec::new();
+            while iter.valid() {
+                let deadline = iter.value().map_err(|ref e| map_kv_err(e))?;
+                if deadline
+                    .as_deref()
+                    .and_then(decode_deadline)
+                    .is_some_and(|at| at <= now)
+                {
+                    keys.push(iter.key());
+                }
+                iter.next().map_err(|ref e| map_kv_err(e))?;
+            }
+            keys
+        }; // iterator dropped — releases immutable borrow on tx
+
+        for tk in &expired {
+            tx.delete(tk).map_err(|ref e| map_kv_err(e))?;
+            tx.delete(&tk[TTL_PREFIX.len()..])
+                .map_err(|ref e| map_kv_err(e))?;
+        }
+        if !expired.is_empty() {
+            tx.commit().await.map_err(|ref e| map_kv_err(e))?;
+        }
+        Ok(u64::try_from(expired.len()).unwrap_or(u64::MAX))
+    }
+
+    async fn snapshot(&self, writer: &mut (dyn std::io::Write + Send)) -> StorageResult<u64> {
+        // A read-only transaction is an MVCC snapshot: concurrent writers
+        // commit new versions without waiting for the export to finish, and
+        // the export never observes them.
+        let tx = self
+            .tree
+            .begin_with_mode(surrealkv::Mode::ReadOnly)
+            .map_err(|ref e| map_kv_err(e))?;
+        let now = now_millis();
+
+        // User keys start with a non-empty UTF-8 namespace, so they fall in
+        // [0x01, 0xFF); expiry records (leading 0x00) are read per key.
+        let mut iter = tx
+            .range(&[1u8][..], &[0xFFu8][..])
+            .map_err(|ref e| map_kv_err(e))?;
+        iter.seek_first().map_err(|ref e| map_kv_err(e))?;
+
+        let mut out = SnapshotWriter::begin(writer)?;
+        while iter.valid() {
+            let raw_key = iter.key();
+            let deadline = tx
+                .get(ttl_key(&raw_key))
+                .map_err(|ref e| map_kv_err(e))?
+                .as_deref()
+                .and_then(decode_deadline);
+            if deadline.is_none_or(|at| at > now)
+                && let Some(sep) = raw_key.iter().position(|&b| b == 0)
+                && let Some(value) = iter.value().map_err(|ref e| map_kv_err(e))?
+            {
+                let (namespace, key) = raw_key.split_at(sep);
+                out.entry(namespace, &key[1..], &value, deadline)?;
+            }
+            iter.next().map_err(|ref e| map_kv_err(e))?;
+        }
+        out.finish()
+    }
+
+    async fn restore(&self, reader: &mut (dyn std::io::Read + Send)) -> StorageResult<u64> {
+        let entries = read_snapshot(reader)?;
+
+        let mut tx = self.tree.begin().map_err(|ref e| map_kv_err(e))?;
+        let occupied = {
+            let mut iter = tx
+                .range(&[0u8][..], &[0xFFu8][..])
+                .map_err(|ref e| map_kv_err(e))?;
+            iter.seek_first().map_err(|ref e| map_kv_err(e))?
+        };
+        if occupied {
+            return Err(StorageError::Internal("restore target is not empty".into()));
+        }
+
+        let count = u64::try_from(entries.len()).unwrap_or(u64::MAX);
+        for e in &entries {
+            let ck = composite_key(&e.entry.namespace, &e.entry.key);
+            tx.set(&ck, &e.entry.value).map_err(|ref e| map_kv_err(e))?;
+            if let Some(at) = e.expires_at {
+                tx.set(ttl_key(&ck), &at.to_be_bytes())
+                    .map_err(|ref e| map_kv_err(e))?;
+            }
+        }
+        if count > 0 {
+            tx.commit().await.map_err(|ref e| map_kv_err(e))?;
+        }
+        Ok(count)
+    }
+}
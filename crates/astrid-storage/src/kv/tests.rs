@@ -0,0 +1,544 @@
+use super::*;
+
+// -- MemoryKvStore tests --
+
+#[tokio::test]
+async fn test_memory_get_set() {
+    let store = MemoryKvStore::new();
+    store.set("ns1", "key1", b"hello".to_vec()).await.unwrap();
+    let val = store.get("ns1", "key1").await.unwrap();
+    assert_eq!(val, Some(b"hello".to_vec()));
+}
+
+#[tokio::test]
+async fn test_memory_get_missing() {
+    let store = MemoryKvStore::new();
+    let val = store.get("ns1", "missing").await.unwrap();
+    assert!(val.is_none());
+}
+
+#[tokio::test]
+async fn test_memory_overwrite() {
+    let store = MemoryKvStore::new();
+    store.set("ns1", "k", b"v1".to_vec()).await.unwrap();
+    store.set("ns1", "k", b"v2".to_vec()).await.unwrap();
+    let val = store.get("ns1", "k").await.unwrap();
+    assert_eq!(val, Some(b"v2".to_vec()));
+}
+
+#[tokio::test]
+async fn test_memory_delete() {
+    let store = MemoryKvStore::new();
+    store.set("ns1", "k", b"v".to_vec()).await.unwrap();
+    assert!(store.delete("ns1", "k").await.unwrap());
+    assert!(!store.delete("ns1", "k").await.unwrap());
+    assert!(store.get("ns1", "k").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_memory_exists() {
+    let store = MemoryKvStore::new();
+    assert!(!store.exists("ns1", "k").await.unwrap());
+    store.set("ns1", "k", b"v".to_vec()).await.unwrap();
+    assert!(store.exists("ns1", "k").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_memory_namespace_isolation() {
+    let store = MemoryKvStore::new();
+    store.set("ns1", "k", b"v1".to_vec()).await.unwrap();
+    store.set("ns2", "k", b"v2".to_vec()).await.unwrap();
+    assert_eq!(store.get("ns1", "k").await.unwrap(), Some(b"v1".to_vec()));
+    assert_eq!(store.get("ns2", "k").await.unwrap(), Some(b"v2".to_vec()));
+}
+
+#[tokio::test]
+async fn test_memory_list_keys() {
+    let store = MemoryKvStore::new();
+    store.set("ns1", "a", b"1".to_vec()).await.unwrap();
+    store.set("ns1", "b", b"2".to_vec()).await.unwrap();
+    store.set("ns2", "c", b"3".to_vec()).await.unwrap();
+    let mut keys = store.list_keys("ns1").await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["a", "b"]);
+}
+
+#[tokio::test]
+async fn test_memory_clear_namespace() {
+    let store = MemoryKvStore::new();
+    store.set("ns1", "a", b"1".to_vec()).await.unwrap();
+    store.set("ns1", "b", b"2".to_vec()).await.unwrap();
+    store.set("ns2", "c", b"3".to_vec()).await.unwrap();
+    let cleared = store.clear_namespace("ns1").await.unwrap();
+    assert_eq!(cleared, 2);
+    assert!(store.list_keys("ns1").await.unwrap().is_empty());
+    assert_eq!(store.list_keys("ns2").await.unwrap().len(), 1);
+}
+
+// -- Validation tests --
+
+#[test]
+fn test_validate_namespace_rejects_empty() {
+    assert!(validate_namespace("").is_err());
+}
+
+#[test]
+fn test_validate_namespace_rejects_null_byte() {
+    assert!(validate_namespace("ns\0bad").is_err());
+}
+
+#[test]
+fn test_validate_key_rejects_empty() {
+    assert!(validate_key("").is_err());
+}
+
+#[test]
+fn test_validate_key_rejects_null_byte() {
+    assert!(validate_key("k\0bad").is_err());
+}
+
+// -- ScopedKvStore tests --
+
+#[tokio::test]
+async fn test_scoped_get_set() {
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "wasm:plugin-a").unwrap();
+
+    scoped.set("greeting", b"hello".to_vec()).await.unwrap();
+    assert_eq!(
+        scoped.get("greeting").await.unwrap(),
+        Some(b"hello".to_vec())
+    );
+}
+
+#[tokio::test]
+async fn test_scoped_isolation() {
+    let store: Arc<dyn KvStore> = Arc::new(MemoryKvStore::new());
+    let a = ScopedKvStore::new(Arc::clone(&store), "wasm:plugin-a").unwrap();
+    let b = ScopedKvStore::new(Arc::clone(&store), "wasm:plugin-b").unwrap();
+
+    a.set("key", b"a-value".to_vec()).await.unwrap();
+    b.set("key", b"b-value".to_vec()).await.unwrap();
+
+    assert_eq!(a.get("key").await.unwrap(), Some(b"a-value".to_vec()));
+    assert_eq!(b.get("key").await.unwrap(), Some(b"b-value".to_vec()));
+}
+
+#[tokio::test]
+async fn test_scoped_delete_and_exists() {
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "ns").unwrap();
+
+    assert!(!scoped.exists("k").await.unwrap());
+    scoped.set("k", b"v".to_vec()).await.unwrap();
+    assert!(scoped.exists("k").await.unwrap());
+    assert!(scoped.delete("k").await.unwrap());
+    assert!(!scoped.exists("k").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_scoped_list_and_clear() {
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "ns").unwrap();
+
+    scoped.set("a", b"1".to_vec()).await.unwrap();
+    scoped.set("b", b"2".to_vec()).await.unwrap();
+
+    let mut keys = scoped.list_keys().await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["a", "b"]);
+
+    assert_eq!(scoped.clear().await.unwrap(), 2);
+    assert!(scoped.list_keys().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_scoped_json_round_trip() {
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Config {
+        name: String,
+        retries: u32,
+    }
+
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "ns").unwrap();
+
+    let cfg = Config {
+        name: "my-plugin".into(),
+        retries: 3,
+    };
+    scoped.set_json("config", &cfg).await.unwrap();
+
+    let loaded: Config = scoped.get_json("config").await.unwrap().unwrap();
+    assert_eq!(loaded, cfg);
+}
+
+#[tokio::test]
+async fn test_scoped_json_missing_returns_none() {
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "ns").unwrap();
+
+    let val: Option<String> = scoped.get_json("missing").await.unwrap();
+    assert!(val.is_none());
+}
+
+#[tokio::test]
+async fn test_scoped_rejects_empty_key() {
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "ns").unwrap();
+    assert!(scoped.get("").await.is_err());
+}
+
+#[test]
+fn test_scoped_rejects_empty_namespace() {
+    let store = Arc::new(MemoryKvStore::new());
+    assert!(ScopedKvStore::new(store, "").is_err());
+}
+
+// -- ScopedKvStore prefix operations --
+
+#[tokio::test]
+async fn test_scoped_list_keys_with_prefix() {
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "ns").unwrap();
+
+    scoped.set("react.turn.abc", b"1".to_vec()).await.unwrap();
+    scoped.set("react.turn.def", b"2".to_vec()).await.unwrap();
+    scoped
+        .set("react.req2sess.xyz", b"3".to_vec())
+        .await
+        .unwrap();
+    scoped.set("session.data.abc", b"4".to_vec()).await.unwrap();
+
+    let mut keys = scoped.list_keys_with_prefix("react.turn.").await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["react.turn.abc", "react.turn.def"]);
+
+    let keys = scoped
+        .list_keys_with_prefix("react.req2sess.")
+        .await
+        .unwrap();
+    assert_eq!(keys, vec!["react.req2sess.xyz"]);
+
+    let keys = scoped.list_keys_with_prefix("session.").await.unwrap();
+    assert_eq!(keys, vec!["session.data.abc"]);
+}
+
+#[tokio::test]
+async fn test_scoped_clear_prefix() {
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "ns").unwrap();
+
+    scoped.set("react.turn.a", b"1".to_vec()).await.unwrap();
+    scoped.set("react.turn.b", b"2".to_vec()).await.unwrap();
+    scoped.set("session.data.a", b"3".to_vec()).await.unwrap();
+
+    let cleared = scoped.clear_prefix("react.turn.").await.unwrap();
+    assert_eq!(cleared, 2);
+
+    // Session data untouched
+    assert!(scoped.exists("session.data.a").await.unwrap());
+    // React turn state cleared
+    assert!(!scoped.exists("react.turn.a").await.unwrap());
+    assert!(!scoped.exists("react.turn.b").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_scoped_clear_prefix_no_matches() {
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "ns").unwrap();
+
+    scoped.set("other.key", b"1".to_vec()).await.unwrap();
+
+    let cleared = scoped.clear_prefix("react.turn.").await.unwrap();
+    assert_eq!(cleared, 0);
+    assert!(scoped.exists("other.key").await.unwrap());
+}
+
+#[tokio::test]
+async fn test_scoped_list_keys_empty_prefix_returns_all() {
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "ns").unwrap();
+
+    scoped.set("a", b"1".to_vec()).await.unwrap();
+    scoped.set("b", b"2".to_vec()).await.unwrap();
+    scoped.set("c", b"3".to_vec()).await.unwrap();
+
+    // Empty prefix matches all keys (every string starts with "")
+    let mut keys = scoped.list_keys_with_prefix("").await.unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn test_scoped_clear_prefix_empty_clears_all() {
+    let store = Arc::new(MemoryKvStore::new());
+    let scoped = ScopedKvStore::new(store, "ns").unwrap();
+
+    scoped.set("a", b"1".to_vec()).await.unwrap();
+    scoped.set("b", b"2".to_vec()).await.unwrap();
+
+    // Empty prefix matches all keys
+    let cleared = scoped.clear_prefix("").await.unwrap();
+    assert_eq!(cleared, 2);
+    assert!(scoped.list_keys().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_memory_dump_load_round_trips() {
+    let store = MemoryKvStore::new();
+    store.set("ns", "a", b"1".to_vec()).await.unwrap();
+    store
+        .set_with_ttl("ns", "b", b"2".to_vec(), Duration::from_hours(1))
+        .await
+        .unwrap();
+
+    let loaded = MemoryKvStore::load(&store.dump().unwrap()).unwrap();
+    assert_eq!(loaded.get("ns", "a").await.unwrap(), Some(b"1".to_vec()));
+    assert_eq!(loaded.get("ns", "b").await.unwrap(), Some(b"2".to_vec()));
+    assert!(MemoryKvStore::load(b"not a snapshot").is_err());
+}
+
+// -- InstrumentedKvStore tests --
+
+#[tokio::test]
+async fn test_instrumented_records_counts_errors_and_bytes() {
+    use futures::StreamExt;
+
+    let store = InstrumentedKvStore::new(MemoryKvStore::new())
+        .with_slow_threshold(Duration::ZERO)
+        .with_sample_interval(1);
+    store.set("ns", "a", vec![0; 100]).await.unwrap();
+    store.set("ns", "b", vec![0; 4]).await.unwrap();
+    let invalid = vec![KvOp::Delete { key: String::new() }];
+    assert!(store.batch("ns", invalid).await.is_err());
+    assert!(store.get("ns", "a").await.unwrap().is_some());
+    let scanned: Vec<_> = store.scan_prefix("ns", "").collect().await;
+    assert_eq!(scanned.len(), 2);
+
+    let metrics = store.metrics();
+    let set = metrics.op(KvOpKind::Set).unwrap();
+    assert_eq!((set.count, set.errors), (2, 0));
+    assert_eq!(set.bytes_total, 104);
+    assert_eq!(set.bytes_p99, 100);
+    // A zero threshold marks every operation as slow.
+    assert_eq!(set.slow, 2);
+
+    let batch = metrics.op(KvOpKind::Batch).unwrap();
+    assert_eq!((batch.count, batch.errors), (1, 1));
+
+    let get = metrics.op(KvOpKind::Get).unwrap();
+    assert_eq!((get.count, get.errors, get.bytes_total), (1, 0, 100));
+    assert!(get.latency_p50_us <= get.latency_p99_us);
+    assert!(get.latency_p99_us <= get.latency_max_us);
+
+    let scan = metrics.op(KvOpKind::Scan).unwrap();
+    assert_eq!((scan.count, scan.bytes_total), (1, 104));
+    assert!(metrics.op(KvOpKind::Delete).is_none());
+}
+
+#[tokio::test]
+async fn test_instrumented_samples_latency_but_counts_every_op() {
+    let store = InstrumentedKvStore::new(MemoryKvStore::new())
+        .with_slow_threshold(Duration::ZERO)
+        .with_sample_interval(4);
+    for i in 0..8u8 {
+        store.set("ns", "k", vec![0; usize::from(i)]).await.unwrap();
+    }
+
+    let set = store.metrics().op(KvOpKind::Set).cloned().unwrap();
+    assert_eq!(set.count, 8);
+    assert_eq!(set.bytes_total, 28);
+    // Only the 1st and 5th writes (0 and 4 bytes) were sampled.
+    assert_eq!(set.slow, 2);
+    assert_eq!(set.bytes_p99, 4);
+}
+
+// -- Optional operations --
+
+/// A backend implementing only the required point operations.
+struct PointOnly(MemoryKvStore);
+
+#[async_trait::async_trait]
+impl KvStore for PointOnly {
+    async fn get(&self, namespace: &str, key: &str) -> StorageResult<Option<Vec<u8>>> {
+        self.0.get(namespace, key).await
+    }
+
+    async fn set(&self, namespace: &str, key: &str, value: Vec<u8>) -> StorageResult<()> {
+        self.0.set(namespace, key, value).await
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        self.0.delete(namespace, key).await
+    }
+
+    async fn exists(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        self.0.exists(namespace, key).await
+    }
+
+    async fn list_keys(&self, namespace: &str) -> StorageResult<Vec<String>> {
+        self.0.list_keys(namespace).await
+    }
+
+    async fn clear_namespace(&self, namespace: &str) -> StorageResult<u64> {
+        self.0.clear_namespace(namespace).await
+    }
+}
+
+#[tokio::test]
+async fn test_optional_operations_default_to_unsupported() {
+    let store = PointOnly(MemoryKvStore::new());
+    let unsupported =
+        |e: StorageError| matches!(e, StorageError::Internal(m) if m == "unsupported");
+
+    let ttl = store
+        .set_with_ttl("ns", "k", b"v".to_vec(), std::time::Duration::from_secs(1))
+        .await;
+    assert!(ttl.is_err_and(unsupported));
+    assert!(
+        store
+            .snapshot(&mut Vec::new())
+            .await
+            .is_err_and(unsupported)
+    );
+    assert!(
+        store
+            .restore(&mut std::io::empty())
+            .await
+            .is_err_and(unsupported)
+    );
+    assert!(!store.exists("ns", "k").await.unwrap());
+}
+
+// -- SurrealKvStore tests (behind feature gate) --
+
+#[cfg(feature = "kv")]
+mod surreal_kv_tests {
+    use super::*;
+
+    fn make_store() -> (SurrealKvStore, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SurrealKvStore::open(dir.path()).unwrap();
+        (store, dir)
+    }
+
+    #[tokio::test]
+    async fn test_surreal_get_set() {
+        let (store, _dir) = make_store();
+        store.set("ns1", "key1", b"hello".to_vec()).await.unwrap();
+        let val = store.get("ns1", "key1").await.unwrap();
+        assert_eq!(val, Some(b"hello".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_surreal_get_missing() {
+        let (store, _dir) = make_store();
+        let val = store.get("ns1", "missing").await.unwrap();
+        assert!(val.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_surreal_overwrite() {
+        let (store, _dir) = make_store();
+        store.set("ns1", "k", b"v1".to_vec()).await.unwrap();
+        store.set("ns1", "k", b"v2".to_vec()).await.unwrap();
+        let val = store.get("ns1", "k").await.unwrap();
+        assert_eq!(val, Some(b"v2".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_surreal_delete() {
+        let (store, _dir) = make_store();
+        store.set("ns1", "k", b"v".to_vec()).await.unwrap();
+        assert!(store.delete("ns1", "k").await.unwrap());
+        assert!(!store.delete("ns1", "k").await.unwrap());
+        assert!(store.get("ns1", "k").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_surreal_exists() {
+        let (store, _dir) = make_store();
+        assert!(!store.exists("ns1", "k").await.unwrap());
+        store.set("ns1", "k", b"v".to_vec()).await.unwrap();
+        assert!(store.exists("ns1", "k").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_surreal_namespace_isolation() {
+        let (store, _dir) = make_store();
+        store.set("ns1", "k", b"v1".to_vec()).await.unwrap();
+        store.set("ns2", "k", b"v2".to_vec()).await.unwrap();
+        assert_eq!(store.get("ns1", "k").await.unwrap(), Some(b"v1".to_vec()));
+        assert_eq!(store.get("ns2", "k").await.unwrap(), Some(b"v2".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn test_surreal_list_keys() {
+        let (store, _dir) = make_store();
+        store.set("ns1", "a", b"1".to_vec()).await.unwrap();
+        store.set("ns1", "b", b"2".to_vec()).await.unwrap();
+        store.set("ns2", "c", b"3".to_vec()).await.unwrap();
+        let mut keys = store.list_keys("ns1").await.unwrap();
+        keys.sort();
+        assert_eq!(keys, vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_surreal_clear_namespace() {
+        let (store, _dir) = make_store();
+        store.set("ns1", "a", b"1".to_vec()).await.unwrap();
+        store.set("ns1", "b", b"2".to_vec()).await.unwrap();
+        store.set("ns2", "c", b"3".to_vec()).await.unwrap();
+        let cleared = store.clear_namespace("ns1").await.unwrap();
+        assert_eq!(cleared, 2);
+        assert!(store.list_keys("ns1").await.unwrap().is_empty());
+        assert_eq!(store.list_keys("ns2").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_surreal_clear_prefix_basic() {
+        let (store, _dir) = make_store();
+        store.set("ns1", "pfx.a", b"1".to_vec()).await.unwrap();
+        store.set("ns1", "pfx.b", b"2".to_vec()).await.unwrap();
+        store.set("ns1", "other", b"3".to_vec()).await.unwrap();
+        store.set("ns2", "pfx.c", b"4".to_vec()).await.unwrap();
+
+        let cleared = store.clear_prefix("ns1", "pfx.").await.unwrap();
+        assert_eq!(cleared, 2);
+        // "other" in ns1 untouched
+        assert_eq!(
+            store.get("ns1", "other").await.unwrap(),
+            Some(b"3".to_vec())
+        );
+        // ns2 untouched
+        assert_eq!(
+            store.get("ns2", "pfx.c").await.unwrap(),
+            Some(b"4".to_vec())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_surreal_clear_prefix_no_matches() {
+        let (store, _dir) = make_store();
+        store.set("ns1", "key", b"v".to_vec()).await.unwrap();
+        let cleared = store.clear_prefix("ns1", "nope.").await.unwrap();
+        assert_eq!(cleared, 0);
+        // Original key untouched
+        assert!(store.exists("ns1", "key").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_surreal_clear_prefix_empty_clears_all() {
+        let (store, _dir) = make_store();
+        store.set("ns1", "a", b"1".to_vec()).await.unwrap();
+        store.set("ns1", "b", b"2".to_vec()).await.unwrap();
+        store.set("ns2", "c", b"3".to_vec()).await.unwrap();
+
+        let cleared = store.clear_prefix("ns1", "").await.unwrap();
+        assert_eq!(cleared, 2);
+        assert!(store.list_keys("ns1").await.unwrap().is_empty());
+        // ns2 untouched
+        assert_eq!(store.list_keys("ns2").await.unwrap().len(), 1);
+    }
+}
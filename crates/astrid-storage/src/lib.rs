@@ -34,6 +34,7 @@
 //! - **`kv`** — `SurrealKV` raw key-value store
 //! - **`db`** — `SurrealDB` full query engine
 //! - **`full`** — Both `kv` and `db`
+//! - **`test-support`** — [`testing::FaultInjectingKvStore`] for failure-path tests
 
 #![deny(unsafe_code)]
 #![deny(missing_docs)]
@@ -46,13 +47,20 @@ pub mod error;
 pub mod identity;
 pub mod kv;
 pub mod secret;
+/// Fault-injecting [`KvStore`] wrapper for failure-path tests.
+/// Requires the `test-support` feature.
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
 
 #[cfg(feature = "db")]
 pub mod db;
 
 pub use error::{StorageError, StorageResult};
 pub use identity::{IdentityError, IdentityStore, KvIdentityStore};
-pub use kv::{KvEntry, KvStore, MemoryKvStore, ScopedKvStore};
+pub use kv::{
+    EncryptedKvStore, InstrumentedKvStore, KvEntry, KvEntryStream, KvMetrics, KvOp, KvOpKind,
+    KvOpMetrics, KvStore, MemoryKvStore, ScopedKvStore, spawn_ttl_sweeper,
+};
 pub use secret::{KvSecretStore, SecretStore, SecretStoreError, build_secret_store};
 
 #[cfg(feature = "keychain")]
@@ -0,0 +1,294 @@
+//! Test helpers for exercising storage failure paths.
+//!
+//! Gated behind the `test-support` feature. Add to your `dev-dependencies`:
+//!
+//! ```toml
+//! astrid-storage = { workspace = true, features = ["test-support"] }
+//! ```
+
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+
+use crate::error::{StorageError, StorageResult};
+use crate::kv::{KvEntryStream, KvOp, KvStore, MemoryKvStore};
+
+/// A [`KvStore`] wrapper that can be scripted to fail, stall, or return
+/// corrupted bytes, so callers' error paths can be tested without disk.
+///
+/// Every trait method counts as one operation (a scan counts once, when the
+/// stream is created). Scripts can be changed at any time through `&self`,
+/// so a test can share the store behind an `Arc` and arm faults between
+/// phases.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let store = Arc::new(FaultInjectingKvStore::new(MemoryKvStore::new()));
+/// store.fail_nth(2); // the second operation from now fails
+/// store.corrupt_key("ns", "k"); // reads of ns/k return flipped bytes
+/// ```
+#[derive(Debug)]
+pub struct FaultInjectingKvStore<S = MemoryKvStore> {
+    inner: S,
+    ops: AtomicU64,
+    script: Mutex<FaultScript>,
+}
+
+#[derive(Debug, Default)]
+struct FaultScript {
+    /// Absolute (1-based) operation numbers that fail.
+    fail_at: BTreeSet<u64>,
+    fail_all: bool,
+    latency: Duration,
+    corrupt: HashSet<(String, String)>,
+}
+
+impl<S: KvStore> FaultInjectingKvStore<S> {
+    /// Wrap `inner` with an empty fault script.
+    #[must_use]
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            ops: AtomicU64::new(0),
+            script: Mutex::new(FaultScript::default()),
+        }
+    }
+
+    /// The wrapped store, for inspecting state behind the faults.
+    #[must_use]
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Number of operations issued so far.
+    #[must_use]
+    pub fn op_count(&self) -> u64 {
+        self.ops.load(Ordering::SeqCst)
+    }
+
+    /// Fail the `n`th operation counted from now (`1` is the next one).
+    pub fn fail_nth(&self, n: u64) {
+        let target = self.op_count().saturating_add(n.max(1));
+        self.with_script(|s| {
+            s.fail_at.insert(target);
+        });
+    }
+
+    /// Fail every operation until turned off again.
+    pub fn fail_all(&self, enabled: bool) {
+        self.with_script(|s| s.fail_all = enabled);
+    }
+
+    /// Delay every operation by `latency` before it reaches the inner store.
+    pub fn set_latency(&self, latency: Duration) {
+        self.with_script(|s| s.latency = latency);
+    }
+
+    /// Return bit-flipped bytes whenever `namespace`/`key` is read.
+    pub fn corrupt_key(&self, namespace: &str, key: &str) {
+        self.with_script(|s| {
+            s.corrupt.insert((namespace.to_string(), key.to_string()));
+        });
+    }
+
+    /// Clear every scripted fault. The operation counter keeps running.
+    pub fn reset(&self) {
+        self.with_script(|s| *s = FaultScript::default());
+    }
+
+    fn with_script<T>(&self, f: impl FnOnce(&mut FaultScript) -> T) -> T {
+        // A poisoned script only means another test thread panicked; the
+        // data is still a plain set of flags.
+        let mut script = self
+            .script
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        f(&mut script)
+    }
+
+    /// Count the operation and decide whether it fails. Returns the latency
+    /// to apply before running it.
+    fn admit(&self, op: &str) -> StorageResult<Duration> {
+        let n = self.ops.fetch_add(1, Ordering::SeqCst).saturating_add(1);
+        self.with_script(|s| {
+            if s.fail_all || s.fail_at.remove(&n) {
+                Err(StorageError::Internal(format!(
+                    "injected fault on {op} (operation {n})"
+                )))
+            } else {
+                Ok(s.latency)
+            }
+        })
+    }
+
+    async fn before(&self, op: &str) -> StorageResult<()> {
+        let latency = self.admit(op)?;
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        Ok(())
+    }
+
+    fn is_corrupt(&self, namespace: &str, key: &str) -> bool {
+        self.with_script(|s| {
+            s.corrupt
+                .contains(&(namespace.to_string(), key.to_string()))
+        })
+    }
+}
+
+fn flip(mut bytes: Vec<u8>) -> Vec<u8> {
+    for b in &mut bytes {
+        *b = !*b;
+    }
+    bytes
+}
+
+#[async_trait]
+impl<S: KvStore> KvStore for FaultInjectingKvStore<S> {
+    async fn get(&self, namespace: &str, key: &str) -> StorageResult<Option<Vec<u8>>> {
+        self.before("get").await?;
+        let value = self.inner.get(namespace, key).await?;
+        Ok(if self.is_corrupt(namespace, key) {
+            value.map(flip)
+        } else {
+            value
+        })
+    }
+
+    async fn set(&self, namespace: &str, key: &str, value: Vec<u8>) -> StorageResult<()> {
+        self.before("set").await?;
+        self.inner.set(namespace, key, value).await
+    }
+
+    async fn delete(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        self.before("delete").await?;
+        self.inner.delete(namespace, key).await
+    }
+
+    async fn exists(&self, namespace: &str, key: &str) -> StorageResult<bool> {
+        self.before("exists").await?;
+        self.inner.exists(namespace, key).await
+    }
+
+    async fn list_keys(&self, namespace: &str) -> StorageResult<Vec<String>> {
+        self.before("list_keys").await?;
+        self.inner.list_keys(namespace).await
+    }
+
+    async fn list_keys_with_prefix(
+        &self,
+        namespace: &str,
+        prefix: &str,
+    ) -> StorageResult<Vec<String>> {
+        self.before("list_keys_with_prefix").await?;
+        self.inner.list_keys_with_prefix(namespace, prefix).await
+    }
+
+    async fn clear_namespace(&self, namespace: &str) -> StorageResult<u64> {
+        self.before("clear_namespace").await?;
+        self.inner.clear_namespace(namespace).await
+    }
+
+    async fn clear_prefix(&self, namespace: &str, prefix: &str) -> StorageResult<u64> {
+        self.before("clear_prefix").await?;
+        self.inner.clear_prefix(namespace, prefix).await
+    }
+
+    async fn set_with_ttl(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: Vec<u8>,
+        ttl: Duration,
+    ) -> StorageResult<()> {
+        self.before("set_with_ttl").await?;
+        self.inner.set_with_ttl(namespace, key, value, ttl).await
+    }
+
+    fn scan_prefix<'a>(&'a self, namespace: &'a str, prefix: &'a str) -> KvEntryStream<'a> {
+        // Latency is not applied to scans: the stream is created
+        // synchronously.
+        if let Err(e) = self.admit("scan_prefix") {
+            return Box::pin(futures::stream::once(async move { Err(e) }));
+        }
+        Box::pin(self.inner.scan_prefix(namespace, prefix).map(|item| {
+            item.map(|mut entry| {
+                if self.is_corrupt(&entry.namespace, &entry.key) {
+                    entry.value = flip(entry.value);
+                }
+                entry
+            })
+        }))
+    }
+
+    async fn batch(&self, namespace: &str, ops: Vec<KvOp>) -> StorageResult<()> {
+        self.before("batch").await?;
+        self.inner.batch(namespace, ops).await
+    }
+
+    async fn purge_expired(&self) -> StorageResult<u64> {
+        self.before("purge_expired").await?;
+        self.inner.purge_expired().await
+    }
+
+    async fn snapshot(&self, writer: &mut (dyn std::io::Write + Send)) -> StorageResult<u64> {
+        self.before("snapshot").await?;
+        self.inner.snapshot(writer).await
+    }
+
+    async fn restore(&self, reader: &mut (dyn std::io::Read + Send)) -> StorageResult<u64> {
+        self.before("restore").await?;
+        self.inner.restore(reader).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn scripted_faults_fire_once_and_in_order() {
+        let store = FaultInjectingKvStore::new(MemoryKvStore::new());
+        store.fail_nth(2);
+        store.set("ns", "a", b"1".to_vec()).await.unwrap();
+        assert!(store.set("ns", "b", b"2".to_vec()).await.is_err());
+        store.set("ns", "b", b"2".to_vec()).await.unwrap();
+        assert_eq!(store.op_count(), 3);
+
+        store.fail_all(true);
+        assert!(store.get("ns", "a").await.is_err());
+        let scanned: Vec<_> = store.scan_prefix("ns", "").collect().await;
+        assert!(matches!(scanned.as_slice(), [Err(_)]));
+        store.reset();
+        assert_eq!(store.get("ns", "a").await.unwrap(), Some(b"1".to_vec()));
+    }
+
+    #[tokio::test]
+    async fn corrupt_key_flips_reads_only() {
+        let store = FaultInjectingKvStore::new(MemoryKvStore::new());
+        store.set("ns", "k", vec![0x00, 0x0F]).await.unwrap();
+        store.corrupt_key("ns", "k");
+        assert_eq!(store.get("ns", "k").await.unwrap(), Some(vec![0xFF, 0xF0]));
+        let scanned: Vec<_> = store.scan_prefix("ns", "").collect().await;
+        assert_eq!(scanned[0].as_ref().unwrap().value, vec![0xFF, 0xF0]);
+        assert_eq!(
+            store.inner().get("ns", "k").await.unwrap(),
+            Some(vec![0x00, 0x0F])
+        );
+    }
+
+    #[tokio::test]
+    async fn latency_delays_operations() {
+        let store = FaultInjectingKvStore::new(MemoryKvStore::new());
+        store.set_latency(Duration::from_millis(20));
+        let start = std::time::Instant::now();
+        store.set("ns", "k", b"v".to_vec()).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
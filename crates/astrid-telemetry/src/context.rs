@@ -117,12 +117,18 @@ impl RequestContext {
     }
 
     /// Create a tracing span with this context.
+    ///
+    /// When exported over OTLP the span is named after the operation. Events
+    /// inside the span are captured for the session, if any (see
+    /// [`session_logs`](crate::session_logs)).
     #[must_use]
     pub fn span(&self) -> tracing::Span {
         tracing::info_span!(
             "request",
+            otel.name = self.operation.as_deref().unwrap_or("request"),
             request_id = %self.request_id,
             correlation_id = %self.correlation_id,
+            session_id = self.session_id.map(tracing::field::display),
             source = %self.source,
             operation = self.operation.as_deref(),
         )
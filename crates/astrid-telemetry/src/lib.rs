@@ -4,6 +4,10 @@
 //! - Configurable logging setup with multiple formats
 //! - Request context for correlation across operations
 //! - Integration with the tracing ecosystem
+//! - OpenTelemetry export of spans and metrics (`otlp` feature)
+//! - Redaction of secrets and prompts in log output
+//! - Per-session capture of log lines for diagnostics
+//! - Changing the log level and directives without a restart
 //!
 //! # Example
 //!
@@ -41,11 +45,19 @@ pub mod prelude;
 
 mod context;
 mod error;
+mod live_filter;
 mod logging;
+mod otlp;
+mod redact;
+mod session_logs;
 
 pub use context::RequestContext;
 pub use error::{TelemetryError, TelemetryResult};
+pub use live_filter::{current_log_filter, reload_log_filter, set_level};
 pub use logging::{LogConfig, LogFormat, LogTarget, setup_logging};
+pub use otlp::{METRICS_TARGET, OtlpConfig, shutdown_telemetry};
+pub use redact::{RedactionMode, Sensitive};
+pub use session_logs::{SESSION_FIELD, SessionCaptureConfig, session_logs};
 
 /// Convert an [`astrid_config::Config`] into a [`LogConfig`] for telemetry init.
 ///
@@ -59,10 +71,28 @@ pub fn log_config_from(cfg: &astrid_config::Config) -> LogConfig {
         "full" => LogFormat::Full,
         _ => LogFormat::Compact,
     };
+    let redaction = match cfg.logging.redaction.as_str() {
+        "off" => RedactionMode::Off,
+        "strict" => RedactionMode::Strict,
+        _ => RedactionMode::Standard,
+    };
+    let otlp = &cfg.logging.otlp;
     LogConfig {
         level: cfg.logging.level.clone(),
         format,
         directives: cfg.logging.directives.clone(),
+        otlp: otlp.enabled.then(|| OtlpConfig {
+            endpoint: otlp.endpoint.clone(),
+            service_name: otlp.service_name.clone(),
+            resource_attributes: otlp.resource_attributes.clone(),
+            headers: otlp.headers.clone(),
+            metrics: otlp.metrics,
+        }),
+        redaction,
+        session_capture: SessionCaptureConfig {
+            max_entries: cfg.logging.session_log_entries,
+            max_bytes: cfg.logging.session_log_bytes,
+        },
         ..Default::default()
     }
 }
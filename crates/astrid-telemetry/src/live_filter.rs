@@ -0,0 +1,191 @@
+//! Changing the log filter of a running process.
+//!
+//! [`setup_logging`](crate::setup_logging) installs the log output filter
+//! behind a reload handle, so the level and per-target directives can be
+//! changed without a restart. A change that does not parse is rejected and
+//! the current filter stays in place.
+
+use std::sync::Mutex;
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::Directive;
+use tracing_subscriber::layer::Layered;
+use tracing_subscriber::{EnvFilter, Registry, reload};
+
+use crate::error::{TelemetryError, TelemetryResult};
+use crate::logging::BoxedLayer;
+use crate::otlp::METRICS_TARGET;
+
+/// The subscriber the output filter sits on.
+pub(crate) type OutputSubscriber = Layered<Vec<BoxedLayer>, Registry>;
+
+/// The reloadable filter of log output.
+pub(crate) type OutputFilter = reload::Layer<EnvFilter, OutputSubscriber>;
+
+/// The filter in effect and the handles that apply a new one.
+struct LiveFilter {
+    level: String,
+    directives: Vec<String>,
+    output: reload::Handle<EnvFilter, OutputSubscriber>,
+    capture: Option<reload::Handle<EnvFilter, Registry>>,
+}
+
+static LIVE: Mutex<Option<LiveFilter>> = Mutex::new(None);
+
+/// Build the log output filter from a level and directives. Metric events
+/// are for the exporter, not the log.
+pub(crate) fn log_filter(level: &str, directives: &[String]) -> TelemetryResult<EnvFilter> {
+    let mut filter =
+        EnvFilter::try_new(level).map_err(|e| TelemetryError::ConfigError(e.to_string()))?;
+    for directive in directives {
+        filter = filter.add_directive(parse_directive(directive)?);
+    }
+    Ok(filter.add_directive(parse_directive(&format!("{METRICS_TARGET}=off"))?))
+}
+
+fn parse_directive(directive: &str) -> TelemetryResult<Directive> {
+    directive
+        .parse()
+        .map_err(|e: tracing_subscriber::filter::ParseError| {
+            TelemetryError::ConfigError(format!("invalid directive '{directive}': {e}"))
+        })
+}
+
+/// Remember the handles of the filters installed by `setup_logging`.
+pub(crate) fn install(
+    level: &str,
+    directives: &[String],
+    output: reload::Handle<EnvFilter, OutputSubscriber>,
+    capture: Option<reload::Handle<EnvFilter, Registry>>,
+) {
+    if let Ok(mut live) = LIVE.lock() {
+        *live = Some(LiveFilter {
+            level: level.to_string(),
+            directives: directives.to_vec(),
+            output,
+            capture,
+        });
+    }
+}
+
+/// Change the log filter of the running process.
+///
+/// `directive` is a comma-separated list in `RUST_LOG` syntax. A bare level
+/// (`debug`) replaces the base level; a target directive
+/// (`astrid_mcp=trace`) is added, replacing any earlier directive for the
+/// same target. Returns the resulting filter.
+///
+/// # Errors
+///
+/// Returns an error if `directive` does not parse, in which case the
+/// current filter is kept, or if [`setup_logging`](crate::setup_logging)
+/// has not run.
+pub fn set_level(directive: &str) -> TelemetryResult<String> {
+    update(|level, directives| {
+        for part in directive
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+        {
+            if part.parse::<LevelFilter>().is_ok() {
+                *level = part.to_string();
+            } else {
+                parse_directive(part)?;
+                let target = directive_target(part);
+                directives.retain(|d| directive_target(d) != target);
+                directives.push(part.to_string());
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Replace the log filter with `level` and `directives`, such as after the
+/// config file changed. Returns the resulting filter.
+///
+/// # Errors
+///
+/// Returns an error if the filter does not parse, in which case the current
+/// filter is kept, or if [`setup_logging`](crate::setup_logging) has not
+/// run.
+pub fn reload_log_filter(level: &str, directives: &[String]) -> TelemetryResult<String> {
+    update(|current_level, current_directives| {
+        level.clone_into(current_level);
+        directives.clone_into(current_directives);
+        Ok(())
+    })
+}
+
+/// The log filter currently in effect, if logging is set up.
+#[must_use]
+pub fn current_log_filter() -> Option<String> {
+    let live = LIVE.lock().ok()?;
+    live.as_ref()
+        .map(|live| describe(&live.level, &live.directives))
+}
+
+/// Apply `change` to a copy of the live level and directives and, if the
+/// result builds, install it.
+fn update(
+    change: impl FnOnce(&mut String, &mut Vec<String>) -> TelemetryResult<()>,
+) -> TelemetryResult<String> {
+    let mut guard = LIVE
+        .lock()
+        .map_err(|_| TelemetryError::InitError("log filter lock poisoned".to_string()))?;
+    let live = guard
+        .as_mut()
+        .ok_or_else(|| TelemetryError::InitError("logging is not set up".to_string()))?;
+
+    let mut level = live.level.clone();
+    let mut directives = live.directives.clone();
+    change(&mut level, &mut directives)?;
+    let output = log_filter(&level, &directives)?;
+    let capture = log_filter(&level, &directives)?;
+
+    live.output
+        .reload(output)
+        .map_err(|e| TelemetryError::InitError(e.to_string()))?;
+    if let Some(handle) = &live.capture {
+        handle
+            .reload(capture)
+            .map_err(|e| TelemetryError::InitError(e.to_string()))?;
+    }
+    live.level = level;
+    live.directives = directives;
+    Ok(describe(&live.level, &live.directives))
+}
+
+/// The target a directive applies to.
+fn directive_target(directive: &str) -> &str {
+    directive
+        .split_once('=')
+        .map_or(directive, |(target, _)| target)
+}
+
+fn describe(level: &str, directives: &[String]) -> String {
+    std::iter::once(level)
+        .chain(directives.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_filter_rejects_bad_directives() {
+        assert!(log_filter("info", &["astrid_mcp=trace".to_string()]).is_ok());
+        assert!(log_filter("info", &["[invalid=syntax".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_directive_target() {
+        assert_eq!(directive_target("astrid_mcp=trace"), "astrid_mcp");
+        assert_eq!(
+            directive_target("astrid_mcp[tool]=debug"),
+            "astrid_mcp[tool]"
+        );
+        assert_eq!(directive_target("hyper"), "hyper");
+    }
+}
@@ -4,13 +4,21 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_subscriber::{
-    EnvFilter,
+    EnvFilter, Layer, Registry,
     fmt::{self, format::FmtSpan},
     layer::SubscriberExt,
+    reload,
     util::SubscriberInitExt,
 };
 
 use crate::error::{TelemetryError, TelemetryResult};
+use crate::live_filter::{self, OutputFilter};
+use crate::otlp::OtlpConfig;
+use crate::redact::{self, RedactEvent, RedactionMode};
+use crate::session_logs::{self, SessionCaptureConfig};
+
+/// A layer installed beneath the formatting layer, such as OTLP export.
+pub(crate) type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
 
 /// Helper to convert init errors to our error type.
 fn init_err<E: std::fmt::Display>(e: E) -> TelemetryError {
@@ -128,6 +136,15 @@ pub struct LogConfig {
     /// Directive overrides (e.g., `astrid_mcp=debug`).
     #[serde(default)]
     pub directives: Vec<String>,
+    /// OTLP export of spans and metrics (requires the `otlp` feature).
+    #[serde(default)]
+    pub otlp: Option<OtlpConfig>,
+    /// Redaction of sensitive values in log output.
+    #[serde(default)]
+    pub redaction: RedactionMode,
+    /// Per-session capture of log lines.
+    #[serde(default)]
+    pub session_capture: SessionCaptureConfig,
 }
 
 fn default_level() -> String {
@@ -152,6 +169,9 @@ impl Default for LogConfig {
             span_events: false,
             ansi: true,
             directives: Vec::new(),
+            otlp: None,
+            redaction: RedactionMode::default(),
+            session_capture: SessionCaptureConfig::default(),
         }
     }
 }
@@ -246,7 +266,29 @@ impl LogConfig {
         self
     }
 
+    /// Export spans and metrics over OTLP.
+    #[must_use]
+    pub fn with_otlp(mut self, otlp: OtlpConfig) -> Self {
+        self.otlp = Some(otlp);
+        self
+    }
+
+    /// Set how much of each log event is redacted.
+    #[must_use]
+    pub fn redaction(mut self, mode: RedactionMode) -> Self {
+        self.redaction = mode;
+        self
+    }
+
+    /// Set the limits of per-session log capture.
+    #[must_use]
+    pub fn with_session_capture(mut self, capture: SessionCaptureConfig) -> Self {
+        self.session_capture = capture;
+        self
+    }
+
     /// Build the env filter from config.
+    #[cfg(any(feature = "otlp", test))]
     fn build_filter(&self) -> TelemetryResult<EnvFilter> {
         let mut filter = EnvFilter::try_new(&self.level)
             .map_err(|e| TelemetryError::ConfigError(e.to_string()))?;
@@ -262,6 +304,11 @@ impl LogConfig {
         Ok(filter)
     }
 
+    /// The filter of log output.
+    fn log_filter(&self) -> TelemetryResult<EnvFilter> {
+        live_filter::log_filter(&self.level, &self.directives)
+    }
+
     /// Get span events configuration.
     fn span_events(&self) -> FmtSpan {
         if self.span_events {
@@ -277,33 +324,48 @@ impl LogConfig {
 /// # Errors
 ///
 /// Returns an error if the configuration is invalid or logging cannot be initialized.
+///
+/// The level and directives can be changed afterwards with
+/// [`set_level`](crate::set_level).
 pub fn setup_logging(config: &LogConfig) -> TelemetryResult<()> {
-    let filter = config.build_filter()?;
+    redact::set_mode(config.redaction);
+    let (filter, output_handle) = reload::Layer::new(config.log_filter()?);
+    let mut layers: Vec<BoxedLayer> = otel_layer(config)?.into_iter().collect();
+    let capture_handle = if config.session_capture.max_entries > 0 {
+        let (capture_filter, handle) = reload::Layer::new(config.log_filter()?);
+        let capture = session_logs::install(config.session_capture)
+            .layer()
+            .with_filter(capture_filter);
+        layers.push(Box::new(capture));
+        Some(handle)
+    } else {
+        None
+    };
 
     match (&config.target, config.format) {
         (LogTarget::Stdout, LogFormat::Json) => {
-            setup_json_logging(filter, config, std::io::stdout)?;
+            setup_json_logging(filter, config, std::io::stdout, layers)?;
         },
         (LogTarget::Stdout, LogFormat::Pretty) => {
-            setup_pretty_logging(filter, config, std::io::stdout)?;
+            setup_pretty_logging(filter, config, std::io::stdout, layers)?;
         },
         (LogTarget::Stdout, LogFormat::Compact) => {
-            setup_compact_logging(filter, config, std::io::stdout)?;
+            setup_compact_logging(filter, config, std::io::stdout, layers)?;
         },
         (LogTarget::Stdout, LogFormat::Full) => {
-            setup_full_logging(filter, config, std::io::stdout)?;
+            setup_full_logging(filter, config, std::io::stdout, layers)?;
         },
         (LogTarget::Stderr, LogFormat::Json) => {
-            setup_json_logging(filter, config, std::io::stderr)?;
+            setup_json_logging(filter, config, std::io::stderr, layers)?;
         },
         (LogTarget::Stderr, LogFormat::Pretty) => {
-            setup_pretty_logging(filter, config, std::io::stderr)?;
+            setup_pretty_logging(filter, config, std::io::stderr, layers)?;
         },
         (LogTarget::Stderr, LogFormat::Compact) => {
-            setup_compact_logging(filter, config, std::io::stderr)?;
+            setup_compact_logging(filter, config, std::io::stderr, layers)?;
         },
         (LogTarget::Stderr, LogFormat::Full) => {
-            setup_full_logging(filter, config, std::io::stderr)?;
+            setup_full_logging(filter, config, std::io::stderr, layers)?;
         },
         (LogTarget::File(dir), format) => {
             // Create the directory if it doesn't exist
@@ -339,18 +401,53 @@ pub fn setup_logging(config: &LogConfig) -> TelemetryResult<()> {
             file_config.ansi = false;
 
             match format {
-                LogFormat::Json => setup_json_logging(filter, &file_config, appender)?,
-                LogFormat::Pretty => setup_pretty_logging(filter, &file_config, appender)?,
-                LogFormat::Compact => setup_compact_logging(filter, &file_config, appender)?,
-                LogFormat::Full => setup_full_logging(filter, &file_config, appender)?,
+                LogFormat::Json => setup_json_logging(filter, &file_config, appender, layers)?,
+                LogFormat::Pretty => setup_pretty_logging(filter, &file_config, appender, layers)?,
+                LogFormat::Compact => {
+                    setup_compact_logging(filter, &file_config, appender, layers)?;
+                },
+                LogFormat::Full => setup_full_logging(filter, &file_config, appender, layers)?,
             }
         },
     }
 
+    live_filter::install(
+        &config.level,
+        &config.directives,
+        output_handle,
+        capture_handle,
+    );
+
+    #[cfg(not(feature = "otlp"))]
+    if config.otlp.is_some() {
+        tracing::warn!("OTLP export is configured but this build lacks the `otlp` feature");
+    }
+
     Ok(())
 }
 
-fn setup_json_logging<W>(filter: EnvFilter, config: &LogConfig, writer: W) -> TelemetryResult<()>
+/// Build the OTLP export layer if export is configured and supported.
+#[cfg(feature = "otlp")]
+fn otel_layer(config: &LogConfig) -> TelemetryResult<Option<BoxedLayer>> {
+    config
+        .otlp
+        .as_ref()
+        .map(|otlp| crate::otlp::layer(otlp, config.build_filter()?))
+        .transpose()
+}
+
+#[cfg(not(feature = "otlp"))]
+#[expect(clippy::unnecessary_wraps)]
+fn otel_layer(_config: &LogConfig) -> TelemetryResult<Option<BoxedLayer>> {
+    Ok(None)
+}
+
+fn setup_json_logging<W>(
+    filter: OutputFilter,
+    config: &LogConfig,
+    writer: W,
+    layers: Vec<BoxedLayer>,
+) -> TelemetryResult<()>
 where
     W: for<'a> tracing_subscriber::fmt::MakeWriter<'a> + Send + Sync + 'static,
 {
@@ -365,20 +462,30 @@ where
 
     if config.timestamps {
         tracing_subscriber::registry()
-            .with(filter)
-            .with(layer)
+            .with(layers)
+            .with(layer.map_event_format(RedactEvent::new).with_filter(filter))
             .try_init()
             .map_err(init_err)
     } else {
         tracing_subscriber::registry()
-            .with(filter)
-            .with(layer.without_time())
+            .with(layers)
+            .with(
+                layer
+                    .without_time()
+                    .map_event_format(RedactEvent::new)
+                    .with_filter(filter),
+            )
             .try_init()
             .map_err(init_err)
     }
 }
 
-fn setup_pretty_logging<W>(filter: EnvFilter, config: &LogConfig, writer: W) -> TelemetryResult<()>
+fn setup_pretty_logging<W>(
+    filter: OutputFilter,
+    config: &LogConfig,
+    writer: W,
+    layers: Vec<BoxedLayer>,
+) -> TelemetryResult<()>
 where
     W: for<'a> tracing_subscriber::fmt::MakeWriter<'a> + Send + Sync + 'static,
 {
@@ -394,20 +501,30 @@ where
 
     if config.timestamps {
         tracing_subscriber::registry()
-            .with(filter)
-            .with(layer)
+            .with(layers)
+            .with(layer.map_event_format(RedactEvent::new).with_filter(filter))
             .try_init()
             .map_err(init_err)
     } else {
         tracing_subscriber::registry()
-            .with(filter)
-            .with(layer.without_time())
+            .with(layers)
+            .with(
+                layer
+                    .without_time()
+                    .map_event_format(RedactEvent::new)
+                    .with_filter(filter),
+            )
             .try_init()
             .map_err(init_err)
     }
 }
 
-fn setup_compact_logging<W>(filter: EnvFilter, config: &LogConfig, writer: W) -> TelemetryResult<()>
+fn setup_compact_logging<W>(
+    filter: OutputFilter,
+    config: &LogConfig,
+    writer: W,
+    layers: Vec<BoxedLayer>,
+) -> TelemetryResult<()>
 where
     W: for<'a> tracing_subscriber::fmt::MakeWriter<'a> + Send + Sync + 'static,
 {
@@ -423,20 +540,30 @@ where
 
     if config.timestamps {
         tracing_subscriber::registry()
-            .with(filter)
-            .with(layer)
+            .with(layers)
+            .with(layer.map_event_format(RedactEvent::new).with_filter(filter))
             .try_init()
             .map_err(init_err)
     } else {
         tracing_subscriber::registry()
-            .with(filter)
-            .with(layer.without_time())
+            .with(layers)
+            .with(
+                layer
+                    .without_time()
+                    .map_event_format(RedactEvent::new)
+                    .with_filter(filter),
+            )
             .try_init()
             .map_err(init_err)
     }
 }
 
-fn setup_full_logging<W>(filter: EnvFilter, config: &LogConfig, writer: W) -> TelemetryResult<()>
+fn setup_full_logging<W>(
+    filter: OutputFilter,
+    config: &LogConfig,
+    writer: W,
+    layers: Vec<BoxedLayer>,
+) -> TelemetryResult<()>
 where
     W: for<'a> tracing_subscriber::fmt::MakeWriter<'a> + Send + Sync + 'static,
 {
@@ -451,14 +578,19 @@ where
 
     if config.timestamps {
         tracing_subscriber::registry()
-            .with(filter)
-            .with(layer)
+            .with(layers)
+            .with(layer.map_event_format(RedactEvent::new).with_filter(filter))
             .try_init()
             .map_err(init_err)
     } else {
         tracing_subscriber::registry()
-            .with(filter)
-            .with(layer.without_time())
+            .with(layers)
+            .with(
+                layer
+                    .without_time()
+                    .map_event_format(RedactEvent::new)
+                    .with_filter(filter),
+            )
             .try_init()
             .map_err(init_err)
     }
@@ -0,0 +1,306 @@
+//! OpenTelemetry export over OTLP/HTTP.
+//!
+//! When [`LogConfig::otlp`](crate::LogConfig::otlp) is set and the crate is
+//! built with the `otlp` feature, [`setup_logging`](crate::setup_logging)
+//! adds a layer that exports every span (including [`RequestContext`]
+//! spans) to `{endpoint}/v1/traces`.
+//!
+//! Metrics are tracing events with the target [`METRICS_TARGET`]. Fields
+//! prefixed `monotonic_counter.` become counters and the remaining fields
+//! become attributes. The counter must come after the attributes, or the
+//! `tracing` macros cannot parse the event:
+//!
+//! ```rust
+//! tracing::info!(
+//!     target: astrid_telemetry::METRICS_TARGET,
+//!     tool = "read_file",
+//!     outcome = "success",
+//!     monotonic_counter.astrid.tool_calls = 1_u64
+//! );
+//! ```
+//!
+//! They are exported to `{endpoint}/v1/metrics` and never written to the log.
+//! Call [`shutdown_telemetry`] before exit so buffered data is flushed.
+//!
+//! [`RequestContext`]: crate::RequestContext
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::TelemetryResult;
+
+/// Target of tracing events that are recorded as metrics.
+pub const METRICS_TARGET: &str = "astrid::metrics";
+
+/// OTLP export configuration.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OtlpConfig {
+    /// Collector base URL; `/v1/traces` and `/v1/metrics` are appended.
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+    /// Value of the `service.name` resource attribute.
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+    /// Extra resource attributes.
+    #[serde(default)]
+    pub resource_attributes: HashMap<String, String>,
+    /// HTTP headers sent with every export.
+    #[serde(default, skip_serializing)]
+    pub headers: HashMap<String, String>,
+    /// Whether to export metrics as well as spans.
+    #[serde(default = "default_true")]
+    pub metrics: bool,
+}
+
+fn default_endpoint() -> String {
+    "http://localhost:4318".to_string()
+}
+
+fn default_service_name() -> String {
+    "astrid".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: default_endpoint(),
+            service_name: default_service_name(),
+            resource_attributes: HashMap::new(),
+            headers: HashMap::new(),
+            metrics: true,
+        }
+    }
+}
+
+impl std::fmt::Debug for OtlpConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redacted_headers: HashMap<&String, &str> =
+            self.headers.keys().map(|k| (k, "***")).collect();
+        f.debug_struct("OtlpConfig")
+            .field("endpoint", &self.endpoint)
+            .field("service_name", &self.service_name)
+            .field("resource_attributes", &self.resource_attributes)
+            .field("headers", &redacted_headers)
+            .field("metrics", &self.metrics)
+            .finish()
+    }
+}
+
+impl OtlpConfig {
+    /// Create a config exporting to `endpoint`.
+    #[must_use]
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the service name.
+    #[must_use]
+    pub fn with_service_name(mut self, service_name: impl Into<String>) -> Self {
+        self.service_name = service_name.into();
+        self
+    }
+
+    /// Add a resource attribute.
+    #[must_use]
+    pub fn with_resource_attribute(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.resource_attributes.insert(key.into(), value.into());
+        self
+    }
+
+    /// Add an HTTP header sent with every export.
+    #[must_use]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Export spans only.
+    #[must_use]
+    pub fn without_metrics(mut self) -> Self {
+        self.metrics = false;
+        self
+    }
+
+    /// The URL a signal (`traces` or `metrics`) is exported to.
+    #[cfg(feature = "otlp")]
+    fn signal_url(&self, signal: &str) -> String {
+        format!("{}/v1/{signal}", self.endpoint.trim_end_matches('/'))
+    }
+}
+
+#[cfg(feature = "otlp")]
+mod export {
+    use std::sync::Mutex;
+
+    use opentelemetry::KeyValue;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::{MetricExporter, SpanExporter, WithExportConfig, WithHttpConfig};
+    use opentelemetry_sdk::Resource;
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use tracing::Level;
+    use tracing_subscriber::Layer;
+    use tracing_subscriber::filter::{EnvFilter, Targets};
+
+    use super::{METRICS_TARGET, OtlpConfig};
+    use crate::error::{TelemetryError, TelemetryResult};
+    use crate::logging::BoxedLayer;
+
+    /// Targets never exported as spans. The exporter's own HTTP client
+    /// would otherwise export the spans of each export, forever.
+    const EXPORTER_TARGETS: &[&str] = &[
+        "opentelemetry",
+        "opentelemetry_sdk",
+        "opentelemetry_otlp",
+        "opentelemetry_http",
+        "reqwest",
+        "hyper",
+        "hyper_util",
+        "h2",
+    ];
+
+    /// Providers to flush on shutdown.
+    static PROVIDERS: Mutex<Option<(SdkTracerProvider, Option<SdkMeterProvider>)>> =
+        Mutex::new(None);
+
+    fn init_err<E: std::fmt::Display>(e: E) -> TelemetryError {
+        TelemetryError::InitError(format!("OTLP exporter: {e}"))
+    }
+
+    /// Build the export layer. `filter` selects the spans to export.
+    pub(crate) fn layer(config: &OtlpConfig, mut filter: EnvFilter) -> TelemetryResult<BoxedLayer> {
+        for target in EXPORTER_TARGETS.iter().chain(&[METRICS_TARGET]) {
+            filter = filter.add_directive(format!("{target}=off").parse().map_err(init_err)?);
+        }
+
+        let resource = Resource::builder()
+            .with_service_name(config.service_name.clone())
+            .with_attributes(
+                config
+                    .resource_attributes
+                    .iter()
+                    .map(|(k, v)| KeyValue::new(k.clone(), v.clone())),
+            )
+            .build();
+
+        let span_exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(config.signal_url("traces"))
+            .with_headers(config.headers.clone())
+            .build()
+            .map_err(init_err)?;
+        let tracer_provider = SdkTracerProvider::builder()
+            .with_batch_exporter(span_exporter)
+            .with_resource(resource.clone())
+            .build();
+        let traces = tracing_opentelemetry::layer()
+            .with_tracer(tracer_provider.tracer("astrid"))
+            .with_filter(filter);
+
+        let (layer, meter_provider): (BoxedLayer, _) = if config.metrics {
+            let metric_exporter = MetricExporter::builder()
+                .with_http()
+                .with_endpoint(config.signal_url("metrics"))
+                .with_headers(config.headers.clone())
+                .build()
+                .map_err(init_err)?;
+            let meter_provider = SdkMeterProvider::builder()
+                .with_periodic_exporter(metric_exporter)
+                .with_resource(resource)
+                .build();
+            let metrics = tracing_opentelemetry::MetricsLayer::new(meter_provider.clone())
+                .with_filter(Targets::new().with_target(METRICS_TARGET, Level::TRACE));
+            (Box::new(traces.and_then(metrics)), Some(meter_provider))
+        } else {
+            (Box::new(traces), None)
+        };
+
+        if let Ok(mut providers) = PROVIDERS.lock() {
+            *providers = Some((tracer_provider, meter_provider));
+        }
+        Ok(layer)
+    }
+
+    pub(crate) fn shutdown() -> TelemetryResult<()> {
+        let Some((tracer_provider, meter_provider)) = PROVIDERS
+            .lock()
+            .ok()
+            .and_then(|mut providers| providers.take())
+        else {
+            return Ok(());
+        };
+        // Shut both down even if the first fails.
+        let traces = tracer_provider.shutdown().map_err(init_err);
+        if let Some(meter_provider) = meter_provider {
+            meter_provider.shutdown().map_err(init_err)?;
+        }
+        traces
+    }
+}
+
+#[cfg(feature = "otlp")]
+pub(crate) use export::layer;
+
+/// Flush and stop OTLP export.
+///
+/// Blocks until buffered spans and metrics are exported or the exporter
+/// times out. Does nothing if export was never set up.
+///
+/// # Errors
+///
+/// Returns an error if the final export fails.
+pub fn shutdown_telemetry() -> TelemetryResult<()> {
+    #[cfg(feature = "otlp")]
+    export::shutdown()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_otlp_config_builder() {
+        let config = OtlpConfig::new("http://collector:4318/")
+            .with_service_name("astrid-daemon")
+            .with_resource_attribute("deployment.environment", "test")
+            .with_header("authorization", "Bearer secret")
+            .without_metrics();
+
+        assert_eq!(config.service_name, "astrid-daemon");
+        assert_eq!(
+            config.resource_attributes.get("deployment.environment"),
+            Some(&"test".to_string())
+        );
+        assert!(!config.metrics);
+        assert!(!format!("{config:?}").contains("secret"));
+        #[cfg(feature = "otlp")]
+        assert_eq!(
+            config.signal_url("traces"),
+            "http://collector:4318/v1/traces"
+        );
+    }
+
+    #[test]
+    fn test_otlp_config_never_serializes_headers() {
+        let config = OtlpConfig::default().with_header("authorization", "Bearer secret");
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("secret"));
+
+        let parsed: OtlpConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(parsed.endpoint, "http://localhost:4318");
+        assert!(parsed.metrics);
+    }
+}
@@ -31,10 +31,13 @@
 pub use crate::{TelemetryError, TelemetryResult};
 
 // Logging configuration
-pub use crate::{LogConfig, LogFormat, LogTarget};
+pub use crate::{LogConfig, LogFormat, LogTarget, OtlpConfig, RedactionMode, SessionCaptureConfig};
+
+// Redaction
+pub use crate::Sensitive;
 
 // Setup functions
-pub use crate::setup_logging;
+pub use crate::{session_logs, set_level, setup_logging, shutdown_telemetry};
 
 // Request context
 pub use crate::RequestContext;
@@ -0,0 +1,419 @@
+//! Redaction of sensitive values in log output.
+//!
+//! Two mechanisms keep prompts, file contents and credentials out of logs:
+//!
+//! - [`Sensitive`] wraps a value so that it formats as `[redacted:<len>]`.
+//!   Use it at call sites that log prompts or tool output; it also keeps the
+//!   value out of OTLP span events.
+//! - Fields of log events named after a known secret (`api_key`,
+//!   `authorization`, `content`, ...) are replaced the same way, whatever
+//!   their type. In [`RedactionMode::Strict`], fields naming a path (`path`,
+//!   `cwd`, `*_path`, ...) are also replaced by a short hash, so equal paths
+//!   can still be matched up.
+//!
+//! Both follow the [`RedactionMode`] installed by
+//! [`setup_logging`](crate::setup_logging); it is
+//! [`Standard`](RedactionMode::Standard) until then.
+
+use std::fmt::{self, Write as _};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+use tracing::field::{DisplayValue, Field, Value, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Field names whose values are always redacted.
+const SECRET_FIELDS: &[&str] = &[
+    "api_key",
+    "authorization",
+    "content",
+    "password",
+    "prompt",
+    "secret",
+];
+
+/// Field names hashed in [`RedactionMode::Strict`], besides `*_path`,
+/// `*_dir` and `*_file`.
+const PATH_FIELDS: &[&str] = &["path", "file", "dir", "cwd"];
+
+/// The most fields a tracing callsite can declare.
+const MAX_FIELDS: usize = 32;
+
+/// How much of a log event is redacted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionMode {
+    /// Log everything as is, including [`Sensitive`] values.
+    Off,
+    /// Redact [`Sensitive`] values and known secret fields.
+    #[default]
+    Standard,
+    /// Also replace path fields with a hash.
+    Strict,
+}
+
+impl RedactionMode {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Off,
+            2 => Self::Strict,
+            _ => Self::Standard,
+        }
+    }
+}
+
+static MODE: AtomicU8 = AtomicU8::new(RedactionMode::Standard as u8);
+
+/// Install the process-wide redaction mode.
+pub(crate) fn set_mode(mode: RedactionMode) {
+    MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+fn mode() -> RedactionMode {
+    RedactionMode::from_u8(MODE.load(Ordering::Relaxed))
+}
+
+/// A value that is redacted when formatted.
+///
+/// Both `Display` and `Debug` print `[redacted:<len>]`, where `len` is the
+/// length in bytes of the value's `Display` output, unless redaction is
+/// [`Off`](RedactionMode::Off).
+///
+/// ```
+/// use astrid_telemetry::Sensitive;
+///
+/// let prompt = "summarize ~/notes.md";
+/// tracing::debug!(prompt = %Sensitive(prompt), "Sending prompt");
+/// assert_eq!(Sensitive(prompt).to_string(), "[redacted:20]");
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sensitive<T>(pub T);
+
+impl<T: fmt::Display> fmt::Display for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if mode() == RedactionMode::Off {
+            return self.0.fmt(f);
+        }
+        let mut len = ByteCount(0);
+        write!(len, "{}", self.0)?;
+        write!(f, "[redacted:{}]", len.0)
+    }
+}
+
+impl<T: fmt::Display> fmt::Debug for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// Counts formatted bytes without storing them.
+struct ByteCount(usize);
+
+impl fmt::Write for ByteCount {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 = self.0.saturating_add(s.len());
+        Ok(())
+    }
+}
+
+/// What a field's name says about its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Secret,
+    Path,
+}
+
+fn classify(name: &str, mode: RedactionMode) -> Option<FieldKind> {
+    let name = name.rsplit('.').next().unwrap_or(name).to_ascii_lowercase();
+    if mode == RedactionMode::Off {
+        None
+    } else if SECRET_FIELDS.contains(&name.as_str()) {
+        Some(FieldKind::Secret)
+    } else if mode == RedactionMode::Strict
+        && (PATH_FIELDS.contains(&name.as_str())
+            || ["_path", "_dir", "_file"].iter().any(|s| name.ends_with(s)))
+    {
+        Some(FieldKind::Path)
+    } else {
+        None
+    }
+}
+
+/// The replacement for a field value, if its name marks it as sensitive.
+pub(crate) fn redact_field(name: &str, rendered: &str) -> Option<String> {
+    classify(name, mode()).map(|kind| replacement(kind, rendered))
+}
+
+/// The replacement for a field value rendered as `rendered`.
+fn replacement(kind: FieldKind, rendered: &str) -> String {
+    // `?field` renders strings and paths quoted.
+    let value = rendered
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(rendered);
+    if value.starts_with("[redacted:") {
+        // Already a `Sensitive` value.
+        return value.to_string();
+    }
+    match kind {
+        FieldKind::Secret => format!("[redacted:{}]", value.len()),
+        FieldKind::Path => {
+            let hash = blake3::hash(value.as_bytes()).to_hex();
+            format!("[path:{}]", &hash[..16])
+        },
+    }
+}
+
+/// An event field value copied out of the original event.
+enum Captured {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bool(bool),
+    Str(String),
+    Fmt(DisplayValue<String>),
+}
+
+impl Captured {
+    fn as_value(&self) -> &dyn Value {
+        match self {
+            Self::I64(v) => v,
+            Self::U64(v) => v,
+            Self::F64(v) => v,
+            Self::Bool(v) => v,
+            Self::Str(v) => v,
+            Self::Fmt(v) => v,
+        }
+    }
+}
+
+/// Copies an event's fields, replacing sensitive ones.
+struct Scrubber {
+    mode: RedactionMode,
+    fields: Vec<(Field, Captured)>,
+}
+
+impl Visit for Scrubber {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.fields.push((field.clone(), Captured::I64(value)));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.fields.push((field.clone(), Captured::U64(value)));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.fields.push((field.clone(), Captured::F64(value)));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.fields.push((field.clone(), Captured::Bool(value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let value = match classify(field.name(), self.mode) {
+            Some(kind) => replacement(kind, value),
+            None => value.to_string(),
+        };
+        self.fields.push((field.clone(), Captured::Str(value)));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let rendered = format!("{value:?}");
+        let captured = match classify(field.name(), self.mode) {
+            Some(kind) => Captured::Str(replacement(kind, &rendered)),
+            None => Captured::Fmt(tracing::field::display(rendered)),
+        };
+        self.fields.push((field.clone(), captured));
+    }
+}
+
+/// Wraps an event formatter so that sensitive fields are redacted.
+///
+/// The event is rebuilt with the redacted values before the inner formatter
+/// sees it, so every output format is covered the same way.
+pub(crate) struct RedactEvent<F> {
+    inner: F,
+}
+
+impl<F> RedactEvent<F> {
+    pub(crate) fn new(inner: F) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S, N, F> FormatEvent<S, N> for RedactEvent<F>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'w> FormatFields<'w> + 'static,
+    F: FormatEvent<S, N>,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mode = mode();
+        let metadata = event.metadata();
+        let fieldset = metadata.fields();
+        let Some(first) = fieldset.iter().next() else {
+            return self.inner.format_event(ctx, writer, event);
+        };
+        if !fieldset.iter().any(|f| classify(f.name(), mode).is_some()) {
+            return self.inner.format_event(ctx, writer, event);
+        }
+
+        let mut scrubber = Scrubber {
+            mode,
+            fields: Vec::with_capacity(fieldset.len()),
+        };
+        event.record(&mut scrubber);
+
+        // A value set is built from a fixed-size array; unused slots repeat
+        // a field with no value, which visitors never see.
+        let mut values: [(&Field, Option<&dyn Value>); MAX_FIELDS] = [(&first, None); MAX_FIELDS];
+        for (slot, (field, value)) in values.iter_mut().zip(&scrubber.fields) {
+            *slot = (field, Some(value.as_value()));
+        }
+        let values = fieldset.value_set(&values);
+        let redacted = if event.is_contextual() {
+            Event::new(metadata, &values)
+        } else {
+            Event::new_child_of(event.parent().cloned(), metadata, &values)
+        };
+        self.inner.format_event(ctx, writer, &redacted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Buffer {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    impl Buffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn log_secrets() {
+        tracing::info!(
+            api_key = "sk-live-1234",
+            content = ?"file body",
+            count = 3,
+            prompt = %Sensitive("a private prompt"),
+            "request sent"
+        );
+    }
+
+    #[test]
+    fn test_sensitive_formats_length_only() {
+        assert_eq!(Sensitive("hunter2").to_string(), "[redacted:7]");
+        assert_eq!(format!("{:?}", Sensitive(1234)), "[redacted:4]");
+    }
+
+    #[test]
+    fn test_classify_fields() {
+        assert_eq!(
+            classify("api_key", RedactionMode::Standard),
+            Some(FieldKind::Secret)
+        );
+        assert_eq!(
+            classify("request.Authorization", RedactionMode::Standard),
+            Some(FieldKind::Secret)
+        );
+        assert_eq!(classify("content", RedactionMode::Off), None);
+        assert_eq!(classify("log_path", RedactionMode::Standard), None);
+        assert_eq!(
+            classify("log_path", RedactionMode::Strict),
+            Some(FieldKind::Path)
+        );
+        assert_eq!(classify("message", RedactionMode::Strict), None);
+    }
+
+    #[test]
+    fn test_path_hash_is_stable_and_unquoted() {
+        let quoted = replacement(FieldKind::Path, "\"/home/me/notes.md\"");
+        let bare = replacement(FieldKind::Path, "/home/me/notes.md");
+        assert_eq!(quoted, bare);
+        assert!(bare.starts_with("[path:"));
+        assert!(!bare.contains("notes"));
+        assert_eq!(
+            replacement(FieldKind::Secret, "[redacted:9]"),
+            "[redacted:9]"
+        );
+    }
+
+    #[test]
+    fn test_redacts_fields_in_text_and_json_formats() {
+        let compact = Buffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .compact()
+            .with_ansi(false)
+            .with_writer(compact.clone())
+            .map_event_format(RedactEvent::new)
+            .finish();
+        tracing::subscriber::with_default(subscriber, log_secrets);
+
+        let pretty = Buffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .pretty()
+            .with_ansi(false)
+            .with_writer(pretty.clone())
+            .map_event_format(RedactEvent::new)
+            .finish();
+        tracing::subscriber::with_default(subscriber, log_secrets);
+
+        let json = Buffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(json.clone())
+            .map_event_format(RedactEvent::new)
+            .finish();
+        tracing::subscriber::with_default(subscriber, log_secrets);
+
+        for output in [compact.contents(), pretty.contents(), json.contents()] {
+            assert!(output.contains("request sent"), "{output}");
+            assert!(output.contains("[redacted:12]"), "{output}");
+            assert!(output.contains("[redacted:9]"), "{output}");
+            assert!(output.contains("[redacted:16]"), "{output}");
+            assert!(output.contains('3'), "{output}");
+            for secret in ["sk-live-1234", "file body", "private prompt"] {
+                assert!(!output.contains(secret), "{secret} leaked: {output}");
+            }
+        }
+        let line: serde_json::Value = serde_json::from_str(json.contents().trim()).unwrap();
+        assert_eq!(line["fields"]["count"], 3);
+        assert_eq!(line["fields"]["api_key"], "[redacted:12]");
+    }
+}
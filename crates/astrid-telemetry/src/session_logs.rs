@@ -0,0 +1,372 @@
+//! Per-session capture of log lines.
+//!
+//! Events logged inside a span with a `session_id` field (such as
+//! [`RequestContext::span`](crate::RequestContext::span) for a context with a
+//! session) or carrying that field themselves are also kept in a bounded
+//! ring buffer for that session, so the logs of one session can be fetched
+//! without searching the whole log. Captured lines are redacted like the
+//! log output. Events without a session go only to the normal sinks.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Write as _};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::redact;
+
+/// Name of the field that tags spans and events with a session.
+pub const SESSION_FIELD: &str = "session_id";
+
+/// Sessions with captured lines kept at once; the oldest is dropped first.
+const MAX_SESSIONS: usize = 64;
+
+/// Limits of the per-session ring buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionCaptureConfig {
+    /// Lines kept per session (0 disables capture).
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+    /// Bytes of log lines kept per session.
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+}
+
+fn default_max_entries() -> usize {
+    1000
+}
+
+fn default_max_bytes() -> usize {
+    256 * 1024
+}
+
+impl Default for SessionCaptureConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: default_max_entries(),
+            max_bytes: default_max_bytes(),
+        }
+    }
+}
+
+/// Captured lines of one session, oldest first.
+#[derive(Default)]
+struct Ring {
+    lines: VecDeque<String>,
+    bytes: usize,
+}
+
+#[derive(Default)]
+struct Sessions {
+    rings: HashMap<String, Ring>,
+    /// Session IDs in the order they were first seen.
+    order: VecDeque<String>,
+}
+
+/// Ring buffers of captured log lines, keyed by session ID.
+#[derive(Clone)]
+pub(crate) struct SessionLogs {
+    config: SessionCaptureConfig,
+    sessions: Arc<Mutex<Sessions>>,
+}
+
+impl SessionLogs {
+    pub(crate) fn new(config: SessionCaptureConfig) -> Self {
+        Self {
+            config,
+            sessions: Arc::default(),
+        }
+    }
+
+    /// Captured lines of `session_id`, oldest first.
+    pub(crate) fn lines(&self, session_id: &str) -> Vec<String> {
+        self.sessions.lock().map_or_else(
+            |_| Vec::new(),
+            |sessions| {
+                sessions
+                    .rings
+                    .get(session_id)
+                    .map(|ring| ring.lines.iter().cloned().collect())
+                    .unwrap_or_default()
+            },
+        )
+    }
+
+    /// The layer that fills these buffers.
+    pub(crate) fn layer(&self) -> SessionCaptureLayer {
+        SessionCaptureLayer { logs: self.clone() }
+    }
+
+    fn push(&self, session_id: String, mut line: String) {
+        let SessionCaptureConfig {
+            max_entries,
+            max_bytes,
+        } = self.config;
+        if max_entries == 0 {
+            return;
+        }
+        if line.len() > max_bytes {
+            let mut end = max_bytes;
+            while !line.is_char_boundary(end) {
+                end = end.saturating_sub(1);
+            }
+            line.truncate(end);
+        }
+
+        let Ok(mut sessions) = self.sessions.lock() else {
+            return;
+        };
+        let Sessions { rings, order } = &mut *sessions;
+        if !rings.contains_key(&session_id) {
+            if order.len() >= MAX_SESSIONS
+                && let Some(oldest) = order.pop_front()
+            {
+                rings.remove(&oldest);
+            }
+            order.push_back(session_id.clone());
+        }
+        let ring = rings.entry(session_id).or_default();
+        ring.bytes = ring.bytes.saturating_add(line.len());
+        ring.lines.push_back(line);
+        while ring.lines.len() > max_entries || ring.bytes > max_bytes {
+            let Some(dropped) = ring.lines.pop_front() else {
+                break;
+            };
+            ring.bytes = ring.bytes.saturating_sub(dropped.len());
+        }
+    }
+}
+
+static GLOBAL: OnceLock<SessionLogs> = OnceLock::new();
+
+/// Install the process-wide buffers used by [`session_logs`].
+pub(crate) fn install(config: SessionCaptureConfig) -> SessionLogs {
+    GLOBAL.get_or_init(|| SessionLogs::new(config)).clone()
+}
+
+/// Log lines captured for `session_id`, oldest first.
+///
+/// Returns nothing if capture is disabled or
+/// [`setup_logging`](crate::setup_logging) has not run.
+#[must_use]
+pub fn session_logs(session_id: &str) -> Vec<String> {
+    GLOBAL
+        .get()
+        .map(|logs| logs.lines(session_id))
+        .unwrap_or_default()
+}
+
+/// Session tag stored in span extensions.
+struct SessionTag(String);
+
+/// Finds the `session_id` field.
+#[derive(Default)]
+struct SessionVisitor(Option<String>);
+
+impl Visit for SessionVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == SESSION_FIELD {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == SESSION_FIELD {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+/// Renders an event as `LEVEL target: message key=value ...`.
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &format_args!("{value}"));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let name = field.name();
+        if name == SESSION_FIELD {
+            return;
+        }
+        let rendered = format!("{value:?}");
+        if name == "message" {
+            self.message = rendered;
+            return;
+        }
+        let value = redact::redact_field(name, &rendered).unwrap_or(rendered);
+        let _ = write!(self.fields, " {name}={value}");
+    }
+}
+
+/// Copies session-tagged events into [`SessionLogs`].
+pub(crate) struct SessionCaptureLayer {
+    logs: SessionLogs,
+}
+
+impl<S> Layer<S> for SessionCaptureLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = SessionVisitor::default();
+        attrs.record(&mut visitor);
+        if let (Some(session), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(SessionTag(session));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let mut visitor = SessionVisitor::default();
+        values.record(&mut visitor);
+        if let (Some(session), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().replace(SessionTag(session));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = SessionVisitor::default();
+        event.record(&mut visitor);
+        let session = visitor.0.or_else(|| {
+            ctx.event_scope(event)?.find_map(|span| {
+                span.extensions()
+                    .get::<SessionTag>()
+                    .map(|tag| tag.0.clone())
+            })
+        });
+        let Some(session) = session else {
+            return;
+        };
+
+        let mut line = LineVisitor {
+            message: String::new(),
+            fields: String::new(),
+        };
+        event.record(&mut line);
+        let metadata = event.metadata();
+        self.logs.push(
+            session,
+            format!(
+                "{} {:>5} {}: {}{}",
+                chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+                metadata.level(),
+                metadata.target(),
+                line.message,
+                line.fields
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    fn capture(config: SessionCaptureConfig) -> (SessionLogs, tracing::Dispatch) {
+        let logs = SessionLogs::new(config);
+        let subscriber = tracing_subscriber::registry().with(logs.layer());
+        (logs, tracing::Dispatch::new(subscriber))
+    }
+
+    #[test]
+    fn test_concurrent_sessions_do_not_mix() {
+        let (logs, dispatch) = capture(SessionCaptureConfig::default());
+        let barrier = Arc::new(std::sync::Barrier::new(2));
+
+        let threads: Vec<_> = ["alpha", "beta"]
+            .into_iter()
+            .map(|session| {
+                let dispatch = dispatch.clone();
+                let barrier = Arc::clone(&barrier);
+                std::thread::spawn(move || {
+                    tracing::dispatcher::with_default(&dispatch, || {
+                        let span = tracing::info_span!("turn", session_id = session);
+                        let _guard = span.enter();
+                        barrier.wait();
+                        for i in 0..200 {
+                            tracing::info!(step = i, "{session} step");
+                            let nested = tracing::debug_span!("tool");
+                            nested.in_scope(|| tracing::debug!("{session} tool"));
+                        }
+                    });
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info!("no session here");
+        });
+
+        for (session, other) in [("alpha", "beta"), ("beta", "alpha")] {
+            let lines = logs.lines(session);
+            assert_eq!(lines.len(), 400);
+            assert!(lines.iter().all(|l| l.contains(session)));
+            assert!(lines.iter().all(|l| !l.contains(other)), "{lines:?}");
+        }
+        assert!(logs.lines("default").is_empty());
+    }
+
+    #[test]
+    fn test_event_field_tags_session_and_is_redacted() {
+        let (logs, dispatch) = capture(SessionCaptureConfig::default());
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::warn!(session_id = "s1", api_key = "sk-secret", "Provider failed");
+        });
+        let lines = logs.lines("s1");
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains(" WARN "), "{}", lines[0]);
+        assert!(lines[0].contains("Provider failed api_key=[redacted:9]"));
+        assert!(!lines[0].contains("sk-secret"));
+    }
+
+    #[test]
+    fn test_ring_is_bounded_by_entries_and_bytes() {
+        let (logs, dispatch) = capture(SessionCaptureConfig {
+            max_entries: 10,
+            max_bytes: 1000,
+        });
+        tracing::dispatcher::with_default(&dispatch, || {
+            for i in 0..50 {
+                tracing::info!(session_id = "s", "line {i}");
+            }
+        });
+        let lines = logs.lines("s");
+        assert_eq!(lines.len(), 10);
+        assert!(lines[0].ends_with("line 40"));
+        assert!(lines[9].ends_with("line 49"));
+
+        tracing::dispatcher::with_default(&dispatch, || {
+            tracing::info!(session_id = "s", "{}", "x".repeat(2000));
+        });
+        let lines = logs.lines("s");
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].len(), 1000);
+    }
+
+    #[test]
+    fn test_oldest_session_is_dropped_first() {
+        let (logs, dispatch) = capture(SessionCaptureConfig::default());
+        tracing::dispatcher::with_default(&dispatch, || {
+            for i in 0..=MAX_SESSIONS {
+                tracing::info!(session_id = i, "hello");
+            }
+        });
+        assert!(logs.lines("0").is_empty());
+        assert_eq!(logs.lines("1").len(), 1);
+        assert_eq!(logs.lines(&MAX_SESSIONS.to_string()).len(), 1);
+    }
+}
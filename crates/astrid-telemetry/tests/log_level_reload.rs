@@ -0,0 +1,74 @@
+//! The log level and directives change at runtime without a restart.
+
+#![allow(missing_docs)]
+
+use astrid_telemetry::{
+    LogConfig, LogFormat, current_log_filter, reload_log_filter, set_level, setup_logging,
+};
+
+fn read_log(dir: &std::path::Path) -> String {
+    let mut log = String::new();
+    for entry in std::fs::read_dir(dir).unwrap() {
+        log.push_str(&std::fs::read_to_string(entry.unwrap().path()).unwrap());
+    }
+    log
+}
+
+// One test per binary: the subscriber is process-global.
+#[test]
+fn level_changes_apply_live_and_bad_directives_are_rejected() {
+    assert!(set_level("debug").is_err(), "no filter before setup");
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = LogConfig::new("info")
+        .with_format(LogFormat::Json)
+        .with_file_logging(dir.path(), "reload");
+    setup_logging(&config).unwrap();
+
+    tracing::debug!(target: "astrid_mcp", "mcp debug 1");
+    tracing::info!(target: "astrid_mcp", "mcp info 1");
+
+    assert_eq!(
+        set_level("astrid_mcp=trace").unwrap(),
+        "info,astrid_mcp=trace"
+    );
+    tracing::trace!(target: "astrid_mcp", "mcp trace 2");
+    tracing::debug!(target: "astrid_kernel", "kernel debug 2");
+
+    // Rejected without disturbing the current filter.
+    assert!(set_level("astrid_mcp=[bad").is_err());
+    assert!(set_level("warn,astrid_mcp=loudest").is_err());
+    assert_eq!(current_log_filter().unwrap(), "info,astrid_mcp=trace");
+    tracing::trace!(target: "astrid_mcp", "mcp trace 3");
+
+    assert_eq!(
+        set_level("warn,astrid_mcp=info").unwrap(),
+        "warn,astrid_mcp=info"
+    );
+    tracing::info!(target: "astrid_kernel", "kernel info 4");
+    tracing::debug!(target: "astrid_mcp", "mcp debug 4");
+    tracing::warn!(target: "astrid_kernel", "kernel warn 4");
+
+    // Back to the config file's filter.
+    assert_eq!(reload_log_filter("info", &[]).unwrap(), "info");
+    tracing::info!(target: "astrid_kernel", "kernel info 5");
+
+    let log = read_log(dir.path());
+    for expected in [
+        "mcp info 1",
+        "mcp trace 2",
+        "mcp trace 3",
+        "kernel warn 4",
+        "kernel info 5",
+    ] {
+        assert!(log.contains(expected), "{expected} missing:\n{log}");
+    }
+    for unexpected in [
+        "mcp debug 1",
+        "kernel debug 2",
+        "kernel info 4",
+        "mcp debug 4",
+    ] {
+        assert!(!log.contains(unexpected), "{unexpected} logged:\n{log}");
+    }
+}
@@ -0,0 +1,203 @@
+//! OTLP export against an in-process collector stub.
+
+#![allow(missing_docs)]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use astrid_telemetry::{
+    LogConfig, METRICS_TARGET, OtlpConfig, RequestContext, setup_logging, shutdown_telemetry,
+};
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+use opentelemetry_proto::tonic::common::v1::{KeyValue, any_value};
+use opentelemetry_proto::tonic::metrics::v1::{metric, number_data_point};
+use prost::Message;
+
+/// One request received by the collector.
+struct Received {
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+/// A minimal OTLP/HTTP collector: accepts every POST and records it.
+struct Collector {
+    url: String,
+    received: Arc<Mutex<Vec<Received>>>,
+}
+
+impl Collector {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let sink = Arc::clone(&sink);
+                std::thread::spawn(move || serve(stream.unwrap(), &sink));
+            }
+        });
+        Self { url, received }
+    }
+
+    fn take(&self, path: &str) -> Vec<Received> {
+        let mut received = self.received.lock().unwrap();
+        let (matching, rest) = received.drain(..).partition(|r| r.path == path);
+        *received = rest;
+        matching
+    }
+}
+
+/// Serve keep-alive HTTP/1.1 requests until the client hangs up.
+fn serve(stream: TcpStream, sink: &Mutex<Vec<Received>>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut writer = stream;
+    loop {
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+            return;
+        }
+        let path = request_line
+            .split_whitespace()
+            .nth(1)
+            .unwrap_or_default()
+            .to_string();
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line.split_once(':').unwrap();
+            headers.push((name.to_ascii_lowercase(), value.trim().to_string()));
+        }
+        let length = headers
+            .iter()
+            .find(|(name, _)| name == "content-length")
+            .map_or(0, |(_, value)| value.parse().unwrap());
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        sink.lock().unwrap().push(Received {
+            path,
+            headers,
+            body,
+        });
+        writer
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .unwrap();
+    }
+}
+
+fn string_attribute<'a>(attributes: &'a [KeyValue], key: &str) -> Option<&'a str> {
+    attributes.iter().find(|kv| kv.key == key).and_then(|kv| {
+        match kv.value.as_ref()?.value.as_ref()? {
+            any_value::Value::StringValue(s) => Some(s.as_str()),
+            _ => None,
+        }
+    })
+}
+
+// One test per binary: the subscriber and the exporter are process-global.
+#[test]
+fn exports_spans_and_metrics_then_flushes_on_shutdown() {
+    let collector = Collector::start();
+    let config = LogConfig::new("info").with_otlp(
+        OtlpConfig::new(&collector.url)
+            .with_service_name("astrid-test")
+            .with_resource_attribute("deployment.environment", "ci")
+            .with_header("x-api-key", "secret"),
+    );
+    setup_logging(&config).unwrap();
+
+    let ctx = RequestContext::new("test").with_operation("handle_message");
+    {
+        let parent = ctx.span();
+        let _parent = parent.enter();
+        let child = ctx.child("tool").with_operation("call_tool").span();
+        let _child = child.enter();
+        tracing::info!(
+            target: METRICS_TARGET,
+            outcome = "success",
+            monotonic_counter.astrid.tool_calls = 1_u64
+        );
+        tracing::info!(
+            target: METRICS_TARGET,
+            outcome = "success",
+            monotonic_counter.astrid.tool_calls = 2_u64
+        );
+    }
+    // Nothing is exported until the batch fills or the exporter is flushed.
+    shutdown_telemetry().unwrap();
+
+    // Spans.
+    let traces = collector.take("/v1/traces");
+    assert!(!traces.is_empty(), "no spans exported");
+    assert!(
+        traces[0]
+            .headers
+            .iter()
+            .any(|(name, value)| name == "x-api-key" && value == "secret")
+    );
+    let mut spans = Vec::new();
+    for request in &traces {
+        let export = ExportTraceServiceRequest::decode(request.body.as_slice()).unwrap();
+        for resource_spans in export.resource_spans {
+            let resource = resource_spans.resource.unwrap();
+            assert_eq!(
+                string_attribute(&resource.attributes, "service.name"),
+                Some("astrid-test")
+            );
+            assert_eq!(
+                string_attribute(&resource.attributes, "deployment.environment"),
+                Some("ci")
+            );
+            for scope_spans in resource_spans.scope_spans {
+                spans.extend(scope_spans.spans);
+            }
+        }
+    }
+    let parent = spans.iter().find(|s| s.name == "handle_message").unwrap();
+    let child = spans.iter().find(|s| s.name == "call_tool").unwrap();
+    assert_eq!(child.trace_id, parent.trace_id);
+    assert_eq!(child.parent_span_id, parent.span_id);
+    assert_eq!(
+        string_attribute(&parent.attributes, "correlation_id"),
+        Some(ctx.correlation_id.to_string().as_str())
+    );
+    // Metric events are not span events.
+    assert!(child.events.is_empty());
+
+    // Metrics.
+    let mut total = None;
+    for request in collector.take("/v1/metrics") {
+        let export = ExportMetricsServiceRequest::decode(request.body.as_slice()).unwrap();
+        let metrics = export
+            .resource_metrics
+            .into_iter()
+            .flat_map(|r| r.scope_metrics)
+            .flat_map(|s| s.metrics);
+        for metric in metrics.filter(|m| m.name == "astrid.tool_calls") {
+            let Some(metric::Data::Sum(sum)) = metric.data else {
+                panic!("counter exported as {:?}", metric.data);
+            };
+            assert!(sum.is_monotonic);
+            let point = &sum.data_points[0];
+            assert_eq!(
+                string_attribute(&point.attributes, "outcome"),
+                Some("success")
+            );
+            total = match point.value {
+                Some(number_data_point::Value::AsInt(n)) => Some(n),
+                _ => None,
+            };
+        }
+    }
+    assert_eq!(total, Some(3));
+}
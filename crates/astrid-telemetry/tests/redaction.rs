@@ -0,0 +1,45 @@
+//! Seeded secrets never reach the log file in the standard redaction mode.
+
+#![allow(missing_docs)]
+
+use astrid_telemetry::{LogConfig, LogFormat, RequestContext, Sensitive, setup_logging};
+
+const SECRETS: &[&str] = &[
+    "sk-ant-seeded-api-key",
+    "Bearer seeded-token",
+    "seeded file contents",
+    "seeded user prompt",
+    "seeded tool output",
+];
+
+// One test per binary: the subscriber is process-global.
+#[test]
+fn seeded_secrets_are_redacted_in_standard_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = LogConfig::new("trace")
+        .with_format(LogFormat::Json)
+        .with_file_logging(dir.path(), "redaction");
+    setup_logging(&config).unwrap();
+
+    let ctx = RequestContext::new("test").with_operation("chat");
+    let span = ctx.span();
+    let _guard = span.enter();
+    tracing::info!(api_key = SECRETS[0], "Configured provider");
+    tracing::debug!(authorization = %SECRETS[1], "Sending request");
+    tracing::debug!(path = "/tmp/notes.md", content = ?SECRETS[2], "Read file");
+    tracing::debug!(prompt = %Sensitive(SECRETS[3]), "Prompt assembled");
+    tracing::warn!(stderr = %Sensitive(SECRETS[4]), "Tool failed");
+    tracing::info!("Summary: {}", Sensitive(SECRETS[3]));
+
+    let mut log = String::new();
+    for entry in std::fs::read_dir(dir.path()).unwrap() {
+        log.push_str(&std::fs::read_to_string(entry.unwrap().path()).unwrap());
+    }
+    assert_eq!(log.lines().count(), 6, "{log}");
+    for secret in SECRETS {
+        assert_eq!(log.matches(secret).count(), 0, "{secret} leaked:\n{log}");
+    }
+    assert!(log.contains("[redacted:21]"));
+    // Paths are only hashed in strict mode.
+    assert!(log.contains("/tmp/notes.md"));
+}
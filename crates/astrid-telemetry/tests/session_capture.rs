@@ -0,0 +1,46 @@
+//! Sessions running concurrently capture only their own log lines.
+
+#![allow(missing_docs)]
+
+use astrid_telemetry::{LogConfig, LogFormat, RequestContext, session_logs, setup_logging};
+use uuid::Uuid;
+
+// One test per binary: the subscriber is process-global.
+#[test]
+fn concurrent_sessions_capture_their_own_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = LogConfig::new("debug")
+        .with_format(LogFormat::Json)
+        .with_file_logging(dir.path(), "sessions");
+    setup_logging(&config).unwrap();
+
+    let sessions = [Uuid::new_v4(), Uuid::new_v4()];
+    let threads: Vec<_> = sessions
+        .into_iter()
+        .map(|session| {
+            std::thread::spawn(move || {
+                let ctx = RequestContext::new("test")
+                    .with_session_id(session)
+                    .with_operation("turn");
+                let span = ctx.span();
+                let _guard = span.enter();
+                for step in 0..100 {
+                    tracing::info!(step, "turn {session}");
+                    tracing::debug!(api_key = "sk-ant-seeded", "calling provider");
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    tracing::info!("outside any session");
+
+    for (session, other) in [(sessions[0], sessions[1]), (sessions[1], sessions[0])] {
+        let lines = session_logs(&session.to_string());
+        assert_eq!(lines.len(), 200);
+        assert!(lines.iter().all(|l| !l.contains(&other.to_string())));
+        assert!(lines.iter().all(|l| !l.contains("sk-ant-seeded")));
+        assert!(lines.iter().any(|l| l.contains(&format!("turn {session}"))));
+    }
+}
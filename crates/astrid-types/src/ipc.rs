@@ -29,6 +29,10 @@ pub struct IpcMessage {
     /// kernel boundary. `None` for system events (boot, lifecycle).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub principal: Option<String>,
+    /// Capsules with a direct call in progress that led to this message,
+    /// outermost caller first. Empty outside direct capsule calls.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub call_chain: Vec<String>,
 }
 
 impl IpcMessage {
@@ -43,6 +47,7 @@ impl IpcMessage {
             timestamp: Utc::now(),
             seq: 0,
             principal: None,
+            call_chain: Vec::new(),
         }
     }
 
@@ -59,6 +64,13 @@ impl IpcMessage {
         self.principal = Some(principal.into());
         self
     }
+
+    /// Set the direct call chain this message is part of.
+    #[must_use]
+    pub fn with_call_chain(mut self, call_chain: Vec<String>) -> Self {
+        self.call_chain = call_chain;
+        self
+    }
 }
 
 /// Default session ID for conversations.
@@ -103,6 +115,13 @@ pub enum IpcPayload {
         resource: String,
         /// Justification.
         reason: String,
+        /// Risk assessed by the kernel: from the action itself when the
+        /// host built it, or by checking a capsule's `file_read` resource
+        /// against the workspace. `None` when nothing was assessed, which
+        /// frontends must not auto-approve on. The host clears it on
+        /// payloads capsules publish, so guests cannot claim a risk.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        risk: Option<ApprovalRisk>,
     },
     /// Response to an [`ApprovalRequired`](IpcPayload::ApprovalRequired).
     ApprovalResponse {
@@ -260,6 +279,23 @@ impl IpcPayload {
         }
     }
 
+    /// The session this payload belongs to, if it names one.
+    ///
+    /// [`Custom`](Self::Custom) payloads name a session with a string
+    /// `session_id` field.
+    #[must_use]
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            Self::UserInput { session_id, .. } | Self::AgentResponse { session_id, .. } => {
+                Some(session_id)
+            },
+            Self::Custom { data } | Self::RawJson(data) => {
+                data.get("session_id").and_then(Value::as_str)
+            },
+            _ => None,
+        }
+    }
+
     /// Serialize only the guest-facing payload data.
     ///
     /// [`Custom`](Self::Custom) and [`RawJson`](Self::RawJson) payloads return
@@ -309,6 +345,17 @@ pub struct OnboardingField {
     pub placeholder: Option<String>,
 }
 
+/// Kernel-assessed risk of an [`ApprovalRequired`](IpcPayload::ApprovalRequired)
+/// request.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRisk {
+    /// A read of a path inside the workspace.
+    Low,
+    /// Anything else.
+    High,
+}
+
 /// The type of input expected for an onboarding field.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum OnboardingFieldType {
@@ -439,6 +486,7 @@ mod tests {
                 action: String::new(),
                 resource: String::new(),
                 reason: String::new(),
+                risk: None,
             },
             IpcPayload::ApprovalResponse {
                 request_id: "req-1".into(),
@@ -545,6 +593,21 @@ mod tests {
         assert!(!IpcPayload::is_known_tag("Raw_Json"));
     }
 
+    #[test]
+    fn session_id_of_payloads() {
+        let input = IpcPayload::UserInput {
+            text: "hi".into(),
+            session_id: "s1".into(),
+            context: None,
+        };
+        assert_eq!(input.session_id(), Some("s1"));
+        let custom = IpcPayload::Custom {
+            data: serde_json::json!({"session_id": "s2"}),
+        };
+        assert_eq!(custom.session_id(), Some("s2"));
+        assert_eq!(IpcPayload::Connect.session_id(), None);
+    }
+
     #[test]
     fn onboarding_field_roundtrip() {
         let field = OnboardingField {
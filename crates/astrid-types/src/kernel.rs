@@ -33,6 +33,41 @@ pub enum KernelRequest {
     ListCapsules,
     /// Reload all capsules from the file system.
     ReloadCapsules,
+    /// Unload one capsule and load it again from its source directory.
+    ReloadCapsule {
+        /// The capsule's name.
+        name: String,
+    },
+    /// Unload one capsule until the next reload.
+    UnloadCapsule {
+        /// The capsule's name.
+        name: String,
+    },
+    /// Request the last lines a capsule wrote to its log, oldest first.
+    GetCapsuleLogs {
+        /// The capsule's name.
+        name: String,
+        /// Lines to return (defaults to 100).
+        #[serde(default)]
+        lines: Option<usize>,
+    },
+    /// Delete every key in a capsule's KV namespace.
+    WipeCapsuleState {
+        /// The capsule's name.
+        name: String,
+    },
+    /// Request the `[[cron]]` jobs of loaded capsules and their next fire
+    /// times.
+    ListCronJobs,
+    /// Enable or disable a capsule's cron job until the capsule reloads.
+    SetCronJobEnabled {
+        /// The capsule's name.
+        name: String,
+        /// The job name from `[[cron]]`.
+        job: String,
+        /// Whether the job should fire.
+        enabled: bool,
+    },
     /// Request the list of globally registered slash commands.
     GetCommands,
     /// Request metadata about loaded capsules (manifests, providers, interceptors).
@@ -45,6 +80,43 @@ pub enum KernelRequest {
     },
     /// Request daemon status information.
     GetStatus,
+    /// Write a snapshot of the persistent KV state to `~/.astrid/backups/`.
+    BackupState,
+    /// Request the log lines captured for a session, oldest first.
+    GetSessionLogs {
+        /// The session whose logs to return.
+        session_id: String,
+    },
+    /// Change the daemon's log filter without a restart.
+    SetLogLevel {
+        /// A level (`debug`) or target directives (`astrid_mcp=trace`),
+        /// comma-separated.
+        directive: String,
+    },
+    /// Issue a one-time code that links an account on `platform` to an
+    /// existing Astrid user once sent from that account.
+    StartIdentityLink {
+        /// UUID of the Astrid user the account will be linked to.
+        user_id: String,
+        /// Platform the code will be completed on (e.g. `"discord"`).
+        platform: String,
+    },
+    /// Complete a link with a code issued by `StartIdentityLink`.
+    CompleteIdentityLink {
+        /// Platform the code was sent from.
+        platform: String,
+        /// The sender's user ID on that platform.
+        platform_user_id: String,
+        /// The code as sent by the user.
+        code: String,
+    },
+    /// Remove the link between a platform account and its Astrid user.
+    RevokeIdentityLink {
+        /// Platform of the linked account.
+        platform: String,
+        /// The account's user ID on that platform.
+        platform_user_id: String,
+    },
 }
 
 /// Management API responses from the core daemon.
@@ -87,6 +159,32 @@ pub struct DaemonStatus {
     pub connected_clients: u32,
     /// Names of loaded capsules.
     pub loaded_capsules: Vec<String>,
+    /// Per-operation metrics for the kernel KV store.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kv_ops: Vec<KvOpStats>,
+}
+
+/// Latency and error metrics for one KV store operation kind.
+///
+/// Percentiles are bucketed upper bounds, accurate to within a factor of two.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KvOpStats {
+    /// Operation name (`get`, `set`, `scan`, ...).
+    pub op: String,
+    /// Completed operations, including failures.
+    pub count: u64,
+    /// Operations that returned an error.
+    pub errors: u64,
+    /// Operations slower than the slow-op threshold.
+    pub slow: u64,
+    /// Median latency in microseconds.
+    pub latency_p50_us: u64,
+    /// 95th percentile latency in microseconds.
+    pub latency_p95_us: u64,
+    /// 99th percentile latency in microseconds.
+    pub latency_p99_us: u64,
+    /// Total value bytes read or written.
+    pub bytes_total: u64,
 }
 
 /// Metadata entry for a loaded capsule.
@@ -94,6 +192,12 @@ pub struct DaemonStatus {
 pub struct CapsuleMetadataEntry {
     /// The capsule's unique name.
     pub name: String,
+    /// The capsule's version from its manifest.
+    #[serde(default)]
+    pub version: String,
+    /// Lifecycle state (`ready`, `failed: <reason>`, ...).
+    #[serde(default)]
+    pub state: String,
     /// Interceptor event patterns declared by this capsule.
     pub interceptor_events: Vec<String>,
 }
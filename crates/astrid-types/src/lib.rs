@@ -19,9 +19,11 @@ pub mod ipc;
 pub mod kernel;
 pub mod llm;
 
-pub use ipc::{IpcMessage, IpcPayload, OnboardingField, OnboardingFieldType, SelectionOption};
+pub use ipc::{
+    ApprovalRisk, IpcMessage, IpcPayload, OnboardingField, OnboardingFieldType, SelectionOption,
+};
 pub use kernel::{
-    CapsuleMetadataEntry, CommandInfo, DaemonStatus, KernelRequest, KernelResponse,
+    CapsuleMetadataEntry, CommandInfo, DaemonStatus, KernelRequest, KernelResponse, KvOpStats,
     SYSTEM_SESSION_UUID,
 };
 pub use llm::{
@@ -79,6 +79,46 @@ impl HostVfs {
             self.open_dirs.read().await;
         dirs.get(handle).cloned().ok_or(VfsError::InvalidHandle)
     }
+
+    /// Open `path` with `options` and register the file under a new handle.
+    async fn open_with(
+        &self,
+        handle: &DirHandle,
+        path: &str,
+        options: cap_std::fs::OpenOptions,
+    ) -> VfsResult<FileHandle> {
+        let dir = self.get_dir(handle).await?;
+        let safe_path = make_relative(path).to_path_buf();
+
+        // Prevent transient FD exhaustion via semaphore before calling the OS
+        let permit = self
+            .fd_semaphore
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| VfsError::PermissionDenied("Too many open files".into()))?;
+
+        let std_file = tokio::task::spawn_blocking(move || dir.open_with(&safe_path, &options))
+            .await
+            .expect("spawn_blocking panicked")
+            .map_err(VfsError::Io)?;
+
+        // Convert the cap_std File into a tokio async File
+        let tokio_file = tokio::fs::File::from_std(std_file.into_std());
+
+        let mut files: tokio::sync::RwLockWriteGuard<'_, HashMap<FileHandle, OpenFileEntry>> =
+            self.open_files.write().await;
+        if files.len() >= 64 {
+            return Err(VfsError::PermissionDenied("Too many open files".into()));
+        }
+
+        let new_handle = FileHandle::new();
+        files.insert(
+            new_handle.clone(),
+            Arc::new(RwLock::new((tokio_file, permit))),
+        );
+
+        Ok(new_handle)
+    }
 }
 
 impl Default for HostVfs {
@@ -201,45 +241,19 @@ impl Vfs for HostVfs {
         write: bool,
         truncate: bool,
     ) -> VfsResult<FileHandle> {
-        let dir = self.get_dir(handle).await?;
-        let safe_path = make_relative(path).to_path_buf();
-
-        // Prevent transient FD exhaustion via semaphore before calling the OS
-        let permit = self
-            .fd_semaphore
-            .clone()
-            .try_acquire_owned()
-            .map_err(|_| VfsError::PermissionDenied("Too many open files".into()))?;
-
-        let std_file = tokio::task::spawn_blocking(move || {
-            let mut options = cap_std::fs::OpenOptions::new();
-            options
-                .read(true)
-                .write(write)
-                .create(write)
-                .truncate(truncate);
-            dir.open_with(&safe_path, &options)
-        })
-        .await
-        .expect("spawn_blocking panicked")
-        .map_err(VfsError::Io)?;
-
-        // Convert the cap_std File into a tokio async File
-        let tokio_file = tokio::fs::File::from_std(std_file.into_std());
-
-        let mut files: tokio::sync::RwLockWriteGuard<'_, HashMap<FileHandle, OpenFileEntry>> =
-            self.open_files.write().await;
-        if files.len() >= 64 {
-            return Err(VfsError::PermissionDenied("Too many open files".into()));
-        }
-
-        let new_handle = FileHandle::new();
-        files.insert(
-            new_handle.clone(),
-            Arc::new(RwLock::new((tokio_file, permit))),
-        );
+        let mut options = cap_std::fs::OpenOptions::new();
+        options
+            .read(true)
+            .write(write)
+            .create(write)
+            .truncate(truncate);
+        self.open_with(handle, path, options).await
+    }
 
-        Ok(new_handle)
+    async fn open_append(&self, handle: &DirHandle, path: &str) -> VfsResult<FileHandle> {
+        let mut options = cap_std::fs::OpenOptions::new();
+        options.append(true).create(true);
+        self.open_with(handle, path, options).await
     }
 
     async fn open_dir(
@@ -318,6 +332,31 @@ impl Vfs for HostVfs {
         Ok(buffer)
     }
 
+    async fn read_at(&self, handle: &FileHandle, offset: u64, len: usize) -> VfsResult<Vec<u8>> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+        let file_arc = {
+            let files: tokio::sync::RwLockReadGuard<'_, HashMap<FileHandle, OpenFileEntry>> =
+                self.open_files.read().await;
+            files.get(handle).cloned().ok_or(VfsError::InvalidHandle)?
+        };
+
+        let mut file_tuple = file_arc.write().await;
+        let file = &mut file_tuple.0;
+
+        file.seek(std::io::SeekFrom::Start(offset))
+            .await
+            .map_err(VfsError::Io)?;
+
+        let mut buffer = Vec::with_capacity(len.min(64 * 1024));
+        (&mut *file)
+            .take(len as u64)
+            .read_to_end(&mut buffer)
+            .await
+            .map_err(VfsError::Io)?;
+
+        Ok(buffer)
+    }
+
     async fn write(&self, handle: &FileHandle, content: &[u8]) -> VfsResult<()> {
         use tokio::io::AsyncWriteExt;
         let file_arc = {
@@ -84,6 +84,30 @@ pub trait Vfs: Send + Sync {
         truncate: bool,
     ) -> VfsResult<FileHandle>;
 
+    /// Open a file for appending, creating it if it does not exist.
+    ///
+    /// Every write through the returned handle lands at the current end of
+    /// the file, so writers that each open, append and close never
+    /// overwrite one another.
+    async fn open_append(&self, handle: &DirHandle, path: &str) -> VfsResult<FileHandle>;
+
+    /// Copy the contents of the file at `src` to `dst`, creating or
+    /// truncating `dst`.
+    ///
+    /// The default reads and writes through this VFS, so wrappers such as
+    /// [`OverlayVfs`] apply their own write semantics to `dst`.
+    async fn copy(&self, handle: &DirHandle, src: &str, dst: &str) -> VfsResult<()> {
+        let src_fh = self.open(handle, src, false, false).await?;
+        let content = self.read(&src_fh).await;
+        let _ = self.close(&src_fh).await;
+        let content = content?;
+
+        let dst_fh = self.open(handle, dst, true, true).await?;
+        let result = self.write(&dst_fh, &content).await;
+        let _ = self.close(&dst_fh).await;
+        result
+    }
+
     /// Open a subdirectory, granting a new narrowed capability handle.
     async fn open_dir(
         &self,
@@ -98,6 +122,13 @@ pub trait Vfs: Send + Sync {
     /// Read from an open file handle.
     async fn read(&self, handle: &FileHandle) -> VfsResult<Vec<u8>>;
 
+    /// Read up to `len` bytes starting at `offset` from an open file handle.
+    ///
+    /// Returns fewer than `len` bytes (possibly none) at end of file. Unlike
+    /// [`Vfs::read`], no whole-file size limit applies, so large files can be
+    /// streamed in bounded chunks.
+    async fn read_at(&self, handle: &FileHandle, offset: u64, len: usize) -> VfsResult<Vec<u8>>;
+
     /// Write to an open file handle.
     async fn write(&self, handle: &FileHandle, content: &[u8]) -> VfsResult<()>;
 
@@ -262,6 +262,90 @@ impl OverlayVfs {
         }
     }
 
+    /// Get `path` ready for writing in the upper layer: create its parent
+    /// directories, copy the lower file up unless it is about to be
+    /// truncated, and track the path as dirty.
+    async fn prepare_upper_write(
+        &self,
+        handle: &DirHandle,
+        path: &str,
+        truncate: bool,
+    ) -> VfsResult<()> {
+        // Validate before any filesystem mutation to avoid phantom files
+        // in upper on SandboxViolation.
+        let normalized = Self::normalize_path(path)?;
+
+        // When the upper layer is a separate temp directory, parent dirs
+        // may not exist yet. Ensure them before any write/copy-up.
+        if let Some(parent) = std::path::Path::new(path).parent() {
+            let parent_str = parent.to_string_lossy();
+            if !parent_str.is_empty() {
+                self.ensure_upper_dirs(handle, &parent_str).await?;
+            }
+        }
+
+        let needs_copy = !self.upper.exists(handle, path).await.unwrap_or(false)
+            && self.lower.exists(handle, path).await.unwrap_or(false);
+
+        if needs_copy {
+            let lock_key = format!("/{normalized}");
+
+            let path_lock = self
+                .copy_locks
+                .entry(lock_key.clone())
+                .or_insert_with(|| Arc::new(Mutex::new(())))
+                .clone();
+            let _guard = path_lock.lock().await;
+
+            let _map_guard = LockGuard {
+                map: &self.copy_locks,
+                key: lock_key.clone(),
+            };
+
+            // Re-check after acquiring the lock in case another task already copied it
+            if !self.upper.exists(handle, path).await.unwrap_or(false) {
+                if truncate {
+                    // Fast path: if truncating, just create an empty file in upper
+                    // We don't need to copy the contents from lower
+                    let new_upper_file = self.upper.open(handle, path, true, true).await?;
+                    let _ = self.upper.close(&new_upper_file).await;
+                } else {
+                    // Prevent OOM during copy-up by capping the size
+                    let meta = self.lower.stat(handle, path).await?;
+                    if meta.size > MAX_OVERLAY_FILE_SIZE {
+                        return Err(crate::VfsError::PermissionDenied(
+                            "File is too large for OverlayVfs copy-up (> 50MB)".into(),
+                        ));
+                    }
+
+                    // Perform copy-up.
+                    let lower_handle = self.lower.open(handle, path, false, false).await?;
+                    let content_result = self.lower.read(&lower_handle).await;
+
+                    // Ensure lower is closed even if read fails
+                    let _ = self.lower.close(&lower_handle).await;
+
+                    let content = content_result?;
+
+                    let new_upper_file = self.upper.open(handle, path, true, true).await?;
+                    let write_result = self.upper.write(&new_upper_file, &content).await;
+
+                    // Ensure upper is closed even if write fails
+                    let _ = self.upper.close(&new_upper_file).await;
+
+                    if let Err(e) = write_result {
+                        // Revert the copy-up so we don't leave a truncated file
+                        let _ = self.upper.unlink(handle, path).await;
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        // Track this path as dirty for commit/rollback.
+        self.dirty_entries.insert(normalized, DirtyKind::File);
+        Ok(())
+    }
+
     /// Normalize a path for dirty tracking consistency.
     ///
     /// Resolves `.` and `..` components and strips any leading `/` so that
@@ -359,78 +443,7 @@ impl Vfs for OverlayVfs {
         truncate: bool,
     ) -> VfsResult<FileHandle> {
         if write {
-            // Validate before any filesystem mutation to avoid phantom files
-            // in upper on SandboxViolation.
-            let normalized = Self::normalize_path(path)?;
-
-            // When the upper layer is a separate temp directory, parent dirs
-            // may not exist yet. Ensure them before any write/copy-up.
-            if let Some(parent) = std::path::Path::new(path).parent() {
-                let parent_str = parent.to_string_lossy();
-                if !parent_str.is_empty() {
-                    self.ensure_upper_dirs(handle, &parent_str).await?;
-                }
-            }
-
-            let needs_copy = !self.upper.exists(handle, path).await.unwrap_or(false)
-                && self.lower.exists(handle, path).await.unwrap_or(false);
-
-            if needs_copy {
-                let lock_key = format!("/{normalized}");
-
-                let path_lock = self
-                    .copy_locks
-                    .entry(lock_key.clone())
-                    .or_insert_with(|| Arc::new(Mutex::new(())))
-                    .clone();
-                let _guard = path_lock.lock().await;
-
-                let _map_guard = LockGuard {
-                    map: &self.copy_locks,
-                    key: lock_key.clone(),
-                };
-
-                // Re-check after acquiring the lock in case another task already copied it
-                if !self.upper.exists(handle, path).await.unwrap_or(false) {
-                    if truncate {
-                        // Fast path: if truncating, just create an empty file in upper
-                        // We don't need to copy the contents from lower
-                        let new_upper_file = self.upper.open(handle, path, true, true).await?;
-                        let _ = self.upper.close(&new_upper_file).await;
-                    } else {
-                        // Prevent OOM during copy-up by capping the size
-                        let meta = self.lower.stat(handle, path).await?;
-                        if meta.size > MAX_OVERLAY_FILE_SIZE {
-                            return Err(crate::VfsError::PermissionDenied(
-                                "File is too large for OverlayVfs copy-up (> 50MB)".into(),
-                            ));
-                        }
-
-                        // Perform copy-up.
-                        let lower_handle = self.lower.open(handle, path, false, false).await?;
-                        let content_result = self.lower.read(&lower_handle).await;
-
-                        // Ensure lower is closed even if read fails
-                        let _ = self.lower.close(&lower_handle).await;
-
-                        let content = content_result?;
-
-                        let new_upper_file = self.upper.open(handle, path, true, true).await?;
-                        let write_result = self.upper.write(&new_upper_file, &content).await;
-
-                        // Ensure upper is closed even if write fails
-                        let _ = self.upper.close(&new_upper_file).await;
-
-                        if let Err(e) = write_result {
-                            // Revert the copy-up so we don't leave a truncated file
-                            let _ = self.upper.unlink(handle, path).await;
-                            return Err(e);
-                        }
-                    }
-                }
-            }
-            // Track this path as dirty for commit/rollback.
-            self.dirty_entries.insert(normalized, DirtyKind::File);
+            self.prepare_upper_write(handle, path, truncate).await?;
             return self.upper.open(handle, path, write, truncate).await;
         }
 
@@ -453,6 +466,12 @@ impl Vfs for OverlayVfs {
         self.lower.open(handle, path, false, false).await
     }
 
+    async fn open_append(&self, handle: &DirHandle, path: &str) -> VfsResult<FileHandle> {
+        // Copy-up keeps the lower content, so appends extend it.
+        self.prepare_upper_write(handle, path, false).await?;
+        self.upper.open_append(handle, path).await
+    }
+
     async fn open_dir(
         &self,
         handle: &DirHandle,
@@ -511,6 +530,14 @@ impl Vfs for OverlayVfs {
         }
     }
 
+    async fn read_at(&self, handle: &FileHandle, offset: u64, len: usize) -> VfsResult<Vec<u8>> {
+        match self.upper.read_at(handle, offset, len).await {
+            Ok(data) => Ok(data),
+            Err(crate::VfsError::InvalidHandle) => self.lower.read_at(handle, offset, len).await,
+            Err(e) => Err(e),
+        }
+    }
+
     async fn write(&self, handle: &FileHandle, content: &[u8]) -> VfsResult<()> {
         // Writes always go to upper. If it's a lower handle, this is an error
         // since lower files are opened read-only.
@@ -736,6 +763,33 @@ mod tests {
         assert!(overlay.dirty_paths().is_empty());
     }
 
+    #[tokio::test]
+    async fn read_at_streams_lower_file_in_chunks() {
+        let (overlay, handle, lower_dir, _upper_dir) = setup().await;
+        let content: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        seed_lower(lower_dir.path(), "big.bin", &content);
+
+        let fh = overlay
+            .open(&handle, "big.bin", false, false)
+            .await
+            .unwrap();
+        let mut reassembled = Vec::new();
+        loop {
+            let chunk = overlay
+                .read_at(&fh, reassembled.len() as u64, 4096)
+                .await
+                .unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            assert!(chunk.len() <= 4096);
+            reassembled.extend_from_slice(&chunk);
+        }
+        overlay.close(&fh).await.unwrap();
+
+        assert_eq!(reassembled, content);
+    }
+
     #[tokio::test]
     async fn rollback_then_read_serves_lower() {
         let (overlay, handle, lower_dir, _upper_dir) = setup().await;
@@ -822,4 +876,115 @@ mod tests {
             "expected NotSupported, got: {err:?}"
         );
     }
+
+    /// Append `line` through a fresh append handle, as a separate
+    /// invocation would.
+    async fn append_line(vfs: &dyn Vfs, handle: &DirHandle, path: &str, line: &str) {
+        let fh = vfs.open_append(handle, path).await.unwrap();
+        vfs.write(&fh, line.as_bytes()).await.unwrap();
+        vfs.close(&fh).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn append_across_separate_opens_keeps_every_line() {
+        let (overlay, handle, lower_dir, upper_dir) = setup().await;
+        seed_lower(lower_dir.path(), "log.txt", b"seed\n");
+
+        for i in 0..1000 {
+            append_line(&overlay, &handle, "log.txt", &format!("line {i}\n")).await;
+        }
+
+        let content = std::fs::read_to_string(upper_dir.path().join("log.txt")).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 1001);
+        assert_eq!(lines[0], "seed");
+        assert_eq!(lines[1000], "line 999");
+        // The lower file is untouched until commit.
+        assert_eq!(
+            std::fs::read(lower_dir.path().join("log.txt")).unwrap(),
+            b"seed\n"
+        );
+
+        overlay.commit(&handle).await.unwrap();
+        let committed = std::fs::read_to_string(lower_dir.path().join("log.txt")).unwrap();
+        assert_eq!(committed, content);
+    }
+
+    #[tokio::test]
+    async fn concurrent_appends_do_not_overwrite_each_other() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let vfs = Arc::new(HostVfs::new());
+        let handle = DirHandle::new();
+        vfs.register_dir(handle.clone(), dir.path().to_path_buf())
+            .await
+            .unwrap();
+
+        let mut tasks = Vec::new();
+        for writer in 0..8 {
+            let vfs = Arc::clone(&vfs);
+            let handle = handle.clone();
+            tasks.push(tokio::spawn(async move {
+                for i in 0..50 {
+                    let line = format!("{writer}:{i}\n");
+                    append_line(vfs.as_ref(), &handle, "shared.log", &line).await;
+                }
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let content = std::fs::read_to_string(dir.path().join("shared.log")).unwrap();
+        assert_eq!(content.lines().count(), 400);
+    }
+
+    #[tokio::test]
+    async fn copy_from_lower_lands_in_upper() {
+        let (overlay, handle, lower_dir, upper_dir) = setup().await;
+        seed_lower(lower_dir.path(), "src.bin", b"\x00\x01payload\xff");
+
+        overlay
+            .copy(&handle, "src.bin", "nested/dst.bin")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read(upper_dir.path().join("nested/dst.bin")).unwrap(),
+            b"\x00\x01payload\xff"
+        );
+        assert!(!lower_dir.path().join("nested/dst.bin").exists());
+        assert!(!upper_dir.path().join("src.bin").exists());
+        assert!(
+            overlay
+                .dirty_paths()
+                .contains(&"nested/dst.bin".to_string())
+        );
+
+        overlay.commit(&handle).await.unwrap();
+        assert_eq!(
+            std::fs::read(lower_dir.path().join("nested/dst.bin")).unwrap(),
+            b"\x00\x01payload\xff"
+        );
+    }
+
+    #[tokio::test]
+    async fn copy_reads_upper_copy_and_overwrites_dst() {
+        let (overlay, handle, lower_dir, _upper_dir) = setup().await;
+        seed_lower(lower_dir.path(), "a.txt", b"old");
+        seed_lower(lower_dir.path(), "b.txt", b"something longer");
+        write_through_overlay(&overlay, &handle, "a.txt", b"new").await;
+
+        overlay.copy(&handle, "a.txt", "b.txt").await.unwrap();
+
+        let fh = overlay.open(&handle, "b.txt", false, false).await.unwrap();
+        assert_eq!(overlay.read(&fh).await.unwrap(), b"new");
+        overlay.close(&fh).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn copy_missing_source_fails_without_creating_dst() {
+        let (overlay, handle, _lower_dir, upper_dir) = setup().await;
+        assert!(overlay.copy(&handle, "missing", "dst").await.is_err());
+        assert!(!upper_dir.path().join("dst").exists());
+    }
 }
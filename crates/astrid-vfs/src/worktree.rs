@@ -102,6 +102,11 @@ impl Vfs for WorktreeVfs {
         self.inner.open(handle, path, write, truncate).await
     }
 
+    async fn open_append(&self, handle: &DirHandle, path: &str) -> VfsResult<FileHandle> {
+        self.check_access(path, false)?;
+        self.inner.open_append(handle, path).await
+    }
+
     async fn open_dir(
         &self,
         handle: &DirHandle,
@@ -121,6 +126,10 @@ impl Vfs for WorktreeVfs {
         self.inner.read(handle).await
     }
 
+    async fn read_at(&self, handle: &FileHandle, offset: u64, len: usize) -> VfsResult<Vec<u8>> {
+        self.inner.read_at(handle, offset, len).await
+    }
+
     async fn write(&self, handle: &FileHandle, content: &[u8]) -> VfsResult<()> {
         self.inner.write(handle, content).await
     }
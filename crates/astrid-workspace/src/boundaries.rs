@@ -5,12 +5,13 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use tracing::{debug, warn};
 
+use crate::command_paths::command_paths;
 use crate::config::{EscapePolicy, WorkspaceConfig, WorkspaceMode};
 
 /// Result of checking a path against workspace boundaries.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
-pub(crate) enum PathCheck {
+pub enum PathCheck {
     /// Path is within the workspace, allowed.
     Allowed,
     /// Path is auto-allowed (outside workspace but configured).
@@ -24,28 +25,37 @@ pub(crate) enum PathCheck {
 impl PathCheck {
     /// Check if the path is allowed (directly or auto).
     #[must_use]
-    pub(crate) fn is_allowed(self) -> bool {
+    pub fn is_allowed(self) -> bool {
         matches!(self, Self::Allowed | Self::AutoAllowed)
     }
 
     /// Check if the path requires approval.
     #[must_use]
-    pub(crate) fn needs_approval(self) -> bool {
+    pub fn needs_approval(self) -> bool {
         matches!(self, Self::RequiresApproval)
     }
 
     /// Check if the path is never allowed.
     #[must_use]
-    pub(crate) fn is_blocked(self) -> bool {
+    pub fn is_blocked(self) -> bool {
         matches!(self, Self::NeverAllowed)
     }
 }
 
+/// Result of checking a shell command's paths against workspace boundaries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandCheck {
+    /// The most restrictive result over all paths the command names.
+    pub verdict: PathCheck,
+    /// Paths that need approval or are blocked, for the approval prompt.
+    pub flagged: Vec<PathBuf>,
+}
+
 /// Workspace boundary checker.
 ///
 /// Pre-compiles glob patterns for efficient matching.
 #[derive(Debug)]
-pub(crate) struct WorkspaceBoundary {
+pub struct WorkspaceBoundary {
     config: WorkspaceConfig,
     /// Pre-compiled glob matchers for auto-allow patterns.
     compiled_matchers: Vec<GlobMatcher>,
@@ -63,7 +73,7 @@ impl WorkspaceBoundary {
     ///
     /// Pre-compiles all glob patterns in the configuration.
     #[must_use]
-    pub(crate) fn new(config: WorkspaceConfig) -> Self {
+    pub fn new(config: WorkspaceConfig) -> Self {
         let compiled_matchers = config
             .auto_allow
             .patterns
@@ -204,6 +214,39 @@ impl WorkspaceBoundary {
         })
     }
 
+    /// Pre-flight a shell command: check every path it appears to name.
+    ///
+    /// Relative paths are resolved against `cwd`, the directory the command
+    /// runs in. Path extraction is best effort (see [`command_paths`]). In
+    /// [`WorkspaceMode::Safe`] a path that would need approval blocks the
+    /// command instead, so callers only ask once, for the whole command,
+    /// in the other modes.
+    #[must_use]
+    pub fn check_command(&self, command: &str, cwd: &Path, home: Option<&Path>) -> CommandCheck {
+        let mut verdict = PathCheck::Allowed;
+        let mut flagged = Vec::new();
+        for path in command_paths(command, cwd, home) {
+            let check = self.check(&path);
+            if check.needs_approval() || check.is_blocked() {
+                flagged.push(path);
+            }
+            verdict = match (verdict, check) {
+                (PathCheck::NeverAllowed, _) | (_, PathCheck::NeverAllowed) => {
+                    PathCheck::NeverAllowed
+                },
+                (PathCheck::RequiresApproval, _) | (_, PathCheck::RequiresApproval) => {
+                    PathCheck::RequiresApproval
+                },
+                (PathCheck::AutoAllowed, _) | (_, PathCheck::AutoAllowed) => PathCheck::AutoAllowed,
+                _ => PathCheck::Allowed,
+            };
+        }
+        if verdict.needs_approval() && self.config.mode == WorkspaceMode::Safe {
+            verdict = PathCheck::NeverAllowed;
+        }
+        CommandCheck { verdict, flagged }
+    }
+
     /// Check multiple paths and return the most restrictive result.
     #[must_use]
     pub(crate) fn check_all(&self, paths: &[&Path]) -> PathCheck {
@@ -289,4 +332,106 @@ mod tests {
             PathCheck::Allowed
         );
     }
+
+    #[test]
+    fn test_check_command_verdicts() {
+        let root = Path::new("/srv/astrid-test/project");
+        let guided = WorkspaceBoundary::new(
+            WorkspaceConfig::new(root)
+                .with_mode(WorkspaceMode::Guided)
+                .allow_read("/srv/astrid-test/shared"),
+        );
+        let safe = WorkspaceBoundary::new(
+            WorkspaceConfig::new(root).allow_read("/srv/astrid-test/shared"),
+        );
+        let home = Some(Path::new("/srv/astrid-test/home"));
+
+        let cases = [
+            ("ls -la", PathCheck::Allowed, PathCheck::Allowed),
+            ("cat src/lib.rs", PathCheck::Allowed, PathCheck::Allowed),
+            (
+                "rm -rf ./target/debug",
+                PathCheck::Allowed,
+                PathCheck::Allowed,
+            ),
+            (
+                "cat a/../b.txt > out/c.txt",
+                PathCheck::Allowed,
+                PathCheck::Allowed,
+            ),
+            ("make 2>/dev/null", PathCheck::Allowed, PathCheck::Allowed),
+            ("/usr/bin/make all", PathCheck::Allowed, PathCheck::Allowed),
+            (
+                "cat ../shared/notes",
+                PathCheck::AutoAllowed,
+                PathCheck::AutoAllowed,
+            ),
+            (
+                "cat ../other/file",
+                PathCheck::RequiresApproval,
+                PathCheck::NeverAllowed,
+            ),
+            (
+                "cp src/a ~/backup",
+                PathCheck::RequiresApproval,
+                PathCheck::NeverAllowed,
+            ),
+            (
+                "cat ../../../../etc/passwd",
+                PathCheck::NeverAllowed,
+                PathCheck::NeverAllowed,
+            ),
+            (
+                "cat ../other/file /etc/hosts",
+                PathCheck::NeverAllowed,
+                PathCheck::NeverAllowed,
+            ),
+            ("cat $HOME/x", PathCheck::Allowed, PathCheck::Allowed),
+        ];
+        for (command, in_guided, in_safe) in cases {
+            assert_eq!(
+                guided.check_command(command, root, home).verdict,
+                in_guided,
+                "guided: {command}"
+            );
+            assert_eq!(
+                safe.check_command(command, root, home).verdict,
+                in_safe,
+                "safe: {command}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_check_command_resolves_against_cwd() {
+        let root = Path::new("/srv/astrid-test/project");
+        let boundary =
+            WorkspaceBoundary::new(WorkspaceConfig::new(root).with_mode(WorkspaceMode::Guided));
+        let cwd = root.join("src");
+        let check = boundary.check_command("cat ../README.md", &cwd, None);
+        assert_eq!(check.verdict, PathCheck::Allowed);
+        let check = boundary.check_command("cat ../../other", &cwd, None);
+        assert_eq!(check.verdict, PathCheck::RequiresApproval);
+        assert_eq!(check.flagged, vec![PathBuf::from("/srv/astrid-test/other")]);
+    }
+
+    #[test]
+    fn test_check_command_lists_flagged_paths() {
+        let boundary = WorkspaceBoundary::new(
+            WorkspaceConfig::new("/srv/astrid-test/project").with_mode(WorkspaceMode::Guided),
+        );
+        let check = boundary.check_command(
+            "diff src/a ../x/b /srv/astrid-test/y",
+            Path::new("/srv/astrid-test/project"),
+            None,
+        );
+        assert_eq!(check.verdict, PathCheck::RequiresApproval);
+        assert_eq!(
+            check.flagged,
+            vec![
+                PathBuf::from("/srv/astrid-test/x/b"),
+                PathBuf::from("/srv/astrid-test/y"),
+            ]
+        );
+    }
 }
@@ -0,0 +1,327 @@
+//! Best-effort extraction of filesystem paths from shell commands.
+//!
+//! Shell commands reach the filesystem without going through the path
+//! checks of the file tools, so `cat ../../etc/passwd` would never consult
+//! the workspace boundary. [`command_paths`] tokenizes a command the way a
+//! POSIX shell roughly would and returns the arguments that look like paths,
+//! resolved against the command's working directory, so the caller can check
+//! them before running the command.
+//!
+//! The extraction is heuristic. Paths built at runtime (`$HOME/x`,
+//! `$(pwd)/..`) are missed, but words that stay inside the workspace never
+//! resolve outside it, so in-workspace commands are not flagged.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Device files commands commonly redirect to; never worth flagging.
+const HARMLESS_DEVICES: &[&str] = &["/dev/null", "/dev/stdin", "/dev/stdout", "/dev/stderr"];
+
+/// A lexical unit of a shell command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// A word, with quotes and escapes removed.
+    Word(String),
+    /// A redirection operator (`>`, `>>`, `<`, ...); the next word is a file.
+    Redirect,
+    /// A command separator (`;`, `|`, `&&`, `(`, newline, ...).
+    Separator,
+}
+
+/// Split a command into words, redirections and separators.
+fn tokenize(command: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars().peekable();
+
+    let end_word = |tokens: &mut Vec<Token>, word: &mut String, in_word: &mut bool| {
+        if *in_word {
+            tokens.push(Token::Word(std::mem::take(word)));
+            *in_word = false;
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                for q in chars.by_ref() {
+                    if q == '\'' {
+                        break;
+                    }
+                    word.push(q);
+                }
+            },
+            '"' => {
+                in_word = true;
+                while let Some(q) = chars.next() {
+                    match q {
+                        '"' => break,
+                        '\\' => match chars.peek() {
+                            Some(&e @ ('"' | '\\' | '$' | '`')) => {
+                                word.push(e);
+                                chars.next();
+                            },
+                            _ => word.push('\\'),
+                        },
+                        _ => word.push(q),
+                    }
+                }
+            },
+            '\\' => {
+                in_word = true;
+                if let Some(e) = chars.next() {
+                    word.push(e);
+                }
+            },
+            '#' if !in_word => {
+                // Comment to the end of the line.
+                while chars.next_if(|&n| n != '\n').is_some() {}
+            },
+            '>' | '<' => {
+                // A bare number before the operator is a file descriptor.
+                if in_word && word.chars().all(|d| d.is_ascii_digit()) {
+                    word.clear();
+                    in_word = false;
+                }
+                end_word(&mut tokens, &mut word, &mut in_word);
+                while chars.next_if(|&n| matches!(n, '>' | '<' | '|')).is_some() {}
+                if chars.next_if_eq(&'&').is_some() {
+                    // `2>&1`, `>&-`: duplicates a descriptor, no file.
+                    while chars.next_if(|&n| n.is_ascii_digit() || n == '-').is_some() {}
+                } else {
+                    tokens.push(Token::Redirect);
+                }
+            },
+            ';' | '|' | '&' | '(' | ')' | '\n' => {
+                end_word(&mut tokens, &mut word, &mut in_word);
+                if tokens.last() != Some(&Token::Separator) {
+                    tokens.push(Token::Separator);
+                }
+            },
+            c if c.is_whitespace() => end_word(&mut tokens, &mut word, &mut in_word),
+            c => {
+                in_word = true;
+                word.push(c);
+            },
+        }
+    }
+    end_word(&mut tokens, &mut word, &mut in_word);
+    tokens
+}
+
+/// Whether `word` is a `NAME=value` variable assignment.
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+/// The part of an argument that may name a file, if any.
+///
+/// Takes the value of `--opt=value` and `key=value` words, drops words
+/// expanded at runtime, and cuts globs back to their directory.
+fn path_candidate(word: &str) -> Option<&str> {
+    let word = match word.split_once('=') {
+        Some((_, value)) => value,
+        None if word.starts_with('-') => return None,
+        None => word,
+    };
+    if word.is_empty() || word.contains(['$', '`']) || word.contains("://") {
+        return None;
+    }
+    let word = match word.find(['*', '?', '[']) {
+        Some(glob) => word
+            .get(..glob)
+            .and_then(|w| w.rfind('/').and_then(|i| w.get(..=i)))?,
+        None => word,
+    };
+    let looks_like_path = word.starts_with(['/', '~'])
+        || word == "."
+        || word == ".."
+        || word.starts_with("./")
+        || word.starts_with("../")
+        || word.contains('/');
+    looks_like_path.then_some(word)
+}
+
+/// Resolve a path word against the working directory and home directory,
+/// removing `.` and `..` components lexically.
+fn resolve(word: &str, cwd: &Path, home: Option<&Path>) -> Option<PathBuf> {
+    let joined = if word == "~" {
+        home?.to_path_buf()
+    } else if let Some(rest) = word.strip_prefix("~/") {
+        home?.join(rest)
+    } else if word.starts_with('~') {
+        // `~user/...`: another user's home, which we cannot resolve.
+        return None;
+    } else {
+        cwd.join(word)
+    };
+
+    let mut normalized = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                normalized.pop();
+            },
+            other => normalized.push(other),
+        }
+    }
+    Some(normalized)
+}
+
+/// Paths a shell command appears to touch, resolved to absolute paths.
+///
+/// Command names (the first word of each simple command) are skipped, as
+/// are descriptor duplications, `/dev/null` and words built from
+/// variables or command substitution. Relative words are resolved against
+/// `cwd`; `home` expands `~`.
+#[must_use]
+pub(crate) fn command_paths(command: &str, cwd: &Path, home: Option<&Path>) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    let mut at_command_start = true;
+    let mut after_redirect = false;
+
+    for token in tokenize(command) {
+        let word = match token {
+            Token::Separator => {
+                at_command_start = true;
+                after_redirect = false;
+                continue;
+            },
+            Token::Redirect => {
+                after_redirect = true;
+                continue;
+            },
+            Token::Word(word) => word,
+        };
+
+        let candidate = if after_redirect {
+            after_redirect = false;
+            Some(word.as_str())
+        } else if at_command_start {
+            if is_assignment(&word) {
+                path_candidate(&word)
+            } else {
+                at_command_start = false;
+                None
+            }
+        } else {
+            path_candidate(&word)
+        };
+
+        if let Some(path) = candidate.and_then(|c| resolve(c, cwd, home))
+            && !HARMLESS_DEVICES.iter().any(|d| path == Path::new(d))
+            && !paths.contains(&path)
+        {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(command: &str) -> Vec<String> {
+        command_paths(
+            command,
+            Path::new("/work/project"),
+            Some(Path::new("/home/me")),
+        )
+        .into_iter()
+        .map(|p| p.display().to_string())
+        .collect()
+    }
+
+    #[test]
+    fn tokenize_respects_quotes_and_operators() {
+        assert_eq!(
+            tokenize(r#"echo 'a b' "c \"d\"" e\ f>out 2>&1 | wc"#),
+            vec![
+                Token::Word("echo".into()),
+                Token::Word("a b".into()),
+                Token::Word("c \"d\"".into()),
+                Token::Word("e f".into()),
+                Token::Redirect,
+                Token::Word("out".into()),
+                Token::Separator,
+                Token::Word("wc".into()),
+            ]
+        );
+        assert_eq!(
+            tokenize("ls # cat /etc/passwd\npwd"),
+            vec![
+                Token::Word("ls".into()),
+                Token::Separator,
+                Token::Word("pwd".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn extracts_expected_paths() {
+        let cases: &[(&str, &[&str])] = &[
+            ("ls", &[]),
+            ("cargo build --release", &[]),
+            ("cat src/main.rs", &["/work/project/src/main.rs"]),
+            ("cat ./README.md", &["/work/project/README.md"]),
+            ("cat ../../etc/passwd", &["/etc/passwd"]),
+            ("cat ../sibling/file", &["/work/sibling/file"]),
+            ("cat src/../../other", &["/work/other"]),
+            ("ls ..", &["/work"]),
+            ("ls .", &["/work/project"]),
+            ("cat /etc/passwd", &["/etc/passwd"]),
+            ("cat '/etc/my file'", &["/etc/my file"]),
+            ("cat \"/etc/my file\"", &["/etc/my file"]),
+            ("cat /etc/my\\ file", &["/etc/my file"]),
+            ("cat ~/.ssh/id_rsa", &["/home/me/.ssh/id_rsa"]),
+            ("ls ~", &["/home/me"]),
+            ("ls ~other/x", &[]),
+            ("/usr/bin/env", &[]),
+            ("/bin/cat notes.txt", &[]),
+            ("echo hi > /tmp/out.txt", &["/tmp/out.txt"]),
+            ("echo hi >> log.txt", &["/work/project/log.txt"]),
+            ("sort < /var/data", &["/var/data"]),
+            ("make 2>/dev/null", &[]),
+            ("make 2>&1 | tee build.log", &[]),
+            ("make >/tmp/a 2>&1", &["/tmp/a"]),
+            ("cp a.txt /opt/b.txt", &["/opt/b.txt"]),
+            ("cp -r src /opt/dst", &["/opt/dst"]),
+            ("tar -C /opt -xf x.tar", &["/opt"]),
+            ("grep --file=/etc/patterns foo", &["/etc/patterns"]),
+            (
+                "dd if=/dev/zero of=out/disk.img",
+                &["/dev/zero", "/work/project/out/disk.img"],
+            ),
+            ("dd if=/dev/sda of=disk.img", &["/dev/sda"]),
+            ("FOO=/opt/x make", &["/opt/x"]),
+            ("cd /tmp && ls", &["/tmp"]),
+            ("true; cat /etc/hosts", &["/etc/hosts"]),
+            ("(cd /srv; ls)", &["/srv"]),
+            ("echo $(cat /etc/shadow)", &["/etc/shadow"]),
+            ("cat $HOME/.bashrc", &[]),
+            ("cat `which ls`", &[]),
+            ("ls /etc/*.conf", &["/etc"]),
+            ("ls *.rs", &[]),
+            ("curl https://example.com/a/b", &[]),
+            ("git log origin/main", &["/work/project/origin/main"]),
+            ("cat /etc/a /etc/a", &["/etc/a"]),
+            ("git commit -m 'fix: a/b'", &["/work/project/fix: a/b"]),
+        ];
+        for (command, expected) in cases {
+            assert_eq!(paths(command), *expected, "command: {command}");
+        }
+    }
+
+    #[test]
+    fn tilde_without_home_is_skipped() {
+        let got = command_paths("cat ~/.netrc", Path::new("/work"), None);
+        assert!(got.is_empty());
+    }
+}
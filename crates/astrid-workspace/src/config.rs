@@ -6,7 +6,7 @@ use std::path::PathBuf;
 /// Operating mode for the workspace.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
-pub(crate) enum WorkspaceMode {
+pub enum WorkspaceMode {
     /// Always ask before operations outside workspace.
     #[default]
     Safe,
@@ -16,6 +16,20 @@ pub(crate) enum WorkspaceMode {
     Autonomous,
 }
 
+impl WorkspaceMode {
+    /// Parse a `workspace.mode` config value. `"yolo"` is an alias for
+    /// [`Autonomous`](Self::Autonomous).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "safe" => Some(Self::Safe),
+            "guided" => Some(Self::Guided),
+            "autonomous" | "yolo" => Some(Self::Autonomous),
+            _ => None,
+        }
+    }
+}
+
 /// Policy for handling escape requests.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -45,7 +59,7 @@ pub(crate) struct AutoAllowPaths {
 
 /// Workspace configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(crate) struct WorkspaceConfig {
+pub struct WorkspaceConfig {
     /// Root directory of the workspace.
     pub(crate) root: PathBuf,
     /// Operating mode.
@@ -71,7 +85,7 @@ pub(crate) struct WorkspaceConfig {
 impl WorkspaceConfig {
     /// Create a new workspace configuration.
     #[must_use]
-    pub(crate) fn new(root: impl Into<PathBuf>) -> Self {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
         Self {
             root: root.into(),
             mode: WorkspaceMode::Safe,
@@ -93,7 +107,7 @@ impl WorkspaceConfig {
 
     /// Set the operating mode.
     #[must_use]
-    pub(crate) fn with_mode(mut self, mode: WorkspaceMode) -> Self {
+    pub fn with_mode(mut self, mode: WorkspaceMode) -> Self {
         self.mode = mode;
         self
     }
@@ -107,14 +121,14 @@ impl WorkspaceConfig {
 
     /// Add an auto-allowed read path.
     #[must_use]
-    pub(crate) fn allow_read(mut self, path: impl Into<PathBuf>) -> Self {
+    pub fn allow_read(mut self, path: impl Into<PathBuf>) -> Self {
         self.auto_allow.read.push(path.into());
         self
     }
 
     /// Add an auto-allowed write path.
     #[must_use]
-    pub(crate) fn allow_write(mut self, path: impl Into<PathBuf>) -> Self {
+    pub fn allow_write(mut self, path: impl Into<PathBuf>) -> Self {
         self.auto_allow.write.push(path.into());
         self
     }
@@ -156,6 +170,19 @@ mod tests {
         assert_eq!(config.mode, WorkspaceMode::Autonomous);
     }
 
+    #[test]
+    fn test_workspace_mode_from_name() {
+        assert_eq!(
+            WorkspaceMode::from_name("guided"),
+            Some(WorkspaceMode::Guided)
+        );
+        assert_eq!(
+            WorkspaceMode::from_name("yolo"),
+            Some(WorkspaceMode::Autonomous)
+        );
+        assert_eq!(WorkspaceMode::from_name("turbo"), None);
+    }
+
     #[test]
     fn test_is_in_workspace() {
         let config = WorkspaceConfig::new("/home/user/project");
@@ -40,6 +40,8 @@ pub mod prelude;
 #[allow(dead_code)]
 pub(crate) mod boundaries;
 #[allow(dead_code)]
+pub(crate) mod command_paths;
+#[allow(dead_code)]
 pub(crate) mod config;
 #[allow(dead_code)]
 pub(crate) mod escape;
@@ -51,4 +53,6 @@ pub mod sandbox;
 #[allow(dead_code)]
 pub(crate) mod worktree;
 
+pub use boundaries::{CommandCheck, PathCheck, WorkspaceBoundary};
+pub use config::{WorkspaceConfig, WorkspaceMode};
 pub use sandbox::{ProcessSandboxConfig, SandboxCommand, SandboxPrefix};
@@ -20,5 +20,8 @@
 //! }
 //! ```
 
+// Boundaries
+pub use crate::{PathCheck, WorkspaceBoundary, WorkspaceConfig};
+
 // Sandbox
 pub use crate::SandboxCommand;